@@ -0,0 +1,157 @@
+//! User-defined command aliases.
+//!
+//! The `[aliases]` table of the config file maps a name to the arguments it
+//! stands for:
+//!
+//! ```toml
+//! [aliases]
+//! ff = "focus right"
+//! half = "set-width --fraction 0.5"
+//! ```
+//!
+//! `openniri-cli ff` then runs `openniri-cli focus right`. Aliases are
+//! expanded before the arguments are parsed, built-in commands always win
+//! over an alias of the same name, and the expansion is split on whitespace
+//! (quoting is not supported). An expansion may also name any IPC command
+//! by its wire name (`ff = "focus-right"`), see [`crate::wire`].
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Alias name to the arguments it expands to.
+pub type Aliases = BTreeMap<String, String>;
+
+/// The part of the config file the CLI reads; everything else belongs to
+/// the daemon.
+#[derive(Debug, Default, Deserialize)]
+struct AliasSection {
+    #[serde(default)]
+    aliases: Aliases,
+}
+
+/// Parse the `[aliases]` table from config file contents.
+pub fn parse(contents: &str) -> Result<Aliases> {
+    let section: AliasSection = toml::from_str(contents)?;
+    Ok(section.aliases)
+}
+
+/// Aliases from the config file at `path`, or none if there is no config file.
+///
+/// # Errors
+///
+/// Returns an error if the config file can't be read or parsed.
+pub fn load(path: Option<&Path>) -> Result<Aliases> {
+    let Some(path) = path else {
+        return Ok(Aliases::new());
+    };
+    let contents = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    parse(&contents).with_context(|| format!("Failed to parse aliases in {}", path.display()))
+}
+
+/// Index of the subcommand in `args` (after the program name and any
+/// global options), or `None` if there is none.
+pub fn command_index(args: &[String], cli: &clap::Command) -> Option<usize> {
+    let mut i = 1;
+    while let Some(arg) = args.get(i) {
+        if !arg.starts_with('-') {
+            return Some(i);
+        }
+        // Skip the value of a global option given as a separate argument
+        let takes_separate_value = arg.strip_prefix("--").is_some_and(|name| {
+            cli.get_arguments().any(|a| {
+                a.get_long() == Some(name) && a.get_action().takes_values() && !a.is_require_equals_set()
+            })
+        });
+        i += if takes_separate_value { 2 } else { 1 };
+    }
+    None
+}
+
+/// Replace the subcommand in `args` with its expansion if it names an alias.
+///
+/// `args` are returned unchanged if the subcommand is built in or unknown.
+pub fn expand(mut args: Vec<String>, cli: &clap::Command, aliases: &Aliases) -> Vec<String> {
+    let Some(index) = command_index(&args, cli) else {
+        return args;
+    };
+    if is_builtin(cli, &args[index]) {
+        return args;
+    }
+    if let Some(expansion) = aliases.get(&args[index]) {
+        args.splice(index..=index, expansion.split_whitespace().map(str::to_string));
+    }
+    args
+}
+
+/// Whether `name` is one of the CLI's own subcommands.
+pub fn is_builtin(cli: &clap::Command, name: &str) -> bool {
+    name == "help" || cli.find_subcommand(name).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{Arg, ArgAction, Command};
+
+    fn cli() -> Command {
+        Command::new("openniri-cli")
+            .arg(Arg::new("json").long("json").global(true).action(ArgAction::SetTrue))
+            .arg(Arg::new("watch").long("watch").global(true).require_equals(true).num_args(0..=1))
+            .arg(Arg::new("pipe-name").long("pipe-name").global(true))
+            .subcommand(Command::new("focus").subcommand(Command::new("right")))
+            .subcommand(Command::new("status"))
+    }
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_aliases_ignores_daemon_settings() {
+        let aliases = parse(
+            r#"
+[layout]
+gap = 10
+
+[aliases]
+ff = "focus right"
+"#,
+        )
+        .unwrap();
+        assert_eq!(aliases.get("ff").map(String::as_str), Some("focus right"));
+        assert!(parse("[layout]\ngap = 10\n").unwrap().is_empty());
+        assert!(parse("[aliases]\nff = 1\n").is_err());
+    }
+
+    #[test]
+    fn test_command_index_skips_global_options() {
+        let cli = cli();
+        assert_eq!(command_index(&args(&["openniri-cli", "ff"]), &cli), Some(1));
+        assert_eq!(command_index(&args(&["openniri-cli", "--json", "ff"]), &cli), Some(2));
+        assert_eq!(command_index(&args(&["openniri-cli", "--pipe-name", "test", "ff"]), &cli), Some(3));
+        assert_eq!(command_index(&args(&["openniri-cli", "--watch", "ff"]), &cli), Some(2));
+        assert_eq!(command_index(&args(&["openniri-cli", "--watch=500", "ff"]), &cli), Some(2));
+        assert_eq!(command_index(&args(&["openniri-cli", "--help"]), &cli), None);
+    }
+
+    #[test]
+    fn test_expand_alias() {
+        let cli = cli();
+        let aliases = Aliases::from([
+            ("ff".to_string(), "focus  right".to_string()),
+            ("status".to_string(), "focus right".to_string()),
+        ]);
+
+        assert_eq!(
+            expand(args(&["openniri-cli", "--json", "ff", "--watch"]), &cli, &aliases),
+            args(&["openniri-cli", "--json", "focus", "right", "--watch"])
+        );
+        // Built-in commands can't be shadowed
+        assert_eq!(expand(args(&["openniri-cli", "status"]), &cli, &aliases), args(&["openniri-cli", "status"]));
+        // Unknown commands are left for clap to report
+        assert_eq!(expand(args(&["openniri-cli", "nope"]), &cli, &aliases), args(&["openniri-cli", "nope"]));
+    }
+}
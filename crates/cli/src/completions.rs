@@ -0,0 +1,225 @@
+//! Shell completion scripts generated from the CLI definition.
+//!
+//! The clap command tree is flattened into one candidate list per command
+//! path (`openniri-cli`, `openniri-cli focus`, ...). The scripts walk the
+//! words typed so far to find the deepest known path and offer its
+//! candidates, so new subcommands and flags are picked up without touching
+//! this module.
+
+use crate::aliases::Aliases;
+use clap::ValueEnum;
+use std::fmt::Write;
+
+/// Shells completion scripts can be generated for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum Shell {
+    /// PowerShell (add to $PROFILE)
+    Powershell,
+    /// Bash 4+
+    Bash,
+    /// Zsh (source it, or save it as _openniri-cli on $fpath)
+    Zsh,
+}
+
+/// Words that may follow one command path.
+#[derive(Debug, PartialEq)]
+struct Node {
+    /// Command names from the binary down, separated by spaces.
+    path: String,
+    /// Candidate words with their help text.
+    candidates: Vec<(String, String)>,
+}
+
+/// Flatten `command` and its subcommands into completion nodes.
+fn collect_nodes(command: &clap::Command, path: String, nodes: &mut Vec<Node>) {
+    let mut candidates = Vec::new();
+    for sub in command.get_subcommands().filter(|sub| !sub.is_hide_set()) {
+        candidates.push((sub.get_name().to_string(), help_text(sub.get_about())));
+    }
+    for arg in command.get_arguments().filter(|arg| !arg.is_hide_set()) {
+        if let Some(long) = arg.get_long() {
+            candidates.push((format!("--{}", long), help_text(arg.get_help())));
+        } else if arg.is_positional() {
+            for value in arg.get_possible_values().iter().filter(|value| !value.is_hide_set()) {
+                candidates.push((value.get_name().to_string(), help_text(value.get_help())));
+            }
+        }
+    }
+    nodes.push(Node { path: path.clone(), candidates });
+
+    // `help <command>` is offered, but its copy of the tree is left out
+    for sub in command.get_subcommands().filter(|sub| !sub.is_hide_set() && sub.get_name() != "help") {
+        collect_nodes(sub, format!("{} {}", path, sub.get_name()), nodes);
+    }
+}
+
+fn help_text(help: Option<&clap::builder::StyledStr>) -> String {
+    help.map(|h| h.to_string()).unwrap_or_default()
+}
+
+/// Completion nodes for the CLI, with `aliases` offered as top-level commands.
+fn nodes(cli: &clap::Command, aliases: &Aliases) -> Vec<Node> {
+    // Building propagates global options and adds help/version
+    let mut cli = cli.clone();
+    cli.build();
+    let bin = cli.get_name().to_string();
+    let mut nodes = Vec::new();
+    collect_nodes(&cli, bin, &mut nodes);
+    for (name, expansion) in aliases {
+        if !crate::aliases::is_builtin(&cli, name) {
+            nodes[0].candidates.push((name.clone(), format!("Alias for '{}'", expansion)));
+        }
+    }
+    nodes
+}
+
+fn words(node: &Node) -> String {
+    node.candidates.iter().map(|(word, _)| word.as_str()).collect::<Vec<_>>().join(" ")
+}
+
+/// Completion script for `shell`.
+///
+/// `aliases` are baked into the script, so it has to be regenerated after
+/// adding one.
+pub fn generate(shell: Shell, cli: &clap::Command, aliases: &Aliases) -> String {
+    let nodes = nodes(cli, aliases);
+    let bin = nodes[0].path.clone();
+    let func = format!("_{}", bin.replace('-', "_"));
+    let mut out = String::new();
+    match shell {
+        Shell::Bash => {
+            let _ = writeln!(out, "{}() {{", func);
+            let _ = writeln!(out, "    local -A opts=(");
+            for node in &nodes {
+                let _ = writeln!(out, "        [\"{}\"]=\"{}\"", node.path, words(node));
+            }
+            let _ = writeln!(out, "    )");
+            let _ = writeln!(out, "    local cur=\"${{COMP_WORDS[COMP_CWORD]}}\" cmd=\"{}\" next i", bin);
+            let _ = writeln!(out, "    for ((i = 1; i < COMP_CWORD; i++)); do");
+            let _ = writeln!(out, "        next=\"$cmd ${{COMP_WORDS[i]}}\"");
+            let _ = writeln!(out, "        [[ -n \"${{opts[$next]+x}}\" ]] && cmd=\"$next\"");
+            let _ = writeln!(out, "    done");
+            let _ = writeln!(out, "    COMPREPLY=($(compgen -W \"${{opts[$cmd]}}\" -- \"$cur\"))");
+            let _ = writeln!(out, "}}");
+            let _ = writeln!(out, "complete -F {} {} {}.exe", func, bin, bin);
+        }
+        Shell::Zsh => {
+            let _ = writeln!(out, "#compdef {} {}.exe", bin, bin);
+            let _ = writeln!(out);
+            let _ = writeln!(out, "{}() {{", func);
+            let _ = writeln!(out, "    local -A opts");
+            let _ = writeln!(out, "    opts=(");
+            for node in &nodes {
+                let _ = writeln!(out, "        '{}' '{}'", node.path, words(node));
+            }
+            let _ = writeln!(out, "    )");
+            let _ = writeln!(out, "    local cmd='{}' next word", bin);
+            let _ = writeln!(out, "    for word in ${{words[2,CURRENT-1]}}; do");
+            let _ = writeln!(out, "        next=\"$cmd $word\"");
+            let _ = writeln!(out, "        (( ${{+opts[$next]}} )) && cmd=$next");
+            let _ = writeln!(out, "    done");
+            let _ = writeln!(out, "    compadd -- ${{=opts[$cmd]}}");
+            let _ = writeln!(out, "}}");
+            let _ = writeln!(out);
+            let _ = writeln!(out, "if [ \"$funcstack[1]\" = \"{}\" ]; then", func);
+            let _ = writeln!(out, "    {} \"$@\"", func);
+            let _ = writeln!(out, "else");
+            let _ = writeln!(out, "    compdef {} {} {}.exe", func, bin, bin);
+            let _ = writeln!(out, "fi");
+        }
+        Shell::Powershell => {
+            let _ = writeln!(
+                out,
+                "Register-ArgumentCompleter -Native -CommandName '{}', '{}.exe' -ScriptBlock {{",
+                bin, bin
+            );
+            let _ = writeln!(out, "    param($wordToComplete, $commandAst, $cursorPosition)");
+            let _ = writeln!(out, "    $opts = @{{");
+            for node in &nodes {
+                let _ = writeln!(out, "        '{}' = @(", node.path);
+                // The leading comma keeps PowerShell from flattening the pairs
+                for (word, help) in &node.candidates {
+                    let help = if help.is_empty() { word } else { help };
+                    let _ = writeln!(out, "            ,@('{}', '{}')", word, help.replace('\'', "''"));
+                }
+                let _ = writeln!(out, "        )");
+            }
+            let _ = writeln!(out, "    }}");
+            let _ = writeln!(out, "    $cmd = '{}'", bin);
+            let _ = writeln!(out, "    foreach ($element in $commandAst.CommandElements | Select-Object -Skip 1) {{");
+            let _ = writeln!(out, "        if ($element.Extent.EndOffset -ge $cursorPosition) {{ break }}");
+            let _ = writeln!(out, "        $next = \"$cmd $element\"");
+            let _ = writeln!(out, "        if ($opts.ContainsKey($next)) {{ $cmd = $next }}");
+            let _ = writeln!(out, "    }}");
+            let _ = writeln!(out, "    $opts[$cmd] | Where-Object {{ $_[0] -like \"$wordToComplete*\" }} | ForEach-Object {{");
+            let _ = writeln!(
+                out,
+                "        [System.Management.Automation.CompletionResult]::new($_[0], $_[0], 'ParameterValue', $_[1])"
+            );
+            let _ = writeln!(out, "    }}");
+            let _ = writeln!(out, "}}");
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::{Arg, ArgAction, Command};
+
+    fn cli() -> Command {
+        Command::new("openniri-cli")
+            .arg(Arg::new("json").long("json").help("Print JSON").global(true).action(ArgAction::SetTrue))
+            .subcommand(
+                Command::new("focus")
+                    .about("Focus commands")
+                    .subcommand(Command::new("left").about("Focus the column to the left"))
+                    .subcommand(Command::new("secret").hide(true)),
+            )
+            .subcommand(Command::new("status").about("Query daemon's status"))
+    }
+
+    #[test]
+    fn test_nodes_follow_command_tree() {
+        let nodes = nodes(&cli(), &Aliases::new());
+        let paths: Vec<_> = nodes.iter().map(|n| n.path.as_str()).collect();
+        assert!(paths.starts_with(&["openniri-cli", "openniri-cli focus", "openniri-cli focus left"]));
+        assert!(!paths.contains(&"openniri-cli focus secret"));
+
+        let focus = &nodes[1];
+        assert!(focus.candidates.contains(&("left".to_string(), "Focus the column to the left".to_string())));
+        // Global options are offered below the top level too
+        assert!(focus.candidates.iter().any(|(word, _)| word == "--json"));
+    }
+
+    #[test]
+    fn test_aliases_are_top_level_candidates() {
+        let aliases = Aliases::from([
+            ("ff".to_string(), "focus left".to_string()),
+            ("status".to_string(), "focus left".to_string()),
+        ]);
+        let nodes = nodes(&cli(), &aliases);
+        let root = &nodes[0].candidates;
+        assert!(root.contains(&("ff".to_string(), "Alias for 'focus left'".to_string())));
+        // Aliases shadowing a built-in are never expanded, so not offered
+        assert_eq!(root.iter().filter(|(word, _)| word == "status").count(), 1);
+    }
+
+    #[test]
+    fn test_generate_scripts() {
+        let aliases = Aliases::new();
+        let bash = generate(Shell::Bash, &cli(), &aliases);
+        assert!(bash.contains("[\"openniri-cli focus\"]=\"left help --json --help\""));
+        assert!(bash.contains("complete -F _openniri_cli openniri-cli openniri-cli.exe"));
+
+        let zsh = generate(Shell::Zsh, &cli(), &aliases);
+        assert!(zsh.starts_with("#compdef openniri-cli openniri-cli.exe"));
+        assert!(zsh.contains("'openniri-cli focus' 'left help --json --help'"));
+
+        let powershell = generate(Shell::Powershell, &cli(), &aliases);
+        assert!(powershell.contains("Register-ArgumentCompleter -Native -CommandName 'openniri-cli', 'openniri-cli.exe'"));
+        // Single quotes in help text are escaped
+        assert!(powershell.contains(",@('status', 'Query daemon''s status')"));
+    }
+}
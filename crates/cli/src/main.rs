@@ -4,27 +4,60 @@
 //!
 //! Commands are sent to the daemon via IPC (named pipe).
 
+mod aliases;
+mod completions;
+mod wire;
+
 use anyhow::{Context, Result};
-use clap::{Parser, Subcommand};
-use directories::ProjectDirs;
-use openniri_ipc::{IpcCommand, IpcResponse, PIPE_NAME};
+use clap::{CommandFactory, Parser, Subcommand};
+use completions::Shell;
+use openniri_client::Client;
+use openniri_ipc::{
+    normalize_pipe_name, pipe_name_for_current_user, ready_event_name, session_suffix, ConfigDiagnostic, DiagnosticSeverity,
+    IpcCommand, IpcResponse, NewWindowPosition, SnapEdge, PROTOCOL_VERSION,
+};
+use openniri_platform_win32::{current_session_id, uncloak_all_visible_windows, wait_for_ready};
 use std::fs;
 use std::fs::File;
 use std::path::PathBuf;
 use std::process::{Command, Stdio};
+use std::sync::OnceLock;
 use std::time::{Duration, Instant};
-use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
-use tokio::net::windows::named_pipe::ClientOptions;
-use tokio::time::{sleep, timeout};
+use tokio::time::sleep;
 
 /// Connection timeout for IPC commands.
 const IPC_TIMEOUT: Duration = Duration::from_secs(5);
 const RUN_WAIT_DEFAULT_MS: u64 = 5000;
+/// Default template for `bar` output.
+const DEFAULT_BAR_FORMAT: &str = "{monitor} {col_idx}/{col_count} {title:30}";
+/// Default interval for `--watch` in milliseconds.
+const WATCH_DEFAULT_MS: &str = "1000";
 
 #[derive(Parser)]
 #[command(name = "openniri-cli")]
 #[command(author, version, about = "Control the OpenNiri window manager")]
 struct Cli {
+    /// Print the raw JSON response, one line per response
+    #[arg(long, global = true)]
+    json: bool,
+    /// Repeat a query every MS milliseconds (`--watch` or `--watch=MS`, default 1000) until interrupted
+    #[arg(
+        long,
+        global = true,
+        value_name = "MS",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = WATCH_DEFAULT_MS,
+        value_parser = clap::value_parser!(u64).range(100..)
+    )]
+    watch: Option<u64>,
+    /// Talk to the daemon on this pipe instead of the current user's default
+    #[arg(long, global = true, value_name = "NAME")]
+    pipe_name: Option<String>,
+    /// Wait up to SECS seconds for the daemon to signal it is ready before
+    /// sending the command, e.g. in startup scripts
+    #[arg(long, global = true, visible_alias = "wait-ready", value_name = "SECS")]
+    wait_for_daemon: Option<u64>,
     #[command(subcommand)]
     command: Commands,
 }
@@ -32,9 +65,20 @@ struct Cli {
 #[derive(Subcommand)]
 enum Commands {
     /// Focus commands
+    ///
+    /// `focus --pick` lists managed windows and focuses the chosen one;
+    /// `focus --id ID` focuses a window from `query all-windows` directly,
+    /// e.g. for use with fzf or rofi.
+    #[command(args_conflicts_with_subcommands = true, arg_required_else_help = true)]
     Focus {
+        /// Choose a window from a numbered list
+        #[arg(long, conflicts_with = "id")]
+        pick: bool,
+        /// Focus the window with this ID
+        #[arg(long, value_name = "ID")]
+        id: Option<u64>,
         #[command(subcommand)]
-        direction: FocusDirection,
+        direction: Option<FocusDirection>,
     },
     /// Scroll the viewport
     Scroll {
@@ -46,22 +90,56 @@ enum Commands {
         #[command(subcommand)]
         direction: MoveDirection,
     },
+    /// Move a window (by ID from `query all`) into a column of its workspace
+    MoveWindow {
+        /// Window ID
+        #[arg(long)]
+        id: u64,
+        /// Column number (1 = leftmost), counted before the window leaves its own
+        #[arg(long, value_parser = column_number())]
+        column: usize,
+        /// Position in the column's stack (1 = top; default: bottom)
+        #[arg(long, value_parser = column_number())]
+        position: Option<usize>,
+    },
+    /// Stack the focused window into a neighboring column, or expel it from a shared one
+    Consume {
+        #[command(subcommand)]
+        direction: ConsumeDirection,
+    },
+    /// Split the focused window off its stack into a new column on the right
+    SplitColumn,
+    /// Swap the focused window with the top window of its stack
+    Promote,
     /// Resize the focused column
     Resize {
         /// Width delta in pixels (positive to grow, negative to shrink)
         #[arg(short, long)]
         delta: i32,
     },
+    /// Resize the focused window within its stack
+    ResizeHeight {
+        /// Height delta in pixels (positive to grow, negative to shrink)
+        #[arg(short, long, allow_negative_numbers = true)]
+        delta: i32,
+    },
     /// Focus a different monitor
     FocusMonitor {
         #[command(subcommand)]
-        direction: MonitorDirection,
+        direction: FocusMonitorDirection,
     },
     /// Move the focused window to a different monitor
     MoveToMonitor {
         #[command(subcommand)]
         direction: MonitorDirection,
     },
+    /// Move the focused column (all stacked windows) to a different monitor
+    MoveColumnToMonitor {
+        #[command(subcommand)]
+        direction: MonitorDirection,
+    },
+    /// Move every tiled window from the other monitors onto the focused one
+    Gather,
     /// Query workspace state
     Query {
         #[command(subcommand)]
@@ -69,6 +147,8 @@ enum Commands {
     },
     /// Re-enumerate windows
     Refresh,
+    /// Remove windows that are gone but still managed
+    Prune,
     /// Apply current layout to windows
     Apply,
     /// Reload configuration from file
@@ -81,6 +161,13 @@ enum Commands {
         /// How long to wait for the daemon to become ready (milliseconds)
         #[arg(long, default_value_t = RUN_WAIT_DEFAULT_MS)]
         wait_ms: u64,
+        /// Restart the daemon automatically if it crashes
+        #[arg(long)]
+        supervised: bool,
+        /// Start the daemon without hooks, hotkeys or cloaking, to diagnose
+        /// input lag or crashes
+        #[arg(long)]
+        safe_mode: bool,
     },
     /// Generate default configuration file
     Init {
@@ -93,27 +180,122 @@ enum Commands {
     },
     /// Stop the daemon
     Stop,
+    /// Make every window visible again, directly if the daemon doesn't respond
+    RestoreWindows,
     /// Close the focused window
     CloseWindow,
     /// Toggle floating for the focused window
     ToggleFloating,
+    /// Move, resize, center or snap the focused floating window
+    Floating {
+        #[command(subcommand)]
+        action: FloatingAction,
+    },
     /// Toggle fullscreen for the focused window
     ToggleFullscreen,
+    /// Zoom out to pick a column, or close the overview
+    Overview,
+    /// Resize the focused column with the arrow keys (Enter keeps, Escape reverts)
+    ResizeMode,
     /// Set the focused column width
     SetWidth {
         /// Width as fraction of viewport (e.g., 0.333, 0.5, 0.667)
         #[arg(short, long)]
         fraction: f64,
     },
+    /// Snap the focused column to the next width preset
+    CycleWidth,
     /// Equalize all column widths
     EqualizeWidths,
+    /// Toggle stretching columns to fill the screen on the focused workspace
+    ToggleAutoBalance,
+    /// Set where new windows open on the focused workspace (until the config is reloaded)
+    NewWindowPosition {
+        #[command(subcommand)]
+        position: PositionArg,
+    },
+    /// Collapse the focused column to a thin placeholder
+    CollapseColumn,
+    /// Expand the focused column and focus its window
+    ExpandColumn,
+    /// Show the focused column's windows as tabs (one at a time, full height)
+    ToggleTabbed,
+    /// Expand the focused column to the screen width, or restore its width
+    MaximizeColumn,
+    /// Pause or resume tiling on the focused monitor
+    TogglePauseMonitor,
+    /// Apply a project profile from the config (launch its apps, set widths)
+    ApplyProject {
+        /// Project name
+        name: String,
+    },
+    /// Save or apply a monitor profile (column arrangement per monitor set)
+    Profile {
+        #[command(subcommand)]
+        action: ProfileAction,
+    },
+    /// Switch the focused monitor to a named workspace (created on first use)
+    SwitchWorkspace {
+        /// Workspace name, e.g. "code"
+        name: String,
+    },
+    /// Move the focused window to a named workspace on the same monitor
+    MoveToWorkspace {
+        /// Workspace name, e.g. "chat"
+        name: String,
+    },
+    /// Show or hide a scratchpad window, or put the focused window in one
+    Scratchpad {
+        #[command(subcommand)]
+        action: ScratchpadAction,
+    },
     /// Query daemon status
     Status,
+    /// Show event pipeline counters (backlog, dropped events)
+    Metrics,
+    /// Show animation frame statistics (missed frames, average frame time)
+    Perf,
+    /// Show the daemon's protocol version and the commands it supports
+    Capabilities,
+    /// Check a config file for unknown keys, invalid hotkeys and conflicting rules
+    CheckConfig {
+        /// Config file to check (default: the file the daemon loaded)
+        #[arg(short, long)]
+        file: Option<PathBuf>,
+    },
+    /// Change the daemon's log level until it restarts or reloads its config
+    LogLevel {
+        /// trace, debug, info, warn, error or off
+        level: String,
+    },
+    /// Print the last lines of the daemon's log file
+    Logs {
+        /// Number of lines to print
+        #[arg(short = 'n', long, default_value_t = 100)]
+        lines: usize,
+    },
+    /// Print a one-line summary per monitor (for status bars)
+    Bar {
+        /// Line template. Placeholders: {monitor} {monitor_name} {col_idx} {col_count}
+        /// {win_idx} {win_count} {windows} {title} {focused}; use {name:N} to truncate
+        #[arg(short, long, default_value = DEFAULT_BAR_FORMAT)]
+        format: String,
+    },
     /// Manage auto-start on login
     Autostart {
         #[command(subcommand)]
         action: AutostartAction,
     },
+    /// Print a shell completion script, including the config's aliases
+    Completions {
+        /// Shell to generate the script for
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+    /// Any IPC command by its wire name, e.g. `focus-urgent` or
+    /// `move_column_to_index to=2`
+    #[command(external_subcommand)]
+    Wire(Vec<String>),
 }
 
 #[derive(Subcommand)]
@@ -126,6 +308,15 @@ enum FocusDirection {
     Up,
     /// Focus the window below (in stacked columns)
     Down,
+    /// Focus the previously focused window, on any monitor
+    Previous,
+    /// Focus the window that requested attention first, on any monitor
+    Urgent,
+    /// Focus a column by number (1 = leftmost)
+    Column {
+        #[arg(value_parser = column_number())]
+        number: usize,
+    },
 }
 
 #[derive(Subcommand)]
@@ -150,6 +341,129 @@ enum MoveDirection {
     Left,
     /// Move focused column right
     Right,
+    /// Move focused column up (vertical strips)
+    Up,
+    /// Move focused column down (vertical strips)
+    Down,
+    /// Move focused column to a position (1 = leftmost)
+    To {
+        #[arg(value_parser = column_number())]
+        number: usize,
+    },
+}
+
+#[derive(Subcommand)]
+enum ConsumeDirection {
+    /// Into the column on the left (or out to a new column on the left)
+    Left,
+    /// Into the column on the right (or out to a new column on the right)
+    Right,
+    /// Into the column above (or out to a new column above; vertical strips)
+    Up,
+    /// Into the column below (or out to a new column below; vertical strips)
+    Down,
+}
+
+#[derive(Subcommand)]
+enum ProfileAction {
+    /// Save the current arrangement for the connected monitors
+    Save {
+        /// Profile name, e.g. "docked"
+        name: String,
+    },
+    /// Move open windows into a saved profile's columns
+    Apply {
+        /// Profile name, e.g. "docked"
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum ScratchpadAction {
+    /// Show the scratchpad's window centered on the focused monitor, or hide it
+    Toggle {
+        /// Scratchpad name, e.g. "term"
+        name: String,
+    },
+    /// Take the focused window out of tiling and hide it in the scratchpad
+    Move {
+        /// Scratchpad name, e.g. "term"
+        name: String,
+    },
+}
+
+#[derive(Subcommand)]
+enum FloatingAction {
+    /// Move by a pixel offset (kept inside the work area)
+    Move {
+        /// Horizontal offset in pixels (positive = right)
+        #[arg(long, default_value_t = 0, allow_negative_numbers = true)]
+        dx: i32,
+        /// Vertical offset in pixels (positive = down)
+        #[arg(long, default_value_t = 0, allow_negative_numbers = true)]
+        dy: i32,
+    },
+    /// Grow or shrink around the top-left corner
+    Resize {
+        /// Width delta in pixels
+        #[arg(long, default_value_t = 0, allow_negative_numbers = true)]
+        dw: i32,
+        /// Height delta in pixels
+        #[arg(long, default_value_t = 0, allow_negative_numbers = true)]
+        dh: i32,
+    },
+    /// Center in the work area
+    Center,
+    /// Move flush against a work area edge
+    Snap {
+        #[command(subcommand)]
+        edge: EdgeDirection,
+    },
+    /// Keep above all other windows (toggles without --on/--off)
+    AlwaysOnTop {
+        /// Turn always-on-top on
+        #[arg(long, conflicts_with = "off")]
+        on: bool,
+        /// Turn always-on-top off
+        #[arg(long)]
+        off: bool,
+    },
+}
+
+#[derive(Subcommand)]
+enum PositionArg {
+    /// Right of the focused column
+    RightOfFocus,
+    /// After the last column
+    EndOfStrip,
+    /// Before the first column
+    Start,
+    /// Into empty space on screen, else right of the focused column
+    NearestEmpty,
+}
+
+#[derive(Subcommand)]
+enum EdgeDirection {
+    /// Left edge
+    Left,
+    /// Right edge
+    Right,
+    /// Top edge
+    Top,
+    /// Bottom edge
+    Bottom,
+}
+
+#[derive(Subcommand)]
+enum FocusMonitorDirection {
+    /// Focus the nearest monitor on the left
+    Left,
+    /// Focus the nearest monitor on the right
+    Right,
+    /// Focus the nearest monitor above
+    Up,
+    /// Focus the nearest monitor below
+    Down,
 }
 
 #[derive(Subcommand)]
@@ -178,15 +492,27 @@ enum AutostartAction {
     Disable,
 }
 
+/// Parser for 1-based column and stack positions.
+fn column_number() -> clap::builder::RangedU64ValueParser<usize> {
+    clap::builder::RangedU64ValueParser::new().range(1..)
+}
+
 /// Convert CLI command to IPC command.
 fn to_ipc_command(cmd: &Commands) -> IpcCommand {
     match cmd {
-        Commands::Focus { direction } => match direction {
+        Commands::Focus { direction: Some(direction), .. } => match direction {
             FocusDirection::Left => IpcCommand::FocusLeft,
             FocusDirection::Right => IpcCommand::FocusRight,
             FocusDirection::Up => IpcCommand::FocusUp,
             FocusDirection::Down => IpcCommand::FocusDown,
+            FocusDirection::Previous => IpcCommand::FocusPrevious,
+            FocusDirection::Urgent => IpcCommand::FocusUrgent,
+            // The daemon counts columns from 0
+            FocusDirection::Column { number } => IpcCommand::FocusColumn { index: number - 1 },
         },
+        Commands::Focus { id: Some(window_id), .. } => IpcCommand::FocusWindowById { window_id: *window_id },
+        // The picker starts from the window list
+        Commands::Focus { .. } => IpcCommand::QueryAllWindows,
         Commands::Scroll { direction } => match direction {
             ScrollDirection::Left { pixels } => IpcCommand::Scroll {
                 delta: -(*pixels as f64),
@@ -198,34 +524,113 @@ fn to_ipc_command(cmd: &Commands) -> IpcCommand {
         Commands::Move { direction } => match direction {
             MoveDirection::Left => IpcCommand::MoveColumnLeft,
             MoveDirection::Right => IpcCommand::MoveColumnRight,
+            MoveDirection::Up => IpcCommand::MoveColumnUp,
+            MoveDirection::Down => IpcCommand::MoveColumnDown,
+            MoveDirection::To { number } => IpcCommand::MoveColumnToIndex { from: None, to: number - 1 },
+        },
+        Commands::MoveWindow { id, column, position } => IpcCommand::MoveWindowToColumn {
+            window_id: *id,
+            column: column - 1,
+            position: position.map(|p| p - 1),
         },
+        Commands::Consume { direction } => match direction {
+            ConsumeDirection::Left => IpcCommand::ConsumeWindowLeft,
+            ConsumeDirection::Right => IpcCommand::ConsumeWindowRight,
+            ConsumeDirection::Up => IpcCommand::ConsumeWindowUp,
+            ConsumeDirection::Down => IpcCommand::ConsumeWindowDown,
+        },
+        Commands::SplitColumn => IpcCommand::SplitColumn,
+        Commands::Promote => IpcCommand::PromoteWindow,
         Commands::Resize { delta } => IpcCommand::Resize { delta: *delta },
+        Commands::ResizeHeight { delta } => IpcCommand::ResizeWindowVertical { delta: *delta },
         Commands::FocusMonitor { direction } => match direction {
-            MonitorDirection::Left => IpcCommand::FocusMonitorLeft,
-            MonitorDirection::Right => IpcCommand::FocusMonitorRight,
+            FocusMonitorDirection::Left => IpcCommand::FocusMonitorLeft,
+            FocusMonitorDirection::Right => IpcCommand::FocusMonitorRight,
+            FocusMonitorDirection::Up => IpcCommand::FocusMonitorUp,
+            FocusMonitorDirection::Down => IpcCommand::FocusMonitorDown,
         },
         Commands::MoveToMonitor { direction } => match direction {
             MonitorDirection::Left => IpcCommand::MoveWindowToMonitorLeft,
             MonitorDirection::Right => IpcCommand::MoveWindowToMonitorRight,
         },
+        Commands::MoveColumnToMonitor { direction } => match direction {
+            MonitorDirection::Left => IpcCommand::MoveColumnToMonitorLeft,
+            MonitorDirection::Right => IpcCommand::MoveColumnToMonitorRight,
+        },
+        Commands::Gather => IpcCommand::GatherWindows,
         Commands::Query { what } => match what {
             QueryType::Workspace => IpcCommand::QueryWorkspace,
             QueryType::Focused => IpcCommand::QueryFocused,
             QueryType::All => IpcCommand::QueryAllWindows,
         },
         Commands::Refresh => IpcCommand::Refresh,
+        Commands::Prune => IpcCommand::Prune,
         Commands::Apply => IpcCommand::Apply,
         Commands::Reload => IpcCommand::Reload,
         Commands::CloseWindow => IpcCommand::CloseWindow,
         Commands::ToggleFloating => IpcCommand::ToggleFloating,
+        Commands::Floating { action } => match action {
+            FloatingAction::Move { dx, dy } => IpcCommand::MoveFloating { dx: *dx, dy: *dy },
+            FloatingAction::Resize { dw, dh } => IpcCommand::ResizeFloating { dw: *dw, dh: *dh },
+            FloatingAction::Center => IpcCommand::CenterFloating,
+            FloatingAction::Snap { edge } => IpcCommand::SnapFloating {
+                edge: match edge {
+                    EdgeDirection::Left => SnapEdge::Left,
+                    EdgeDirection::Right => SnapEdge::Right,
+                    EdgeDirection::Top => SnapEdge::Top,
+                    EdgeDirection::Bottom => SnapEdge::Bottom,
+                },
+            },
+            FloatingAction::AlwaysOnTop { on: true, .. } => IpcCommand::SetAlwaysOnTop { enabled: true },
+            FloatingAction::AlwaysOnTop { off: true, .. } => IpcCommand::SetAlwaysOnTop { enabled: false },
+            FloatingAction::AlwaysOnTop { .. } => IpcCommand::ToggleAlwaysOnTop,
+        },
         Commands::ToggleFullscreen => IpcCommand::ToggleFullscreen,
+        Commands::Overview => IpcCommand::ToggleOverview,
+        Commands::ResizeMode => IpcCommand::EnterResizeMode,
         Commands::SetWidth { fraction } => IpcCommand::SetColumnWidth { fraction: *fraction },
+        Commands::CycleWidth => IpcCommand::CycleColumnWidth,
         Commands::EqualizeWidths => IpcCommand::EqualizeColumnWidths,
+        Commands::ToggleAutoBalance => IpcCommand::ToggleAutoBalance,
+        Commands::NewWindowPosition { position } => IpcCommand::SetNewWindowPosition {
+            position: match position {
+                PositionArg::RightOfFocus => NewWindowPosition::RightOfFocus,
+                PositionArg::EndOfStrip => NewWindowPosition::EndOfStrip,
+                PositionArg::Start => NewWindowPosition::Start,
+                PositionArg::NearestEmpty => NewWindowPosition::NearestEmpty,
+            },
+        },
+        Commands::CollapseColumn => IpcCommand::CollapseColumn,
+        Commands::ExpandColumn => IpcCommand::ExpandColumn,
+        Commands::ToggleTabbed => IpcCommand::ToggleColumnTabbed,
+        Commands::MaximizeColumn => IpcCommand::ToggleMaximizeColumn,
+        Commands::TogglePauseMonitor => IpcCommand::TogglePauseMonitor,
+        Commands::ApplyProject { name } => IpcCommand::ApplyProject { name: name.clone() },
+        Commands::Profile { action } => match action {
+            ProfileAction::Save { name } => IpcCommand::SaveMonitorProfile { name: name.clone() },
+            ProfileAction::Apply { name } => IpcCommand::ApplyMonitorProfile { name: name.clone() },
+        },
+        Commands::SwitchWorkspace { name } => IpcCommand::SwitchWorkspace { name: name.clone() },
+        Commands::Scratchpad { action } => match action {
+            ScratchpadAction::Toggle { name } => IpcCommand::ToggleScratchpad { name: name.clone() },
+            ScratchpadAction::Move { name } => IpcCommand::MoveToScratchpad { name: name.clone() },
+        },
+        Commands::MoveToWorkspace { name } => IpcCommand::MoveWindowToWorkspace { name: name.clone() },
         Commands::Status => IpcCommand::QueryStatus,
+        Commands::Metrics => IpcCommand::QueryMetrics,
+        Commands::Perf => IpcCommand::QueryPerfStats,
+        Commands::Capabilities => IpcCommand::Hello { protocol_version: PROTOCOL_VERSION },
+        Commands::Bar { format } => IpcCommand::QueryBarText { format: format.clone() },
+        Commands::CheckConfig { .. } => IpcCommand::CheckConfig { contents: None },
+        Commands::LogLevel { level } => IpcCommand::SetLogLevel { level: level.clone() },
+        Commands::Logs { lines } => IpcCommand::QueryLogs { lines: *lines },
         Commands::Run { .. } => unreachable!("Run is handled separately"),
         Commands::Init { .. } => unreachable!("Init is handled separately"),
         Commands::Autostart { .. } => unreachable!("Autostart is handled separately"),
+        Commands::Completions { .. } => unreachable!("Completions is handled separately"),
+        Commands::Wire(_) => unreachable!("Wire commands are parsed separately"),
         Commands::Stop => IpcCommand::Stop,
+        Commands::RestoreWindows => IpcCommand::RestoreAll,
     }
 }
 
@@ -286,11 +691,12 @@ fn apply_detach_flags(cmd: &mut Command) {
 #[cfg(not(windows))]
 fn apply_detach_flags(_cmd: &mut Command) {}
 
-fn spawn_daemon() -> Result<u32> {
+fn spawn_daemon(supervised: bool, safe_mode: bool) -> Result<u32> {
     let daemon_path = ensure_daemon_binary()?;
     let log_dir = std::env::temp_dir();
-    let stdout_path = log_dir.join("openniri-daemon.log");
-    let stderr_path = log_dir.join("openniri-daemon.err.log");
+    let suffix = session_suffix(current_session_id());
+    let stdout_path = log_dir.join(format!("openniri-daemon{}.log", suffix));
+    let stderr_path = log_dir.join(format!("openniri-daemon{}.err.log", suffix));
 
     let stdout = File::create(&stdout_path).context("Failed to create daemon stdout log")?;
     let stderr = File::create(&stderr_path).context("Failed to create daemon stderr log")?;
@@ -299,6 +705,15 @@ fn spawn_daemon() -> Result<u32> {
     cmd.stdin(Stdio::null())
         .stdout(stdout)
         .stderr(stderr);
+    if let Some(name) = PIPE_NAME_OVERRIDE.get() {
+        cmd.args(["--pipe-name", name]);
+    }
+    if supervised {
+        cmd.arg("--supervised");
+    }
+    if safe_mode {
+        cmd.arg("--safe-mode");
+    }
     apply_detach_flags(&mut cmd);
 
     let child = cmd.spawn().context("Failed to start openniri daemon")?;
@@ -311,48 +726,60 @@ fn spawn_daemon() -> Result<u32> {
     Ok(child.id())
 }
 
-async fn wait_for_daemon(timeout: Duration) -> Result<()> {
-    let _ = open_pipe_with_retry(timeout).await?;
-    Ok(())
-}
-
-fn is_pipe_busy(err: &std::io::Error) -> bool {
-    err.raw_os_error() == Some(231)
-}
+/// Pipe given with `--pipe-name`, set once at startup.
+static PIPE_NAME_OVERRIDE: OnceLock<String> = OnceLock::new();
 
-fn is_pipe_not_found(err: &std::io::Error) -> bool {
-    err.raw_os_error() == Some(2)
+/// Pipe of the daemon to talk to: the `--pipe-name` override, or the
+/// current user's daemon in this logon session.
+fn pipe_name() -> String {
+    PIPE_NAME_OVERRIDE
+        .get()
+        .cloned()
+        .unwrap_or_else(pipe_name_for_current_user)
 }
 
-async fn open_pipe_with_retry(
-    timeout: Duration,
-) -> Result<tokio::net::windows::named_pipe::NamedPipeClient> {
+/// Wait until the daemon has finished starting up.
+///
+/// The daemon sets its ready event (see [`ready_event_name`]) once its event
+/// loop runs, after windows are enumerated and hooks and hotkeys are
+/// installed. A `Hello` then checks that it answers on our pipe; connection
+/// failures (e.g. the daemon restarted by its supervisor) are retried until
+/// `timeout`.
+async fn wait_for_daemon(timeout_duration: Duration) -> Result<()> {
     let start = Instant::now();
+    let not_ready = || format!("Daemon not ready after {:.1}s", timeout_duration.as_secs_f64());
+    let event_name = ready_event_name(&pipe_name());
+    let signalled = tokio::task::spawn_blocking(move || wait_for_ready(&event_name, timeout_duration))
+        .await
+        .context("Ready event wait panicked")??;
+    if !signalled {
+        anyhow::bail!(not_ready());
+    }
     loop {
-        match ClientOptions::new().open(PIPE_NAME) {
-            Ok(client) => return Ok(client),
-            Err(e) if is_pipe_busy(&e) || is_pipe_not_found(&e) => {
-                if start.elapsed() >= timeout {
-                    return Err(e).context("Failed to connect to daemon. Is openniri running?");
-                }
-            }
-            Err(e) => {
-                return Err(e).context("Failed to connect to daemon. Is openniri running?");
-            }
+        let remaining = timeout_duration.saturating_sub(start.elapsed());
+        let hello = IpcCommand::Hello { protocol_version: PROTOCOL_VERSION };
+        match send_command_with_timeout(hello, remaining).await {
+            Ok(_) => return Ok(()),
+            Err(e) if start.elapsed() >= timeout_duration => return Err(e).context(not_ready()),
+            Err(_) => sleep(Duration::from_millis(100)).await,
         }
-        sleep(Duration::from_millis(100)).await;
     }
 }
 
-async fn handle_run(no_apply: bool, wait_ms: u64) -> Result<()> {
-    let already_running = match ClientOptions::new().open(PIPE_NAME) {
-        Ok(_) => true,
-        Err(e) if is_pipe_busy(&e) => true,
-        Err(_) => false,
-    };
+/// Whether a command talks to a running daemon, so `--wait-for-daemon`
+/// applies to it. `run` starts the daemon and waits on its own.
+fn uses_daemon(command: &Commands) -> bool {
+    !matches!(
+        command,
+        Commands::Init { .. } | Commands::Completions { .. } | Commands::Run { .. } | Commands::Autostart { .. }
+    )
+}
+
+async fn handle_run(no_apply: bool, wait_ms: u64, supervised: bool, safe_mode: bool, json: bool) -> Result<()> {
+    let already_running = openniri_client::is_running(&pipe_name());
 
     if !already_running {
-        spawn_daemon()?;
+        spawn_daemon(supervised, safe_mode)?;
     } else {
         println!("Daemon already running.");
     }
@@ -365,8 +792,8 @@ async fn handle_run(no_apply: bool, wait_ms: u64) -> Result<()> {
 
     let response =
         send_command_with_timeout(IpcCommand::Apply, Duration::from_millis(wait_ms)).await?;
-    print_response(&response);
-    if matches!(response, IpcResponse::Error { .. }) {
+    print_output(&response, json)?;
+    if response.is_error() {
         std::process::exit(1);
     }
 
@@ -375,48 +802,40 @@ async fn handle_run(no_apply: bool, wait_ms: u64) -> Result<()> {
 
 /// Send a command to the daemon and return the response (with timeout).
 async fn send_command(cmd: IpcCommand) -> Result<IpcResponse> {
-    timeout(IPC_TIMEOUT, send_command_inner(cmd, IPC_TIMEOUT))
-        .await
-        .context("Timed out waiting for daemon response")?
+    send_command_with_timeout(cmd, IPC_TIMEOUT).await
 }
 
-/// Inner implementation without timeout.
+/// Send a command to the daemon and return the response, allowing
+/// `timeout_duration` for connecting and answering.
 async fn send_command_with_timeout(cmd: IpcCommand, timeout_duration: Duration) -> Result<IpcResponse> {
-    timeout(timeout_duration, send_command_inner(cmd, timeout_duration))
-        .await
-        .context("Timed out waiting for daemon response")?
+    let client = Client::new(pipe_name()).with_timeout(timeout_duration);
+    Ok(client.send(cmd).await?)
 }
 
-/// Inner implementation without timeout.
-async fn send_command_inner(cmd: IpcCommand, connect_timeout: Duration) -> Result<IpcResponse> {
-    // Connect to the named pipe (retry if busy/starting)
-    let client = open_pipe_with_retry(connect_timeout).await?;
-
-    let (reader, mut writer) = tokio::io::split(client);
-
-    // Send command as JSON line
-    let json = serde_json::to_string(&cmd)? + "\n";
-    writer
-        .write_all(json.as_bytes())
-        .await
-        .context("Failed to send command")?;
-
-    // Read response
-    let mut reader = BufReader::new(reader);
-    let mut line = String::new();
-    let bytes_read = reader
-        .read_line(&mut line)
-        .await
-        .context("Failed to read response")?;
-
-    if bytes_read == 0 {
-        anyhow::bail!("Daemon disconnected before sending a response");
+/// Print a response as raw JSON or in a human-readable format.
+fn print_output(response: &IpcResponse, json: bool) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string(response)?);
+    } else {
+        print_response(response);
     }
+    Ok(())
+}
 
-    let response: IpcResponse =
-        serde_json::from_str(line.trim()).context("Failed to parse response")?;
-
-    Ok(response)
+/// Re-send a query on an interval and print each response until interrupted.
+///
+/// Errors (e.g. the daemon restarting) are reported and the query retried
+/// on the next tick, so status bars keep running.
+async fn watch_query(cmd: IpcCommand, interval: Duration, json: bool) -> Result<()> {
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+    loop {
+        ticker.tick().await;
+        match send_command(cmd.clone()).await {
+            Ok(response) => print_output(&response, json)?,
+            Err(e) => eprintln!("Error: {:#}", e),
+        }
+    }
 }
 
 /// Print a response in a human-readable format.
@@ -435,14 +854,29 @@ fn print_response(response: &IpcResponse) {
             focused_window,
             scroll_offset,
             total_width,
+            workspace_name,
+            columns_detail,
         } => {
             println!("Workspace State:");
+            if let Some(name) = workspace_name {
+                println!("  Name: {}", name);
+            }
             println!("  Columns: {}", columns);
             println!("  Windows: {}", windows);
             println!("  Focused column: {}", focused_column);
             println!("  Focused window in column: {}", focused_window);
             println!("  Scroll offset: {:.1}", scroll_offset);
             println!("  Total width: {}", total_width);
+            for (i, column) in columns_detail.iter().enumerate() {
+                println!(
+                    "  [{}] {}px ({:.0}%), {} window(s){}",
+                    i,
+                    column.width,
+                    column.width_fraction * 100.0,
+                    column.window_count,
+                    if column.visible { "" } else { ", off-screen" }
+                );
+            }
         }
         IpcResponse::FocusedWindow {
             window_id,
@@ -466,7 +900,11 @@ fn print_response(response: &IpcResponse) {
                     format!("col {} win {}", win.column_index.unwrap_or(0), win.window_index.unwrap_or(0))
                 };
                 let focus_marker = if win.is_focused { " [FOCUSED]" } else { "" };
-                println!("  {} - {} ({}) [{}]{}", win.window_id, win.title, win.executable, location, focus_marker);
+                let urgent_marker = if win.is_urgent { " [URGENT]" } else { "" };
+                println!(
+                    "  {} - {} ({}) [{}]{}{}",
+                    win.window_id, win.title, win.executable, location, focus_marker, urgent_marker
+                );
             }
         }
         IpcResponse::FocusedWindowInfo { window } => {
@@ -493,17 +931,188 @@ fn print_response(response: &IpcResponse) {
                 }
             }
         }
-        IpcResponse::StatusInfo { version, monitors, total_windows, uptime_seconds } => {
+        IpcResponse::StatusInfo {
+            version,
+            monitors,
+            total_windows,
+            uptime_seconds,
+            session_id,
+            active_project,
+            degraded,
+            safe_mode,
+            subsystems,
+        } => {
             println!("OpenNiri Daemon Status:");
             println!("  Version: {}", version);
+            match session_id {
+                Some(id) => println!("  Session: {}", id),
+                None => println!("  Session: unknown"),
+            }
             println!("  Monitors: {}", monitors);
             println!("  Total windows: {}", total_windows);
             let hours = uptime_seconds / 3600;
             let mins = (uptime_seconds % 3600) / 60;
             let secs = uptime_seconds % 60;
             println!("  Uptime: {}h {}m {}s", hours, mins, secs);
+            if let Some(project) = active_project {
+                println!("  Project: {}", project);
+            }
+            for feature in degraded {
+                println!("  Degraded: {}", feature);
+            }
+            if *safe_mode {
+                println!("  Safe mode: hooks, hotkeys and cloaking disabled");
+            }
+            if !subsystems.is_empty() {
+                println!("  Subsystems: {}", subsystems.join(", "));
+            }
+        }
+        IpcResponse::BarText { lines } => {
+            for line in lines {
+                println!("{}", line);
+            }
         }
+        IpcResponse::Logs { path, lines } => match path {
+            Some(_) => {
+                for line in lines {
+                    println!("{}", line);
+                }
+            }
+            None => println!("The daemon is not logging to a file"),
+        },
+        IpcResponse::Metrics {
+            hook_backlog,
+            hook_backlog_peak,
+            hook_capacity,
+            hook_events_dropped,
+            hook_events_coalesced,
+            hook_events_unmanaged,
+            queue_backlog,
+            queue_backlog_peak,
+            queue_capacity,
+        } => {
+            println!("OpenNiri Event Metrics:");
+            println!("  Hook channel: {}/{} queued (peak {})", hook_backlog, hook_capacity, hook_backlog_peak);
+            println!("  Hook events dropped: {}", hook_events_dropped);
+            println!(
+                "  Location changes coalesced: {} (unmanaged skipped: {})",
+                hook_events_coalesced, hook_events_unmanaged
+            );
+            println!("  Event queue: {}/{} queued (peak {})", queue_backlog, queue_capacity, queue_backlog_peak);
+        }
+        IpcResponse::PerfStats {
+            animating,
+            vblank_synced,
+            frame_period_ms,
+            frames,
+            missed_frames,
+            average_frame_ms,
+        } => {
+            println!("OpenNiri Animation Stats:");
+            println!(
+                "  Pacing: {} ({:.2} ms per frame){}",
+                if *vblank_synced { "display refresh" } else { "timer" },
+                frame_period_ms,
+                if *animating { ", animating" } else { "" }
+            );
+            println!("  Frames: {} (missed {})", frames, missed_frames);
+            println!("  Average frame time: {:.2} ms", average_frame_ms);
+        }
+        IpcResponse::ConfigDiagnostics { path, diagnostics } => {
+            let path = path.as_deref().unwrap_or("config");
+            if diagnostics.is_empty() {
+                println!("{}: no problems found", path);
+            }
+            for line in format_diagnostics(path, diagnostics) {
+                println!("{}", line);
+            }
+        }
+        IpcResponse::ServerInfo { version, protocol_version, capabilities } => {
+            println!("OpenNiri Daemon v{} (protocol {})", version, protocol_version);
+            println!("  Commands: {}", capabilities.join(", "));
+        }
+        IpcResponse::ProtocolMismatch { client_version, server_version } => {
+            eprintln!(
+                "Error: the daemon speaks protocol {} but this CLI speaks {}. Use matching versions of both.",
+                server_version, client_version
+            );
+        }
+        IpcResponse::UnsupportedCommand { command } => {
+            eprintln!(
+                "Error: the daemon does not support '{}'. It may be older than this CLI; restart it after upgrading.",
+                command
+            );
+        }
+        // Includes IpcResponse::Unknown: a response kind added after this CLI was built
+        _ => {
+            eprintln!("Unrecognized response from the daemon. It may be newer than this CLI; use --json to see it.");
+        }
+    }
+}
+
+/// Format config diagnostics as `path:line: severity: field: message`.
+fn format_diagnostics(path: &str, diagnostics: &[ConfigDiagnostic]) -> Vec<String> {
+    diagnostics
+        .iter()
+        .map(|d| {
+            let severity = match d.severity {
+                DiagnosticSeverity::Error => "error",
+                DiagnosticSeverity::Warning => "warning",
+            };
+            let location = match d.line {
+                Some(line) => format!("{}:{}", path, line),
+                None => path.to_string(),
+            };
+            format!("{}: {}: {}: {}", location, severity, d.field, d.message)
+        })
+        .collect()
+}
+
+/// Send a config file (or the daemon's own) for checking and print the diagnostics.
+///
+/// Exits with code 1 if any diagnostic is an error.
+async fn handle_check_config(file: Option<PathBuf>, json: bool) -> Result<()> {
+    let contents = match &file {
+        Some(path) => Some(
+            fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?,
+        ),
+        None => None,
+    };
+    let mut response = send_command(IpcCommand::CheckConfig { contents }).await?;
+    if let (Some(path), IpcResponse::ConfigDiagnostics { path: reported, .. }) = (&file, &mut response) {
+        *reported = Some(path.display().to_string());
+    }
+    print_output(&response, json)?;
+    let has_errors = match &response {
+        IpcResponse::ConfigDiagnostics { diagnostics, .. } => {
+            diagnostics.iter().any(|d| d.severity == DiagnosticSeverity::Error)
+        }
+        other => other.is_error(),
+    };
+    if has_errors {
+        std::process::exit(1);
     }
+    Ok(())
+}
+
+/// Ask the daemon to restore its windows, or uncloak every window on the
+/// system from this process if the daemon is unreachable or fails.
+async fn handle_restore_windows(json: bool) -> Result<()> {
+    let response = match send_command(IpcCommand::RestoreAll).await {
+        Ok(response) if !response.is_error() => response,
+        Ok(response) => {
+            print_output(&response, json)?;
+            eprintln!("Uncloaking all windows directly instead.");
+            uncloak_all_visible_windows();
+            IpcResponse::Ok
+        }
+        Err(e) => {
+            eprintln!("{:#}. Uncloaking all windows directly instead.", e);
+            uncloak_all_visible_windows();
+            IpcResponse::Ok
+        }
+    };
+    print_output(&response, json)
 }
 
 /// Generate default configuration content.
@@ -511,6 +1120,13 @@ fn generate_default_config() -> String {
     r#"# OpenNiri Windows Configuration
 # https://github.com/AdEx-Partners-DE/OpenNiri-Windows
 
+# Which windows to manage: "all" (default) tiles everything not ignored by a
+# window rule; "whitelist" only manages windows matched by a tile/float rule
+# management_mode = "all"
+
+# Executables never touched, with * and ? wildcards (games, launchers)
+# ignore_processes = ["*.tmp.exe", "EpicGamesLauncher.exe"]
+
 [layout]
 # Gap between columns in pixels
 gap = 10
@@ -524,9 +1140,20 @@ default_column_width = 800
 # Minimum column width in pixels
 min_column_width = 400
 
-# Maximum column width in pixels
+# Maximum column width in pixels (columns are never laid out wider)
 max_column_width = 1600
 
+# Stretch columns to fill the screen when they don't cover it
+# (toggle per workspace with "toggle_auto_balance")
+auto_balance = false
+
+# Focusing or moving a column past either end of the strip wraps around
+wrap_navigation = false
+
+# Where new windows open: "right_of_focus", "end_of_strip", "start" or
+# "nearest_empty" (into empty space on screen, else right of the focused column)
+new_window_position = "right_of_focus"
+
 # Centering mode: "center" or "just_in_view"
 # - center: Always center the focused column
 # - just_in_view: Only scroll if focused column would be outside viewport
@@ -536,9 +1163,29 @@ centering_mode = "center"
 # Use DWM cloaking for off-screen windows (keeps them in Alt-Tab)
 use_cloaking = true
 
+# Or pick the hiding method explicitly: "cloak", "move_off_screen" or "minimize"
+# (minimize shows off-strip windows as minimized in the taskbar)
+# hide_strategy = "minimize"
+
 # Use batched window positioning for smoother updates
 use_deferred_positioning = true
 
+# Highlight the focused window's border (on Windows 10, with an outline overlay
+# of the given width in pixels)
+active_border = true
+active_border_color = "4285F4"
+active_border_width = 3
+
+# How long neighbors take to slide into place when a window opens or closes,
+# in milliseconds (0 disables)
+window_animation_ms = 150
+
+# Paint translucent backgrounds behind columns and the empty parts of the
+# viewport instead of showing the desktop there
+column_backgrounds = false
+column_background_color = "202020"
+column_background_opacity = 96
+
 [behavior]
 # Automatically focus new windows when they appear
 focus_new_windows = true
@@ -549,8 +1196,34 @@ track_focus_changes = true
 # Log level: trace, debug, info, warn, error
 log_level = "info"
 
+# Reload this file automatically when it's saved
+watch_config = true
+
 # Focus follows mouse (hover to focus)
 focus_follows_mouse = false
+# Hover time before focus changes
+focus_follows_mouse_delay_ms = 100
+# Only follow the mouse while a modifier is held: "ctrl", "alt", "shift" or "win"
+# focus_follows_mouse_modifier = "ctrl"
+# Also scroll the hovered column fully into view; false focuses it in place
+focus_follows_mouse_strict = true
+
+# Windows taking focus without keyboard or mouse input: "allow" scrolls to
+# them, "refuse" gives focus back, "urgent" also marks them urgent and runs
+# window_urgent hooks
+focus_steal_policy = "allow"
+
+# Mouse wheel over a title bar (or with Win held) scrolls the strip
+wheel_scroll_on_titlebar = false
+
+# Show the column insertion preview for windows dragged by custom title bars
+track_mouse_drags = false
+
+# Manage only windows on the current virtual desktop
+virtual_desktops = true
+
+# Keep each virtual desktop's columns in its own workspace across switches
+desktop_workspaces = false
 
 [hotkeys]
 # Vim-style navigation with Win key
@@ -566,6 +1239,8 @@ focus_follows_mouse = false
 # Resize with Win+Ctrl
 "Win+Ctrl+H" = "resize_shrink"
 "Win+Ctrl+L" = "resize_grow"
+"Win+Ctrl+J" = "height_shrink"
+"Win+Ctrl+K" = "height_grow"
 
 # Close focused window
 "Win+Shift+Q" = "close_window"
@@ -574,11 +1249,50 @@ focus_follows_mouse = false
 "Win+F" = "toggle_floating"
 "Win+Shift+F" = "toggle_fullscreen"
 
+# Overview: arrows or the mouse pick a column, Enter or a click focuses it, Escape cancels
+"Win+O" = "toggle_overview"
+
 # Column width presets
 "Win+1" = "width_third"
 "Win+2" = "width_half"
 "Win+3" = "width_two_thirds"
 "Win+0" = "equalize_widths"
+# Step through layout.preset_widths
+# "Win+W" = "cycle_width"
+# Jump to a column by number, or move the focused column there
+# "Win+Alt+1" = "focus_column 1"
+# "Win+Alt+Shift+1" = "move_column_to 1"
+
+# Collapse / expand the focused column
+"Win+Minus" = "collapse_column"
+"Win+Equals" = "expand_column"
+
+# Scroll the strip with Win + mouse wheel
+# "Win+WheelUp" = "scroll -120"
+# "Win+WheelDown" = "scroll 120"
+
+# Show the focused column's windows as tabs
+# "Win+Shift+T" = "toggle_column_tabbed"
+
+# Expand the focused column to the screen width and back
+# "Win+Shift+M" = "toggle_maximize_column"
+
+# Jump to the window that requested attention (flashing taskbar button)
+# "Win+U" = "focus_urgent"
+
+# Run several commands in order
+# "Win+Shift+Enter" = ["move_column_left", "focus_left"]
+
+# Claim combinations the shell owns through a keyboard hook
+# "Win+Left" = { command = "focus_left", backend = "hook" }
+
+# Named workspaces on the focused monitor (created on first use)
+# "Win+Alt+1" = "switch_workspace code"
+# "Win+Alt+Shift+1" = "move_to_workspace code"
+
+# Scratchpads: summon or hide a window held by a window rule's `scratchpad`
+# "Win+Alt+T" = "toggle_scratchpad term"
+# "Win+Alt+Shift+T" = "move_to_scratchpad term"
 
 [gestures]
 # Touchpad gesture support
@@ -587,6 +1301,9 @@ swipe_left = "focus_left"
 swipe_right = "focus_right"
 swipe_up = "focus_up"
 swipe_down = "focus_down"
+# Pan the viewport with horizontal scrolling and let it coast to a stop
+kinetic_scroll = false
+kinetic_friction = 0.995
 
 [snap_hints]
 # Visual snap hint overlays during resize
@@ -594,18 +1311,72 @@ enabled = true
 duration_ms = 200
 opacity = 128
 
+[ipc]
+# Only the current user (and allowed_group, if set) may send commands
+restrict_to_user = true
+# allowed_group = "Administrators"
+
+# Shortcuts for openniri-cli, e.g. `openniri-cli ff` runs `openniri-cli focus right`
+# [aliases]
+# ff = "focus right"
+# half = "set-width --fraction 0.5"
+
 # [[window_rules]]
 # match_class = "Chrome_WidgetWin_1"
 # match_title = ".*DevTools.*"
 # action = "float"
+
+# With management_mode = "whitelist", list the apps to tile:
+# [[window_rules]]
+# match_executable = "WindowsTerminal.exe"
+# action = "tile"
+
+# Stack chat apps into one shared column:
+# [[window_rules]]
+# match_executable = "slack.exe"
+# column_tag = "comm"
+
+# Open new Explorer windows in the column of a running one (class or exe):
+# [[window_rules]]
+# match_executable = "explorer.exe"
+# stack_with = "explorer.exe"
+
+# Keep a terminal hidden in the "term" scratchpad (see toggle_scratchpad):
+# [[window_rules]]
+# match_executable = "WindowsTerminal.exe"
+# scratchpad = "term"
+# width = 1200
+# height = 700
+
+# Run a command on daemon events (window_created, focus_changed,
+# workspace_scrolled, window_urgent); event data is passed as JSON on stdin:
+# [[hooks]]
+# event = "window_created"
+# command = "powershell"
+# args = ["-NoProfile", "-File", "C:\\scripts\\on_window.ps1"]
+
+# Apply a profile when a window working in a project directory is focused:
+# [[projects]]
+# name = "web"
+# path = "C:\\src\\web"
+# [[projects.launch]]
+# command = "wt.exe"
+# args = ["-d", "."]
+# [[projects.widths]]
+# match_executable = "Code.exe"
+# fraction = 0.667
 "#
     .to_string()
 }
 
-/// Get the default config file path.
+/// Get the default config file path: the first place the daemon looks.
 fn default_config_path() -> Option<PathBuf> {
-    ProjectDirs::from("", "", "openniri")
-        .map(|dirs| dirs.config_dir().join("config.toml"))
+    openniri_ipc::config_paths().into_iter().next()
+}
+
+/// The config file the daemon would load, if one exists.
+fn existing_config_path() -> Option<PathBuf> {
+    openniri_ipc::config_paths().into_iter().find(|path| path.exists())
 }
 
 /// Handle the init command (generate default config).
@@ -682,24 +1453,113 @@ fn handle_autostart(action: AutostartAction) -> Result<()> {
     Ok(())
 }
 
+/// Parse a 1-based choice from the window picker into a list index.
+fn parse_pick(input: &str, count: usize) -> Option<usize> {
+    match input.trim().parse::<usize>() {
+        Ok(n) if (1..=count).contains(&n) => Some(n - 1),
+        _ => None,
+    }
+}
+
+/// List managed windows, read a choice from stdin and focus it.
+async fn handle_pick(json: bool) -> Result<()> {
+    let windows = match send_command(IpcCommand::QueryAllWindows).await? {
+        IpcResponse::WindowList { windows } => windows,
+        other => {
+            print_output(&other, json)?;
+            std::process::exit(1);
+        }
+    };
+    if windows.is_empty() {
+        println!("No managed windows");
+        return Ok(());
+    }
+
+    for (i, win) in windows.iter().enumerate() {
+        let focus_marker = if win.is_focused { " *" } else { "" };
+        println!("{:>3}) {} ({}){}", i + 1, win.title, win.executable, focus_marker);
+    }
+    print!("Window number: ");
+    std::io::Write::flush(&mut std::io::stdout())?;
+
+    let mut input = String::new();
+    std::io::stdin().read_line(&mut input).context("Failed to read choice")?;
+    let Some(index) = parse_pick(&input, windows.len()) else {
+        anyhow::bail!("Invalid choice: {:?} (expected 1-{})", input.trim(), windows.len());
+    };
+
+    let response = send_command(IpcCommand::FocusWindowById { window_id: windows[index].window_id }).await?;
+    print_output(&response, json)?;
+    if response.is_error() {
+        std::process::exit(1);
+    }
+    Ok(())
+}
+
+/// Command-line arguments with a leading alias from the config expanded.
+///
+/// The config file is only read when the command isn't a built-in one.
+fn expand_aliases() -> Result<Vec<String>> {
+    let args: Vec<String> = std::env::args_os().map(|arg| arg.to_string_lossy().into_owned()).collect();
+    let cli = Cli::command();
+    match aliases::command_index(&args, &cli) {
+        Some(index) if !aliases::is_builtin(&cli, &args[index]) => {
+            Ok(aliases::expand(args, &cli, &aliases::load(existing_config_path().as_deref())?))
+        }
+        _ => Ok(args),
+    }
+}
+
+/// Handle the completions command (print a completion script).
+fn handle_completions(shell: Shell) -> Result<()> {
+    let aliases = aliases::load(existing_config_path().as_deref()).unwrap_or_else(|e| {
+        eprintln!("Warning: {:#}; generating completions without aliases", e);
+        aliases::Aliases::new()
+    });
+    print!("{}", completions::generate(shell, &Cli::command(), &aliases));
+    Ok(())
+}
+
 #[tokio::main]
 async fn main() -> Result<()> {
-    let cli = Cli::parse();
+    let cli = Cli::parse_from(expand_aliases()?);
+    if let Some(name) = &cli.pipe_name {
+        let _ = PIPE_NAME_OVERRIDE.set(normalize_pipe_name(name));
+    }
+    if let Some(secs) = cli.wait_for_daemon.filter(|_| uses_daemon(&cli.command)) {
+        wait_for_daemon(Duration::from_secs(secs)).await?;
+    }
 
-    // Handle init, run, and autostart commands separately (do not use IPC command mapping)
+    // Handle init, run, autostart and completions separately (do not use IPC command mapping)
     match cli.command {
         Commands::Init { output, force } => return handle_init(output, force),
-        Commands::Run { no_apply, wait_ms } => return handle_run(no_apply, wait_ms).await,
+        Commands::Completions { shell } => return handle_completions(shell),
+        Commands::Run { no_apply, wait_ms, supervised, safe_mode } => {
+            return handle_run(no_apply, wait_ms, supervised, safe_mode, cli.json).await
+        }
         Commands::Autostart { action } => return handle_autostart(action),
+        Commands::Focus { pick: true, .. } => return handle_pick(cli.json).await,
+        Commands::CheckConfig { file } => return handle_check_config(file, cli.json).await,
+        Commands::RestoreWindows => return handle_restore_windows(cli.json).await,
         _ => {}
     }
 
-    let ipc_cmd = to_ipc_command(&cli.command);
+    let ipc_cmd = match &cli.command {
+        Commands::Wire(args) => wire::parse(args)?,
+        command => to_ipc_command(command),
+    };
+    if let Some(interval_ms) = cli.watch {
+        if !ipc_cmd.is_query() {
+            anyhow::bail!("--watch only works with query commands (query, status, metrics, perf, bar, logs)");
+        }
+        return watch_query(ipc_cmd, Duration::from_millis(interval_ms), cli.json).await;
+    }
+
     let response = send_command(ipc_cmd).await?;
-    print_response(&response);
+    print_output(&response, cli.json)?;
 
     // Exit with error code if response was an error
-    if matches!(response, IpcResponse::Error { .. }) {
+    if response.is_error() {
         std::process::exit(1);
     }
 
@@ -716,28 +1576,73 @@ mod tests {
 
     #[test]
     fn test_to_ipc_command_focus_left() {
-        let cmd = Commands::Focus { direction: FocusDirection::Left };
+        let cmd = Commands::Focus { pick: false, id: None, direction: Some(FocusDirection::Left) };
         assert!(matches!(to_ipc_command(&cmd), IpcCommand::FocusLeft));
     }
 
     #[test]
     fn test_to_ipc_command_focus_right() {
-        let cmd = Commands::Focus { direction: FocusDirection::Right };
+        let cmd = Commands::Focus { pick: false, id: None, direction: Some(FocusDirection::Right) };
         assert!(matches!(to_ipc_command(&cmd), IpcCommand::FocusRight));
     }
 
     #[test]
     fn test_to_ipc_command_focus_up() {
-        let cmd = Commands::Focus { direction: FocusDirection::Up };
+        let cmd = Commands::Focus { pick: false, id: None, direction: Some(FocusDirection::Up) };
         assert!(matches!(to_ipc_command(&cmd), IpcCommand::FocusUp));
     }
 
     #[test]
     fn test_to_ipc_command_focus_down() {
-        let cmd = Commands::Focus { direction: FocusDirection::Down };
+        let cmd = Commands::Focus { pick: false, id: None, direction: Some(FocusDirection::Down) };
         assert!(matches!(to_ipc_command(&cmd), IpcCommand::FocusDown));
     }
 
+    #[test]
+    fn test_to_ipc_command_column_numbers_are_one_based() {
+        let cmd = Commands::Focus { pick: false, id: None, direction: Some(FocusDirection::Column { number: 1 }) };
+        assert_eq!(to_ipc_command(&cmd), IpcCommand::FocusColumn { index: 0 });
+        let cmd = Commands::Move { direction: MoveDirection::To { number: 3 } };
+        assert_eq!(to_ipc_command(&cmd), IpcCommand::MoveColumnToIndex { from: None, to: 2 });
+        let cmd = Commands::MoveWindow { id: 42, column: 2, position: None };
+        assert_eq!(
+            to_ipc_command(&cmd),
+            IpcCommand::MoveWindowToColumn { window_id: 42, column: 1, position: None }
+        );
+        assert!(Cli::try_parse_from(["openniri-cli", "focus", "column", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_to_ipc_command_focus_previous() {
+        let cmd = Commands::Focus { pick: false, id: None, direction: Some(FocusDirection::Previous) };
+        assert!(matches!(to_ipc_command(&cmd), IpcCommand::FocusPrevious));
+    }
+
+    #[test]
+    fn test_to_ipc_command_focus_urgent() {
+        let cli = Cli::try_parse_from(["openniri-cli", "focus", "urgent"]).unwrap();
+        assert!(matches!(to_ipc_command(&cli.command), IpcCommand::FocusUrgent));
+    }
+
+    #[test]
+    fn test_to_ipc_command_focus_by_id() {
+        let cli = Cli::try_parse_from(["openniri-cli", "focus", "--id", "42"]).unwrap();
+        assert!(matches!(to_ipc_command(&cli.command), IpcCommand::FocusWindowById { window_id: 42 }));
+        assert!(Cli::try_parse_from(["openniri-cli", "focus", "--pick"]).is_ok());
+        // Flags and directions are mutually exclusive
+        assert!(Cli::try_parse_from(["openniri-cli", "focus", "--pick", "--id", "1"]).is_err());
+        assert!(Cli::try_parse_from(["openniri-cli", "focus", "--id", "1", "left"]).is_err());
+    }
+
+    #[test]
+    fn test_parse_pick() {
+        assert_eq!(parse_pick("1\n", 3), Some(0));
+        assert_eq!(parse_pick(" 3 ", 3), Some(2));
+        assert_eq!(parse_pick("0", 3), None);
+        assert_eq!(parse_pick("4", 3), None);
+        assert_eq!(parse_pick("abc", 3), None);
+    }
+
     #[test]
     fn test_to_ipc_command_scroll_left() {
         let cmd = Commands::Scroll { direction: ScrollDirection::Left { pixels: 100 } };
@@ -768,6 +1673,48 @@ mod tests {
         assert!(matches!(to_ipc_command(&cmd), IpcCommand::MoveColumnRight));
     }
 
+    #[test]
+    fn test_to_ipc_command_consume() {
+        let cmd = Commands::Consume { direction: ConsumeDirection::Left };
+        assert!(matches!(to_ipc_command(&cmd), IpcCommand::ConsumeWindowLeft));
+        let cmd = Commands::Consume { direction: ConsumeDirection::Right };
+        assert!(matches!(to_ipc_command(&cmd), IpcCommand::ConsumeWindowRight));
+        let cmd = Commands::Consume { direction: ConsumeDirection::Down };
+        assert!(matches!(to_ipc_command(&cmd), IpcCommand::ConsumeWindowDown));
+    }
+
+    #[test]
+    fn test_run_safe_mode_flag() {
+        let cli = Cli::try_parse_from(["openniri-cli", "run", "--safe-mode"]).unwrap();
+        assert!(matches!(cli.command, Commands::Run { safe_mode: true, supervised: false, .. }));
+        let cli = Cli::try_parse_from(["openniri-cli", "run"]).unwrap();
+        assert!(matches!(cli.command, Commands::Run { safe_mode: false, .. }));
+    }
+
+    #[test]
+    fn test_wait_for_daemon_flag() {
+        let cli = Cli::try_parse_from(["openniri-cli", "--wait-for-daemon", "30", "status"]).unwrap();
+        assert_eq!(cli.wait_for_daemon, Some(30));
+        assert!(uses_daemon(&cli.command));
+        let cli = Cli::try_parse_from(["openniri-cli", "focus", "left", "--wait-for-daemon", "5"]).unwrap();
+        assert_eq!(cli.wait_for_daemon, Some(5));
+        let cli = Cli::try_parse_from(["openniri-cli", "--wait-ready", "10", "status"]).unwrap();
+        assert_eq!(cli.wait_for_daemon, Some(10));
+
+        // Commands that don't need a running daemon don't wait for one
+        let cli = Cli::try_parse_from(["openniri-cli", "--wait-for-daemon", "30", "run"]).unwrap();
+        assert!(!uses_daemon(&cli.command));
+        assert!(Cli::try_parse_from(["openniri-cli", "--wait-for-daemon", "soon", "status"]).is_err());
+    }
+
+    #[test]
+    fn test_to_ipc_command_split_and_promote() {
+        let cli = Cli::try_parse_from(["openniri-cli", "split-column"]).unwrap();
+        assert!(matches!(to_ipc_command(&cli.command), IpcCommand::SplitColumn));
+        let cli = Cli::try_parse_from(["openniri-cli", "promote"]).unwrap();
+        assert!(matches!(to_ipc_command(&cli.command), IpcCommand::PromoteWindow));
+    }
+
     #[test]
     fn test_to_ipc_command_resize() {
         let cmd = Commands::Resize { delta: 50 };
@@ -786,18 +1733,32 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_to_ipc_command_resize_height() {
+        let cmd = Commands::ResizeHeight { delta: -80 };
+        assert_eq!(to_ipc_command(&cmd), IpcCommand::ResizeWindowVertical { delta: -80 });
+    }
+
     #[test]
     fn test_to_ipc_command_focus_monitor_left() {
-        let cmd = Commands::FocusMonitor { direction: MonitorDirection::Left };
+        let cmd = Commands::FocusMonitor { direction: FocusMonitorDirection::Left };
         assert!(matches!(to_ipc_command(&cmd), IpcCommand::FocusMonitorLeft));
     }
 
     #[test]
     fn test_to_ipc_command_focus_monitor_right() {
-        let cmd = Commands::FocusMonitor { direction: MonitorDirection::Right };
+        let cmd = Commands::FocusMonitor { direction: FocusMonitorDirection::Right };
         assert!(matches!(to_ipc_command(&cmd), IpcCommand::FocusMonitorRight));
     }
 
+    #[test]
+    fn test_to_ipc_command_focus_monitor_up_down() {
+        let cmd = Commands::FocusMonitor { direction: FocusMonitorDirection::Up };
+        assert!(matches!(to_ipc_command(&cmd), IpcCommand::FocusMonitorUp));
+        let cmd = Commands::FocusMonitor { direction: FocusMonitorDirection::Down };
+        assert!(matches!(to_ipc_command(&cmd), IpcCommand::FocusMonitorDown));
+    }
+
     #[test]
     fn test_to_ipc_command_move_to_monitor_left() {
         let cmd = Commands::MoveToMonitor { direction: MonitorDirection::Left };
@@ -810,6 +1771,19 @@ mod tests {
         assert!(matches!(to_ipc_command(&cmd), IpcCommand::MoveWindowToMonitorRight));
     }
 
+    #[test]
+    fn test_to_ipc_command_move_column_to_monitor() {
+        let cmd = Commands::MoveColumnToMonitor { direction: MonitorDirection::Left };
+        assert!(matches!(to_ipc_command(&cmd), IpcCommand::MoveColumnToMonitorLeft));
+        let cmd = Commands::MoveColumnToMonitor { direction: MonitorDirection::Right };
+        assert!(matches!(to_ipc_command(&cmd), IpcCommand::MoveColumnToMonitorRight));
+    }
+
+    #[test]
+    fn test_to_ipc_command_gather() {
+        assert!(matches!(to_ipc_command(&Commands::Gather), IpcCommand::GatherWindows));
+    }
+
     #[test]
     fn test_to_ipc_command_query_workspace() {
         let cmd = Commands::Query { what: QueryType::Workspace };
@@ -834,6 +1808,12 @@ mod tests {
         assert!(matches!(to_ipc_command(&cmd), IpcCommand::Refresh));
     }
 
+    #[test]
+    fn test_to_ipc_command_prune() {
+        let cmd = Commands::Prune;
+        assert!(matches!(to_ipc_command(&cmd), IpcCommand::Prune));
+    }
+
     #[test]
     fn test_to_ipc_command_apply() {
         let cmd = Commands::Apply;
@@ -946,12 +1926,47 @@ mod tests {
         assert!(matches!(to_ipc_command(&cmd), IpcCommand::ToggleFloating));
     }
 
+    #[test]
+    fn test_to_ipc_command_floating() {
+        let cli = Cli::try_parse_from(["openniri-cli", "floating", "move", "--dx", "-40"]).unwrap();
+        assert!(matches!(to_ipc_command(&cli.command), IpcCommand::MoveFloating { dx: -40, dy: 0 }));
+        let cmd = Commands::Floating { action: FloatingAction::Resize { dw: 100, dh: -50 } };
+        assert!(matches!(to_ipc_command(&cmd), IpcCommand::ResizeFloating { dw: 100, dh: -50 }));
+        let cmd = Commands::Floating { action: FloatingAction::Center };
+        assert!(matches!(to_ipc_command(&cmd), IpcCommand::CenterFloating));
+        let cmd = Commands::Floating { action: FloatingAction::Snap { edge: EdgeDirection::Top } };
+        assert!(matches!(to_ipc_command(&cmd), IpcCommand::SnapFloating { edge: SnapEdge::Top }));
+    }
+
+    #[test]
+    fn test_to_ipc_command_floating_always_on_top() {
+        let parse = |args: &[&str]| {
+            let cli = Cli::try_parse_from([&["openniri-cli", "floating", "always-on-top"], args].concat()).unwrap();
+            to_ipc_command(&cli.command)
+        };
+        assert_eq!(parse(&[]), IpcCommand::ToggleAlwaysOnTop);
+        assert_eq!(parse(&["--on"]), IpcCommand::SetAlwaysOnTop { enabled: true });
+        assert_eq!(parse(&["--off"]), IpcCommand::SetAlwaysOnTop { enabled: false });
+        assert!(Cli::try_parse_from(["openniri-cli", "floating", "always-on-top", "--on", "--off"]).is_err());
+    }
+
     #[test]
     fn test_to_ipc_command_toggle_fullscreen() {
         let cmd = Commands::ToggleFullscreen;
         assert!(matches!(to_ipc_command(&cmd), IpcCommand::ToggleFullscreen));
     }
 
+    #[test]
+    fn test_to_ipc_command_overview() {
+        assert!(matches!(to_ipc_command(&Commands::Overview), IpcCommand::ToggleOverview));
+    }
+
+    #[test]
+    fn test_to_ipc_command_resize_mode() {
+        let cli = Cli::try_parse_from(["openniri-cli", "resize-mode"]).unwrap();
+        assert!(matches!(to_ipc_command(&cli.command), IpcCommand::EnterResizeMode));
+    }
+
     #[test]
     fn test_to_ipc_command_set_width() {
         let cmd = Commands::SetWidth { fraction: 0.5 };
@@ -967,12 +1982,174 @@ mod tests {
         assert!(matches!(to_ipc_command(&cmd), IpcCommand::EqualizeColumnWidths));
     }
 
+    #[test]
+    fn test_to_ipc_command_restore_windows() {
+        let cli = Cli::try_parse_from(["openniri-cli", "restore-windows"]).unwrap();
+        assert_eq!(to_ipc_command(&cli.command), IpcCommand::RestoreAll);
+    }
+
+    #[test]
+    fn test_to_ipc_command_toggle_auto_balance() {
+        assert!(matches!(to_ipc_command(&Commands::ToggleAutoBalance), IpcCommand::ToggleAutoBalance));
+    }
+
+    #[test]
+    fn test_to_ipc_command_new_window_position() {
+        let cmd = Commands::NewWindowPosition { position: PositionArg::EndOfStrip };
+        assert!(matches!(
+            to_ipc_command(&cmd),
+            IpcCommand::SetNewWindowPosition { position: NewWindowPosition::EndOfStrip }
+        ));
+    }
+
+    #[test]
+    fn test_to_ipc_command_collapse_expand() {
+        assert!(matches!(to_ipc_command(&Commands::CollapseColumn), IpcCommand::CollapseColumn));
+        assert!(matches!(to_ipc_command(&Commands::ExpandColumn), IpcCommand::ExpandColumn));
+    }
+
+    #[test]
+    fn test_json_and_watch_flags() {
+        let cli = Cli::try_parse_from(["openniri-cli", "query", "workspace", "--json"]).unwrap();
+        assert!(cli.json);
+        assert_eq!(cli.watch, None);
+
+        let cli = Cli::try_parse_from(["openniri-cli", "--watch", "status"]).unwrap();
+        assert_eq!(cli.watch, Some(1000));
+        let cli = Cli::try_parse_from(["openniri-cli", "bar", "--watch=500"]).unwrap();
+        assert_eq!(cli.watch, Some(500));
+
+        // Intervals below 100 ms would flood the daemon
+        assert!(Cli::try_parse_from(["openniri-cli", "status", "--watch=10"]).is_err());
+    }
+
+    #[test]
+    fn test_pipe_name_flag() {
+        let cli = Cli::try_parse_from(["openniri-cli", "status"]).unwrap();
+        assert_eq!(cli.pipe_name, None);
+        let cli = Cli::try_parse_from(["openniri-cli", "status", "--pipe-name", "openniri-test"]).unwrap();
+        assert_eq!(cli.pipe_name.as_deref(), Some("openniri-test"));
+        let cli = Cli::try_parse_from(["openniri-cli", "--pipe-name", "openniri-test", "focus", "left"]).unwrap();
+        assert_eq!(cli.pipe_name.as_deref(), Some("openniri-test"));
+    }
+
+    #[test]
+    fn test_to_ipc_command_cycle_width() {
+        assert!(matches!(to_ipc_command(&Commands::CycleWidth), IpcCommand::CycleColumnWidth));
+    }
+
+    #[test]
+    fn test_to_ipc_command_toggle_tabbed() {
+        assert!(matches!(to_ipc_command(&Commands::ToggleTabbed), IpcCommand::ToggleColumnTabbed));
+    }
+
+    #[test]
+    fn test_to_ipc_command_maximize_column() {
+        assert!(matches!(to_ipc_command(&Commands::MaximizeColumn), IpcCommand::ToggleMaximizeColumn));
+    }
+
+    #[test]
+    fn test_to_ipc_command_toggle_pause_monitor() {
+        assert!(matches!(to_ipc_command(&Commands::TogglePauseMonitor), IpcCommand::TogglePauseMonitor));
+    }
+
+    #[test]
+    fn test_to_ipc_command_apply_project() {
+        let cmd = Commands::ApplyProject { name: "web".to_string() };
+        assert_eq!(to_ipc_command(&cmd), IpcCommand::ApplyProject { name: "web".to_string() });
+    }
+
+    #[test]
+    fn test_to_ipc_command_profile() {
+        let cli = Cli::try_parse_from(["openniri-cli", "profile", "save", "docked"]).unwrap();
+        assert_eq!(to_ipc_command(&cli.command), IpcCommand::SaveMonitorProfile { name: "docked".to_string() });
+        let cli = Cli::try_parse_from(["openniri-cli", "profile", "apply", "laptop-only"]).unwrap();
+        assert_eq!(
+            to_ipc_command(&cli.command),
+            IpcCommand::ApplyMonitorProfile { name: "laptop-only".to_string() }
+        );
+        assert!(Cli::try_parse_from(["openniri-cli", "profile", "apply"]).is_err());
+    }
+
+    #[test]
+    fn test_to_ipc_command_scratchpad() {
+        let cli = Cli::try_parse_from(["openniri-cli", "scratchpad", "toggle", "term"]).unwrap();
+        assert_eq!(to_ipc_command(&cli.command), IpcCommand::ToggleScratchpad { name: "term".to_string() });
+        let cli = Cli::try_parse_from(["openniri-cli", "scratchpad", "move", "music"]).unwrap();
+        assert_eq!(to_ipc_command(&cli.command), IpcCommand::MoveToScratchpad { name: "music".to_string() });
+        assert!(Cli::try_parse_from(["openniri-cli", "scratchpad", "toggle"]).is_err());
+    }
+
+    #[test]
+    fn test_to_ipc_command_workspaces() {
+        let cmd = Commands::SwitchWorkspace { name: "code".to_string() };
+        assert_eq!(to_ipc_command(&cmd), IpcCommand::SwitchWorkspace { name: "code".to_string() });
+        let cmd = Commands::MoveToWorkspace { name: "chat".to_string() };
+        assert_eq!(to_ipc_command(&cmd), IpcCommand::MoveWindowToWorkspace { name: "chat".to_string() });
+    }
+
     #[test]
     fn test_to_ipc_command_status() {
         let cmd = Commands::Status;
         assert!(matches!(to_ipc_command(&cmd), IpcCommand::QueryStatus));
     }
 
+    #[test]
+    fn test_to_ipc_command_perf() {
+        assert_eq!(to_ipc_command(&Commands::Perf), IpcCommand::QueryPerfStats);
+    }
+
+    #[test]
+    fn test_to_ipc_command_metrics() {
+        assert!(matches!(to_ipc_command(&Commands::Metrics), IpcCommand::QueryMetrics));
+        let cli = Cli::try_parse_from(["openniri-cli", "logs", "-n", "20"]).unwrap();
+        assert!(matches!(to_ipc_command(&cli.command), IpcCommand::QueryLogs { lines: 20 }));
+        let cli = Cli::try_parse_from(["openniri-cli", "log-level", "debug"]).unwrap();
+        assert!(matches!(to_ipc_command(&cli.command), IpcCommand::SetLogLevel { level } if level == "debug"));
+    }
+
+    #[test]
+    fn test_to_ipc_command_capabilities() {
+        assert_eq!(
+            to_ipc_command(&Commands::Capabilities),
+            IpcCommand::Hello { protocol_version: PROTOCOL_VERSION }
+        );
+    }
+
+    #[test]
+    fn test_format_diagnostics() {
+        let diagnostics = vec![
+            ConfigDiagnostic {
+                severity: DiagnosticSeverity::Warning,
+                field: "layout.gapp".to_string(),
+                message: "unknown key (did you mean 'gap'?)".to_string(),
+                line: Some(3),
+            },
+            ConfigDiagnostic {
+                severity: DiagnosticSeverity::Error,
+                field: "hotkeys".to_string(),
+                message: "invalid hotkey".to_string(),
+                line: None,
+            },
+        ];
+        assert_eq!(
+            format_diagnostics("config.toml", &diagnostics),
+            vec![
+                "config.toml:3: warning: layout.gapp: unknown key (did you mean 'gap'?)",
+                "config.toml: error: hotkeys: invalid hotkey",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_to_ipc_command_bar() {
+        let cmd = Commands::Bar { format: DEFAULT_BAR_FORMAT.to_string() };
+        match to_ipc_command(&cmd) {
+            IpcCommand::QueryBarText { format } => assert_eq!(format, DEFAULT_BAR_FORMAT),
+            other => panic!("Expected QueryBarText, got {:?}", other),
+        }
+    }
+
     #[test]
     fn test_generate_default_config_contains_hotkeys() {
         let config = generate_default_config();
@@ -993,4 +2170,23 @@ mod tests {
         let config = generate_default_config();
         assert!(config.contains("[snap_hints]"));
     }
+
+    #[test]
+    fn test_default_config_parses_without_aliases() {
+        let aliases = aliases::parse(&generate_default_config()).unwrap();
+        assert!(aliases.is_empty());
+    }
+
+    #[test]
+    fn test_completions_cover_subcommands() {
+        let cli = Cli::try_parse_from(["openniri-cli", "completions", "powershell"]).unwrap();
+        assert!(matches!(cli.command, Commands::Completions { shell: Shell::Powershell }));
+        assert!(Cli::try_parse_from(["openniri-cli", "completions", "fish"]).is_err());
+
+        let aliases = aliases::Aliases::from([("ff".to_string(), "focus right".to_string())]);
+        let script = completions::generate(Shell::Bash, &Cli::command(), &aliases);
+        assert!(script.contains("[\"openniri-cli focus-monitor\"]=\"left right up down help"));
+        assert!(script.contains("[\"openniri-cli completions\"]=\"powershell bash zsh"));
+        assert!(script.contains(" ff\""));
+    }
 }
@@ -0,0 +1,75 @@
+//! IPC commands by their wire names.
+//!
+//! Subcommands the CLI doesn't define are looked up in
+//! [`IpcCommand::TYPES`], so every command the protocol knows can be sent
+//! (and aliased) without a hand-written subcommand:
+//!
+//! ```text
+//! openniri-cli focus-urgent
+//! openniri-cli move_column_to_index to=2
+//! ```
+//!
+//! Hyphens and underscores are interchangeable in names and field keys.
+//! Field values are read as JSON, falling back to a plain string.
+
+use anyhow::{Context, Result};
+use openniri_ipc::IpcCommand;
+use serde_json::{Map, Value};
+
+/// Build the command named by `args[0]` from `key=value` fields in the rest.
+///
+/// # Errors
+///
+/// Returns an error for unknown commands, arguments that aren't `key=value`
+/// and fields the command doesn't accept.
+pub fn parse(args: &[String]) -> Result<IpcCommand> {
+    let Some((name, fields)) = args.split_first() else {
+        anyhow::bail!("No command given");
+    };
+    let name = name.replace('-', "_");
+    if !IpcCommand::TYPES.contains(&name.as_str()) {
+        anyhow::bail!("Unknown command '{}'; see `openniri-cli --help` or `openniri-cli capabilities`", name);
+    }
+
+    let mut object = Map::new();
+    object.insert("type".to_string(), Value::String(name.clone()));
+    for field in fields {
+        let Some((key, value)) = field.split_once('=') else {
+            anyhow::bail!("Expected key=value, got '{}'", field);
+        };
+        let value = serde_json::from_str(value).unwrap_or_else(|_| Value::String(value.to_string()));
+        object.insert(key.replace('-', "_"), value);
+    }
+    serde_json::from_value(Value::Object(object)).with_context(|| format!("Invalid arguments for '{}'", name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_wire_names() {
+        assert_eq!(parse(&args(&["focus-urgent"])).unwrap(), IpcCommand::FocusUrgent);
+        assert_eq!(parse(&args(&["focus_right"])).unwrap(), IpcCommand::FocusRight);
+        assert_eq!(
+            parse(&args(&["move-column-to-index", "to=2"])).unwrap(),
+            IpcCommand::MoveColumnToIndex { from: None, to: 2 }
+        );
+        assert_eq!(
+            parse(&args(&["switch_workspace", "name=chat"])).unwrap(),
+            IpcCommand::SwitchWorkspace { name: "chat".to_string() }
+        );
+    }
+
+    #[test]
+    fn test_parse_rejects_bad_commands() {
+        assert!(parse(&args(&["nope"])).is_err());
+        assert!(parse(&args(&["move_column_to_index"])).is_err(), "missing field");
+        assert!(parse(&args(&["move_column_to_index", "2"])).is_err(), "not key=value");
+        assert!(parse(&[]).is_err());
+    }
+}
@@ -0,0 +1,342 @@
+//! OpenNiri IPC client
+//!
+//! Async client for the daemon's named pipe, for status bars, launchers and
+//! other tools written in Rust. The daemon reads one JSON command line per
+//! connection and answers with one JSON response line; [`Client`] handles the
+//! pipe, that framing and the protocol handshake, so callers only deal with
+//! [`IpcCommand`] and [`IpcResponse`].
+//!
+//! ```no_run
+//! use futures_util::StreamExt;
+//! use openniri_client::Client;
+//! use openniri_ipc::IpcCommand;
+//! use std::time::Duration;
+//!
+//! # async fn example() -> Result<(), openniri_client::ClientError> {
+//! let client = Client::connect().await?;
+//! client.send(IpcCommand::FocusRight).await?;
+//!
+//! let mut focus = std::pin::pin!(client.subscribe(IpcCommand::QueryFocused, Duration::from_millis(250)));
+//! while let Some(response) = focus.next().await {
+//!     println!("{:?}", response?);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use futures_util::stream::{self, Stream};
+use openniri_ipc::{pipe_name_for_current_user, IpcCommand, IpcResponse, PROTOCOL_VERSION};
+use std::time::Duration;
+use thiserror::Error;
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncWrite, AsyncWriteExt, BufReader};
+use tokio::time::MissedTickBehavior;
+
+/// Time allowed for connecting to the daemon and getting its answer.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Time between attempts to open a busy or missing pipe.
+#[cfg(windows)]
+const CONNECT_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Errors from talking to the daemon.
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("Failed to connect to daemon. Is openniri running?")]
+    Connect(#[source] std::io::Error),
+
+    #[error("Timed out waiting for daemon response")]
+    Timeout,
+
+    #[error("Failed to talk to daemon: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Daemon disconnected before sending a response")]
+    Disconnected,
+
+    #[error("Invalid message: {0}")]
+    Json(#[from] serde_json::Error),
+
+    #[error("Daemon speaks protocol version {server_version}, this client {client_version}")]
+    ProtocolMismatch { client_version: u32, server_version: u32 },
+
+    #[error("Unexpected response from daemon: {0:?}")]
+    UnexpectedResponse(Box<IpcResponse>),
+}
+
+/// What the daemon reported in the handshake.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ServerInfo {
+    /// Daemon version.
+    pub version: String,
+    /// The daemon's [`PROTOCOL_VERSION`].
+    pub protocol_version: u32,
+    /// Wire names of the commands the daemon supports.
+    pub capabilities: Vec<String>,
+}
+
+/// Client for a daemon's named pipe.
+///
+/// Each command is sent on a connection of its own, so a client is cheap to
+/// clone and can be shared between tasks.
+#[derive(Debug, Clone)]
+pub struct Client {
+    pipe_name: String,
+    timeout: Duration,
+    server: Option<ServerInfo>,
+}
+
+impl Client {
+    /// Client for the daemon on `pipe_name`, without connecting to it yet.
+    pub fn new(pipe_name: impl Into<String>) -> Self {
+        Self { pipe_name: pipe_name.into(), timeout: DEFAULT_TIMEOUT, server: None }
+    }
+
+    /// Connect to the current user's daemon in this session.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the daemon can't be reached within [`DEFAULT_TIMEOUT`] or
+    /// speaks another protocol version.
+    pub async fn connect() -> Result<Self, ClientError> {
+        Self::connect_to(pipe_name_for_current_user()).await
+    }
+
+    /// Connect to the daemon on `pipe_name`, e.g. one started with `--pipe-name`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the daemon can't be reached within [`DEFAULT_TIMEOUT`] or
+    /// speaks another protocol version.
+    pub async fn connect_to(pipe_name: impl Into<String>) -> Result<Self, ClientError> {
+        let mut client = Self::new(pipe_name);
+        client.server = Some(client.handshake().await?);
+        Ok(client)
+    }
+
+    /// Use `timeout` instead of [`DEFAULT_TIMEOUT`] for each command.
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Pipe the client talks to.
+    pub fn pipe_name(&self) -> &str {
+        &self.pipe_name
+    }
+
+    /// What the daemon reported when connecting, or None for a client made
+    /// with [`Client::new`].
+    pub fn server_info(&self) -> Option<&ServerInfo> {
+        self.server.as_ref()
+    }
+
+    /// Send a command and wait for the daemon's response.
+    ///
+    /// A busy pipe, or one that is missing because the daemon is starting or
+    /// restarting, is retried until the timeout.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the daemon can't be reached or doesn't answer in time, or the
+    /// answer isn't a valid response. Commands the daemon rejects are an
+    /// [`IpcResponse::Error`], not an error here.
+    pub async fn send(&self, command: IpcCommand) -> Result<IpcResponse, ClientError> {
+        tokio::time::timeout(self.timeout, async {
+            let pipe = open_pipe(&self.pipe_name, self.timeout).await?;
+            exchange(pipe, &command).await
+        })
+        .await
+        .map_err(|_| ClientError::Timeout)?
+    }
+
+    /// Re-send a query every `interval` and yield its response whenever it
+    /// changes.
+    ///
+    /// The daemon doesn't push updates, so this polls; the first response is
+    /// always yielded. Errors (e.g. while the daemon restarts) are yielded
+    /// too and polling goes on, so the stream never ends by itself.
+    pub fn subscribe(
+        &self,
+        query: IpcCommand,
+        interval: Duration,
+    ) -> impl Stream<Item = Result<IpcResponse, ClientError>> {
+        let subscription = Subscription { client: self.clone(), query, interval, ticker: None, last: None };
+        stream::unfold(subscription, |mut subscription| async move {
+            let item = subscription.next_change().await;
+            Some((item, subscription))
+        })
+    }
+
+    /// Exchange a `Hello` for the daemon's [`ServerInfo`].
+    async fn handshake(&self) -> Result<ServerInfo, ClientError> {
+        match self.send(IpcCommand::Hello { protocol_version: PROTOCOL_VERSION }).await? {
+            IpcResponse::ServerInfo { version, protocol_version, capabilities } => {
+                Ok(ServerInfo { version, protocol_version, capabilities })
+            }
+            IpcResponse::ProtocolMismatch { client_version, server_version } => {
+                Err(ClientError::ProtocolMismatch { client_version, server_version })
+            }
+            other => Err(ClientError::UnexpectedResponse(Box::new(other))),
+        }
+    }
+}
+
+/// State of a [`Client::subscribe`] stream.
+struct Subscription {
+    client: Client,
+    query: IpcCommand,
+    interval: Duration,
+    /// Created on first use, since timers need a running runtime.
+    ticker: Option<tokio::time::Interval>,
+    /// Last response yielded, or None after an error.
+    last: Option<IpcResponse>,
+}
+
+impl Subscription {
+    /// Poll until the response differs from the last one yielded.
+    async fn next_change(&mut self) -> Result<IpcResponse, ClientError> {
+        let interval = self.interval;
+        let ticker = self.ticker.get_or_insert_with(|| {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+            ticker
+        });
+        loop {
+            ticker.tick().await;
+            match self.client.send(self.query.clone()).await {
+                Ok(response) if self.last.as_ref() == Some(&response) => continue,
+                Ok(response) => {
+                    self.last = Some(response.clone());
+                    return Ok(response);
+                }
+                Err(e) => {
+                    self.last = None;
+                    return Err(e);
+                }
+            }
+        }
+    }
+}
+
+/// Send one command line on a connected stream and read the response line.
+///
+/// # Errors
+///
+/// Fails if the stream fails, closes before a response, or the response
+/// isn't valid JSON.
+pub async fn exchange<S>(stream: S, command: &IpcCommand) -> Result<IpcResponse, ClientError>
+where
+    S: AsyncRead + AsyncWrite,
+{
+    let (reader, mut writer) = tokio::io::split(stream);
+    let json = serde_json::to_string(command)? + "\n";
+    writer.write_all(json.as_bytes()).await?;
+
+    let mut line = String::new();
+    if BufReader::new(reader).read_line(&mut line).await? == 0 {
+        return Err(ClientError::Disconnected);
+    }
+    Ok(serde_json::from_str(line.trim())?)
+}
+
+/// Whether a daemon is listening on `pipe_name`, without waiting for it.
+pub fn is_running(pipe_name: &str) -> bool {
+    #[cfg(windows)]
+    {
+        match tokio::net::windows::named_pipe::ClientOptions::new().open(pipe_name) {
+            Ok(_) => true,
+            Err(e) => is_pipe_busy(&e),
+        }
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = pipe_name;
+        false
+    }
+}
+
+#[cfg(windows)]
+fn is_pipe_busy(err: &std::io::Error) -> bool {
+    err.raw_os_error() == Some(231)
+}
+
+#[cfg(windows)]
+fn is_pipe_not_found(err: &std::io::Error) -> bool {
+    err.raw_os_error() == Some(2)
+}
+
+/// Open the pipe, retrying while it is busy or missing until `timeout`.
+#[cfg(windows)]
+async fn open_pipe(
+    pipe_name: &str,
+    timeout: Duration,
+) -> Result<tokio::net::windows::named_pipe::NamedPipeClient, ClientError> {
+    let start = tokio::time::Instant::now();
+    loop {
+        match tokio::net::windows::named_pipe::ClientOptions::new().open(pipe_name) {
+            Ok(client) => return Ok(client),
+            Err(e) if (is_pipe_busy(&e) || is_pipe_not_found(&e)) && start.elapsed() < timeout => {}
+            Err(e) => return Err(ClientError::Connect(e)),
+        }
+        tokio::time::sleep(CONNECT_RETRY_INTERVAL).await;
+    }
+}
+
+/// Named pipes are Windows-only; elsewhere there is never a daemon to reach.
+#[cfg(not(windows))]
+async fn open_pipe(_pipe_name: &str, _timeout: Duration) -> Result<tokio::io::DuplexStream, ClientError> {
+    Err(ClientError::Connect(std::io::ErrorKind::Unsupported.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::StreamExt;
+
+    /// Answer one command line on `stream` with `response`, returning the command.
+    async fn serve_one(stream: tokio::io::DuplexStream, response: &str) -> IpcCommand {
+        let (reader, mut writer) = tokio::io::split(stream);
+        let mut line = String::new();
+        BufReader::new(reader).read_line(&mut line).await.unwrap();
+        writer.write_all(response.as_bytes()).await.unwrap();
+        serde_json::from_str(line.trim()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_exchange_frames_command_and_response() {
+        let (client, server) = tokio::io::duplex(1024);
+        let server = tokio::spawn(async move { serve_one(server, "{\"status\":\"ok\"}\n").await });
+
+        let response = exchange(client, &IpcCommand::FocusLeft).await.unwrap();
+        assert_eq!(response, IpcResponse::Ok);
+        assert_eq!(server.await.unwrap(), IpcCommand::FocusLeft);
+    }
+
+    #[tokio::test]
+    async fn test_exchange_reports_disconnect_and_bad_json() {
+        let (client, server) = tokio::io::duplex(1024);
+        drop(server);
+        assert!(matches!(exchange(client, &IpcCommand::QueryStatus).await, Err(ClientError::Io(_) | ClientError::Disconnected)));
+
+        let (client, server) = tokio::io::duplex(1024);
+        tokio::spawn(async move { serve_one(server, "not json\n").await });
+        assert!(matches!(exchange(client, &IpcCommand::QueryStatus).await, Err(ClientError::Json(_))));
+    }
+
+    #[test]
+    fn test_new_client_defaults() {
+        let client = Client::new(r"\\.\pipe\openniri-test").with_timeout(Duration::from_secs(1));
+        assert_eq!(client.pipe_name(), r"\\.\pipe\openniri-test");
+        assert_eq!(client.timeout, Duration::from_secs(1));
+        assert!(client.server_info().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_subscription_yields_errors_and_keeps_polling() {
+        let client = Client::new(r"\\.\pipe\openniri-missing").with_timeout(Duration::from_millis(10));
+        let mut updates = std::pin::pin!(client.subscribe(IpcCommand::QueryFocused, Duration::from_millis(1)));
+        for _ in 0..2 {
+            assert!(updates.next().await.unwrap().is_err());
+        }
+    }
+}
@@ -8,11 +8,16 @@
 //! - New windows append without resizing existing ones
 
 use serde::{Deserialize, Serialize};
+use std::cell::OnceCell;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use thiserror::Error;
 
 /// Minimum width for columns in pixels.
 const MIN_COLUMN_WIDTH: i32 = 100;
 
+/// Minimum width and height for floating windows resized by command.
+const MIN_FLOATING_SIZE: i32 = 100;
+
 /// Default gap between columns in pixels.
 pub const DEFAULT_GAP: i32 = 10;
 /// Default gap at viewport edges in pixels.
@@ -20,6 +25,48 @@ pub const DEFAULT_OUTER_GAP: i32 = 10;
 /// Default width for new columns in pixels.
 pub const DEFAULT_COLUMN_WIDTH: i32 = 800;
 
+/// Largest scale of the strip in the overview, so even a strip that already
+/// fits visibly zooms out.
+const OVERVIEW_MAX_SCALE: f64 = 0.75;
+
+/// Width of the insertion slot hint shown while dragging a window, in pixels.
+const INSERTION_HINT_WIDTH: i32 = 8;
+
+/// Default strip width of a collapsed column placeholder in pixels.
+pub const DEFAULT_COLLAPSED_COLUMN_WIDTH: i32 = 40;
+
+/// Minimum height of a stacked window resized within its column, in pixels.
+const MIN_WINDOW_HEIGHT: i32 = 50;
+
+/// Minimum strip width of a collapsed column placeholder in pixels.
+const MIN_COLLAPSED_COLUMN_WIDTH: i32 = 8;
+
+fn default_collapsed_column_width() -> i32 {
+    DEFAULT_COLLAPSED_COLUMN_WIDTH
+}
+
+fn default_max_column_width() -> i32 {
+    i32::MAX
+}
+
+/// Map every tiled window to its (column index, index in column).
+fn build_window_index(columns: &[Column]) -> HashMap<WindowId, (usize, usize)> {
+    columns
+        .iter()
+        .enumerate()
+        .flat_map(|(col_idx, column)| {
+            column.windows.iter().enumerate().map(move |(win_idx, &w)| (w, (col_idx, win_idx)))
+        })
+        .collect()
+}
+
+/// Maximum shrink-to-fit threshold, as a fraction of the viewport width.
+pub const MAX_SHRINK_TO_FIT_THRESHOLD: f64 = 0.5;
+
+/// Extra overflow, as a fraction of the viewport width, tolerated before an
+/// active shrink-to-fit is released. Prevents flip-flopping at the threshold.
+const SHRINK_TO_FIT_HYSTERESIS: f64 = 0.02;
+
 /// Unique identifier for a window.
 /// On Windows, this will typically be the HWND cast to u64.
 pub type WindowId = u64;
@@ -38,6 +85,9 @@ pub enum LayoutError {
 
     #[error("Window index {0} is out of bounds in column {1} (max: {2})")]
     WindowIndexOutOfBounds(usize, usize, usize),
+
+    #[error("Invariant violation: {0}")]
+    InvariantViolation(String),
 }
 
 /// A rectangle in screen coordinates (pixels).
@@ -81,6 +131,44 @@ impl Rect {
     pub fn bottom(&self) -> i32 {
         self.y + self.height
     }
+
+    /// Check if this rectangle has no area.
+    pub fn is_empty(&self) -> bool {
+        self.width <= 0 || self.height <= 0
+    }
+
+    /// Check if another rectangle lies entirely within this one.
+    pub fn contains_rect(&self, other: &Rect) -> bool {
+        other.x >= self.x && other.y >= self.y && other.right() <= self.right() && other.bottom() <= self.bottom()
+    }
+
+    /// Check if a point lies within this rectangle.
+    pub fn contains_point(&self, x: i32, y: i32) -> bool {
+        x >= self.x && x < self.right() && y >= self.y && y < self.bottom()
+    }
+
+    /// Get the smallest rectangle covering both rectangles.
+    pub fn union(&self, other: &Rect) -> Rect {
+        let x = self.x.min(other.x);
+        let y = self.y.min(other.y);
+        Rect::new(x, y, self.right().max(other.right()) - x, self.bottom().max(other.bottom()) - y)
+    }
+
+    /// Get the overlapping area of two rectangles, or None if they don't intersect.
+    pub fn intersection(&self, other: &Rect) -> Option<Rect> {
+        if !self.intersects(other) {
+            return None;
+        }
+        let x = self.x.max(other.x);
+        let y = self.y.max(other.y);
+        Some(Rect::new(x, y, self.right().min(other.right()) - x, self.bottom().min(other.bottom()) - y))
+    }
+
+    /// This rectangle mirrored across the main diagonal (x and y swapped,
+    /// width and height swapped).
+    pub fn transposed(&self) -> Rect {
+        Rect::new(self.y, self.x, self.height, self.width)
+    }
 }
 
 /// Visibility state for layout computation.
@@ -201,9 +289,169 @@ impl ScrollAnimation {
     }
 }
 
+/// Duration of columns sliding into place when windows open or close, in milliseconds.
+pub const DEFAULT_COLUMN_ANIMATION_MS: u64 = 150;
+
+/// Columns sliding from where they were shown to their current position,
+/// e.g. neighbors closing the gap left by a closed window.
+///
+/// Holds each moved window's distance from its current strip position when
+/// the layout changed; the distances shrink to zero as `progress` runs from
+/// 1.0 to 0.0.
+#[derive(Debug, Clone)]
+struct ColumnAnimation {
+    offsets: HashMap<WindowId, f64>,
+    progress: ScrollAnimation,
+}
+
+impl ColumnAnimation {
+    /// Current horizontal offset of a window from its strip position.
+    fn offset(&self, window_id: WindowId) -> f64 {
+        self.offsets.get(&window_id).map_or(0.0, |offset| offset * self.progress.current_offset())
+    }
+}
+
+/// Default friction for kinetic scrolling: fraction of velocity kept per millisecond.
+pub const DEFAULT_KINETIC_FRICTION: f64 = 0.995;
+
+/// Time without new deltas after which a kinetic gesture counts as released.
+const KINETIC_RELEASE_MS: u64 = 50;
+
+/// Coasting stops once the offset is this close to where it comes to rest,
+/// in pixels, so finishing at [`KineticAnimation::target`] doesn't jump.
+const KINETIC_REST_DISTANCE: f64 = 0.5;
+
+/// Shortest interval used to estimate velocity, so bursts of deltas
+/// delivered together don't produce huge speeds.
+const KINETIC_MIN_SAMPLE_MS: u64 = 8;
+
+/// Animation state for kinetic (inertial) scrolling.
+///
+/// While a gesture is in progress, [`push`](Self::push) moves the offset
+/// directly and estimates the gesture's velocity. Once no delta has arrived
+/// for a short while the gesture counts as released, and [`tick`](Self::tick)
+/// keeps the offset moving at that velocity, decaying by `friction` every
+/// millisecond, until it slows to a stop or hits either end of the strip.
+#[derive(Debug, Clone)]
+pub struct KineticAnimation {
+    /// Current scroll offset.
+    pub offset: f64,
+    /// Current velocity in pixels per millisecond.
+    pub velocity: f64,
+    /// Fraction of velocity kept per millisecond while coasting.
+    pub friction: f64,
+    /// Largest valid scroll offset.
+    pub max_offset: f64,
+    /// Time since the last delta in milliseconds.
+    pub idle_ms: u64,
+}
+
+impl KineticAnimation {
+    /// Create a kinetic animation at rest at `offset`.
+    ///
+    /// `friction` is clamped to a range that always stops within a few
+    /// seconds; non-finite values fall back to [`DEFAULT_KINETIC_FRICTION`].
+    pub fn new(offset: f64, max_offset: f64, friction: f64) -> Self {
+        let friction = if friction.is_finite() { friction.clamp(0.5, 0.999) } else { DEFAULT_KINETIC_FRICTION };
+        let max_offset = max_offset.max(0.0);
+        Self {
+            offset: offset.clamp(0.0, max_offset),
+            velocity: 0.0,
+            friction,
+            max_offset,
+            idle_ms: 0,
+        }
+    }
+
+    /// Apply a gesture delta in pixels.
+    pub fn push(&mut self, delta: f64) {
+        if !delta.is_finite() {
+            return;
+        }
+        let sample = delta / self.idle_ms.max(KINETIC_MIN_SAMPLE_MS) as f64;
+        // Smooth over consecutive samples; a change of direction starts over
+        self.velocity = if self.velocity != 0.0 && sample.signum() == self.velocity.signum() {
+            (self.velocity + sample) / 2.0
+        } else {
+            sample
+        };
+        self.offset = (self.offset + delta).clamp(0.0, self.max_offset);
+        self.idle_ms = 0;
+    }
+
+    /// Whether the gesture was released and the offset is coasting.
+    pub fn is_coasting(&self) -> bool {
+        self.idle_ms >= KINETIC_RELEASE_MS
+    }
+
+    /// Check if the animation is complete.
+    pub fn is_complete(&self) -> bool {
+        self.is_coasting() && (self.target() - self.offset).abs() < KINETIC_REST_DISTANCE
+    }
+
+    /// Get the current scroll offset.
+    pub fn current_offset(&self) -> f64 {
+        self.offset
+    }
+
+    /// Advance the animation by the given delta time in milliseconds.
+    /// Returns true if the animation is still running, false if complete.
+    pub fn tick(&mut self, delta_ms: u64) -> bool {
+        let held_ms = KINETIC_RELEASE_MS.saturating_sub(self.idle_ms);
+        self.idle_ms = self.idle_ms.saturating_add(delta_ms);
+        let coast_ms = delta_ms.saturating_sub(held_ms);
+        if coast_ms > 0 {
+            // Distance is the integral of velocity * friction^t over the step
+            let decay = self.friction.powf(coast_ms as f64);
+            let distance = self.velocity * (decay - 1.0) / self.friction.ln();
+            self.offset = (self.offset + distance).clamp(0.0, self.max_offset);
+            self.velocity *= decay;
+            if self.offset <= 0.0 || self.offset >= self.max_offset {
+                self.velocity = 0.0;
+            }
+        }
+        !self.is_complete()
+    }
+
+    /// Get the offset the animation will come to rest at.
+    pub fn target(&self) -> f64 {
+        (self.offset - self.velocity / self.friction.ln()).clamp(0.0, self.max_offset)
+    }
+}
+
+/// Viewport animation in progress on a workspace.
+#[derive(Debug, Clone)]
+enum ViewportAnimation {
+    Scroll(ScrollAnimation),
+    Kinetic(KineticAnimation),
+}
+
+impl ViewportAnimation {
+    fn current_offset(&self) -> f64 {
+        match self {
+            Self::Scroll(anim) => anim.current_offset(),
+            Self::Kinetic(anim) => anim.current_offset(),
+        }
+    }
+
+    fn target(&self) -> f64 {
+        match self {
+            Self::Scroll(anim) => anim.target(),
+            Self::Kinetic(anim) => anim.target(),
+        }
+    }
+
+    fn tick(&mut self, delta_ms: u64) -> bool {
+        match self {
+            Self::Scroll(anim) => anim.tick(delta_ms),
+            Self::Kinetic(anim) => anim.tick(delta_ms),
+        }
+    }
+}
+
 /// Computed placement for a window.
 /// Contains the target rectangle and visibility state.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct WindowPlacement {
     /// The window identifier.
     pub window_id: WindowId,
@@ -215,14 +463,101 @@ pub struct WindowPlacement {
     pub column_index: usize,
 }
 
+/// Size limits a window imposes on itself, e.g. through `WM_GETMINMAXINFO`.
+///
+/// Windows refuse sizes outside these limits, so placing them there would
+/// make them overlap their neighbors. A minimum of 0 and a maximum of `None`
+/// mean unconstrained.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SizeConstraints {
+    pub min_width: i32,
+    pub min_height: i32,
+    pub max_width: Option<i32>,
+    pub max_height: Option<i32>,
+}
+
+impl SizeConstraints {
+    /// Whether no limits are set.
+    pub fn is_unconstrained(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Split `available` pixels among windows with `(weight, min, max)` height
+/// limits.
+///
+/// Windows get shares proportional to their weights, with the rounding
+/// remainder going to the last one. A window whose share is outside its
+/// limits is pinned to the limit and the difference is redistributed among
+/// the others. If the minimums don't fit, windows get their minimums and
+/// overflow.
+fn distribute_heights(available: i32, limits: &[(u32, i32, Option<i32>)]) -> Vec<i32> {
+    let mut heights: Vec<Option<i32>> = vec![None; limits.len()];
+    loop {
+        let free: Vec<usize> = (0..limits.len()).filter(|&i| heights[i].is_none()).collect();
+        if free.is_empty() {
+            break;
+        }
+        let pinned: i32 = heights.iter().flatten().sum();
+        let remaining = (available - pinned).max(0);
+        let total_weight: i64 = free.iter().map(|&i| i64::from(limits[i].0.max(1))).sum();
+        let share = |i: usize| (i64::from(remaining) * i64::from(limits[i].0.max(1)) / total_weight) as i32;
+
+        let mut changed = false;
+        for &i in &free {
+            let (_, min, max) = limits[i];
+            let share = share(i);
+            if share < min {
+                heights[i] = Some(min);
+                changed = true;
+            } else if let Some(max) = max.filter(|&max| share > max) {
+                heights[i] = Some(max.max(min));
+                changed = true;
+            }
+        }
+        if !changed {
+            let last = *free.last().unwrap_or(&0);
+            let mut assigned = 0;
+            for &i in &free {
+                let height = if i == last { remaining - assigned } else { share(i) };
+                assigned += height;
+                heights[i] = Some(height);
+            }
+        }
+    }
+    heights.into_iter().map(|h| h.unwrap_or(0)).collect()
+}
+
 /// A column in the infinite strip.
-/// A column contains one or more vertically stacked windows.
+/// A column contains one or more vertically stacked windows, or in tabbed
+/// mode shows one of them at full height.
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Column {
     /// Width of the column in pixels.
     width: i32,
     /// Windows in this column (vertically stacked).
     windows: Vec<WindowId>,
+    /// Whether the column is collapsed to a thin placeholder.
+    #[serde(default)]
+    collapsed: bool,
+    /// Whether only one window (the active tab) is shown at a time.
+    #[serde(default)]
+    tabbed: bool,
+    /// Index of the window shown in tabbed mode while the column is not focused.
+    #[serde(default)]
+    active_tab: usize,
+    /// Tag shared by windows that always open together in this column.
+    #[serde(default)]
+    tag: Option<String>,
+    /// Height weights of stacked windows, set once one of them is resized.
+    ///
+    /// Windows without a weight get the average of the others; an empty map
+    /// means equal heights.
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    height_weights: BTreeMap<WindowId, u32>,
+    /// Width to restore while the column is maximized to the viewport width.
+    #[serde(default)]
+    restore_width: Option<i32>,
 }
 
 impl Column {
@@ -232,6 +567,12 @@ impl Column {
         Self {
             width: width.max(MIN_COLUMN_WIDTH),
             windows: vec![window_id],
+            collapsed: false,
+            tabbed: false,
+            active_tab: 0,
+            tag: None,
+            height_weights: BTreeMap::new(),
+            restore_width: None,
         }
     }
 
@@ -241,6 +582,12 @@ impl Column {
         Self {
             width: width.max(MIN_COLUMN_WIDTH),
             windows: Vec::new(),
+            collapsed: false,
+            tabbed: false,
+            active_tab: 0,
+            tag: None,
+            height_weights: BTreeMap::new(),
+            restore_width: None,
         }
     }
 
@@ -257,19 +604,43 @@ impl Column {
     /// Add a window to this column (at the bottom of the stack).
     pub fn add_window(&mut self, window_id: WindowId) {
         self.windows.push(window_id);
+        self.weigh_new_window(window_id);
+    }
+
+    /// Give a window joining resized windows the average weight, so it
+    /// keeps its share when others leave.
+    fn weigh_new_window(&mut self, window_id: WindowId) {
+        if self.height_weights.is_empty() || self.height_weights.contains_key(&window_id) {
+            return;
+        }
+        let average = self.height_weights.values().map(|&w| u64::from(w)).sum::<u64>() / self.height_weights.len() as u64;
+        self.height_weights.insert(window_id, average as u32);
+    }
+
+    /// Insert a window at `position` in the stack (clamped to the bottom).
+    fn insert_window_at(&mut self, position: usize, window_id: WindowId) {
+        self.windows.insert(position.min(self.windows.len()), window_id);
+        self.weigh_new_window(window_id);
     }
 
     /// Remove a window from this column.
     /// Returns the index of the removed window if found, None otherwise.
     pub fn remove_window(&mut self, window_id: WindowId) -> Option<usize> {
         if let Some(pos) = self.windows.iter().position(|&w| w == window_id) {
-            self.windows.remove(pos);
+            self.remove_at(pos);
             Some(pos)
         } else {
             None
         }
     }
 
+    /// Remove the window at `index`, forgetting its height weight.
+    fn remove_at(&mut self, index: usize) -> WindowId {
+        let window_id = self.windows.remove(index);
+        self.height_weights.remove(&window_id);
+        window_id
+    }
+
     /// Get the width of this column.
     pub fn width(&self) -> i32 {
         self.width
@@ -277,8 +648,41 @@ impl Column {
 
     /// Set the width of this column.
     /// Width is clamped to MIN_COLUMN_WIDTH (100px) minimum.
+    ///
+    /// A maximized column keeps the new width and forgets its previous one.
     pub fn set_width(&mut self, width: i32) {
         self.width = width.max(MIN_COLUMN_WIDTH);
+        self.restore_width = None;
+    }
+
+    /// Check if this column is maximized to the viewport width.
+    pub fn is_maximized(&self) -> bool {
+        self.restore_width.is_some()
+    }
+
+    /// Check if this column is collapsed to a placeholder.
+    pub fn is_collapsed(&self) -> bool {
+        self.collapsed
+    }
+
+    /// Check if this column shows its windows as tabs.
+    pub fn is_tabbed(&self) -> bool {
+        self.tabbed
+    }
+
+    /// Index of the window shown in tabbed mode while the column is not focused.
+    pub fn active_tab(&self) -> usize {
+        self.active_tab.min(self.windows.len().saturating_sub(1))
+    }
+
+    /// Tag of this column, if windows are grouped into it by tag.
+    pub fn tag(&self) -> Option<&str> {
+        self.tag.as_deref()
+    }
+
+    /// Set or clear the column's tag.
+    pub fn set_tag(&mut self, tag: Option<String>) {
+        self.tag = tag;
     }
 
     /// Get a slice of windows in this column.
@@ -295,6 +699,49 @@ impl Column {
     pub fn get(&self, index: usize) -> Option<WindowId> {
         self.windows.get(index).copied()
     }
+
+    /// Whether stacked windows share the height equally.
+    pub fn has_equal_heights(&self) -> bool {
+        self.height_weights.is_empty()
+    }
+
+    /// Give all stacked windows the same height again.
+    pub fn reset_heights(&mut self) {
+        self.height_weights.clear();
+    }
+
+    /// Height weight of each window, in stacking order.
+    fn height_weights(&self) -> Vec<u32> {
+        let known: Vec<u32> = self.windows.iter().filter_map(|id| self.height_weights.get(id).copied()).collect();
+        if known.is_empty() {
+            return vec![1; self.windows.len()];
+        }
+        let average = (known.iter().map(|&w| u64::from(w)).sum::<u64>() / known.len() as u64) as u32;
+        self.windows
+            .iter()
+            .map(|id| self.height_weights.get(id).copied().unwrap_or(average).max(1))
+            .collect()
+    }
+
+    /// Width and window heights of this column.
+    pub fn size(&self) -> ColumnSize {
+        ColumnSize { width: self.width, height_weights: self.height_weights.clone() }
+    }
+}
+
+/// Width and stacked window heights of a column, saved so an interactive
+/// resize can be reverted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnSize {
+    width: i32,
+    height_weights: BTreeMap<WindowId, u32>,
+}
+
+impl ColumnSize {
+    /// Saved column width in pixels.
+    pub fn width(&self) -> i32 {
+        self.width
+    }
 }
 
 /// Focus centering mode.
@@ -308,6 +755,74 @@ pub enum CenteringMode {
     JustInView,
 }
 
+/// Where a new window's column is inserted into the strip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum InsertPosition {
+    /// Right of the focused column.
+    #[default]
+    RightOfFocus,
+    /// After the last column.
+    EndOfStrip,
+    /// Before the first column.
+    Start,
+    /// Into empty space showing in the viewport: after the last column if
+    /// the strip ends inside it, and right of the focused column otherwise.
+    /// The strip never starts inside the viewport, as the scroll offset is
+    /// at least 0.
+    NearestEmpty,
+}
+
+/// Layout mode of a workspace.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LayoutMode {
+    /// Scrollable strip of columns.
+    #[default]
+    Scrolling,
+    /// A single full-width column that all windows stack into.
+    /// Suited to portrait side monitors (chat, logs, etc.).
+    StackOnly,
+}
+
+/// Direction the strip of a scrolling workspace runs in.
+///
+/// A vertical strip is a horizontal one turned on its side: columns become
+/// rows stacked top to bottom, their windows sit side by side, and the
+/// viewport scrolls up and down. Column widths, scroll offsets and the
+/// `viewport_width` arguments of scrolling methods are then measured along
+/// the vertical axis; see [`Workspace::strip_length`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Orientation {
+    /// Columns side by side, scrolling left and right.
+    #[default]
+    Horizontal,
+    /// Rows on top of each other, scrolling up and down.
+    /// Suited to portrait monitors.
+    Vertical,
+}
+
+/// A window in a workspace together with its position in the layout.
+///
+/// Yielded by [`Workspace::iter_windows`] and [`Workspace::iter_placements`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WindowEntry {
+    /// The window identifier.
+    pub window_id: WindowId,
+    /// Index of the column holding the window, or `None` if floating.
+    pub column_index: Option<usize>,
+    /// Index of the window within its column, or `None` if floating.
+    pub window_index: Option<usize>,
+}
+
+impl WindowEntry {
+    /// Whether the window is floating rather than tiled.
+    pub fn is_floating(&self) -> bool {
+        self.column_index.is_none()
+    }
+}
+
 /// A floating window that is not part of the tiling layout.
 ///
 /// Floating windows are positioned at absolute coordinates and always
@@ -320,6 +835,91 @@ pub struct FloatingWindow {
     pub rect: Rect,
 }
 
+/// Work area edge a floating window can be snapped against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapEdge {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+impl FloatingWindow {
+    /// Keep the window inside `area`, shrinking it if it is larger than the area.
+    pub fn clamp_to(&mut self, area: Rect) {
+        let width = self.rect.width.min(area.width);
+        let height = self.rect.height.min(area.height);
+        let x = self.rect.x.clamp(area.x, area.right() - width);
+        let y = self.rect.y.clamp(area.y, area.bottom() - height);
+        self.rect = Rect::new(x, y, width, height);
+    }
+
+    /// Move the window by a pixel offset, staying inside `area`.
+    pub fn move_by(&mut self, dx: i32, dy: i32, area: Rect) {
+        self.rect.x = self.rect.x.saturating_add(dx);
+        self.rect.y = self.rect.y.saturating_add(dy);
+        self.clamp_to(area);
+    }
+
+    /// Grow or shrink the window around its top-left corner, staying inside `area`.
+    ///
+    /// The size never drops below [`MIN_FLOATING_SIZE`] unless the area itself is smaller.
+    pub fn resize_by(&mut self, dw: i32, dh: i32, area: Rect) {
+        self.rect.width = self.rect.width.saturating_add(dw).max(MIN_FLOATING_SIZE.min(area.width));
+        self.rect.height = self.rect.height.saturating_add(dh).max(MIN_FLOATING_SIZE.min(area.height));
+        self.clamp_to(area);
+    }
+
+    /// Center the window in `area`, shrinking it first if it doesn't fit.
+    pub fn center_in(&mut self, area: Rect) {
+        self.clamp_to(area);
+        self.rect.x = area.x + (area.width - self.rect.width) / 2;
+        self.rect.y = area.y + (area.height - self.rect.height) / 2;
+    }
+
+    /// Move the window flush against an edge of `area`, keeping its size and
+    /// its position along that edge.
+    pub fn snap_to(&mut self, edge: SnapEdge, area: Rect) {
+        self.clamp_to(area);
+        match edge {
+            SnapEdge::Left => self.rect.x = area.x,
+            SnapEdge::Right => self.rect.x = area.right() - self.rect.width,
+            SnapEdge::Top => self.rect.y = area.y,
+            SnapEdge::Bottom => self.rect.y = area.bottom() - self.rect.height,
+        }
+    }
+}
+
+/// A tiled window taken out of the strip while it is minimized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct ParkedWindow {
+    /// The window identifier.
+    id: WindowId,
+    /// Index of the column the window was in.
+    column_index: usize,
+    /// Position in its column if it shared the column, None if it was alone.
+    stack_index: Option<usize>,
+    /// Width of the column it was in.
+    width: i32,
+}
+
+/// A window shown over its whole monitor, above the strip.
+///
+/// The window keeps its place in its column; the layer only records what
+/// is needed to put the strip back exactly as it was on exit.
+#[derive(Debug, Clone, PartialEq)]
+struct FullscreenLayer {
+    /// The fullscreen window.
+    window_id: WindowId,
+    /// Monitor rect the window covers, including the taskbar area.
+    rect: Rect,
+    /// Width of the window's column when fullscreen was entered.
+    column_width: i32,
+    /// Strip scroll offset when fullscreen was entered.
+    scroll_offset: f64,
+}
+
 /// The scrollable workspace.
 /// This is the core data structure representing the infinite horizontal strip.
 ///
@@ -334,6 +934,8 @@ pub struct FloatingWindow {
 /// 3. **Valid column widths:** All column widths are >= `MIN_COLUMN_WIDTH` (100px).
 /// 4. **Valid scroll range:** `0.0 <= scroll_offset <= max_scroll` where
 ///    `max_scroll = (total_width() - viewport_width).max(0)`.
+/// 5. **Consistent window index:** Every tiled window maps to its current
+///    (column, index) position, so lookups by ID don't scan the columns.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Workspace {
     /// Columns in the workspace, ordered left to right.
@@ -352,15 +954,66 @@ pub struct Workspace {
     default_column_width: i32,
     /// Centering mode for focus changes.
     centering_mode: CenteringMode,
-    /// Active scroll animation, if any.
+    /// Active scroll or kinetic animation, if any.
     #[serde(skip)]
-    active_animation: Option<ScrollAnimation>,
+    active_animation: Option<ViewportAnimation>,
+    /// Columns sliding into place after a window opened or closed, if any.
+    #[serde(skip)]
+    column_animation: Option<ColumnAnimation>,
     /// Floating windows outside the tiling layout.
     #[serde(default)]
     floating_windows: Vec<FloatingWindow>,
-    /// Window ID in fullscreen mode, if any.
+    /// Fullscreen window and the strip state to restore on exit, if any.
+    #[serde(skip)]
+    fullscreen: Option<FullscreenLayer>,
+    /// Strip width of collapsed columns (always >= MIN_COLLAPSED_COLUMN_WIDTH).
+    #[serde(default = "default_collapsed_column_width")]
+    collapsed_column_width: i32,
+    /// Layout mode (scrolling strip or single stack).
     #[serde(default)]
-    fullscreen_window: Option<WindowId>,
+    layout_mode: LayoutMode,
+    /// Direction of the scrolling strip.
+    #[serde(default)]
+    orientation: Orientation,
+    /// Maximum overflow, as a fraction of the viewport width, that is absorbed
+    /// by shrinking columns instead of scrolling (0.0 disables).
+    #[serde(default)]
+    shrink_to_fit_threshold: f64,
+    /// Whether the last applied layout shrank the strip to fit (hysteresis state).
+    #[serde(skip)]
+    shrink_to_fit_active: bool,
+    /// Whether columns stretch to fill the viewport when the strip is narrower.
+    #[serde(default)]
+    auto_balance: bool,
+    /// Whether focusing or moving past either end of the strip wraps around.
+    #[serde(default)]
+    wrap_navigation: bool,
+    /// Where new windows are inserted.
+    #[serde(default)]
+    insert_position: InsertPosition,
+    /// Widest a column is laid out (always >= MIN_COLUMN_WIDTH).
+    #[serde(default = "default_max_column_width")]
+    max_column_width: i32,
+    /// Last floating rect of windows that were floated and then tiled again.
+    #[serde(default)]
+    floating_restore_rects: HashMap<WindowId, Rect>,
+    /// Tiled window lookup: window ID to (column index, index in column).
+    ///
+    /// Rebuilt by every method that changes the column structure; built on
+    /// first use after deserialization.
+    #[serde(skip)]
+    window_index: OnceCell<HashMap<WindowId, (usize, usize)>>,
+    /// Minimized tiled windows waiting to be restored to their column.
+    ///
+    /// Not persisted: window handles of minimized windows are re-tiled on
+    /// enumeration after a restart.
+    #[serde(skip)]
+    parked_windows: Vec<ParkedWindow>,
+    /// Size limits of windows that have any, applied when laying out columns.
+    ///
+    /// Not persisted: the daemon queries them from each window.
+    #[serde(skip)]
+    size_constraints: HashMap<WindowId, SizeConstraints>,
 }
 
 impl Default for Workspace {
@@ -375,8 +1028,22 @@ impl Default for Workspace {
             default_column_width: DEFAULT_COLUMN_WIDTH,
             centering_mode: CenteringMode::default(),
             active_animation: None,
+            column_animation: None,
             floating_windows: Vec::new(),
-            fullscreen_window: None,
+            fullscreen: None,
+            collapsed_column_width: DEFAULT_COLLAPSED_COLUMN_WIDTH,
+            layout_mode: LayoutMode::default(),
+            orientation: Orientation::default(),
+            shrink_to_fit_threshold: 0.0,
+            shrink_to_fit_active: false,
+            auto_balance: false,
+            wrap_navigation: false,
+            insert_position: InsertPosition::default(),
+            max_column_width: default_max_column_width(),
+            floating_restore_rects: HashMap::new(),
+            window_index: OnceCell::new(),
+            parked_windows: Vec::new(),
+            size_constraints: HashMap::new(),
         }
     }
 }
@@ -387,6 +1054,27 @@ impl Workspace {
         Self::default()
     }
 
+    /// Create an empty workspace with the same settings (gaps, widths,
+    /// centering, layout mode, orientation, shrink-to-fit, auto-balance,
+    /// wrap-around navigation, insert position) as this one.
+    pub fn empty_like(&self) -> Self {
+        Self {
+            gap: self.gap,
+            outer_gap: self.outer_gap,
+            default_column_width: self.default_column_width,
+            centering_mode: self.centering_mode,
+            collapsed_column_width: self.collapsed_column_width,
+            layout_mode: self.layout_mode,
+            orientation: self.orientation,
+            shrink_to_fit_threshold: self.shrink_to_fit_threshold,
+            auto_balance: self.auto_balance,
+            wrap_navigation: self.wrap_navigation,
+            insert_position: self.insert_position,
+            max_column_width: self.max_column_width,
+            ..Self::default()
+        }
+    }
+
     /// Create a workspace with custom gap settings.
     /// Gap values are clamped to >= 0.
     pub fn with_gaps(gap: i32, outer_gap: i32) -> Self {
@@ -407,10 +1095,21 @@ impl Workspace {
         self.columns.len()
     }
 
-    /// Check if a window ID already exists in the workspace (tiled or floating).
+    /// Check if a window ID already exists in the workspace (tiled, floating or parked).
     pub fn contains_window(&self, window_id: WindowId) -> bool {
-        self.columns.iter().any(|c| c.windows.contains(&window_id))
+        self.window_index().contains_key(&window_id)
             || self.floating_windows.iter().any(|f| f.id == window_id)
+            || self.is_parked(window_id)
+    }
+
+    /// The tiled window lookup index, built from the columns if needed.
+    fn window_index(&self) -> &HashMap<WindowId, (usize, usize)> {
+        self.window_index.get_or_init(|| build_window_index(&self.columns))
+    }
+
+    /// Rebuild the window lookup index after the column structure changed.
+    fn reindex(&mut self) {
+        self.window_index = OnceCell::from(build_window_index(&self.columns));
     }
 
     /// Check if a window is floating.
@@ -459,6 +1158,11 @@ impl Workspace {
         }
     }
 
+    /// Get a floating window for in-place adjustment.
+    pub fn floating_window_mut(&mut self, window_id: WindowId) -> Option<&mut FloatingWindow> {
+        self.floating_windows.iter_mut().find(|f| f.id == window_id)
+    }
+
     /// Get all floating windows.
     pub fn floating_windows(&self) -> &[FloatingWindow] {
         &self.floating_windows
@@ -477,7 +1181,7 @@ impl Workspace {
         let outer_gap = self.outer_gap.max(0);
 
         let column_widths: i32 = self.columns.iter()
-            .map(|c| c.width)
+            .map(|c| self.strip_width(c))
             .fold(0i32, |acc, w| acc.saturating_add(w));
         let gaps = gap.saturating_mul(self.columns.len().saturating_sub(1) as i32);
         let outer_gaps = outer_gap.saturating_mul(2);
@@ -492,23 +1196,57 @@ impl Workspace {
     ///
     /// Returns `LayoutError::DuplicateWindow` if the window ID already exists.
     pub fn insert_window(&mut self, window_id: WindowId, width: Option<i32>) -> Result<(), LayoutError> {
+        self.insert_window_at(window_id, width, InsertPosition::RightOfFocus, 0)
+    }
+
+    /// Insert a window as a new column at `position` and focus it.
+    /// Column width is clamped to MIN_COLUMN_WIDTH (100px) minimum.
+    ///
+    /// Stack-only workspaces stack the window into their single column.
+    /// `viewport_width` is only needed for [`InsertPosition::NearestEmpty`].
+    ///
+    /// # Errors
+    ///
+    /// Returns `LayoutError::DuplicateWindow` if the window ID already exists.
+    pub fn insert_window_at(
+        &mut self,
+        window_id: WindowId,
+        width: Option<i32>,
+        position: InsertPosition,
+        viewport_width: i32,
+    ) -> Result<(), LayoutError> {
         if self.contains_window(window_id) {
             return Err(LayoutError::DuplicateWindow(window_id));
         }
 
-        let column_width = width.unwrap_or(self.default_column_width).max(MIN_COLUMN_WIDTH);
-        let new_column = Column::new(window_id, column_width);
-
-        if self.columns.is_empty() {
-            self.columns.push(new_column);
+        if self.layout_mode == LayoutMode::StackOnly && !self.columns.is_empty() {
+            // Stack-only workspaces have exactly one column
+            self.columns[0].add_window(window_id);
             self.focused_column = 0;
-        } else {
-            // Insert to the right of the focused column
-            let insert_pos = self.focused_column + 1;
-            self.columns.insert(insert_pos, new_column);
-            self.focused_column = insert_pos;
+            self.focused_window_in_column = self.columns[0].len() - 1;
+            self.reindex();
+            return Ok(());
         }
+
+        let index = match position {
+            _ if self.columns.is_empty() => 0,
+            InsertPosition::RightOfFocus => self.focused_column + 1,
+            InsertPosition::EndOfStrip => self.columns.len(),
+            InsertPosition::Start => 0,
+            InsertPosition::NearestEmpty => {
+                let offset = self.effective_scroll_offset();
+                if f64::from(self.total_width()) < offset + f64::from(viewport_width) {
+                    self.columns.len()
+                } else {
+                    self.focused_column + 1
+                }
+            }
+        };
+        let column_width = width.unwrap_or(self.default_column_width).max(MIN_COLUMN_WIDTH);
+        self.columns.insert(index, Column::new(window_id, column_width));
+        self.focused_column = index;
         self.focused_window_in_column = 0;
+        self.reindex();
 
         debug_assert!(
             self.focused_column < self.columns.len(),
@@ -541,79 +1279,221 @@ impl Workspace {
         }
 
         self.columns[column_index].add_window(window_id);
+        self.reindex();
         Ok(())
     }
 
-    /// Remove a window from the workspace.
-    /// If removing the last window from a column, the column is removed.
-    /// If removing the last column, the workspace becomes empty.
+    /// Find the column carrying a tag.
+    pub fn find_tagged_column(&self, tag: &str) -> Option<usize> {
+        self.columns.iter().position(|c| c.tag() == Some(tag))
+    }
+
+    /// Insert a window into the column carrying `tag` and focus it.
     ///
-    /// # Focus Policy
+    /// If no column has the tag yet, a new column is created (as with
+    /// [`insert_window`](Self::insert_window)) and tagged; `width` only
+    /// applies in that case. Tagged windows join the bottom of the stack.
     ///
-    /// When removing a window from a stacked column:
-    /// - If removed window was before the focused window, focus index decrements to stay on same window
-    /// - If removed window was the focused window, focus moves to next window (or previous if at end)
-    /// - If removed window was after the focused window, focus index stays the same
-    pub fn remove_window(&mut self, window_id: WindowId) -> Result<(), LayoutError> {
-        for (col_idx, column) in self.columns.iter_mut().enumerate() {
-            if let Some(removed_idx) = column.remove_window(window_id) {
-                // If column is now empty, remove it
-                if column.is_empty() {
-                    self.columns.remove(col_idx);
-                    if self.columns.is_empty() {
-                        // Workspace is now empty - reset all state
-                        self.focused_column = 0;
-                        self.focused_window_in_column = 0;
-                        self.scroll_offset = 0.0;
-                    } else if self.focused_column >= self.columns.len() {
-                        self.focused_column = self.columns.len() - 1;
-                    } else if self.focused_column > col_idx {
-                        self.focused_column -= 1;
-                    }
-                } else {
-                    // Adjust focused window in column if this is the focused column
-                    if col_idx == self.focused_column {
-                        let col_len = self.columns[self.focused_column].len();
-                        if removed_idx < self.focused_window_in_column {
-                            // Removed window was before focused - decrement to stay on same window
-                            self.focused_window_in_column -= 1;
-                        } else if removed_idx == self.focused_window_in_column {
-                            // Removed the focused window - move to next (or previous if at end)
-                            if self.focused_window_in_column >= col_len {
-                                self.focused_window_in_column = col_len.saturating_sub(1);
-                            }
-                            // If focus index is still valid, it now points to the "next" window
-                            // (which slid into this position), which is the expected behavior
-                        }
-                        // If removed_idx > focused_window_in_column, no adjustment needed
-                    }
+    /// # Errors
+    ///
+    /// Returns `LayoutError::DuplicateWindow` if the window ID already exists.
+    pub fn insert_window_tagged(
+        &mut self,
+        window_id: WindowId,
+        tag: &str,
+        width: Option<i32>,
+    ) -> Result<(), LayoutError> {
+        match self.find_tagged_column(tag) {
+            Some(col_idx) => self.stack_in_column(window_id, col_idx)?,
+            None => {
+                self.insert_window(window_id, width)?;
+                // Stack-only workspaces share one column; don't claim it for a tag
+                if self.layout_mode == LayoutMode::Scrolling {
+                    self.columns[self.focused_column].set_tag(Some(tag.to_string()));
                 }
+            }
+        }
+        Ok(())
+    }
+
+    /// Insert a window at the bottom of the stack holding `anchor` and focus it.
+    ///
+    /// If `anchor` isn't tiled in this workspace, a new column is created as
+    /// with [`insert_window`](Self::insert_window); `width` only applies in
+    /// that case.
+    ///
+    /// # Errors
+    ///
+    /// Returns `LayoutError::DuplicateWindow` if the window ID already exists.
+    pub fn insert_window_with(
+        &mut self,
+        window_id: WindowId,
+        anchor: WindowId,
+        width: Option<i32>,
+    ) -> Result<(), LayoutError> {
+        match self.find_window_location(anchor) {
+            Some((col_idx, _)) => self.stack_in_column(window_id, col_idx),
+            None => self.insert_window(window_id, width),
+        }
+    }
+
+    /// Append a window to the bottom of a column and focus it.
+    fn stack_in_column(&mut self, window_id: WindowId, col_idx: usize) -> Result<(), LayoutError> {
+        self.insert_window_in_column(window_id, col_idx)?;
+        self.remember_active_tab();
+        self.focused_column = col_idx;
+        self.focused_window_in_column = self.columns[col_idx].len() - 1;
+        Ok(())
+    }
+
+    /// Insert a whole column to the right of the focused column and focus it.
+    ///
+    /// The column keeps its windows (in order), width, and collapsed and tabbed state;
+    /// `focused_window` picks the window to focus and is clamped to the column.
+    /// In stack-only mode the windows are appended to the single stack instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns `LayoutError::DuplicateWindow` if any of the windows already exists.
+    pub fn insert_column(&mut self, column: Column, focused_window: usize) -> Result<(), LayoutError> {
+        if let Some(&duplicate) = column.windows.iter().find(|&&w| self.contains_window(w)) {
+            return Err(LayoutError::DuplicateWindow(duplicate));
+        }
+        if column.is_empty() {
+            return Ok(());
+        }
+        let focused_window = focused_window.min(column.len() - 1);
+
+        if self.layout_mode == LayoutMode::StackOnly && !self.columns.is_empty() {
+            let offset = self.columns[0].len();
+            self.columns[0].windows.extend(column.windows);
+            self.focused_column = 0;
+            self.focused_window_in_column = offset + focused_window;
+            self.reindex();
+            return Ok(());
+        }
+
+        let insert_pos = if self.columns.is_empty() { 0 } else { self.focused_column + 1 };
+        self.columns.insert(insert_pos, column);
+        self.focused_column = insert_pos;
+        self.focused_window_in_column = focused_window;
+        self.reindex();
+        Ok(())
+    }
+
+    /// Remove the focused column with all its windows.
+    ///
+    /// Returns the column and the index of its focused window, or None if the
+    /// workspace is empty. Focus moves to the column that takes its place.
+    pub fn take_focused_column(&mut self) -> Option<(Column, usize)> {
+        if self.columns.is_empty() {
+            return None;
+        }
+        let column = self.columns.remove(self.focused_column);
+        let focused_window = self.focused_window_in_column;
+        self.reindex();
+
+        if self.fullscreen.as_ref().is_some_and(|f| column.contains(f.window_id)) {
+            self.fullscreen = None;
+        }
+        if self.columns.is_empty() {
+            self.focused_column = 0;
+            self.scroll_offset = 0.0;
+        } else if self.focused_column >= self.columns.len() {
+            self.focused_column = self.columns.len() - 1;
+        }
+        self.focused_window_in_column = 0;
+
+        Some((column, focused_window))
+    }
+
+    /// Remove a window from the workspace.
+    /// If removing the last window from a column, the column is removed.
+    /// If removing the last column, the workspace becomes empty.
+    /// A parked (minimized) window is simply forgotten.
+    ///
+    /// # Focus Policy
+    ///
+    /// When removing a window from a stacked column:
+    /// - If removed window was before the focused window, focus index decrements to stay on same window
+    /// - If removed window was the focused window, focus moves to next window (or previous if at end)
+    /// - If removed window was after the focused window, focus index stays the same
+    pub fn remove_window(&mut self, window_id: WindowId) -> Result<(), LayoutError> {
+        if let Some(pos) = self.parked_windows.iter().position(|p| p.id == window_id) {
+            self.parked_windows.remove(pos);
+            return Ok(());
+        }
+        let (col_idx, removed_idx) = self
+            .find_window_location(window_id)
+            .ok_or(LayoutError::WindowNotFound(window_id))?;
+        self.columns[col_idx].remove_at(removed_idx);
+        if self.fullscreen_window_id() == Some(window_id) {
+            self.fullscreen = None;
+        }
 
-                debug_assert!(
-                    self.columns.is_empty() || self.focused_column < self.columns.len(),
-                    "Invariant violation: focused_column out of bounds after remove"
-                );
-                debug_assert!(
-                    self.columns.is_empty()
-                        || self.focused_window_in_column < self.columns[self.focused_column].len(),
-                    "Invariant violation: focused_window_in_column out of bounds after remove"
-                );
-
-                return Ok(());
+        // If column is now empty, remove it
+        if self.columns[col_idx].is_empty() {
+            self.columns.remove(col_idx);
+            if self.columns.is_empty() {
+                // Workspace is now empty - reset all state
+                self.focused_column = 0;
+                self.focused_window_in_column = 0;
+                self.scroll_offset = 0.0;
+            } else if self.focused_column >= self.columns.len() {
+                self.focused_column = self.columns.len() - 1;
+            } else if self.focused_column > col_idx {
+                self.focused_column -= 1;
+            }
+        } else if col_idx == self.focused_column {
+            // Adjust focused window in column if this is the focused column
+            let col_len = self.columns[self.focused_column].len();
+            if removed_idx < self.focused_window_in_column {
+                // Removed window was before focused - decrement to stay on same window
+                self.focused_window_in_column -= 1;
+            } else if removed_idx == self.focused_window_in_column {
+                // Removed the focused window - move to next (or previous if at end)
+                if self.focused_window_in_column >= col_len {
+                    self.focused_window_in_column = col_len.saturating_sub(1);
+                }
+                // If focus index is still valid, it now points to the "next" window
+                // (which slid into this position), which is the expected behavior
             }
+            // If removed_idx > focused_window_in_column, no adjustment needed
         }
-        Err(LayoutError::WindowNotFound(window_id))
+        self.reindex();
+
+        debug_assert!(
+            self.columns.is_empty() || self.focused_column < self.columns.len(),
+            "Invariant violation: focused_column out of bounds after remove"
+        );
+        debug_assert!(
+            self.columns.is_empty()
+                || self.focused_window_in_column < self.columns[self.focused_column].len(),
+            "Invariant violation: focused_window_in_column out of bounds after remove"
+        );
+
+        Ok(())
     }
 
     /// Move focus to the column on the left.
+    ///
+    /// With wrap-around navigation, focus moves from the first column to the
+    /// last.
     pub fn focus_left(&mut self) {
-        if self.focused_column > 0 {
-            self.focused_column -= 1;
+        let target = match self.focused_column {
+            0 if self.wrap_navigation => self.columns.len().checked_sub(1).filter(|&last| last > 0),
+            0 => None,
+            index => Some(index - 1),
+        };
+        if let Some(target) = target {
+            self.remember_active_tab();
+            self.focused_column = target;
             // Clamp focused window in column
             let col_len = self.columns[self.focused_column].len();
             if self.focused_window_in_column >= col_len {
                 self.focused_window_in_column = col_len.saturating_sub(1);
             }
+            self.enter_active_tab();
         }
 
         debug_assert!(
@@ -625,14 +1505,26 @@ impl Workspace {
     }
 
     /// Move focus to the column on the right.
+    ///
+    /// With wrap-around navigation, focus moves from the last column to the
+    /// first.
     pub fn focus_right(&mut self) {
-        if self.focused_column + 1 < self.columns.len() {
-            self.focused_column += 1;
+        let target = if self.focused_column + 1 < self.columns.len() {
+            Some(self.focused_column + 1)
+        } else if self.wrap_navigation && self.columns.len() > 1 {
+            Some(0)
+        } else {
+            None
+        };
+        if let Some(target) = target {
+            self.remember_active_tab();
+            self.focused_column = target;
             // Clamp focused window in column
             let col_len = self.columns[self.focused_column].len();
             if self.focused_window_in_column >= col_len {
                 self.focused_window_in_column = col_len.saturating_sub(1);
             }
+            self.enter_active_tab();
         }
 
         debug_assert!(
@@ -692,15 +1584,26 @@ impl Workspace {
         self.columns.get(index)
     }
 
+    /// Restore the size of the column containing `window_id`.
+    ///
+    /// Heights of windows that joined the column since the size was saved
+    /// fall back to the average of the others. Returns false if the window
+    /// isn't tiled.
+    pub fn restore_column_size(&mut self, window_id: WindowId, size: &ColumnSize) -> bool {
+        let Some((index, _)) = self.find_window_location(window_id) else {
+            return false;
+        };
+        let column = &mut self.columns[index];
+        column.set_width(size.width);
+        column.height_weights =
+            size.height_weights.iter().filter(|(id, _)| column.windows.contains(id)).map(|(&id, &w)| (id, w)).collect();
+        true
+    }
+
     /// Find a window's location in the workspace.
     /// Returns (column_index, window_index_in_column) if found.
     pub fn find_window_location(&self, window_id: WindowId) -> Option<(usize, usize)> {
-        for (col_idx, column) in self.columns.iter().enumerate() {
-            if let Some(win_idx) = column.windows.iter().position(|&w| w == window_id) {
-                return Some((col_idx, win_idx));
-            }
-        }
-        None
+        self.window_index().get(&window_id).copied()
     }
 
     /// Get total window count across all columns.
@@ -708,7 +1611,7 @@ impl Workspace {
         self.columns.iter().map(|c| c.len()).sum()
     }
 
-    /// Get all window IDs in this workspace (both tiled and floating).
+    /// Get all window IDs in this workspace (tiled, floating and parked).
     ///
     /// Useful for migrating windows when monitors are disconnected.
     pub fn all_window_ids(&self) -> Vec<WindowId> {
@@ -717,6 +1620,7 @@ impl Workspace {
             .flat_map(|c| c.windows().iter().copied())
             .collect();
         ids.extend(self.floating_windows.iter().map(|f| f.id));
+        ids.extend(self.parked_windows.iter().map(|p| p.id));
         ids
     }
 
@@ -763,6 +1667,97 @@ impl Workspace {
         self.centering_mode = mode;
     }
 
+    /// Get the layout mode.
+    pub fn layout_mode(&self) -> LayoutMode {
+        self.layout_mode
+    }
+
+    /// Get the strip orientation.
+    pub fn orientation(&self) -> Orientation {
+        self.orientation
+    }
+
+    /// Set the strip orientation. Column widths and the scroll offset are
+    /// kept, now measured along the new axis.
+    pub fn set_orientation(&mut self, orientation: Orientation) {
+        self.orientation = orientation;
+    }
+
+    /// Length of `viewport` along the strip: its width, or its height on a
+    /// vertical strip. This is the `viewport_width` to pass to scrolling and
+    /// column width methods.
+    pub fn strip_length(&self, viewport: Rect) -> i32 {
+        match self.orientation {
+            Orientation::Horizontal => viewport.width,
+            Orientation::Vertical => viewport.height,
+        }
+    }
+
+    /// Length of `viewport` across the strip, which the windows of a column
+    /// share: its height, or its width on a vertical strip.
+    pub fn cross_length(&self, viewport: Rect) -> i32 {
+        match self.orientation {
+            Orientation::Horizontal => viewport.height,
+            Orientation::Vertical => viewport.width,
+        }
+    }
+
+    /// Whether tiled windows are laid out transposed: on a vertical scrolling
+    /// strip outside of fullscreen.
+    fn is_transposed(&self) -> bool {
+        self.orientation == Orientation::Vertical
+            && self.layout_mode == LayoutMode::Scrolling
+            && self.fullscreen.is_none()
+    }
+
+    /// Compute placements with `layout`, which lays out a horizontal strip.
+    ///
+    /// Vertical strips are laid out in the transposed viewport and the rects
+    /// of their tiled windows transposed back; floating windows keep theirs.
+    fn oriented_placements(
+        &self,
+        viewport: Rect,
+        layout: impl FnOnce(&Self, Rect) -> Vec<WindowPlacement>,
+    ) -> Vec<WindowPlacement> {
+        if !self.is_transposed() {
+            return layout(self, viewport);
+        }
+        let mut placements = layout(self, viewport.transposed());
+        for placement in &mut placements {
+            if placement.column_index != usize::MAX {
+                placement.rect = placement.rect.transposed();
+            }
+        }
+        placements
+    }
+
+    /// Set the layout mode.
+    ///
+    /// Switching to `StackOnly` merges all columns into the first one, keeping
+    /// window order (left to right, then top to bottom) and the focused window.
+    pub fn set_layout_mode(&mut self, mode: LayoutMode) {
+        self.layout_mode = mode;
+        if mode != LayoutMode::StackOnly || self.columns.len() <= 1 {
+            return;
+        }
+
+        let focused = self.focused_window();
+        let mut merged = self.columns.remove(0);
+        merged.collapsed = false;
+        for column in self.columns.drain(..) {
+            merged.windows.extend(column.windows);
+        }
+        self.columns.push(merged);
+        self.reindex();
+
+        self.focused_column = 0;
+        self.focused_window_in_column = focused
+            .and_then(|id| self.columns[0].windows.iter().position(|&w| w == id))
+            .unwrap_or(0);
+        self.scroll_offset = 0.0;
+        self.active_animation = None;
+    }
+
     /// Set focus to a specific column and window index with validation.
     ///
     /// # Errors
@@ -786,6 +1781,7 @@ impl Workspace {
             ));
         }
 
+        self.remember_active_tab();
         self.focused_column = column;
         self.focused_window_in_column = window_in_column;
         Ok(())
@@ -797,14 +1793,36 @@ impl Workspace {
     ///
     /// Returns `LayoutError::WindowNotFound` if the window is not in the workspace.
     pub fn focus_window(&mut self, window_id: WindowId) -> Result<(), LayoutError> {
-        for (col_idx, column) in self.columns.iter().enumerate() {
-            if let Some(win_idx) = column.windows.iter().position(|&w| w == window_id) {
-                self.focused_column = col_idx;
-                self.focused_window_in_column = win_idx;
-                return Ok(());
-            }
+        let (col_idx, win_idx) = self
+            .find_window_location(window_id)
+            .ok_or(LayoutError::WindowNotFound(window_id))?;
+        self.remember_active_tab();
+        self.focused_column = col_idx;
+        self.focused_window_in_column = win_idx;
+        Ok(())
+    }
+
+    /// Focus the column at `index`, keeping the window position within the
+    /// column like [`focus_left`](Self::focus_left) does.
+    ///
+    /// # Errors
+    ///
+    /// Returns `LayoutError::ColumnOutOfBounds` if the column index is invalid.
+    pub fn focus_column(&mut self, index: usize) -> Result<(), LayoutError> {
+        if index >= self.columns.len() {
+            return Err(LayoutError::ColumnOutOfBounds(index, self.columns.len().saturating_sub(1)));
+        }
+        if index == self.focused_column {
+            return Ok(());
         }
-        Err(LayoutError::WindowNotFound(window_id))
+        self.remember_active_tab();
+        self.focused_column = index;
+        let col_len = self.columns[index].len();
+        if self.focused_window_in_column >= col_len {
+            self.focused_window_in_column = col_len.saturating_sub(1);
+        }
+        self.enter_active_tab();
+        Ok(())
     }
 
     /// Calculate the x-coordinate of a column's left edge on the strip.
@@ -820,24 +1838,150 @@ impl Workspace {
             if i == column_index {
                 return x;
             }
-            x = x.saturating_add(col.width).saturating_add(gap);
+            x = x.saturating_add(self.strip_width(col)).saturating_add(gap);
         }
         x
     }
 
+    /// Width a column occupies on the strip (its placeholder width when collapsed).
+    fn strip_width(&self, column: &Column) -> i32 {
+        if column.collapsed {
+            self.collapsed_column_width
+        } else {
+            self.layout_width(column)
+        }
+    }
+
+    /// Width an expanded column takes: its own width, capped at the maximum
+    /// column width, widened to fit the largest minimum width of its windows.
+    fn layout_width(&self, column: &Column) -> i32 {
+        column
+            .windows
+            .iter()
+            .filter_map(|id| self.size_constraints.get(id))
+            .fold(column.width.min(self.max_column_width), |width, c| width.max(c.min_width))
+    }
+
+    /// Width of a window in a column of `column_width`, within its maximum.
+    fn window_width(&self, window_id: WindowId, column_width: i32) -> i32 {
+        match self.size_constraints.get(&window_id).and_then(|c| c.max_width) {
+            Some(max) => column_width.min(max.max(MIN_COLUMN_WIDTH)),
+            None => column_width,
+        }
+    }
+
+    /// Heights of the stacked windows of a column sharing `usable_height`.
+    fn window_heights(&self, column: &Column, usable_height: i32, gap: i32) -> Vec<i32> {
+        self.stacked_heights(&self.height_limits(column), usable_height, gap)
+    }
+
+    /// Height weight and size limits of each window of a column, as taken by
+    /// [`distribute_heights`].
+    fn height_limits(&self, column: &Column) -> Vec<(u32, i32, Option<i32>)> {
+        column
+            .windows
+            .iter()
+            .zip(column.height_weights())
+            .map(|(id, weight)| {
+                let c = self.size_constraints.get(id).copied().unwrap_or_default();
+                (weight, c.min_height, c.max_height)
+            })
+            .collect()
+    }
+
+    /// Heights of windows with `limits` stacked in `usable_height`.
+    fn stacked_heights(&self, limits: &[(u32, i32, Option<i32>)], usable_height: i32, gap: i32) -> Vec<i32> {
+        let count = limits.len() as i32;
+        let gaps = if count > 1 { gap.saturating_mul(count - 1) } else { 0 };
+        distribute_heights((usable_height - gaps).max(0), limits)
+    }
+
+    /// Set the size limits of a window.
+    pub fn set_size_constraints(&mut self, window_id: WindowId, constraints: SizeConstraints) {
+        if constraints.is_unconstrained() {
+            self.size_constraints.remove(&window_id);
+        } else {
+            self.size_constraints.insert(window_id, constraints);
+        }
+    }
+
+    /// Size limits of a window; unconstrained if none were set.
+    pub fn size_constraints(&self, window_id: WindowId) -> SizeConstraints {
+        self.size_constraints.get(&window_id).copied().unwrap_or_default()
+    }
+
+    /// Check the [invariants](Workspace#invariants) documented on the type,
+    /// with the scroll range taken for a viewport of `viewport_width`.
+    ///
+    /// Meant for tests and trace replay; the methods maintain the invariants
+    /// themselves.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LayoutError::InvariantViolation`] describing the first
+    /// invariant that doesn't hold.
+    pub fn check_invariants(&self, viewport_width: i32) -> Result<(), LayoutError> {
+        let violation = |message: String| Err(LayoutError::InvariantViolation(message));
+
+        let mut seen = HashSet::new();
+        for id in self.all_window_ids() {
+            if !seen.insert(id) {
+                return violation(format!("window {} appears more than once", id));
+            }
+        }
+
+        if !self.columns.is_empty() {
+            let Some(column) = self.columns.get(self.focused_column) else {
+                return violation(format!(
+                    "focused column {} of {} columns",
+                    self.focused_column,
+                    self.columns.len()
+                ));
+            };
+            if self.focused_window_in_column >= column.len() {
+                return violation(format!(
+                    "focused window {} of {} in column {}",
+                    self.focused_window_in_column,
+                    column.len(),
+                    self.focused_column
+                ));
+            }
+        }
+
+        if let Some((index, column)) = self.columns.iter().enumerate().find(|(_, c)| c.width() < MIN_COLUMN_WIDTH) {
+            return violation(format!("column {} is {}px wide", index, column.width()));
+        }
+
+        let max_scroll = (self.total_width() - viewport_width).max(0) as f64;
+        if !(0.0..=max_scroll).contains(&self.scroll_offset) {
+            return violation(format!("scroll offset {} outside 0..={}", self.scroll_offset, max_scroll));
+        }
+
+        let index = self.window_index();
+        if *index != build_window_index(&self.columns) {
+            return violation("window index is out of date".to_string());
+        }
+
+        Ok(())
+    }
+
     /// Get the x-coordinate and width of the focused column.
     fn focused_column_bounds(&self) -> Option<(i32, i32)> {
         self.columns.get(self.focused_column).map(|col| {
             let x = self.column_x(self.focused_column);
-            (x, col.width)
+            (x, self.strip_width(col))
         })
     }
 
     /// Ensure the focused column is visible in the viewport.
     /// Adjusts scroll_offset according to the centering mode.
     ///
+    /// A running animation is cancelled first, so the jump starts from the
+    /// current visual position.
+    ///
     /// Note: Negative gaps are treated as zero for calculation purposes.
     pub fn ensure_focused_visible(&mut self, viewport_width: i32) {
+        self.cancel_animation();
         if self.columns.is_empty() {
             return;
         }
@@ -878,6 +2022,25 @@ impl Workspace {
         self.scroll_offset = self.scroll_offset.clamp(0.0, max_scroll as f64);
     }
 
+    /// Scroll so the focused column is centered in the viewport, whatever
+    /// the centering mode, e.g. for the window that had focus at startup.
+    pub fn center_focused_column(&mut self, viewport_width: i32) {
+        self.cancel_animation();
+        if let Some(offset) = self.column_center_offset(self.focused_column, viewport_width) {
+            self.scroll_offset = offset;
+        }
+    }
+
+    /// Scroll offset that centers column `index` in a viewport of
+    /// `viewport_width`, clamped to the strip, or None if there is no such
+    /// column.
+    pub fn column_center_offset(&self, index: usize, viewport_width: i32) -> Option<f64> {
+        let column = self.columns.get(index)?;
+        let col_center = self.column_x(index).saturating_add(self.strip_width(column) / 2);
+        let max_scroll = (self.total_width() - viewport_width).max(0);
+        Some(col_center.saturating_sub(viewport_width / 2).clamp(0, max_scroll) as f64)
+    }
+
     /// Compute placements for all windows given a viewport.
     ///
     /// Returns a list of WindowPlacement structs indicating where each window
@@ -885,9 +2048,17 @@ impl Workspace {
     ///
     /// Note: Negative gaps are treated as zero for calculation purposes.
     pub fn compute_placements(&self, viewport: Rect) -> Vec<WindowPlacement> {
-        // Fullscreen mode: one window covers the entire viewport, others are off-screen
-        if let Some(fs_wid) = self.fullscreen_window {
-            return self.compute_fullscreen_placements(fs_wid, viewport);
+        self.oriented_placements(viewport, Self::compute_strip_placements)
+    }
+
+    /// Compute placements as for a horizontal strip.
+    fn compute_strip_placements(&self, viewport: Rect) -> Vec<WindowPlacement> {
+        // Fullscreen mode: one window covers its monitor, others are off-screen
+        if let Some(layer) = &self.fullscreen {
+            return self.compute_fullscreen_placements(layer);
+        }
+        if self.layout_mode == LayoutMode::StackOnly {
+            return self.compute_stack_placements(viewport);
         }
 
         let mut placements = Vec::new();
@@ -905,13 +2076,13 @@ impl Workspace {
         for (col_idx, column) in self.columns.iter().enumerate() {
             // Calculate column position in strip coordinates
             let col_strip_x = current_x;
-            let col_strip_right = col_strip_x.saturating_add(column.width);
+            let col_strip_right = col_strip_x.saturating_add(self.strip_width(column));
 
             // Transform to screen coordinates (relative to viewport)
             let col_screen_x = col_strip_x.saturating_sub(viewport_left).saturating_add(viewport.x);
 
-            // Determine visibility
-            let visibility = if col_strip_right <= viewport_left {
+            // Determine visibility (collapsed columns hide their windows)
+            let visibility = if column.collapsed || col_strip_right <= viewport_left {
                 Visibility::OffScreenLeft
             } else if col_strip_x >= viewport_right {
                 Visibility::OffScreenRight
@@ -919,37 +2090,34 @@ impl Workspace {
                 Visibility::Visible
             };
 
-            // Calculate window heights (equal split for stacked windows)
-            // Clamp usable_height to >= 0 to handle tight viewports
-            // Use saturating arithmetic to prevent overflow
+            // Tabbed columns show one window at full height and hide the rest
+            let shown_tab = self.shown_tab(col_idx);
+            let column_width = self.layout_width(column);
+
+            // Calculate window heights (equal split for stacked windows, within
+            // each window's size limits). Clamp usable_height to >= 0 to handle
+            // tight viewports
             let usable_height = viewport.height.saturating_sub(outer_gap.saturating_mul(2)).max(0);
-            let window_count = column.windows.len() as i32;
-            let window_gaps = if window_count > 1 {
-                gap.saturating_mul(window_count - 1)
-            } else {
-                0
-            };
-            // Clamp window_height to >= 0 to prevent negative dimensions
-            let window_height = if window_count > 0 {
-                ((usable_height - window_gaps).max(0)) / window_count
-            } else {
-                0
-            };
+            let heights = self.window_heights(column, usable_height, gap);
 
             let mut current_y = viewport.y + outer_gap;
 
             for (win_idx, &window_id) in column.windows.iter().enumerate() {
-                // Adjust height for last window to handle rounding
-                // Clamp to >= 0 to prevent negative dimensions
-                let height = if win_idx == column.windows.len() - 1 {
-                    (viewport.y + viewport.height - outer_gap - current_y).max(0)
-                } else {
-                    window_height
-                };
+                let width = self.window_width(window_id, column_width);
+                if let Some(shown) = shown_tab {
+                    placements.push(WindowPlacement {
+                        window_id,
+                        rect: Rect::new(col_screen_x, viewport.y + outer_gap, width, usable_height),
+                        visibility: if win_idx == shown { visibility } else { Visibility::OffScreenLeft },
+                        column_index: col_idx,
+                    });
+                    continue;
+                }
 
+                let height = heights[win_idx];
                 placements.push(WindowPlacement {
                     window_id,
-                    rect: Rect::new(col_screen_x, current_y, column.width, height),
+                    rect: Rect::new(col_screen_x, current_y, width, height),
                     visibility,
                     column_index: col_idx,
                 });
@@ -957,9 +2125,11 @@ impl Workspace {
                 current_y = current_y.saturating_add(height).saturating_add(gap);
             }
 
-            current_x = current_x.saturating_add(column.width).saturating_add(gap);
+            current_x = current_x.saturating_add(self.strip_width(column)).saturating_add(gap);
         }
 
+        self.apply_fitted_layout(&mut placements, viewport);
+
         // Add floating windows (always visible, at their absolute positions)
         for floating in &self.floating_windows {
             placements.push(WindowPlacement {
@@ -973,34 +2143,195 @@ impl Workspace {
         placements
     }
 
+    /// Compute placements for the overview: the whole strip scaled down so
+    /// every column fits in the viewport, centered.
+    ///
+    /// Columns keep their order and relative sizes. The strip is laid out
+    /// unscrolled and without the fullscreen layer, then scaled by the same
+    /// factor in both directions. Floating windows are hidden while the
+    /// overview is shown. Stack-only workspaces have nothing to zoom out of
+    /// and get their normal placements.
+    pub fn compute_overview_placements(&self, viewport: Rect) -> Vec<WindowPlacement> {
+        if self.layout_mode == LayoutMode::StackOnly {
+            return self.compute_placements(viewport);
+        }
+        self.oriented_placements(viewport, Self::compute_strip_overview_placements)
+    }
+
+    /// Compute overview placements as for a horizontal strip.
+    fn compute_strip_overview_placements(&self, viewport: Rect) -> Vec<WindowPlacement> {
+        let mut strip = self.clone();
+        strip.orientation = Orientation::Horizontal;
+        strip.fullscreen = None;
+        strip.active_animation = None;
+        strip.scroll_offset = 0.0;
+        strip.floating_windows.clear();
+        let strip_width = strip.total_width().max(1);
+        let strip_viewport = Rect::new(0, 0, strip_width, viewport.height);
+
+        let scale = (viewport.width as f64 / strip_width as f64).min(OVERVIEW_MAX_SCALE);
+        let left = viewport.x as f64 + (viewport.width as f64 - strip_width as f64 * scale) / 2.0;
+        let top = viewport.y as f64 + viewport.height as f64 * (1.0 - scale) / 2.0;
+        let scale_rect = |rect: Rect| {
+            let x = (left + rect.x as f64 * scale).round() as i32;
+            let y = (top + rect.y as f64 * scale).round() as i32;
+            let right = (left + rect.right() as f64 * scale).round() as i32;
+            let bottom = (top + rect.bottom() as f64 * scale).round() as i32;
+            Rect::new(x, y, right - x, bottom - y)
+        };
+
+        let mut placements: Vec<WindowPlacement> = strip
+            .compute_placements(strip_viewport)
+            .into_iter()
+            .map(|p| WindowPlacement { rect: scale_rect(p.rect), ..p })
+            .collect();
+        placements.extend(self.floating_windows.iter().map(|floating| WindowPlacement {
+            window_id: floating.id,
+            rect: floating.rect,
+            visibility: Visibility::OffScreenLeft,
+            column_index: usize::MAX,
+        }));
+        placements
+    }
+
+    /// Rect of each column's thumbnail in the overview, by column index.
+    pub fn overview_column_rects(&self, viewport: Rect) -> Vec<Rect> {
+        let mut rects: Vec<Option<Rect>> = vec![None; self.columns.len()];
+        for placement in self.compute_overview_placements(viewport) {
+            if let Some(slot) = rects.get_mut(placement.column_index) {
+                *slot = Some(match *slot {
+                    Some(rect) => rect.union(&placement.rect),
+                    None => placement.rect,
+                });
+            }
+        }
+        rects.into_iter().map(|rect| rect.unwrap_or(Rect::new(0, 0, 0, 0))).collect()
+    }
+
+    /// Column whose overview thumbnail contains a screen point.
+    pub fn overview_column_at(&self, viewport: Rect, x: i32, y: i32) -> Option<usize> {
+        self.overview_column_rects(viewport)
+            .iter()
+            .position(|rect| rect.contains_point(x, y))
+    }
+
     /// Resize the focused column by a delta amount.
     pub fn resize_focused_column(&mut self, delta: i32) {
         if let Some(column) = self.columns.get_mut(self.focused_column) {
-            let new_width = column.width.saturating_add(delta).max(MIN_COLUMN_WIDTH);
-            column.width = new_width;
+            column.set_width(column.width.saturating_add(delta));
+        }
+    }
+
+    /// Make the focused window `delta` pixels taller (or shorter, if
+    /// negative) within its column on a viewport of `viewport_height`.
+    ///
+    /// The other windows of the column give up or gain the height in
+    /// proportion to their own, and every window keeps at least
+    /// `MIN_WINDOW_HEIGHT`. The new heights are kept as weights, so they scale
+    /// with the monitor. Returns false if the window isn't stacked with
+    /// others (alone, or in a tabbed column).
+    pub fn resize_focused_window_height(&mut self, delta: i32, viewport_height: i32) -> bool {
+        let Some(column) = self.columns.get(self.focused_column) else {
+            return false;
+        };
+        if column.len() < 2 || column.tabbed {
+            return false;
         }
+        let gap = self.gap.max(0);
+        let usable_height = viewport_height.saturating_sub(self.outer_gap.max(0).saturating_mul(2)).max(0);
+        let heights = self.window_heights(column, usable_height, gap);
+        let focused = self.focused_window_in_column;
+        let total: i64 = heights.iter().map(|&h| i64::from(h.max(1))).sum();
+        let others = column.len() as i64 - 1;
+
+        let old_height = i64::from(heights[focused].max(1));
+        let max_height = (total - others * i64::from(MIN_WINDOW_HEIGHT)).max(i64::from(MIN_WINDOW_HEIGHT));
+        let new_height = (old_height + i64::from(delta)).clamp(i64::from(MIN_WINDOW_HEIGHT), max_height);
+        let (old_rest, new_rest) = (total - old_height, total - new_height);
+
+        let weights: BTreeMap<WindowId, u32> = column
+            .windows
+            .iter()
+            .zip(&heights)
+            .enumerate()
+            .map(|(i, (&id, &height))| {
+                let weight = if i == focused {
+                    new_height
+                } else {
+                    (i64::from(height.max(1)) * new_rest / old_rest.max(1)).max(i64::from(MIN_WINDOW_HEIGHT))
+                };
+                (id, weight as u32)
+            })
+            .collect();
+        self.columns[self.focused_column].height_weights = weights;
+        true
     }
 
     /// Move the focused column left (swap with the column to its left).
+    ///
+    /// With wrap-around navigation, the first column moves to the end.
     pub fn move_column_left(&mut self) {
         if self.focused_column > 0 {
             self.columns.swap(self.focused_column, self.focused_column - 1);
             self.focused_column -= 1;
+            self.reindex();
+        } else if self.wrap_navigation && self.columns.len() > 1 {
+            let last = self.columns.len() - 1;
+            let _ = self.move_column_to_index(0, last);
         }
     }
 
     /// Move the focused column right (swap with the column to its right).
+    ///
+    /// With wrap-around navigation, the last column moves to the start.
     pub fn move_column_right(&mut self) {
         if self.focused_column + 1 < self.columns.len() {
             self.columns.swap(self.focused_column, self.focused_column + 1);
             self.focused_column += 1;
+            self.reindex();
+        } else if self.wrap_navigation && self.columns.len() > 1 {
+            let _ = self.move_column_to_index(self.focused_column, 0);
         }
     }
 
+    /// Move the column at `from` to index `to`, shifting the columns in
+    /// between. Focus stays on the column that had it.
+    ///
+    /// # Errors
+    ///
+    /// Returns `LayoutError::ColumnOutOfBounds` if either index is invalid.
+    pub fn move_column_to_index(&mut self, from: usize, to: usize) -> Result<(), LayoutError> {
+        let max = self.columns.len().saturating_sub(1);
+        if let Some(&index) = [from, to].iter().find(|&&index| index >= self.columns.len()) {
+            return Err(LayoutError::ColumnOutOfBounds(index, max));
+        }
+        if from == to {
+            return Ok(());
+        }
+        let column = self.columns.remove(from);
+        self.columns.insert(to, column);
+
+        let focused = self.focused_column;
+        self.focused_column = if focused == from {
+            to
+        } else if from < focused && to >= focused {
+            focused - 1
+        } else if from > focused && to <= focused {
+            focused + 1
+        } else {
+            focused
+        };
+        self.reindex();
+        Ok(())
+    }
+
     /// Scroll the viewport by a pixel delta.
     ///
-    /// Special float values (NaN, Infinity) are treated as zero for safety.
+    /// Scrolls from the current visual position, cancelling any running
+    /// animation. Special float values (NaN, Infinity) are treated as zero
+    /// for safety.
     pub fn scroll_by(&mut self, delta: f64, viewport_width: i32) {
+        self.cancel_animation();
         // Treat NaN and Infinity as zero for safety
         let safe_delta = if delta.is_finite() { delta } else { 0.0 };
         self.scroll_offset += safe_delta;
@@ -1012,25 +2343,78 @@ impl Workspace {
     // Animation Methods
     // ========================================================================
 
-    /// Check if a scroll animation is currently active.
+    /// Check if a scroll or column animation is currently active.
     pub fn is_animating(&self) -> bool {
-        self.active_animation.is_some()
+        self.active_animation.is_some() || self.column_animation.is_some()
     }
 
-    /// Get the current effective scroll offset.
-    /// Returns the animated offset if an animation is active, otherwise the base offset.
-    pub fn effective_scroll_offset(&self) -> f64 {
-        match &self.active_animation {
-            Some(anim) => anim.current_offset(),
-            None => self.scroll_offset,
+    /// Strip x position each tiled window is shown at, including any column
+    /// animation in progress.
+    ///
+    /// Take this before changing the layout and pass it to
+    /// [`animate_columns_from`](Self::animate_columns_from) afterwards.
+    pub fn strip_positions(&self) -> HashMap<WindowId, f64> {
+        let mut positions = HashMap::new();
+        for (col_idx, column) in self.columns.iter().enumerate() {
+            let x = f64::from(self.column_x(col_idx));
+            for &window_id in &column.windows {
+                positions.insert(window_id, x + self.column_offset(window_id));
+            }
         }
+        positions
     }
 
-    /// Start an animated scroll to a target offset.
-    /// If an animation is already active, it will be cancelled and a new one started.
-    pub fn start_scroll_animation(
-        &mut self,
-        target: f64,
+    /// Slide windows from the strip positions in `before` to where they are
+    /// now, over `duration_ms`.
+    ///
+    /// Windows that weren't in `before` (e.g. a new window) appear in place.
+    /// A duration of 0 moves everything immediately.
+    pub fn animate_columns_from(&mut self, before: &HashMap<WindowId, f64>, duration_ms: u64) {
+        let offsets: HashMap<WindowId, f64> = self
+            .strip_positions_at_rest()
+            .into_iter()
+            .filter_map(|(window_id, x)| {
+                let offset = before.get(&window_id)? - x;
+                (offset.abs() >= 0.5).then_some((window_id, offset))
+            })
+            .collect();
+        self.column_animation = (duration_ms > 0 && !offsets.is_empty()).then(|| ColumnAnimation {
+            offsets,
+            progress: ScrollAnimation::new(1.0, 0.0, duration_ms, Easing::default()),
+        });
+    }
+
+    /// Strip x position of each tiled window once column animations end.
+    fn strip_positions_at_rest(&self) -> Vec<(WindowId, f64)> {
+        self.columns
+            .iter()
+            .enumerate()
+            .flat_map(|(col_idx, column)| {
+                let x = f64::from(self.column_x(col_idx));
+                column.windows.iter().map(move |&window_id| (window_id, x))
+            })
+            .collect()
+    }
+
+    /// Current horizontal offset of a tiled window from its strip position.
+    fn column_offset(&self, window_id: WindowId) -> f64 {
+        self.column_animation.as_ref().map_or(0.0, |anim| anim.offset(window_id))
+    }
+
+    /// Get the current effective scroll offset.
+    /// Returns the animated offset if an animation is active, otherwise the base offset.
+    pub fn effective_scroll_offset(&self) -> f64 {
+        match &self.active_animation {
+            Some(anim) => anim.current_offset(),
+            None => self.scroll_offset,
+        }
+    }
+
+    /// Start an animated scroll to a target offset.
+    /// If an animation is already active, it will be cancelled and a new one started.
+    pub fn start_scroll_animation(
+        &mut self,
+        target: f64,
         viewport_width: i32,
         duration_ms: Option<u64>,
         easing: Option<Easing>,
@@ -1052,14 +2436,42 @@ impl Workspace {
         let duration = duration_ms.unwrap_or(DEFAULT_ANIMATION_DURATION_MS);
         let ease = easing.unwrap_or_default();
 
-        self.active_animation = Some(ScrollAnimation::new(start, clamped_target, duration, ease));
+        self.active_animation = Some(ViewportAnimation::Scroll(ScrollAnimation::new(
+            start,
+            clamped_target,
+            duration,
+            ease,
+        )));
+    }
+
+    /// Pan the viewport by a pixel delta from a continuous gesture.
+    ///
+    /// The viewport follows each delta immediately. Once deltas stop
+    /// arriving, [`tick_animation`](Self::tick_animation) keeps it coasting
+    /// with the gesture's velocity until `friction` brings it to rest.
+    pub fn pan_kinetic(&mut self, delta: f64, viewport_width: i32, friction: f64) {
+        let max_scroll = (self.total_width() - viewport_width).max(0) as f64;
+        if !matches!(self.active_animation, Some(ViewportAnimation::Kinetic(_))) {
+            let start = self.effective_scroll_offset();
+            self.active_animation = Some(ViewportAnimation::Kinetic(KineticAnimation::new(start, max_scroll, friction)));
+        }
+        if let Some(ViewportAnimation::Kinetic(anim)) = &mut self.active_animation {
+            anim.max_offset = max_scroll;
+            anim.push(delta);
+        }
     }
 
     /// Advance the active animation by the given delta time in milliseconds.
     /// Returns true if an animation is still active, false if complete or no animation.
     pub fn tick_animation(&mut self, delta_ms: u64) -> bool {
+        if let Some(anim) = &mut self.column_animation {
+            if !anim.progress.tick(delta_ms) {
+                self.column_animation = None;
+            }
+        }
+
         let Some(anim) = &mut self.active_animation else {
-            return false;
+            return self.column_animation.is_some();
         };
 
         let still_running = anim.tick(delta_ms);
@@ -1068,14 +2480,15 @@ impl Workspace {
             // Animation complete - finalize scroll offset and clear animation
             self.scroll_offset = anim.target();
             self.active_animation = None;
-            false
+            self.column_animation.is_some()
         } else {
             true
         }
     }
 
-    /// Stop the current animation and snap to the target position.
+    /// Stop the current animations and snap to the target position.
     pub fn stop_animation(&mut self) {
+        self.column_animation = None;
         if let Some(anim) = self.active_animation.take() {
             self.scroll_offset = anim.target();
         }
@@ -1090,6 +2503,10 @@ impl Workspace {
 
     /// Ensure the focused column is visible with animation.
     /// Like `ensure_focused_visible` but animates the scroll instead of jumping.
+    ///
+    /// In `JustInView` mode a column that is already in view still pulls the
+    /// viewport back when the strip shrank under it, so the offset stays in
+    /// range (see the `just_in_view_burst` trace of `openniri-sim`).
     pub fn ensure_focused_visible_animated(&mut self, viewport_width: i32) {
         if self.columns.is_empty() {
             return;
@@ -1122,8 +2539,13 @@ impl Workspace {
                     // Column is to the right of viewport, scroll right
                     col_right.saturating_add(outer_gap).saturating_sub(viewport_width) as f64
                 } else {
-                    // Already in view, no scroll needed
-                    return;
+                    // Already in view; only pull back a viewport the strip
+                    // shrank under, e.g. after closing columns on the right
+                    let max_scroll = (self.total_width() - viewport_width).max(0) as f64;
+                    if current <= max_scroll {
+                        return;
+                    }
+                    max_scroll
                 }
             }
         };
@@ -1136,9 +2558,17 @@ impl Workspace {
     /// This is similar to `compute_placements` but uses `effective_scroll_offset()`
     /// to support smooth scrolling animations.
     pub fn compute_placements_animated(&self, viewport: Rect) -> Vec<WindowPlacement> {
-        // Fullscreen mode: one window covers the entire viewport, others are off-screen
-        if let Some(fs_wid) = self.fullscreen_window {
-            return self.compute_fullscreen_placements(fs_wid, viewport);
+        self.oriented_placements(viewport, Self::compute_strip_placements_animated)
+    }
+
+    /// Compute animated placements as for a horizontal strip.
+    fn compute_strip_placements_animated(&self, viewport: Rect) -> Vec<WindowPlacement> {
+        // Fullscreen mode: one window covers its monitor, others are off-screen
+        if let Some(layer) = &self.fullscreen {
+            return self.compute_fullscreen_placements(layer);
+        }
+        if self.layout_mode == LayoutMode::StackOnly {
+            return self.compute_stack_placements(viewport);
         }
 
         let mut placements = Vec::new();
@@ -1156,13 +2586,13 @@ impl Workspace {
         for (col_idx, column) in self.columns.iter().enumerate() {
             // Calculate column position in strip coordinates
             let col_strip_x = current_x;
-            let col_strip_right = col_strip_x.saturating_add(column.width);
+            let col_strip_right = col_strip_x.saturating_add(self.strip_width(column));
 
             // Transform to screen coordinates (relative to viewport)
             let col_screen_x = col_strip_x.saturating_sub(viewport_left).saturating_add(viewport.x);
 
-            // Determine visibility
-            let visibility = if col_strip_right <= viewport_left {
+            // Determine visibility (collapsed columns hide their windows)
+            let visibility = if column.collapsed || col_strip_right <= viewport_left {
                 Visibility::OffScreenLeft
             } else if col_strip_x >= viewport_right {
                 Visibility::OffScreenRight
@@ -1170,39 +2600,47 @@ impl Workspace {
                 Visibility::Visible
             };
 
-            // Calculate window heights (equal split for stacked windows)
+            // Tabbed columns show one window at full height and hide the rest
+            let shown_tab = self.shown_tab(col_idx);
+            let column_width = self.layout_width(column);
+
+            // Calculate window heights (equal split for stacked windows, within
+            // each window's size limits)
             let usable_height = viewport.height.saturating_sub(outer_gap.saturating_mul(2)).max(0);
-            let window_count = column.windows.len() as i32;
-            let window_gaps = if window_count > 1 {
-                gap.saturating_mul(window_count - 1)
-            } else {
-                0
-            };
-            let window_height = if window_count > 0 {
-                ((usable_height - window_gaps).max(0)) / window_count
-            } else {
-                0
-            };
+            let heights = self.window_heights(column, usable_height, gap);
 
             let mut window_y = viewport.y + outer_gap;
 
             for (win_idx, &window_id) in column.windows.iter().enumerate() {
+                let width = self.window_width(window_id, column_width);
+                // Columns sliding after a window opened or closed
+                let col_screen_x = col_screen_x.saturating_add(self.column_offset(window_id).round() as i32);
+                if let Some(shown) = shown_tab {
+                    placements.push(WindowPlacement {
+                        window_id,
+                        rect: Rect::new(col_screen_x, viewport.y + outer_gap, width, usable_height),
+                        visibility: if win_idx == shown { visibility } else { Visibility::OffScreenLeft },
+                        column_index: col_idx,
+                    });
+                    continue;
+                }
+
+                let height = heights[win_idx];
                 placements.push(WindowPlacement {
                     window_id,
-                    rect: Rect::new(col_screen_x, window_y, column.width, window_height),
+                    rect: Rect::new(col_screen_x, window_y, width, height),
                     visibility,
                     column_index: col_idx,
                 });
 
-                window_y = window_y.saturating_add(window_height);
-                if win_idx < column.windows.len() - 1 {
-                    window_y = window_y.saturating_add(gap);
-                }
+                window_y = window_y.saturating_add(height).saturating_add(gap);
             }
 
-            current_x = current_x.saturating_add(column.width).saturating_add(gap);
+            current_x = current_x.saturating_add(self.strip_width(column)).saturating_add(gap);
         }
 
+        self.apply_fitted_layout(&mut placements, viewport);
+
         // Add floating windows (always visible, at their absolute positions)
         for floating in &self.floating_windows {
             placements.push(WindowPlacement {
@@ -1216,9 +2654,59 @@ impl Workspace {
         placements
     }
 
+    /// Get a tiled or floating window's visibility at the current (animated)
+    /// scroll position, or `None` if the window is not in this workspace.
+    pub fn window_visibility(&self, window_id: WindowId, viewport: Rect) -> Option<Visibility> {
+        self.compute_placements_animated(viewport)
+            .into_iter()
+            .find(|p| p.window_id == window_id)
+            .map(|p| p.visibility)
+    }
+
+    /// Compute placements for a stack-only workspace.
+    /// Every tiled window gets the full usable viewport width, stacked vertically.
+    fn compute_stack_placements(&self, viewport: Rect) -> Vec<WindowPlacement> {
+        let gap = self.gap.max(0);
+        let outer_gap = self.outer_gap.max(0);
+
+        let x = viewport.x.saturating_add(outer_gap);
+        let width = viewport.width.saturating_sub(outer_gap.saturating_mul(2)).max(0);
+
+        // Weighted heights within each window's size limits, as in a column
+        let windows: Vec<WindowId> = self.columns.iter().flat_map(|c| c.windows.iter().copied()).collect();
+        let limits: Vec<_> = self.columns.iter().flat_map(|c| self.height_limits(c)).collect();
+        let usable_height = viewport.height.saturating_sub(outer_gap.saturating_mul(2)).max(0);
+        let heights = self.stacked_heights(&limits, usable_height, gap);
+
+        let mut placements = Vec::with_capacity(windows.len() + self.floating_windows.len());
+        let mut y = viewport.y.saturating_add(outer_gap);
+        for (&window_id, &height) in windows.iter().zip(&heights) {
+            placements.push(WindowPlacement {
+                window_id,
+                rect: Rect::new(x, y, self.window_width(window_id, width), height),
+                visibility: Visibility::Visible,
+                column_index: 0,
+            });
+            y = y.saturating_add(height).saturating_add(gap);
+        }
+
+        for floating in &self.floating_windows {
+            placements.push(WindowPlacement {
+                window_id: floating.id,
+                rect: floating.rect,
+                visibility: Visibility::Visible,
+                column_index: usize::MAX,
+            });
+        }
+
+        placements
+    }
+
     /// Compute placements when a window is fullscreen.
-    /// The fullscreen window gets the full viewport; all others are marked off-screen.
-    fn compute_fullscreen_placements(&self, fs_wid: WindowId, viewport: Rect) -> Vec<WindowPlacement> {
+    /// The fullscreen window covers the whole monitor; all others are marked off-screen.
+    fn compute_fullscreen_placements(&self, layer: &FullscreenLayer) -> Vec<WindowPlacement> {
+        let fs_wid = layer.window_id;
+        let viewport = layer.rect;
         let mut placements = Vec::new();
 
         for (col_idx, column) in self.columns.iter().enumerate() {
@@ -1269,34 +2757,70 @@ impl Workspace {
 
     /// Check if a window is currently fullscreen.
     pub fn is_fullscreen(&self) -> bool {
-        self.fullscreen_window.is_some()
+        self.fullscreen.is_some()
     }
 
     /// Get the fullscreen window ID, if any.
     pub fn fullscreen_window_id(&self) -> Option<WindowId> {
-        self.fullscreen_window
+        self.fullscreen.as_ref().map(|f| f.window_id)
     }
 
     /// Toggle fullscreen mode for the focused window.
+    ///
+    /// Entering shows the focused window over `monitor` (the full monitor
+    /// rect, not the work area) and hides everything else on the workspace.
     /// Returns true if entering fullscreen, false if exiting.
-    pub fn toggle_fullscreen(&mut self) -> bool {
-        if self.fullscreen_window.is_some() {
-            self.fullscreen_window = None;
+    pub fn toggle_fullscreen(&mut self, monitor: Rect) -> bool {
+        if self.fullscreen.is_some() {
+            self.exit_fullscreen();
             false
-        } else if let Some(wid) = self.focused_window() {
-            self.fullscreen_window = Some(wid);
+        } else if let Some(window_id) = self.focused_window() {
+            self.fullscreen = Some(FullscreenLayer {
+                window_id,
+                rect: monitor,
+                column_width: self.columns[self.focused_column].width,
+                scroll_offset: self.scroll_offset,
+            });
             true
         } else {
             false
         }
     }
 
+    /// Leave fullscreen, restoring the window's column width, focus and the
+    /// strip's scroll position from when fullscreen was entered.
+    ///
+    /// The restored scroll offset is clamped to the strip as it is now, using
+    /// the fullscreen monitor's width as the viewport, since columns may have
+    /// closed in the meantime.
+    pub fn exit_fullscreen(&mut self) {
+        let Some(layer) = self.fullscreen.take() else {
+            return;
+        };
+        if let Some((col_idx, _)) = self.find_window_location(layer.window_id) {
+            self.columns[col_idx].width = layer.column_width;
+            let _ = self.focus_window(layer.window_id);
+        }
+        self.active_animation = None;
+        let max_scroll = (self.total_width() - layer.rect.width).max(0);
+        self.scroll_offset = layer.scroll_offset.clamp(0.0, f64::from(max_scroll));
+    }
+
+    /// Update the monitor rect covered by the fullscreen window, e.g. after a
+    /// resolution change.
+    pub fn set_fullscreen_rect(&mut self, monitor: Rect) {
+        if let Some(layer) = &mut self.fullscreen {
+            layer.rect = monitor;
+        }
+    }
+
     // ========================================================================
     // Toggle Floating
     // ========================================================================
 
     /// Toggle floating state for the focused window.
-    /// If the focused window is tiled, move it to floating with a centered rect.
+    /// If the focused window is tiled, move it to floating at the rect it had
+    /// when it was last floating (if still on the viewport), or a centered rect.
     /// If the focused window is floating, this is a no-op (floating windows are not focused via column focus).
     /// Returns the window ID that was toggled, if any.
     pub fn toggle_floating(&mut self, viewport: Rect) -> Option<WindowId> {
@@ -1306,20 +2830,31 @@ impl Workspace {
         // Remove from columns
         let _ = self.remove_window(wid);
 
-        // Center a floating window of 800x600 or clamped to viewport
-        let float_w = 800.min(viewport.width - 40);
-        let float_h = 600.min(viewport.height - 40);
-        let float_x = viewport.x + (viewport.width - float_w) / 2;
-        let float_y = viewport.y + (viewport.height - float_h) / 2;
-        let rect = Rect::new(float_x, float_y, float_w, float_h);
+        let rect = self
+            .floating_restore_rects
+            .remove(&wid)
+            .filter(|rect| rect.intersects(&viewport))
+            .unwrap_or_else(|| {
+                // Center a floating window of 800x600 or clamped to viewport
+                let float_w = 800.min(viewport.width - 40);
+                let float_h = 600.min(viewport.height - 40);
+                let float_x = viewport.x + (viewport.width - float_w) / 2;
+                let float_y = viewport.y + (viewport.height - float_h) / 2;
+                Rect::new(float_x, float_y, float_w, float_h)
+            });
 
         let _ = self.add_floating(wid, rect);
         Some(wid)
     }
 
     /// Move a floating window back to the tiling layout.
+    ///
+    /// Its floating rect is remembered for the next [`toggle_floating`](Self::toggle_floating).
     /// Returns true if the window was unfloated.
     pub fn unfloat_window(&mut self, window_id: WindowId) -> bool {
+        if let Some(rect) = self.floating_windows.iter().find(|f| f.id == window_id).map(|f| f.rect) {
+            self.floating_restore_rects.insert(window_id, rect);
+        }
         if self.remove_floating(window_id) {
             // Insert as a new column
             let _ = self.insert_window(window_id, None);
@@ -1329,23 +2864,183 @@ impl Workspace {
         }
     }
 
+    /// Floating rect a tiled window returns to when floated again.
+    pub fn floating_restore_rect(&self, window_id: WindowId) -> Option<Rect> {
+        self.floating_restore_rects.get(&window_id).copied()
+    }
+
+    /// All remembered floating rects.
+    pub fn floating_restore_rects(&self) -> impl Iterator<Item = (WindowId, Rect)> + '_ {
+        self.floating_restore_rects.iter().map(|(&id, &rect)| (id, rect))
+    }
+
+    /// Remember the floating rect of a window (e.g. restored from a snapshot).
+    pub fn set_floating_restore_rect(&mut self, window_id: WindowId, rect: Rect) {
+        self.floating_restore_rects.insert(window_id, rect);
+    }
+
+    /// Forget the remembered floating rect of a window that went away.
+    pub fn forget_floating_rect(&mut self, window_id: WindowId) {
+        self.floating_restore_rects.remove(&window_id);
+    }
+
+    // ========================================================================
+    // Minimized Windows
+    // ========================================================================
+
+    /// Take a minimized tiled window out of the strip so the gap closes.
+    ///
+    /// Focus moves as if the window was removed. The window still belongs to
+    /// the workspace and returns to its column with
+    /// [`unpark_window`](Self::unpark_window).
+    ///
+    /// # Errors
+    ///
+    /// Returns `LayoutError::WindowNotFound` if the window is not tiled in this workspace.
+    pub fn park_window(&mut self, window_id: WindowId) -> Result<(), LayoutError> {
+        let (col_idx, win_idx) = self
+            .find_window_location(window_id)
+            .ok_or(LayoutError::WindowNotFound(window_id))?;
+        let column = &self.columns[col_idx];
+        let parked = ParkedWindow {
+            id: window_id,
+            column_index: col_idx,
+            stack_index: (column.len() > 1).then_some(win_idx),
+            width: column.width,
+        };
+        self.remove_window(window_id)?;
+        self.parked_windows.push(parked);
+        Ok(())
+    }
+
+    /// Put a parked window back at its original column index and focus it.
+    ///
+    /// A window that shared a column rejoins the column now at that index;
+    /// a window that was alone gets a new column with its old width. Indices
+    /// past the end are clamped. Returns false if the window isn't parked.
+    pub fn unpark_window(&mut self, window_id: WindowId) -> bool {
+        let Some(pos) = self.parked_windows.iter().position(|p| p.id == window_id) else {
+            return false;
+        };
+        let parked = self.parked_windows.remove(pos);
+        self.remember_active_tab();
+
+        match parked.stack_index {
+            Some(stack_index) if parked.column_index < self.columns.len() => {
+                let column = &mut self.columns[parked.column_index];
+                let at = stack_index.min(column.len());
+                column.windows.insert(at, window_id);
+                column.weigh_new_window(window_id);
+                self.focused_column = parked.column_index;
+                self.focused_window_in_column = at;
+            }
+            _ if self.layout_mode == LayoutMode::StackOnly && !self.columns.is_empty() => {
+                self.columns[0].add_window(window_id);
+                self.focused_column = 0;
+                self.focused_window_in_column = self.columns[0].len() - 1;
+            }
+            _ => {
+                let at = parked.column_index.min(self.columns.len());
+                self.columns.insert(at, Column::new(window_id, parked.width));
+                self.focused_column = at;
+                self.focused_window_in_column = 0;
+            }
+        }
+        self.reindex();
+        true
+    }
+
+    /// Check if a window is parked while minimized.
+    pub fn is_parked(&self, window_id: WindowId) -> bool {
+        self.parked_windows.iter().any(|p| p.id == window_id)
+    }
+
+    /// IDs of windows parked while minimized, in the order they were minimized.
+    pub fn parked_window_ids(&self) -> impl Iterator<Item = WindowId> + '_ {
+        self.parked_windows.iter().map(|p| p.id)
+    }
+
     // ========================================================================
     // Column Width Presets
     // ========================================================================
 
+    /// Width available to columns: the viewport minus outer gaps.
+    fn usable_width(&self, viewport_width: i32) -> i32 {
+        viewport_width.saturating_sub(self.outer_gap.max(0) * 2)
+    }
+
+    /// Express a column width as a fraction of the usable viewport width.
+    ///
+    /// Returns 0.0 for a viewport with no usable width.
+    pub fn width_fraction(&self, width: i32, viewport_width: i32) -> f64 {
+        let usable_width = self.usable_width(viewport_width);
+        if usable_width <= 0 {
+            return 0.0;
+        }
+        width as f64 / usable_width as f64
+    }
+
+    /// Column width in pixels for a fraction of the usable viewport width.
+    ///
+    /// Unlike the width presets, the fraction is not clamped.
+    pub fn width_from_fraction(&self, fraction: f64, viewport_width: i32) -> i32 {
+        (self.usable_width(viewport_width) as f64 * fraction).round() as i32
+    }
+
     /// Set the focused column's width as a fraction of the viewport width.
     /// Fraction should be between 0.1 and 1.0.
     pub fn set_focused_column_width_fraction(&mut self, fraction: f64, viewport_width: i32) {
-        let fraction = fraction.clamp(0.1, 1.0);
-        let outer_gap = self.outer_gap.max(0);
-        let usable_width = viewport_width.saturating_sub(outer_gap * 2);
-        let new_width = (usable_width as f64 * fraction).round() as i32;
+        let new_width = self.width_from_fraction(fraction.clamp(0.1, 1.0), viewport_width);
 
         if let Some(column) = self.columns.get_mut(self.focused_column) {
             column.set_width(new_width);
         }
     }
 
+    /// Set the width of the column containing a window as a fraction of the viewport width.
+    ///
+    /// # Errors
+    ///
+    /// Returns `LayoutError::WindowNotFound` if the window is not tiled in this workspace.
+    pub fn set_window_column_width_fraction(
+        &mut self,
+        window_id: WindowId,
+        fraction: f64,
+        viewport_width: i32,
+    ) -> Result<(), LayoutError> {
+        let (col_idx, _) = self
+            .find_window_location(window_id)
+            .ok_or(LayoutError::WindowNotFound(window_id))?;
+        let new_width = self.width_from_fraction(fraction.clamp(0.1, 1.0), viewport_width);
+        self.columns[col_idx].set_width(new_width);
+        Ok(())
+    }
+
+    /// Pick the preset that follows a column width.
+    ///
+    /// Presets are fractions of the viewport width in ascending order. The
+    /// first preset wider than `width` is chosen, wrapping around to the
+    /// first preset after the widest. Widths within a pixel of a preset count
+    /// as that preset. Returns None if there are no presets.
+    pub fn next_preset_fraction(&self, presets: &[f64], width: i32, viewport_width: i32) -> Option<f64> {
+        presets
+            .iter()
+            .copied()
+            .find(|&fraction| self.width_from_fraction(fraction.clamp(0.1, 1.0), viewport_width) > width + 1)
+            .or_else(|| presets.first().copied())
+    }
+
+    /// Snap the focused column to the next width preset.
+    ///
+    /// See [`next_preset_fraction`](Self::next_preset_fraction). Returns the
+    /// applied fraction, or None if the workspace is empty or there are no presets.
+    pub fn cycle_focused_column_width(&mut self, presets: &[f64], viewport_width: i32) -> Option<f64> {
+        let width = self.columns.get(self.focused_column)?.width;
+        let fraction = self.next_preset_fraction(presets, width, viewport_width)?;
+        self.set_focused_column_width_fraction(fraction, viewport_width);
+        Some(fraction)
+    }
+
     /// Equalize all column widths to share the viewport equally.
     pub fn equalize_column_widths(&mut self, viewport_width: i32) {
         if self.columns.is_empty() {
@@ -1354,1699 +3049,4989 @@ impl Workspace {
 
         let outer_gap = self.outer_gap.max(0);
         let gap = self.gap.max(0);
-        let n = self.columns.len() as i32;
-        let total_gaps = gap * (n - 1) + outer_gap * 2;
-        let per_column = ((viewport_width - total_gaps).max(MIN_COLUMN_WIDTH * n)) / n;
+        let total_gaps = gap * (self.columns.len() as i32 - 1) + outer_gap * 2;
+
+        // Collapsed columns keep their placeholder width; the rest share the remainder
+        let collapsed = self.columns.iter().filter(|c| c.collapsed).count() as i32;
+        let n = self.columns.len() as i32 - collapsed;
+        if n == 0 {
+            return;
+        }
+        let reserved = total_gaps + collapsed * self.collapsed_column_width;
+        let per_column = ((viewport_width - reserved).max(MIN_COLUMN_WIDTH * n)) / n;
 
-        for col in &mut self.columns {
+        for col in self.columns.iter_mut().filter(|c| !c.collapsed) {
             col.set_width(per_column);
         }
     }
-}
 
-// Test-only helper methods for direct state manipulation
-#[cfg(test)]
-impl Workspace {
-    /// Set focus state directly without validation (test helper).
-    pub fn test_set_focus_unchecked(&mut self, column: usize, win: usize) {
-        self.focused_column = column;
-        self.focused_window_in_column = win;
-    }
+    // ========================================================================
+    // Column Collapsing
+    // ========================================================================
 
-    /// Set scroll offset directly (test helper).
-    pub fn test_set_scroll_offset(&mut self, offset: f64) {
-        self.scroll_offset = offset;
+    /// Get the strip width used by collapsed columns.
+    pub fn collapsed_column_width(&self) -> i32 {
+        self.collapsed_column_width
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
-    #[test]
-    fn test_create_empty_workspace() {
-        let ws = Workspace::new();
-        assert!(ws.is_empty());
-        assert_eq!(ws.column_count(), 0);
-        assert_eq!(ws.total_width(), 0);
+    /// Set the strip width used by collapsed columns.
+    /// Width is clamped to MIN_COLLAPSED_COLUMN_WIDTH (8px) minimum.
+    pub fn set_collapsed_column_width(&mut self, width: i32) {
+        self.collapsed_column_width = width.max(MIN_COLLAPSED_COLUMN_WIDTH);
     }
 
-    #[test]
-    fn test_insert_window() {
-        let mut ws = Workspace::new();
-        ws.insert_window(1, Some(400)).unwrap();
-
-        assert!(!ws.is_empty());
-        assert_eq!(ws.column_count(), 1);
-        assert_eq!(ws.focused_column_index(), 0);
-        assert_eq!(ws.focused_window(), Some(1));
+    /// Collapse the focused column to a thin placeholder.
+    ///
+    /// The column keeps its width and windows; its windows are hidden until it
+    /// is expanded again. Returns false if there is no focused column, it is
+    /// already collapsed, or the workspace is stack-only.
+    pub fn collapse_focused_column(&mut self) -> bool {
+        if self.layout_mode == LayoutMode::StackOnly {
+            return false;
+        }
+        match self.columns.get_mut(self.focused_column) {
+            Some(column) if !column.collapsed => {
+                column.collapsed = true;
+                true
+            }
+            _ => false,
+        }
     }
 
-    #[test]
-    fn test_insert_multiple_windows() {
-        let mut ws = Workspace::with_gaps(10, 10);
-        ws.insert_window(1, Some(400)).unwrap();
-        ws.insert_window(2, Some(600)).unwrap();
-        ws.insert_window(3, Some(400)).unwrap();
+    /// Expand the focused column back to its previous width.
+    ///
+    /// Returns false if there is no focused column or it is not collapsed.
+    pub fn expand_focused_column(&mut self) -> bool {
+        match self.columns.get_mut(self.focused_column) {
+            Some(column) if column.collapsed => {
+                column.collapsed = false;
+                true
+            }
+            _ => false,
+        }
+    }
 
-        assert_eq!(ws.column_count(), 3);
-        // Last inserted window should be focused
-        assert_eq!(ws.focused_column_index(), 2);
-        assert_eq!(ws.focused_window(), Some(3));
+    /// Toggle maximizing the focused column to the viewport width.
+    ///
+    /// A maximized column spans the viewport between the outer gaps and stays
+    /// tiled; toggling again restores the width it had before. Returns the new
+    /// state, or None if there is no focused column.
+    pub fn toggle_maximize_focused_column(&mut self, viewport_width: i32) -> Option<bool> {
+        let usable_width = self.usable_width(viewport_width);
+        let column = self.columns.get_mut(self.focused_column)?;
+        match column.restore_width.take() {
+            Some(width) => {
+                column.width = width;
+                Some(false)
+            }
+            None => {
+                column.restore_width = Some(column.width);
+                column.width = usable_width.max(MIN_COLUMN_WIDTH);
+                Some(true)
+            }
+        }
+    }
 
-        // Total width: outer_gap + 400 + gap + 600 + gap + 400 + outer_gap
-        // = 10 + 400 + 10 + 600 + 10 + 400 + 10 = 1440
-        assert_eq!(ws.total_width(), 1440);
+    /// Toggle tabbed mode for the focused column.
+    ///
+    /// In tabbed mode only the focused window of the column (or, while the
+    /// column is not focused, the window focused last) is shown, at full
+    /// height; focus up/down switches tabs. Returns the new state, or None if
+    /// there is no focused column.
+    pub fn toggle_focused_column_tabbed(&mut self) -> Option<bool> {
+        let focused_window = self.focused_window_in_column;
+        let column = self.columns.get_mut(self.focused_column)?;
+        column.tabbed = !column.tabbed;
+        column.active_tab = focused_window;
+        Some(column.tabbed)
+    }
+
+    /// Index of the window a tabbed column shows, or None if all of its
+    /// windows are shown (not tabbed, or a single window).
+    fn shown_tab(&self, col_idx: usize) -> Option<usize> {
+        let column = self.columns.get(col_idx)?;
+        if !column.tabbed || column.windows.len() < 2 {
+            return None;
+        }
+        if col_idx == self.focused_column {
+            Some(self.focused_window_in_column.min(column.windows.len() - 1))
+        } else {
+            Some(column.active_tab())
+        }
     }
 
-    #[test]
-    fn test_focus_navigation() {
-        let mut ws = Workspace::new();
-        ws.insert_window(1, Some(400)).unwrap();
-        ws.insert_window(2, Some(400)).unwrap();
-        ws.insert_window(3, Some(400)).unwrap();
+    /// Record the focused window as the active tab of the focused column,
+    /// before focus moves to another column.
+    fn remember_active_tab(&mut self) {
+        let focused_window = self.focused_window_in_column;
+        if let Some(column) = self.columns.get_mut(self.focused_column) {
+            column.active_tab = focused_window;
+        }
+    }
 
-        assert_eq!(ws.focused_column_index(), 2); // Last inserted
+    /// Focus the active tab after focus moved into a tabbed column.
+    fn enter_active_tab(&mut self) {
+        if let Some(column) = self.columns.get(self.focused_column).filter(|c| c.tabbed) {
+            self.focused_window_in_column = column.active_tab();
+        }
+    }
 
-        ws.focus_left();
-        assert_eq!(ws.focused_column_index(), 1);
-        assert_eq!(ws.focused_window(), Some(2));
+    /// Expand the column containing a window, if it is collapsed.
+    ///
+    /// Used when a hidden window gains focus from outside the layout (e.g. Alt-Tab).
+    pub fn expand_column_of(&mut self, window_id: WindowId) -> bool {
+        match self.columns.iter_mut().find(|c| c.contains(window_id)) {
+            Some(column) if column.collapsed => {
+                column.collapsed = false;
+                true
+            }
+            _ => false,
+        }
+    }
 
-        ws.focus_left();
-        assert_eq!(ws.focused_column_index(), 0);
+    /// Get the screen rectangles of collapsed column placeholders that are in view.
+    ///
+    /// Returns `(column_index, rect)` pairs, using the animated scroll offset.
+    pub fn collapsed_column_rects(&self, viewport: Rect) -> Vec<(usize, Rect)> {
+        if self.fullscreen.is_some() {
+            return Vec::new();
+        }
+        if self.is_transposed() {
+            return self
+                .collapsed_strip_rects(viewport.transposed())
+                .into_iter()
+                .map(|(i, rect)| (i, rect.transposed()))
+                .collect();
+        }
+        self.collapsed_strip_rects(viewport)
+    }
 
-        // Should not go below 0
-        ws.focus_left();
-        assert_eq!(ws.focused_column_index(), 0);
+    /// Collapsed column placeholder rects as for a horizontal strip.
+    fn collapsed_strip_rects(&self, viewport: Rect) -> Vec<(usize, Rect)> {
 
-        ws.focus_right();
-        ws.focus_right();
-        assert_eq!(ws.focused_column_index(), 2);
+        let outer_gap = self.outer_gap.max(0);
+        let viewport_left = self.effective_scroll_offset().round() as i32;
+        let height = viewport.height.saturating_sub(outer_gap.saturating_mul(2)).max(0);
+        let fitted = self.fitted_layout(viewport.width);
 
-        // Should not go beyond last column
-        ws.focus_right();
-        assert_eq!(ws.focused_column_index(), 2);
+        self.columns
+            .iter()
+            .enumerate()
+            .filter(|(_, column)| column.collapsed)
+            .map(|(i, _)| {
+                let x = match &fitted {
+                    Some(layout) => layout[i].0.saturating_add(viewport.x),
+                    None => self.column_x(i).saturating_sub(viewport_left).saturating_add(viewport.x),
+                };
+                (i, Rect::new(x, viewport.y + outer_gap, self.collapsed_column_width, height))
+            })
+            .filter(|(_, rect)| rect.intersects(&viewport))
+            .collect()
     }
 
+    /// Get the screen rectangles to paint behind tiled windows: the slot of
+    /// every expanded column in view, and the parts of the viewport past
+    /// either end of the strip.
+    ///
+    /// Uses the animated scroll offset. Empty in fullscreen, in stack-only
+    /// mode, and without columns.
+    pub fn column_background_rects(&self, viewport: Rect) -> Vec<Rect> {
+        if self.fullscreen.is_some() || self.layout_mode == LayoutMode::StackOnly || self.columns.is_empty() {
+            return Vec::new();
+        }
+        if self.is_transposed() {
+            return self
+                .strip_background_rects(viewport.transposed())
+                .into_iter()
+                .map(|rect| rect.transposed())
+                .collect();
+        }
+        self.strip_background_rects(viewport)
+    }
+
+    /// Column background rects as for a horizontal strip.
+    fn strip_background_rects(&self, viewport: Rect) -> Vec<Rect> {
+        let outer_gap = self.outer_gap.max(0);
+        let viewport_left = self.effective_scroll_offset().round() as i32;
+        let y = viewport.y + outer_gap;
+        let height = viewport.height.saturating_sub(outer_gap.saturating_mul(2)).max(0);
+        let fitted = self.fitted_layout(viewport.width);
+
+        let slots: Vec<(i32, i32)> = self
+            .columns
+            .iter()
+            .enumerate()
+            .map(|(i, column)| match &fitted {
+                Some(layout) => (layout[i].0.saturating_add(viewport.x), layout[i].1),
+                None => (
+                    self.column_x(i).saturating_sub(viewport_left).saturating_add(viewport.x),
+                    self.strip_width(column),
+                ),
+            })
+            .collect();
+
+        let mut rects: Vec<Rect> = slots
+            .iter()
+            .zip(&self.columns)
+            .filter(|(_, column)| !column.collapsed)
+            .map(|(&(x, width), _)| Rect::new(x, y, width, height))
+            .collect();
+
+        // Space the strip doesn't reach, beyond its outer gaps
+        let strip_left = slots[0].0.saturating_sub(outer_gap);
+        let (last_x, last_width) = slots[slots.len() - 1];
+        let strip_right = last_x.saturating_add(last_width).saturating_add(outer_gap);
+        if strip_left > viewport.x {
+            rects.push(Rect::new(viewport.x, y, strip_left - viewport.x, height));
+        }
+        if strip_right < viewport.right() {
+            rects.push(Rect::new(strip_right, y, viewport.right() - strip_right, height));
+        }
+
+        rects.into_iter().filter_map(|rect| rect.intersection(&viewport)).filter(|r| r.width > 0).collect()
+    }
+
+    // ========================================================================
+    // Shrink-to-Fit
+    // ========================================================================
+
+    /// Get the shrink-to-fit threshold (fraction of the viewport width).
+    pub fn shrink_to_fit_threshold(&self) -> f64 {
+        self.shrink_to_fit_threshold
+    }
+
+    /// Set the shrink-to-fit threshold.
+    ///
+    /// When the strip is wider than the viewport by at most this fraction of
+    /// the viewport width, columns are shrunk proportionally at placement time
+    /// so everything fits without scrolling. Stored column widths are not
+    /// changed. The value is clamped to `0.0..=MAX_SHRINK_TO_FIT_THRESHOLD`;
+    /// 0.0 disables shrinking.
+    pub fn set_shrink_to_fit_threshold(&mut self, threshold: f64) {
+        self.shrink_to_fit_threshold = if threshold.is_finite() {
+            threshold.clamp(0.0, MAX_SHRINK_TO_FIT_THRESHOLD)
+        } else {
+            0.0
+        };
+    }
+
+    /// Whether the most recently applied layout shrank the strip to fit.
+    pub fn is_shrunk_to_fit(&self) -> bool {
+        self.shrink_to_fit_threshold > 0.0 && self.shrink_to_fit_active
+    }
+
+    /// Record whether the strip is shrunk to fit `viewport_width`, for the
+    /// hysteresis of later placements.
+    ///
+    /// Call this when placements are applied; computing placements only
+    /// reads the state, so queries don't move the hysteresis band.
+    pub fn update_shrink_to_fit(&mut self, viewport_width: i32) {
+        self.shrink_to_fit_active = self.shrink_to_fit_applies(viewport_width);
+    }
+
+    /// Whether the strip's overflow is within the shrink-to-fit limit.
+    ///
+    /// Shrinking starts once the overflow drops to the threshold and only
+    /// stops once it exceeds the threshold plus `SHRINK_TO_FIT_HYSTERESIS`.
+    fn shrink_to_fit_applies(&self, viewport_width: i32) -> bool {
+        if self.shrink_to_fit_threshold <= 0.0 || self.columns.is_empty() || viewport_width <= 0 {
+            return false;
+        }
+        let overflow = self.total_width().saturating_sub(viewport_width);
+        let limit = if self.shrink_to_fit_active {
+            self.shrink_to_fit_threshold + SHRINK_TO_FIT_HYSTERESIS
+        } else {
+            self.shrink_to_fit_threshold
+        };
+        overflow as f64 / viewport_width as f64 <= limit
+    }
+
+    /// Compute fitted `(x, width)` pairs for every column, relative to the
+    /// viewport's left edge, or `None` if the strip should scroll normally.
+    fn shrink_to_fit_layout(&self, viewport_width: i32) -> Option<Vec<(i32, i32)>> {
+        let overflow = self.total_width().saturating_sub(viewport_width);
+        // Nothing to shrink if the strip already fits
+        if overflow <= 0 || !self.shrink_to_fit_applies(viewport_width) {
+            return None;
+        }
+
+        let gap = self.gap.max(0);
+        let outer_gap = self.outer_gap.max(0);
+        let expanded: i32 = self.columns.iter()
+            .filter(|c| !c.collapsed)
+            .map(|c| self.layout_width(c))
+            .fold(0i32, |acc, w| acc.saturating_add(w));
+        if expanded == 0 {
+            return None;
+        }
+        let available = expanded - overflow;
+        let scale = available as f64 / expanded as f64;
+
+        // Give rounding leftovers to the last expanded column so the strip fits exactly
+        let last_expanded = self.columns.iter().rposition(|c| !c.collapsed);
+        let mut remaining = available;
+        let mut x = outer_gap;
+        let mut layout = Vec::with_capacity(self.columns.len());
+        for (i, column) in self.columns.iter().enumerate() {
+            let width = if column.collapsed {
+                self.collapsed_column_width
+            } else if Some(i) == last_expanded {
+                remaining
+            } else {
+                let w = (self.layout_width(column) as f64 * scale).floor() as i32;
+                remaining -= w;
+                w
+            };
+            if !column.collapsed && width < MIN_COLUMN_WIDTH {
+                return None;
+            }
+            layout.push((x, width));
+            x = x.saturating_add(width).saturating_add(gap);
+        }
+
+        Some(layout)
+    }
+
+    // ========================================================================
+    // Auto-Balance and Maximum Column Width
+    // ========================================================================
+
+    /// Whether columns stretch to fill the viewport.
+    pub fn auto_balance(&self) -> bool {
+        self.auto_balance
+    }
+
+    /// Set whether columns stretch to fill the viewport.
+    ///
+    /// When the strip is narrower than the viewport, expanded columns are
+    /// widened proportionally at placement time so there is no empty space at
+    /// the right, none past the maximum column width. Stored column widths
+    /// are not changed.
+    pub fn set_auto_balance(&mut self, enabled: bool) {
+        self.auto_balance = enabled;
+    }
+
+    /// Whether focus and column moves wrap around the ends of the strip.
+    pub fn wrap_navigation(&self) -> bool {
+        self.wrap_navigation
+    }
+
+    /// Set whether focus and column moves wrap around the ends of the strip.
+    pub fn set_wrap_navigation(&mut self, enabled: bool) {
+        self.wrap_navigation = enabled;
+    }
+
+    /// Where newly opened windows are inserted, see [`insert_window_at`](Self::insert_window_at).
+    pub fn insert_position(&self) -> InsertPosition {
+        self.insert_position
+    }
+
+    /// Set where new windows are inserted.
+    pub fn set_insert_position(&mut self, position: InsertPosition) {
+        self.insert_position = position;
+    }
+
+    /// Get the maximum column width.
+    pub fn max_column_width(&self) -> i32 {
+        self.max_column_width
+    }
+
+    /// Set the widest a column is laid out, whatever its stored width.
+    /// Width is clamped to MIN_COLUMN_WIDTH (100px) minimum.
+    pub fn set_max_column_width(&mut self, width: i32) {
+        self.max_column_width = width.max(MIN_COLUMN_WIDTH);
+    }
+
+    /// Compute balanced `(x, width)` pairs for every column, relative to the
+    /// viewport's left edge, or `None` if auto-balance is off or the strip
+    /// doesn't leave space to fill.
+    ///
+    /// The spare width goes to expanded columns in proportion to their
+    /// widths; a column reaching the maximum width passes its share on.
+    fn auto_balance_layout(&self, viewport_width: i32) -> Option<Vec<(i32, i32)>> {
+        if !self.auto_balance || self.columns.is_empty() {
+            return None;
+        }
+        let spare = viewport_width.saturating_sub(self.total_width());
+        if spare <= 0 {
+            return None;
+        }
+
+        let mut widths: Vec<i32> = self.columns.iter().map(|c| self.strip_width(c)).collect();
+        let mut remaining = spare;
+        while remaining > 0 {
+            let growable: Vec<usize> = (0..self.columns.len())
+                .filter(|&i| !self.columns[i].collapsed && widths[i] < self.max_column_width)
+                .collect();
+            let total: i64 = growable.iter().map(|&i| i64::from(widths[i])).sum();
+            if total == 0 {
+                break;
+            }
+            let mut given = 0;
+            for (k, &i) in growable.iter().enumerate() {
+                // The last column takes the rounding leftovers
+                let share = if k + 1 == growable.len() {
+                    remaining - given
+                } else {
+                    (i64::from(remaining) * i64::from(widths[i]) / total) as i32
+                };
+                let grow = share.min(self.max_column_width - widths[i]);
+                widths[i] += grow;
+                given += grow;
+            }
+            if given == 0 {
+                break;
+            }
+            remaining -= given;
+        }
+
+        let gap = self.gap.max(0);
+        let mut x = self.outer_gap.max(0);
+        let layout = widths
+            .into_iter()
+            .map(|width| {
+                let slot = (x, width);
+                x = x.saturating_add(width).saturating_add(gap);
+                slot
+            })
+            .collect();
+        Some(layout)
+    }
+
+    /// Columns shrunk to fit a slightly overflowing strip, or stretched to
+    /// fill a narrow one, if either applies.
+    fn fitted_layout(&self, viewport_width: i32) -> Option<Vec<(i32, i32)>> {
+        self.shrink_to_fit_layout(viewport_width)
+            .or_else(|| self.auto_balance_layout(viewport_width))
+    }
+
+    /// Post-processing pass that rewrites tiled placements to the fitted layout.
+    fn apply_fitted_layout(&self, placements: &mut [WindowPlacement], viewport: Rect) {
+        let Some(layout) = self.fitted_layout(viewport.width) else {
+            return;
+        };
+
+        for placement in placements.iter_mut() {
+            let Some(&(x, width)) = layout.get(placement.column_index) else {
+                continue;
+            };
+            placement.rect.x = viewport.x.saturating_add(x);
+            if !self.columns[placement.column_index].collapsed {
+                placement.rect.width = self.window_width(placement.window_id, width);
+                placement.visibility = Visibility::Visible;
+            }
+        }
+    }
+
+    // ========================================================================
+    // Drag-and-Drop Positioning
+    // ========================================================================
+
+    /// Get the column insertion slot under a screen x-coordinate (the
+    /// y-coordinate on a vertical strip; see [`strip_length`](Self::strip_length)).
+    ///
+    /// Slot boundaries sit at column midpoints: a point over the left half of
+    /// column `i` resolves to slot `i`, the right half to slot `i + 1`.
+    /// Returns a value in `0..=column_count()`.
+    pub fn insertion_index_at(&self, screen_x: i32, viewport: Rect) -> usize {
+        let viewport = if self.is_transposed() { viewport.transposed() } else { viewport };
+        let viewport_left = self.effective_scroll_offset().round() as i32;
+        let strip_x = screen_x.saturating_sub(viewport.x).saturating_add(viewport_left);
+
+        for (i, column) in self.columns.iter().enumerate() {
+            let midpoint = self.column_x(i).saturating_add(self.strip_width(column) / 2);
+            if strip_x < midpoint {
+                return i;
+            }
+        }
+        self.columns.len()
+    }
+
+    /// Get the screen rectangle used to preview an insertion slot.
+    ///
+    /// The rect is a thin bar centered in the gap before column `index` (or
+    /// after the last column), lying across the strip. Out-of-range indices
+    /// are clamped.
+    pub fn insertion_slot_rect(&self, index: usize, viewport: Rect) -> Rect {
+        if self.is_transposed() {
+            return self.insertion_strip_slot_rect(index, viewport.transposed()).transposed();
+        }
+        self.insertion_strip_slot_rect(index, viewport)
+    }
+
+    /// Insertion slot preview rect as for a horizontal strip.
+    fn insertion_strip_slot_rect(&self, index: usize, viewport: Rect) -> Rect {
+        let gap = self.gap.max(0);
+        let outer_gap = self.outer_gap.max(0);
+        let index = index.min(self.columns.len());
+
+        let boundary_x = if self.columns.is_empty() {
+            outer_gap
+        } else if index < self.columns.len() {
+            self.column_x(index).saturating_sub(gap / 2)
+        } else {
+            let last = self.columns.len() - 1;
+            self.column_x(last)
+                .saturating_add(self.strip_width(&self.columns[last]))
+                .saturating_add(gap / 2)
+        };
+
+        let viewport_left = self.effective_scroll_offset().round() as i32;
+        let screen_x = boundary_x
+            .saturating_sub(viewport_left)
+            .saturating_add(viewport.x)
+            .saturating_sub(INSERTION_HINT_WIDTH / 2);
+
+        Rect::new(
+            screen_x,
+            viewport.y + outer_gap,
+            INSERTION_HINT_WIDTH,
+            viewport.height.saturating_sub(outer_gap.saturating_mul(2)),
+        )
+    }
+
+    /// Move a tiled window into a new column at the given insertion slot.
+    ///
+    /// `index` refers to the column list before the window is removed, matching
+    /// the values returned by [`insertion_index_at`](Self::insertion_index_at).
+    /// The window keeps its column width and becomes focused.
+    ///
+    /// # Errors
+    ///
+    /// Returns `LayoutError::WindowNotFound` if the window is not tiled in this workspace.
+    pub fn move_window_to_index(&mut self, window_id: WindowId, index: usize) -> Result<(), LayoutError> {
+        if self.layout_mode == LayoutMode::StackOnly {
+            // There is only one column to drop into
+            return self.focus_window(window_id);
+        }
+        let (col_idx, _) = self
+            .find_window_location(window_id)
+            .ok_or(LayoutError::WindowNotFound(window_id))?;
+        let width = self.columns[col_idx].width;
+        let was_alone = self.columns[col_idx].len() == 1;
+
+        // Dropping a lone window on either side of its own column is a no-op
+        if was_alone && (index == col_idx || index == col_idx + 1) {
+            self.focused_column = col_idx;
+            self.focused_window_in_column = 0;
+            return Ok(());
+        }
+
+        self.remove_window(window_id)?;
+
+        let mut target = index;
+        if was_alone && target > col_idx {
+            // The source column was removed, shifting later slots left
+            target -= 1;
+        }
+        let target = target.min(self.columns.len());
+
+        self.columns.insert(target, Column::new(window_id, width));
+        self.focused_column = target;
+        self.focused_window_in_column = 0;
+        self.reindex();
+
+        Ok(())
+    }
+
+    /// Move a tiled window into an existing column, at `position` in its
+    /// stack (clamped to the bottom).
+    ///
+    /// `column` refers to the column list before the window is removed, as
+    /// returned by queries. The window's old column is removed if it becomes
+    /// empty, and the window becomes focused.
+    ///
+    /// # Errors
+    ///
+    /// Returns `LayoutError::WindowNotFound` if the window is not tiled in this
+    /// workspace, or `LayoutError::ColumnOutOfBounds` if the column index is invalid.
+    pub fn move_window_to_column(
+        &mut self,
+        window_id: WindowId,
+        column: usize,
+        position: usize,
+    ) -> Result<(), LayoutError> {
+        let (source, index) = self
+            .find_window_location(window_id)
+            .ok_or(LayoutError::WindowNotFound(window_id))?;
+        if column >= self.columns.len() {
+            return Err(LayoutError::ColumnOutOfBounds(column, self.columns.len() - 1));
+        }
+        self.remember_active_tab();
+
+        let mut target = column;
+        self.columns[source].remove_at(index);
+        if source != column {
+            if self.fullscreen_window_id() == Some(window_id) {
+                self.fullscreen = None;
+            }
+            if self.columns[source].is_empty() {
+                self.columns.remove(source);
+                if target > source {
+                    target -= 1;
+                }
+            }
+        }
+        self.columns[target].insert_window_at(position, window_id);
+
+        self.focused_column = target;
+        self.reindex();
+        self.focused_window_in_column = self.find_window_location(window_id).map_or(0, |(_, w)| w);
+        Ok(())
+    }
+
+    // ========================================================================
+    // Consume and Expel
+    // ========================================================================
+
+    /// Move the focused window into the adjacent column, stacking it at the bottom.
+    ///
+    /// The window stays focused; its old column is removed if it becomes
+    /// empty. Returns false without changes if there is no column on that
+    /// side or the workspace is in [`LayoutMode::StackOnly`].
+    pub fn consume_into_column(&mut self, to_left: bool) -> bool {
+        if self.layout_mode == LayoutMode::StackOnly || self.columns.is_empty() {
+            return false;
+        }
+        let source = self.focused_column;
+        let target = if to_left { source.checked_sub(1) } else { Some(source + 1) };
+        let Some(mut target) = target.filter(|&t| t < self.columns.len()) else {
+            return false;
+        };
+
+        self.remember_active_tab();
+        let window_id = self.columns[source].remove_at(self.focused_window_in_column);
+        self.columns[target].add_window(window_id);
+        if self.fullscreen_window_id() == Some(window_id) {
+            self.fullscreen = None;
+        }
+        if self.columns[source].is_empty() {
+            self.columns.remove(source);
+            if target > source {
+                target -= 1;
+            }
+        }
+
+        self.focused_column = target;
+        self.focused_window_in_column = self.columns[target].len() - 1;
+        self.reindex();
+        true
+    }
+
+    /// Move the focused window out of its stack into a new column beside it.
+    ///
+    /// The new column gets the old column's width and is focused. Returns
+    /// false without changes if the window is alone in its column or the
+    /// workspace is in [`LayoutMode::StackOnly`].
+    pub fn expel_from_column(&mut self, to_left: bool) -> bool {
+        if self.layout_mode == LayoutMode::StackOnly {
+            return false;
+        }
+        let source = self.focused_column;
+        let Some(column) = self.columns.get_mut(source).filter(|c| c.len() > 1) else {
+            return false;
+        };
+
+        let window_id = column.remove_at(self.focused_window_in_column);
+        let width = column.width;
+        let target = if to_left { source } else { source + 1 };
+        self.columns.insert(target, Column::new(window_id, width));
+
+        self.focused_column = target;
+        self.focused_window_in_column = 0;
+        self.reindex();
+        true
+    }
+
+    /// Split the focused window off its stack into a new column immediately
+    /// to the right, keeping it focused.
+    ///
+    /// Same as [`expel_from_column`](Self::expel_from_column) to the right;
+    /// returns false without changes if the window is alone in its column.
+    pub fn split_column(&mut self) -> bool {
+        self.expel_from_column(false)
+    }
+
+    /// Swap the focused window with the top window of its stack.
+    ///
+    /// The window stays focused at the top, and a tabbed column's active tab
+    /// follows whichever of the two windows it showed. Returns false without
+    /// changes if the window is already at the top.
+    pub fn promote_window(&mut self) -> bool {
+        let index = self.focused_window_in_column;
+        if index == 0 {
+            return false;
+        }
+        let Some(column) = self.columns.get_mut(self.focused_column).filter(|c| index < c.windows.len()) else {
+            return false;
+        };
+        column.windows.swap(0, index);
+        if column.active_tab == index {
+            column.active_tab = 0;
+        } else if column.active_tab == 0 {
+            column.active_tab = index;
+        }
+        self.focused_window_in_column = 0;
+        self.reindex();
+        true
+    }
+
+    // ========================================================================
+    // Arrangement Restore
+    // ========================================================================
+
+    /// Rebuild the leading columns from a saved arrangement.
+    ///
+    /// Windows listed in `columns` are moved out of their current columns into
+    /// the given ones, which keep their width and tag and become the first
+    /// columns in order. Listed windows this workspace doesn't tile, including
+    /// its floating and parked ones, are skipped, and columns left empty are
+    /// dropped. Remaining columns follow
+    /// in their current order. The focused window keeps focus.
+    ///
+    /// Returns the number of windows placed. Does nothing in
+    /// [`LayoutMode::StackOnly`], which has a single column.
+    pub fn arrange_columns(&mut self, columns: Vec<Column>) -> usize {
+        if self.layout_mode == LayoutMode::StackOnly {
+            return 0;
+        }
+        let focused = self.focused_window();
+
+        let mut placed = HashSet::new();
+        let arranged: Vec<Column> = columns
+            .into_iter()
+            .filter_map(|mut column| {
+                // Floating and parked windows stay where they are
+                column.windows.retain(|&id| self.find_window_location(id).is_some() && placed.insert(id));
+                column.collapsed = false;
+                column.active_tab = 0;
+                (!column.is_empty()).then_some(column)
+            })
+            .collect();
+        if placed.is_empty() {
+            return 0;
+        }
+
+        for column in &mut self.columns {
+            column.windows.retain(|id| !placed.contains(id));
+            column.active_tab = column.active_tab.min(column.windows.len().saturating_sub(1));
+        }
+        self.columns.retain(|column| !column.is_empty());
+        let remaining = std::mem::replace(&mut self.columns, arranged);
+        self.columns.extend(remaining);
+        self.reindex();
+
+        match focused.and_then(|id| self.find_window_location(id)) {
+            Some((col, win)) => {
+                self.focused_column = col;
+                self.focused_window_in_column = win;
+            }
+            None => {
+                self.focused_column = 0;
+                self.focused_window_in_column = 0;
+            }
+        }
+        placed.len()
+    }
+
+    // ========================================================================
+    // Iteration
+    // ========================================================================
+
+    /// Iterate over all windows with their layout position.
+    ///
+    /// Tiled windows come first, in column then stacking order, followed by
+    /// floating windows.
+    pub fn iter_windows(&self) -> impl Iterator<Item = WindowEntry> + '_ {
+        let tiled = self.columns.iter().enumerate().flat_map(|(col_idx, column)| {
+            column.windows().iter().enumerate().map(move |(win_idx, &window_id)| WindowEntry {
+                window_id,
+                column_index: Some(col_idx),
+                window_index: Some(win_idx),
+            })
+        });
+        let floating = self.floating_windows.iter().map(|f| WindowEntry {
+            window_id: f.id,
+            column_index: None,
+            window_index: None,
+        });
+        tiled.chain(floating)
+    }
+
+    /// Iterate over all windows paired with their computed placement.
+    ///
+    /// Placements are computed once for the whole workspace, in the same
+    /// order as [`iter_windows`](Self::iter_windows).
+    pub fn iter_placements(&self, viewport: Rect) -> impl Iterator<Item = (WindowEntry, WindowPlacement)> + '_ {
+        let mut placements: HashMap<WindowId, WindowPlacement> = self
+            .compute_placements(viewport)
+            .into_iter()
+            .map(|p| (p.window_id, p))
+            .collect();
+        self.iter_windows()
+            .filter_map(move |entry| placements.remove(&entry.window_id).map(|p| (entry, p)))
+    }
+}
+
+// ============================================================================
+// Named Workspaces
+// ============================================================================
+
+/// Name of the workspace every monitor starts with.
+pub const DEFAULT_WORKSPACE_NAME: &str = "default";
+
+/// The named workspaces of one monitor.
+///
+/// Only one workspace per monitor is active (laid out on screen). The active
+/// workspace is owned by the caller; the set stores the inactive ones and
+/// swaps them in on [`switch`](Self::switch). Empty workspaces other than the
+/// default are discarded when switching away from them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkspaceSet {
+    /// Name of the active workspace.
+    active: String,
+    /// Inactive workspaces, in the order they were last left.
+    inactive: Vec<(String, Workspace)>,
+}
+
+impl Default for WorkspaceSet {
+    fn default() -> Self {
+        Self {
+            active: DEFAULT_WORKSPACE_NAME.to_string(),
+            inactive: Vec::new(),
+        }
+    }
+}
+
+impl WorkspaceSet {
+    /// Create a set whose active workspace is the default one.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Name of the active workspace.
+    pub fn active_name(&self) -> &str {
+        &self.active
+    }
+
+    /// Names of all workspaces, the active one first.
+    pub fn names(&self) -> Vec<&str> {
+        std::iter::once(self.active.as_str())
+            .chain(self.inactive.iter().map(|(name, _)| name.as_str()))
+            .collect()
+    }
+
+    /// Iterate over the inactive workspaces.
+    pub fn inactive(&self) -> impl Iterator<Item = (&str, &Workspace)> {
+        self.inactive.iter().map(|(name, ws)| (name.as_str(), ws))
+    }
+
+    /// Iterate mutably over the inactive workspaces.
+    pub fn inactive_mut(&mut self) -> impl Iterator<Item = &mut Workspace> {
+        self.inactive.iter_mut().map(|(_, ws)| ws)
+    }
+
+    /// Make `name` the active workspace.
+    ///
+    /// `active` is the currently active workspace: it is stored under the
+    /// current name and replaced by the workspace called `name`, which is
+    /// created with `active`'s settings if it doesn't exist yet. Returns false
+    /// if `name` is already active.
+    pub fn switch(&mut self, name: &str, active: &mut Workspace) -> bool {
+        if name == self.active {
+            return false;
+        }
+        let next = match self.inactive.iter().position(|(n, _)| n == name) {
+            Some(index) => self.inactive.remove(index).1,
+            None => active.empty_like(),
+        };
+        let previous = std::mem::replace(active, next);
+        let previous_name = std::mem::replace(&mut self.active, name.to_string());
+        // Parked (minimized) windows count too, or they'd fall out of management
+        let keep = !previous.all_window_ids().is_empty() || previous_name == DEFAULT_WORKSPACE_NAME;
+        if keep {
+            self.inactive.push((previous_name, previous));
+        }
+        true
+    }
+
+    /// Get an inactive workspace by name, creating it with `template`'s
+    /// settings if it doesn't exist. Returns None for the active workspace's name.
+    pub fn inactive_or_create(&mut self, name: &str, template: &Workspace) -> Option<&mut Workspace> {
+        if name == self.active {
+            return None;
+        }
+        let index = match self.inactive.iter().position(|(n, _)| n == name) {
+            Some(index) => index,
+            None => {
+                self.inactive.push((name.to_string(), template.empty_like()));
+                self.inactive.len() - 1
+            }
+        };
+        Some(&mut self.inactive[index].1)
+    }
+
+    /// Name of the inactive workspace holding a window, if any.
+    pub fn find_window(&self, window_id: WindowId) -> Option<&str> {
+        self.inactive
+            .iter()
+            .find(|(_, ws)| ws.contains_window(window_id))
+            .map(|(name, _)| name.as_str())
+    }
+
+    /// Remove a window from whichever inactive workspace holds it.
+    ///
+    /// Returns true if the window was found.
+    pub fn remove_window(&mut self, window_id: WindowId) -> bool {
+        self.inactive.iter_mut().any(|(_, ws)| {
+            ws.forget_floating_rect(window_id);
+            ws.remove_floating(window_id) || ws.remove_window(window_id).is_ok()
+        })
+    }
+
+    /// IDs of all windows (tiled and floating) in inactive workspaces.
+    pub fn all_window_ids(&self) -> Vec<WindowId> {
+        self.inactive.iter().flat_map(|(_, ws)| ws.all_window_ids()).collect()
+    }
+
+    /// Remove and return all inactive workspaces.
+    pub fn take_inactive(&mut self) -> Vec<(String, Workspace)> {
+        std::mem::take(&mut self.inactive)
+    }
+}
+
+// Test-only helper methods for direct state manipulation
+#[cfg(test)]
+impl Workspace {
+    /// Set focus state directly without validation (test helper).
+    pub fn test_set_focus_unchecked(&mut self, column: usize, win: usize) {
+        self.focused_column = column;
+        self.focused_window_in_column = win;
+    }
+
+    /// Set scroll offset directly (test helper).
+    pub fn test_set_scroll_offset(&mut self, offset: f64) {
+        self.scroll_offset = offset;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_create_empty_workspace() {
+        let ws = Workspace::new();
+        assert!(ws.is_empty());
+        assert_eq!(ws.column_count(), 0);
+        assert_eq!(ws.total_width(), 0);
+    }
+
+    #[test]
+    fn test_insert_window() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, Some(400)).unwrap();
+
+        assert!(!ws.is_empty());
+        assert_eq!(ws.column_count(), 1);
+        assert_eq!(ws.focused_column_index(), 0);
+        assert_eq!(ws.focused_window(), Some(1));
+    }
+
+    #[test]
+    fn test_insert_multiple_windows() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(600)).unwrap();
+        ws.insert_window(3, Some(400)).unwrap();
+
+        assert_eq!(ws.column_count(), 3);
+        // Last inserted window should be focused
+        assert_eq!(ws.focused_column_index(), 2);
+        assert_eq!(ws.focused_window(), Some(3));
+
+        // Total width: outer_gap + 400 + gap + 600 + gap + 400 + outer_gap
+        // = 10 + 400 + 10 + 600 + 10 + 400 + 10 = 1440
+        assert_eq!(ws.total_width(), 1440);
+    }
+
+    #[test]
+    fn test_focus_navigation() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(400)).unwrap();
+        ws.insert_window(3, Some(400)).unwrap();
+
+        assert_eq!(ws.focused_column_index(), 2); // Last inserted
+
+        ws.focus_left();
+        assert_eq!(ws.focused_column_index(), 1);
+        assert_eq!(ws.focused_window(), Some(2));
+
+        ws.focus_left();
+        assert_eq!(ws.focused_column_index(), 0);
+
+        // Should not go below 0
+        ws.focus_left();
+        assert_eq!(ws.focused_column_index(), 0);
+
+        ws.focus_right();
+        ws.focus_right();
+        assert_eq!(ws.focused_column_index(), 2);
+
+        // Should not go beyond last column
+        ws.focus_right();
+        assert_eq!(ws.focused_column_index(), 2);
+    }
+
+    #[test]
+    fn test_remove_window() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(400)).unwrap();
+        ws.insert_window(3, Some(400)).unwrap();
+
+        assert_eq!(ws.column_count(), 3);
+
+        ws.remove_window(2).unwrap();
+        assert_eq!(ws.column_count(), 2);
+
+        // Windows 1 and 3 should remain
+        assert!(ws
+            .columns()
+            .iter()
+            .any(|c| c.contains(1)));
+        assert!(ws
+            .columns()
+            .iter()
+            .any(|c| c.contains(3)));
+    }
+
+    #[test]
+    fn test_compute_placements_visibility() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.insert_window(1, Some(400)).unwrap(); // x: 10-410
+        ws.insert_window(2, Some(400)).unwrap(); // x: 420-820
+        ws.insert_window(3, Some(400)).unwrap(); // x: 830-1230
+
+        ws.test_set_scroll_offset(0.0);
+
+        // Viewport of 500px wide starting at (0, 0)
+        let viewport = Rect::new(0, 0, 500, 600);
+        let placements = ws.compute_placements(viewport);
+
+        assert_eq!(placements.len(), 3);
+
+        // First column should be visible
+        assert_eq!(placements[0].visibility, Visibility::Visible);
+        assert_eq!(placements[0].window_id, 1);
+
+        // Second column partially visible
+        assert_eq!(placements[1].visibility, Visibility::Visible);
+
+        // Third column off-screen right
+        assert_eq!(placements[2].visibility, Visibility::OffScreenRight);
+    }
+
+    #[test]
+    fn test_vertical_strip_lays_out_rows() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.set_orientation(Orientation::Vertical);
+        ws.insert_window(1, Some(400)).unwrap(); // y: 10-410
+        ws.insert_window_in_column(2, 0).unwrap();
+        ws.insert_window(3, Some(400)).unwrap(); // y: 420-820
+        ws.insert_window(4, Some(400)).unwrap(); // y: 830-1230
+
+        let viewport = Rect::new(100, 0, 500, 1000);
+        assert_eq!(ws.strip_length(viewport), 1000);
+        assert_eq!(ws.cross_length(viewport), 500);
+
+        // Stacked windows share a row side by side
+        ws.test_set_scroll_offset(0.0);
+        let placements = ws.compute_placements(viewport);
+        let rect_of = |id| placements.iter().find(|p| p.window_id == id).unwrap().rect;
+        assert_eq!(rect_of(1), Rect::new(110, 10, 235, 400));
+        assert_eq!(rect_of(2), Rect::new(355, 10, 235, 400));
+        assert_eq!(rect_of(3), Rect::new(110, 420, 480, 400));
+
+        // The viewport scrolls down to the focused row
+        ws.ensure_focused_visible(ws.strip_length(viewport));
+        assert_eq!(ws.scroll_offset(), 240.0);
+        let placements = ws.compute_placements(viewport);
+        let last = placements.iter().find(|p| p.window_id == 4).unwrap();
+        assert_eq!(last.rect, Rect::new(110, 590, 480, 400));
+        assert_eq!(last.visibility, Visibility::Visible);
+    }
+
+    #[test]
+    fn test_vertical_strip_keeps_floating_and_drop_slots() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.set_orientation(Orientation::Vertical);
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(400)).unwrap();
+        ws.add_floating(9, Rect::new(50, 60, 300, 200)).unwrap();
+        ws.test_set_scroll_offset(0.0);
+
+        let viewport = Rect::new(0, 0, 500, 1000);
+        let placements = ws.compute_placements(viewport);
+        assert_eq!(placements.iter().find(|p| p.window_id == 9).unwrap().rect, Rect::new(50, 60, 300, 200));
+
+        // Drop slots follow the vertical axis
+        assert_eq!(ws.insertion_index_at(100, viewport), 0);
+        assert_eq!(ws.insertion_index_at(300, viewport), 1);
+        let slot = ws.insertion_slot_rect(1, viewport);
+        assert!(slot.width > slot.height);
+        assert_eq!(ws.empty_like().orientation(), Orientation::Vertical);
+    }
+
+    #[test]
+    fn test_ensure_focused_visible_center() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.set_centering_mode(CenteringMode::Center);
+
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(400)).unwrap();
+        ws.insert_window(3, Some(400)).unwrap();
+
+        ws.test_set_focus_unchecked(0, 0);
+        ws.test_set_scroll_offset(500.0); // Start scrolled right
+
+        ws.ensure_focused_visible(500);
+
+        // Should center column 0 in the viewport
+        // Column 0 is at x=10, width=400, center=210
+        // Viewport width=500, center=250
+        // scroll_offset = 210 - 250 = -40, clamped to 0
+        assert_eq!(ws.scroll_offset(), 0.0);
+    }
+
+    #[test]
+    fn test_center_focused_column_ignores_centering_mode() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.set_centering_mode(CenteringMode::JustInView);
+        for id in 1..=4 {
+            ws.insert_window(id, Some(400)).unwrap();
+        }
+
+        // Column 1 spans 420..820 and is already in view
+        ws.focus_column(1).unwrap();
+        ws.test_set_scroll_offset(0.0);
+        ws.center_focused_column(1000);
+        assert_eq!(ws.scroll_offset(), 120.0);
+
+        // Clamped at the end of the strip
+        ws.focus_column(3).unwrap();
+        ws.center_focused_column(1000);
+        assert_eq!(ws.scroll_offset(), f64::from(ws.total_width() - 1000));
+        assert_eq!(ws.column_center_offset(0, 1000), Some(0.0));
+        assert_eq!(ws.column_center_offset(4, 1000), None);
+    }
+
+    #[test]
+    fn test_stacked_windows() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window_in_column(2, 0).unwrap();
+        ws.insert_window_in_column(3, 0).unwrap();
+
+        assert_eq!(ws.column_count(), 1);
+        assert_eq!(ws.columns()[0].len(), 3);
+
+        let viewport = Rect::new(0, 0, 500, 600);
+        let placements = ws.compute_placements(viewport);
+
+        assert_eq!(placements.len(), 3);
+        // All three windows should be in the same column
+        assert!(placements.iter().all(|p| p.column_index == 0));
+    }
+
+    #[test]
+    fn test_resize_column() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, Some(400)).unwrap();
+
+        assert_eq!(ws.columns()[0].width(), 400);
+
+        ws.resize_focused_column(100);
+        assert_eq!(ws.columns()[0].width(), 500);
+
+        ws.resize_focused_column(-200);
+        assert_eq!(ws.columns()[0].width(), 300);
+
+        // Should not go below minimum (100)
+        ws.resize_focused_column(-500);
+        assert_eq!(ws.columns()[0].width(), 100);
+    }
+
+    #[test]
+    fn test_move_column() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(400)).unwrap();
+        ws.insert_window(3, Some(400)).unwrap();
+
+        ws.test_set_focus_unchecked(1, 0);
+        ws.move_column_left();
+
+        assert_eq!(ws.focused_column_index(), 0);
+        assert_eq!(ws.columns()[0].get(0), Some(2));
+        assert_eq!(ws.columns()[1].get(0), Some(1));
+
+        ws.move_column_right();
+        assert_eq!(ws.focused_column_index(), 1);
+        assert_eq!(ws.columns()[0].get(0), Some(1));
+        assert_eq!(ws.columns()[1].get(0), Some(2));
+    }
+
+    #[test]
+    fn test_scroll_by() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(400)).unwrap();
+        ws.insert_window(3, Some(400)).unwrap();
+
+        let viewport_width = 500;
+
+        ws.scroll_by(100.0, viewport_width);
+        assert_eq!(ws.scroll_offset(), 100.0);
+
+        ws.scroll_by(2000.0, viewport_width);
+        // Should clamp to max scroll
+        let max_scroll = (ws.total_width() - viewport_width).max(0) as f64;
+        assert_eq!(ws.scroll_offset(), max_scroll);
+
+        ws.scroll_by(-5000.0, viewport_width);
+        assert_eq!(ws.scroll_offset(), 0.0);
+    }
+
+    #[test]
+    fn test_rect_intersects() {
+        let r1 = Rect::new(0, 0, 100, 100);
+        let r2 = Rect::new(50, 50, 100, 100);
+        let r3 = Rect::new(200, 200, 50, 50);
+
+        assert!(r1.intersects(&r2));
+        assert!(r2.intersects(&r1));
+        assert!(!r1.intersects(&r3));
+        assert!(!r3.intersects(&r1));
+    }
+
+    // ====== Tests added from code review (Cycle 1) ======
+
+    #[test]
+    fn test_remove_last_window_empties_workspace() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, Some(400)).unwrap();
+
+        ws.remove_window(1).unwrap();
+
+        assert!(ws.is_empty());
+        assert_eq!(ws.column_count(), 0);
+        assert_eq!(ws.focused_column_index(), 0);
+        assert_eq!(ws.focused_window_index_in_column(), 0);
+        assert_eq!(ws.scroll_offset(), 0.0);
+    }
+
+    #[test]
+    fn test_ensure_focused_visible_just_in_view() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.set_centering_mode(CenteringMode::JustInView);
+
+        ws.insert_window(1, Some(200)).unwrap(); // x: 10-210
+        ws.insert_window(2, Some(200)).unwrap(); // x: 220-420
+        ws.insert_window(3, Some(200)).unwrap(); // x: 430-630
+
+        ws.test_set_focus_unchecked(0, 0);
+        ws.test_set_scroll_offset(0.0);
+
+        // Column 0 is already in view - should NOT scroll
+        ws.ensure_focused_visible(500);
+        assert_eq!(ws.scroll_offset(), 0.0);
+
+        // Focus column 2, which is partially out of view
+        ws.test_set_focus_unchecked(2, 0);
+        ws.ensure_focused_visible(500);
+        // Should scroll just enough to bring column 2 into view
+        assert!(ws.scroll_offset() > 0.0);
+    }
+
+    #[test]
+    fn test_compute_placements_tight_viewport() {
+        let mut ws = Workspace::with_gaps(10, 50); // Large outer_gap
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window_in_column(2, 0).unwrap();
+        ws.insert_window_in_column(3, 0).unwrap();
+
+        // Viewport smaller than outer_gaps * 2
+        let viewport = Rect::new(0, 0, 500, 80); // Only 80px tall
+        let placements = ws.compute_placements(viewport);
+
+        // All heights should be >= 0
+        for p in &placements {
+            assert!(p.rect.height >= 0, "Height was negative: {}", p.rect.height);
+        }
+    }
+
+    #[test]
+    fn test_insert_window_clamps_width() {
+        let mut ws = Workspace::new();
+
+        // Try to insert with zero width
+        ws.insert_window(1, Some(0)).unwrap();
+        assert_eq!(ws.columns()[0].width(), 100); // Clamped to minimum
+
+        // Try to insert with negative width
+        ws.insert_window(2, Some(-50)).unwrap();
+        assert_eq!(ws.columns()[1].width(), 100); // Clamped to minimum
+    }
+
+    #[test]
+    fn test_scroll_offset_rounding() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(400)).unwrap();
+
+        // Set fractional scroll offset
+        ws.test_set_scroll_offset(100.7);
+
+        let viewport = Rect::new(0, 0, 500, 600);
+        let placements = ws.compute_placements(viewport);
+
+        // Verify placements use rounded value (101, not truncated 100)
+        // The first column at x=10 should be at screen x = 10 - 101 + 0 = -91
+        assert_eq!(placements[0].rect.x, -91);
+    }
+
+    // ====== Tests added from code review (Cycle 2) ======
+
+    #[test]
+    fn test_column_empty_constructor() {
+        let col = Column::empty(50);
+        assert_eq!(col.width(), 100); // Clamped to MIN_COLUMN_WIDTH
+        assert!(col.is_empty());
+        assert_eq!(col.len(), 0);
+    }
+
+    #[test]
+    fn test_rect_right_and_bottom() {
+        let r = Rect::new(10, 20, 100, 50);
+        assert_eq!(r.right(), 110);
+        assert_eq!(r.bottom(), 70);
+
+        // Edge case: negative coordinates
+        let r2 = Rect::new(-50, -30, 100, 80);
+        assert_eq!(r2.right(), 50);
+        assert_eq!(r2.bottom(), 50);
+    }
+
+    #[test]
+    fn test_focus_operations_on_empty_workspace() {
+        let mut ws = Workspace::new();
+
+        // All focus operations should safely do nothing
+        ws.focus_left();
+        ws.focus_right();
+        ws.focus_up();
+        ws.focus_down();
+
+        assert!(ws.focused_window().is_none());
+        assert_eq!(ws.focused_column_index(), 0);
+        assert_eq!(ws.focused_window_index_in_column(), 0);
+    }
+
+    #[test]
+    fn test_remove_nonexistent_window() {
+        let mut ws = Workspace::new();
+        let result = ws.remove_window(999);
+        assert!(matches!(result, Err(LayoutError::WindowNotFound(999))));
+    }
+
+    #[test]
+    fn test_remove_window_adjusts_focus_correctly() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(400)).unwrap();
+        ws.insert_window(3, Some(400)).unwrap();
+
+        // Focus on column 2 (window 3)
+        ws.test_set_focus_unchecked(2, 0);
+
+        // Remove from column 0
+        ws.remove_window(1).unwrap();
+
+        // Focus should adjust: was 2, column 0 removed, now should be 1
+        assert_eq!(ws.focused_column_index(), 1);
+        assert_eq!(ws.focused_window(), Some(3));
+    }
+
+    #[test]
+    fn test_duplicate_window_rejected() {
+        let mut ws = Workspace::new();
+        ws.insert_window(42, Some(400)).unwrap();
+
+        // Try to insert same window as new column
+        let result = ws.insert_window(42, Some(400));
+        assert!(matches!(result, Err(LayoutError::DuplicateWindow(42))));
+
+        // Try to insert same window into existing column
+        let result = ws.insert_window_in_column(42, 0);
+        assert!(matches!(result, Err(LayoutError::DuplicateWindow(42))));
+
+        // Workspace should still have only one column with one window
+        assert_eq!(ws.column_count(), 1);
+        assert_eq!(ws.columns()[0].len(), 1);
+    }
+
+    #[test]
+    fn test_rect_clamps_negative_dimensions() {
+        let r = Rect::new(10, 20, -100, -50);
+        assert_eq!(r.width, 0);
+        assert_eq!(r.height, 0);
+        assert_eq!(r.x, 10);
+        assert_eq!(r.y, 20);
+        assert!(r.is_empty());
+    }
+
+    #[test]
+    fn test_rect_contains_and_intersection() {
+        let outer = Rect::new(0, 0, 1920, 1080);
+        let inner = Rect::new(0, 0, 1920, 1040);
+        assert!(outer.contains_rect(&inner));
+        assert!(outer.contains_rect(&outer));
+        assert!(!inner.contains_rect(&outer));
+
+        let right = Rect::new(1000, 500, 1920, 1080);
+        assert_eq!(outer.intersection(&right), Some(Rect::new(1000, 500, 920, 580)));
+        assert_eq!(outer.intersection(&Rect::new(1920, 0, 100, 100)), None);
+    }
+
+    #[test]
+    fn test_total_width_saturates() {
+        let mut ws = Workspace::new();
+
+        // Insert many columns with large widths to test saturation
+        for i in 0..1000 {
+            ws.insert_window(i, Some(i32::MAX / 100)).unwrap();
+        }
+
+        // Should saturate to i32::MAX instead of overflowing/panicking
+        let width = ws.total_width();
+        assert!(width > 0); // Should not wrap to negative
+        assert_eq!(width, i32::MAX); // Should saturate at max
+    }
+
+    // ====== Tests added from code review (Cycle 4) ======
+
+    #[test]
+    fn test_focus_window_by_id() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(400)).unwrap();
+        ws.insert_window(3, Some(400)).unwrap();
+
+        // Focus is on column 2 (window 3) after inserts
+        assert_eq!(ws.focused_window(), Some(3));
+
+        // Focus window 1 by ID
+        ws.focus_window(1).unwrap();
+        assert_eq!(ws.focused_column_index(), 0);
+        assert_eq!(ws.focused_window(), Some(1));
+
+        // Focus window 2 by ID
+        ws.focus_window(2).unwrap();
+        assert_eq!(ws.focused_column_index(), 1);
+        assert_eq!(ws.focused_window(), Some(2));
+
+        // Try to focus nonexistent window
+        let result = ws.focus_window(999);
+        assert!(matches!(result, Err(LayoutError::WindowNotFound(999))));
+    }
+
+    #[test]
+    fn test_set_focus_validates() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(400)).unwrap();
+        ws.insert_window_in_column(3, 1).unwrap(); // Stack window 3 in column 1
+
+        // Valid focus
+        ws.set_focus(1, 1).unwrap();
+        assert_eq!(ws.focused_column_index(), 1);
+        assert_eq!(ws.focused_window_index_in_column(), 1);
+        assert_eq!(ws.focused_window(), Some(3));
+
+        // Invalid column index
+        let result = ws.set_focus(5, 0);
+        assert!(matches!(result, Err(LayoutError::ColumnOutOfBounds(5, 1))));
+
+        // Invalid window index in column
+        let result = ws.set_focus(0, 10);
+        assert!(matches!(result, Err(LayoutError::WindowIndexOutOfBounds(10, 0, 0))));
+    }
+
+    #[test]
+    fn test_scroll_by_special_floats() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(400)).unwrap();
+
+        let viewport_width = 500;
+
+        // Scroll to a known position
+        ws.scroll_by(50.0, viewport_width);
+        assert_eq!(ws.scroll_offset(), 50.0);
+
+        // NaN should be treated as zero (no change)
+        ws.scroll_by(f64::NAN, viewport_width);
+        assert_eq!(ws.scroll_offset(), 50.0);
+
+        // Infinity should be treated as zero (no change)
+        ws.scroll_by(f64::INFINITY, viewport_width);
+        assert_eq!(ws.scroll_offset(), 50.0);
+
+        // Negative infinity should be treated as zero (no change)
+        ws.scroll_by(f64::NEG_INFINITY, viewport_width);
+        assert_eq!(ws.scroll_offset(), 50.0);
+    }
+
+    #[test]
+    fn test_column_width_getter() {
+        let col = Column::new(1, 500);
+        assert_eq!(col.width(), 500);
+
+        let col2 = Column::new(2, 50); // Below minimum
+        assert_eq!(col2.width(), 100); // Clamped
+    }
+
+    #[test]
+    fn test_column_contains() {
+        let mut col = Column::new(1, 400);
+        col.add_window(2);
+        col.add_window(3);
+
+        assert!(col.contains(1));
+        assert!(col.contains(2));
+        assert!(col.contains(3));
+        assert!(!col.contains(999));
+
+        // Test get() method
+        assert_eq!(col.get(0), Some(1));
+        assert_eq!(col.get(1), Some(2));
+        assert_eq!(col.get(2), Some(3));
+        assert_eq!(col.get(10), None);
+
+        // Test windows() slice
+        assert_eq!(col.windows(), &[1, 2, 3]);
+    }
+
+    // ====== Tests added from code review (Cycle 5) ======
+
+    #[test]
+    fn test_remove_window_before_focus_in_stacked_column() {
+        // Bug test: removing a window BEFORE the focused window in a stacked column
+        // should keep focus on the same window (index should decrement)
+        let mut ws = Workspace::new();
+        ws.insert_window(1, Some(400)).unwrap(); // Column 0
+        ws.insert_window_in_column(2, 0).unwrap(); // Stack: [1, 2]
+        ws.insert_window_in_column(3, 0).unwrap(); // Stack: [1, 2, 3]
+
+        // Focus on window 2 (index 1)
+        ws.test_set_focus_unchecked(0, 1);
+        assert_eq!(ws.focused_window(), Some(2));
+
+        // Remove window 1 (index 0, before focused)
+        ws.remove_window(1).unwrap();
+
+        // Focus should still be on window 2, but index should now be 0
+        assert_eq!(ws.focused_window(), Some(2));
+        assert_eq!(ws.focused_window_index_in_column(), 0);
+    }
+
+    #[test]
+    fn test_remove_focused_window_in_stacked_column() {
+        // Removing the focused window should move focus to next window (or previous if at end)
+        let mut ws = Workspace::new();
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window_in_column(2, 0).unwrap();
+        ws.insert_window_in_column(3, 0).unwrap(); // Stack: [1, 2, 3]
+
+        // Focus on window 2 (index 1, middle)
+        ws.test_set_focus_unchecked(0, 1);
+        assert_eq!(ws.focused_window(), Some(2));
+
+        // Remove window 2 (the focused window)
+        ws.remove_window(2).unwrap();
+
+        // Stack is now [1, 3], focus index 1 should point to window 3 (next)
+        assert_eq!(ws.focused_window(), Some(3));
+        assert_eq!(ws.focused_window_index_in_column(), 1);
+    }
+
+    #[test]
+    fn test_remove_last_focused_window_in_stacked_column() {
+        // Removing the last focused window should move focus to previous
+        let mut ws = Workspace::new();
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window_in_column(2, 0).unwrap();
+        ws.insert_window_in_column(3, 0).unwrap(); // Stack: [1, 2, 3]
+
+        // Focus on window 3 (index 2, last)
+        ws.test_set_focus_unchecked(0, 2);
+        assert_eq!(ws.focused_window(), Some(3));
+
+        // Remove window 3 (the focused window, at end)
+        ws.remove_window(3).unwrap();
+
+        // Stack is now [1, 2], focus should move to index 1 (window 2)
+        assert_eq!(ws.focused_window(), Some(2));
+        assert_eq!(ws.focused_window_index_in_column(), 1);
+    }
+
+    #[test]
+    fn test_compute_placements_wide_column() {
+        // Column wider than viewport
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.insert_window(1, Some(1000)).unwrap(); // Column wider than viewport
+
+        let viewport = Rect::new(0, 0, 500, 600); // Viewport only 500px wide
+        let placements = ws.compute_placements(viewport);
+
+        assert_eq!(placements.len(), 1);
+        assert_eq!(placements[0].visibility, Visibility::Visible);
+        assert_eq!(placements[0].rect.width, 1000); // Full column width preserved
+    }
+
+    #[test]
+    fn test_column_empty_type() {
+        // Tests the Column::empty() constructor and its properties.
+        // Note: In practice, empty columns are automatically removed from workspaces
+        // when the last window is removed, so empty columns don't occur in normal use.
+        // Column::empty() exists for construction purposes (e.g., building columns
+        // before adding windows).
+        let empty_col = Column::empty(300);
+        assert!(empty_col.is_empty());
+        assert_eq!(empty_col.width(), 300);
+        assert_eq!(empty_col.len(), 0);
+        assert_eq!(empty_col.windows(), &[]);
+
+        // Verify workspace doesn't produce placements for non-existent windows
+        let mut ws = Workspace::new();
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(400)).unwrap();
+
+        let viewport = Rect::new(0, 0, 2000, 600);
+        let placements = ws.compute_placements(viewport);
+        assert_eq!(placements.len(), 2); // Only 2 windows, no extras
+    }
+
+    #[test]
+    fn test_negative_gaps_clamped() {
+        // Negative gaps should be clamped to 0
+        let ws = Workspace::with_gaps(-100, -50);
+        assert_eq!(ws.gap(), 0);
+        assert_eq!(ws.outer_gap(), 0);
+    }
+
+    #[test]
+    fn test_gap_setters_clamp() {
+        let mut ws = Workspace::new();
+
+        // Test gap setter
+        ws.set_gap(20);
+        assert_eq!(ws.gap(), 20);
+        ws.set_gap(-50);
+        assert_eq!(ws.gap(), 0); // Clamped
+
+        // Test outer_gap setter
+        ws.set_outer_gap(15);
+        assert_eq!(ws.outer_gap(), 15);
+        ws.set_outer_gap(-100);
+        assert_eq!(ws.outer_gap(), 0); // Clamped
+
+        // Test default_column_width setter
+        ws.set_default_column_width(500);
+        assert_eq!(ws.default_column_width(), 500);
+        ws.set_default_column_width(50); // Below MIN_COLUMN_WIDTH
+        assert_eq!(ws.default_column_width(), 100); // Clamped to minimum
+
+        // Test centering_mode getter/setter
+        assert_eq!(ws.centering_mode(), CenteringMode::Center); // Default
+        ws.set_centering_mode(CenteringMode::JustInView);
+        assert_eq!(ws.centering_mode(), CenteringMode::JustInView);
+    }
+
+    #[test]
+    fn test_compute_placements_spacing_integrity() {
+        // Verify stacked window heights + gaps sum correctly
+        let mut ws = Workspace::with_gaps(10, 20);
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window_in_column(2, 0).unwrap();
+        ws.insert_window_in_column(3, 0).unwrap(); // Stack: [1, 2, 3]
+
+        let viewport = Rect::new(0, 0, 500, 600);
+        let placements = ws.compute_placements(viewport);
+
+        // usable_height = 600 - 20*2 = 560
+        // 3 windows with 2 gaps = 560 - 10*2 = 540 for windows
+        // Each window ~180px, but last takes remainder
+
+        let total_height: i32 = placements.iter().map(|p| p.rect.height).sum();
+        let expected_usable = viewport.height - ws.outer_gap() * 2;
+        let expected_gaps = ws.gap() * (placements.len() as i32 - 1);
+
+        // Total heights + gaps should equal usable height
+        assert_eq!(total_height + expected_gaps, expected_usable);
+    }
+
+    #[test]
+    fn test_column_remove_returns_index() {
+        let mut col = Column::new(1, 400);
+        col.add_window(2);
+        col.add_window(3);
+        // Windows: [1, 2, 3]
+
+        // Remove middle window
+        let removed = col.remove_window(2);
+        assert_eq!(removed, Some(1)); // Index 1
+
+        // Remove first window
+        let removed = col.remove_window(1);
+        assert_eq!(removed, Some(0)); // Index 0
+
+        // Remove nonexistent
+        let removed = col.remove_window(999);
+        assert_eq!(removed, None);
+    }
+
+    // ====== Tests added from code review (Cycle 7) ======
+
+    #[test]
+    fn test_compute_placements_zero_viewport_width() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(400)).unwrap();
+
+        // Zero width viewport - edge case
+        let viewport = Rect::new(0, 0, 0, 600);
+        let placements = ws.compute_placements(viewport);
+
+        // Should produce placements without panicking
+        assert_eq!(placements.len(), 2);
+        // All columns should be off-screen right (viewport has no width)
+        for p in &placements {
+            assert_eq!(p.visibility, Visibility::OffScreenRight);
+        }
+    }
+
+    #[test]
+    fn test_compute_placements_zero_viewport_height() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window_in_column(2, 0).unwrap();
+
+        // Zero height viewport - edge case
+        let viewport = Rect::new(0, 0, 500, 0);
+        let placements = ws.compute_placements(viewport);
+
+        // Should produce placements without panicking
+        assert_eq!(placements.len(), 2);
+        // All heights should be >= 0 (clamped)
+        for p in &placements {
+            assert!(p.rect.height >= 0, "Height was negative: {}", p.rect.height);
+        }
+    }
+
+    #[test]
+    fn test_focus_navigation_clamps_to_shorter_column() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, Some(400)).unwrap(); // Column 0: [1]
+        ws.insert_window(2, Some(400)).unwrap(); // Column 1: [2]
+        ws.insert_window_in_column(3, 0).unwrap(); // Column 0: [1, 3]
+        ws.insert_window_in_column(4, 0).unwrap(); // Column 0: [1, 3, 4]
+
+        // Focus on window 4 (column 0, index 2)
+        ws.test_set_focus_unchecked(0, 2);
+        assert_eq!(ws.focused_window(), Some(4));
+
+        // Move right to column 1 which only has 1 window
+        ws.focus_right();
+
+        // Focus should clamp to index 0 (the only window in column 1)
+        assert_eq!(ws.focused_column_index(), 1);
+        assert_eq!(ws.focused_window_index_in_column(), 0);
+        assert_eq!(ws.focused_window(), Some(2));
+    }
+
+    #[test]
+    fn test_resize_then_ensure_focused_visible() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.set_centering_mode(CenteringMode::JustInView);
+        ws.insert_window(1, Some(200)).unwrap();
+        ws.insert_window(2, Some(200)).unwrap();
+        ws.insert_window(3, Some(200)).unwrap();
+
+        // Focus column 2, resize it significantly
+        ws.test_set_focus_unchecked(2, 0);
+        ws.resize_focused_column(500); // Now 700px wide
+
+        // Ensure focused visible should adjust scroll
+        ws.ensure_focused_visible(500);
+
+        // Should have scrolled to bring the widened column into view
+        assert!(ws.scroll_offset() > 0.0);
+    }
+
+    #[test]
+    fn test_move_column_then_resize() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(300)).unwrap();
+        ws.insert_window(3, Some(500)).unwrap();
+
+        // Focus column 1 (window 2, 300px)
+        ws.test_set_focus_unchecked(1, 0);
+        assert_eq!(ws.columns()[1].width(), 300);
+
+        // Move column left
+        ws.move_column_left();
+        assert_eq!(ws.focused_column_index(), 0);
+        assert_eq!(ws.columns()[0].width(), 300); // Column with window 2
+
+        // Resize the moved column
+        ws.resize_focused_column(100);
+        assert_eq!(ws.columns()[0].width(), 400);
+    }
+
+    #[test]
+    fn test_remove_reinsert_same_window_id() {
+        let mut ws = Workspace::new();
+        ws.insert_window(42, Some(400)).unwrap();
+        ws.insert_window(100, Some(300)).unwrap();
+
+        // Remove window 42
+        ws.remove_window(42).unwrap();
+        assert!(!ws.contains_window(42));
+
+        // Re-insert same ID should work now
+        ws.insert_window(42, Some(500)).unwrap();
+        assert!(ws.contains_window(42));
+        assert_eq!(ws.focused_window(), Some(42));
+    }
+
+    #[test]
+    fn test_find_window_location() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, Some(400)).unwrap(); // Column 0
+        ws.insert_window(2, Some(400)).unwrap(); // Column 1
+        ws.insert_window_in_column(3, 0).unwrap(); // Column 0, index 1
+        ws.insert_window_in_column(4, 1).unwrap(); // Column 1, index 1
+
+        assert_eq!(ws.find_window_location(1), Some((0, 0)));
+        assert_eq!(ws.find_window_location(2), Some((1, 0)));
+        assert_eq!(ws.find_window_location(3), Some((0, 1)));
+        assert_eq!(ws.find_window_location(4), Some((1, 1)));
+        assert_eq!(ws.find_window_location(999), None);
+    }
+
+    #[test]
+    fn test_window_count() {
+        let mut ws = Workspace::new();
+        assert_eq!(ws.window_count(), 0);
+
+        ws.insert_window(1, Some(400)).unwrap();
+        assert_eq!(ws.window_count(), 1);
+
+        ws.insert_window(2, Some(400)).unwrap();
+        assert_eq!(ws.window_count(), 2);
+
+        ws.insert_window_in_column(3, 0).unwrap();
+        ws.insert_window_in_column(4, 0).unwrap();
+        assert_eq!(ws.window_count(), 4);
+
+        ws.remove_window(2).unwrap();
+        assert_eq!(ws.window_count(), 3);
+    }
+
+    #[test]
+    fn test_column_safe_access() {
+        let mut ws = Workspace::new();
+        assert!(ws.column(0).is_none());
+
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(500)).unwrap();
+
+        assert!(ws.column(0).is_some());
+        assert_eq!(ws.column(0).unwrap().width(), 400);
+        assert!(ws.column(1).is_some());
+        assert_eq!(ws.column(1).unwrap().width(), 500);
+        assert!(ws.column(2).is_none());
+        assert!(ws.column(100).is_none());
+    }
+
+    #[test]
+    fn test_single_column_move_operations() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, Some(400)).unwrap();
+
+        // Move operations on single column should do nothing
+        ws.move_column_left();
+        assert_eq!(ws.focused_column_index(), 0);
+        assert_eq!(ws.focused_window(), Some(1));
+
+        ws.move_column_right();
+        assert_eq!(ws.focused_column_index(), 0);
+        assert_eq!(ws.focused_window(), Some(1));
+    }
+
+    #[test]
+    fn test_resize_on_empty_workspace() {
+        let mut ws = Workspace::new();
+
+        // Resize on empty workspace should do nothing without panic
+        ws.resize_focused_column(100);
+        ws.resize_focused_column(-100);
+        ws.resize_focused_column(i32::MAX);
+        ws.resize_focused_column(i32::MIN);
+
+        assert!(ws.is_empty());
+    }
+
+    #[test]
+    fn test_invariants_after_complex_sequence() {
+        let mut ws = Workspace::with_gaps(10, 10);
+
+        // Insert several windows
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(300)).unwrap();
+        ws.insert_window(3, Some(500)).unwrap();
+        ws.insert_window_in_column(4, 1).unwrap();
+        ws.insert_window_in_column(5, 1).unwrap();
+
+        // Complex sequence of operations
+        ws.focus_left();
+        ws.focus_down();
+        ws.move_column_right();
+        ws.resize_focused_column(100);
+        ws.focus_right();
+        ws.focus_up();
+        ws.remove_window(4).unwrap();
+        ws.focus_left();
+        ws.move_column_left();
+
+        // Verify invariants still hold
+        assert!(ws.focused_column < ws.columns.len());
+        assert!(ws.focused_window_in_column < ws.columns[ws.focused_column].len());
+
+        // No duplicate windows
+        let mut all_windows: Vec<WindowId> = ws.columns
+            .iter()
+            .flat_map(|c| c.windows().iter().copied())
+            .collect();
+        all_windows.sort();
+        let len_before = all_windows.len();
+        all_windows.dedup();
+        assert_eq!(all_windows.len(), len_before, "Duplicate windows found");
+    }
+
+    #[test]
+    fn test_animated_just_in_view_clamps_after_strip_shrinks() {
+        let mut ws = Workspace::with_gaps(0, 0);
+        ws.set_centering_mode(CenteringMode::JustInView);
+        for id in 1..=4 {
+            ws.insert_window(id, Some(900)).unwrap();
+            ws.ensure_focused_visible_animated(2560);
+            ws.stop_animation();
+        }
+        assert_eq!(ws.scroll_offset(), 1040.0);
+
+        // The focused column stays in view, but the strip no longer reaches
+        // the viewport's right edge
+        ws.focus_window(1).unwrap();
+        ws.remove_window(4).unwrap();
+        ws.focus_window(3).unwrap();
+        ws.ensure_focused_visible_animated(2560);
+        ws.stop_animation();
+        assert_eq!(ws.scroll_offset(), 140.0);
+        ws.check_invariants(2560).unwrap();
+    }
+
+    #[test]
+    fn test_check_invariants_reports_violations() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(400)).unwrap();
+        ws.check_invariants(1920).unwrap();
+
+        let mut bad = ws.clone();
+        bad.focused_column = 2;
+        assert!(matches!(bad.check_invariants(1920), Err(LayoutError::InvariantViolation(_))));
+
+        let mut bad = ws.clone();
+        bad.scroll_offset = 500.0;
+        assert!(bad.check_invariants(1920).is_err());
+        // The same offset is in range for a narrower viewport
+        assert!(bad.check_invariants(300).is_ok());
+
+        let mut bad = ws.clone();
+        bad.floating_windows.push(FloatingWindow { id: 1, rect: Rect::new(0, 0, 100, 100) });
+        assert!(bad.check_invariants(1920).unwrap_err().to_string().contains("window 1"));
+    }
+
+    #[test]
+    fn test_column_partial_eq() {
+        let col1 = Column::new(1, 400);
+        let col2 = Column::new(1, 400);
+        let col3 = Column::new(2, 400);
+        let col4 = Column::new(1, 500);
+
+        assert_eq!(col1, col2);
+        assert_ne!(col1, col3); // Different window
+        assert_ne!(col1, col4); // Different width
+    }
+
+    // ========================================================================
+    // Animation Tests
+    // ========================================================================
+
+    #[test]
+    fn test_easing_linear() {
+        assert!((Easing::Linear.apply(0.0) - 0.0).abs() < f64::EPSILON);
+        assert!((Easing::Linear.apply(0.25) - 0.25).abs() < f64::EPSILON);
+        assert!((Easing::Linear.apply(0.5) - 0.5).abs() < f64::EPSILON);
+        assert!((Easing::Linear.apply(0.75) - 0.75).abs() < f64::EPSILON);
+        assert!((Easing::Linear.apply(1.0) - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_easing_ease_out() {
+        // EaseOut starts fast, ends slow (cubic)
+        let ease_out = Easing::EaseOut;
+
+        // At t=0, should be 0
+        assert!((ease_out.apply(0.0) - 0.0).abs() < f64::EPSILON);
+        // At t=1, should be 1
+        assert!((ease_out.apply(1.0) - 1.0).abs() < f64::EPSILON);
+
+        // EaseOut should be ahead of linear in the middle
+        assert!(ease_out.apply(0.5) > 0.5);
+
+        // Verify cubic formula: 1 - (1 - t)^3
+        let t: f64 = 0.5;
+        let expected = 1.0 - (1.0 - t).powi(3);
+        assert!((ease_out.apply(t) - expected).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_easing_ease_in() {
+        // EaseIn starts slow, ends fast (cubic)
+        let ease_in = Easing::EaseIn;
+
+        // At t=0, should be 0
+        assert!((ease_in.apply(0.0) - 0.0).abs() < f64::EPSILON);
+        // At t=1, should be 1
+        assert!((ease_in.apply(1.0) - 1.0).abs() < f64::EPSILON);
+
+        // EaseIn should be behind linear in the middle
+        assert!(ease_in.apply(0.5) < 0.5);
+
+        // Verify cubic formula: t^3
+        let t: f64 = 0.5;
+        let expected = t.powi(3);
+        assert!((ease_in.apply(t) - expected).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_easing_ease_in_out() {
+        let ease_in_out = Easing::EaseInOut;
+
+        // At t=0, should be 0
+        assert!((ease_in_out.apply(0.0) - 0.0).abs() < f64::EPSILON);
+        // At t=1, should be 1
+        assert!((ease_in_out.apply(1.0) - 1.0).abs() < f64::EPSILON);
+        // At t=0.5, should be 0.5 (inflection point)
+        assert!((ease_in_out.apply(0.5) - 0.5).abs() < f64::EPSILON);
+
+        // First half should be behind linear
+        assert!(ease_in_out.apply(0.25) < 0.25);
+        // Second half should be ahead of linear
+        assert!(ease_in_out.apply(0.75) > 0.75);
+    }
+
+    #[test]
+    fn test_easing_clamps_input() {
+        // Values outside [0, 1] should be clamped
+        assert!((Easing::Linear.apply(-0.5) - 0.0).abs() < f64::EPSILON);
+        assert!((Easing::Linear.apply(1.5) - 1.0).abs() < f64::EPSILON);
+        assert!((Easing::EaseOut.apply(-1.0) - 0.0).abs() < f64::EPSILON);
+        assert!((Easing::EaseOut.apply(2.0) - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_easing_default_is_ease_out() {
+        assert_eq!(Easing::default(), Easing::EaseOut);
+    }
+
+    #[test]
+    fn test_scroll_animation_new() {
+        let anim = ScrollAnimation::new(0.0, 100.0, 200, Easing::Linear);
+
+        assert!((anim.start_offset - 0.0).abs() < f64::EPSILON);
+        assert!((anim.target_offset - 100.0).abs() < f64::EPSILON);
+        assert_eq!(anim.duration_ms, 200);
+        assert_eq!(anim.elapsed_ms, 0);
+        assert_eq!(anim.easing, Easing::Linear);
+    }
+
+    #[test]
+    fn test_scroll_animation_with_defaults() {
+        let anim = ScrollAnimation::with_defaults(50.0, 150.0);
+
+        assert!((anim.start_offset - 50.0).abs() < f64::EPSILON);
+        assert!((anim.target_offset - 150.0).abs() < f64::EPSILON);
+        assert_eq!(anim.duration_ms, DEFAULT_ANIMATION_DURATION_MS);
+        assert_eq!(anim.easing, Easing::default());
+    }
+
+    #[test]
+    fn test_scroll_animation_progress() {
+        let mut anim = ScrollAnimation::new(0.0, 100.0, 100, Easing::Linear);
+
+        assert!((anim.progress() - 0.0).abs() < f64::EPSILON);
+
+        anim.elapsed_ms = 50;
+        assert!((anim.progress() - 0.5).abs() < f64::EPSILON);
+
+        anim.elapsed_ms = 100;
+        assert!((anim.progress() - 1.0).abs() < f64::EPSILON);
+
+        // Over time should clamp to 1.0
+        anim.elapsed_ms = 150;
+        assert!((anim.progress() - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_scroll_animation_progress_zero_duration() {
+        let anim = ScrollAnimation::new(0.0, 100.0, 0, Easing::Linear);
+        // Zero duration should return 1.0 progress immediately
+        assert!((anim.progress() - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_scroll_animation_current_offset_linear() {
+        let mut anim = ScrollAnimation::new(0.0, 100.0, 100, Easing::Linear);
+
+        // At start
+        assert!((anim.current_offset() - 0.0).abs() < f64::EPSILON);
+
+        // At midpoint
+        anim.elapsed_ms = 50;
+        assert!((anim.current_offset() - 50.0).abs() < f64::EPSILON);
+
+        // At end
+        anim.elapsed_ms = 100;
+        assert!((anim.current_offset() - 100.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_scroll_animation_current_offset_eased() {
+        let mut anim = ScrollAnimation::new(0.0, 100.0, 100, Easing::EaseOut);
+
+        // At midpoint with ease out, should be ahead of 50
+        anim.elapsed_ms = 50;
+        assert!(anim.current_offset() > 50.0);
+    }
+
+    #[test]
+    fn test_scroll_animation_negative_direction() {
+        let mut anim = ScrollAnimation::new(100.0, 0.0, 100, Easing::Linear);
+
+        // At start
+        assert!((anim.current_offset() - 100.0).abs() < f64::EPSILON);
+
+        // At midpoint - should be halfway back to 0
+        anim.elapsed_ms = 50;
+        assert!((anim.current_offset() - 50.0).abs() < f64::EPSILON);
+
+        // At end
+        anim.elapsed_ms = 100;
+        assert!((anim.current_offset() - 0.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_scroll_animation_is_complete() {
+        let mut anim = ScrollAnimation::new(0.0, 100.0, 100, Easing::Linear);
+
+        assert!(!anim.is_complete());
+
+        anim.elapsed_ms = 50;
+        assert!(!anim.is_complete());
+
+        anim.elapsed_ms = 100;
+        assert!(anim.is_complete());
+
+        anim.elapsed_ms = 150;
+        assert!(anim.is_complete());
+    }
+
+    #[test]
+    fn test_scroll_animation_tick() {
+        let mut anim = ScrollAnimation::new(0.0, 100.0, 100, Easing::Linear);
+
+        // Tick returns true while running
+        assert!(anim.tick(25));
+        assert_eq!(anim.elapsed_ms, 25);
+
+        assert!(anim.tick(25));
+        assert_eq!(anim.elapsed_ms, 50);
+
+        assert!(anim.tick(25));
+        assert_eq!(anim.elapsed_ms, 75);
+
+        // Final tick completes
+        assert!(!anim.tick(25));
+        assert_eq!(anim.elapsed_ms, 100);
+
+        // Further ticks still return false
+        assert!(!anim.tick(50));
+        assert_eq!(anim.elapsed_ms, 150);
+    }
+
+    #[test]
+    fn test_scroll_animation_tick_saturating() {
+        let mut anim = ScrollAnimation::new(0.0, 100.0, 100, Easing::Linear);
+
+        // Large tick value should not overflow
+        anim.elapsed_ms = u64::MAX - 10;
+        anim.tick(100);
+        assert_eq!(anim.elapsed_ms, u64::MAX); // Saturates at MAX
+    }
+
+    #[test]
+    fn test_scroll_animation_target() {
+        let anim = ScrollAnimation::new(0.0, 456.78, 100, Easing::Linear);
+        assert!((anim.target() - 456.78).abs() < f64::EPSILON);
+    }
+
+    // ========================================================================
+    // Workspace Animation Tests
+    // ========================================================================
+
+    #[test]
+    fn test_workspace_is_animating() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        // Add enough windows to have scrollable content
+        for i in 1..=5 {
+            ws.insert_window(i, Some(400)).unwrap();
+        }
+        // Total: 10 + (5*400) + (4*10) + 10 = 2060
+
+        assert!(!ws.is_animating());
+
+        // Viewport 500 means max_scroll = 2060 - 500 = 1560
+        ws.start_scroll_animation(100.0, 500, None, None);
+        assert!(ws.is_animating());
+
+        // Complete the animation
+        ws.tick_animation(300);
+        assert!(!ws.is_animating());
+    }
+
+    #[test]
+    fn test_workspace_effective_scroll_offset() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        // Add enough windows to have scrollable content
+        for i in 1..=5 {
+            ws.insert_window(i, Some(400)).unwrap();
+        }
+        // Total: 10 + (5*400) + (4*10) + 10 = 2060
+
+        // Initially no animation
+        assert!((ws.effective_scroll_offset() - 0.0).abs() < 1.0);
+
+        // Start animation to 200 with viewport 500 (max_scroll = 1560)
+        ws.start_scroll_animation(200.0, 500, Some(100), Some(Easing::Linear));
+        assert!(ws.is_animating());
+
+        // At start, should be near 0
+        assert!(ws.effective_scroll_offset() < 10.0);
+
+        // Tick halfway
+        ws.tick_animation(50);
+        // Should be around 100 (halfway)
+        assert!(ws.effective_scroll_offset() > 80.0 && ws.effective_scroll_offset() < 120.0);
+    }
+
+    #[test]
+    fn test_workspace_start_scroll_animation_clamps_target() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.insert_window(1, Some(400)).unwrap();
+
+        // Total width: 10 + 400 + 10 = 420
+        // Max scroll with 1000 viewport = max(420 - 1000, 0) = 0
+
+        ws.start_scroll_animation(500.0, 1000, None, None);
+
+        // Target should be clamped to 0 (can't scroll past content)
+        assert!(!ws.is_animating()); // Already at target (both clamped to 0)
+    }
+
+    #[test]
+    fn test_workspace_tick_animation() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(400)).unwrap();
+        ws.insert_window(3, Some(400)).unwrap();
+
+        ws.start_scroll_animation(200.0, 500, Some(100), Some(Easing::Linear));
+
+        // Should be animating
+        assert!(ws.tick_animation(30));
+        assert!(ws.is_animating());
+
+        // Tick to completion
+        assert!(!ws.tick_animation(100));
+        assert!(!ws.is_animating());
+
+        // After animation, scroll_offset should be at target
+        assert!((ws.effective_scroll_offset() - 200.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_workspace_stop_animation() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(400)).unwrap();
+        ws.insert_window(3, Some(400)).unwrap();
+
+        ws.start_scroll_animation(200.0, 500, Some(100), Some(Easing::Linear));
+        ws.tick_animation(50);
+
+        // Stop should snap to target
+        ws.stop_animation();
+        assert!(!ws.is_animating());
+        assert!((ws.effective_scroll_offset() - 200.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_workspace_cancel_animation() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(400)).unwrap();
+        ws.insert_window(3, Some(400)).unwrap();
+
+        ws.start_scroll_animation(200.0, 500, Some(100), Some(Easing::Linear));
+        ws.tick_animation(50);
+
+        let current = ws.effective_scroll_offset();
+        // Should be around 100 (halfway)
+
+        // Cancel should stay at current position
+        ws.cancel_animation();
+        assert!(!ws.is_animating());
+        assert!((ws.effective_scroll_offset() - current).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_workspace_animation_no_effect_when_at_target() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.insert_window(1, Some(400)).unwrap();
+
+        // Already at offset 0, trying to animate to 0 shouldn't start animation
+        ws.start_scroll_animation(0.0, 1000, None, None);
+        assert!(!ws.is_animating());
+    }
+
+    #[test]
+    fn test_workspace_animation_interrupt() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(400)).unwrap();
+        ws.insert_window(3, Some(400)).unwrap();
+
+        // Start animation to 200
+        ws.start_scroll_animation(200.0, 500, Some(100), Some(Easing::Linear));
+        ws.tick_animation(50);
+
+        // Interrupt with new animation to 300
+        ws.start_scroll_animation(300.0, 500, Some(100), Some(Easing::Linear));
+
+        // New animation should start from current position (~100)
+        assert!(ws.is_animating());
+
+        // Complete new animation
+        ws.tick_animation(150);
+        assert!((ws.effective_scroll_offset() - 300.0).abs() < 1.0);
+    }
+
+    #[test]
+    fn test_compute_placements_animated() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(400)).unwrap();
+
+        let viewport = Rect::new(0, 0, 500, 600);
+
+        // Without animation
+        let placements1 = ws.compute_placements_animated(viewport);
+        assert_eq!(placements1.len(), 2);
+
+        // Start animation that shifts viewport
+        ws.start_scroll_animation(200.0, 500, Some(100), Some(Easing::Linear));
+        ws.tick_animation(100); // Complete
+
+        let placements2 = ws.compute_placements_animated(viewport);
+        assert_eq!(placements2.len(), 2);
+
+        // Window positions should be shifted left (viewport scrolled right)
+        assert!(placements2[0].rect.x < placements1[0].rect.x);
+    }
+
+    #[test]
+    fn test_ensure_focused_visible_animated_center_mode() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        // Add enough windows to require scrolling
+        for i in 1..=5 {
+            ws.insert_window(i, Some(400)).unwrap();
+        }
+        // Total: 10 + (5*400) + (4*10) + 10 = 2060
+
+        // Focus is at column 4 (last inserted), scroll to make it visible first
+        ws.ensure_focused_visible(500);
+
+        // Now focus first column which is off-screen
+        ws.focus_left();
+        ws.focus_left();
+        ws.focus_left();
+        ws.focus_left();
+
+        // This should trigger an animation because column 0 is now off-screen
+        ws.ensure_focused_visible_animated(500);
+
+        // Should start an animation to scroll back to column 0
+        assert!(ws.is_animating());
+    }
+
+    // ========================================================================
+    // Floating Window Tests
+    // ========================================================================
+
     #[test]
-    fn test_remove_window() {
+    fn test_add_floating_window() {
         let mut ws = Workspace::new();
-        ws.insert_window(1, Some(400)).unwrap();
-        ws.insert_window(2, Some(400)).unwrap();
-        ws.insert_window(3, Some(400)).unwrap();
-
-        assert_eq!(ws.column_count(), 3);
+        let rect = Rect::new(100, 100, 400, 300);
 
-        ws.remove_window(2).unwrap();
-        assert_eq!(ws.column_count(), 2);
+        ws.add_floating(1, rect).unwrap();
 
-        // Windows 1 and 3 should remain
-        assert!(ws
-            .columns()
-            .iter()
-            .any(|c| c.contains(1)));
-        assert!(ws
-            .columns()
-            .iter()
-            .any(|c| c.contains(3)));
+        assert!(ws.contains_window(1));
+        assert!(ws.is_floating(1));
+        assert_eq!(ws.floating_count(), 1);
+        assert_eq!(ws.column_count(), 0); // Not in columns
     }
 
     #[test]
-    fn test_compute_placements_visibility() {
-        let mut ws = Workspace::with_gaps(10, 10);
-        ws.insert_window(1, Some(400)).unwrap(); // x: 10-410
-        ws.insert_window(2, Some(400)).unwrap(); // x: 420-820
-        ws.insert_window(3, Some(400)).unwrap(); // x: 830-1230
+    fn test_floating_window_in_placements() {
+        let mut ws = Workspace::new();
+        let rect = Rect::new(100, 100, 400, 300);
+        let viewport = Rect::new(0, 0, 1920, 1080);
 
-        ws.test_set_scroll_offset(0.0);
+        ws.add_floating(1, rect).unwrap();
 
-        // Viewport of 500px wide starting at (0, 0)
-        let viewport = Rect::new(0, 0, 500, 600);
         let placements = ws.compute_placements(viewport);
+        assert_eq!(placements.len(), 1);
+        assert_eq!(placements[0].window_id, 1);
+        assert_eq!(placements[0].rect, rect);
+        assert_eq!(placements[0].visibility, Visibility::Visible);
+        assert_eq!(placements[0].column_index, usize::MAX); // Floating sentinel
+    }
 
-        assert_eq!(placements.len(), 3);
+    #[test]
+    fn test_remove_floating_window() {
+        let mut ws = Workspace::new();
+        let rect = Rect::new(100, 100, 400, 300);
 
-        // First column should be visible
-        assert_eq!(placements[0].visibility, Visibility::Visible);
-        assert_eq!(placements[0].window_id, 1);
+        ws.add_floating(1, rect).unwrap();
+        assert!(ws.contains_window(1));
 
-        // Second column partially visible
-        assert_eq!(placements[1].visibility, Visibility::Visible);
+        let removed = ws.remove_floating(1);
+        assert!(removed);
+        assert!(!ws.contains_window(1));
+        assert_eq!(ws.floating_count(), 0);
+    }
 
-        // Third column off-screen right
-        assert_eq!(placements[2].visibility, Visibility::OffScreenRight);
+    #[test]
+    fn test_remove_nonexistent_floating_window() {
+        let mut ws = Workspace::new();
+        let removed = ws.remove_floating(999);
+        assert!(!removed);
     }
 
     #[test]
-    fn test_ensure_focused_visible_center() {
+    fn test_update_floating_window() {
+        let mut ws = Workspace::new();
+        let rect1 = Rect::new(100, 100, 400, 300);
+        let rect2 = Rect::new(200, 200, 600, 400);
+
+        ws.add_floating(1, rect1).unwrap();
+
+        let updated = ws.update_floating(1, rect2);
+        assert!(updated);
+
+        let placements = ws.compute_placements(Rect::new(0, 0, 1920, 1080));
+        assert_eq!(placements[0].rect, rect2);
+    }
+
+    #[test]
+    fn test_floating_and_tiled_windows_together() {
         let mut ws = Workspace::with_gaps(10, 10);
-        ws.set_centering_mode(CenteringMode::Center);
+        let floating_rect = Rect::new(500, 500, 300, 200);
+        let viewport = Rect::new(0, 0, 1920, 1080);
 
+        // Add tiled window
         ws.insert_window(1, Some(400)).unwrap();
         ws.insert_window(2, Some(400)).unwrap();
-        ws.insert_window(3, Some(400)).unwrap();
 
-        ws.test_set_focus_unchecked(0, 0);
-        ws.test_set_scroll_offset(500.0); // Start scrolled right
+        // Add floating window
+        ws.add_floating(3, floating_rect).unwrap();
 
-        ws.ensure_focused_visible(500);
+        assert_eq!(ws.column_count(), 2);
+        assert_eq!(ws.floating_count(), 1);
+        assert!(ws.contains_window(1));
+        assert!(ws.contains_window(2));
+        assert!(ws.contains_window(3));
+        assert!(!ws.is_floating(1));
+        assert!(!ws.is_floating(2));
+        assert!(ws.is_floating(3));
 
-        // Should center column 0 in the viewport
-        // Column 0 is at x=10, width=400, center=210
-        // Viewport width=500, center=250
-        // scroll_offset = 210 - 250 = -40, clamped to 0
-        assert_eq!(ws.scroll_offset(), 0.0);
+        let placements = ws.compute_placements(viewport);
+        assert_eq!(placements.len(), 3);
+
+        // Floating window should be last in placements
+        assert_eq!(placements[2].window_id, 3);
+        assert_eq!(placements[2].rect, floating_rect);
     }
 
     #[test]
-    fn test_stacked_windows() {
+    fn test_duplicate_floating_window_rejected() {
         let mut ws = Workspace::new();
-        ws.insert_window(1, Some(400)).unwrap();
-        ws.insert_window_in_column(2, 0).unwrap();
-        ws.insert_window_in_column(3, 0).unwrap();
-
-        assert_eq!(ws.column_count(), 1);
-        assert_eq!(ws.columns()[0].len(), 3);
+        let rect = Rect::new(100, 100, 400, 300);
 
-        let viewport = Rect::new(0, 0, 500, 600);
-        let placements = ws.compute_placements(viewport);
+        ws.add_floating(1, rect).unwrap();
 
-        assert_eq!(placements.len(), 3);
-        // All three windows should be in the same column
-        assert!(placements.iter().all(|p| p.column_index == 0));
+        let result = ws.add_floating(1, rect);
+        assert!(matches!(result, Err(LayoutError::DuplicateWindow(1))));
     }
 
     #[test]
-    fn test_resize_column() {
+    fn test_floating_window_duplicate_with_tiled() {
         let mut ws = Workspace::new();
+
+        // Add tiled window
         ws.insert_window(1, Some(400)).unwrap();
 
-        assert_eq!(ws.columns()[0].width(), 400);
+        // Try to add same ID as floating - should fail
+        let result = ws.add_floating(1, Rect::new(100, 100, 400, 300));
+        assert!(matches!(result, Err(LayoutError::DuplicateWindow(1))));
+    }
 
-        ws.resize_focused_column(100);
-        assert_eq!(ws.columns()[0].width(), 500);
+    // ====================================================================
+    // Fullscreen Tests
+    // ====================================================================
 
-        ws.resize_focused_column(-200);
-        assert_eq!(ws.columns()[0].width(), 300);
+    #[test]
+    fn test_fullscreen_toggle() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, Some(400)).unwrap();
 
-        // Should not go below minimum (100)
-        ws.resize_focused_column(-500);
-        assert_eq!(ws.columns()[0].width(), 100);
+        assert!(!ws.is_fullscreen());
+
+        // Enter fullscreen
+        let entered = ws.toggle_fullscreen(Rect::new(0, 0, 1920, 1080));
+        assert!(entered);
+        assert!(ws.is_fullscreen());
+        assert_eq!(ws.fullscreen_window_id(), Some(1));
+
+        // Exit fullscreen
+        let entered = ws.toggle_fullscreen(Rect::new(0, 0, 1920, 1080));
+        assert!(!entered);
+        assert!(!ws.is_fullscreen());
+        assert_eq!(ws.fullscreen_window_id(), None);
     }
 
     #[test]
-    fn test_move_column() {
+    fn test_fullscreen_empty_workspace() {
         let mut ws = Workspace::new();
+        let entered = ws.toggle_fullscreen(Rect::new(0, 0, 1920, 1080));
+        assert!(!entered);
+        assert!(!ws.is_fullscreen());
+    }
+
+    #[test]
+    fn test_fullscreen_placements() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        let viewport = Rect::new(0, 0, 1920, 1080);
+
         ws.insert_window(1, Some(400)).unwrap();
         ws.insert_window(2, Some(400)).unwrap();
         ws.insert_window(3, Some(400)).unwrap();
 
-        ws.test_set_focus_unchecked(1, 0);
-        ws.move_column_left();
+        // Focus window 2 and make it fullscreen
+        ws.focus_left();
+        assert_eq!(ws.focused_window(), Some(2));
+        ws.toggle_fullscreen(viewport);
 
-        assert_eq!(ws.focused_column_index(), 0);
-        assert_eq!(ws.columns()[0].get(0), Some(2));
-        assert_eq!(ws.columns()[1].get(0), Some(1));
+        let placements = ws.compute_placements(viewport);
+        assert_eq!(placements.len(), 3);
 
-        ws.move_column_right();
-        assert_eq!(ws.focused_column_index(), 1);
-        assert_eq!(ws.columns()[0].get(0), Some(1));
-        assert_eq!(ws.columns()[1].get(0), Some(2));
+        // Window 2 should cover the full viewport
+        let fs = placements.iter().find(|p| p.window_id == 2).unwrap();
+        assert_eq!(fs.rect, viewport);
+        assert_eq!(fs.visibility, Visibility::Visible);
+
+        // Others should be off-screen
+        let w1 = placements.iter().find(|p| p.window_id == 1).unwrap();
+        assert_eq!(w1.visibility, Visibility::OffScreenLeft);
+
+        let w3 = placements.iter().find(|p| p.window_id == 3).unwrap();
+        assert_eq!(w3.visibility, Visibility::OffScreenLeft);
     }
 
     #[test]
-    fn test_scroll_by() {
+    fn test_fullscreen_animated_placements() {
         let mut ws = Workspace::with_gaps(10, 10);
+        let viewport = Rect::new(0, 0, 1920, 1080);
+
         ws.insert_window(1, Some(400)).unwrap();
-        ws.insert_window(2, Some(400)).unwrap();
-        ws.insert_window(3, Some(400)).unwrap();
+        ws.toggle_fullscreen(viewport);
 
-        let viewport_width = 500;
+        let placements = ws.compute_placements_animated(viewport);
+        assert_eq!(placements.len(), 1);
+        assert_eq!(placements[0].rect, viewport);
+        assert_eq!(placements[0].visibility, Visibility::Visible);
+    }
 
-        ws.scroll_by(100.0, viewport_width);
-        assert_eq!(ws.scroll_offset(), 100.0);
+    #[test]
+    fn test_fullscreen_covers_monitor_not_work_area() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        let work_area = Rect::new(0, 0, 1920, 1040);
+        let monitor = Rect::new(0, 0, 1920, 1080);
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.add_floating(9, Rect::new(100, 100, 300, 200)).unwrap();
+        ws.toggle_fullscreen(monitor);
 
-        ws.scroll_by(2000.0, viewport_width);
-        // Should clamp to max scroll
-        let max_scroll = (ws.total_width() - viewport_width).max(0) as f64;
-        assert_eq!(ws.scroll_offset(), max_scroll);
+        let placements = ws.compute_placements(work_area);
+        let fs = placements.iter().find(|p| p.window_id == 1).unwrap();
+        assert_eq!(fs.rect, monitor);
+        let floating = placements.iter().find(|p| p.window_id == 9).unwrap();
+        assert_eq!(floating.visibility, Visibility::OffScreenLeft);
 
-        ws.scroll_by(-5000.0, viewport_width);
+        // A resolution change moves the fullscreen window along
+        let resized = Rect::new(0, 0, 2560, 1440);
+        ws.set_fullscreen_rect(resized);
+        assert_eq!(ws.compute_placements(work_area)[0].rect, resized);
+    }
+
+    #[test]
+    fn test_fullscreen_exit_restores_strip() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        let viewport = Rect::new(0, 0, 1920, 1080);
+        for id in 1..=4 {
+            ws.insert_window(id, Some(800)).unwrap();
+        }
+        ws.set_focus(1, 0).unwrap();
+        ws.ensure_focused_visible(1920);
+        let before_offset = ws.scroll_offset();
+        let rects = |ws: &Workspace| -> Vec<Rect> { ws.compute_placements(viewport).iter().map(|p| p.rect).collect() };
+        let before = rects(&ws);
+        ws.toggle_fullscreen(viewport);
+
+        // The strip changes while the window is fullscreen
+        ws.resize_focused_column(400);
+        ws.set_focus(3, 0).unwrap();
+        ws.ensure_focused_visible(1920);
+
+        assert!(!ws.toggle_fullscreen(viewport));
+        assert_eq!(ws.focused_window(), Some(2));
+        assert_eq!(ws.scroll_offset(), before_offset);
+        assert_eq!(rects(&ws), before);
+    }
+
+    #[test]
+    fn test_exit_fullscreen_clamps_scroll_after_columns_close() {
+        let viewport = Rect::new(0, 0, 1920, 1080);
+        let mut ws = Workspace::new();
+        for id in 1..=4 {
+            ws.insert_window(id, Some(800)).unwrap();
+        }
+        ws.ensure_focused_visible(1920);
+        assert!(ws.scroll_offset() > 0.0);
+        ws.toggle_fullscreen(viewport);
+
+        ws.remove_window(1).unwrap();
+        ws.remove_window(2).unwrap();
+
+        assert!(!ws.toggle_fullscreen(viewport));
+        assert_eq!(ws.focused_window(), Some(4));
         assert_eq!(ws.scroll_offset(), 0.0);
+        ws.check_invariants(1920).unwrap();
     }
 
     #[test]
-    fn test_rect_intersects() {
-        let r1 = Rect::new(0, 0, 100, 100);
-        let r2 = Rect::new(50, 50, 100, 100);
-        let r3 = Rect::new(200, 200, 50, 50);
+    fn test_fullscreen_cleared_when_window_closes() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(400)).unwrap();
+        ws.toggle_fullscreen(Rect::new(0, 0, 1920, 1080));
+        assert_eq!(ws.fullscreen_window_id(), Some(2));
 
-        assert!(r1.intersects(&r2));
-        assert!(r2.intersects(&r1));
-        assert!(!r1.intersects(&r3));
-        assert!(!r3.intersects(&r1));
+        ws.remove_window(2).unwrap();
+        assert!(!ws.is_fullscreen());
+        assert!(ws.compute_placements(Rect::new(0, 0, 1920, 1080))
+            .iter()
+            .all(|p| p.visibility == Visibility::Visible));
     }
 
-    // ====== Tests added from code review (Cycle 1) ======
+    // ====================================================================
+    // Toggle Floating Tests
+    // ====================================================================
 
     #[test]
-    fn test_remove_last_window_empties_workspace() {
+    fn test_toggle_floating_tiled_to_float() {
         let mut ws = Workspace::new();
-        ws.insert_window(1, Some(400)).unwrap();
+        let viewport = Rect::new(0, 0, 1920, 1080);
 
-        ws.remove_window(1).unwrap();
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(400)).unwrap();
 
-        assert!(ws.is_empty());
-        assert_eq!(ws.column_count(), 0);
-        assert_eq!(ws.focused_column_index(), 0);
-        assert_eq!(ws.focused_window_index_in_column(), 0);
-        assert_eq!(ws.scroll_offset(), 0.0);
+        // Focus window 2 and toggle to floating
+        let wid = ws.toggle_floating(viewport);
+        assert_eq!(wid, Some(2));
+        assert!(ws.is_floating(2));
+        assert_eq!(ws.column_count(), 1); // Only window 1 tiled
+        assert_eq!(ws.floating_count(), 1);
     }
 
     #[test]
-    fn test_ensure_focused_visible_just_in_view() {
-        let mut ws = Workspace::with_gaps(10, 10);
-        ws.set_centering_mode(CenteringMode::JustInView);
+    fn test_toggle_floating_back() {
+        let mut ws = Workspace::new();
+        let viewport = Rect::new(0, 0, 1920, 1080);
 
-        ws.insert_window(1, Some(200)).unwrap(); // x: 10-210
-        ws.insert_window(2, Some(200)).unwrap(); // x: 220-420
-        ws.insert_window(3, Some(200)).unwrap(); // x: 430-630
+        ws.insert_window(1, Some(400)).unwrap();
 
-        ws.test_set_focus_unchecked(0, 0);
-        ws.test_set_scroll_offset(0.0);
+        // Toggle to floating
+        let wid = ws.toggle_floating(viewport);
+        assert_eq!(wid, Some(1));
+        assert!(ws.is_floating(1));
+        assert_eq!(ws.column_count(), 0);
 
-        // Column 0 is already in view - should NOT scroll
-        ws.ensure_focused_visible(500);
-        assert_eq!(ws.scroll_offset(), 0.0);
+        // Use unfloat to bring it back
+        let ok = ws.unfloat_window(1);
+        assert!(ok);
+        assert!(!ws.is_floating(1));
+        assert_eq!(ws.column_count(), 1);
+    }
 
-        // Focus column 2, which is partially out of view
-        ws.test_set_focus_unchecked(2, 0);
-        ws.ensure_focused_visible(500);
-        // Should scroll just enough to bring column 2 into view
-        assert!(ws.scroll_offset() > 0.0);
+    #[test]
+    fn test_toggle_floating_empty_workspace() {
+        let mut ws = Workspace::new();
+        let viewport = Rect::new(0, 0, 1920, 1080);
+        let wid = ws.toggle_floating(viewport);
+        assert_eq!(wid, None);
     }
 
+    // ====================================================================
+    // Column Width Preset Tests
+    // ====================================================================
+
     #[test]
-    fn test_compute_placements_tight_viewport() {
-        let mut ws = Workspace::with_gaps(10, 50); // Large outer_gap
+    fn test_set_column_width_fraction() {
+        let mut ws = Workspace::with_gaps(10, 10);
         ws.insert_window(1, Some(400)).unwrap();
-        ws.insert_window_in_column(2, 0).unwrap();
-        ws.insert_window_in_column(3, 0).unwrap();
 
-        // Viewport smaller than outer_gaps * 2
-        let viewport = Rect::new(0, 0, 500, 80); // Only 80px tall
-        let placements = ws.compute_placements(viewport);
+        // Set to half viewport (1920 - 20 outer gaps = 1900 usable)
+        ws.set_focused_column_width_fraction(0.5, 1920);
+        assert_eq!(ws.columns()[0].width(), 950); // 1900 * 0.5
 
-        // All heights should be >= 0
-        for p in &placements {
-            assert!(p.rect.height >= 0, "Height was negative: {}", p.rect.height);
-        }
+        // Set to one-third
+        ws.set_focused_column_width_fraction(0.333, 1920);
+        assert_eq!(ws.columns()[0].width(), 633); // round(1900 * 0.333)
     }
 
     #[test]
-    fn test_insert_window_clamps_width() {
+    fn test_set_column_width_fraction_clamp() {
         let mut ws = Workspace::new();
+        ws.insert_window(1, Some(400)).unwrap();
 
-        // Try to insert with zero width
-        ws.insert_window(1, Some(0)).unwrap();
-        assert_eq!(ws.columns()[0].width(), 100); // Clamped to minimum
-
-        // Try to insert with negative width
-        ws.insert_window(2, Some(-50)).unwrap();
-        assert_eq!(ws.columns()[1].width(), 100); // Clamped to minimum
+        // Fraction is clamped to [0.1, 1.0]
+        // Default outer_gap = 10, so usable = 1920 - 20 = 1900
+        ws.set_focused_column_width_fraction(2.0, 1920);
+        let w = ws.columns()[0].width();
+        // fraction clamped to 1.0: (1920 - 20 outer) * 1.0 = 1900
+        assert_eq!(w, 1900);
     }
 
     #[test]
-    fn test_scroll_offset_rounding() {
+    fn test_set_window_column_width_fraction() {
         let mut ws = Workspace::with_gaps(10, 10);
         ws.insert_window(1, Some(400)).unwrap();
         ws.insert_window(2, Some(400)).unwrap();
 
-        // Set fractional scroll offset
-        ws.test_set_scroll_offset(100.7);
-
-        let viewport = Rect::new(0, 0, 500, 600);
-        let placements = ws.compute_placements(viewport);
+        // Unfocused column is resized; focus is unchanged
+        ws.set_window_column_width_fraction(1, 0.5, 1920).unwrap();
+        assert_eq!(ws.columns()[0].width(), 950);
+        assert_eq!(ws.columns()[1].width(), 400);
+        assert_eq!(ws.focused_window(), Some(2));
 
-        // Verify placements use rounded value (101, not truncated 100)
-        // The first column at x=10 should be at screen x = 10 - 101 + 0 = -91
-        assert_eq!(placements[0].rect.x, -91);
+        assert!(matches!(
+            ws.set_window_column_width_fraction(99, 0.5, 1920),
+            Err(LayoutError::WindowNotFound(99))
+        ));
     }
 
-    // ====== Tests added from code review (Cycle 2) ======
-
     #[test]
-    fn test_column_empty_constructor() {
-        let col = Column::empty(50);
-        assert_eq!(col.width(), 100); // Clamped to MIN_COLUMN_WIDTH
-        assert!(col.is_empty());
-        assert_eq!(col.len(), 0);
-    }
+    fn test_equalize_widths() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.insert_window(1, Some(300)).unwrap();
+        ws.insert_window(2, Some(600)).unwrap();
+        ws.insert_window(3, Some(400)).unwrap();
 
-    #[test]
-    fn test_rect_right_and_bottom() {
-        let r = Rect::new(10, 20, 100, 50);
-        assert_eq!(r.right(), 110);
-        assert_eq!(r.bottom(), 70);
+        // 3 columns, viewport 1920
+        // total_gaps = 10*2 + 10*2 = 40
+        // per_column = (1920 - 40) / 3 = 626
+        ws.equalize_column_widths(1920);
 
-        // Edge case: negative coordinates
-        let r2 = Rect::new(-50, -30, 100, 80);
-        assert_eq!(r2.right(), 50);
-        assert_eq!(r2.bottom(), 50);
+        assert_eq!(ws.columns()[0].width(), 626);
+        assert_eq!(ws.columns()[1].width(), 626);
+        assert_eq!(ws.columns()[2].width(), 626);
     }
 
     #[test]
-    fn test_focus_operations_on_empty_workspace() {
+    fn test_equalize_widths_empty() {
         let mut ws = Workspace::new();
-
-        // All focus operations should safely do nothing
-        ws.focus_left();
-        ws.focus_right();
-        ws.focus_up();
-        ws.focus_down();
-
-        assert!(ws.focused_window().is_none());
-        assert_eq!(ws.focused_column_index(), 0);
-        assert_eq!(ws.focused_window_index_in_column(), 0);
+        ws.equalize_column_widths(1920); // Should not panic
     }
 
     #[test]
-    fn test_remove_nonexistent_window() {
+    fn test_unfloat_nonexistent() {
         let mut ws = Workspace::new();
-        let result = ws.remove_window(999);
-        assert!(matches!(result, Err(LayoutError::WindowNotFound(999))));
+        assert!(!ws.unfloat_window(999));
     }
 
     #[test]
-    fn test_remove_window_adjusts_focus_correctly() {
+    fn test_refloat_restores_last_floating_rect() {
         let mut ws = Workspace::new();
+        let viewport = Rect::new(0, 0, 1920, 1080);
         ws.insert_window(1, Some(400)).unwrap();
-        ws.insert_window(2, Some(400)).unwrap();
-        ws.insert_window(3, Some(400)).unwrap();
 
-        // Focus on column 2 (window 3)
-        ws.test_set_focus_unchecked(2, 0);
+        ws.toggle_floating(viewport);
+        let moved = Rect::new(100, 150, 640, 480);
+        assert!(ws.update_floating(1, moved));
 
-        // Remove from column 0
-        ws.remove_window(1).unwrap();
+        assert!(ws.unfloat_window(1));
+        assert_eq!(ws.floating_restore_rect(1), Some(moved));
 
-        // Focus should adjust: was 2, column 0 removed, now should be 1
-        assert_eq!(ws.focused_column_index(), 1);
-        assert_eq!(ws.focused_window(), Some(3));
+        ws.toggle_floating(viewport);
+        assert_eq!(ws.floating_windows()[0].rect, moved);
+        assert_eq!(ws.floating_restore_rect(1), None);
     }
 
     #[test]
-    fn test_duplicate_window_rejected() {
+    fn test_refloat_ignores_rect_outside_viewport() {
         let mut ws = Workspace::new();
-        ws.insert_window(42, Some(400)).unwrap();
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.set_floating_restore_rect(1, Rect::new(3000, 0, 640, 480));
 
-        // Try to insert same window as new column
-        let result = ws.insert_window(42, Some(400));
-        assert!(matches!(result, Err(LayoutError::DuplicateWindow(42))));
+        ws.toggle_floating(Rect::new(0, 0, 1920, 1080));
+        assert_eq!(ws.floating_windows()[0].rect, Rect::new(560, 240, 800, 600));
+    }
 
-        // Try to insert same window into existing column
-        let result = ws.insert_window_in_column(42, 0);
-        assert!(matches!(result, Err(LayoutError::DuplicateWindow(42))));
+    #[test]
+    fn test_forget_floating_rect() {
+        let mut ws = Workspace::new();
+        ws.set_floating_restore_rect(7, Rect::new(10, 20, 300, 200));
+        ws.set_floating_restore_rect(8, Rect::new(0, 0, 300, 200));
+        assert_eq!(ws.floating_restore_rects().count(), 2);
 
-        // Workspace should still have only one column with one window
-        assert_eq!(ws.column_count(), 1);
-        assert_eq!(ws.columns()[0].len(), 1);
+        ws.forget_floating_rect(7);
+        assert_eq!(ws.floating_restore_rect(7), None);
+        assert_eq!(ws.floating_restore_rects().collect::<Vec<_>>(), vec![(8, Rect::new(0, 0, 300, 200))]);
     }
 
+    // ====================================================================
+    // Drag-and-Drop Positioning Tests
+    // ====================================================================
+
     #[test]
-    fn test_rect_clamps_negative_dimensions() {
-        let r = Rect::new(10, 20, -100, -50);
-        assert_eq!(r.width, 0);
-        assert_eq!(r.height, 0);
-        assert_eq!(r.x, 10);
-        assert_eq!(r.y, 20);
+    fn test_insertion_index_at_midpoints() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        let viewport = Rect::new(0, 0, 1920, 1080);
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(400)).unwrap();
+
+        // Column 0 spans 10..410 (midpoint 210), column 1 spans 420..820 (midpoint 620)
+        assert_eq!(ws.insertion_index_at(0, viewport), 0);
+        assert_eq!(ws.insertion_index_at(209, viewport), 0);
+        assert_eq!(ws.insertion_index_at(210, viewport), 1);
+        assert_eq!(ws.insertion_index_at(619, viewport), 1);
+        assert_eq!(ws.insertion_index_at(620, viewport), 2);
+        assert_eq!(ws.insertion_index_at(5000, viewport), 2);
     }
 
     #[test]
-    fn test_total_width_saturates() {
-        let mut ws = Workspace::new();
+    fn test_insertion_index_at_accounts_for_scroll_and_viewport_origin() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        let viewport = Rect::new(1920, 0, 1920, 1080);
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(400)).unwrap();
+        ws.test_set_scroll_offset(300.0);
 
-        // Insert many columns with large widths to test saturation
-        for i in 0..1000 {
-            ws.insert_window(i, Some(i32::MAX / 100)).unwrap();
-        }
+        // Screen x 1920 + 310 -> strip x 610, left of column 1's midpoint
+        assert_eq!(ws.insertion_index_at(2230, viewport), 1);
+        assert_eq!(ws.insertion_index_at(2240, viewport), 2);
+    }
 
-        // Should saturate to i32::MAX instead of overflowing/panicking
-        let width = ws.total_width();
-        assert!(width > 0); // Should not wrap to negative
-        assert_eq!(width, i32::MAX); // Should saturate at max
+    #[test]
+    fn test_insertion_slot_rect_positions() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        let viewport = Rect::new(0, 0, 1920, 1080);
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(400)).unwrap();
+
+        // Slot 1 is the gap between the columns, centered at strip x 415
+        let rect = ws.insertion_slot_rect(1, viewport);
+        assert_eq!(rect.x, 415 - INSERTION_HINT_WIDTH / 2);
+        assert_eq!(rect.width, INSERTION_HINT_WIDTH);
+        assert_eq!(rect.y, 10);
+        assert_eq!(rect.height, 1060);
+
+        // Slot past the end sits after the last column
+        let rect = ws.insertion_slot_rect(2, viewport);
+        assert_eq!(rect.x, 825 - INSERTION_HINT_WIDTH / 2);
+
+        // Out-of-range indices clamp to the end slot
+        assert_eq!(ws.insertion_slot_rect(99, viewport), rect);
     }
 
-    // ====== Tests added from code review (Cycle 4) ======
+    #[test]
+    fn test_insertion_slot_rect_empty_workspace() {
+        let ws = Workspace::with_gaps(10, 10);
+        let rect = ws.insertion_slot_rect(0, Rect::new(0, 0, 1920, 1080));
+        assert_eq!(rect.x, 10 - INSERTION_HINT_WIDTH / 2);
+    }
 
     #[test]
-    fn test_focus_window_by_id() {
+    fn test_move_window_to_index_reorders_columns() {
         let mut ws = Workspace::new();
-        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(1, Some(300)).unwrap();
         ws.insert_window(2, Some(400)).unwrap();
-        ws.insert_window(3, Some(400)).unwrap();
+        ws.insert_window(3, Some(500)).unwrap();
 
-        // Focus is on column 2 (window 3) after inserts
-        assert_eq!(ws.focused_window(), Some(3));
+        // Move window 1 to the end
+        ws.move_window_to_index(1, 3).unwrap();
+        let order: Vec<_> = ws.columns().iter().map(|c| c.windows()[0]).collect();
+        assert_eq!(order, vec![2, 3, 1]);
+        assert_eq!(ws.focused_window(), Some(1));
+        assert_eq!(ws.columns()[2].width(), 300);
 
-        // Focus window 1 by ID
-        ws.focus_window(1).unwrap();
+        // Move window 1 back to the front
+        ws.move_window_to_index(1, 0).unwrap();
+        let order: Vec<_> = ws.columns().iter().map(|c| c.windows()[0]).collect();
+        assert_eq!(order, vec![1, 2, 3]);
         assert_eq!(ws.focused_column_index(), 0);
-        assert_eq!(ws.focused_window(), Some(1));
+    }
 
-        // Focus window 2 by ID
-        ws.focus_window(2).unwrap();
-        assert_eq!(ws.focused_column_index(), 1);
+    #[test]
+    fn test_move_window_to_index_own_slots_are_noop() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, None).unwrap();
+        ws.insert_window(2, None).unwrap();
+        ws.insert_window(3, None).unwrap();
+
+        ws.move_window_to_index(2, 1).unwrap();
+        ws.move_window_to_index(2, 2).unwrap();
+        let order: Vec<_> = ws.columns().iter().map(|c| c.windows()[0]).collect();
+        assert_eq!(order, vec![1, 2, 3]);
         assert_eq!(ws.focused_window(), Some(2));
-
-        // Try to focus nonexistent window
-        let result = ws.focus_window(999);
-        assert!(matches!(result, Err(LayoutError::WindowNotFound(999))));
     }
 
     #[test]
-    fn test_set_focus_validates() {
+    fn test_move_window_to_index_splits_stacked_column() {
         let mut ws = Workspace::new();
-        ws.insert_window(1, Some(400)).unwrap();
-        ws.insert_window(2, Some(400)).unwrap();
-        ws.insert_window_in_column(3, 1).unwrap(); // Stack window 3 in column 1
-
-        // Valid focus
-        ws.set_focus(1, 1).unwrap();
-        assert_eq!(ws.focused_column_index(), 1);
-        assert_eq!(ws.focused_window_index_in_column(), 1);
-        assert_eq!(ws.focused_window(), Some(3));
+        ws.insert_window(1, None).unwrap();
+        ws.insert_window_in_column(2, 0).unwrap();
+        ws.insert_window(3, None).unwrap();
 
-        // Invalid column index
-        let result = ws.set_focus(5, 0);
-        assert!(matches!(result, Err(LayoutError::ColumnOutOfBounds(5, 1))));
+        // Pull window 2 out of the stack into its own column between 1 and 3
+        ws.move_window_to_index(2, 1).unwrap();
+        assert_eq!(ws.column_count(), 3);
+        assert_eq!(ws.columns()[0].windows(), &[1]);
+        assert_eq!(ws.columns()[1].windows(), &[2]);
+        assert_eq!(ws.columns()[2].windows(), &[3]);
+        assert_eq!(ws.focused_window(), Some(2));
+    }
 
-        // Invalid window index in column
-        let result = ws.set_focus(0, 10);
-        assert!(matches!(result, Err(LayoutError::WindowIndexOutOfBounds(10, 0, 0))));
+    #[test]
+    fn test_move_window_to_index_not_found() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, None).unwrap();
+        assert!(matches!(
+            ws.move_window_to_index(99, 0),
+            Err(LayoutError::WindowNotFound(99))
+        ));
     }
+    // ====================================================================
+    // Column Collapsing Tests
+    // ====================================================================
 
     #[test]
-    fn test_scroll_by_special_floats() {
+    fn test_collapse_focused_column_shrinks_strip() {
         let mut ws = Workspace::with_gaps(10, 10);
         ws.insert_window(1, Some(400)).unwrap();
-        ws.insert_window(2, Some(400)).unwrap();
-
-        let viewport_width = 500;
-
-        // Scroll to a known position
-        ws.scroll_by(50.0, viewport_width);
-        assert_eq!(ws.scroll_offset(), 50.0);
-
-        // NaN should be treated as zero (no change)
-        ws.scroll_by(f64::NAN, viewport_width);
-        assert_eq!(ws.scroll_offset(), 50.0);
+        ws.insert_window(2, Some(600)).unwrap();
+        assert_eq!(ws.total_width(), 10 + 400 + 10 + 600 + 10);
 
-        // Infinity should be treated as zero (no change)
-        ws.scroll_by(f64::INFINITY, viewport_width);
-        assert_eq!(ws.scroll_offset(), 50.0);
+        assert!(ws.collapse_focused_column());
+        assert!(ws.columns()[1].is_collapsed());
+        assert_eq!(ws.columns()[1].width(), 600); // Width is remembered
+        assert_eq!(ws.total_width(), 10 + 400 + 10 + DEFAULT_COLLAPSED_COLUMN_WIDTH + 10);
 
-        // Negative infinity should be treated as zero (no change)
-        ws.scroll_by(f64::NEG_INFINITY, viewport_width);
-        assert_eq!(ws.scroll_offset(), 50.0);
+        // Collapsing twice is a no-op
+        assert!(!ws.collapse_focused_column());
     }
 
     #[test]
-    fn test_column_width_getter() {
-        let col = Column::new(1, 500);
-        assert_eq!(col.width(), 500);
+    fn test_expand_focused_column_restores_width() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.insert_window(1, Some(400)).unwrap();
+        assert!(!ws.expand_focused_column());
 
-        let col2 = Column::new(2, 50); // Below minimum
-        assert_eq!(col2.width(), 100); // Clamped
+        ws.collapse_focused_column();
+        assert!(ws.expand_focused_column());
+        assert!(!ws.columns()[0].is_collapsed());
+        assert_eq!(ws.total_width(), 10 + 400 + 10);
     }
 
     #[test]
-    fn test_column_contains() {
-        let mut col = Column::new(1, 400);
-        col.add_window(2);
-        col.add_window(3);
+    fn test_toggle_maximize_focused_column() {
+        let mut ws = Workspace::with_gaps(10, 20);
+        assert_eq!(ws.toggle_maximize_focused_column(1920), None);
 
-        assert!(col.contains(1));
-        assert!(col.contains(2));
-        assert!(col.contains(3));
-        assert!(!col.contains(999));
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(600)).unwrap();
+        assert_eq!(ws.toggle_maximize_focused_column(1920), Some(true));
+        assert!(ws.columns()[1].is_maximized());
+        assert_eq!(ws.columns()[1].width(), 1920 - 2 * 20);
 
-        // Test get() method
-        assert_eq!(col.get(0), Some(1));
-        assert_eq!(col.get(1), Some(2));
-        assert_eq!(col.get(2), Some(3));
-        assert_eq!(col.get(10), None);
+        assert_eq!(ws.toggle_maximize_focused_column(1920), Some(false));
+        assert!(!ws.columns()[1].is_maximized());
+        assert_eq!(ws.columns()[1].width(), 600);
 
-        // Test windows() slice
-        assert_eq!(col.windows(), &[1, 2, 3]);
+        // Resizing a maximized column keeps the new width
+        ws.toggle_maximize_focused_column(1920);
+        ws.set_focused_column_width_fraction(0.5, 1920);
+        assert!(!ws.columns()[1].is_maximized());
+        assert_eq!(ws.toggle_maximize_focused_column(1920), Some(true));
     }
 
-    // ====== Tests added from code review (Cycle 5) ======
-
     #[test]
-    fn test_remove_window_before_focus_in_stacked_column() {
-        // Bug test: removing a window BEFORE the focused window in a stacked column
-        // should keep focus on the same window (index should decrement)
+    fn test_collapse_empty_workspace() {
         let mut ws = Workspace::new();
-        ws.insert_window(1, Some(400)).unwrap(); // Column 0
-        ws.insert_window_in_column(2, 0).unwrap(); // Stack: [1, 2]
-        ws.insert_window_in_column(3, 0).unwrap(); // Stack: [1, 2, 3]
-
-        // Focus on window 2 (index 1)
-        ws.test_set_focus_unchecked(0, 1);
-        assert_eq!(ws.focused_window(), Some(2));
-
-        // Remove window 1 (index 0, before focused)
-        ws.remove_window(1).unwrap();
-
-        // Focus should still be on window 2, but index should now be 0
-        assert_eq!(ws.focused_window(), Some(2));
-        assert_eq!(ws.focused_window_index_in_column(), 0);
+        assert!(!ws.collapse_focused_column());
+        assert!(!ws.expand_focused_column());
     }
 
     #[test]
-    fn test_remove_focused_window_in_stacked_column() {
-        // Removing the focused window should move focus to next window (or previous if at end)
-        let mut ws = Workspace::new();
+    fn test_collapsed_column_placements_hidden_and_neighbours_shift() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        let viewport = Rect::new(0, 0, 1920, 1080);
         ws.insert_window(1, Some(400)).unwrap();
-        ws.insert_window_in_column(2, 0).unwrap();
-        ws.insert_window_in_column(3, 0).unwrap(); // Stack: [1, 2, 3]
-
-        // Focus on window 2 (index 1, middle)
-        ws.test_set_focus_unchecked(0, 1);
-        assert_eq!(ws.focused_window(), Some(2));
+        ws.insert_window(2, Some(400)).unwrap();
+        ws.focus_left();
+        ws.collapse_focused_column();
 
-        // Remove window 2 (the focused window)
-        ws.remove_window(2).unwrap();
+        for placements in [ws.compute_placements(viewport), ws.compute_placements_animated(viewport)] {
+            let p1 = placements.iter().find(|p| p.window_id == 1).unwrap();
+            assert_ne!(p1.visibility, Visibility::Visible);
+            assert_eq!(p1.rect.width, 400); // Keeps its real size while hidden
 
-        // Stack is now [1, 3], focus index 1 should point to window 3 (next)
-        assert_eq!(ws.focused_window(), Some(3));
-        assert_eq!(ws.focused_window_index_in_column(), 1);
+            let p2 = placements.iter().find(|p| p.window_id == 2).unwrap();
+            assert_eq!(p2.visibility, Visibility::Visible);
+            assert_eq!(p2.rect.x, 10 + DEFAULT_COLLAPSED_COLUMN_WIDTH + 10);
+        }
     }
 
     #[test]
-    fn test_remove_last_focused_window_in_stacked_column() {
-        // Removing the last focused window should move focus to previous
-        let mut ws = Workspace::new();
+    fn test_collapsed_column_rects() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        let viewport = Rect::new(0, 0, 1920, 1080);
         ws.insert_window(1, Some(400)).unwrap();
-        ws.insert_window_in_column(2, 0).unwrap();
-        ws.insert_window_in_column(3, 0).unwrap(); // Stack: [1, 2, 3]
+        ws.insert_window(2, Some(400)).unwrap();
+        assert!(ws.collapsed_column_rects(viewport).is_empty());
 
-        // Focus on window 3 (index 2, last)
-        ws.test_set_focus_unchecked(0, 2);
-        assert_eq!(ws.focused_window(), Some(3));
+        ws.set_collapsed_column_width(30);
+        ws.collapse_focused_column();
+        assert_eq!(
+            ws.collapsed_column_rects(viewport),
+            vec![(1, Rect::new(420, 10, 30, 1060))]
+        );
+    }
 
-        // Remove window 3 (the focused window, at end)
-        ws.remove_window(3).unwrap();
+    #[test]
+    fn test_collapsed_column_rects_skip_offscreen() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        let viewport = Rect::new(0, 0, 500, 1080);
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(400)).unwrap();
+        ws.insert_window(3, Some(400)).unwrap();
+        ws.collapse_focused_column(); // Column 2 starts at strip x 830
 
-        // Stack is now [1, 2], focus should move to index 1 (window 2)
-        assert_eq!(ws.focused_window(), Some(2));
-        assert_eq!(ws.focused_window_index_in_column(), 1);
+        assert!(ws.collapsed_column_rects(viewport).is_empty());
     }
 
     #[test]
-    fn test_compute_placements_wide_column() {
-        // Column wider than viewport
+    fn test_column_background_rects() {
         let mut ws = Workspace::with_gaps(10, 10);
-        ws.insert_window(1, Some(1000)).unwrap(); // Column wider than viewport
+        let viewport = Rect::new(0, 0, 1920, 1080);
+        assert!(ws.column_background_rects(viewport).is_empty());
 
-        let viewport = Rect::new(0, 0, 500, 600); // Viewport only 500px wide
-        let placements = ws.compute_placements(viewport);
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(400)).unwrap();
+        ws.set_collapsed_column_width(30);
+        ws.insert_window(3, Some(400)).unwrap();
+        ws.collapse_focused_column();
+        // Collapsed columns have their own placeholder; the rest of the viewport is empty
+        assert_eq!(
+            ws.column_background_rects(viewport),
+            vec![Rect::new(10, 10, 400, 1060), Rect::new(420, 10, 400, 1060), Rect::new(870, 10, 1050, 1060)]
+        );
 
-        assert_eq!(placements.len(), 1);
-        assert_eq!(placements[0].visibility, Visibility::Visible);
-        assert_eq!(placements[0].rect.width, 1000); // Full column width preserved
+        ws.set_layout_mode(LayoutMode::StackOnly);
+        assert!(ws.column_background_rects(viewport).is_empty());
     }
 
     #[test]
-    fn test_column_empty_type() {
-        // Tests the Column::empty() constructor and its properties.
-        // Note: In practice, empty columns are automatically removed from workspaces
-        // when the last window is removed, so empty columns don't occur in normal use.
-        // Column::empty() exists for construction purposes (e.g., building columns
-        // before adding windows).
-        let empty_col = Column::empty(300);
-        assert!(empty_col.is_empty());
-        assert_eq!(empty_col.width(), 300);
-        assert_eq!(empty_col.len(), 0);
-        assert_eq!(empty_col.windows(), &[]);
-
-        // Verify workspace doesn't produce placements for non-existent windows
-        let mut ws = Workspace::new();
+    fn test_column_background_rects_clip_to_viewport() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        let viewport = Rect::new(100, 0, 500, 1080);
         ws.insert_window(1, Some(400)).unwrap();
         ws.insert_window(2, Some(400)).unwrap();
+        ws.insert_window(3, Some(400)).unwrap();
+        ws.scroll_by(500.0, viewport.width);
 
-        let viewport = Rect::new(0, 0, 2000, 600);
-        let placements = ws.compute_placements(viewport);
-        assert_eq!(placements.len(), 2); // Only 2 windows, no extras
+        assert_eq!(
+            ws.column_background_rects(viewport),
+            vec![Rect::new(100, 10, 320, 1060), Rect::new(430, 10, 170, 1060)]
+        );
     }
 
     #[test]
-    fn test_negative_gaps_clamped() {
-        // Negative gaps should be clamped to 0
-        let ws = Workspace::with_gaps(-100, -50);
-        assert_eq!(ws.gap(), 0);
-        assert_eq!(ws.outer_gap(), 0);
+    fn test_set_collapsed_column_width_clamps() {
+        let mut ws = Workspace::new();
+        ws.set_collapsed_column_width(0);
+        assert_eq!(ws.collapsed_column_width(), MIN_COLLAPSED_COLUMN_WIDTH);
+        ws.set_collapsed_column_width(64);
+        assert_eq!(ws.collapsed_column_width(), 64);
     }
 
     #[test]
-    fn test_gap_setters_clamp() {
+    fn test_expand_column_of_window() {
         let mut ws = Workspace::new();
+        ws.insert_window(1, None).unwrap();
+        ws.insert_window(2, None).unwrap();
+        ws.collapse_focused_column();
 
-        // Test gap setter
-        ws.set_gap(20);
-        assert_eq!(ws.gap(), 20);
-        ws.set_gap(-50);
-        assert_eq!(ws.gap(), 0); // Clamped
+        assert!(!ws.expand_column_of(1));
+        assert!(ws.expand_column_of(2));
+        assert!(!ws.columns()[1].is_collapsed());
+        assert!(!ws.expand_column_of(999));
+    }
 
-        // Test outer_gap setter
-        ws.set_outer_gap(15);
-        assert_eq!(ws.outer_gap(), 15);
-        ws.set_outer_gap(-100);
-        assert_eq!(ws.outer_gap(), 0); // Clamped
+    #[test]
+    fn test_equalize_widths_skips_collapsed_columns() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.insert_window(1, Some(300)).unwrap();
+        ws.insert_window(2, Some(600)).unwrap();
+        ws.insert_window(3, Some(400)).unwrap();
+        ws.collapse_focused_column();
 
-        // Test default_column_width setter
-        ws.set_default_column_width(500);
-        assert_eq!(ws.default_column_width(), 500);
-        ws.set_default_column_width(50); // Below MIN_COLUMN_WIDTH
-        assert_eq!(ws.default_column_width(), 100); // Clamped to minimum
+        // reserved = 40 gaps + 40 placeholder, per_column = (1920 - 80) / 2 = 920
+        ws.equalize_column_widths(1920);
+        assert_eq!(ws.columns()[0].width(), 920);
+        assert_eq!(ws.columns()[1].width(), 920);
+        assert_eq!(ws.columns()[2].width(), 400);
+    }
 
-        // Test centering_mode getter/setter
-        assert_eq!(ws.centering_mode(), CenteringMode::Center); // Default
-        ws.set_centering_mode(CenteringMode::JustInView);
-        assert_eq!(ws.centering_mode(), CenteringMode::JustInView);
+    // ====================================================================
+    // Stack-Only Layout Tests
+    // ====================================================================
+
+    #[test]
+    fn test_stack_only_inserts_into_single_column() {
+        let mut ws = Workspace::new();
+        ws.set_layout_mode(LayoutMode::StackOnly);
+        ws.insert_window(1, None).unwrap();
+        ws.insert_window(2, None).unwrap();
+        ws.insert_window(3, Some(300)).unwrap();
+
+        assert_eq!(ws.column_count(), 1);
+        assert_eq!(ws.columns()[0].windows(), &[1, 2, 3]);
+        assert_eq!(ws.focused_window(), Some(3));
+        assert!(matches!(ws.insert_window(2, None), Err(LayoutError::DuplicateWindow(2))));
     }
 
     #[test]
-    fn test_compute_placements_spacing_integrity() {
-        // Verify stacked window heights + gaps sum correctly
-        let mut ws = Workspace::with_gaps(10, 20);
+    fn test_stack_only_placements_fill_viewport_width() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.set_layout_mode(LayoutMode::StackOnly);
+        let viewport = Rect::new(1920, 0, 1080, 1900);
         ws.insert_window(1, Some(400)).unwrap();
-        ws.insert_window_in_column(2, 0).unwrap();
-        ws.insert_window_in_column(3, 0).unwrap(); // Stack: [1, 2, 3]
+        ws.insert_window(2, Some(400)).unwrap();
+        ws.scroll_by(500.0, 1080);
+
+        for placements in [ws.compute_placements(viewport), ws.compute_placements_animated(viewport)] {
+            assert_eq!(placements.len(), 2);
+            // usable height 1880, minus one gap, split in two
+            assert_eq!(placements[0].rect, Rect::new(1930, 10, 1060, 935));
+            assert_eq!(placements[1].rect, Rect::new(1930, 955, 1060, 935));
+            assert!(placements.iter().all(|p| p.visibility == Visibility::Visible));
+        }
+    }
 
-        let viewport = Rect::new(0, 0, 500, 600);
-        let placements = ws.compute_placements(viewport);
+    #[test]
+    fn test_stack_only_placements_follow_heights_and_size_limits() {
+        let mut ws = Workspace::with_gaps(0, 0);
+        ws.set_layout_mode(LayoutMode::StackOnly);
+        let viewport = Rect::new(0, 0, 1000, 1000);
+        ws.insert_window(1, None).unwrap();
+        ws.insert_window(2, None).unwrap();
+        ws.focus_window(1).unwrap();
+        assert!(ws.resize_focused_window_height(300, 1000));
 
-        // usable_height = 600 - 20*2 = 560
-        // 3 windows with 2 gaps = 560 - 10*2 = 540 for windows
-        // Each window ~180px, but last takes remainder
+        let rects = |ws: &Workspace| -> Vec<Rect> { ws.compute_placements(viewport).iter().map(|p| p.rect).collect() };
+        assert_eq!(rects(&ws), vec![Rect::new(0, 0, 1000, 800), Rect::new(0, 800, 1000, 200)]);
 
-        let total_height: i32 = placements.iter().map(|p| p.rect.height).sum();
-        let expected_usable = viewport.height - ws.outer_gap() * 2;
-        let expected_gaps = ws.gap() * (placements.len() as i32 - 1);
+        ws.set_size_constraints(1, SizeConstraints { max_height: Some(600), ..Default::default() });
+        ws.set_size_constraints(2, SizeConstraints { max_width: Some(500), ..Default::default() });
+        assert_eq!(rects(&ws), vec![Rect::new(0, 0, 1000, 600), Rect::new(0, 600, 500, 400)]);
+    }
 
-        // Total heights + gaps should equal usable height
-        assert_eq!(total_height + expected_gaps, expected_usable);
+    #[test]
+    fn test_set_layout_mode_merges_columns_and_keeps_focus() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, None).unwrap();
+        ws.insert_window(2, None).unwrap();
+        ws.insert_window_in_column(3, 1).unwrap();
+        ws.insert_window(4, None).unwrap();
+        ws.focus_window(2).unwrap();
+
+        ws.set_layout_mode(LayoutMode::StackOnly);
+        assert_eq!(ws.layout_mode(), LayoutMode::StackOnly);
+        assert_eq!(ws.column_count(), 1);
+        assert_eq!(ws.columns()[0].windows(), &[1, 2, 3, 4]);
+        assert_eq!(ws.focused_window(), Some(2));
+        assert_eq!(ws.scroll_offset(), 0.0);
     }
 
     #[test]
-    fn test_column_remove_returns_index() {
-        let mut col = Column::new(1, 400);
-        col.add_window(2);
-        col.add_window(3);
-        // Windows: [1, 2, 3]
+    fn test_set_layout_mode_empty_workspace() {
+        let mut ws = Workspace::new();
+        ws.set_layout_mode(LayoutMode::StackOnly);
+        assert!(ws.is_empty());
+        ws.set_layout_mode(LayoutMode::Scrolling);
+        ws.insert_window(1, None).unwrap();
+        ws.insert_window(2, None).unwrap();
+        assert_eq!(ws.column_count(), 2);
+    }
 
-        // Remove middle window
-        let removed = col.remove_window(2);
-        assert_eq!(removed, Some(1)); // Index 1
+    #[test]
+    fn test_stack_only_ignores_column_operations() {
+        let mut ws = Workspace::new();
+        ws.set_layout_mode(LayoutMode::StackOnly);
+        ws.insert_window(1, None).unwrap();
+        ws.insert_window(2, None).unwrap();
 
-        // Remove first window
-        let removed = col.remove_window(1);
-        assert_eq!(removed, Some(0)); // Index 0
+        ws.move_window_to_index(2, 5).unwrap();
+        assert_eq!(ws.column_count(), 1);
+        assert_eq!(ws.focused_window(), Some(2));
+        assert!(!ws.collapse_focused_column());
+    }
 
-        // Remove nonexistent
-        let removed = col.remove_window(999);
-        assert_eq!(removed, None);
+    #[test]
+    fn test_stack_only_remove_window() {
+        let mut ws = Workspace::new();
+        ws.set_layout_mode(LayoutMode::StackOnly);
+        ws.insert_window(1, None).unwrap();
+        ws.insert_window(2, None).unwrap();
+        ws.remove_window(1).unwrap();
+        ws.remove_window(2).unwrap();
+        assert!(ws.is_empty());
+
+        ws.insert_window(3, None).unwrap();
+        assert_eq!(ws.column_count(), 1);
     }
 
-    // ====== Tests added from code review (Cycle 7) ======
+    #[test]
+    fn test_window_visibility() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        let viewport = Rect::new(0, 0, 1000, 800);
+        ws.insert_window(1, Some(600)).unwrap();
+        ws.insert_window(2, Some(600)).unwrap();
+        ws.insert_window(3, Some(600)).unwrap();
+        ws.set_focus(0, 0).unwrap();
+        ws.ensure_focused_visible(viewport.width);
+
+        assert_eq!(ws.window_visibility(1, viewport), Some(Visibility::Visible));
+        assert_eq!(ws.window_visibility(3, viewport), Some(Visibility::OffScreenRight));
+        assert_eq!(ws.window_visibility(99, viewport), None);
+
+        ws.add_floating(4, Rect::new(50, 50, 200, 200)).unwrap();
+        assert_eq!(ws.window_visibility(4, viewport), Some(Visibility::Visible));
+    }
+
+    // ====================================================================
+    // Shrink-to-Fit Tests
+    // ====================================================================
+
+    /// Two 500px columns with 10px gaps: 1030px strip, 30px (3%) wider than the viewport.
+    fn slightly_wide_workspace() -> (Workspace, Rect) {
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.insert_window(1, Some(500)).unwrap();
+        ws.insert_window(2, Some(500)).unwrap();
+        (ws, Rect::new(0, 0, 1000, 800))
+    }
 
     #[test]
-    fn test_compute_placements_zero_viewport_width() {
-        let mut ws = Workspace::with_gaps(10, 10);
-        ws.insert_window(1, Some(400)).unwrap();
-        ws.insert_window(2, Some(400)).unwrap();
+    fn test_shrink_to_fit_disabled_by_default() {
+        let (ws, viewport) = slightly_wide_workspace();
+        assert_eq!(ws.shrink_to_fit_threshold(), 0.0);
 
-        // Zero width viewport - edge case
-        let viewport = Rect::new(0, 0, 0, 600);
         let placements = ws.compute_placements(viewport);
+        assert_eq!(placements[1].rect.x, 520);
+        assert_eq!(placements[1].rect.width, 500);
+        assert!(!ws.is_shrunk_to_fit());
+    }
 
-        // Should produce placements without panicking
-        assert_eq!(placements.len(), 2);
-        // All columns should be off-screen right (viewport has no width)
-        for p in &placements {
-            assert_eq!(p.visibility, Visibility::OffScreenRight);
+    #[test]
+    fn test_shrink_to_fit_shrinks_proportionally() {
+        let (mut ws, viewport) = slightly_wide_workspace();
+        ws.set_shrink_to_fit_threshold(0.05);
+        ws.test_set_scroll_offset(30.0);
+
+        for placements in [ws.compute_placements(viewport), ws.compute_placements_animated(viewport)] {
+            // 970px of column space shared 50/50, scroll offset ignored
+            assert_eq!(placements[0].rect.x, 10);
+            assert_eq!(placements[0].rect.width, 485);
+            assert_eq!(placements[1].rect.x, 505);
+            assert_eq!(placements[1].rect.width, 485);
+            assert!(placements.iter().all(|p| p.visibility == Visibility::Visible));
         }
+        ws.update_shrink_to_fit(viewport.width);
+        assert!(ws.is_shrunk_to_fit());
+
+        // Stored widths are untouched
+        assert_eq!(ws.columns()[0].width(), 500);
+        assert_eq!(ws.columns()[1].width(), 500);
     }
 
     #[test]
-    fn test_compute_placements_zero_viewport_height() {
+    fn test_shrink_to_fit_fills_viewport_exactly() {
         let mut ws = Workspace::with_gaps(10, 10);
-        ws.insert_window(1, Some(400)).unwrap();
-        ws.insert_window_in_column(2, 0).unwrap();
+        ws.set_shrink_to_fit_threshold(0.1);
+        ws.insert_window(1, Some(333)).unwrap();
+        ws.insert_window(2, Some(333)).unwrap();
+        ws.insert_window(3, Some(380)).unwrap();
+        let viewport = Rect::new(100, 0, 1000, 800);
 
-        // Zero height viewport - edge case
-        let viewport = Rect::new(0, 0, 500, 0);
         let placements = ws.compute_placements(viewport);
-
-        // Should produce placements without panicking
-        assert_eq!(placements.len(), 2);
-        // All heights should be >= 0 (clamped)
-        for p in &placements {
-            assert!(p.rect.height >= 0, "Height was negative: {}", p.rect.height);
-        }
+        let last = placements.last().unwrap();
+        assert_eq!(last.rect.right(), viewport.right() - 10);
+        assert!(placements.windows(2).all(|w| w[0].rect.right() + 10 == w[1].rect.x));
     }
 
     #[test]
-    fn test_focus_navigation_clamps_to_shorter_column() {
-        let mut ws = Workspace::new();
-        ws.insert_window(1, Some(400)).unwrap(); // Column 0: [1]
-        ws.insert_window(2, Some(400)).unwrap(); // Column 1: [2]
-        ws.insert_window_in_column(3, 0).unwrap(); // Column 0: [1, 3]
-        ws.insert_window_in_column(4, 0).unwrap(); // Column 0: [1, 3, 4]
-
-        // Focus on window 4 (column 0, index 2)
-        ws.test_set_focus_unchecked(0, 2);
-        assert_eq!(ws.focused_window(), Some(4));
-
-        // Move right to column 1 which only has 1 window
-        ws.focus_right();
+    fn test_shrink_to_fit_skips_large_overflow() {
+        let (mut ws, viewport) = slightly_wide_workspace();
+        ws.set_shrink_to_fit_threshold(0.1);
+        ws.insert_window(3, Some(500)).unwrap();
 
-        // Focus should clamp to index 0 (the only window in column 1)
-        assert_eq!(ws.focused_column_index(), 1);
-        assert_eq!(ws.focused_window_index_in_column(), 0);
-        assert_eq!(ws.focused_window(), Some(2));
+        let placements = ws.compute_placements(viewport);
+        assert!(placements.iter().all(|p| p.rect.width == 500));
+        ws.update_shrink_to_fit(viewport.width);
+        assert!(!ws.is_shrunk_to_fit());
     }
 
     #[test]
-    fn test_resize_then_ensure_focused_visible() {
-        let mut ws = Workspace::with_gaps(10, 10);
-        ws.set_centering_mode(CenteringMode::JustInView);
-        ws.insert_window(1, Some(200)).unwrap();
-        ws.insert_window(2, Some(200)).unwrap();
-        ws.insert_window(3, Some(200)).unwrap();
-
-        // Focus column 2, resize it significantly
-        ws.test_set_focus_unchecked(2, 0);
-        ws.resize_focused_column(500); // Now 700px wide
+    fn test_shrink_to_fit_hysteresis() {
+        let (mut ws, viewport) = slightly_wide_workspace();
+        ws.set_shrink_to_fit_threshold(0.05);
+
+        // 60px overflow (6%): above threshold, scrolls
+        ws.resize_focused_column(30);
+        ws.update_shrink_to_fit(viewport.width);
+        assert!(!ws.is_shrunk_to_fit());
+
+        // 40px overflow (4%): shrinks
+        ws.resize_focused_column(-20);
+        ws.update_shrink_to_fit(viewport.width);
+        assert!(ws.is_shrunk_to_fit());
+
+        // Back to 60px (6%): within hysteresis band, stays shrunk
+        ws.resize_focused_column(20);
+        let placements = ws.compute_placements(viewport);
+        assert_eq!(placements[1].rect.right(), 990);
+        ws.update_shrink_to_fit(viewport.width);
+        assert!(ws.is_shrunk_to_fit());
 
-        // Ensure focused visible should adjust scroll
-        ws.ensure_focused_visible(500);
+        // 80px overflow (8%): released
+        ws.resize_focused_column(20);
+        ws.update_shrink_to_fit(viewport.width);
+        assert!(!ws.is_shrunk_to_fit());
 
-        // Should have scrolled to bring the widened column into view
-        assert!(ws.scroll_offset() > 0.0);
+        // Computing placements alone leaves the hysteresis state alone
+        ws.resize_focused_column(-40);
+        ws.compute_placements(viewport);
+        assert!(!ws.is_shrunk_to_fit());
     }
 
     #[test]
-    fn test_move_column_then_resize() {
-        let mut ws = Workspace::new();
-        ws.insert_window(1, Some(400)).unwrap();
-        ws.insert_window(2, Some(300)).unwrap();
+    fn test_shrink_to_fit_keeps_collapsed_strips() {
+        let (mut ws, viewport) = slightly_wide_workspace();
+        ws.set_shrink_to_fit_threshold(0.05);
         ws.insert_window(3, Some(500)).unwrap();
+        ws.collapse_focused_column();
+        // 1030 + 40 placeholder + 10 gap = 1080px, 80px too wide; raise threshold
+        ws.set_shrink_to_fit_threshold(0.1);
 
-        // Focus column 1 (window 2, 300px)
-        ws.test_set_focus_unchecked(1, 0);
-        assert_eq!(ws.columns()[1].width(), 300);
-
-        // Move column left
-        ws.move_column_left();
-        assert_eq!(ws.focused_column_index(), 0);
-        assert_eq!(ws.columns()[0].width(), 300); // Column with window 2
-
-        // Resize the moved column
-        ws.resize_focused_column(100);
-        assert_eq!(ws.columns()[0].width(), 400);
+        let placements = ws.compute_placements(viewport);
+        assert_eq!(placements[0].rect.width, 460);
+        assert_eq!(placements[1].rect.width, 460);
+        assert_eq!(placements[2].rect.x, 950);
+        assert_eq!(placements[2].visibility, Visibility::OffScreenLeft);
+        assert_eq!(ws.collapsed_column_rects(viewport), vec![(2, Rect::new(950, 10, 40, 780))]);
     }
 
     #[test]
-    fn test_remove_reinsert_same_window_id() {
+    fn test_set_shrink_to_fit_threshold_clamps() {
         let mut ws = Workspace::new();
-        ws.insert_window(42, Some(400)).unwrap();
-        ws.insert_window(100, Some(300)).unwrap();
+        ws.set_shrink_to_fit_threshold(2.0);
+        assert_eq!(ws.shrink_to_fit_threshold(), MAX_SHRINK_TO_FIT_THRESHOLD);
+        ws.set_shrink_to_fit_threshold(-1.0);
+        assert_eq!(ws.shrink_to_fit_threshold(), 0.0);
+        ws.set_shrink_to_fit_threshold(f64::NAN);
+        assert_eq!(ws.shrink_to_fit_threshold(), 0.0);
+    }
 
-        // Remove window 42
-        ws.remove_window(42).unwrap();
-        assert!(!ws.contains_window(42));
+    // ====================================================================
+    // Auto-Balance and Maximum Column Width Tests
+    // ====================================================================
 
-        // Re-insert same ID should work now
-        ws.insert_window(42, Some(500)).unwrap();
-        assert!(ws.contains_window(42));
-        assert_eq!(ws.focused_window(), Some(42));
+    fn tiled_rects(placements: &[WindowPlacement]) -> Vec<(i32, i32)> {
+        placements.iter().map(|p| (p.rect.x, p.rect.width)).collect()
     }
 
     #[test]
-    fn test_find_window_location() {
-        let mut ws = Workspace::new();
-        ws.insert_window(1, Some(400)).unwrap(); // Column 0
-        ws.insert_window(2, Some(400)).unwrap(); // Column 1
-        ws.insert_window_in_column(3, 0).unwrap(); // Column 0, index 1
-        ws.insert_window_in_column(4, 1).unwrap(); // Column 1, index 1
+    fn test_auto_balance_stretches_columns_to_fill_viewport() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.insert_window(1, Some(300)).unwrap();
+        ws.insert_window(2, Some(100)).unwrap();
+        let viewport = Rect::new(0, 0, 1000, 800);
+        assert_eq!(tiled_rects(&ws.compute_placements(viewport)), vec![(10, 300), (320, 100)]);
 
-        assert_eq!(ws.find_window_location(1), Some((0, 0)));
-        assert_eq!(ws.find_window_location(2), Some((1, 0)));
-        assert_eq!(ws.find_window_location(3), Some((0, 1)));
-        assert_eq!(ws.find_window_location(4), Some((1, 1)));
-        assert_eq!(ws.find_window_location(999), None);
+        ws.set_auto_balance(true);
+        let placements = ws.compute_placements(viewport);
+        assert_eq!(tiled_rects(&placements), vec![(10, 727), (747, 243)]);
+        assert!(placements.iter().all(|p| p.visibility == Visibility::Visible));
+        assert_eq!(ws.column(0).unwrap().width(), 300, "stored widths are unchanged");
     }
 
     #[test]
-    fn test_window_count() {
-        let mut ws = Workspace::new();
-        assert_eq!(ws.window_count(), 0);
+    fn test_auto_balance_leaves_overflowing_strip_alone() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.insert_window(1, Some(600)).unwrap();
+        ws.insert_window(2, Some(600)).unwrap();
+        ws.set_auto_balance(true);
+        let placements = ws.compute_placements(Rect::new(0, 0, 1000, 800));
+        assert!(placements.iter().all(|p| p.rect.width == 600));
+    }
 
-        ws.insert_window(1, Some(400)).unwrap();
-        assert_eq!(ws.window_count(), 1);
+    #[test]
+    fn test_max_column_width_caps_placements_and_balancing() {
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.insert_window(1, Some(800)).unwrap();
+        ws.set_max_column_width(400);
+        let viewport = Rect::new(0, 0, 1200, 800);
+        assert_eq!(tiled_rects(&ws.compute_placements(viewport)), vec![(10, 400)]);
+        assert_eq!(ws.total_width(), 420);
 
-        ws.insert_window(2, Some(400)).unwrap();
-        assert_eq!(ws.window_count(), 2);
+        ws.insert_window(2, Some(300)).unwrap();
+        ws.set_auto_balance(true);
+        assert_eq!(tiled_rects(&ws.compute_placements(viewport)), vec![(10, 400), (420, 400)]);
 
-        ws.insert_window_in_column(3, 0).unwrap();
-        ws.insert_window_in_column(4, 0).unwrap();
-        assert_eq!(ws.window_count(), 4);
+        ws.set_max_column_width(0);
+        assert_eq!(ws.max_column_width(), MIN_COLUMN_WIDTH);
+        assert!(ws.empty_like().auto_balance());
+    }
 
-        ws.remove_window(2).unwrap();
-        assert_eq!(ws.window_count(), 3);
+    // ====================================================================
+    // Iteration Tests
+    // ====================================================================
+
+    /// Columns [1, 2] and [3] plus floating window 4.
+    fn mixed_workspace() -> Workspace {
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window_in_column(2, 0).unwrap();
+        ws.insert_window(3, Some(400)).unwrap();
+        ws.add_floating(4, Rect::new(100, 100, 300, 200)).unwrap();
+        ws
     }
 
     #[test]
-    fn test_column_safe_access() {
-        let mut ws = Workspace::new();
-        assert!(ws.column(0).is_none());
+    fn test_iter_windows_yields_positions() {
+        let ws = mixed_workspace();
+        let entries: Vec<_> = ws.iter_windows().collect();
+
+        assert_eq!(entries.len(), 4);
+        assert_eq!(
+            entries[0],
+            WindowEntry { window_id: 1, column_index: Some(0), window_index: Some(0) }
+        );
+        assert_eq!(
+            entries[1],
+            WindowEntry { window_id: 2, column_index: Some(0), window_index: Some(1) }
+        );
+        assert_eq!(
+            entries[2],
+            WindowEntry { window_id: 3, column_index: Some(1), window_index: Some(0) }
+        );
+        assert!(entries[3].is_floating());
+        assert_eq!(entries[3].window_id, 4);
+        assert_eq!(
+            ws.iter_windows().map(|e| e.window_id).collect::<Vec<_>>(),
+            ws.all_window_ids()
+        );
+    }
 
-        ws.insert_window(1, Some(400)).unwrap();
-        ws.insert_window(2, Some(500)).unwrap();
+    #[test]
+    fn test_iter_placements_matches_compute_placements() {
+        let ws = mixed_workspace();
+        let viewport = Rect::new(0, 0, 1920, 1080);
+        let placements = ws.compute_placements(viewport);
 
-        assert!(ws.column(0).is_some());
-        assert_eq!(ws.column(0).unwrap().width(), 400);
-        assert!(ws.column(1).is_some());
-        assert_eq!(ws.column(1).unwrap().width(), 500);
-        assert!(ws.column(2).is_none());
-        assert!(ws.column(100).is_none());
+        let mut count = 0;
+        for (entry, placement) in ws.iter_placements(viewport) {
+            assert_eq!(entry.window_id, placement.window_id);
+            let expected = placements.iter().find(|p| p.window_id == entry.window_id).unwrap();
+            assert_eq!(placement.rect, expected.rect);
+            if let Some(col_idx) = entry.column_index {
+                assert_eq!(placement.column_index, col_idx);
+            } else {
+                assert_eq!(placement.rect, Rect::new(100, 100, 300, 200));
+            }
+            count += 1;
+        }
+        assert_eq!(count, 4);
     }
 
     #[test]
-    fn test_single_column_move_operations() {
-        let mut ws = Workspace::new();
+    fn test_iter_placements_empty_workspace() {
+        let ws = Workspace::new();
+        assert_eq!(ws.iter_windows().count(), 0);
+        assert_eq!(ws.iter_placements(Rect::new(0, 0, 1920, 1080)).count(), 0);
+    }
+
+    // ====================================================================
+    // Column Transfer Tests
+    // ====================================================================
+
+    #[test]
+    fn test_take_focused_column() {
+        let mut ws = Workspace::with_gaps(10, 10);
         ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(600)).unwrap();
+        ws.insert_window_in_column(3, 1).unwrap();
+        ws.insert_window(4, Some(400)).unwrap();
+        ws.focus_left();
+        ws.focus_down();
 
-        // Move operations on single column should do nothing
-        ws.move_column_left();
-        assert_eq!(ws.focused_column_index(), 0);
-        assert_eq!(ws.focused_window(), Some(1));
+        let (column, focused) = ws.take_focused_column().unwrap();
+        assert_eq!(column.windows(), &[2, 3]);
+        assert_eq!(column.width(), 600);
+        assert_eq!(focused, 1);
 
-        ws.move_column_right();
-        assert_eq!(ws.focused_column_index(), 0);
-        assert_eq!(ws.focused_window(), Some(1));
+        // Focus moves to the column that slid into place
+        assert_eq!(ws.column_count(), 2);
+        assert_eq!(ws.focused_window(), Some(4));
     }
 
     #[test]
-    fn test_resize_on_empty_workspace() {
+    fn test_take_last_column_empties_workspace() {
         let mut ws = Workspace::new();
+        assert!(ws.take_focused_column().is_none());
 
-        // Resize on empty workspace should do nothing without panic
-        ws.resize_focused_column(100);
-        ws.resize_focused_column(-100);
-        ws.resize_focused_column(i32::MAX);
-        ws.resize_focused_column(i32::MIN);
-
+        ws.insert_window(1, None).unwrap();
+        ws.toggle_fullscreen(Rect::new(0, 0, 1920, 1080));
+        let (column, _) = ws.take_focused_column().unwrap();
+        assert_eq!(column.windows(), &[1]);
         assert!(ws.is_empty());
+        assert!(!ws.is_fullscreen());
     }
 
     #[test]
-    fn test_invariants_after_complex_sequence() {
-        let mut ws = Workspace::with_gaps(10, 10);
-
-        // Insert several windows
+    fn test_insert_column_after_focused() {
+        let mut ws = Workspace::new();
         ws.insert_window(1, Some(400)).unwrap();
-        ws.insert_window(2, Some(300)).unwrap();
-        ws.insert_window(3, Some(500)).unwrap();
-        ws.insert_window_in_column(4, 1).unwrap();
-        ws.insert_window_in_column(5, 1).unwrap();
-
-        // Complex sequence of operations
-        ws.focus_left();
-        ws.focus_down();
-        ws.move_column_right();
-        ws.resize_focused_column(100);
-        ws.focus_right();
-        ws.focus_up();
-        ws.remove_window(4).unwrap();
+        ws.insert_window(2, Some(400)).unwrap();
         ws.focus_left();
-        ws.move_column_left();
 
-        // Verify invariants still hold
-        assert!(ws.focused_column < ws.columns.len());
-        assert!(ws.focused_window_in_column < ws.columns[ws.focused_column].len());
+        let mut column = Column::new(10, 700);
+        column.add_window(11);
+        ws.insert_column(column, 1).unwrap();
 
-        // No duplicate windows
-        let mut all_windows: Vec<WindowId> = ws.columns
-            .iter()
-            .flat_map(|c| c.windows().iter().copied())
-            .collect();
-        all_windows.sort();
-        let len_before = all_windows.len();
-        all_windows.dedup();
-        assert_eq!(all_windows.len(), len_before, "Duplicate windows found");
+        assert_eq!(ws.columns()[1].windows(), &[10, 11]);
+        assert_eq!(ws.columns()[1].width(), 700);
+        assert_eq!(ws.focused_column_index(), 1);
+        assert_eq!(ws.focused_window(), Some(11));
+        assert!(matches!(
+            ws.insert_column(Column::new(2, 400), 0),
+            Err(LayoutError::DuplicateWindow(2))
+        ));
     }
 
     #[test]
-    fn test_column_partial_eq() {
-        let col1 = Column::new(1, 400);
-        let col2 = Column::new(1, 400);
-        let col3 = Column::new(2, 400);
-        let col4 = Column::new(1, 500);
-
-        assert_eq!(col1, col2);
-        assert_ne!(col1, col3); // Different window
-        assert_ne!(col1, col4); // Different width
-    }
+    fn test_insert_column_stack_only_appends() {
+        let mut ws = Workspace::new();
+        ws.set_layout_mode(LayoutMode::StackOnly);
+        ws.insert_window(1, None).unwrap();
 
-    // ========================================================================
-    // Animation Tests
-    // ========================================================================
+        let mut column = Column::new(10, 700);
+        column.add_window(11);
+        ws.insert_column(column, 0).unwrap();
 
-    #[test]
-    fn test_easing_linear() {
-        assert!((Easing::Linear.apply(0.0) - 0.0).abs() < f64::EPSILON);
-        assert!((Easing::Linear.apply(0.25) - 0.25).abs() < f64::EPSILON);
-        assert!((Easing::Linear.apply(0.5) - 0.5).abs() < f64::EPSILON);
-        assert!((Easing::Linear.apply(0.75) - 0.75).abs() < f64::EPSILON);
-        assert!((Easing::Linear.apply(1.0) - 1.0).abs() < f64::EPSILON);
+        assert_eq!(ws.column_count(), 1);
+        assert_eq!(ws.columns()[0].windows(), &[1, 10, 11]);
+        assert_eq!(ws.focused_window(), Some(10));
     }
 
     #[test]
-    fn test_easing_ease_out() {
-        // EaseOut starts fast, ends slow (cubic)
-        let ease_out = Easing::EaseOut;
+    fn test_width_fraction_roundtrip() {
+        let ws = Workspace::with_gaps(10, 10);
+        // 1920 - 20 outer gaps = 1900 usable
+        assert_eq!(ws.width_fraction(950, 1920), 0.5);
+        assert_eq!(ws.width_from_fraction(0.5, 2560), 1270);
+        assert_eq!(ws.width_from_fraction(1.5, 1920), 2850);
+        assert_eq!(ws.width_fraction(500, 20), 0.0);
+    }
 
-        // At t=0, should be 0
-        assert!((ease_out.apply(0.0) - 0.0).abs() < f64::EPSILON);
-        // At t=1, should be 1
-        assert!((ease_out.apply(1.0) - 1.0).abs() < f64::EPSILON);
+    // ====================================================================
+    // Workspace Set Tests
+    // ====================================================================
 
-        // EaseOut should be ahead of linear in the middle
-        assert!(ease_out.apply(0.5) > 0.5);
+    #[test]
+    fn test_empty_like_copies_settings() {
+        let mut ws = Workspace::with_gaps(12, 4);
+        ws.set_layout_mode(LayoutMode::StackOnly);
+        ws.insert_window(1, None).unwrap();
 
-        // Verify cubic formula: 1 - (1 - t)^3
-        let t: f64 = 0.5;
-        let expected = 1.0 - (1.0 - t).powi(3);
-        assert!((ease_out.apply(t) - expected).abs() < f64::EPSILON);
+        let empty = ws.empty_like();
+        assert!(empty.is_empty());
+        assert_eq!(empty.gap(), 12);
+        assert_eq!(empty.outer_gap(), 4);
+        assert_eq!(empty.layout_mode(), LayoutMode::StackOnly);
     }
 
     #[test]
-    fn test_easing_ease_in() {
-        // EaseIn starts slow, ends fast (cubic)
-        let ease_in = Easing::EaseIn;
+    fn test_workspace_set_switch() {
+        let mut set = WorkspaceSet::new();
+        let mut active = Workspace::with_gaps(10, 10);
+        active.insert_window(1, None).unwrap();
+        assert_eq!(set.active_name(), DEFAULT_WORKSPACE_NAME);
 
-        // At t=0, should be 0
-        assert!((ease_in.apply(0.0) - 0.0).abs() < f64::EPSILON);
-        // At t=1, should be 1
-        assert!((ease_in.apply(1.0) - 1.0).abs() < f64::EPSILON);
+        assert!(set.switch("code", &mut active));
+        assert_eq!(set.active_name(), "code");
+        assert!(active.is_empty());
+        assert_eq!(active.gap(), 10);
+        assert_eq!(set.find_window(1), Some(DEFAULT_WORKSPACE_NAME));
+        assert_eq!(set.names(), vec!["code", DEFAULT_WORKSPACE_NAME]);
 
-        // EaseIn should be behind linear in the middle
-        assert!(ease_in.apply(0.5) < 0.5);
+        // Switching to the active workspace is a no-op
+        assert!(!set.switch("code", &mut active));
 
-        // Verify cubic formula: t^3
-        let t: f64 = 0.5;
-        let expected = t.powi(3);
-        assert!((ease_in.apply(t) - expected).abs() < f64::EPSILON);
+        active.insert_window(2, None).unwrap();
+        assert!(set.switch(DEFAULT_WORKSPACE_NAME, &mut active));
+        assert_eq!(active.all_window_ids(), vec![1]);
+        assert_eq!(set.find_window(2), Some("code"));
+        assert_eq!(set.all_window_ids(), vec![2]);
     }
 
     #[test]
-    fn test_easing_ease_in_out() {
-        let ease_in_out = Easing::EaseInOut;
+    fn test_workspace_set_discards_empty_named_workspaces() {
+        let mut set = WorkspaceSet::new();
+        let mut active = Workspace::new();
 
-        // At t=0, should be 0
-        assert!((ease_in_out.apply(0.0) - 0.0).abs() < f64::EPSILON);
-        // At t=1, should be 1
-        assert!((ease_in_out.apply(1.0) - 1.0).abs() < f64::EPSILON);
-        // At t=0.5, should be 0.5 (inflection point)
-        assert!((ease_in_out.apply(0.5) - 0.5).abs() < f64::EPSILON);
+        set.switch("chat", &mut active);
+        set.switch("mail", &mut active);
+        // "chat" was empty and is gone; the default workspace is always kept
+        assert_eq!(set.names(), vec!["mail", DEFAULT_WORKSPACE_NAME]);
 
-        // First half should be behind linear
-        assert!(ease_in_out.apply(0.25) < 0.25);
-        // Second half should be ahead of linear
-        assert!(ease_in_out.apply(0.75) > 0.75);
+        active.add_floating(5, Rect::new(0, 0, 100, 100)).unwrap();
+        set.switch(DEFAULT_WORKSPACE_NAME, &mut active);
+        assert_eq!(set.find_window(5), Some("mail"));
     }
 
     #[test]
-    fn test_easing_clamps_input() {
-        // Values outside [0, 1] should be clamped
-        assert!((Easing::Linear.apply(-0.5) - 0.0).abs() < f64::EPSILON);
-        assert!((Easing::Linear.apply(1.5) - 1.0).abs() < f64::EPSILON);
-        assert!((Easing::EaseOut.apply(-1.0) - 0.0).abs() < f64::EPSILON);
-        assert!((Easing::EaseOut.apply(2.0) - 1.0).abs() < f64::EPSILON);
+    fn test_workspace_set_keeps_workspaces_with_only_parked_windows() {
+        let mut set = WorkspaceSet::new();
+        let mut active = Workspace::new();
+        set.switch("chat", &mut active);
+        active.insert_window(7, None).unwrap();
+        active.park_window(7).unwrap();
+        assert!(active.is_empty());
+
+        set.switch(DEFAULT_WORKSPACE_NAME, &mut active);
+        assert_eq!(set.find_window(7), Some("chat"));
+        assert_eq!(set.all_window_ids(), vec![7]);
     }
 
     #[test]
-    fn test_easing_default_is_ease_out() {
-        assert_eq!(Easing::default(), Easing::EaseOut);
+    fn test_workspace_set_inactive_or_create_and_remove() {
+        let mut set = WorkspaceSet::new();
+        let template = Workspace::with_gaps(8, 8);
+        assert!(set.inactive_or_create(DEFAULT_WORKSPACE_NAME, &template).is_none());
+
+        let chat = set.inactive_or_create("chat", &template).unwrap();
+        assert_eq!(chat.gap(), 8);
+        chat.insert_window(7, None).unwrap();
+        set.inactive_or_create("chat", &template).unwrap().add_floating(8, Rect::new(0, 0, 50, 50)).unwrap();
+        assert_eq!(set.names(), vec![DEFAULT_WORKSPACE_NAME, "chat"]);
+
+        assert!(set.remove_window(7));
+        assert!(set.remove_window(8));
+        assert!(!set.remove_window(7));
+        assert!(set.all_window_ids().is_empty());
+
+        let taken = set.take_inactive();
+        assert_eq!(taken.len(), 1);
+        assert_eq!(set.names(), vec![DEFAULT_WORKSPACE_NAME]);
     }
 
-    #[test]
-    fn test_scroll_animation_new() {
-        let anim = ScrollAnimation::new(0.0, 100.0, 200, Easing::Linear);
+    // ====================================================================
+    // Tabbed Column Tests
+    // ====================================================================
 
-        assert!((anim.start_offset - 0.0).abs() < f64::EPSILON);
-        assert!((anim.target_offset - 100.0).abs() < f64::EPSILON);
-        assert_eq!(anim.duration_ms, 200);
-        assert_eq!(anim.elapsed_ms, 0);
-        assert_eq!(anim.easing, Easing::Linear);
+    fn tabbed_workspace() -> Workspace {
+        let mut ws = Workspace::with_gaps(10, 10);
+        ws.insert_window(1, Some(800)).unwrap();
+        ws.insert_window_in_column(2, 0).unwrap();
+        ws.insert_window_in_column(3, 0).unwrap();
+        ws.insert_window(4, Some(800)).unwrap();
+        ws.focus_left();
+        ws
     }
 
     #[test]
-    fn test_scroll_animation_with_defaults() {
-        let anim = ScrollAnimation::with_defaults(50.0, 150.0);
+    fn test_toggle_column_tabbed() {
+        let mut ws = Workspace::new();
+        assert_eq!(ws.toggle_focused_column_tabbed(), None);
 
-        assert!((anim.start_offset - 50.0).abs() < f64::EPSILON);
-        assert!((anim.target_offset - 150.0).abs() < f64::EPSILON);
-        assert_eq!(anim.duration_ms, DEFAULT_ANIMATION_DURATION_MS);
-        assert_eq!(anim.easing, Easing::default());
+        let mut ws = tabbed_workspace();
+        assert_eq!(ws.toggle_focused_column_tabbed(), Some(true));
+        assert!(ws.columns()[0].is_tabbed());
+        assert!(!ws.columns()[1].is_tabbed());
+        assert_eq!(ws.toggle_focused_column_tabbed(), Some(false));
+        assert!(!ws.columns()[0].is_tabbed());
     }
 
     #[test]
-    fn test_scroll_animation_progress() {
-        let mut anim = ScrollAnimation::new(0.0, 100.0, 100, Easing::Linear);
+    fn test_tabbed_column_shows_focused_window_full_height() {
+        let mut ws = tabbed_workspace();
+        let viewport = Rect::new(0, 0, 1920, 1080);
+        ws.focus_down();
+        assert_eq!(ws.focused_window(), Some(2));
+        ws.toggle_focused_column_tabbed();
+
+        for placements in [ws.compute_placements(viewport), ws.compute_placements_animated(viewport)] {
+            let tab = |id| placements.iter().find(|p| p.window_id == id).unwrap();
+            assert_eq!(tab(2).visibility, Visibility::Visible);
+            assert_eq!(tab(2).rect, Rect::new(10, 10, 800, 1060));
+            assert_eq!(tab(1).visibility, Visibility::OffScreenLeft);
+            assert_eq!(tab(3).visibility, Visibility::OffScreenLeft);
+            assert_eq!(tab(4).visibility, Visibility::Visible);
+        }
 
-        assert!((anim.progress() - 0.0).abs() < f64::EPSILON);
+        // Focus up/down switches tabs
+        ws.focus_down();
+        let placements = ws.compute_placements(viewport);
+        let visible: Vec<_> = placements.iter().filter(|p| p.visibility == Visibility::Visible).map(|p| p.window_id).collect();
+        assert_eq!(visible, vec![3, 4]);
+    }
 
-        anim.elapsed_ms = 50;
-        assert!((anim.progress() - 0.5).abs() < f64::EPSILON);
+    #[test]
+    fn test_tabbed_column_keeps_active_tab_when_unfocused() {
+        let mut ws = tabbed_workspace();
+        let viewport = Rect::new(0, 0, 1920, 1080);
+        ws.focus_down();
+        ws.toggle_focused_column_tabbed();
 
-        anim.elapsed_ms = 100;
-        assert!((anim.progress() - 1.0).abs() < f64::EPSILON);
+        ws.focus_right();
+        assert_eq!(ws.focused_window(), Some(4));
+        let placements = ws.compute_placements(viewport);
+        let shown = placements.iter().find(|p| p.column_index == 0 && p.visibility == Visibility::Visible);
+        assert_eq!(shown.map(|p| p.window_id), Some(2));
 
-        // Over time should clamp to 1.0
-        anim.elapsed_ms = 150;
-        assert!((anim.progress() - 1.0).abs() < f64::EPSILON);
+        // Coming back focuses the active tab
+        ws.focus_left();
+        assert_eq!(ws.focused_window(), Some(2));
     }
 
     #[test]
-    fn test_scroll_animation_progress_zero_duration() {
-        let anim = ScrollAnimation::new(0.0, 100.0, 0, Easing::Linear);
-        // Zero duration should return 1.0 progress immediately
-        assert!((anim.progress() - 1.0).abs() < f64::EPSILON);
+    fn test_untabbed_column_stacks_again() {
+        let mut ws = tabbed_workspace();
+        let viewport = Rect::new(0, 0, 1920, 1080);
+        ws.toggle_focused_column_tabbed();
+        ws.toggle_focused_column_tabbed();
+        let placements = ws.compute_placements(viewport);
+        assert!(placements.iter().all(|p| p.visibility == Visibility::Visible));
+        assert!(placements.iter().filter(|p| p.column_index == 0).all(|p| p.rect.height < 400));
     }
 
+    // ========================================================================
+    // Tagged Column Tests
+    // ========================================================================
+
     #[test]
-    fn test_scroll_animation_current_offset_linear() {
-        let mut anim = ScrollAnimation::new(0.0, 100.0, 100, Easing::Linear);
+    fn test_insert_window_tagged_shares_column() {
+        let mut ws = Workspace::new();
+        ws.insert_window_tagged(1, "comm", Some(500)).unwrap();
+        ws.insert_window(2, None).unwrap();
+        ws.insert_window_tagged(3, "comm", Some(900)).unwrap();
 
-        // At start
-        assert!((anim.current_offset() - 0.0).abs() < f64::EPSILON);
+        assert_eq!(ws.column_count(), 2);
+        assert_eq!(ws.find_tagged_column("comm"), Some(0));
+        assert_eq!(ws.columns()[0].windows(), &[1, 3]);
+        assert_eq!(ws.columns()[0].width(), 500);
+        assert_eq!(ws.focused_window(), Some(3));
+    }
 
-        // At midpoint
-        anim.elapsed_ms = 50;
-        assert!((anim.current_offset() - 50.0).abs() < f64::EPSILON);
+    #[test]
+    fn test_insert_window_tagged_recreates_column() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, None).unwrap();
+        ws.insert_window_tagged(2, "comm", None).unwrap();
+        ws.remove_window(2).unwrap();
+        assert_eq!(ws.find_tagged_column("comm"), None);
 
-        // At end
-        anim.elapsed_ms = 100;
-        assert!((anim.current_offset() - 100.0).abs() < f64::EPSILON);
+        ws.insert_window_tagged(3, "comm", None).unwrap();
+        assert_eq!(ws.find_tagged_column("comm"), Some(1));
+        assert_eq!(ws.find_tagged_column("other"), None);
+        assert!(ws.insert_window_tagged(3, "comm", None).is_err());
     }
 
     #[test]
-    fn test_scroll_animation_current_offset_eased() {
-        let mut anim = ScrollAnimation::new(0.0, 100.0, 100, Easing::EaseOut);
-
-        // At midpoint with ease out, should be ahead of 50
-        anim.elapsed_ms = 50;
-        assert!(anim.current_offset() > 50.0);
+    fn test_insert_window_tagged_stack_only() {
+        let mut ws = Workspace::new();
+        ws.set_layout_mode(LayoutMode::StackOnly);
+        ws.insert_window_tagged(1, "comm", None).unwrap();
+        ws.insert_window_tagged(2, "mail", None).unwrap();
+        assert_eq!(ws.column_count(), 1);
+        assert_eq!(ws.find_tagged_column("comm"), None);
     }
 
     #[test]
-    fn test_scroll_animation_negative_direction() {
-        let mut anim = ScrollAnimation::new(100.0, 0.0, 100, Easing::Linear);
+    fn test_insert_window_with_joins_anchor_column() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, Some(500)).unwrap();
+        ws.insert_window(2, None).unwrap();
+        ws.insert_window_with(3, 1, Some(900)).unwrap();
 
-        // At start
-        assert!((anim.current_offset() - 100.0).abs() < f64::EPSILON);
+        assert_eq!(ws.column_count(), 2);
+        assert_eq!(ws.columns()[0].windows(), &[1, 3]);
+        assert_eq!(ws.columns()[0].width(), 500);
+        assert_eq!(ws.focused_window(), Some(3));
 
-        // At midpoint - should be halfway back to 0
-        anim.elapsed_ms = 50;
-        assert!((anim.current_offset() - 50.0).abs() < f64::EPSILON);
+        // An anchor that isn't tiled here opens a new column
+        ws.insert_window_with(4, 99, Some(900)).unwrap();
+        assert_eq!(ws.column_count(), 3);
+        assert_eq!(ws.find_window_location(4).map(|(_, w)| w), Some(0));
+        assert!(ws.insert_window_with(4, 1, None).is_err());
+    }
 
-        // At end
-        anim.elapsed_ms = 100;
-        assert!((anim.current_offset() - 0.0).abs() < f64::EPSILON);
+    // ========================================================================
+    // Window Index Tests
+    // ========================================================================
+
+    /// Assert that the lookup index matches the column structure.
+    fn assert_index_consistent(ws: &Workspace) {
+        assert_eq!(ws.window_index(), &build_window_index(&ws.columns));
+        for (col_idx, column) in ws.columns().iter().enumerate() {
+            for (win_idx, &w) in column.windows().iter().enumerate() {
+                assert_eq!(ws.find_window_location(w), Some((col_idx, win_idx)));
+            }
+        }
     }
 
     #[test]
-    fn test_scroll_animation_is_complete() {
-        let mut anim = ScrollAnimation::new(0.0, 100.0, 100, Easing::Linear);
+    fn test_window_index_tracks_mutations() {
+        let mut ws = Workspace::new();
+        for id in 1..=4 {
+            ws.insert_window(id, None).unwrap();
+            assert_index_consistent(&ws);
+        }
+        ws.insert_window_in_column(5, 1).unwrap();
+        assert_index_consistent(&ws);
+        ws.insert_window_tagged(6, "comm", None).unwrap();
+        ws.insert_window_tagged(7, "comm", None).unwrap();
+        assert_index_consistent(&ws);
 
-        assert!(!anim.is_complete());
+        ws.move_column_left();
+        assert_index_consistent(&ws);
+        ws.move_column_right();
+        assert_index_consistent(&ws);
+        ws.move_window_to_index(1, 3).unwrap();
+        assert_index_consistent(&ws);
 
-        anim.elapsed_ms = 50;
-        assert!(!anim.is_complete());
+        ws.remove_window(5).unwrap();
+        ws.remove_window(2).unwrap();
+        assert_index_consistent(&ws);
+        assert!(!ws.contains_window(2));
+        assert_eq!(ws.find_window_location(2), None);
 
-        anim.elapsed_ms = 100;
-        assert!(anim.is_complete());
+        let (column, focused) = ws.take_focused_column().unwrap();
+        assert_index_consistent(&ws);
+        ws.insert_column(column, focused).unwrap();
+        assert_index_consistent(&ws);
 
-        anim.elapsed_ms = 150;
-        assert!(anim.is_complete());
+        ws.set_layout_mode(LayoutMode::StackOnly);
+        assert_index_consistent(&ws);
+        ws.insert_window(8, None).unwrap();
+        assert_index_consistent(&ws);
     }
 
     #[test]
-    fn test_scroll_animation_tick() {
-        let mut anim = ScrollAnimation::new(0.0, 100.0, 100, Easing::Linear);
+    fn test_window_index_floating_round_trip() {
+        let mut ws = Workspace::new();
+        let viewport = Rect::new(0, 0, 1920, 1080);
+        ws.insert_window(1, None).unwrap();
+        ws.insert_window(2, None).unwrap();
 
-        // Tick returns true while running
-        assert!(anim.tick(25));
-        assert_eq!(anim.elapsed_ms, 25);
+        ws.toggle_floating(viewport);
+        assert_index_consistent(&ws);
+        assert!(ws.contains_window(2));
+        assert_eq!(ws.find_window_location(2), None);
 
-        assert!(anim.tick(25));
-        assert_eq!(anim.elapsed_ms, 50);
+        ws.unfloat_window(2);
+        assert_index_consistent(&ws);
+        assert!(ws.focus_window(1).is_ok());
+        assert!(ws.focus_window(99).is_err());
+    }
 
-        assert!(anim.tick(25));
-        assert_eq!(anim.elapsed_ms, 75);
+    #[test]
+    fn test_window_index_built_lazily() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, None).unwrap();
+        ws.insert_window(2, None).unwrap();
 
-        // Final tick completes
-        assert!(!anim.tick(25));
-        assert_eq!(anim.elapsed_ms, 100);
+        // Deserialized workspaces start without an index
+        ws.window_index = OnceCell::new();
+        assert_eq!(ws.find_window_location(2), Some((1, 0)));
+        assert_index_consistent(&ws);
 
-        // Further ticks still return false
-        assert!(!anim.tick(50));
-        assert_eq!(anim.elapsed_ms, 150);
+        let clone = ws.clone();
+        assert_index_consistent(&clone);
     }
 
+    // ========================================================================
+    // Width Preset Tests
+    // ========================================================================
+
     #[test]
-    fn test_scroll_animation_tick_saturating() {
-        let mut anim = ScrollAnimation::new(0.0, 100.0, 100, Easing::Linear);
+    fn test_cycle_focused_column_width() {
+        let mut ws = Workspace::with_gaps(0, 0);
+        let presets = [0.25, 0.5, 1.0];
+        ws.insert_window(1, Some(700)).unwrap();
+
+        // From an off-preset width, snap to the next wider preset
+        assert_eq!(ws.cycle_focused_column_width(&presets, 2000), Some(0.5));
+        assert_eq!(ws.columns()[0].width(), 1000);
+        assert_eq!(ws.cycle_focused_column_width(&presets, 2000), Some(1.0));
+        assert_eq!(ws.columns()[0].width(), 2000);
+
+        // Wraps around after the widest preset
+        assert_eq!(ws.cycle_focused_column_width(&presets, 2000), Some(0.25));
+        assert_eq!(ws.columns()[0].width(), 500);
+    }
 
-        // Large tick value should not overflow
-        anim.elapsed_ms = u64::MAX - 10;
-        anim.tick(100);
-        assert_eq!(anim.elapsed_ms, u64::MAX); // Saturates at MAX
+    #[test]
+    fn test_next_preset_fraction_tolerates_rounding() {
+        let ws = Workspace::with_gaps(0, 0);
+        let presets = [0.333, 0.667];
+        // 0.333 of 1920 is 639.36, rounded to 639; a column one pixel off still counts
+        assert_eq!(ws.next_preset_fraction(&presets, 640, 1920), Some(0.667));
+        assert_eq!(ws.next_preset_fraction(&presets, 1281, 1920), Some(0.333));
+        assert_eq!(ws.next_preset_fraction(&[], 640, 1920), None);
     }
 
     #[test]
-    fn test_scroll_animation_target() {
-        let anim = ScrollAnimation::new(0.0, 456.78, 100, Easing::Linear);
-        assert!((anim.target() - 456.78).abs() < f64::EPSILON);
+    fn test_cycle_focused_column_width_empty() {
+        let mut ws = Workspace::new();
+        assert_eq!(ws.cycle_focused_column_width(&[0.5], 1920), None);
     }
 
     // ========================================================================
-    // Workspace Animation Tests
+    // Animation Interruption Tests
     // ========================================================================
 
-    #[test]
-    fn test_workspace_is_animating() {
+    fn animating_workspace() -> Workspace {
         let mut ws = Workspace::with_gaps(10, 10);
-        // Add enough windows to have scrollable content
         for i in 1..=5 {
             ws.insert_window(i, Some(400)).unwrap();
         }
-        // Total: 10 + (5*400) + (4*10) + 10 = 2060
+        // Total width 2060; max scroll with a 1000px viewport is 1060
+        ws.start_scroll_animation(1000.0, 1000, Some(100), Some(Easing::Linear));
+        ws.tick_animation(50);
+        ws
+    }
 
+    #[test]
+    fn test_scroll_by_starts_from_visual_position() {
+        let mut ws = animating_workspace();
+        let visual = ws.effective_scroll_offset();
+        assert!(visual > 400.0 && visual < 600.0);
+
+        ws.scroll_by(50.0, 1000);
         assert!(!ws.is_animating());
+        assert!((ws.scroll_offset() - (visual + 50.0)).abs() < 1e-9);
+    }
 
-        // Viewport 500 means max_scroll = 2060 - 500 = 1560
-        ws.start_scroll_animation(100.0, 500, None, None);
-        assert!(ws.is_animating());
+    #[test]
+    fn test_ensure_focused_visible_cancels_animation() {
+        let mut ws = animating_workspace();
+        let visual = ws.effective_scroll_offset();
+        ws.set_centering_mode(CenteringMode::JustInView);
+
+        // Window 3's column (x = 830..1230) is in view at the visual position,
+        // so the viewport stays there instead of continuing to the old target
+        ws.focus_window(3).unwrap();
+        ws.ensure_focused_visible(1000);
+        assert!(!ws.is_animating());
+        assert!((ws.scroll_offset() - visual).abs() < 1e-9);
+    }
+
+    // ========================================================================
+    // Arrangement Restore Tests
+    // ========================================================================
+
+    fn column_of(windows: &[WindowId], width: i32) -> Column {
+        let mut column = Column::empty(width);
+        for &id in windows {
+            column.add_window(id);
+        }
+        column
+    }
+
+    #[test]
+    fn test_arrange_columns_restores_order_and_widths() {
+        let mut ws = Workspace::new();
+        for id in [1, 2, 3, 4] {
+            ws.insert_window(id, Some(400)).unwrap();
+        }
+        ws.focus_window(2).unwrap();
+
+        let placed = ws.arrange_columns(vec![column_of(&[3, 1], 700), column_of(&[2], 500)]);
+        assert_eq!(placed, 3);
+
+        let layout: Vec<(Vec<WindowId>, i32)> =
+            ws.columns().iter().map(|c| (c.windows().to_vec(), c.width())).collect();
+        assert_eq!(layout, vec![(vec![3, 1], 700), (vec![2], 500), (vec![4], 400)]);
+        assert_eq!(ws.focused_window(), Some(2));
+        assert_index_consistent(&ws);
+    }
+
+    #[test]
+    fn test_arrange_columns_skips_unknown_and_duplicate_windows() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(400)).unwrap();
+
+        let mut tagged = column_of(&[99, 2, 2], 600);
+        tagged.set_tag(Some("comm".to_string()));
+        let placed = ws.arrange_columns(vec![column_of(&[98], 300), tagged]);
+
+        assert_eq!(placed, 1);
+        assert_eq!(ws.column_count(), 2);
+        assert_eq!(ws.columns()[0].windows(), &[2]);
+        assert_eq!(ws.find_tagged_column("comm"), Some(0));
+        assert_index_consistent(&ws);
+    }
+
+    #[test]
+    fn test_arrange_columns_skips_floating_and_parked_windows() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(400)).unwrap();
+        ws.insert_window(3, Some(400)).unwrap();
+        ws.add_floating(4, Rect::new(10, 10, 300, 200)).unwrap();
+        ws.park_window(3).unwrap();
+
+        let placed = ws.arrange_columns(vec![column_of(&[4, 2], 600), column_of(&[3], 500)]);
+
+        assert_eq!(placed, 1);
+        let tiled: Vec<&[WindowId]> = ws.columns().iter().map(|c| c.windows()).collect();
+        assert_eq!(tiled, vec![&[2][..], &[1][..]]);
+        assert!(ws.is_floating(4));
+        assert!(ws.is_parked(3));
+        assert_index_consistent(&ws);
+    }
+
+    #[test]
+    fn test_arrange_columns_noop_in_stack_only() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, None).unwrap();
+        ws.insert_window(2, None).unwrap();
+        ws.set_layout_mode(LayoutMode::StackOnly);
+        assert_eq!(ws.arrange_columns(vec![column_of(&[2], 400), column_of(&[1], 400)]), 0);
+        assert_eq!(ws.column_count(), 1);
+    }
+
+    // ========================================================================
+    // Consume and Expel Tests
+    // ========================================================================
 
-        // Complete the animation
-        ws.tick_animation(300);
-        assert!(!ws.is_animating());
+    fn layout_ids(ws: &Workspace) -> Vec<Vec<WindowId>> {
+        ws.columns().iter().map(|c| c.windows().to_vec()).collect()
     }
 
     #[test]
-    fn test_workspace_effective_scroll_offset() {
-        let mut ws = Workspace::with_gaps(10, 10);
-        // Add enough windows to have scrollable content
-        for i in 1..=5 {
-            ws.insert_window(i, Some(400)).unwrap();
+    fn test_consume_into_left_column_removes_empty_source() {
+        let mut ws = Workspace::new();
+        for id in [1, 2, 3] {
+            ws.insert_window(id, Some(400)).unwrap();
         }
-        // Total: 10 + (5*400) + (4*10) + 10 = 2060
-
-        // Initially no animation
-        assert!((ws.effective_scroll_offset() - 0.0).abs() < 1.0);
-
-        // Start animation to 200 with viewport 500 (max_scroll = 1560)
-        ws.start_scroll_animation(200.0, 500, Some(100), Some(Easing::Linear));
-        assert!(ws.is_animating());
-
-        // At start, should be near 0
-        assert!(ws.effective_scroll_offset() < 10.0);
+        ws.focus_window(2).unwrap();
 
-        // Tick halfway
-        ws.tick_animation(50);
-        // Should be around 100 (halfway)
-        assert!(ws.effective_scroll_offset() > 80.0 && ws.effective_scroll_offset() < 120.0);
+        assert!(ws.consume_into_column(true));
+        assert_eq!(layout_ids(&ws), vec![vec![1, 2], vec![3]]);
+        assert_eq!(ws.focused_window(), Some(2));
+        assert_eq!(ws.focused_column_index(), 0);
+        assert_index_consistent(&ws);
     }
 
     #[test]
-    fn test_workspace_start_scroll_animation_clamps_target() {
-        let mut ws = Workspace::with_gaps(10, 10);
+    fn test_consume_into_right_column_from_stack() {
+        let mut ws = Workspace::new();
         ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window_in_column(2, 0).unwrap();
+        ws.insert_window(3, Some(600)).unwrap();
+        ws.focus_window(1).unwrap();
 
-        // Total width: 10 + 400 + 10 = 420
-        // Max scroll with 1000 viewport = max(420 - 1000, 0) = 0
+        assert!(ws.consume_into_column(false));
+        assert_eq!(layout_ids(&ws), vec![vec![2], vec![3, 1]]);
+        assert_eq!(ws.focused_window(), Some(1));
+        assert_eq!(ws.columns()[1].width(), 600);
+        assert_index_consistent(&ws);
+    }
 
-        ws.start_scroll_animation(500.0, 1000, None, None);
+    #[test]
+    fn test_consume_from_tabbed_column_shows_next_tab() {
+        let mut ws = tabbed_workspace();
+        ws.toggle_focused_column_tabbed();
+        ws.focus_window(2).unwrap();
 
-        // Target should be clamped to 0 (can't scroll past content)
-        assert!(!ws.is_animating()); // Already at target (both clamped to 0)
+        assert!(ws.consume_into_column(false));
+        assert_eq!(layout_ids(&ws), vec![vec![1, 3], vec![4, 2]]);
+        let placements = ws.compute_placements(Rect::new(0, 0, 1920, 1080));
+        let shown = placements.iter().find(|p| p.column_index == 0 && p.visibility == Visibility::Visible);
+        assert_eq!(shown.map(|p| p.window_id), Some(3));
     }
 
     #[test]
-    fn test_workspace_tick_animation() {
-        let mut ws = Workspace::with_gaps(10, 10);
+    fn test_consume_clears_fullscreen_of_moved_window() {
+        let mut ws = Workspace::new();
         ws.insert_window(1, Some(400)).unwrap();
         ws.insert_window(2, Some(400)).unwrap();
-        ws.insert_window(3, Some(400)).unwrap();
+        ws.toggle_fullscreen(Rect::new(0, 0, 1920, 1080));
 
-        ws.start_scroll_animation(200.0, 500, Some(100), Some(Easing::Linear));
+        assert!(ws.consume_into_column(true));
+        assert!(!ws.is_fullscreen());
+        assert_eq!(layout_ids(&ws), vec![vec![1, 2]]);
+    }
 
-        // Should be animating
-        assert!(ws.tick_animation(30));
-        assert!(ws.is_animating());
+    #[test]
+    fn test_consume_at_edge_is_noop() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, None).unwrap();
+        ws.insert_window(2, None).unwrap();
+        assert!(!ws.consume_into_column(false));
+        ws.focus_window(1).unwrap();
+        assert!(!ws.consume_into_column(true));
+        assert_eq!(layout_ids(&ws), vec![vec![1], vec![2]]);
+    }
 
-        // Tick to completion
-        assert!(!ws.tick_animation(100));
-        assert!(!ws.is_animating());
+    #[test]
+    fn test_expel_from_column_both_sides() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, Some(500)).unwrap();
+        ws.insert_window_in_column(2, 0).unwrap();
+        ws.insert_window_in_column(3, 0).unwrap();
 
-        // After animation, scroll_offset should be at target
-        assert!((ws.effective_scroll_offset() - 200.0).abs() < 1.0);
+        ws.focus_window(2).unwrap();
+        assert!(ws.expel_from_column(false));
+        assert_eq!(layout_ids(&ws), vec![vec![1, 3], vec![2]]);
+        assert_eq!(ws.focused_window(), Some(2));
+        assert_eq!(ws.columns()[1].width(), 500);
+
+        ws.focus_window(3).unwrap();
+        assert!(ws.expel_from_column(true));
+        assert_eq!(layout_ids(&ws), vec![vec![3], vec![1], vec![2]]);
+        assert_eq!(ws.focused_window(), Some(3));
+        assert_index_consistent(&ws);
+
+        // A lone window has nothing to leave
+        assert!(!ws.expel_from_column(true));
     }
 
     #[test]
-    fn test_workspace_stop_animation() {
-        let mut ws = Workspace::with_gaps(10, 10);
-        ws.insert_window(1, Some(400)).unwrap();
-        ws.insert_window(2, Some(400)).unwrap();
+    fn test_split_column_inserts_right_of_stack() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, Some(500)).unwrap();
+        ws.insert_window_in_column(2, 0).unwrap();
         ws.insert_window(3, Some(400)).unwrap();
+        ws.focus_window(1).unwrap();
 
-        ws.start_scroll_animation(200.0, 500, Some(100), Some(Easing::Linear));
-        ws.tick_animation(50);
+        assert!(ws.split_column());
+        assert_eq!(layout_ids(&ws), vec![vec![2], vec![1], vec![3]]);
+        assert_eq!(ws.focused_window(), Some(1));
+        assert_eq!(ws.focused_column_index(), 1);
+        assert_eq!(ws.columns()[1].width(), 500);
+        assert_index_consistent(&ws);
 
-        // Stop should snap to target
-        ws.stop_animation();
-        assert!(!ws.is_animating());
-        assert!((ws.effective_scroll_offset() - 200.0).abs() < 1.0);
+        assert!(!ws.split_column());
     }
 
     #[test]
-    fn test_workspace_cancel_animation() {
-        let mut ws = Workspace::with_gaps(10, 10);
-        ws.insert_window(1, Some(400)).unwrap();
-        ws.insert_window(2, Some(400)).unwrap();
-        ws.insert_window(3, Some(400)).unwrap();
+    fn test_promote_window_swaps_with_top() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, None).unwrap();
+        ws.insert_window_in_column(2, 0).unwrap();
+        ws.insert_window_in_column(3, 0).unwrap();
+        ws.focus_window(3).unwrap();
 
-        ws.start_scroll_animation(200.0, 500, Some(100), Some(Easing::Linear));
-        ws.tick_animation(50);
+        assert!(ws.promote_window());
+        assert_eq!(layout_ids(&ws), vec![vec![3, 2, 1]]);
+        assert_eq!(ws.focused_window(), Some(3));
+        assert_eq!(ws.focused_window_index_in_column(), 0);
+        assert_index_consistent(&ws);
 
-        let current = ws.effective_scroll_offset();
-        // Should be around 100 (halfway)
+        // Already at the top
+        assert!(!ws.promote_window());
+        assert_eq!(layout_ids(&ws), vec![vec![3, 2, 1]]);
+    }
 
-        // Cancel should stay at current position
-        ws.cancel_animation();
-        assert!(!ws.is_animating());
-        assert!((ws.effective_scroll_offset() - current).abs() < 1.0);
+    #[test]
+    fn test_consume_and_expel_noop_in_stack_only() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, None).unwrap();
+        ws.insert_window(2, None).unwrap();
+        ws.set_layout_mode(LayoutMode::StackOnly);
+        assert!(!ws.expel_from_column(false));
+        assert!(!ws.consume_into_column(true));
+        assert_eq!(ws.column_count(), 1);
     }
 
+    // ========================================================================
+    // Minimized Window Tests
+    // ========================================================================
+
     #[test]
-    fn test_workspace_animation_no_effect_when_at_target() {
-        let mut ws = Workspace::with_gaps(10, 10);
-        ws.insert_window(1, Some(400)).unwrap();
+    fn test_park_focused_window_moves_focus_and_closes_gap() {
+        let mut ws = Workspace::new();
+        for id in [1, 2, 3] {
+            ws.insert_window(id, Some(400)).unwrap();
+        }
+        ws.focus_window(3).unwrap();
 
-        // Already at offset 0, trying to animate to 0 shouldn't start animation
-        ws.start_scroll_animation(0.0, 1000, None, None);
-        assert!(!ws.is_animating());
+        ws.park_window(3).unwrap();
+        assert_eq!(layout_ids(&ws), vec![vec![1], vec![2]]);
+        assert_eq!(ws.focused_window(), Some(2));
+        assert!(ws.is_parked(3));
+        assert!(ws.contains_window(3));
+        assert!(ws.all_window_ids().contains(&3));
+        assert_index_consistent(&ws);
     }
 
     #[test]
-    fn test_workspace_animation_interrupt() {
-        let mut ws = Workspace::with_gaps(10, 10);
-        ws.insert_window(1, Some(400)).unwrap();
-        ws.insert_window(2, Some(400)).unwrap();
-        ws.insert_window(3, Some(400)).unwrap();
-
-        // Start animation to 200
-        ws.start_scroll_animation(200.0, 500, Some(100), Some(Easing::Linear));
-        ws.tick_animation(50);
+    fn test_park_unfocused_window_keeps_focus() {
+        let mut ws = Workspace::new();
+        for id in [1, 2, 3] {
+            ws.insert_window(id, Some(400)).unwrap();
+        }
+        ws.focus_window(3).unwrap();
 
-        // Interrupt with new animation to 300
-        ws.start_scroll_animation(300.0, 500, Some(100), Some(Easing::Linear));
+        ws.park_window(1).unwrap();
+        assert_eq!(ws.focused_window(), Some(3));
+        assert_eq!(ws.focused_column_index(), 1);
+    }
 
-        // New animation should start from current position (~100)
-        assert!(ws.is_animating());
+    #[test]
+    fn test_unpark_restores_column_index_and_width() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, Some(400)).unwrap();
+        ws.insert_window(2, Some(700)).unwrap();
+        ws.insert_window(3, Some(400)).unwrap();
 
-        // Complete new animation
-        ws.tick_animation(150);
-        assert!((ws.effective_scroll_offset() - 300.0).abs() < 1.0);
+        ws.park_window(2).unwrap();
+        assert_eq!(layout_ids(&ws), vec![vec![1], vec![3]]);
+        assert!(ws.unpark_window(2));
+        assert_eq!(layout_ids(&ws), vec![vec![1], vec![2], vec![3]]);
+        assert_eq!(ws.columns()[1].width(), 700);
+        assert_eq!(ws.focused_window(), Some(2));
+        assert!(!ws.is_parked(2));
+        assert!(!ws.unpark_window(2));
+        assert_index_consistent(&ws);
     }
 
     #[test]
-    fn test_compute_placements_animated() {
-        let mut ws = Workspace::with_gaps(10, 10);
+    fn test_unpark_rejoins_stacked_column() {
+        let mut ws = Workspace::new();
         ws.insert_window(1, Some(400)).unwrap();
-        ws.insert_window(2, Some(400)).unwrap();
+        ws.insert_window_in_column(2, 0).unwrap();
+        ws.insert_window_in_column(3, 0).unwrap();
+        ws.focus_window(2).unwrap();
 
-        let viewport = Rect::new(0, 0, 500, 600);
+        ws.park_window(2).unwrap();
+        // Focus moves to the window that slid into its place
+        assert_eq!(ws.focused_window(), Some(3));
+        assert!(ws.unpark_window(2));
+        assert_eq!(layout_ids(&ws), vec![vec![1, 2, 3]]);
+        assert_eq!(ws.focused_window(), Some(2));
+    }
 
-        // Without animation
-        let placements1 = ws.compute_placements_animated(viewport);
-        assert_eq!(placements1.len(), 2);
+    #[test]
+    fn test_unpark_clamps_to_remaining_columns() {
+        let mut ws = Workspace::new();
+        for id in [1, 2, 3] {
+            ws.insert_window(id, Some(400)).unwrap();
+        }
+        ws.park_window(3).unwrap();
+        ws.remove_window(2).unwrap();
+        ws.remove_window(1).unwrap();
+        assert!(ws.is_empty());
 
-        // Start animation that shifts viewport
-        ws.start_scroll_animation(200.0, 500, Some(100), Some(Easing::Linear));
-        ws.tick_animation(100); // Complete
+        assert!(ws.unpark_window(3));
+        assert_eq!(layout_ids(&ws), vec![vec![3]]);
+        assert_eq!(ws.focused_window(), Some(3));
+    }
 
-        let placements2 = ws.compute_placements_animated(viewport);
-        assert_eq!(placements2.len(), 2);
+    #[test]
+    fn test_remove_parked_window_forgets_it() {
+        let mut ws = Workspace::new();
+        ws.insert_window(1, None).unwrap();
+        ws.insert_window(2, None).unwrap();
+        ws.park_window(2).unwrap();
+        ws.remove_window(2).unwrap();
+        assert!(!ws.contains_window(2));
+        assert_eq!(ws.parked_window_ids().count(), 0);
+        assert!(ws.park_window(5).is_err());
+    }
 
-        // Window positions should be shifted left (viewport scrolled right)
-        assert!(placements2[0].rect.x < placements1[0].rect.x);
+    // ========================================================================
+    // Floating Window Adjustment
+    // ========================================================================
+
+    fn floating(x: i32, y: i32, width: i32, height: i32) -> FloatingWindow {
+        FloatingWindow { id: 1, rect: Rect::new(x, y, width, height) }
     }
 
     #[test]
-    fn test_ensure_focused_visible_animated_center_mode() {
-        let mut ws = Workspace::with_gaps(10, 10);
-        // Add enough windows to require scrolling
-        for i in 1..=5 {
-            ws.insert_window(i, Some(400)).unwrap();
-        }
-        // Total: 10 + (5*400) + (4*10) + 10 = 2060
+    fn test_floating_clamp_to_work_area() {
+        let area = Rect::new(0, 0, 1920, 1040);
+        let mut window = floating(1800, -50, 400, 300);
+        window.clamp_to(area);
+        assert_eq!(window.rect, Rect::new(1520, 0, 400, 300));
 
-        // Focus is at column 4 (last inserted), scroll to make it visible first
-        ws.ensure_focused_visible(500);
+        // Larger than the area: shrunk to fit
+        let mut window = floating(-100, 100, 2500, 1200);
+        window.clamp_to(area);
+        assert_eq!(window.rect, area);
+    }
 
-        // Now focus first column which is off-screen
-        ws.focus_left();
-        ws.focus_left();
-        ws.focus_left();
-        ws.focus_left();
+    #[test]
+    fn test_floating_move_and_resize_stay_in_area() {
+        let area = Rect::new(1920, 0, 1920, 1040);
+        let mut window = floating(2000, 100, 600, 400);
+        window.move_by(-500, 50, area);
+        assert_eq!(window.rect, Rect::new(1920, 150, 600, 400));
 
-        // This should trigger an animation because column 0 is now off-screen
-        ws.ensure_focused_visible_animated(500);
+        window.resize_by(200, 2000, area);
+        assert_eq!(window.rect, Rect::new(1920, 0, 800, 1040));
 
-        // Should start an animation to scroll back to column 0
-        assert!(ws.is_animating());
+        window.resize_by(-5000, -5000, area);
+        assert_eq!(window.rect.width, MIN_FLOATING_SIZE);
+        assert_eq!(window.rect.height, MIN_FLOATING_SIZE);
     }
 
-    // ========================================================================
-    // Floating Window Tests
-    // ========================================================================
+    #[test]
+    fn test_floating_center_and_snap() {
+        let area = Rect::new(0, 40, 1920, 1000);
+        let mut window = floating(10, 50, 800, 600);
+        window.center_in(area);
+        assert_eq!(window.rect, Rect::new(560, 240, 800, 600));
+
+        window.snap_to(SnapEdge::Right, area);
+        assert_eq!(window.rect, Rect::new(1120, 240, 800, 600));
+        window.snap_to(SnapEdge::Top, area);
+        assert_eq!(window.rect, Rect::new(1120, 40, 800, 600));
+        window.snap_to(SnapEdge::Bottom, area);
+        assert_eq!(window.rect.bottom(), area.bottom());
+        window.snap_to(SnapEdge::Left, area);
+        assert_eq!(window.rect.x, 0);
+    }
 
     #[test]
-    fn test_add_floating_window() {
+    fn test_floating_window_mut() {
         let mut ws = Workspace::new();
-        let rect = Rect::new(100, 100, 400, 300);
+        ws.add_floating(7, Rect::new(0, 0, 400, 300)).unwrap();
+        ws.floating_window_mut(7).unwrap().move_by(10, 20, Rect::new(0, 0, 1920, 1080));
+        assert_eq!(ws.floating_windows()[0].rect, Rect::new(10, 20, 400, 300));
+        assert!(ws.floating_window_mut(8).is_none());
+    }
 
-        ws.add_floating(1, rect).unwrap();
+    // ====================================================================
+    // Kinetic Scrolling Tests
+    // ====================================================================
 
-        assert!(ws.contains_window(1));
-        assert!(ws.is_floating(1));
-        assert_eq!(ws.floating_count(), 1);
-        assert_eq!(ws.column_count(), 0); // Not in columns
+    #[test]
+    fn test_kinetic_follows_deltas_then_coasts() {
+        let mut anim = KineticAnimation::new(100.0, 5000.0, DEFAULT_KINETIC_FRICTION);
+        for _ in 0..5 {
+            anim.tick(10);
+            anim.push(20.0);
+        }
+        assert_eq!(anim.current_offset(), 200.0);
+        assert!((anim.velocity - 2.0).abs() < 1e-9);
+
+        // Still held: no movement until the gesture is released
+        anim.tick(KINETIC_RELEASE_MS - 10);
+        assert_eq!(anim.current_offset(), 200.0);
+        assert!(!anim.is_coasting());
+
+        let target = anim.target();
+        let mut last = anim.current_offset();
+        while anim.tick(16) {
+            assert!(anim.current_offset() >= last);
+            last = anim.current_offset();
+        }
+        assert!(anim.current_offset() > 300.0);
+        assert!((anim.current_offset() - target).abs() < KINETIC_REST_DISTANCE);
     }
 
     #[test]
-    fn test_floating_window_in_placements() {
-        let mut ws = Workspace::new();
-        let rect = Rect::new(100, 100, 400, 300);
+    fn test_kinetic_stops_at_strip_edges() {
+        let mut anim = KineticAnimation::new(50.0, 1000.0, DEFAULT_KINETIC_FRICTION);
+        anim.push(-40.0);
+        anim.tick(8);
+        anim.push(-40.0);
+        assert_eq!(anim.current_offset(), 0.0);
+
+        anim.tick(KINETIC_RELEASE_MS + 100);
+        assert_eq!(anim.current_offset(), 0.0);
+        assert_eq!(anim.velocity, 0.0);
+        assert!(anim.is_complete());
+    }
+
+    #[test]
+    fn test_kinetic_direction_change_resets_velocity() {
+        let mut anim = KineticAnimation::new(500.0, 1000.0, DEFAULT_KINETIC_FRICTION);
+        anim.push(80.0);
+        anim.tick(8);
+        anim.push(-16.0);
+        assert!((anim.velocity + 2.0).abs() < 1e-9);
+
+        let mut bad = KineticAnimation::new(0.0, 100.0, f64::NAN);
+        assert_eq!(bad.friction, DEFAULT_KINETIC_FRICTION);
+        bad.push(f64::INFINITY);
+        assert_eq!(bad.current_offset(), 0.0);
+    }
+
+    #[test]
+    fn test_workspace_pan_kinetic() {
+        let mut ws = Workspace::with_gaps(0, 0);
+        for id in 1..=5 {
+            ws.insert_window(id, Some(800)).unwrap();
+        }
+        ws.set_focus(0, 0).unwrap();
+        ws.ensure_focused_visible(1920);
         let viewport = Rect::new(0, 0, 1920, 1080);
 
-        ws.add_floating(1, rect).unwrap();
+        ws.pan_kinetic(300.0, 1920, DEFAULT_KINETIC_FRICTION);
+        assert!(ws.is_animating());
+        assert_eq!(ws.effective_scroll_offset(), 300.0);
+        assert_eq!(ws.compute_placements_animated(viewport)[0].rect.x, -300);
+        ws.tick_animation(8);
+        ws.pan_kinetic(40.0, 1920, DEFAULT_KINETIC_FRICTION);
+
+        while ws.tick_animation(16) {}
+        let rest = ws.scroll_offset();
+        assert!(rest > 340.0);
+        assert!(rest <= (4000 - 1920) as f64);
+        assert_eq!(ws.focused_column_index(), 0);
 
-        let placements = ws.compute_placements(viewport);
-        assert_eq!(placements.len(), 1);
-        assert_eq!(placements[0].window_id, 1);
-        assert_eq!(placements[0].rect, rect);
-        assert_eq!(placements[0].visibility, Visibility::Visible);
-        assert_eq!(placements[0].column_index, usize::MAX); // Floating sentinel
+        // A command scroll takes over from a kinetic pan
+        ws.pan_kinetic(100.0, 1920, DEFAULT_KINETIC_FRICTION);
+        ws.start_scroll_animation(0.0, 1920, None, None);
+        while ws.tick_animation(16) {}
+        assert_eq!(ws.scroll_offset(), 0.0);
     }
 
+    // ====================================================================
+    // Overview Tests
+    // ====================================================================
+
     #[test]
-    fn test_remove_floating_window() {
-        let mut ws = Workspace::new();
-        let rect = Rect::new(100, 100, 400, 300);
+    fn test_overview_fits_whole_strip() {
+        let mut ws = Workspace::with_gaps(0, 0);
+        for id in 1..=6 {
+            ws.insert_window(id, Some(800)).unwrap();
+        }
+        ws.add_floating(9, Rect::new(100, 100, 300, 200)).unwrap();
+        let viewport = Rect::new(0, 40, 1920, 1000);
+        let scroll_offset = ws.scroll_offset();
 
-        ws.add_floating(1, rect).unwrap();
-        assert!(ws.contains_window(1));
+        let placements = ws.compute_overview_placements(viewport);
+        let tiled: Vec<_> = placements.iter().filter(|p| p.column_index != usize::MAX).collect();
+        assert!(tiled.iter().all(|p| p.visibility == Visibility::Visible));
+        assert!(tiled.iter().all(|p| viewport.contains_rect(&p.rect)));
+        // 4800px strip into 1920px: scale 0.4, centered vertically
+        assert_eq!(tiled[0].rect, Rect::new(0, 340, 320, 400));
+        assert_eq!(tiled[5].rect.right(), 1920);
 
-        let removed = ws.remove_floating(1);
-        assert!(removed);
-        assert!(!ws.contains_window(1));
-        assert_eq!(ws.floating_count(), 0);
+        let floating = placements.iter().find(|p| p.window_id == 9).unwrap();
+        assert_eq!(floating.visibility, Visibility::OffScreenLeft);
+
+        // The real layout is untouched
+        assert_eq!(ws.scroll_offset(), scroll_offset);
+        assert_eq!(ws.compute_placements(viewport)[5].rect.width, 800);
     }
 
     #[test]
-    fn test_remove_nonexistent_floating_window() {
-        let mut ws = Workspace::new();
-        let removed = ws.remove_floating(999);
-        assert!(!removed);
+    fn test_overview_never_zooms_in() {
+        let mut ws = Workspace::with_gaps(0, 0);
+        ws.insert_window(1, Some(800)).unwrap();
+        let viewport = Rect::new(0, 0, 1920, 1000);
+
+        let rect = ws.compute_overview_placements(viewport)[0].rect;
+        assert_eq!(rect.width, 600);
+        assert_eq!(rect.height, 750);
+        assert_eq!(rect.x, (1920 - 600) / 2);
     }
 
     #[test]
-    fn test_update_floating_window() {
-        let mut ws = Workspace::new();
-        let rect1 = Rect::new(100, 100, 400, 300);
-        let rect2 = Rect::new(200, 200, 600, 400);
+    fn test_overview_hit_testing() {
+        let mut ws = Workspace::with_gaps(0, 0);
+        for id in 1..=6 {
+            ws.insert_window(id, Some(800)).unwrap();
+        }
+        let viewport = Rect::new(0, 0, 1920, 1000);
 
-        ws.add_floating(1, rect1).unwrap();
+        let rects = ws.overview_column_rects(viewport);
+        assert_eq!(rects.len(), ws.column_count());
+        assert_eq!(ws.overview_column_at(viewport, rects[2].x + 1, 500), Some(2));
+        assert_eq!(ws.overview_column_at(viewport, rects[0].x + 1, 5), None);
+    }
 
-        let updated = ws.update_floating(1, rect2);
-        assert!(updated);
+    // ====================================================================
+    // Size Constraint Tests
+    // ====================================================================
 
-        let placements = ws.compute_placements(Rect::new(0, 0, 1920, 1080));
-        assert_eq!(placements[0].rect, rect2);
+    #[test]
+    fn test_distribute_heights() {
+        assert_eq!(distribute_heights(1000, &[(1, 0, None), (1, 0, None), (1, 0, None)]), vec![333, 333, 334]);
+        // A tall minimum takes its share from the others
+        assert_eq!(distribute_heights(1000, &[(1, 600, None), (1, 0, None), (1, 0, None)]), vec![600, 200, 200]);
+        // A small maximum gives its excess to the others
+        assert_eq!(distribute_heights(1000, &[(1, 0, Some(200)), (1, 0, None)]), vec![200, 800]);
+        // Minimums that don't fit overflow
+        assert_eq!(distribute_heights(500, &[(1, 400, None), (1, 400, None)]), vec![400, 400]);
+        assert!(distribute_heights(500, &[]).is_empty());
+        // Weights split proportionally, within limits
+        assert_eq!(distribute_heights(1000, &[(3, 0, None), (1, 0, None)]), vec![750, 250]);
+        assert_eq!(distribute_heights(1000, &[(3, 0, None), (1, 400, None)]), vec![600, 400]);
     }
 
     #[test]
-    fn test_floating_and_tiled_windows_together() {
-        let mut ws = Workspace::with_gaps(10, 10);
-        let floating_rect = Rect::new(500, 500, 300, 200);
-        let viewport = Rect::new(0, 0, 1920, 1080);
+    fn test_min_height_redistributes_within_column() {
+        let mut ws = Workspace::with_gaps(0, 0);
+        ws.insert_window(1, Some(800)).unwrap();
+        ws.insert_window(2, Some(800)).unwrap();
+        ws.consume_into_column(true);
+        ws.set_size_constraints(2, SizeConstraints { min_height: 700, ..Default::default() });
+        let viewport = Rect::new(0, 0, 1920, 1000);
+
+        for placements in [ws.compute_placements(viewport), ws.compute_placements_animated(viewport)] {
+            let rect = |id| placements.iter().find(|p| p.window_id == id).unwrap().rect;
+            assert_eq!(rect(1).height, 300);
+            assert_eq!(rect(2).height, 700);
+            assert_eq!(rect(2).y, rect(1).bottom());
+        }
+    }
 
-        // Add tiled window
+    #[test]
+    fn test_min_width_widens_column_and_shifts_neighbors() {
+        let mut ws = Workspace::with_gaps(10, 0);
         ws.insert_window(1, Some(400)).unwrap();
         ws.insert_window(2, Some(400)).unwrap();
-
-        // Add floating window
-        ws.add_floating(3, floating_rect).unwrap();
-
-        assert_eq!(ws.column_count(), 2);
-        assert_eq!(ws.floating_count(), 1);
-        assert!(ws.contains_window(1));
-        assert!(ws.contains_window(2));
-        assert!(ws.contains_window(3));
-        assert!(!ws.is_floating(1));
-        assert!(!ws.is_floating(2));
-        assert!(ws.is_floating(3));
+        ws.set_size_constraints(1, SizeConstraints { min_width: 600, ..Default::default() });
+        let viewport = Rect::new(0, 0, 1920, 1000);
 
         let placements = ws.compute_placements(viewport);
-        assert_eq!(placements.len(), 3);
+        assert_eq!(placements[0].rect.width, 600);
+        assert_eq!(placements[1].rect.x, 610);
+        assert_eq!(ws.total_width(), 1010);
+        // The column keeps its own width for when the limit goes away
+        assert_eq!(ws.column(0).unwrap().width(), 400);
 
-        // Floating window should be last in placements
-        assert_eq!(placements[2].window_id, 3);
-        assert_eq!(placements[2].rect, floating_rect);
+        ws.set_size_constraints(1, SizeConstraints::default());
+        assert_eq!(ws.compute_placements(viewport)[1].rect.x, 410);
     }
 
     #[test]
-    fn test_duplicate_floating_window_rejected() {
-        let mut ws = Workspace::new();
-        let rect = Rect::new(100, 100, 400, 300);
+    fn test_max_width_limits_window_not_column() {
+        let mut ws = Workspace::with_gaps(0, 0);
+        ws.insert_window(1, Some(800)).unwrap();
+        ws.insert_window(2, Some(800)).unwrap();
+        ws.set_size_constraints(1, SizeConstraints { max_width: Some(500), ..Default::default() });
 
-        ws.add_floating(1, rect).unwrap();
+        let placements = ws.compute_placements(Rect::new(0, 0, 1920, 1000));
+        assert_eq!(placements[0].rect.width, 500);
+        assert_eq!(placements[1].rect.x, 800);
+        assert_eq!(ws.size_constraints(1).max_width, Some(500));
+        assert!(ws.size_constraints(2).is_unconstrained());
+    }
 
-        let result = ws.add_floating(1, rect);
-        assert!(matches!(result, Err(LayoutError::DuplicateWindow(1))));
+    // ========================================================================
+    // Window Height Tests
+    // ========================================================================
+
+    fn stacked_workspace() -> Workspace {
+        let mut ws = Workspace::with_gaps(0, 0);
+        ws.insert_window(1, Some(800)).unwrap();
+        ws.insert_window_in_column(2, 0).unwrap();
+        ws
+    }
+
+    fn heights(ws: &Workspace) -> Vec<i32> {
+        ws.compute_placements(Rect::new(0, 0, 1920, 1000)).iter().map(|p| p.rect.height).collect()
     }
 
     #[test]
-    fn test_floating_window_duplicate_with_tiled() {
-        let mut ws = Workspace::new();
+    fn test_resize_focused_window_height() {
+        let mut ws = stacked_workspace();
+        ws.focus_window(1).unwrap();
+        assert_eq!(heights(&ws), vec![500, 500]);
 
-        // Add tiled window
-        ws.insert_window(1, Some(400)).unwrap();
+        assert!(ws.resize_focused_window_height(200, 1000));
+        assert_eq!(heights(&ws), vec![700, 300]);
+        assert!(!ws.columns()[0].has_equal_heights());
 
-        // Try to add same ID as floating - should fail
-        let result = ws.add_floating(1, Rect::new(100, 100, 400, 300));
-        assert!(matches!(result, Err(LayoutError::DuplicateWindow(1))));
-    }
+        // Weights scale with the monitor
+        let placements = ws.compute_placements(Rect::new(0, 0, 1920, 2000));
+        assert_eq!(placements[0].rect.height, 1400);
 
-    // ====================================================================
-    // Fullscreen Tests
-    // ====================================================================
+        // Neighbors keep a minimum height
+        assert!(ws.resize_focused_window_height(5000, 1000));
+        assert_eq!(heights(&ws), vec![1000 - MIN_WINDOW_HEIGHT, MIN_WINDOW_HEIGHT]);
+
+        ws.columns[0].reset_heights();
+        assert_eq!(heights(&ws), vec![500, 500]);
+    }
 
     #[test]
-    fn test_fullscreen_toggle() {
-        let mut ws = Workspace::new();
-        ws.insert_window(1, Some(400)).unwrap();
+    fn test_restore_column_size() {
+        let mut ws = stacked_workspace();
+        ws.focus_window(1).unwrap();
+        let size = ws.columns()[0].size();
+        let width = size.width();
 
-        assert!(!ws.is_fullscreen());
+        ws.resize_focused_column(300);
+        assert!(ws.resize_focused_window_height(200, 1000));
+        assert!(ws.restore_column_size(2, &size));
+        assert_eq!(ws.columns()[0].width(), width);
+        assert_eq!(heights(&ws), vec![500, 500]);
+        assert!(!ws.restore_column_size(99, &size));
+    }
 
-        // Enter fullscreen
-        let entered = ws.toggle_fullscreen();
-        assert!(entered);
-        assert!(ws.is_fullscreen());
-        assert_eq!(ws.fullscreen_window_id(), Some(1));
+    #[test]
+    fn test_resize_window_height_needs_a_stack() {
+        let mut ws = Workspace::with_gaps(0, 0);
+        ws.insert_window(1, Some(800)).unwrap();
+        assert!(!ws.resize_focused_window_height(100, 1000));
 
-        // Exit fullscreen
-        let entered = ws.toggle_fullscreen();
-        assert!(!entered);
-        assert!(!ws.is_fullscreen());
-        assert_eq!(ws.fullscreen_window_id(), None);
+        let mut ws = stacked_workspace();
+        ws.toggle_focused_column_tabbed();
+        assert!(!ws.resize_focused_window_height(100, 1000));
     }
 
     #[test]
-    fn test_fullscreen_empty_workspace() {
-        let mut ws = Workspace::new();
-        let entered = ws.toggle_fullscreen();
-        assert!(!entered);
-        assert!(!ws.is_fullscreen());
+    fn test_window_heights_follow_stack_changes() {
+        let mut ws = stacked_workspace();
+        ws.focus_window(1).unwrap();
+        ws.resize_focused_window_height(300, 1000);
+        assert_eq!(heights(&ws), vec![800, 200]);
+
+        // A new window gets the average weight
+        ws.insert_window_in_column(3, 0).unwrap();
+        assert_eq!(heights(&ws), vec![533, 133, 334]);
+
+        // Removed windows take their weight with them
+        ws.remove_window(1).unwrap();
+        assert_eq!(heights(&ws), vec![285, 715]);
+        ws.remove_window(3).unwrap();
+        assert_eq!(heights(&ws), vec![1000]);
+    }
+
+    // ========================================================================
+    // Column Animation Tests
+    // ========================================================================
+
+    fn xs(ws: &Workspace) -> Vec<(WindowId, i32)> {
+        ws.compute_placements_animated(Rect::new(0, 0, 4000, 1000))
+            .iter()
+            .map(|p| (p.window_id, p.rect.x))
+            .collect()
     }
 
     #[test]
-    fn test_fullscreen_placements() {
-        let mut ws = Workspace::with_gaps(10, 10);
-        let viewport = Rect::new(0, 0, 1920, 1080);
+    fn test_neighbors_slide_into_closed_gap() {
+        let mut ws = Workspace::with_gaps(0, 0);
+        for id in 1..=3 {
+            ws.insert_window(id, Some(500)).unwrap();
+        }
 
-        ws.insert_window(1, Some(400)).unwrap();
-        ws.insert_window(2, Some(400)).unwrap();
-        ws.insert_window(3, Some(400)).unwrap();
+        let before = ws.strip_positions();
+        ws.remove_window(2).unwrap();
+        ws.animate_columns_from(&before, 100);
+        assert!(ws.is_animating());
+        // Window 3 starts where it was
+        assert_eq!(xs(&ws), vec![(1, 0), (3, 1000)]);
 
-        // Focus window 2 and make it fullscreen
-        ws.focus_left();
-        assert_eq!(ws.focused_window(), Some(2));
-        ws.toggle_fullscreen();
+        ws.tick_animation(50);
+        let x = xs(&ws)[1].1;
+        assert!(x > 500 && x < 1000, "x = {}", x);
 
-        let placements = ws.compute_placements(viewport);
-        assert_eq!(placements.len(), 3);
+        assert!(!ws.tick_animation(50));
+        assert!(!ws.is_animating());
+        assert_eq!(xs(&ws), vec![(1, 0), (3, 500)]);
+    }
 
-        // Window 2 should cover the full viewport
-        let fs = placements.iter().find(|p| p.window_id == 2).unwrap();
-        assert_eq!(fs.rect, viewport);
-        assert_eq!(fs.visibility, Visibility::Visible);
+    #[test]
+    fn test_new_window_appears_in_place() {
+        let mut ws = Workspace::with_gaps(0, 0);
+        ws.insert_window(1, Some(500)).unwrap();
+        ws.insert_window(2, Some(500)).unwrap();
+        ws.focus_window(1).unwrap();
 
-        // Others should be off-screen
-        let w1 = placements.iter().find(|p| p.window_id == 1).unwrap();
-        assert_eq!(w1.visibility, Visibility::OffScreenLeft);
+        let before = ws.strip_positions();
+        ws.insert_window(3, Some(500)).unwrap();
+        ws.animate_columns_from(&before, 100);
+        // The new window is at its slot, its right neighbor slides over
+        assert_eq!(xs(&ws), vec![(1, 0), (3, 500), (2, 500)]);
 
-        let w3 = placements.iter().find(|p| p.window_id == 3).unwrap();
-        assert_eq!(w3.visibility, Visibility::OffScreenLeft);
+        // Nothing moved, nothing to animate
+        ws.stop_animation();
+        let before = ws.strip_positions();
+        ws.animate_columns_from(&before, 100);
+        assert!(!ws.is_animating());
     }
 
     #[test]
-    fn test_fullscreen_animated_placements() {
-        let mut ws = Workspace::with_gaps(10, 10);
-        let viewport = Rect::new(0, 0, 1920, 1080);
-
-        ws.insert_window(1, Some(400)).unwrap();
-        ws.toggle_fullscreen();
+    fn test_interrupted_column_animation_starts_from_shown_position() {
+        let mut ws = Workspace::with_gaps(0, 0);
+        for id in 1..=3 {
+            ws.insert_window(id, Some(500)).unwrap();
+        }
+        let before = ws.strip_positions();
+        ws.remove_window(1).unwrap();
+        ws.animate_columns_from(&before, 100);
+        ws.tick_animation(50);
+        let shown = xs(&ws);
 
-        let placements = ws.compute_placements_animated(viewport);
-        assert_eq!(placements.len(), 1);
-        assert_eq!(placements[0].rect, viewport);
-        assert_eq!(placements[0].visibility, Visibility::Visible);
+        // A second change picks up from where the windows are shown
+        let before = ws.strip_positions();
+        ws.insert_window(4, Some(500)).unwrap();
+        ws.animate_columns_from(&before, 100);
+        let now = xs(&ws);
+        assert_eq!(now[0], shown[0]);
+        assert_eq!(now[1], shown[1]);
     }
 
-    // ====================================================================
-    // Toggle Floating Tests
-    // ====================================================================
+    // ========================================================================
+    // Index Addressing Tests
+    // ========================================================================
 
     #[test]
-    fn test_toggle_floating_tiled_to_float() {
+    fn test_focus_column_by_index() {
         let mut ws = Workspace::new();
-        let viewport = Rect::new(0, 0, 1920, 1080);
-
-        ws.insert_window(1, Some(400)).unwrap();
-        ws.insert_window(2, Some(400)).unwrap();
+        for id in 1..=3 {
+            ws.insert_window(id, Some(500)).unwrap();
+        }
+        ws.insert_window_in_column(4, 0).unwrap();
+        ws.set_focus(2, 0).unwrap();
 
-        // Focus window 2 and toggle to floating
-        let wid = ws.toggle_floating(viewport);
-        assert_eq!(wid, Some(2));
-        assert!(ws.is_floating(2));
-        assert_eq!(ws.column_count(), 1); // Only window 1 tiled
-        assert_eq!(ws.floating_count(), 1);
+        ws.focus_column(0).unwrap();
+        assert_eq!(ws.focused_window(), Some(1));
+        assert!(matches!(ws.focus_column(3), Err(LayoutError::ColumnOutOfBounds(3, 2))));
+        assert_eq!(ws.focused_column_index(), 0);
     }
 
     #[test]
-    fn test_toggle_floating_back() {
+    fn test_move_column_to_index_keeps_focus() {
         let mut ws = Workspace::new();
-        let viewport = Rect::new(0, 0, 1920, 1080);
+        for id in 1..=4 {
+            ws.insert_window(id, Some(500)).unwrap();
+        }
+        ws.focus_window(2).unwrap();
 
-        ws.insert_window(1, Some(400)).unwrap();
+        // Moving another column past the focused one shifts focus with it
+        ws.move_column_to_index(0, 3).unwrap();
+        assert_eq!(ws.all_window_ids(), vec![2, 3, 4, 1]);
+        assert_eq!(ws.focused_window(), Some(2));
 
-        // Toggle to floating
-        let wid = ws.toggle_floating(viewport);
-        assert_eq!(wid, Some(1));
-        assert!(ws.is_floating(1));
-        assert_eq!(ws.column_count(), 0);
+        // Moving the focused column takes focus along
+        ws.move_column_to_index(0, 2).unwrap();
+        assert_eq!(ws.all_window_ids(), vec![3, 4, 2, 1]);
+        assert_eq!(ws.focused_column_index(), 2);
+        assert_eq!(ws.find_window_location(1), Some((3, 0)));
 
-        // Use unfloat to bring it back
-        let ok = ws.unfloat_window(1);
-        assert!(ok);
-        assert!(!ws.is_floating(1));
-        assert_eq!(ws.column_count(), 1);
+        assert!(ws.move_column_to_index(0, 4).is_err());
+        assert!(ws.check_invariants(1920).is_ok());
     }
 
     #[test]
-    fn test_toggle_floating_empty_workspace() {
+    fn test_insert_window_at_positions() {
+        let order = |ws: &Workspace| -> Vec<WindowId> { ws.columns().iter().map(|c| c.windows()[0]).collect() };
         let mut ws = Workspace::new();
-        let viewport = Rect::new(0, 0, 1920, 1080);
-        let wid = ws.toggle_floating(viewport);
-        assert_eq!(wid, None);
+        for id in 1..=3 {
+            ws.insert_window(id, Some(500)).unwrap();
+        }
+        ws.focus_column(0).unwrap();
+
+        ws.insert_window_at(4, None, InsertPosition::RightOfFocus, 1920).unwrap();
+        assert_eq!(order(&ws), vec![1, 4, 2, 3]);
+        ws.insert_window_at(5, None, InsertPosition::EndOfStrip, 1920).unwrap();
+        assert_eq!(order(&ws), vec![1, 4, 2, 3, 5]);
+        ws.insert_window_at(6, None, InsertPosition::Start, 1920).unwrap();
+        assert_eq!(order(&ws), vec![6, 1, 4, 2, 3, 5]);
+        assert_eq!(ws.focused_window(), Some(6));
+        assert!(matches!(
+            ws.insert_window_at(6, None, InsertPosition::Start, 1920),
+            Err(LayoutError::DuplicateWindow(6))
+        ));
+
+        ws.set_insert_position(InsertPosition::EndOfStrip);
+        ws.insert_window_at(7, None, ws.insert_position(), 1920).unwrap();
+        assert_eq!(ws.columns().last().unwrap().windows(), &[7]);
+        assert_eq!(ws.empty_like().insert_position(), InsertPosition::EndOfStrip);
     }
 
-    // ====================================================================
-    // Column Width Preset Tests
-    // ====================================================================
-
     #[test]
-    fn test_set_column_width_fraction() {
-        let mut ws = Workspace::with_gaps(10, 10);
-        ws.insert_window(1, Some(400)).unwrap();
+    fn test_insert_window_at_nearest_empty() {
+        let mut ws = Workspace::new();
+        for id in 1..=2 {
+            ws.insert_window(id, Some(500)).unwrap();
+        }
+        ws.focus_column(0).unwrap();
 
-        // Set to half viewport (1920 - 20 outer gaps = 1900 usable)
-        ws.set_focused_column_width_fraction(0.5, 1920);
-        assert_eq!(ws.columns()[0].width(), 950); // 1900 * 0.5
+        // The strip ends inside the viewport: fill the space on the right
+        ws.insert_window_at(3, None, InsertPosition::NearestEmpty, 1920).unwrap();
+        assert_eq!(ws.columns().last().unwrap().windows(), &[3]);
 
-        // Set to one-third
-        ws.set_focused_column_width_fraction(0.333, 1920);
-        assert_eq!(ws.columns()[0].width(), 633); // round(1900 * 0.333)
+        // Scrolled to the start of an overflowing strip: only the outer gap
+        // shows before the first column, so next to the focused column
+        let mut ws = Workspace::with_gaps(10, 10);
+        for id in 1..=3 {
+            ws.insert_window(id, Some(500)).unwrap();
+        }
+        ws.focus_column(0).unwrap();
+        ws.ensure_focused_visible(1000);
+        assert_eq!(ws.scroll_offset(), 0.0);
+        ws.insert_window_at(4, Some(500), InsertPosition::NearestEmpty, 1000).unwrap();
+        assert_eq!(ws.columns()[1].windows(), &[4]);
+
+        // No empty space showing in the middle of the strip either
+        ws.test_set_scroll_offset(300.0);
+        ws.insert_window_at(5, None, InsertPosition::NearestEmpty, 1000).unwrap();
+        assert_eq!(ws.columns()[2].windows(), &[5]);
     }
 
     #[test]
-    fn test_set_column_width_fraction_clamp() {
+    fn test_wrap_navigation_focus() {
         let mut ws = Workspace::new();
-        ws.insert_window(1, Some(400)).unwrap();
-
-        // Fraction is clamped to [0.1, 1.0]
-        // Default outer_gap = 10, so usable = 1920 - 20 = 1900
-        ws.set_focused_column_width_fraction(2.0, 1920);
-        let w = ws.columns()[0].width();
-        // fraction clamped to 1.0: (1920 - 20 outer) * 1.0 = 1900
-        assert_eq!(w, 1900);
-    }
+        for id in 1..=3 {
+            ws.insert_window(id, Some(500)).unwrap();
+        }
+        ws.focus_column(0).unwrap();
 
-    #[test]
-    fn test_equalize_widths() {
-        let mut ws = Workspace::with_gaps(10, 10);
-        ws.insert_window(1, Some(300)).unwrap();
-        ws.insert_window(2, Some(600)).unwrap();
-        ws.insert_window(3, Some(400)).unwrap();
+        // Without wrapping, focus stops at the ends
+        ws.focus_left();
+        assert_eq!(ws.focused_column_index(), 0);
 
-        // 3 columns, viewport 1920
-        // total_gaps = 10*2 + 10*2 = 40
-        // per_column = (1920 - 40) / 3 = 626
-        ws.equalize_column_widths(1920);
+        ws.set_wrap_navigation(true);
+        ws.focus_left();
+        assert_eq!(ws.focused_window(), Some(3));
+        ws.focus_right();
+        assert_eq!(ws.focused_window(), Some(1));
+        assert!(ws.empty_like().wrap_navigation());
 
-        assert_eq!(ws.columns()[0].width(), 626);
-        assert_eq!(ws.columns()[1].width(), 626);
-        assert_eq!(ws.columns()[2].width(), 626);
+        // A single column stays put
+        let mut single = Workspace::new();
+        single.set_wrap_navigation(true);
+        single.insert_window(1, None).unwrap();
+        single.focus_left();
+        single.focus_right();
+        assert_eq!(single.focused_window(), Some(1));
     }
 
     #[test]
-    fn test_equalize_widths_empty() {
+    fn test_wrap_navigation_move_column() {
         let mut ws = Workspace::new();
-        ws.equalize_column_widths(1920); // Should not panic
+        for id in 1..=3 {
+            ws.insert_window(id, Some(500)).unwrap();
+        }
+        ws.set_wrap_navigation(true);
+
+        ws.move_column_right();
+        assert_eq!(ws.all_window_ids(), vec![3, 1, 2]);
+        assert_eq!(ws.focused_column_index(), 0);
+
+        ws.move_column_left();
+        assert_eq!(ws.all_window_ids(), vec![1, 2, 3]);
+        assert_eq!(ws.focused_column_index(), 2);
+        assert_eq!(ws.focused_window(), Some(3));
+        assert!(ws.check_invariants(1920).is_ok());
     }
 
     #[test]
-    fn test_unfloat_nonexistent() {
+    fn test_move_window_to_column() {
         let mut ws = Workspace::new();
-        assert!(!ws.unfloat_window(999));
+        for id in 1..=3 {
+            ws.insert_window(id, Some(500)).unwrap();
+        }
+        ws.insert_window_in_column(4, 2).unwrap();
+
+        // A lone window leaves its column behind; indices refer to the old list
+        ws.move_window_to_column(1, 2, 0).unwrap();
+        assert_eq!(ws.column_count(), 2);
+        assert_eq!(ws.column(1).unwrap().windows(), &[1, 3, 4]);
+        assert_eq!(ws.focused_window(), Some(1));
+
+        // Positions past the bottom append; moving within a column reorders
+        ws.move_window_to_column(2, 1, 99).unwrap();
+        assert_eq!(ws.column(0).unwrap().windows(), &[1, 3, 4, 2]);
+        ws.move_window_to_column(2, 0, 0).unwrap();
+        assert_eq!(ws.column(0).unwrap().windows(), &[2, 1, 3, 4]);
+        assert_eq!(ws.focused_window(), Some(2));
+
+        assert!(matches!(ws.move_window_to_column(9, 0, 0), Err(LayoutError::WindowNotFound(9))));
+        assert!(matches!(ws.move_window_to_column(2, 1, 0), Err(LayoutError::ColumnOutOfBounds(1, 0))));
+        assert!(ws.check_invariants(1920).is_ok());
     }
 }
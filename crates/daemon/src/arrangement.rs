@@ -0,0 +1,206 @@
+//! Column arrangement persistence across daemon restarts.
+//!
+//! Window handles stay valid while a window lives, but after a restart the
+//! re-enumerated windows would otherwise be appended in enumeration order.
+//! Each saved window carries a fingerprint (executable, class and title
+//! prefix) so it can be matched back to its previous column, even when its
+//! handle changed because the app was reopened.
+//!
+//! Matching runs in passes, each only using windows not matched yet:
+//! 1. Same handle and same app
+//! 2. Same app and same title prefix
+//! 3. Same app
+
+use openniri_core_layout::{Column, Workspace};
+use serde::{Deserialize, Serialize};
+
+/// Number of title characters kept in a fingerprint.
+///
+/// Titles often end with changing details (document names, unread counts),
+/// so only the start is compared.
+pub const TITLE_PREFIX_CHARS: usize = 24;
+
+/// Identity of a window that survives handle changes.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WindowFingerprint {
+    /// Executable name, lowercased (e.g. `code.exe`).
+    pub executable: String,
+    /// Window class name.
+    pub class_name: String,
+    /// Start of the window title.
+    pub title_prefix: String,
+}
+
+impl WindowFingerprint {
+    /// Build a fingerprint from a window's properties.
+    pub fn new(executable: &str, class_name: &str, title: &str) -> Self {
+        Self {
+            executable: executable.to_lowercase(),
+            class_name: class_name.to_string(),
+            title_prefix: title.chars().take(TITLE_PREFIX_CHARS).collect(),
+        }
+    }
+
+    /// Whether both fingerprints belong to the same application window type.
+    pub fn same_app(&self, other: &WindowFingerprint) -> bool {
+        self.executable == other.executable && self.class_name == other.class_name
+    }
+}
+
+/// A window in a saved column.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedWindow {
+    /// Window handle when the state was saved.
+    pub window_id: u64,
+    /// Identity used to match the window after a restart.
+    pub fingerprint: WindowFingerprint,
+}
+
+/// A saved column: its windows top to bottom, width and tag.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SavedColumn {
+    /// Column width in pixels.
+    pub width: i32,
+    /// Shared column tag from window rules, if any.
+    #[serde(default)]
+    pub tag: Option<String>,
+    /// Windows in stacking order.
+    pub windows: Vec<SavedWindow>,
+}
+
+/// Record a workspace's columns with window fingerprints.
+///
+/// Windows `fingerprint` returns None for (e.g. already closed) are left out.
+pub fn save_columns(
+    workspace: &Workspace,
+    fingerprint: impl Fn(u64) -> Option<WindowFingerprint>,
+) -> Vec<SavedColumn> {
+    workspace
+        .columns()
+        .iter()
+        .map(|column| SavedColumn {
+            width: column.width(),
+            tag: column.tag().map(str::to_string),
+            windows: column
+                .windows()
+                .iter()
+                .filter_map(|&window_id| {
+                    fingerprint(window_id).map(|fingerprint| SavedWindow { window_id, fingerprint })
+                })
+                .collect(),
+        })
+        .filter(|column| !column.windows.is_empty())
+        .collect()
+}
+
+/// Match current windows to saved columns.
+///
+/// Matched windows are removed from `available`, so several workspaces can
+/// be restored from one pool without a window being claimed twice. Returns
+/// the saved columns filled with current window IDs, ready for
+/// [`Workspace::arrange_columns`]; columns with no match are left out.
+pub fn match_columns(saved: &[SavedColumn], available: &mut Vec<(u64, WindowFingerprint)>) -> Vec<Column> {
+    let slots: Vec<&SavedWindow> = saved.iter().flat_map(|column| &column.windows).collect();
+    let mut matched: Vec<Option<u64>> = vec![None; slots.len()];
+
+    let passes: [fn(&SavedWindow, u64, &WindowFingerprint) -> bool; 3] = [
+        |saved, id, fp| saved.window_id == id && saved.fingerprint.same_app(fp),
+        |saved, _, fp| saved.fingerprint.same_app(fp) && saved.fingerprint.title_prefix == fp.title_prefix,
+        |saved, _, fp| saved.fingerprint.same_app(fp),
+    ];
+    for pass in passes {
+        for (slot, saved_window) in slots.iter().enumerate() {
+            if matched[slot].is_some() {
+                continue;
+            }
+            if let Some(pos) = available.iter().position(|(id, fp)| pass(saved_window, *id, fp)) {
+                matched[slot] = Some(available.remove(pos).0);
+            }
+        }
+    }
+
+    let mut matched = matched.into_iter();
+    saved
+        .iter()
+        .filter_map(|saved_column| {
+            let mut column = Column::empty(saved_column.width);
+            column.set_tag(saved_column.tag.clone());
+            for id in matched.by_ref().take(saved_column.windows.len()).flatten() {
+                column.add_window(id);
+            }
+            (!column.is_empty()).then_some(column)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fp(executable: &str, title: &str) -> WindowFingerprint {
+        WindowFingerprint::new(executable, "Class", title)
+    }
+
+    fn saved(windows: &[(u64, WindowFingerprint)], width: i32) -> SavedColumn {
+        SavedColumn {
+            width,
+            tag: None,
+            windows: windows
+                .iter()
+                .map(|(window_id, fingerprint)| SavedWindow { window_id: *window_id, fingerprint: fingerprint.clone() })
+                .collect(),
+        }
+    }
+
+    fn ids(columns: &[Column]) -> Vec<Vec<u64>> {
+        columns.iter().map(|c| c.windows().to_vec()).collect()
+    }
+
+    #[test]
+    fn test_fingerprint_normalizes() {
+        let fingerprint = WindowFingerprint::new("Code.EXE", "Chrome_WidgetWin_1", &"x".repeat(100));
+        assert_eq!(fingerprint.executable, "code.exe");
+        assert_eq!(fingerprint.title_prefix.chars().count(), TITLE_PREFIX_CHARS);
+    }
+
+    #[test]
+    fn test_save_columns() {
+        let mut workspace = Workspace::new();
+        workspace.insert_window(1, Some(500)).unwrap();
+        workspace.insert_window(2, Some(600)).unwrap();
+        workspace.insert_window(3, Some(700)).unwrap();
+
+        // Window 2 is gone, so its column is dropped
+        let columns = save_columns(&workspace, |id| (id != 2).then(|| fp("app.exe", "title")));
+        assert_eq!(columns.len(), 2);
+        assert_eq!(columns[0].width, 500);
+        assert_eq!(columns[1].windows[0].window_id, 3);
+    }
+
+    #[test]
+    fn test_match_prefers_same_handle() {
+        let saved = vec![saved(&[(10, fp("term.exe", "a"))], 500), saved(&[(11, fp("term.exe", "b"))], 800)];
+        let mut available = vec![(11, fp("term.exe", "b")), (10, fp("term.exe", "a"))];
+        let columns = match_columns(&saved, &mut available);
+        assert_eq!(ids(&columns), vec![vec![10], vec![11]]);
+        assert_eq!(columns[1].width(), 800);
+        assert!(available.is_empty());
+    }
+
+    #[test]
+    fn test_match_by_title_then_app_after_reopen() {
+        let saved = vec![saved(&[(10, fp("code.exe", "main.rs - web")), (11, fp("code.exe", "lib.rs - api"))], 900)];
+        // Reopened windows have new handles; titles pick the right slot
+        let mut available = vec![(21, fp("code.exe", "lib.rs - api")), (20, fp("code.exe", "notes.md - web"))];
+        let columns = match_columns(&saved, &mut available);
+        assert_eq!(ids(&columns), vec![vec![20, 21]]);
+    }
+
+    #[test]
+    fn test_match_leaves_other_apps_available() {
+        let saved = vec![saved(&[(10, fp("slack.exe", "Slack"))], 500)];
+        let mut available = vec![(30, fp("notepad.exe", "Untitled"))];
+        assert!(match_columns(&saved, &mut available).is_empty());
+        assert_eq!(available.len(), 1);
+    }
+}
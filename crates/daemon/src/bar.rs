@@ -0,0 +1,169 @@
+//! Bar text rendering for status bar integrations.
+//!
+//! Renders a user-supplied template into one plain-text line per monitor so
+//! minimal bars (taskbar tooltips, Rainmeter skins, etc.) can show workspace
+//! state without parsing JSON.
+//!
+//! Placeholders are written as `{name}` or `{name:N}`, where `N` truncates the
+//! value to at most `N` characters (grapheme clusters, so emoji and accented
+//! letters stay whole). `{{` and `}}` produce literal braces. Unknown
+//! placeholders are left in the output unchanged.
+
+use openniri_ipc::text::truncate_with_ellipsis;
+
+/// Per-monitor values available to bar templates.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BarContext {
+    /// 1-based monitor position, left to right (`{monitor}`).
+    pub monitor: usize,
+    /// Monitor device name (`{monitor_name}`).
+    pub monitor_name: String,
+    /// 1-based focused column index, 0 when empty (`{col_idx}`).
+    pub col_idx: usize,
+    /// Number of columns (`{col_count}`).
+    pub col_count: usize,
+    /// 1-based focused window index within its column, 0 when empty (`{win_idx}`).
+    pub win_idx: usize,
+    /// Number of windows in the focused column (`{win_count}`).
+    pub win_count: usize,
+    /// Number of managed windows on the monitor, tiled and floating (`{windows}`).
+    pub windows: usize,
+    /// Title of the focused window (`{title}`).
+    pub title: String,
+    /// Whether this is the focused monitor (`{focused}` renders `*` or nothing).
+    pub focused: bool,
+}
+
+impl BarContext {
+    /// Look up a placeholder value by name.
+    fn value(&self, name: &str) -> Option<String> {
+        let value = match name {
+            "monitor" => self.monitor.to_string(),
+            "monitor_name" => self.monitor_name.clone(),
+            "col_idx" => self.col_idx.to_string(),
+            "col_count" => self.col_count.to_string(),
+            "win_idx" => self.win_idx.to_string(),
+            "win_count" => self.win_count.to_string(),
+            "windows" => self.windows.to_string(),
+            "title" => self.title.clone(),
+            "focused" => if self.focused { "*".to_string() } else { String::new() },
+            _ => return None,
+        };
+        Some(value)
+    }
+}
+
+/// Render a bar template for one monitor.
+pub fn render(format: &str, ctx: &BarContext) -> String {
+    let mut out = String::with_capacity(format.len());
+    let mut rest = format;
+
+    while let Some(pos) = rest.find(['{', '}']) {
+        out.push_str(&rest[..pos]);
+        let tail = &rest[pos..];
+
+        if tail.starts_with("{{") || tail.starts_with("}}") {
+            out.push_str(&tail[..1]);
+            rest = &tail[2..];
+            continue;
+        }
+        if let Some(after) = tail.strip_prefix('}') {
+            out.push('}');
+            rest = after;
+            continue;
+        }
+
+        // Placeholder: find the closing brace, or emit the remainder verbatim
+        let Some(end) = tail.find('}') else {
+            out.push_str(tail);
+            return out;
+        };
+        let spec = &tail[1..end];
+        let (name, width) = match spec.split_once(':') {
+            Some((name, width)) => (name.trim(), width.trim().parse::<usize>().ok()),
+            None => (spec.trim(), None),
+        };
+
+        match ctx.value(name) {
+            Some(value) => match width {
+                Some(max) => out.push_str(&truncate_with_ellipsis(&value, max)),
+                None => out.push_str(&value),
+            },
+            None => out.push_str(&tail[..=end]),
+        }
+        rest = &tail[end + 1..];
+    }
+
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx() -> BarContext {
+        BarContext {
+            monitor: 1,
+            monitor_name: "DISPLAY1".to_string(),
+            col_idx: 2,
+            col_count: 5,
+            win_idx: 1,
+            win_count: 2,
+            windows: 7,
+            title: "Visual Studio Code".to_string(),
+            focused: true,
+        }
+    }
+
+    #[test]
+    fn test_render_basic_format() {
+        assert_eq!(render("{monitor} {col_idx}/{col_count} {title:30}", &ctx()), "1 2/5 Visual Studio Code");
+    }
+
+    #[test]
+    fn test_render_all_placeholders() {
+        let line = render(
+            "{focused}{monitor_name} {win_idx}/{win_count} ({windows})",
+            &ctx(),
+        );
+        assert_eq!(line, "*DISPLAY1 1/2 (7)");
+    }
+
+    #[test]
+    fn test_render_truncates_with_ellipsis() {
+        assert_eq!(render("{title:6}", &ctx()), "Visua…");
+        assert_eq!(render("{title:0}", &ctx()), "");
+        assert_eq!(render("{title:100}", &ctx()), "Visual Studio Code");
+    }
+
+    #[test]
+    fn test_render_truncates_by_characters() {
+        let mut c = ctx();
+        c.title = "日本語のタイトル".to_string();
+        assert_eq!(render("{title:4}", &c), "日本語…");
+        c.title = "🇯🇵 Tokyo 👨\u{200D}💻".to_string();
+        assert_eq!(render("{title:2}", &c), "🇯🇵…");
+    }
+
+    #[test]
+    fn test_render_escaped_braces() {
+        assert_eq!(render("{{{monitor}}}", &ctx()), "{1}");
+    }
+
+    #[test]
+    fn test_render_unknown_placeholder_kept() {
+        assert_eq!(render("{nope} {monitor}", &ctx()), "{nope} 1");
+    }
+
+    #[test]
+    fn test_render_unterminated_placeholder() {
+        assert_eq!(render("{monitor} {title", &ctx()), "1 {title");
+    }
+
+    #[test]
+    fn test_render_unfocused_empty_monitor() {
+        let c = BarContext { monitor: 2, ..Default::default() };
+        assert_eq!(render("{focused}{monitor} {col_idx}/{col_count} [{title}]", &c), "2 0/0 []");
+    }
+}
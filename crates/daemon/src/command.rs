@@ -0,0 +1,850 @@
+//! Command grammar for hotkeys, gestures, and other text-configured actions.
+//!
+//! A command string is a name followed by whitespace-separated arguments:
+//!
+//! ```text
+//! focus_left
+//! resize -50
+//! set_width 0.5
+//! scroll_right 200
+//! move_floating -50 0
+//! focus_column 3
+//! ```
+//!
+//! Names are case-insensitive and accept `-` in place of `_`. Arguments are
+//! type- and range-checked, and failures produce a [`CommandParseError`]
+//! describing what was wrong so configuration mistakes are easy to fix.
+
+use openniri_ipc::{IpcCommand, NewWindowPosition, SnapEdge};
+use thiserror::Error;
+
+/// Default step for `resize_grow` / `resize_shrink` in pixels.
+const DEFAULT_RESIZE_STEP: i32 = 50;
+
+/// Default step for `scroll_left` / `scroll_right` in pixels.
+const DEFAULT_SCROLL_STEP: f64 = 100.0;
+
+/// Largest accepted resize or scroll step in pixels.
+const MAX_PIXEL_ARG: i32 = 10_000;
+
+/// Largest accepted column number (1-based).
+const MAX_COLUMN_NUMBER: usize = 1000;
+
+/// Accepted range for column width fractions.
+const MIN_WIDTH_FRACTION: f64 = 0.1;
+const MAX_WIDTH_FRACTION: f64 = 1.0;
+
+/// Errors produced when parsing a command string.
+#[derive(Debug, Clone, PartialEq, Error)]
+pub enum CommandParseError {
+    #[error("Empty command")]
+    Empty,
+
+    #[error("Unknown command '{name}'{}", suggestion_hint(.suggestion))]
+    UnknownCommand {
+        name: String,
+        suggestion: Option<&'static str>,
+    },
+
+    #[error("'{command}' requires an argument: {expected}")]
+    MissingArgument {
+        command: String,
+        expected: &'static str,
+    },
+
+    #[error("'{command}' expects {expected}, got '{value}'")]
+    InvalidArgument {
+        command: String,
+        expected: &'static str,
+        value: String,
+    },
+
+    #[error("'{command}' argument {value} is out of range ({range})")]
+    OutOfRange {
+        command: String,
+        value: String,
+        range: String,
+    },
+
+    #[error("'{command}' takes at most {max} argument(s), got {got}")]
+    TooManyArguments {
+        command: String,
+        max: usize,
+        got: usize,
+    },
+
+    #[error("Command {step} of the sequence: {error}")]
+    InSequence {
+        step: usize,
+        error: Box<CommandParseError>,
+    },
+}
+
+/// Names of all supported commands, in documentation order.
+pub const COMMAND_NAMES: &[&str] = &[
+    "focus_left",
+    "focus_right",
+    "focus_up",
+    "focus_down",
+    "focus_previous",
+    "focus_urgent",
+    "focus_column",
+    "move_column_left",
+    "move_column_right",
+    "move_column_up",
+    "move_column_down",
+    "move_column_to",
+    "consume_window_left",
+    "consume_window_right",
+    "consume_window_up",
+    "consume_window_down",
+    "split_column",
+    "promote_window",
+    "focus_monitor_left",
+    "focus_monitor_right",
+    "focus_monitor_up",
+    "focus_monitor_down",
+    "move_to_monitor_left",
+    "move_to_monitor_right",
+    "move_column_to_monitor_left",
+    "move_column_to_monitor_right",
+    "gather_windows",
+    "resize",
+    "resize_grow",
+    "resize_shrink",
+    "resize_height",
+    "height_grow",
+    "height_shrink",
+    "scroll",
+    "scroll_left",
+    "scroll_right",
+    "set_width",
+    "width_third",
+    "width_half",
+    "width_two_thirds",
+    "cycle_width",
+    "equalize_widths",
+    "toggle_auto_balance",
+    "set_new_window_position",
+    "collapse_column",
+    "expand_column",
+    "toggle_column_tabbed",
+    "toggle_maximize_column",
+    "toggle_pause_monitor",
+    "apply_project",
+    "save_monitor_profile",
+    "apply_monitor_profile",
+    "switch_workspace",
+    "move_to_workspace",
+    "toggle_scratchpad",
+    "move_to_scratchpad",
+    "refresh",
+    "reload",
+    "close_window",
+    "toggle_floating",
+    "move_floating",
+    "resize_floating",
+    "center_floating",
+    "snap_floating",
+    "set_always_on_top",
+    "toggle_always_on_top",
+    "toggle_fullscreen",
+    "toggle_overview",
+    "enter_resize_mode",
+];
+
+/// Parse a command string into an IpcCommand.
+///
+/// # Errors
+///
+/// Returns a [`CommandParseError`] if the command name is unknown or an
+/// argument is missing, malformed, or out of range.
+pub fn parse(input: &str) -> Result<IpcCommand, CommandParseError> {
+    let mut parts = input.split_whitespace();
+    let name = parts
+        .next()
+        .ok_or(CommandParseError::Empty)?
+        .to_lowercase()
+        .replace('-', "_");
+    let args: Vec<&str> = parts.collect();
+    let args = Args { command: &name, args: &args };
+
+    let cmd = match name.as_str() {
+        "focus_left" => args.none(IpcCommand::FocusLeft)?,
+        "focus_right" => args.none(IpcCommand::FocusRight)?,
+        "focus_up" => args.none(IpcCommand::FocusUp)?,
+        "focus_down" => args.none(IpcCommand::FocusDown)?,
+        "focus_previous" => args.none(IpcCommand::FocusPrevious)?,
+        "focus_urgent" => args.none(IpcCommand::FocusUrgent)?,
+        "focus_column" => IpcCommand::FocusColumn { index: args.required_column()? },
+        "move_column_to" => IpcCommand::MoveColumnToIndex { from: None, to: args.required_column()? },
+        "move_column_left" => args.none(IpcCommand::MoveColumnLeft)?,
+        "move_column_right" => args.none(IpcCommand::MoveColumnRight)?,
+        "move_column_up" => args.none(IpcCommand::MoveColumnUp)?,
+        "move_column_down" => args.none(IpcCommand::MoveColumnDown)?,
+        "consume_window_left" => args.none(IpcCommand::ConsumeWindowLeft)?,
+        "consume_window_right" => args.none(IpcCommand::ConsumeWindowRight)?,
+        "consume_window_up" => args.none(IpcCommand::ConsumeWindowUp)?,
+        "consume_window_down" => args.none(IpcCommand::ConsumeWindowDown)?,
+        "split_column" => args.none(IpcCommand::SplitColumn)?,
+        "promote_window" => args.none(IpcCommand::PromoteWindow)?,
+        "focus_monitor_left" => args.none(IpcCommand::FocusMonitorLeft)?,
+        "focus_monitor_right" => args.none(IpcCommand::FocusMonitorRight)?,
+        "focus_monitor_up" => args.none(IpcCommand::FocusMonitorUp)?,
+        "focus_monitor_down" => args.none(IpcCommand::FocusMonitorDown)?,
+        "move_to_monitor_left" => args.none(IpcCommand::MoveWindowToMonitorLeft)?,
+        "move_to_monitor_right" => args.none(IpcCommand::MoveWindowToMonitorRight)?,
+        "move_column_to_monitor_left" => args.none(IpcCommand::MoveColumnToMonitorLeft)?,
+        "move_column_to_monitor_right" => args.none(IpcCommand::MoveColumnToMonitorRight)?,
+        "gather_windows" => args.none(IpcCommand::GatherWindows)?,
+        "resize" => IpcCommand::Resize { delta: args.required_pixels(true)? },
+        "resize_grow" => IpcCommand::Resize {
+            delta: args.optional_step()?.unwrap_or(DEFAULT_RESIZE_STEP),
+        },
+        "resize_shrink" => IpcCommand::Resize {
+            delta: -args.optional_step()?.unwrap_or(DEFAULT_RESIZE_STEP),
+        },
+        "resize_height" => IpcCommand::ResizeWindowVertical { delta: args.required_pixels(true)? },
+        "height_grow" => IpcCommand::ResizeWindowVertical {
+            delta: args.optional_step()?.unwrap_or(DEFAULT_RESIZE_STEP),
+        },
+        "height_shrink" => IpcCommand::ResizeWindowVertical {
+            delta: -args.optional_step()?.unwrap_or(DEFAULT_RESIZE_STEP),
+        },
+        "scroll" => IpcCommand::Scroll { delta: args.required_pixels(true)? as f64 },
+        "scroll_left" => IpcCommand::Scroll {
+            delta: -args.optional_step()?.map_or(DEFAULT_SCROLL_STEP, f64::from),
+        },
+        "scroll_right" => IpcCommand::Scroll {
+            delta: args.optional_step()?.map_or(DEFAULT_SCROLL_STEP, f64::from),
+        },
+        "set_width" => IpcCommand::SetColumnWidth { fraction: args.required_fraction()? },
+        "width_third" => args.none(IpcCommand::SetColumnWidth { fraction: 0.333 })?,
+        "width_half" => args.none(IpcCommand::SetColumnWidth { fraction: 0.5 })?,
+        "width_two_thirds" => args.none(IpcCommand::SetColumnWidth { fraction: 0.667 })?,
+        "cycle_width" => args.none(IpcCommand::CycleColumnWidth)?,
+        "equalize_widths" => args.none(IpcCommand::EqualizeColumnWidths)?,
+        "toggle_auto_balance" => args.none(IpcCommand::ToggleAutoBalance)?,
+        "set_new_window_position" => IpcCommand::SetNewWindowPosition { position: args.required_position()? },
+        "collapse_column" => args.none(IpcCommand::CollapseColumn)?,
+        "expand_column" => args.none(IpcCommand::ExpandColumn)?,
+        "toggle_column_tabbed" => args.none(IpcCommand::ToggleColumnTabbed)?,
+        "toggle_maximize_column" => args.none(IpcCommand::ToggleMaximizeColumn)?,
+        "toggle_pause_monitor" => args.none(IpcCommand::TogglePauseMonitor)?,
+        "apply_project" => IpcCommand::ApplyProject { name: args.required_name()? },
+        "save_monitor_profile" => IpcCommand::SaveMonitorProfile { name: args.required_name()? },
+        "apply_monitor_profile" => IpcCommand::ApplyMonitorProfile { name: args.required_name()? },
+        "switch_workspace" => IpcCommand::SwitchWorkspace { name: args.required_name()? },
+        "move_to_workspace" => IpcCommand::MoveWindowToWorkspace { name: args.required_name()? },
+        "toggle_scratchpad" => IpcCommand::ToggleScratchpad { name: args.required_name()? },
+        "move_to_scratchpad" => IpcCommand::MoveToScratchpad { name: args.required_name()? },
+        "refresh" => args.none(IpcCommand::Refresh)?,
+        "reload" => args.none(IpcCommand::Reload)?,
+        "close_window" => args.none(IpcCommand::CloseWindow)?,
+        "toggle_floating" => args.none(IpcCommand::ToggleFloating)?,
+        "move_floating" => {
+            let (dx, dy) = args.required_offset()?;
+            IpcCommand::MoveFloating { dx, dy }
+        }
+        "resize_floating" => {
+            let (dw, dh) = args.required_offset()?;
+            IpcCommand::ResizeFloating { dw, dh }
+        }
+        "center_floating" => args.none(IpcCommand::CenterFloating)?,
+        "snap_floating" => IpcCommand::SnapFloating { edge: args.required_edge()? },
+        "set_always_on_top" => IpcCommand::SetAlwaysOnTop { enabled: args.required_switch()? },
+        "toggle_always_on_top" => args.none(IpcCommand::ToggleAlwaysOnTop)?,
+        "toggle_fullscreen" => args.none(IpcCommand::ToggleFullscreen)?,
+        "toggle_overview" => args.none(IpcCommand::ToggleOverview)?,
+        "enter_resize_mode" => args.none(IpcCommand::EnterResizeMode)?,
+        _ => {
+            let suggestion = suggest(&name);
+            return Err(CommandParseError::UnknownCommand { name, suggestion });
+        }
+    };
+
+    Ok(cmd)
+}
+
+/// Parse a sequence of command strings, run one after another.
+///
+/// # Errors
+///
+/// Returns [`CommandParseError::Empty`] for an empty sequence, or
+/// [`CommandParseError::InSequence`] naming the 1-based position of the first
+/// command that fails to parse.
+pub fn parse_sequence<S: AsRef<str>>(inputs: &[S]) -> Result<Vec<IpcCommand>, CommandParseError> {
+    if inputs.is_empty() {
+        return Err(CommandParseError::Empty);
+    }
+    inputs
+        .iter()
+        .enumerate()
+        .map(|(i, input)| {
+            parse(input.as_ref())
+                .map_err(|error| CommandParseError::InSequence { step: i + 1, error: Box::new(error) })
+        })
+        .collect()
+}
+
+fn suggestion_hint(suggestion: &Option<&'static str>) -> String {
+    suggestion.map(|s| format!(" (did you mean '{}'?)", s)).unwrap_or_default()
+}
+
+/// Find the closest known command name for a typo, if any is close enough.
+fn suggest(name: &str) -> Option<&'static str> {
+    COMMAND_NAMES
+        .iter()
+        .map(|candidate| (edit_distance(name, candidate), *candidate))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein distance between two strings, by characters.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut curr = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            curr[j + 1] = (prev[j] + cost).min(prev[j + 1] + 1).min(curr[j] + 1);
+        }
+        prev = curr;
+    }
+    prev[b.len()]
+}
+
+/// Argument list for a single command, with typed accessors.
+struct Args<'a> {
+    command: &'a str,
+    args: &'a [&'a str],
+}
+
+impl Args<'_> {
+    fn check_max(&self, max: usize) -> Result<(), CommandParseError> {
+        if self.args.len() > max {
+            return Err(CommandParseError::TooManyArguments {
+                command: self.command.to_string(),
+                max,
+                got: self.args.len(),
+            });
+        }
+        Ok(())
+    }
+
+    /// Accept a command that takes no arguments.
+    fn none(&self, cmd: IpcCommand) -> Result<IpcCommand, CommandParseError> {
+        self.check_max(0)?;
+        Ok(cmd)
+    }
+
+    fn parse_pixels(&self, value: &str, allow_negative: bool) -> Result<i32, CommandParseError> {
+        let expected = if allow_negative { "a pixel amount" } else { "a positive pixel amount" };
+        let parsed: i32 = value.parse().map_err(|_| CommandParseError::InvalidArgument {
+            command: self.command.to_string(),
+            expected,
+            value: value.to_string(),
+        })?;
+
+        let min = if allow_negative { -MAX_PIXEL_ARG } else { 1 };
+        if parsed < min || parsed > MAX_PIXEL_ARG || parsed == 0 {
+            let range = if allow_negative {
+                format!("non-zero, {}..={}", -MAX_PIXEL_ARG, MAX_PIXEL_ARG)
+            } else {
+                format!("1..={}", MAX_PIXEL_ARG)
+            };
+            return Err(CommandParseError::OutOfRange {
+                command: self.command.to_string(),
+                value: value.to_string(),
+                range,
+            });
+        }
+        Ok(parsed)
+    }
+
+    /// A single required pixel amount.
+    fn required_pixels(&self, allow_negative: bool) -> Result<i32, CommandParseError> {
+        self.check_max(1)?;
+        let value = self.args.first().ok_or_else(|| CommandParseError::MissingArgument {
+            command: self.command.to_string(),
+            expected: "a pixel amount",
+        })?;
+        self.parse_pixels(value, allow_negative)
+    }
+
+    /// An optional positive step in pixels.
+    fn optional_step(&self) -> Result<Option<i32>, CommandParseError> {
+        self.check_max(1)?;
+        self.args.first().map(|v| self.parse_pixels(v, false)).transpose()
+    }
+
+    /// A required horizontal and vertical pixel amount, either of which may be zero.
+    fn required_offset(&self) -> Result<(i32, i32), CommandParseError> {
+        self.check_max(2)?;
+        let [x, y] = self.args else {
+            return Err(CommandParseError::MissingArgument {
+                command: self.command.to_string(),
+                expected: "two pixel amounts (x y)",
+            });
+        };
+        let parse = |value: &str| {
+            let parsed: i32 = value.parse().map_err(|_| CommandParseError::InvalidArgument {
+                command: self.command.to_string(),
+                expected: "a pixel amount",
+                value: value.to_string(),
+            })?;
+            if !(-MAX_PIXEL_ARG..=MAX_PIXEL_ARG).contains(&parsed) {
+                return Err(CommandParseError::OutOfRange {
+                    command: self.command.to_string(),
+                    value: value.to_string(),
+                    range: format!("{}..={}", -MAX_PIXEL_ARG, MAX_PIXEL_ARG),
+                });
+            }
+            Ok(parsed)
+        };
+        Ok((parse(x)?, parse(y)?))
+    }
+
+    /// A single required work area edge.
+    fn required_edge(&self) -> Result<SnapEdge, CommandParseError> {
+        self.check_max(1)?;
+        let expected = "an edge (left, right, top or bottom)";
+        let value = self.args.first().ok_or_else(|| CommandParseError::MissingArgument {
+            command: self.command.to_string(),
+            expected,
+        })?;
+        match value.to_lowercase().as_str() {
+            "left" => Ok(SnapEdge::Left),
+            "right" => Ok(SnapEdge::Right),
+            "top" => Ok(SnapEdge::Top),
+            "bottom" => Ok(SnapEdge::Bottom),
+            _ => Err(CommandParseError::InvalidArgument {
+                command: self.command.to_string(),
+                expected,
+                value: value.to_string(),
+            }),
+        }
+    }
+
+    /// A single required new window position.
+    fn required_position(&self) -> Result<NewWindowPosition, CommandParseError> {
+        self.check_max(1)?;
+        let expected = "a position (right_of_focus, end_of_strip, start or nearest_empty)";
+        let value = self.args.first().ok_or_else(|| CommandParseError::MissingArgument {
+            command: self.command.to_string(),
+            expected,
+        })?;
+        match value.to_lowercase().replace('-', "_").as_str() {
+            "right_of_focus" => Ok(NewWindowPosition::RightOfFocus),
+            "end_of_strip" => Ok(NewWindowPosition::EndOfStrip),
+            "start" => Ok(NewWindowPosition::Start),
+            "nearest_empty" => Ok(NewWindowPosition::NearestEmpty),
+            _ => Err(CommandParseError::InvalidArgument {
+                command: self.command.to_string(),
+                expected,
+                value: value.to_string(),
+            }),
+        }
+    }
+
+    /// A single required on/off switch.
+    fn required_switch(&self) -> Result<bool, CommandParseError> {
+        self.check_max(1)?;
+        let expected = "on or off";
+        let value = self.args.first().ok_or_else(|| CommandParseError::MissingArgument {
+            command: self.command.to_string(),
+            expected,
+        })?;
+        match value.to_lowercase().as_str() {
+            "on" | "true" => Ok(true),
+            "off" | "false" => Ok(false),
+            _ => Err(CommandParseError::InvalidArgument {
+                command: self.command.to_string(),
+                expected,
+                value: value.to_string(),
+            }),
+        }
+    }
+
+    /// A single required 1-based column number, returned as a 0-based index.
+    fn required_column(&self) -> Result<usize, CommandParseError> {
+        self.check_max(1)?;
+        let expected = "a column number";
+        let value = self.args.first().ok_or_else(|| CommandParseError::MissingArgument {
+            command: self.command.to_string(),
+            expected,
+        })?;
+        let number: usize = value.parse().map_err(|_| CommandParseError::InvalidArgument {
+            command: self.command.to_string(),
+            expected,
+            value: value.to_string(),
+        })?;
+        if !(1..=MAX_COLUMN_NUMBER).contains(&number) {
+            return Err(CommandParseError::OutOfRange {
+                command: self.command.to_string(),
+                value: value.to_string(),
+                range: format!("1..={}", MAX_COLUMN_NUMBER),
+            });
+        }
+        Ok(number - 1)
+    }
+
+    /// A single required name, e.g. a project name.
+    fn required_name(&self) -> Result<String, CommandParseError> {
+        self.check_max(1)?;
+        self.args
+            .first()
+            .map(|name| name.to_string())
+            .ok_or_else(|| CommandParseError::MissingArgument {
+                command: self.command.to_string(),
+                expected: "a name",
+            })
+    }
+
+    /// A single required width fraction, e.g. `0.5` or `50%`.
+    fn required_fraction(&self) -> Result<f64, CommandParseError> {
+        self.check_max(1)?;
+        let value = self.args.first().ok_or_else(|| CommandParseError::MissingArgument {
+            command: self.command.to_string(),
+            expected: "a width fraction",
+        })?;
+
+        let parsed = match value.strip_suffix('%') {
+            Some(percent) => percent.parse::<f64>().map(|p| p / 100.0),
+            None => value.parse::<f64>(),
+        };
+        let fraction = parsed.map_err(|_| CommandParseError::InvalidArgument {
+            command: self.command.to_string(),
+            expected: "a width fraction",
+            value: value.to_string(),
+        })?;
+
+        if !(MIN_WIDTH_FRACTION..=MAX_WIDTH_FRACTION).contains(&fraction) {
+            return Err(CommandParseError::OutOfRange {
+                command: self.command.to_string(),
+                value: value.to_string(),
+                range: format!("{}..={}", MIN_WIDTH_FRACTION, MAX_WIDTH_FRACTION),
+            });
+        }
+        Ok(fraction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Example argument for commands that require one.
+    fn sample_args(name: &str) -> &'static str {
+        match name {
+            "resize" | "resize_height" | "scroll" => " -25",
+            "move_floating" | "resize_floating" => " -25 0",
+            "snap_floating" => " left",
+            "set_new_window_position" => " start",
+            "set_always_on_top" => " on",
+            "set_width" => " 0.5",
+            "focus_column" | "move_column_to" => " 2",
+            "apply_project" => " web",
+            "save_monitor_profile" | "apply_monitor_profile" => " docked",
+            "switch_workspace" | "move_to_workspace" => " code",
+            "toggle_scratchpad" | "move_to_scratchpad" => " term",
+            _ => "",
+        }
+    }
+
+    #[test]
+    fn test_every_command_name_parses() {
+        for name in COMMAND_NAMES {
+            let input = format!("{}{}", name, sample_args(name));
+            assert!(parse(&input).is_ok(), "Command '{}' failed to parse", input);
+        }
+    }
+
+    #[test]
+    fn test_every_command_name_rejects_extra_arguments() {
+        for name in COMMAND_NAMES {
+            let input = format!("{}{} 1 2", name, sample_args(name));
+            assert!(
+                matches!(parse(&input), Err(CommandParseError::TooManyArguments { .. })),
+                "Command '{}' accepted extra arguments",
+                input
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_no_argument_commands() {
+        let cases = [
+            ("focus_left", IpcCommand::FocusLeft),
+            ("focus_right", IpcCommand::FocusRight),
+            ("focus_up", IpcCommand::FocusUp),
+            ("focus_down", IpcCommand::FocusDown),
+            ("focus_previous", IpcCommand::FocusPrevious),
+            ("focus_urgent", IpcCommand::FocusUrgent),
+            ("move_column_left", IpcCommand::MoveColumnLeft),
+            ("move_column_right", IpcCommand::MoveColumnRight),
+            ("consume_window_left", IpcCommand::ConsumeWindowLeft),
+            ("consume_window_right", IpcCommand::ConsumeWindowRight),
+            ("move_column_up", IpcCommand::MoveColumnUp),
+            ("consume_window_down", IpcCommand::ConsumeWindowDown),
+            ("split_column", IpcCommand::SplitColumn),
+            ("promote_window", IpcCommand::PromoteWindow),
+            ("focus_monitor_left", IpcCommand::FocusMonitorLeft),
+            ("focus_monitor_right", IpcCommand::FocusMonitorRight),
+            ("focus_monitor_up", IpcCommand::FocusMonitorUp),
+            ("focus_monitor_down", IpcCommand::FocusMonitorDown),
+            ("move_to_monitor_left", IpcCommand::MoveWindowToMonitorLeft),
+            ("move_to_monitor_right", IpcCommand::MoveWindowToMonitorRight),
+            ("move_column_to_monitor_left", IpcCommand::MoveColumnToMonitorLeft),
+            ("move_column_to_monitor_right", IpcCommand::MoveColumnToMonitorRight),
+            ("gather_windows", IpcCommand::GatherWindows),
+            ("width_third", IpcCommand::SetColumnWidth { fraction: 0.333 }),
+            ("width_half", IpcCommand::SetColumnWidth { fraction: 0.5 }),
+            ("width_two_thirds", IpcCommand::SetColumnWidth { fraction: 0.667 }),
+            ("cycle_width", IpcCommand::CycleColumnWidth),
+            ("equalize_widths", IpcCommand::EqualizeColumnWidths),
+            ("toggle_auto_balance", IpcCommand::ToggleAutoBalance),
+            ("collapse_column", IpcCommand::CollapseColumn),
+            ("expand_column", IpcCommand::ExpandColumn),
+            ("toggle_column_tabbed", IpcCommand::ToggleColumnTabbed),
+            ("toggle_maximize_column", IpcCommand::ToggleMaximizeColumn),
+            ("toggle_pause_monitor", IpcCommand::TogglePauseMonitor),
+            ("refresh", IpcCommand::Refresh),
+            ("reload", IpcCommand::Reload),
+            ("close_window", IpcCommand::CloseWindow),
+            ("toggle_floating", IpcCommand::ToggleFloating),
+            ("center_floating", IpcCommand::CenterFloating),
+            ("toggle_always_on_top", IpcCommand::ToggleAlwaysOnTop),
+            ("toggle_fullscreen", IpcCommand::ToggleFullscreen),
+            ("toggle_overview", IpcCommand::ToggleOverview),
+            ("enter_resize_mode", IpcCommand::EnterResizeMode),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(parse(input), Ok(expected), "Mismatch for '{}'", input);
+        }
+    }
+
+    #[test]
+    fn test_parse_set_new_window_position() {
+        assert_eq!(
+            parse("set_new_window_position end-of-strip"),
+            Ok(IpcCommand::SetNewWindowPosition { position: NewWindowPosition::EndOfStrip })
+        );
+        assert_eq!(
+            parse("set-new-window-position Nearest_Empty"),
+            Ok(IpcCommand::SetNewWindowPosition { position: NewWindowPosition::NearestEmpty })
+        );
+        assert!(matches!(parse("set_new_window_position"), Err(CommandParseError::MissingArgument { .. })));
+        assert!(matches!(parse("set_new_window_position left"), Err(CommandParseError::InvalidArgument { .. })));
+    }
+
+    #[test]
+    fn test_parse_floating_commands() {
+        assert_eq!(parse("move_floating -50 0"), Ok(IpcCommand::MoveFloating { dx: -50, dy: 0 }));
+        assert_eq!(parse("resize_floating 100 -40"), Ok(IpcCommand::ResizeFloating { dw: 100, dh: -40 }));
+        assert_eq!(parse("snap_floating Top"), Ok(IpcCommand::SnapFloating { edge: SnapEdge::Top }));
+        assert!(matches!(parse("move_floating 50"), Err(CommandParseError::MissingArgument { .. })));
+        assert!(matches!(parse("move_floating 50 x"), Err(CommandParseError::InvalidArgument { .. })));
+        assert!(matches!(parse("move_floating 0 20000"), Err(CommandParseError::OutOfRange { .. })));
+        assert!(matches!(parse("snap_floating middle"), Err(CommandParseError::InvalidArgument { .. })));
+        assert_eq!(parse("set_always_on_top OFF"), Ok(IpcCommand::SetAlwaysOnTop { enabled: false }));
+        assert!(matches!(parse("set_always_on_top"), Err(CommandParseError::MissingArgument { .. })));
+        assert!(matches!(parse("set_always_on_top maybe"), Err(CommandParseError::InvalidArgument { .. })));
+    }
+
+    #[test]
+    fn test_parse_column_commands() {
+        // Column numbers are 1-based, like the bar's {col_idx}
+        assert_eq!(parse("focus_column 1"), Ok(IpcCommand::FocusColumn { index: 0 }));
+        assert_eq!(parse("move_column_to 3"), Ok(IpcCommand::MoveColumnToIndex { from: None, to: 2 }));
+        assert!(matches!(parse("focus_column"), Err(CommandParseError::MissingArgument { .. })));
+        assert!(matches!(parse("focus_column first"), Err(CommandParseError::InvalidArgument { .. })));
+        assert!(matches!(parse("focus_column 0"), Err(CommandParseError::OutOfRange { .. })));
+    }
+
+    #[test]
+    fn test_parse_case_and_separator_insensitive() {
+        assert_eq!(parse("FOCUS_RIGHT"), Ok(IpcCommand::FocusRight));
+        assert_eq!(parse("focus-right"), Ok(IpcCommand::FocusRight));
+        assert_eq!(parse("  Set-Width   0.5  "), Ok(IpcCommand::SetColumnWidth { fraction: 0.5 }));
+    }
+
+    #[test]
+    fn test_parse_resize() {
+        assert_eq!(parse("resize -50"), Ok(IpcCommand::Resize { delta: -50 }));
+        assert_eq!(parse("resize 120"), Ok(IpcCommand::Resize { delta: 120 }));
+        assert_eq!(parse("resize_grow"), Ok(IpcCommand::Resize { delta: 50 }));
+        assert_eq!(parse("resize_shrink"), Ok(IpcCommand::Resize { delta: -50 }));
+        assert_eq!(parse("resize_grow 80"), Ok(IpcCommand::Resize { delta: 80 }));
+        assert_eq!(parse("resize_shrink 80"), Ok(IpcCommand::Resize { delta: -80 }));
+        assert_eq!(parse("resize_height -120"), Ok(IpcCommand::ResizeWindowVertical { delta: -120 }));
+        assert_eq!(parse("height_grow"), Ok(IpcCommand::ResizeWindowVertical { delta: 50 }));
+        assert_eq!(parse("height_shrink 80"), Ok(IpcCommand::ResizeWindowVertical { delta: -80 }));
+    }
+
+    #[test]
+    fn test_parse_scroll() {
+        assert_eq!(parse("scroll 250"), Ok(IpcCommand::Scroll { delta: 250.0 }));
+        assert_eq!(parse("scroll -250"), Ok(IpcCommand::Scroll { delta: -250.0 }));
+        assert_eq!(parse("scroll_left"), Ok(IpcCommand::Scroll { delta: -100.0 }));
+        assert_eq!(parse("scroll_right"), Ok(IpcCommand::Scroll { delta: 100.0 }));
+        assert_eq!(parse("scroll_left 300"), Ok(IpcCommand::Scroll { delta: -300.0 }));
+    }
+
+    #[test]
+    fn test_parse_set_width() {
+        assert_eq!(parse("set_width 0.5"), Ok(IpcCommand::SetColumnWidth { fraction: 0.5 }));
+        assert_eq!(parse("set_width 75%"), Ok(IpcCommand::SetColumnWidth { fraction: 0.75 }));
+        assert_eq!(parse("set_width 1"), Ok(IpcCommand::SetColumnWidth { fraction: 1.0 }));
+    }
+
+    #[test]
+    fn test_parse_monitor_profile() {
+        assert_eq!(
+            parse("apply_monitor_profile laptop-only"),
+            Ok(IpcCommand::ApplyMonitorProfile { name: "laptop-only".to_string() })
+        );
+        assert!(matches!(
+            parse("save_monitor_profile"),
+            Err(CommandParseError::MissingArgument { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_apply_project() {
+        assert_eq!(
+            parse("apply_project web"),
+            Ok(IpcCommand::ApplyProject { name: "web".to_string() })
+        );
+        assert!(matches!(
+            parse("apply-project"),
+            Err(CommandParseError::MissingArgument { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_workspace_commands() {
+        assert_eq!(
+            parse("switch_workspace code"),
+            Ok(IpcCommand::SwitchWorkspace { name: "code".to_string() })
+        );
+        assert_eq!(
+            parse("move_to_workspace mail"),
+            Ok(IpcCommand::MoveWindowToWorkspace { name: "mail".to_string() })
+        );
+        assert!(matches!(
+            parse("switch_workspace"),
+            Err(CommandParseError::MissingArgument { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_scratchpad_commands() {
+        assert_eq!(
+            parse("toggle_scratchpad term"),
+            Ok(IpcCommand::ToggleScratchpad { name: "term".to_string() })
+        );
+        assert_eq!(
+            parse("move_to_scratchpad music"),
+            Ok(IpcCommand::MoveToScratchpad { name: "music".to_string() })
+        );
+        assert!(matches!(
+            parse("toggle_scratchpad"),
+            Err(CommandParseError::MissingArgument { .. })
+        ));
+    }
+
+    #[test]
+    fn test_parse_empty() {
+        assert_eq!(parse(""), Err(CommandParseError::Empty));
+        assert_eq!(parse("   "), Err(CommandParseError::Empty));
+    }
+
+    #[test]
+    fn test_parse_unknown_command() {
+        assert_eq!(
+            parse("explode"),
+            Err(CommandParseError::UnknownCommand { name: "explode".to_string(), suggestion: None })
+        );
+    }
+
+    #[test]
+    fn test_parse_unknown_command_suggests_close_match() {
+        assert_eq!(
+            parse("focus_lfet"),
+            Err(CommandParseError::UnknownCommand {
+                name: "focus_lfet".to_string(),
+                suggestion: Some("focus_left"),
+            })
+        );
+        assert_eq!(
+            parse("focus_lfet").unwrap_err().to_string(),
+            "Unknown command 'focus_lfet' (did you mean 'focus_left'?)"
+        );
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("abc", "abc"), 0);
+        assert_eq!(edit_distance("abc", "abd"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "scroll"), 6);
+    }
+
+    #[test]
+    fn test_parse_sequence() {
+        assert_eq!(
+            parse_sequence(&["move-column-left", "focus_column 1"]),
+            Ok(vec![IpcCommand::MoveColumnLeft, IpcCommand::FocusColumn { index: 0 }])
+        );
+        assert_eq!(parse_sequence::<&str>(&[]), Err(CommandParseError::Empty));
+
+        let err = parse_sequence(&["focus_left", "resize abc"]).unwrap_err();
+        assert!(matches!(err, CommandParseError::InSequence { step: 2, .. }));
+        assert_eq!(err.to_string(), "Command 2 of the sequence: 'resize' expects a pixel amount, got 'abc'");
+    }
+
+    #[test]
+    fn test_parse_missing_argument() {
+        assert!(matches!(parse("resize"), Err(CommandParseError::MissingArgument { .. })));
+        assert!(matches!(parse("scroll"), Err(CommandParseError::MissingArgument { .. })));
+        assert!(matches!(parse("set_width"), Err(CommandParseError::MissingArgument { .. })));
+    }
+
+    #[test]
+    fn test_parse_invalid_argument_type() {
+        assert!(matches!(parse("resize abc"), Err(CommandParseError::InvalidArgument { .. })));
+        assert!(matches!(parse("resize 1.5"), Err(CommandParseError::InvalidArgument { .. })));
+        assert!(matches!(parse("set_width half"), Err(CommandParseError::InvalidArgument { .. })));
+    }
+
+    #[test]
+    fn test_parse_out_of_range() {
+        assert!(matches!(parse("resize 0"), Err(CommandParseError::OutOfRange { .. })));
+        assert!(matches!(parse("resize 20000"), Err(CommandParseError::OutOfRange { .. })));
+        assert!(matches!(parse("resize_grow -10"), Err(CommandParseError::OutOfRange { .. })));
+        assert!(matches!(parse("set_width 0.05"), Err(CommandParseError::OutOfRange { .. })));
+        assert!(matches!(parse("set_width 1.5"), Err(CommandParseError::OutOfRange { .. })));
+        assert!(matches!(parse("set_width NaN"), Err(CommandParseError::OutOfRange { .. })));
+    }
+
+    #[test]
+    fn test_error_messages_are_descriptive() {
+        assert_eq!(parse("explode").unwrap_err().to_string(), "Unknown command 'explode'");
+        assert_eq!(
+            parse("resize abc").unwrap_err().to_string(),
+            "'resize' expects a pixel amount, got 'abc'"
+        );
+        assert_eq!(
+            parse("set_width 2").unwrap_err().to_string(),
+            "'set_width' argument 2 is out of range (0.1..=1)"
+        );
+        assert_eq!(
+            parse("focus_left now").unwrap_err().to_string(),
+            "'focus_left' takes at most 0 argument(s), got 1"
+        );
+    }
+}
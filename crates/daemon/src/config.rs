@@ -4,10 +4,12 @@
 //! 1. `%APPDATA%/openniri/config.toml` (Windows standard)
 //! 2. `~/.config/openniri/config.toml` (Unix-style, for WSL compatibility)
 //! 3. `./config.toml` (current directory, for development)
+//!
+//! Setting [`openniri_ipc::CONFIG_ENV_VAR`] to a file path loads only that file.
 
 use anyhow::{Context, Result};
-use directories::ProjectDirs;
-use openniri_core_layout::CenteringMode;
+use openniri_core_layout::{CenteringMode, LayoutMode, Orientation, Rect, DEFAULT_COLUMN_ANIMATION_MS, MAX_SHRINK_TO_FIT_THRESHOLD};
+use openniri_ipc::NewWindowPosition;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
@@ -25,6 +27,14 @@ pub struct Config {
     pub behavior: BehaviorConfig,
     /// Hotkey bindings.
     pub hotkeys: HotkeyConfig,
+    /// Which windows are managed when no window rule matches.
+    #[serde(default)]
+    pub management_mode: ManagementMode,
+    /// Executable name patterns (`*` and `?` wildcards, case-insensitive)
+    /// whose windows are never managed, e.g. games and launchers. Checked
+    /// when windows are enumerated, before window rules.
+    #[serde(default)]
+    pub ignore_processes: Vec<String>,
     /// Window rules for per-window behavior.
     #[serde(default)]
     pub window_rules: Vec<WindowRule>,
@@ -34,8 +44,30 @@ pub struct Config {
     /// Snap hint configuration.
     #[serde(default)]
     pub snap_hints: SnapHintConfig,
+    /// Per-monitor settings, keyed by monitor device name (e.g. "DISPLAY2").
+    #[serde(default)]
+    pub monitors: HashMap<String, MonitorConfig>,
+    /// Layout overrides for monitors matched by device name or resolution
+    /// (`[[monitor]]` tables).
+    #[serde(default, rename = "monitor")]
+    pub monitor_overrides: Vec<MonitorOverride>,
+    /// External commands run when daemon events occur.
+    #[serde(default)]
+    pub hooks: Vec<HookConfig>,
+    /// Per-project layout profiles.
+    #[serde(default)]
+    pub projects: Vec<ProjectProfile>,
+    /// Access control for the IPC pipe.
+    #[serde(default)]
+    pub ipc: IpcConfig,
 }
 
+/// Thickest allowed `appearance.active_border_width`.
+const MAX_ACTIVE_BORDER_WIDTH: u32 = 32;
+
+/// Longest allowed `appearance.window_animation_ms`.
+const MAX_WINDOW_ANIMATION_MS: u64 = 1000;
+
 /// Layout-related configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -63,6 +95,34 @@ pub struct LayoutConfig {
     /// Centering mode for focus navigation.
     #[serde(default)]
     pub centering_mode: CenteringModeConfig,
+
+    /// Width of a collapsed column placeholder in pixels.
+    #[serde(default = "default_collapsed_column_width")]
+    pub collapsed_column_width: i32,
+
+    /// Shrink columns to fit when the strip overflows the viewport by at most
+    /// this fraction of its width (0.0 disables, max 0.5).
+    #[serde(default)]
+    pub shrink_to_fit_threshold: f64,
+
+    /// Stretch columns proportionally to fill the viewport when the strip is
+    /// narrower, up to `max_column_width`.
+    #[serde(default)]
+    pub auto_balance: bool,
+
+    /// Wrap focus and column moves around the ends of the strip (focusing
+    /// left from the first column goes to the last).
+    #[serde(default)]
+    pub wrap_navigation: bool,
+
+    /// Where new windows open: `right_of_focus`, `end_of_strip`, `start`
+    /// or `nearest_empty` (into empty space on screen, if any).
+    #[serde(default)]
+    pub new_window_position: NewWindowPosition,
+
+    /// Column widths, as fractions of the viewport, stepped through by `cycle_width`.
+    #[serde(default = "default_preset_widths")]
+    pub preset_widths: Vec<f64>,
 }
 
 impl Default for LayoutConfig {
@@ -74,6 +134,12 @@ impl Default for LayoutConfig {
             min_column_width: default_min_column_width(),
             max_column_width: default_max_column_width(),
             centering_mode: CenteringModeConfig::default(),
+            collapsed_column_width: default_collapsed_column_width(),
+            shrink_to_fit_threshold: 0.0,
+            auto_balance: false,
+            wrap_navigation: false,
+            new_window_position: NewWindowPosition::default(),
+            preset_widths: default_preset_widths(),
         }
     }
 }
@@ -98,6 +164,316 @@ impl From<CenteringModeConfig> for CenteringMode {
     }
 }
 
+/// Layout mode configuration (wrapper for serialization).
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LayoutModeConfig {
+    /// Scrollable strip of columns.
+    #[default]
+    Scrolling,
+    /// One full-width column that all windows stack into.
+    StackOnly,
+}
+
+impl From<LayoutModeConfig> for LayoutMode {
+    fn from(config: LayoutModeConfig) -> Self {
+        match config {
+            LayoutModeConfig::Scrolling => LayoutMode::Scrolling,
+            LayoutModeConfig::StackOnly => LayoutMode::StackOnly,
+        }
+    }
+}
+
+/// Strip orientation configuration (wrapper for serialization).
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum OrientationConfig {
+    /// Columns side by side, scrolling left and right.
+    #[default]
+    Horizontal,
+    /// Rows on top of each other, scrolling up and down.
+    Vertical,
+}
+
+impl From<OrientationConfig> for Orientation {
+    fn from(config: OrientationConfig) -> Self {
+        match config {
+            OrientationConfig::Horizontal => Orientation::Horizontal,
+            OrientationConfig::Vertical => Orientation::Vertical,
+        }
+    }
+}
+
+/// Settings that apply to a single monitor.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct MonitorConfig {
+    /// Layout mode for this monitor's workspace.
+    pub layout_mode: LayoutModeConfig,
+    /// Direction of this monitor's strip, e.g. vertical on portrait monitors.
+    pub orientation: OrientationConfig,
+    /// Conditions that automatically pause tiling on this monitor.
+    /// The monitor is paused while any of them holds.
+    pub pause_when: Vec<PauseCondition>,
+    /// Space kept free for docks and status bars other than the taskbar.
+    pub reserved_space: ReservedSpace,
+}
+
+/// Layout settings overridden on matching monitors.
+///
+/// Every matching entry applies, in file order, on top of `[layout]`:
+///
+/// ```toml
+/// [[monitor]]
+/// match_resolution = "5120x1440"
+/// gap = 20
+/// default_column_width = 1600
+///
+/// [[monitor]]
+/// match_name = "DISPLAY2"
+/// default_column_width = 1000
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+#[serde(default)]
+pub struct MonitorOverride {
+    /// Monitor device name, e.g. "DISPLAY2" (case-insensitive, `\\.\` prefix optional).
+    pub match_name: Option<String>,
+    /// Monitor resolution as "WIDTHxHEIGHT", e.g. "3440x1440".
+    pub match_resolution: Option<String>,
+    // Overrides of the same-named `[layout]` settings
+    pub gap: Option<i32>,
+    pub outer_gap: Option<i32>,
+    pub default_column_width: Option<i32>,
+    pub centering_mode: Option<CenteringModeConfig>,
+    pub collapsed_column_width: Option<i32>,
+    pub shrink_to_fit_threshold: Option<f64>,
+}
+
+impl MonitorOverride {
+    /// Whether this entry applies to a monitor. All criteria that are set
+    /// must match; an entry without criteria matches nothing.
+    pub fn matches(&self, device_name: &str, width: i32, height: i32) -> bool {
+        if self.match_name.is_none() && self.match_resolution.is_none() {
+            return false;
+        }
+        let name_matches = self
+            .match_name
+            .as_deref()
+            .is_none_or(|name| normalize_device_name(name) == normalize_device_name(device_name));
+        let resolution_matches = self
+            .match_resolution
+            .as_deref()
+            .is_none_or(|resolution| parse_resolution(resolution) == Some((width, height)));
+        name_matches && resolution_matches
+    }
+
+    /// Apply the overridden settings to `layout`.
+    fn apply(&self, layout: &mut LayoutConfig) {
+        if let Some(gap) = self.gap {
+            layout.gap = gap;
+        }
+        if let Some(outer_gap) = self.outer_gap {
+            layout.outer_gap = outer_gap;
+        }
+        if let Some(width) = self.default_column_width {
+            layout.default_column_width = width;
+        }
+        if let Some(mode) = self.centering_mode {
+            layout.centering_mode = mode;
+        }
+        if let Some(width) = self.collapsed_column_width {
+            layout.collapsed_column_width = width;
+        }
+        if let Some(threshold) = self.shrink_to_fit_threshold {
+            layout.shrink_to_fit_threshold = threshold;
+        }
+    }
+}
+
+/// Parse a "WIDTHxHEIGHT" resolution, e.g. "3440x1440".
+fn parse_resolution(resolution: &str) -> Option<(i32, i32)> {
+    let (width, height) = resolution.trim().to_ascii_lowercase().split_once('x').map(|(w, h)| {
+        (w.trim().parse::<i32>(), h.trim().parse::<i32>())
+    })?;
+    match (width, height) {
+        (Ok(width), Ok(height)) if width > 0 && height > 0 => Some((width, height)),
+        _ => None,
+    }
+}
+
+/// Device name in a comparable form: upper case, without the `\\.\` prefix.
+pub fn normalize_device_name(name: &str) -> String {
+    name.trim_start_matches(r"\\.\").to_ascii_uppercase()
+}
+
+/// Smallest viewport side reserved space may leave; reservations that
+/// would leave less are ignored on that axis.
+const MIN_RESERVED_VIEWPORT: i32 = 200;
+
+/// Pixels kept free at each edge of a monitor's work area, e.g.
+/// `reserved_space = { top = 32 }` for a status bar.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct ReservedSpace {
+    pub top: i32,
+    pub bottom: i32,
+    pub left: i32,
+    pub right: i32,
+}
+
+impl ReservedSpace {
+    /// `work_area` without the reserved edges.
+    pub fn apply(&self, work_area: Rect) -> Rect {
+        let (mut x, mut width) = (work_area.x, work_area.width);
+        if width - self.left - self.right >= MIN_RESERVED_VIEWPORT {
+            x += self.left;
+            width -= self.left + self.right;
+        }
+        let (mut y, mut height) = (work_area.y, work_area.height);
+        if height - self.top - self.bottom >= MIN_RESERVED_VIEWPORT {
+            y += self.top;
+            height -= self.top + self.bottom;
+        }
+        Rect::new(x, y, width, height)
+    }
+}
+
+/// A condition under which tiling on a monitor is paused.
+///
+/// All criteria that are set must hold; see [`crate::schedule`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(default)]
+pub struct PauseCondition {
+    /// Daily local-time window, e.g. "19:00-23:00" (may wrap past midnight).
+    pub between: Option<String>,
+    /// Executable shown fullscreen on the monitor, e.g. "vlc.exe".
+    pub fullscreen_executable: Option<String>,
+}
+
+/// Daemon event that can trigger a hook.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEventKind {
+    /// A new window was added to the layout.
+    WindowCreated,
+    /// The focused window changed.
+    FocusChanged,
+    /// A workspace's scroll position changed.
+    WorkspaceScrolled,
+    /// A window took focus without user input and was marked urgent.
+    WindowUrgent,
+}
+
+impl HookEventKind {
+    /// Name used in config files and passed to hook commands.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HookEventKind::WindowCreated => "window_created",
+            HookEventKind::FocusChanged => "focus_changed",
+            HookEventKind::WorkspaceScrolled => "workspace_scrolled",
+            HookEventKind::WindowUrgent => "window_urgent",
+        }
+    }
+}
+
+/// An external command run when a daemon event occurs.
+///
+/// Event data is written to the command's stdin as JSON; see [`crate::hooks`].
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HookConfig {
+    /// Event that triggers the hook.
+    pub event: HookEventKind,
+    /// Program to run.
+    pub command: String,
+    /// Arguments passed to the program.
+    #[serde(default)]
+    pub args: Vec<String>,
+    /// Minimum time between two runs of this hook; events in between are skipped.
+    #[serde(default = "default_hook_min_interval_ms")]
+    pub min_interval_ms: u64,
+}
+
+fn default_hook_min_interval_ms() -> u64 {
+    250
+}
+
+/// A layout profile applied when a window working in a project is focused.
+///
+/// See [`crate::projects`] for how projects are detected.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ProjectProfile {
+    /// Profile name, used by `openniri-cli apply-project`.
+    pub name: String,
+    /// Project root directory.
+    pub path: String,
+    /// Programs started in the project directory the first time the project is detected.
+    #[serde(default)]
+    pub launch: Vec<ProjectLaunch>,
+    /// Column widths for windows on the project's monitor, by executable.
+    #[serde(default)]
+    pub widths: Vec<ProjectWidth>,
+}
+
+/// A program started for a project.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ProjectLaunch {
+    /// Program to run.
+    pub command: String,
+    /// Arguments passed to the program.
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+/// Column width for a project's windows of one application.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProjectWidth {
+    /// Executable name to match (case-insensitive), e.g. "Code.exe".
+    pub match_executable: String,
+    /// Column width as a fraction of the viewport (0.1 - 1.0).
+    pub fraction: f64,
+}
+
+/// A modifier key that gates a mouse behavior.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ModifierKey {
+    Ctrl,
+    Alt,
+    Shift,
+    /// Either Windows key.
+    Win,
+}
+
+/// What happens when a managed window takes focus without the user asking.
+///
+/// A focus change counts as unrequested when the daemon didn't focus the
+/// window itself and there was no keyboard or mouse input just before it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FocusStealPolicy {
+    /// Follow the focus change, scrolling the window into view.
+    #[default]
+    Allow,
+    /// Give focus back to the previously focused window.
+    Refuse,
+    /// Give focus back and mark the window urgent, firing `window_urgent`
+    /// hooks. The mark is cleared when the window is focused.
+    Urgent,
+}
+
+/// How windows scrolled out of view are hidden.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HideStrategyConfig {
+    /// DWM cloaking; windows stay in Alt-Tab and the taskbar.
+    Cloak,
+    /// Move windows far off-screen.
+    MoveOffScreen,
+    /// Minimize windows, so they show as minimized in the taskbar.
+    /// Restoring one from the taskbar scrolls the strip to it.
+    Minimize,
+}
+
 /// Appearance-related configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -106,30 +482,75 @@ pub struct AppearanceConfig {
     #[serde(default = "default_true")]
     pub use_cloaking: bool,
 
+    /// How off-screen windows are hidden. Overrides `use_cloaking` when set.
+    #[serde(default)]
+    pub hide_strategy: Option<HideStrategyConfig>,
+
     /// Whether to use batched window positioning (DeferWindowPos).
     #[serde(default = "default_true")]
     pub use_deferred_positioning: bool,
 
-    /// Whether to highlight the active window border (Windows 11+).
+    /// Whether to highlight the active window border. Uses the DWM border
+    /// color on Windows 11 and an outline overlay on Windows 10.
     #[serde(default = "default_true")]
     pub active_border: bool,
 
     /// Active window border color as hex RGB (e.g., "4285F4").
     #[serde(default = "default_active_border_color")]
     pub active_border_color: String,
+
+    /// Thickness of the Windows 10 outline overlay in pixels. The Windows 11
+    /// DWM border has a fixed width.
+    #[serde(default = "default_active_border_width")]
+    pub active_border_width: u32,
+
+    /// How long columns take to slide into place when windows open or
+    /// close, and the overview to zoom back, in milliseconds (0 disables).
+    #[serde(default = "default_window_animation_ms")]
+    pub window_animation_ms: u64,
+
+    /// Whether to paint backgrounds behind tiled columns and the empty parts
+    /// of the viewport instead of showing the desktop there.
+    #[serde(default)]
+    pub column_backgrounds: bool,
+
+    /// Column background color as hex RGB (e.g., "202020").
+    #[serde(default = "default_column_background_color")]
+    pub column_background_color: String,
+
+    /// Column background opacity (0-255).
+    #[serde(default = "default_column_background_opacity")]
+    pub column_background_opacity: u8,
 }
 
 impl Default for AppearanceConfig {
     fn default() -> Self {
         Self {
             use_cloaking: true,
+            hide_strategy: None,
             use_deferred_positioning: true,
             active_border: true,
             active_border_color: default_active_border_color(),
+            active_border_width: default_active_border_width(),
+            window_animation_ms: default_window_animation_ms(),
+            column_backgrounds: false,
+            column_background_color: default_column_background_color(),
+            column_background_opacity: default_column_background_opacity(),
         }
     }
 }
 
+impl AppearanceConfig {
+    /// The configured hide strategy, falling back to `use_cloaking`.
+    pub fn effective_hide_strategy(&self) -> HideStrategyConfig {
+        self.hide_strategy.unwrap_or(if self.use_cloaking {
+            HideStrategyConfig::Cloak
+        } else {
+            HideStrategyConfig::MoveOffScreen
+        })
+    }
+}
+
 /// Behavior-related configuration.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
@@ -155,6 +576,51 @@ pub struct BehaviorConfig {
     /// Only applies when focus_follows_mouse is true.
     #[serde(default = "default_focus_delay")]
     pub focus_follows_mouse_delay_ms: u32,
+
+    /// Only follow the mouse while this modifier key is held.
+    #[serde(default)]
+    pub focus_follows_mouse_modifier: Option<ModifierKey>,
+
+    /// Also scroll the hovered window's column fully into view (the
+    /// default). Turn off to focus a column peeking in at the edge where it is.
+    #[serde(default = "default_true")]
+    pub focus_follows_mouse_strict: bool,
+
+    /// What to do when a window takes focus without user input.
+    #[serde(default)]
+    pub focus_steal_policy: FocusStealPolicy,
+
+    /// Whether to reload the config automatically when the file is saved.
+    #[serde(default = "default_true")]
+    pub watch_config: bool,
+
+    /// Scroll the viewport of the monitor under the cursor when the mouse
+    /// wheel turns over a managed window's title bar, or anywhere with Win
+    /// held (unless a `Win+Wheel...` hotkey is bound).
+    #[serde(default = "default_false")]
+    pub wheel_scroll_on_titlebar: bool,
+
+    /// Viewport scroll per wheel notch in pixels.
+    #[serde(default = "default_wheel_scroll_step")]
+    pub wheel_scroll_step: u32,
+
+    /// Detect drags of tiled windows from left button presses seen by the
+    /// low-level mouse hook, so applications that move their own windows
+    /// (custom title bars) also get the column insertion preview.
+    /// Takes effect on restart.
+    #[serde(default = "default_false")]
+    pub track_mouse_drags: bool,
+
+    /// Manage only windows on the current Windows virtual desktop. Windows
+    /// of the desktop left behind leave the layout when another desktop's
+    /// window takes focus, and the new desktop's windows are adopted.
+    #[serde(default = "default_true")]
+    pub virtual_desktops: bool,
+
+    /// Keep a named workspace per virtual desktop, so each desktop's column
+    /// layout survives switching away and back. Requires `virtual_desktops`.
+    #[serde(default = "default_false")]
+    pub desktop_workspaces: bool,
 }
 
 impl Default for BehaviorConfig {
@@ -165,6 +631,15 @@ impl Default for BehaviorConfig {
             log_level: default_log_level(),
             focus_follows_mouse: false,
             focus_follows_mouse_delay_ms: default_focus_delay(),
+            focus_follows_mouse_modifier: None,
+            focus_follows_mouse_strict: true,
+            focus_steal_policy: FocusStealPolicy::Allow,
+            watch_config: true,
+            wheel_scroll_on_titlebar: false,
+            wheel_scroll_step: default_wheel_scroll_step(),
+            track_mouse_drags: false,
+            virtual_desktops: true,
+            desktop_workspaces: false,
         }
     }
 }
@@ -190,6 +665,14 @@ fn default_max_column_width() -> i32 {
     1600
 }
 
+fn default_collapsed_column_width() -> i32 {
+    openniri_core_layout::DEFAULT_COLLAPSED_COLUMN_WIDTH
+}
+
+fn default_preset_widths() -> Vec<f64> {
+    vec![0.333, 0.5, 0.667, 1.0]
+}
+
 fn default_true() -> bool {
     true
 }
@@ -202,10 +685,30 @@ fn default_focus_delay() -> u32 {
     100
 }
 
+fn default_wheel_scroll_step() -> u32 {
+    100
+}
+
 fn default_active_border_color() -> String {
     "4285F4".to_string()
 }
 
+fn default_column_background_color() -> String {
+    "202020".to_string()
+}
+
+fn default_column_background_opacity() -> u8 {
+    96
+}
+
+fn default_active_border_width() -> u32 {
+    3
+}
+
+fn default_window_animation_ms() -> u64 {
+    DEFAULT_COLUMN_ANIMATION_MS
+}
+
 // ============================================================================
 // Window Rules
 // ============================================================================
@@ -213,6 +716,8 @@ fn default_active_border_color() -> String {
 /// A rule for per-window behavior.
 ///
 /// Window rules are evaluated in order; the first matching rule wins.
+/// They are evaluated again when a window's title changes, so apps that set
+/// their title after the window appears are still matched.
 ///
 /// # Example Config
 ///
@@ -229,6 +734,20 @@ fn default_active_border_color() -> String {
 /// [[window_rules]]
 /// match_class = "#32770"  # Windows dialogs
 /// action = "ignore"
+///
+/// # Chat apps always stack into one shared column
+/// [[window_rules]]
+/// match_executable = "slack.exe"
+/// column_tag = "comm"
+///
+/// [[window_rules]]
+/// match_executable = "ms-teams.exe"
+/// column_tag = "comm"
+///
+/// # New Explorer windows join the column of a running Explorer window
+/// [[window_rules]]
+/// match_executable = "explorer.exe"
+/// stack_with = "explorer.exe"
 /// ```
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WindowRule {
@@ -255,6 +774,26 @@ pub struct WindowRule {
     /// Fixed height for floating windows (optional).
     #[serde(default)]
     pub height: Option<i32>,
+
+    /// Tiled windows of rules with the same tag share one column (optional).
+    #[serde(default)]
+    pub column_tag: Option<String>,
+
+    /// Keep floating windows of this rule above all other windows.
+    #[serde(default)]
+    pub always_on_top: bool,
+
+    /// Hold matching windows in the named scratchpad, hidden until
+    /// `toggle_scratchpad <name>` shows them (optional). A window is only
+    /// taken while the scratchpad is empty; later ones follow `action`.
+    #[serde(default)]
+    pub scratchpad: Option<String>,
+
+    /// Stack new tiled windows into the column of an already tiled window of
+    /// this app, given as a class name or executable (optional). Without
+    /// one on the same monitor, the window opens a new column.
+    #[serde(default)]
+    pub stack_with: Option<String>,
 }
 
 /// Action to take for a matching window.
@@ -270,6 +809,37 @@ pub enum WindowAction {
     Ignore,
 }
 
+/// Which windows the daemon manages.
+///
+/// ```toml
+/// management_mode = "whitelist"
+///
+/// [[window_rules]]
+/// match_executable = "WindowsTerminal.exe"
+/// action = "tile"
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ManagementMode {
+    /// Manage every window; rules with `action = "ignore"` opt windows out.
+    #[default]
+    #[serde(alias = "blacklist")]
+    All,
+    /// Manage only windows matched by a `tile` or `float` rule; everything
+    /// else is left to normal Windows behavior.
+    Whitelist,
+}
+
+impl ManagementMode {
+    /// Action for a window that no rule matched.
+    pub fn default_action(self) -> WindowAction {
+        match self {
+            ManagementMode::All => WindowAction::Tile,
+            ManagementMode::Whitelist => WindowAction::Ignore,
+        }
+    }
+}
+
 impl WindowRule {
     /// Check if this rule matches a window with the given properties.
     ///
@@ -328,18 +898,130 @@ impl WindowRule {
 /// Each key is a hotkey string (e.g., "Win+H") and each value is a command
 /// (e.g., "focus_left"). Supported commands:
 /// - focus_left, focus_right, focus_up, focus_down
-/// - move_column_left, move_column_right
-/// - focus_monitor_left, focus_monitor_right
+/// - focus_urgent (the window that requested attention first)
+/// - move_column_left, move_column_right, move_column_up, move_column_down
+///   (up and down on vertical strips)
+/// - consume_window_left, consume_window_right, consume_window_up,
+///   consume_window_down (stack into the neighboring column, or expel from a
+///   shared column)
+/// - split_column (focused window into a new column on the right),
+///   promote_window (swap the focused window with the top of its stack)
+/// - focus_monitor_left, focus_monitor_right, focus_monitor_up, focus_monitor_down
 /// - move_to_monitor_left, move_to_monitor_right
+/// - move_column_to_monitor_left, move_column_to_monitor_right
+/// - gather_windows (tiled windows of all other monitors onto the focused one)
+/// - switch_workspace <name>, move_to_workspace <name>
+/// - save_monitor_profile <name>, apply_monitor_profile <name>
+/// - toggle_column_tabbed
+/// - toggle_maximize_column (focused column to the viewport width and back)
+/// - move_floating <dx> <dy>, resize_floating <dw> <dh>, center_floating,
+///   snap_floating <left|right|top|bottom> (focused floating window, kept
+///   inside the work area)
+/// - toggle_always_on_top, set_always_on_top <on|off> (focused floating window)
+/// - toggle_scratchpad <name> (show the scratchpad window centered and
+///   floating on the focused monitor, or hide it), move_to_scratchpad <name>
+/// - cycle_width (through `layout.preset_widths`)
+/// - toggle_auto_balance (stretch columns to fill the viewport)
+/// - set_new_window_position <right_of_focus|end_of_strip|start|nearest_empty>
+///   (on the focused workspace, until the config is reloaded)
 /// - resize_grow, resize_shrink (by 50px)
+/// - enter_resize_mode (arrow keys resize the focused column and window,
+///   Enter keeps the new size, Escape restores the old one)
+/// - height_grow, height_shrink (focused window within its stack, by 50px)
 /// - scroll_left, scroll_right (by 100px)
 /// - refresh, reload
+///
+/// Keys may also be a modifier plus a wheel direction (`WheelUp`, `WheelDown`,
+/// `WheelLeft`, `WheelRight`), e.g. `"Win+WheelUp" = "scroll -120"`. Matching
+/// wheel events are swallowed so the window under the cursor doesn't scroll.
+///
+/// A value may also be a list of commands run in order, e.g.
+/// `"Win+Shift+Enter" = ["move_column_left", "focus_left"]`.
+///
+/// A table sets options for one binding, e.g.
+/// `"Win+Left" = { command = "focus_left", backend = "hook" }`. The `hook`
+/// backend claims combinations the shell owns (Win+Arrow, Win+Tab, ...)
+/// through a low-level keyboard hook instead of `RegisterHotKey`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(default)]
 pub struct HotkeyConfig {
-    /// Map of hotkey string to command name.
+    /// Map of hotkey string to command or command sequence.
     #[serde(flatten)]
-    pub bindings: HashMap<String, String>,
+    pub bindings: HashMap<String, HotkeyBinding>,
+}
+
+/// What a hotkey runs: one command string, or several in order.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum HotkeyBinding {
+    /// A single command, e.g. `"resize -100"`.
+    Command(String),
+    /// Commands run one after another, e.g. `["move_column_left", "focus_left"]`.
+    Sequence(Vec<String>),
+    /// A command or sequence with options, e.g.
+    /// `{ command = "focus_left", backend = "hook" }`.
+    Options(Box<HotkeyOptions>),
+}
+
+/// A hotkey binding with options.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HotkeyOptions {
+    /// The command or command sequence to run.
+    pub command: HotkeyBinding,
+    /// How the key combination is registered.
+    #[serde(default)]
+    pub backend: HotkeyBackend,
+}
+
+/// How a hotkey is registered with the system.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HotkeyBackend {
+    /// `RegisterHotKey`; fails for combinations the shell already owns.
+    #[default]
+    Register,
+    /// A low-level keyboard hook that sees key presses before the shell.
+    /// Win+L can't be claimed either way.
+    Hook,
+}
+
+impl HotkeyBinding {
+    /// Parse all commands.
+    ///
+    /// # Errors
+    ///
+    /// Returns the error of the first command that fails to parse.
+    pub fn parse(&self) -> Result<Vec<openniri_ipc::IpcCommand>, crate::command::CommandParseError> {
+        match self {
+            Self::Command(command) => crate::command::parse(command).map(|cmd| vec![cmd]),
+            Self::Sequence(commands) => crate::command::parse_sequence(commands),
+            Self::Options(options) => options.command.parse(),
+        }
+    }
+
+    /// How the key combination is registered.
+    pub fn backend(&self) -> HotkeyBackend {
+        match self {
+            Self::Options(options) => options.backend,
+            Self::Command(_) | Self::Sequence(_) => HotkeyBackend::Register,
+        }
+    }
+}
+
+impl From<&str> for HotkeyBinding {
+    fn from(command: &str) -> Self {
+        Self::Command(command.to_string())
+    }
+}
+
+impl std::fmt::Display for HotkeyBinding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Command(command) => f.write_str(command),
+            Self::Sequence(commands) => write!(f, "[{}]", commands.join(", ")),
+            Self::Options(options) => options.command.fmt(f),
+        }
+    }
 }
 
 impl Default for HotkeyConfig {
@@ -347,41 +1029,52 @@ impl Default for HotkeyConfig {
         let mut bindings = HashMap::new();
 
         // Default vim-style navigation with Win key
-        bindings.insert("Win+H".to_string(), "focus_left".to_string());
-        bindings.insert("Win+L".to_string(), "focus_right".to_string());
-        bindings.insert("Win+J".to_string(), "focus_down".to_string());
-        bindings.insert("Win+K".to_string(), "focus_up".to_string());
+        bindings.insert("Win+H".to_string(), "focus_left".into());
+        bindings.insert("Win+L".to_string(), "focus_right".into());
+        bindings.insert("Win+J".to_string(), "focus_down".into());
+        bindings.insert("Win+K".to_string(), "focus_up".into());
 
         // Move columns with Win+Shift
-        bindings.insert("Win+Shift+H".to_string(), "move_column_left".to_string());
-        bindings.insert("Win+Shift+L".to_string(), "move_column_right".to_string());
+        bindings.insert("Win+Shift+H".to_string(), "move_column_left".into());
+        bindings.insert("Win+Shift+L".to_string(), "move_column_right".into());
 
         // Resize with Win+Ctrl
-        bindings.insert("Win+Ctrl+H".to_string(), "resize_shrink".to_string());
-        bindings.insert("Win+Ctrl+L".to_string(), "resize_grow".to_string());
+        bindings.insert("Win+Ctrl+H".to_string(), "resize_shrink".into());
+        bindings.insert("Win+Ctrl+L".to_string(), "resize_grow".into());
+        bindings.insert("Win+Ctrl+J".to_string(), "height_shrink".into());
+        bindings.insert("Win+Ctrl+K".to_string(), "height_grow".into());
 
         // Monitor navigation with Win+Alt
-        bindings.insert("Win+Alt+H".to_string(), "focus_monitor_left".to_string());
-        bindings.insert("Win+Alt+L".to_string(), "focus_monitor_right".to_string());
+        bindings.insert("Win+Alt+H".to_string(), "focus_monitor_left".into());
+        bindings.insert("Win+Alt+L".to_string(), "focus_monitor_right".into());
 
         // Move to monitor with Win+Alt+Shift
-        bindings.insert("Win+Alt+Shift+H".to_string(), "move_to_monitor_left".to_string());
-        bindings.insert("Win+Alt+Shift+L".to_string(), "move_to_monitor_right".to_string());
+        bindings.insert("Win+Alt+Shift+H".to_string(), "move_to_monitor_left".into());
+        bindings.insert("Win+Alt+Shift+L".to_string(), "move_to_monitor_right".into());
+
+        // Move the whole column to monitor with Win+Ctrl+Shift
+        bindings.insert("Win+Ctrl+Shift+H".to_string(), "move_column_to_monitor_left".into());
+        bindings.insert("Win+Ctrl+Shift+L".to_string(), "move_column_to_monitor_right".into());
 
         // Utility
-        bindings.insert("Win+R".to_string(), "refresh".to_string());
+        bindings.insert("Win+R".to_string(), "refresh".into());
 
         // Close focused window
-        bindings.insert("Win+Shift+Q".to_string(), "close_window".to_string());
+        bindings.insert("Win+Shift+Q".to_string(), "close_window".into());
         // Toggle floating
-        bindings.insert("Win+F".to_string(), "toggle_floating".to_string());
+        bindings.insert("Win+F".to_string(), "toggle_floating".into());
         // Toggle fullscreen
-        bindings.insert("Win+Shift+F".to_string(), "toggle_fullscreen".to_string());
+        bindings.insert("Win+Shift+F".to_string(), "toggle_fullscreen".into());
+        // Overview
+        bindings.insert("Win+O".to_string(), "toggle_overview".into());
         // Column width presets
-        bindings.insert("Win+1".to_string(), "width_third".to_string());
-        bindings.insert("Win+2".to_string(), "width_half".to_string());
-        bindings.insert("Win+3".to_string(), "width_two_thirds".to_string());
-        bindings.insert("Win+0".to_string(), "equalize_widths".to_string());
+        bindings.insert("Win+1".to_string(), "width_third".into());
+        bindings.insert("Win+2".to_string(), "width_half".into());
+        bindings.insert("Win+3".to_string(), "width_two_thirds".into());
+        bindings.insert("Win+0".to_string(), "equalize_widths".into());
+        // Collapse / expand column
+        bindings.insert("Win+Minus".to_string(), "collapse_column".into());
+        bindings.insert("Win+Equals".to_string(), "expand_column".into());
 
         Self { bindings }
     }
@@ -412,6 +1105,16 @@ pub struct GestureConfig {
     /// Command for three-finger swipe down.
     #[serde(default = "default_swipe_down")]
     pub swipe_down: String,
+
+    /// Pan the viewport along with horizontal touchpad scrolling and let it
+    /// coast with inertia, instead of running `swipe_left`/`swipe_right`.
+    #[serde(default = "default_false")]
+    pub kinetic_scroll: bool,
+
+    /// Fraction of kinetic scroll velocity kept per millisecond; lower
+    /// values stop sooner.
+    #[serde(default = "default_kinetic_friction")]
+    pub kinetic_friction: f64,
 }
 
 fn default_false() -> bool {
@@ -434,6 +1137,10 @@ fn default_swipe_down() -> String {
     "focus_down".to_string()
 }
 
+fn default_kinetic_friction() -> f64 {
+    openniri_core_layout::DEFAULT_KINETIC_FRICTION
+}
+
 impl Default for GestureConfig {
     fn default() -> Self {
         Self {
@@ -442,6 +1149,8 @@ impl Default for GestureConfig {
             swipe_right: default_swipe_right(),
             swipe_up: default_swipe_up(),
             swipe_down: default_swipe_down(),
+            kinetic_scroll: false,
+            kinetic_friction: default_kinetic_friction(),
         }
     }
 }
@@ -484,6 +1193,31 @@ impl Default for SnapHintConfig {
     }
 }
 
+/// IPC pipe access control.
+///
+/// Read once at startup, when the pipe is created.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct IpcConfig {
+    /// Create the pipe with a DACL for the current user only and reject
+    /// clients run by other users.
+    #[serde(default = "default_true")]
+    pub restrict_to_user: bool,
+
+    /// Group (account name or SID) whose members may also use the pipe.
+    #[serde(default)]
+    pub allowed_group: Option<String>,
+}
+
+impl Default for IpcConfig {
+    fn default() -> Self {
+        Self {
+            restrict_to_user: true,
+            allowed_group: None,
+        }
+    }
+}
+
 /// A warning generated during config validation.
 #[derive(Debug, Clone)]
 pub struct ConfigWarning {
@@ -506,6 +1240,14 @@ pub struct CompiledWindowRule {
     pub width: Option<i32>,
     /// Fixed height for floating windows (optional).
     pub height: Option<i32>,
+    /// Tag of the shared column for tiled windows (optional).
+    pub column_tag: Option<String>,
+    /// Keep floating windows above all other windows.
+    pub always_on_top: bool,
+    /// Scratchpad that holds matching windows (optional).
+    pub scratchpad: Option<String>,
+    /// Class name or executable of the app whose column to join (optional).
+    pub stack_with: Option<String>,
 }
 
 impl CompiledWindowRule {
@@ -541,40 +1283,6 @@ impl CompiledWindowRule {
     }
 }
 
-/// Parse a command string into an IpcCommand.
-///
-/// Returns None if the command is not recognized.
-pub fn parse_command(cmd: &str) -> Option<openniri_ipc::IpcCommand> {
-    use openniri_ipc::IpcCommand;
-
-    match cmd.to_lowercase().as_str() {
-        "focus_left" => Some(IpcCommand::FocusLeft),
-        "focus_right" => Some(IpcCommand::FocusRight),
-        "focus_up" => Some(IpcCommand::FocusUp),
-        "focus_down" => Some(IpcCommand::FocusDown),
-        "move_column_left" => Some(IpcCommand::MoveColumnLeft),
-        "move_column_right" => Some(IpcCommand::MoveColumnRight),
-        "focus_monitor_left" => Some(IpcCommand::FocusMonitorLeft),
-        "focus_monitor_right" => Some(IpcCommand::FocusMonitorRight),
-        "move_to_monitor_left" => Some(IpcCommand::MoveWindowToMonitorLeft),
-        "move_to_monitor_right" => Some(IpcCommand::MoveWindowToMonitorRight),
-        "resize_grow" => Some(IpcCommand::Resize { delta: 50 }),
-        "resize_shrink" => Some(IpcCommand::Resize { delta: -50 }),
-        "scroll_left" => Some(IpcCommand::Scroll { delta: -100.0 }),
-        "scroll_right" => Some(IpcCommand::Scroll { delta: 100.0 }),
-        "refresh" => Some(IpcCommand::Refresh),
-        "reload" => Some(IpcCommand::Reload),
-        "close_window" => Some(IpcCommand::CloseWindow),
-        "toggle_floating" => Some(IpcCommand::ToggleFloating),
-        "toggle_fullscreen" => Some(IpcCommand::ToggleFullscreen),
-        "width_third" => Some(IpcCommand::SetColumnWidth { fraction: 0.333 }),
-        "width_half" => Some(IpcCommand::SetColumnWidth { fraction: 0.5 }),
-        "width_two_thirds" => Some(IpcCommand::SetColumnWidth { fraction: 0.667 }),
-        "equalize_widths" => Some(IpcCommand::EqualizeColumnWidths),
-        _ => None,
-    }
-}
-
 impl Config {
     /// Load configuration from standard locations.
     ///
@@ -585,17 +1293,60 @@ impl Config {
     ///
     /// Returns default config if no file is found.
     pub fn load() -> Result<Self> {
-        let paths = config_paths();
-
-        for path in &paths {
-            if path.exists() {
+        match config_paths().into_iter().find(|path| path.exists()) {
+            Some(path) => {
                 tracing::info!("Loading config from: {}", path.display());
-                return Self::load_from_path(path);
+                Self::load_from_path(&path)
+            }
+            None => {
+                tracing::info!("No config file found, using defaults");
+                Ok(Self::default())
+            }
+        }
+    }
+
+    /// Look up per-monitor settings by device name.
+    ///
+    /// Keys match case-insensitively, with or without the `\\.\` device prefix.
+    pub fn monitor_config(&self, device_name: &str) -> Option<&MonitorConfig> {
+        let wanted = normalize_device_name(device_name);
+        self.monitors
+            .iter()
+            .find(|(key, _)| normalize_device_name(key) == wanted)
+            .map(|(_, config)| config)
+    }
+
+    /// Layout settings for a monitor: `[layout]` with every matching
+    /// `[[monitor]]` override applied in order.
+    pub fn layout_for(&self, device_name: &str, width: i32, height: i32) -> LayoutConfig {
+        let mut layout = self.layout.clone();
+        for monitor_override in &self.monitor_overrides {
+            if monitor_override.matches(device_name, width, height) {
+                monitor_override.apply(&mut layout);
             }
         }
+        layout
+    }
 
-        tracing::info!("No config file found, using defaults");
-        Ok(Self::default())
+    /// Layout mode configured for a monitor (scrolling unless overridden).
+    pub fn layout_mode_for(&self, device_name: &str) -> LayoutMode {
+        self.monitor_config(device_name)
+            .map(|m| m.layout_mode.into())
+            .unwrap_or_default()
+    }
+
+    /// Strip orientation configured for a monitor (horizontal unless overridden).
+    pub fn orientation_for(&self, device_name: &str) -> Orientation {
+        self.monitor_config(device_name)
+            .map(|m| m.orientation.into())
+            .unwrap_or_default()
+    }
+
+    /// Space reserved on a monitor (none unless configured).
+    pub fn reserved_space_for(&self, device_name: &str) -> ReservedSpace {
+        self.monitor_config(device_name)
+            .map(|m| m.reserved_space)
+            .unwrap_or_default()
     }
 
     /// Validate configuration values, clamping out-of-range fields and returning warnings.
@@ -656,6 +1407,92 @@ impl Config {
             self.layout.default_column_width = clamped;
         }
 
+        // shrink_to_fit_threshold must be within [0.0, 0.5]
+        let threshold = self.layout.shrink_to_fit_threshold;
+        if !(0.0..=MAX_SHRINK_TO_FIT_THRESHOLD).contains(&threshold) {
+            let clamped = if threshold.is_nan() {
+                0.0
+            } else {
+                threshold.clamp(0.0, MAX_SHRINK_TO_FIT_THRESHOLD)
+            };
+            warnings.push(ConfigWarning {
+                field: "layout.shrink_to_fit_threshold".to_string(),
+                message: format!(
+                    "shrink_to_fit_threshold ({}) outside [0, {}], clamped to {}",
+                    threshold, MAX_SHRINK_TO_FIT_THRESHOLD, clamped,
+                ),
+            });
+            self.layout.shrink_to_fit_threshold = clamped;
+        }
+
+        // [[monitor]] overrides need valid criteria and follow the [layout] limits
+        let (min_width, max_width) = (self.layout.min_column_width, self.layout.max_column_width);
+        for (i, entry) in self.monitor_overrides.iter_mut().enumerate() {
+            let mut warn = |field: &str, message: String| {
+                warnings.push(ConfigWarning { field: format!("monitor[{}]{}", i, field), message });
+            };
+            if entry.match_name.is_none() && entry.match_resolution.is_none() {
+                warn("", "no match_name or match_resolution; this entry matches no monitor".to_string());
+            }
+            if let Some(resolution) = entry.match_resolution.as_deref() {
+                if parse_resolution(resolution).is_none() {
+                    warn(
+                        ".match_resolution",
+                        format!("'{}' is not a resolution like \"3440x1440\"; this entry matches no monitor", resolution),
+                    );
+                }
+            }
+            for (field, value) in [(".gap", &mut entry.gap), (".outer_gap", &mut entry.outer_gap)] {
+                if let Some(v) = value.filter(|v| *v < 0) {
+                    warn(field, format!("Negative value ({}) clamped to 0", v));
+                    *value = Some(0);
+                }
+            }
+            if let Some(width) = entry.default_column_width.filter(|w| !(min_width..=max_width).contains(w)) {
+                let clamped = width.clamp(min_width, max_width);
+                warn(
+                    ".default_column_width",
+                    format!("default_column_width ({}) outside [{}, {}], clamped to {}", width, min_width, max_width, clamped),
+                );
+                entry.default_column_width = Some(clamped);
+            }
+            if let Some(threshold) =
+                entry.shrink_to_fit_threshold.filter(|t| !(0.0..=MAX_SHRINK_TO_FIT_THRESHOLD).contains(t))
+            {
+                let clamped = if threshold.is_nan() { 0.0 } else { threshold.clamp(0.0, MAX_SHRINK_TO_FIT_THRESHOLD) };
+                warn(
+                    ".shrink_to_fit_threshold",
+                    format!(
+                        "shrink_to_fit_threshold ({}) outside [0, {}], clamped to {}",
+                        threshold, MAX_SHRINK_TO_FIT_THRESHOLD, clamped
+                    ),
+                );
+                entry.shrink_to_fit_threshold = Some(clamped);
+            }
+        }
+
+        // preset_widths must be fractions in [0.1, 1.0], cycled in ascending order
+        let presets_before = self.layout.preset_widths.len();
+        self.layout.preset_widths.retain(|f| (0.1..=1.0).contains(f));
+        if self.layout.preset_widths.len() < presets_before {
+            warnings.push(ConfigWarning {
+                field: "layout.preset_widths".to_string(),
+                message: format!(
+                    "{} preset width(s) outside [0.1, 1.0] will be ignored",
+                    presets_before - self.layout.preset_widths.len()
+                ),
+            });
+        }
+        self.layout.preset_widths.sort_by(f64::total_cmp);
+        self.layout.preset_widths.dedup();
+        if self.layout.preset_widths.is_empty() {
+            warnings.push(ConfigWarning {
+                field: "layout.preset_widths".to_string(),
+                message: "no preset widths, using defaults".to_string(),
+            });
+            self.layout.preset_widths = default_preset_widths();
+        }
+
         // focus_follows_mouse_delay_ms must be >= 50 when enabled
         if self.behavior.focus_follows_mouse && self.behavior.focus_follows_mouse_delay_ms < 50 {
             warnings.push(ConfigWarning {
@@ -668,6 +1505,59 @@ impl Config {
             self.behavior.focus_follows_mouse_delay_ms = 50;
         }
 
+        if self.behavior.wheel_scroll_step == 0 {
+            warnings.push(ConfigWarning {
+                field: "behavior.wheel_scroll_step".to_string(),
+                message: format!("wheel_scroll_step must be positive, using {}", default_wheel_scroll_step()),
+            });
+            self.behavior.wheel_scroll_step = default_wheel_scroll_step();
+        }
+
+        // reserved_space edges must be >= 0
+        for (name, monitor) in self.monitors.iter_mut() {
+            let space = &mut monitor.reserved_space;
+            for (edge, value) in [
+                ("top", &mut space.top),
+                ("bottom", &mut space.bottom),
+                ("left", &mut space.left),
+                ("right", &mut space.right),
+            ] {
+                if *value < 0 {
+                    warnings.push(ConfigWarning {
+                        field: format!("monitors.{}.reserved_space.{}", name, edge),
+                        message: format!("Negative reserved space ({}) clamped to 0", value),
+                    });
+                    *value = 0;
+                }
+            }
+        }
+
+        // active_border_width must be in 1..=32
+        let width = self.appearance.active_border_width;
+        if !(1..=MAX_ACTIVE_BORDER_WIDTH).contains(&width) {
+            let clamped = width.clamp(1, MAX_ACTIVE_BORDER_WIDTH);
+            warnings.push(ConfigWarning {
+                field: "appearance.active_border_width".to_string(),
+                message: format!(
+                    "active_border_width ({}) outside 1-{}, clamped to {}",
+                    width, MAX_ACTIVE_BORDER_WIDTH, clamped
+                ),
+            });
+            self.appearance.active_border_width = clamped;
+        }
+
+        // window_animation_ms must be <= 1000
+        if self.appearance.window_animation_ms > MAX_WINDOW_ANIMATION_MS {
+            warnings.push(ConfigWarning {
+                field: "appearance.window_animation_ms".to_string(),
+                message: format!(
+                    "window_animation_ms ({}) above maximum {}, clamped to {}",
+                    self.appearance.window_animation_ms, MAX_WINDOW_ANIMATION_MS, MAX_WINDOW_ANIMATION_MS
+                ),
+            });
+            self.appearance.window_animation_ms = MAX_WINDOW_ANIMATION_MS;
+        }
+
         // snap_hints.duration_ms must be >= 50 when enabled
         if self.snap_hints.enabled && self.snap_hints.duration_ms < 50 {
             warnings.push(ConfigWarning {
@@ -680,6 +1570,148 @@ impl Config {
             self.snap_hints.duration_ms = 50;
         }
 
+        // pause conditions must have valid criteria
+        for (name, monitor) in self.monitors.iter_mut() {
+            monitor.pause_when.retain(|condition| {
+                let problem = if condition.between.is_none() && condition.fullscreen_executable.is_none() {
+                    Some("condition has no criteria".to_string())
+                } else {
+                    condition.between.as_deref()
+                        .and_then(|between| between.parse::<crate::schedule::TimeRange>().err())
+                        .map(|e| e.to_string())
+                };
+                match problem {
+                    Some(message) => {
+                        warnings.push(ConfigWarning {
+                            field: format!("monitors.{}.pause_when", name),
+                            message: format!("{}; condition will be ignored", message),
+                        });
+                        false
+                    }
+                    None => true,
+                }
+            });
+        }
+
+        // hooks need a command to run
+        let hooks_before = self.hooks.len();
+        self.hooks.retain(|hook| !hook.command.trim().is_empty());
+        if self.hooks.len() < hooks_before {
+            warnings.push(ConfigWarning {
+                field: "hooks".to_string(),
+                message: format!(
+                    "{} hook(s) without a command will be ignored",
+                    hooks_before - self.hooks.len()
+                ),
+            });
+        }
+
+        // projects need a name and a directory
+        let mut project_names = std::collections::HashSet::new();
+        self.projects.retain(|project| {
+            let problem = if project.name.trim().is_empty() || project.path.trim().is_empty() {
+                Some("project needs a name and a path")
+            } else if !project_names.insert(project.name.to_lowercase()) {
+                Some("duplicate project name")
+            } else {
+                None
+            };
+            if let Some(message) = problem {
+                warnings.push(ConfigWarning {
+                    field: format!("projects.{}", project.name),
+                    message: format!("{}; profile will be ignored", message),
+                });
+            }
+            problem.is_none()
+        });
+        for project in &mut self.projects {
+            for width in &mut project.widths {
+                if !(0.1..=1.0).contains(&width.fraction) {
+                    let clamped = if width.fraction.is_nan() { 0.5 } else { width.fraction.clamp(0.1, 1.0) };
+                    warnings.push(ConfigWarning {
+                        field: format!("projects.{}.widths", project.name),
+                        message: format!(
+                            "fraction {} for {} out of range, clamped to {}",
+                            width.fraction, width.match_executable, clamped
+                        ),
+                    });
+                    width.fraction = clamped;
+                }
+            }
+        }
+
+        // whitelist mode needs at least one rule that manages windows
+        if self.management_mode == ManagementMode::Whitelist
+            && !self.window_rules.iter().any(|r| r.action != WindowAction::Ignore)
+        {
+            warnings.push(ConfigWarning {
+                field: "management_mode".to_string(),
+                message: "whitelist mode without any tile/float window rules; no windows will be managed"
+                    .to_string(),
+            });
+        }
+
+        // column tags only group tiled windows
+        for (i, rule) in self.window_rules.iter_mut().enumerate() {
+            let problem = match rule.column_tag.as_deref() {
+                Some(tag) if tag.trim().is_empty() => Some("empty column_tag"),
+                Some(_) if rule.action != WindowAction::Tile => Some("column_tag only applies to tiled windows"),
+                _ => None,
+            };
+            if let Some(message) = problem {
+                warnings.push(ConfigWarning {
+                    field: format!("window_rules[{}].column_tag", i),
+                    message: format!("{}; tag will be ignored", message),
+                });
+                rule.column_tag = None;
+            }
+        }
+
+        // stack_with only stacks tiled windows, and a column tag takes precedence
+        for (i, rule) in self.window_rules.iter_mut().enumerate() {
+            let problem = match rule.stack_with.as_deref() {
+                Some(app) if app.trim().is_empty() => Some("empty stack_with"),
+                Some(_) if rule.action != WindowAction::Tile => Some("stack_with only applies to tiled windows"),
+                Some(_) if rule.column_tag.is_some() => Some("stack_with can't be combined with column_tag"),
+                _ => None,
+            };
+            if let Some(message) = problem {
+                warnings.push(ConfigWarning {
+                    field: format!("window_rules[{}].stack_with", i),
+                    message: format!("{}; it will be ignored", message),
+                });
+                rule.stack_with = None;
+            }
+        }
+
+        // always_on_top only applies to floating windows, scratchpads to managed ones
+        for (i, rule) in self.window_rules.iter_mut().enumerate() {
+            if rule.always_on_top && rule.action != WindowAction::Float {
+                warnings.push(ConfigWarning {
+                    field: format!("window_rules[{}].always_on_top", i),
+                    message: "always_on_top only applies to floating windows; it will be ignored".to_string(),
+                });
+                rule.always_on_top = false;
+            }
+            if rule.scratchpad.is_some() && rule.action == WindowAction::Ignore {
+                warnings.push(ConfigWarning {
+                    field: format!("window_rules[{}].scratchpad", i),
+                    message: "ignored windows can't be scratchpads; it will be ignored".to_string(),
+                });
+                rule.scratchpad = None;
+            }
+        }
+
+        // hotkey commands must parse
+        for (key, binding) in &self.hotkeys.bindings {
+            if let Err(e) = binding.parse() {
+                warnings.push(ConfigWarning {
+                    field: format!("hotkeys.{}", key),
+                    message: format!("{}; binding will be ignored", e),
+                });
+            }
+        }
+
         warnings
     }
 
@@ -725,6 +1757,10 @@ impl Config {
                 action: rule.action,
                 width: rule.width,
                 height: rule.height,
+                column_tag: rule.column_tag.clone(),
+                always_on_top: rule.always_on_top,
+                scratchpad: rule.scratchpad.clone(),
+                stack_with: rule.stack_with.clone(),
             });
         }
 
@@ -743,30 +1779,17 @@ impl Config {
     }
 }
 
-/// Get all possible config file paths in priority order.
-pub fn config_paths() -> Vec<PathBuf> {
-    let mut paths = Vec::new();
-
-    // 1. Windows standard: %APPDATA%/openniri/config/config.toml
-    if let Some(proj_dirs) = ProjectDirs::from("", "", "openniri") {
-        paths.push(proj_dirs.config_dir().join("config.toml"));
-    }
-
-    // 2. Unix-style: ~/.config/openniri/config.toml
-    if let Some(home) = dirs_home() {
-        paths.push(home.join(".config").join("openniri").join("config.toml"));
-    }
-
-    // 3. Current directory: ./config.toml
-    paths.push(PathBuf::from("config.toml"));
+pub use openniri_ipc::config_paths;
 
-    paths
+/// Path of the config file [`Config::load`] reads.
+///
+/// Falls back to the preferred location when no config file exists yet, so
+/// watching it notices when one is created.
+pub fn active_config_path() -> Option<PathBuf> {
+    let paths = config_paths();
+    paths.iter().find(|path| path.exists()).or(paths.first()).cloned()
 }
 
-/// Get the user's home directory.
-fn dirs_home() -> Option<PathBuf> {
-    directories::BaseDirs::new().map(|d| d.home_dir().to_path_buf())
-}
 
 #[cfg(test)]
 mod tests {
@@ -783,6 +1806,30 @@ mod tests {
         assert!(config.behavior.focus_new_windows);
     }
 
+    #[test]
+    fn test_ipc_config() {
+        let config = Config::default();
+        assert!(config.ipc.restrict_to_user);
+        assert_eq!(config.ipc.allowed_group, None);
+
+        let config: Config = toml::from_str("[ipc]\nallowed_group = \"Administrators\"\n").unwrap();
+        assert!(config.ipc.restrict_to_user);
+        assert_eq!(config.ipc.allowed_group.as_deref(), Some("Administrators"));
+    }
+
+    #[test]
+    fn test_hide_strategy_overrides_use_cloaking() {
+        let config = Config::default();
+        assert_eq!(config.appearance.effective_hide_strategy(), HideStrategyConfig::Cloak);
+
+        let config: Config = toml::from_str("[appearance]\nuse_cloaking = false\n").unwrap();
+        assert_eq!(config.appearance.effective_hide_strategy(), HideStrategyConfig::MoveOffScreen);
+
+        let config: Config =
+            toml::from_str("[appearance]\nuse_cloaking = true\nhide_strategy = \"minimize\"\n").unwrap();
+        assert_eq!(config.appearance.effective_hide_strategy(), HideStrategyConfig::Minimize);
+    }
+
     #[test]
     fn test_config_serialization_roundtrip() {
         let config = Config::default();
@@ -823,27 +1870,123 @@ mod tests {
         assert!(!paths.is_empty());
     }
 
+    #[test]
+    fn test_active_config_path_is_a_candidate() {
+        let path = active_config_path().expect("config path");
+        assert!(config_paths().contains(&path));
+    }
+
     #[test]
     fn test_hotkey_config_default() {
         let config = HotkeyConfig::default();
         assert!(!config.bindings.is_empty());
-        assert_eq!(config.bindings.get("Win+H"), Some(&"focus_left".to_string()));
-        assert_eq!(config.bindings.get("Win+L"), Some(&"focus_right".to_string()));
-        assert_eq!(config.bindings.get("Win+Shift+H"), Some(&"move_column_left".to_string()));
+        assert_eq!(config.bindings.get("Win+H"), Some(&"focus_left".into()));
+        assert_eq!(config.bindings.get("Win+L"), Some(&"focus_right".into()));
+        assert_eq!(config.bindings.get("Win+Shift+H"), Some(&"move_column_left".into()));
     }
 
     #[test]
     fn test_parse_command() {
         use openniri_ipc::IpcCommand;
 
+        let parse_command = |cmd: &str| crate::command::parse(cmd).ok();
+
         assert_eq!(parse_command("focus_left"), Some(IpcCommand::FocusLeft));
         assert_eq!(parse_command("FOCUS_RIGHT"), Some(IpcCommand::FocusRight));
         assert_eq!(parse_command("move_column_left"), Some(IpcCommand::MoveColumnLeft));
         assert_eq!(parse_command("focus_monitor_left"), Some(IpcCommand::FocusMonitorLeft));
         assert_eq!(parse_command("resize_grow"), Some(IpcCommand::Resize { delta: 50 }));
         assert_eq!(parse_command("resize_shrink"), Some(IpcCommand::Resize { delta: -50 }));
+        assert_eq!(parse_command("height_grow"), Some(IpcCommand::ResizeWindowVertical { delta: 50 }));
         assert_eq!(parse_command("refresh"), Some(IpcCommand::Refresh));
         assert_eq!(parse_command("unknown_command"), None);
+        assert_eq!(parse_command("resize -30"), Some(IpcCommand::Resize { delta: -30 }));
+        assert_eq!(parse_command("set_width 2.0"), None);
+    }
+
+    #[test]
+    fn test_validate_warns_on_invalid_hotkey_command() {
+        let mut config = Config::default();
+        config.hotkeys.bindings.insert("Win+Z".to_string(), "resize abc".into());
+        let warnings = config.validate();
+        let warning = warnings.iter().find(|w| w.field == "hotkeys.Win+Z").unwrap();
+        assert!(warning.message.contains("expects a pixel amount"));
+    }
+
+    #[test]
+    fn test_validate_default_hotkeys_are_valid() {
+        let mut config = Config::default();
+        let warnings = config.validate();
+        assert!(warnings.iter().all(|w| !w.field.starts_with("hotkeys.")));
+    }
+
+    #[test]
+    fn test_monitor_layout_mode_config() {
+        let toml_str = r#"
+            [monitors.DISPLAY2]
+            layout_mode = "stack_only"
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.layout_mode_for("DISPLAY2"), LayoutMode::StackOnly);
+        assert_eq!(config.layout_mode_for(r"\\.\DISPLAY2"), LayoutMode::StackOnly);
+        assert_eq!(config.layout_mode_for("display2"), LayoutMode::StackOnly);
+        assert_eq!(config.layout_mode_for("DISPLAY1"), LayoutMode::Scrolling);
+    }
+
+    #[test]
+    fn test_monitor_orientation_config() {
+        let toml_str = r#"
+            [monitors.DISPLAY2]
+            orientation = "vertical"
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.orientation_for(r"\\.\DISPLAY2"), Orientation::Vertical);
+        assert_eq!(config.orientation_for("DISPLAY1"), Orientation::Horizontal);
+        assert!(toml::from_str::<Config>("[monitors.DISPLAY2]\norientation = \"diagonal\"").is_err());
+    }
+
+    #[test]
+    fn test_monitor_pause_conditions_config() {
+        let toml_str = r#"
+            [[monitors.DISPLAY3.pause_when]]
+            between = "19:00-23:00"
+
+            [[monitors.DISPLAY3.pause_when]]
+            fullscreen_executable = "vlc.exe"
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let monitor = config.monitor_config("DISPLAY3").unwrap();
+        assert_eq!(monitor.pause_when.len(), 2);
+        assert_eq!(monitor.pause_when[0].between.as_deref(), Some("19:00-23:00"));
+        assert_eq!(monitor.pause_when[1].fullscreen_executable.as_deref(), Some("vlc.exe"));
+    }
+
+    #[test]
+    fn test_validate_drops_invalid_pause_conditions() {
+        let toml_str = r#"
+            [[monitors.DISPLAY3.pause_when]]
+            between = "7pm-11pm"
+
+            [[monitors.DISPLAY3.pause_when]]
+
+            [[monitors.DISPLAY3.pause_when]]
+            between = "19:00-23:00"
+        "#;
+        let mut config: Config = toml::from_str(toml_str).unwrap();
+        let warnings = config.validate();
+        assert_eq!(warnings.iter().filter(|w| w.field == "monitors.DISPLAY3.pause_when").count(), 2);
+        assert_eq!(config.monitors["DISPLAY3"].pause_when.len(), 1);
+    }
+
+    #[test]
+    fn test_monitor_config_defaults() {
+        let config = Config::default();
+        assert!(config.monitors.is_empty());
+        assert!(config.monitor_config("DISPLAY1").is_none());
+
+        let config: Config = toml::from_str("[monitors.DISPLAY1]").unwrap();
+        assert_eq!(config.monitor_config("DISPLAY1"), Some(&MonitorConfig::default()));
+        assert_eq!(config.layout_mode_for("DISPLAY1"), LayoutMode::Scrolling);
     }
 
     #[test]
@@ -854,8 +1997,69 @@ mod tests {
             "Ctrl+Alt+B" = "focus_right"
         "#;
         let config: Config = toml::from_str(toml_str).unwrap();
-        assert_eq!(config.hotkeys.bindings.get("Win+A"), Some(&"focus_left".to_string()));
-        assert_eq!(config.hotkeys.bindings.get("Ctrl+Alt+B"), Some(&"focus_right".to_string()));
+        assert_eq!(config.hotkeys.bindings.get("Win+A"), Some(&"focus_left".into()));
+        assert_eq!(config.hotkeys.bindings.get("Ctrl+Alt+B"), Some(&"focus_right".into()));
+    }
+
+    #[test]
+    fn test_hotkey_sequence_bindings() {
+        use openniri_ipc::IpcCommand;
+
+        let toml_str = r#"
+            [hotkeys]
+            "Win+Minus" = "resize -100"
+            "Win+Shift+Enter" = ["move-column-left", "focus-left"]
+        "#;
+        let mut config: Config = toml::from_str(toml_str).unwrap();
+        let sequence = &config.hotkeys.bindings["Win+Shift+Enter"];
+        assert!(matches!(sequence, HotkeyBinding::Sequence(commands) if commands.len() == 2));
+        assert_eq!(sequence.parse(), Ok(vec![IpcCommand::MoveColumnLeft, IpcCommand::FocusLeft]));
+        assert_eq!(config.hotkeys.bindings["Win+Minus"].parse(), Ok(vec![IpcCommand::Resize { delta: -100 }]));
+        assert!(config.validate().iter().all(|w| !w.field.starts_with("hotkeys.")));
+    }
+
+    #[test]
+    fn test_hotkey_backend_option() {
+        use openniri_ipc::IpcCommand;
+
+        let toml_str = r#"
+            [hotkeys]
+            "Win+Left" = { command = "focus_left", backend = "hook" }
+            "Win+Tab" = { command = ["focus_right", "refresh"] }
+            "Win+H" = "focus_left"
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        let hook = &config.hotkeys.bindings["Win+Left"];
+        assert_eq!(hook.backend(), HotkeyBackend::Hook);
+        assert_eq!(hook.parse(), Ok(vec![IpcCommand::FocusLeft]));
+        assert_eq!(hook.to_string(), "focus_left");
+        let sequence = &config.hotkeys.bindings["Win+Tab"];
+        assert_eq!(sequence.backend(), HotkeyBackend::Register);
+        assert_eq!(sequence.parse(), Ok(vec![IpcCommand::FocusRight, IpcCommand::Refresh]));
+        assert_eq!(config.hotkeys.bindings["Win+H"].backend(), HotkeyBackend::Register);
+
+        assert!(toml::from_str::<Config>("[hotkeys]\n\"Win+Left\" = { command = \"focus_left\", backend = \"poll\" }").is_err());
+    }
+
+    #[test]
+    fn test_validate_warns_on_invalid_sequence_step() {
+        let mut config = Config::default();
+        config
+            .hotkeys
+            .bindings
+            .insert("Win+Z".to_string(), HotkeyBinding::Sequence(vec!["focus_left".to_string(), "explode".to_string()]));
+        let warnings = config.validate();
+        let warning = warnings.iter().find(|w| w.field == "hotkeys.Win+Z").unwrap();
+        assert!(warning.message.contains("Command 2 of the sequence"));
+    }
+
+    #[test]
+    fn test_new_window_position_config() {
+        assert_eq!(Config::default().layout.new_window_position, NewWindowPosition::RightOfFocus);
+        let config: Config = toml::from_str("[layout]\nnew_window_position = \"end-of-strip\"").unwrap();
+        assert_eq!(config.layout.new_window_position, NewWindowPosition::EndOfStrip);
+        let config: Config = toml::from_str("[layout]\nnew_window_position = \"nearest_empty\"").unwrap();
+        assert_eq!(config.layout.new_window_position, NewWindowPosition::NearestEmpty);
     }
 
     #[test]
@@ -918,6 +2122,10 @@ mod tests {
             action: WindowAction::Float,
             width: None,
             height: None,
+            column_tag: None,
+            always_on_top: false,
+            scratchpad: None,
+            stack_with: None,
         };
 
         assert!(rule.matches("Notepad", "Untitled - Notepad", "notepad.exe"));
@@ -933,6 +2141,10 @@ mod tests {
             action: WindowAction::Float,
             width: Some(800),
             height: Some(600),
+            column_tag: None,
+            always_on_top: false,
+            scratchpad: None,
+            stack_with: None,
         };
 
         assert!(rule.matches("Chrome_WidgetWin_1", "DevTools - localhost:3000", "chrome.exe"));
@@ -949,6 +2161,10 @@ mod tests {
             action: WindowAction::Float,
             width: None,
             height: None,
+            column_tag: None,
+            always_on_top: false,
+            scratchpad: None,
+            stack_with: None,
         };
 
         assert!(rule.matches("SpotifyClass", "Spotify - Song Title", "spotify.exe"));
@@ -965,6 +2181,10 @@ mod tests {
             action: WindowAction::Tile,
             width: None,
             height: None,
+            column_tag: None,
+            always_on_top: false,
+            scratchpad: None,
+            stack_with: None,
         };
 
         // Both patterns must match
@@ -982,6 +2202,10 @@ mod tests {
             action: WindowAction::Ignore,
             width: None,
             height: None,
+            column_tag: None,
+            always_on_top: false,
+            scratchpad: None,
+            stack_with: None,
         };
 
         assert!(!rule.matches("AnyClass", "Any Title", "any.exe"));
@@ -1019,6 +2243,162 @@ mod tests {
         assert_eq!(config.window_rules[2].action, WindowAction::Ignore);
     }
 
+    #[test]
+    fn test_monitor_overrides_by_name_and_resolution() {
+        let toml_str = r#"
+            [layout]
+            gap = 10
+            default_column_width = 800
+
+            [[monitor]]
+            match_resolution = "5120x1440"
+            gap = 20
+            default_column_width = 1600
+
+            [[monitor]]
+            match_name = "DISPLAY2"
+            default_column_width = 1000
+            centering_mode = "just_in_view"
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+
+        let ultrawide = config.layout_for(r"\\.\DISPLAY1", 5120, 1440);
+        assert_eq!((ultrawide.gap, ultrawide.default_column_width), (20, 1600));
+
+        let side = config.layout_for("display2", 1080, 1920);
+        assert_eq!((side.gap, side.default_column_width), (10, 1000));
+        assert_eq!(side.centering_mode, CenteringModeConfig::JustInView);
+
+        // Later entries win where several match
+        let both = config.layout_for("DISPLAY2", 5120, 1440);
+        assert_eq!((both.gap, both.default_column_width), (20, 1000));
+
+        let other = config.layout_for("DISPLAY3", 1920, 1080);
+        assert_eq!(other.default_column_width, 800);
+    }
+
+    #[test]
+    fn test_validate_monitor_overrides() {
+        let toml_str = r#"
+            [[monitor]]
+            gap = 5
+
+            [[monitor]]
+            match_resolution = "wide"
+
+            [[monitor]]
+            match_name = "DISPLAY1"
+            gap = -4
+            default_column_width = 99999
+        "#;
+        let mut config: Config = toml::from_str(toml_str).unwrap();
+        let warnings = config.validate();
+        let fields: Vec<_> = warnings.iter().map(|w| w.field.as_str()).collect();
+        assert!(fields.contains(&"monitor[0]"));
+        assert!(fields.contains(&"monitor[1].match_resolution"));
+        assert!(fields.contains(&"monitor[2].gap"));
+        assert!(fields.contains(&"monitor[2].default_column_width"));
+        assert_eq!(config.monitor_overrides[2].gap, Some(0));
+        assert_eq!(config.monitor_overrides[2].default_column_width, Some(config.layout.max_column_width));
+        assert!(!config.monitor_overrides[1].matches("DISPLAY1", 1920, 1080));
+    }
+
+    #[test]
+    fn test_window_rule_always_on_top() {
+        let toml_str = r#"
+            [[window_rules]]
+            match_executable = "mpv.exe"
+            action = "float"
+            always_on_top = true
+
+            [[window_rules]]
+            match_executable = "code.exe"
+            always_on_top = true
+        "#;
+        let mut config: Config = toml::from_str(toml_str).unwrap();
+        let warnings = config.validate();
+        assert!(warnings.iter().any(|w| w.field == "window_rules[1].always_on_top"));
+        assert!(!config.window_rules[1].always_on_top);
+
+        let compiled = config.compile_window_rules();
+        assert!(compiled[0].always_on_top);
+        assert!(!compiled[1].always_on_top);
+    }
+
+    #[test]
+    fn test_window_rule_scratchpad() {
+        let toml_str = r#"
+            [[window_rules]]
+            match_executable = "WindowsTerminal.exe"
+            scratchpad = "term"
+            width = 1200
+
+            [[window_rules]]
+            match_executable = "spotify.exe"
+            action = "ignore"
+            scratchpad = "music"
+        "#;
+        let mut config: Config = toml::from_str(toml_str).unwrap();
+        let warnings = config.validate();
+        assert!(warnings.iter().any(|w| w.field == "window_rules[1].scratchpad"));
+
+        let compiled = config.compile_window_rules();
+        assert_eq!(compiled[0].scratchpad.as_deref(), Some("term"));
+        assert_eq!(compiled[1].scratchpad, None);
+    }
+
+    #[test]
+    fn test_window_rule_stack_with() {
+        let toml_str = r#"
+            [[window_rules]]
+            match_executable = "explorer.exe"
+            stack_with = "explorer.exe"
+
+            [[window_rules]]
+            match_class = "Notepad"
+            action = "float"
+            stack_with = "Notepad"
+
+            [[window_rules]]
+            match_executable = "slack.exe"
+            column_tag = "comm"
+            stack_with = "ms-teams.exe"
+        "#;
+        let mut config: Config = toml::from_str(toml_str).unwrap();
+        let warnings = config.validate();
+        assert!(!warnings.iter().any(|w| w.field == "window_rules[0].stack_with"));
+        assert!(warnings.iter().any(|w| w.field == "window_rules[1].stack_with"));
+        assert!(warnings.iter().any(|w| w.field == "window_rules[2].stack_with"));
+
+        let compiled = config.compile_window_rules();
+        assert_eq!(compiled[0].stack_with.as_deref(), Some("explorer.exe"));
+        assert_eq!(compiled[1].stack_with, None);
+        assert_eq!(compiled[2].stack_with, None);
+    }
+
+    #[test]
+    fn test_window_rule_column_tag() {
+        let toml_str = r#"
+            [[window_rules]]
+            match_executable = "slack.exe"
+            column_tag = "comm"
+
+            [[window_rules]]
+            match_executable = "discord.exe"
+            action = "float"
+            column_tag = "comm"
+        "#;
+        let mut config: Config = toml::from_str(toml_str).unwrap();
+        assert_eq!(config.window_rules[0].column_tag.as_deref(), Some("comm"));
+
+        let warnings = config.validate();
+        assert!(warnings.iter().any(|w| w.field == "window_rules[1].column_tag"));
+        assert_eq!(config.window_rules[1].column_tag, None);
+
+        let compiled = config.compile_window_rules();
+        assert_eq!(compiled[0].column_tag.as_deref(), Some("comm"));
+    }
+
     #[test]
     fn test_window_action_default() {
         let action = WindowAction::default();
@@ -1052,6 +2432,8 @@ mod tests {
         let config = Config::default();
         assert!(!config.behavior.focus_follows_mouse);
         assert_eq!(config.behavior.focus_follows_mouse_delay_ms, 100);
+        assert_eq!(config.behavior.focus_follows_mouse_modifier, None);
+        assert!(config.behavior.focus_follows_mouse_strict);
     }
 
     #[test]
@@ -1060,10 +2442,38 @@ mod tests {
             [behavior]
             focus_follows_mouse = true
             focus_follows_mouse_delay_ms = 200
+            focus_follows_mouse_modifier = "ctrl"
+            focus_follows_mouse_strict = false
         "#;
         let config: Config = toml::from_str(toml_str).unwrap();
         assert!(config.behavior.focus_follows_mouse);
         assert_eq!(config.behavior.focus_follows_mouse_delay_ms, 200);
+        assert_eq!(config.behavior.focus_follows_mouse_modifier, Some(ModifierKey::Ctrl));
+        assert!(!config.behavior.focus_follows_mouse_strict);
+    }
+
+    #[test]
+    fn test_focus_steal_policy() {
+        assert_eq!(Config::default().behavior.focus_steal_policy, FocusStealPolicy::Allow);
+        let config: Config = toml::from_str("[behavior]\nfocus_steal_policy = \"urgent\"").unwrap();
+        assert_eq!(config.behavior.focus_steal_policy, FocusStealPolicy::Urgent);
+        assert!(toml::from_str::<Config>("[behavior]\nfocus_steal_policy = \"never\"").is_err());
+    }
+
+    #[test]
+    fn test_virtual_desktop_settings() {
+        let config = Config::default();
+        assert!(config.behavior.virtual_desktops);
+        assert!(!config.behavior.desktop_workspaces);
+
+        let toml_str = r#"
+            [behavior]
+            virtual_desktops = false
+            desktop_workspaces = true
+        "#;
+        let config: Config = toml::from_str(toml_str).unwrap();
+        assert!(!config.behavior.virtual_desktops);
+        assert!(config.behavior.desktop_workspaces);
     }
 
     // =========================================================================
@@ -1081,6 +2491,10 @@ mod tests {
                 action: WindowAction::Float,
                 width: Some(800),
                 height: Some(600),
+                column_tag: None,
+                always_on_top: false,
+                scratchpad: None,
+                stack_with: None,
             },
             WindowRule {
                 match_class: Some("Notepad".to_string()),
@@ -1089,6 +2503,10 @@ mod tests {
                 action: WindowAction::Ignore, // Different action
                 width: None,
                 height: None,
+                column_tag: None,
+                always_on_top: false,
+                scratchpad: None,
+                stack_with: None,
             },
         ];
 
@@ -1113,6 +2531,10 @@ mod tests {
             action: WindowAction::Ignore,
             width: None,
             height: None,
+            column_tag: None,
+            always_on_top: false,
+            scratchpad: None,
+            stack_with: None,
         };
 
         assert!(rule.matches("AnyClass", "[DEBUG] Application started", "app.exe"));
@@ -1129,6 +2551,10 @@ mod tests {
             action: WindowAction::Float,
             width: None,
             height: None,
+            column_tag: None,
+            always_on_top: false,
+            scratchpad: None,
+            stack_with: None,
         };
 
         assert!(rule.matches("AnyClass", "Error Dialog", "app.exe"));
@@ -1145,6 +2571,10 @@ mod tests {
             action: WindowAction::Float,
             width: None,
             height: None,
+            column_tag: None,
+            always_on_top: false,
+            scratchpad: None,
+            stack_with: None,
         };
 
         assert!(rule.matches("AnyClass", "Error Dialog", "app.exe"));
@@ -1162,6 +2592,10 @@ mod tests {
             action: WindowAction::Tile,
             width: None,
             height: None,
+            column_tag: None,
+            always_on_top: false,
+            scratchpad: None,
+            stack_with: None,
         };
 
         assert!(rule.matches("MyClass", "Any Title", "any.exe"));
@@ -1179,6 +2613,10 @@ mod tests {
             action: WindowAction::Float,
             width: None,
             height: None,
+            column_tag: None,
+            always_on_top: false,
+            scratchpad: None,
+            stack_with: None,
         };
 
         assert!(rule.matches("AnyClass", "App Settings", "any.exe"));
@@ -1196,6 +2634,10 @@ mod tests {
             action: WindowAction::Tile,
             width: None,
             height: None,
+            column_tag: None,
+            always_on_top: false,
+            scratchpad: None,
+            stack_with: None,
         };
 
         assert!(rule.matches("AnyClass", "Any Title", "notepad.exe"));
@@ -1213,6 +2655,10 @@ mod tests {
             action: WindowAction::Float,
             width: None,
             height: None,
+            column_tag: None,
+            always_on_top: false,
+            scratchpad: None,
+            stack_with: None,
         };
 
         // Should return false because regex is invalid
@@ -1229,6 +2675,10 @@ mod tests {
             action: WindowAction::Float,
             width: None,
             height: None,
+            column_tag: None,
+            always_on_top: false,
+            scratchpad: None,
+            stack_with: None,
         };
 
         assert!(rule.matches("", "Title", "app.exe")); // Empty class matches .*
@@ -1304,6 +2754,32 @@ mod tests {
         assert!(warnings.iter().any(|w| w.field == "layout.default_column_width"));
     }
 
+    #[test]
+    fn test_validate_preset_widths() {
+        let mut config: Config = toml::from_str("[layout]\npreset_widths = [1.0, 0.5, 1.5, 0.25, 0.5]").unwrap();
+        let warnings = config.validate();
+        assert_eq!(config.layout.preset_widths, vec![0.25, 0.5, 1.0]);
+        assert!(warnings.iter().any(|w| w.field == "layout.preset_widths"));
+
+        config.layout.preset_widths = vec![0.0];
+        config.validate();
+        assert_eq!(config.layout.preset_widths, default_preset_widths());
+        assert!(Config::default().validate().is_empty());
+    }
+
+    #[test]
+    fn test_validate_shrink_to_fit_threshold_clamped() {
+        let mut config = Config::default();
+        config.layout.shrink_to_fit_threshold = 0.8;
+        let warnings = config.validate();
+        assert_eq!(config.layout.shrink_to_fit_threshold, MAX_SHRINK_TO_FIT_THRESHOLD);
+        assert!(warnings.iter().any(|w| w.field == "layout.shrink_to_fit_threshold"));
+
+        config.layout.shrink_to_fit_threshold = -0.1;
+        config.validate();
+        assert_eq!(config.layout.shrink_to_fit_threshold, 0.0);
+    }
+
     #[test]
     fn test_validate_focus_delay_below_min_clamped() {
         let mut config = Config::default();
@@ -1314,6 +2790,33 @@ mod tests {
         assert!(warnings.iter().any(|w| w.field == "behavior.focus_follows_mouse_delay_ms"));
     }
 
+    #[test]
+    fn test_validate_active_border_width() {
+        let mut config = Config::default();
+        assert_eq!(config.appearance.active_border_width, 3);
+
+        config.appearance.active_border_width = 0;
+        let warnings = config.validate();
+        assert_eq!(config.appearance.active_border_width, 1);
+        assert!(warnings.iter().any(|w| w.field == "appearance.active_border_width"));
+
+        config.appearance.active_border_width = 100;
+        config.validate();
+        assert_eq!(config.appearance.active_border_width, MAX_ACTIVE_BORDER_WIDTH);
+    }
+
+    #[test]
+    fn test_validate_wheel_scroll_step() {
+        let mut config: Config = toml::from_str("[behavior]\nwheel_scroll_on_titlebar = true\n").unwrap();
+        assert!(config.behavior.wheel_scroll_on_titlebar);
+        assert_eq!(config.behavior.wheel_scroll_step, 100);
+
+        config.behavior.wheel_scroll_step = 0;
+        let warnings = config.validate();
+        assert_eq!(config.behavior.wheel_scroll_step, 100);
+        assert!(warnings.iter().any(|w| w.field == "behavior.wheel_scroll_step"));
+    }
+
     #[test]
     fn test_validate_snap_duration_below_min_clamped() {
         let mut config = Config::default();
@@ -1324,6 +2827,204 @@ mod tests {
         assert!(warnings.iter().any(|w| w.field == "snap_hints.duration_ms"));
     }
 
+    #[test]
+    fn test_validate_window_animation_above_max_clamped() {
+        let mut config = Config::default();
+        assert_eq!(config.appearance.window_animation_ms, 150);
+        config.appearance.window_animation_ms = 5000;
+        let warnings = config.validate();
+        assert_eq!(config.appearance.window_animation_ms, 1000);
+        assert!(warnings.iter().any(|w| w.field == "appearance.window_animation_ms"));
+    }
+
+    #[test]
+    fn test_column_background_config() {
+        let config = Config::default();
+        assert!(!config.appearance.column_backgrounds);
+        assert_eq!(config.appearance.column_background_opacity, 96);
+
+        let config: Config = toml::from_str(
+            "[appearance]\ncolumn_backgrounds = true\ncolumn_background_color = \"101820\"\ncolumn_background_opacity = 160",
+        )
+        .unwrap();
+        assert!(config.appearance.column_backgrounds);
+        assert_eq!(config.appearance.column_background_color, "101820");
+        assert_eq!(config.appearance.column_background_opacity, 160);
+    }
+
+    #[test]
+    fn test_reserved_space() {
+        let mut config: Config = toml::from_str(
+            r#"
+[monitors.DISPLAY2]
+reserved_space = { top = 32, left = -5 }
+"#,
+        )
+        .unwrap();
+        let warnings = config.validate();
+        assert!(warnings.iter().any(|w| w.field == "monitors.DISPLAY2.reserved_space.left"));
+
+        let space = config.reserved_space_for(r"\\.\DISPLAY2");
+        assert_eq!(space, ReservedSpace { top: 32, ..Default::default() });
+        assert_eq!(space.apply(Rect::new(1920, 0, 1920, 1040)), Rect::new(1920, 32, 1920, 1008));
+        assert_eq!(config.reserved_space_for("DISPLAY1"), ReservedSpace::default());
+
+        // Reservations leaving too little room are ignored on that axis
+        let space = ReservedSpace { top: 900, left: 100, ..Default::default() };
+        assert_eq!(space.apply(Rect::new(0, 0, 1920, 1040)), Rect::new(100, 0, 1820, 1040));
+    }
+
+    #[test]
+    fn test_ignore_processes_parsing() {
+        assert!(Config::default().ignore_processes.is_empty());
+        let config: Config =
+            toml::from_str(r#"ignore_processes = ["*.tmp.exe", "EpicGamesLauncher.exe"]"#).unwrap();
+        assert_eq!(config.ignore_processes, vec!["*.tmp.exe", "EpicGamesLauncher.exe"]);
+    }
+
+    #[test]
+    fn test_management_mode_parsing() {
+        assert_eq!(Config::default().management_mode, ManagementMode::All);
+
+        let config: Config = toml::from_str(r#"management_mode = "whitelist""#).unwrap();
+        assert_eq!(config.management_mode, ManagementMode::Whitelist);
+        assert_eq!(config.management_mode.default_action(), WindowAction::Ignore);
+
+        let config: Config = toml::from_str(r#"management_mode = "blacklist""#).unwrap();
+        assert_eq!(config.management_mode, ManagementMode::All);
+        assert_eq!(config.management_mode.default_action(), WindowAction::Tile);
+    }
+
+    #[test]
+    fn test_validate_whitelist_without_rules_warns() {
+        let mut config: Config = toml::from_str(
+            r##"
+            management_mode = "whitelist"
+
+            [[window_rules]]
+            match_class = "#32770"
+            action = "ignore"
+            "##,
+        )
+        .unwrap();
+        let warnings = config.validate();
+        assert!(warnings.iter().any(|w| w.field == "management_mode"));
+
+        config.window_rules[0].action = WindowAction::Tile;
+        let warnings = config.validate();
+        assert!(!warnings.iter().any(|w| w.field == "management_mode"));
+    }
+
+    #[test]
+    fn test_hooks_parsing() {
+        let config: Config = toml::from_str(
+            r#"
+            [[hooks]]
+            event = "window_created"
+            command = "powershell"
+            args = ["-File", "on_window.ps1"]
+
+            [[hooks]]
+            event = "workspace_scrolled"
+            command = "notify.exe"
+            min_interval_ms = 1000
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.hooks.len(), 2);
+        assert_eq!(config.hooks[0].event, HookEventKind::WindowCreated);
+        assert_eq!(config.hooks[0].args, vec!["-File", "on_window.ps1"]);
+        assert_eq!(config.hooks[0].min_interval_ms, 250);
+        assert_eq!(config.hooks[1].event, HookEventKind::WorkspaceScrolled);
+        assert_eq!(config.hooks[1].min_interval_ms, 1000);
+
+        let result: Result<Config, _> = toml::from_str(
+            r#"
+            [[hooks]]
+            event = "window_exploded"
+            command = "x.exe"
+            "#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_validate_drops_hooks_without_command() {
+        let mut config: Config = toml::from_str(
+            r#"
+            [[hooks]]
+            event = "focus_changed"
+            command = "  "
+
+            [[hooks]]
+            event = "focus_changed"
+            command = "log.exe"
+            "#,
+        )
+        .unwrap();
+        let warnings = config.validate();
+        assert!(warnings.iter().any(|w| w.field == "hooks"));
+        assert_eq!(config.hooks.len(), 1);
+        assert_eq!(config.hooks[0].command, "log.exe");
+    }
+
+    #[test]
+    fn test_projects_parsing() {
+        let config: Config = toml::from_str(
+            r#"
+            [[projects]]
+            name = "web"
+            path = "C:\\src\\web"
+
+            [[projects.launch]]
+            command = "wt.exe"
+            args = ["-d", "."]
+
+            [[projects.widths]]
+            match_executable = "Code.exe"
+            fraction = 0.667
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.projects.len(), 1);
+        let project = &config.projects[0];
+        assert_eq!(project.path, "C:\\src\\web");
+        assert_eq!(project.launch[0].command, "wt.exe");
+        assert_eq!(project.launch[0].args, vec!["-d", "."]);
+        assert_eq!(project.widths[0].match_executable, "Code.exe");
+        assert!((project.widths[0].fraction - 0.667).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn test_validate_projects() {
+        let mut config: Config = toml::from_str(
+            r#"
+            [[projects]]
+            name = "web"
+            path = "C:/src/web"
+
+            [[projects.widths]]
+            match_executable = "Code.exe"
+            fraction = 3.0
+
+            [[projects]]
+            name = "Web"
+            path = "C:/src/other"
+
+            [[projects]]
+            name = "nowhere"
+            path = ""
+            "#,
+        )
+        .unwrap();
+        let warnings = config.validate();
+        assert!(warnings.iter().any(|w| w.field == "projects.Web"));
+        assert!(warnings.iter().any(|w| w.field == "projects.nowhere"));
+        assert!(warnings.iter().any(|w| w.field == "projects.web.widths"));
+        assert_eq!(config.projects.len(), 1);
+        assert_eq!(config.projects[0].widths[0].fraction, 1.0);
+    }
+
     #[test]
     fn test_validate_valid_config_no_warnings() {
         let mut config = Config::default();
@@ -1346,6 +3047,10 @@ mod tests {
                     action: WindowAction::Float,
                     width: Some(1024),
                     height: Some(768),
+                    column_tag: None,
+                    always_on_top: false,
+                    scratchpad: None,
+                    stack_with: None,
                 },
                 WindowRule {
                     match_class: None,
@@ -1354,6 +3059,10 @@ mod tests {
                     action: WindowAction::Tile,
                     width: None,
                     height: None,
+                    column_tag: None,
+                    always_on_top: false,
+                    scratchpad: None,
+                    stack_with: None,
                 },
             ],
             ..Default::default()
@@ -1384,6 +3093,10 @@ mod tests {
                     action: WindowAction::Float,
                     width: None,
                     height: None,
+                    column_tag: None,
+                    always_on_top: false,
+                    scratchpad: None,
+                    stack_with: None,
                 },
                 WindowRule {
                     match_class: Some("ValidClass".to_string()),
@@ -1392,6 +3105,10 @@ mod tests {
                     action: WindowAction::Tile,
                     width: None,
                     height: None,
+                    column_tag: None,
+                    always_on_top: false,
+                    scratchpad: None,
+                    stack_with: None,
                 },
             ],
             ..Default::default()
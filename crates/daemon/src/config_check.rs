@@ -0,0 +1,516 @@
+//! Config file diagnostics.
+//!
+//! Loading the config is lenient: unknown keys are ignored, invalid hotkeys
+//! and window rules are skipped and out-of-range values are clamped, with at
+//! most a log line to show for it. [`check`] reports all of these up front,
+//! with the line they are on, for `openniri-cli check-config`.
+
+use crate::config::Config;
+use openniri_ipc::{ConfigDiagnostic, DiagnosticSeverity};
+use serde::de::{Deserialize, Deserializer, MapAccess, SeqAccess, Visitor};
+use std::collections::HashMap;
+use std::fmt;
+use toml::Spanned;
+
+/// Known keys of each config table.
+///
+/// Elements of an array of tables use the array's path. `*` in a path
+/// stands for any name; a `*` key accepts any key (hotkeys, CLI aliases).
+/// Tables without an entry aren't checked.
+const SCHEMA: &[(&str, &[&str])] = &[
+    (
+        "",
+        &[
+            "layout", "appearance", "behavior", "hotkeys", "management_mode", "ignore_processes", "window_rules", "gestures",
+            "snap_hints", "monitors", "monitor", "hooks", "projects", "ipc", "aliases",
+        ],
+    ),
+    (
+        "layout",
+        &[
+            "gap", "outer_gap", "default_column_width", "min_column_width", "max_column_width", "centering_mode",
+            "collapsed_column_width", "shrink_to_fit_threshold", "auto_balance", "wrap_navigation",
+            "new_window_position", "preset_widths",
+        ],
+    ),
+    (
+        "appearance",
+        &[
+            "use_cloaking", "hide_strategy", "use_deferred_positioning", "active_border", "active_border_color",
+            "active_border_width", "window_animation_ms", "column_backgrounds", "column_background_color",
+            "column_background_opacity",
+        ],
+    ),
+    (
+        "behavior",
+        &[
+            "focus_new_windows", "track_focus_changes", "log_level", "focus_follows_mouse",
+            "focus_follows_mouse_delay_ms", "focus_follows_mouse_modifier", "focus_follows_mouse_strict",
+            "focus_steal_policy", "watch_config", "wheel_scroll_on_titlebar", "wheel_scroll_step",
+            "track_mouse_drags", "virtual_desktops", "desktop_workspaces",
+        ],
+    ),
+    ("hotkeys", &["*"]),
+    ("hotkeys.*", &["command", "backend"]),
+    ("aliases", &["*"]),
+    (
+        "window_rules",
+        &[
+            "match_class", "match_title", "match_executable", "action", "width", "height", "column_tag",
+            "always_on_top", "scratchpad", "stack_with",
+        ],
+    ),
+    (
+        "gestures",
+        &["enabled", "swipe_left", "swipe_right", "swipe_up", "swipe_down", "kinetic_scroll", "kinetic_friction"],
+    ),
+    ("snap_hints", &["enabled", "duration_ms", "opacity"]),
+    ("monitors", &["*"]),
+    ("monitors.*", &["layout_mode", "orientation", "pause_when", "reserved_space"]),
+    ("monitors.*.reserved_space", &["top", "bottom", "left", "right"]),
+    ("monitors.*.pause_when", &["between", "fullscreen_executable"]),
+    (
+        "monitor",
+        &[
+            "match_name", "match_resolution", "gap", "outer_gap", "default_column_width", "centering_mode",
+            "collapsed_column_width", "shrink_to_fit_threshold",
+        ],
+    ),
+    ("hooks", &["event", "command", "args", "min_interval_ms"]),
+    ("projects", &["name", "path", "launch", "widths"]),
+    ("projects.launch", &["command", "args"]),
+    ("projects.widths", &["match_executable", "fraction"]),
+    ("ipc", &["restrict_to_user", "allowed_group"]),
+];
+
+/// Prefix of the keys toml uses internally to represent datetimes.
+const TOML_PRIVATE_PREFIX: &str = "$__toml_private";
+
+/// A parsed TOML document that remembers where each key was written.
+#[derive(Debug)]
+enum Node {
+    Table(Vec<(Spanned<String>, Node)>),
+    Array(Vec<Spanned<Node>>),
+    Value,
+}
+
+impl<'de> Deserialize<'de> for Node {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct NodeVisitor;
+
+        impl<'de> Visitor<'de> for NodeVisitor {
+            type Value = Node;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a TOML value")
+            }
+
+            fn visit_bool<E>(self, _: bool) -> Result<Node, E> {
+                Ok(Node::Value)
+            }
+
+            fn visit_i64<E>(self, _: i64) -> Result<Node, E> {
+                Ok(Node::Value)
+            }
+
+            fn visit_u64<E>(self, _: u64) -> Result<Node, E> {
+                Ok(Node::Value)
+            }
+
+            fn visit_f64<E>(self, _: f64) -> Result<Node, E> {
+                Ok(Node::Value)
+            }
+
+            fn visit_str<E>(self, _: &str) -> Result<Node, E> {
+                Ok(Node::Value)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Node, A::Error> {
+                let mut items = Vec::new();
+                while let Some(item) = seq.next_element()? {
+                    items.push(item);
+                }
+                Ok(Node::Array(items))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Node, A::Error> {
+                let mut entries = Vec::new();
+                while let Some(key) = map.next_key::<Spanned<String>>()? {
+                    let value = map.next_value()?;
+                    entries.push((key, value));
+                }
+                Ok(Node::Table(entries))
+            }
+        }
+
+        deserializer.deserialize_any(NodeVisitor)
+    }
+}
+
+/// Collects diagnostics with line numbers for one file.
+struct Checker<'a> {
+    contents: &'a str,
+    schema: HashMap<&'static str, &'static [&'static str]>,
+    diagnostics: Vec<ConfigDiagnostic>,
+}
+
+impl Checker<'_> {
+    fn push(&mut self, severity: DiagnosticSeverity, field: String, message: String, offset: Option<usize>) {
+        let line = offset.map(|offset| line_of(self.contents, offset));
+        self.diagnostics.push(ConfigDiagnostic { severity, field, message, line });
+    }
+
+    /// Report keys of `table` missing from the schema of `schema_path`,
+    /// recursing into known subtables.
+    fn check_keys(&mut self, table: &[(Spanned<String>, Node)], schema_path: &str, field_path: &str) {
+        let Some(&known) = self.schema.get(schema_path) else {
+            return;
+        };
+        for (key, value) in table {
+            let name = key.get_ref();
+            if name.starts_with(TOML_PRIVATE_PREFIX) {
+                continue;
+            }
+            let field = if field_path.is_empty() { name.clone() } else { format!("{}.{}", field_path, name) };
+            let child_schema = if known.contains(&"*") {
+                format!("{}.*", schema_path)
+            } else if known.contains(&name.as_str()) {
+                if schema_path.is_empty() { name.clone() } else { format!("{}.{}", schema_path, name) }
+            } else {
+                let message = match closest_key(name, known) {
+                    Some(suggestion) => format!("unknown key `{}` is ignored; did you mean `{}`?", name, suggestion),
+                    None => format!("unknown key `{}` is ignored", name),
+                };
+                self.push(DiagnosticSeverity::Warning, field, message, Some(key.span().start));
+                continue;
+            };
+            match value {
+                Node::Table(entries) => self.check_keys(entries, &child_schema, &field),
+                Node::Array(items) => {
+                    for (i, item) in items.iter().enumerate() {
+                        if let Node::Table(entries) = item.get_ref() {
+                            self.check_keys(entries, &child_schema, &format!("{}[{}]", field, i));
+                        }
+                    }
+                }
+                Node::Value => {}
+            }
+        }
+    }
+}
+
+/// 1-based line of byte `offset` in `contents`.
+fn line_of(contents: &str, offset: usize) -> usize {
+    contents[..offset.min(contents.len())].matches('\n').count() + 1
+}
+
+/// Known key within edit distance 2 of `key`, for typo suggestions.
+fn closest_key<'a>(key: &str, known: &[&'a str]) -> Option<&'a str> {
+    known
+        .iter()
+        .filter(|candidate| **candidate != "*")
+        .map(|candidate| (edit_distance(key, candidate), *candidate))
+        .filter(|(distance, _)| *distance <= 2)
+        .min_by_key(|(distance, _)| *distance)
+        .map(|(_, candidate)| candidate)
+}
+
+/// Levenshtein distance between two strings.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != *cb);
+            current.push(substitution.min(previous[j + 1] + 1).min(current[j] + 1));
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+/// Byte offset of the key a diagnostic `field` names, e.g. `layout.gap`,
+/// `window_rules[1].column_tag` or `monitors.\\.\DISPLAY1.reserved_space.top`.
+///
+/// Falls back to the deepest part of the path that exists, so a field naming
+/// e.g. a project by name still points at `projects`.
+fn locate(root: &[(Spanned<String>, Node)], field: &str) -> Option<usize> {
+    // Validation warnings about two settings name both, separated by " / "
+    let field = field.split(" / ").next().unwrap_or(field);
+    let mut table = root;
+    let mut rest = field;
+    let mut found = None;
+    while !rest.is_empty() {
+        // Prefer the longest key, as monitor names may contain dots
+        let candidate = table
+            .iter()
+            .filter_map(|(key, value)| {
+                let name = key.get_ref().as_str();
+                let after = rest.strip_prefix(name)?;
+                let (index, after) = match after.strip_prefix('[') {
+                    Some(indexed) => {
+                        let (index, after) = indexed.split_once(']')?;
+                        (Some(index.parse::<usize>().ok()?), after)
+                    }
+                    None => (None, after),
+                };
+                (after.is_empty() || after.starts_with('.')).then_some((name.len(), key, value, index, after))
+            })
+            .max_by_key(|(len, ..)| *len);
+        let Some((_, key, value, index, after)) = candidate else {
+            break;
+        };
+        found = Some(key.span().start);
+        rest = after.strip_prefix('.').unwrap_or(after);
+        let value = match (value, index) {
+            (Node::Array(items), Some(i)) => items.get(i).map(|item| {
+                found = Some(item.span().start);
+                item.get_ref()
+            }),
+            (value, None) => Some(value),
+            _ => None,
+        };
+        match value {
+            Some(Node::Table(entries)) => table = entries,
+            _ => break,
+        }
+    }
+    found
+}
+
+/// Check config file `contents`.
+///
+/// `normalize_hotkey` turns a hotkey string into a canonical form, so two
+/// spellings of the same key combination can be reported as a conflict, or
+/// returns `None` for an invalid hotkey. Diagnostics are sorted by line.
+pub fn check(contents: &str, normalize_hotkey: impl Fn(&str) -> Option<String>) -> Vec<ConfigDiagnostic> {
+    let mut checker = Checker {
+        contents,
+        schema: SCHEMA.iter().copied().collect(),
+        diagnostics: Vec::new(),
+    };
+
+    let root = match toml::from_str::<Node>(contents) {
+        Ok(Node::Table(root)) => root,
+        Ok(_) => Vec::new(),
+        Err(e) => {
+            let offset = e.span().map(|span| span.start);
+            checker.push(DiagnosticSeverity::Error, String::new(), e.message().to_string(), offset);
+            return checker.diagnostics;
+        }
+    };
+    checker.check_keys(&root, "", "");
+
+    let mut config = match toml::from_str::<Config>(contents) {
+        Ok(config) => config,
+        Err(e) => {
+            let offset = e.span().map(|span| span.start);
+            let message = format!("{}; the config file will not load", e.message());
+            checker.push(DiagnosticSeverity::Error, String::new(), message, offset);
+            return sorted(checker.diagnostics);
+        }
+    };
+
+    // Hotkeys: invalid strings and different spellings of the same keys
+    let mut bindings: Vec<_> = config.hotkeys.bindings.iter().collect();
+    bindings.sort_by_key(|(key, _)| *key);
+    let mut seen: HashMap<String, &str> = HashMap::new();
+    for (key, _) in bindings {
+        let field = format!("hotkeys.{}", key);
+        let offset = locate(&root, &field);
+        match normalize_hotkey(key) {
+            None => checker.push(
+                DiagnosticSeverity::Error,
+                field,
+                format!("invalid hotkey `{}`; binding will be ignored", key),
+                offset,
+            ),
+            Some(canonical) => {
+                if let Some(first) = seen.get(&canonical) {
+                    let message = format!("`{}` is the same key combination as `{}`; only one takes effect", key, first);
+                    checker.push(DiagnosticSeverity::Warning, field, message, offset);
+                } else {
+                    seen.insert(canonical, key);
+                }
+            }
+        }
+    }
+
+    // Window rules: invalid patterns, and rules an earlier one always wins over
+    for (i, rule) in config.window_rules.iter().enumerate() {
+        let field = format!("window_rules[{}]", i);
+        for (name, pattern) in [("match_class", &rule.match_class), ("match_title", &rule.match_title)] {
+            if let Some(Err(e)) = pattern.as_deref().map(regex::Regex::new) {
+                let field = format!("{}.{}", field, name);
+                let offset = locate(&root, &field);
+                let message = format!("invalid regex: {}; rule will be ignored", e);
+                checker.push(DiagnosticSeverity::Error, field, message, offset);
+            }
+        }
+        let criteria = (&rule.match_class, &rule.match_title, rule.match_executable.as_deref().map(str::to_lowercase));
+        if criteria == (&None, &None, None) {
+            let offset = locate(&root, &field);
+            let message = "rule has no match_class, match_title or match_executable and never matches".to_string();
+            checker.push(DiagnosticSeverity::Warning, field, message, offset);
+            continue;
+        }
+        let shadowed_by = config.window_rules[..i].iter().position(|earlier| {
+            (&earlier.match_class, &earlier.match_title, earlier.match_executable.as_deref().map(str::to_lowercase))
+                == criteria
+        });
+        if let Some(earlier) = shadowed_by {
+            let offset = locate(&root, &field);
+            let message = format!("matches the same windows as window_rules[{}], which takes precedence", earlier);
+            checker.push(DiagnosticSeverity::Warning, field, message, offset);
+        }
+    }
+
+    // Out-of-range and inconsistent values the daemon would adjust
+    for warning in config.validate() {
+        let offset = locate(&root, &warning.field);
+        checker.push(DiagnosticSeverity::Warning, warning.field, warning.message, offset);
+    }
+
+    sorted(checker.diagnostics)
+}
+
+/// Diagnostics in file order, those without a line last.
+fn sorted(mut diagnostics: Vec<ConfigDiagnostic>) -> Vec<ConfigDiagnostic> {
+    diagnostics.sort_by_key(|d| d.line.unwrap_or(usize::MAX));
+    diagnostics
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_str(contents: &str) -> Vec<ConfigDiagnostic> {
+        check(contents, |key| {
+            let mut parts: Vec<String> = key.split('+').map(str::to_lowercase).collect();
+            parts.sort();
+            (!key.contains("Bogus")).then(|| parts.join("+"))
+        })
+    }
+
+    #[test]
+    fn test_default_config_is_clean() {
+        let contents = toml::to_string(&Config::default()).unwrap();
+        assert_eq!(check_str(&contents), vec![]);
+    }
+
+    #[test]
+    fn test_unknown_keys_with_lines() {
+        let diagnostics = check_str(
+            r#"[layout]
+gap = 10
+gapp = 5
+
+[[window_rules]]
+match_class = "Notepad"
+action = "float"
+widht = 400
+
+[monitors.'\\.\DISPLAY1'.reserved_space]
+topp = 32
+
+[aliases]
+ff = "focus right"
+"#,
+        );
+        let unknown: Vec<_> = diagnostics.iter().map(|d| (d.field.as_str(), d.line)).collect();
+        assert_eq!(
+            unknown,
+            vec![
+                ("layout.gapp", Some(3)),
+                ("window_rules[0].widht", Some(8)),
+                ("monitors.\\\\.\\DISPLAY1.reserved_space.topp", Some(11)),
+            ]
+        );
+        assert!(diagnostics[0].message.contains("did you mean `gap`?"));
+        assert!(diagnostics.iter().all(|d| d.severity == DiagnosticSeverity::Warning));
+    }
+
+    #[test]
+    fn test_syntax_and_type_errors() {
+        let diagnostics = check_str("[layout]\ngap = \n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].severity, DiagnosticSeverity::Error);
+        assert_eq!(diagnostics[0].line, Some(2));
+
+        let diagnostics = check_str("[layout]\ngap = \"wide\"\n");
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].line, Some(2));
+        assert!(diagnostics[0].message.contains("will not load"));
+    }
+
+    #[test]
+    fn test_hotkey_diagnostics() {
+        let diagnostics = check_str(
+            r#"[hotkeys]
+"Win+Bogus" = "focus_left"
+"Win+Shift+H" = "move_column_left"
+"Shift+Win+H" = "focus_left"
+"Win+K" = "jump_around"
+"Win+Left" = { command = "focus_left", backnd = "hook" }
+"#,
+        );
+        let found: Vec<_> = diagnostics.iter().map(|d| (d.field.as_str(), d.severity, d.line)).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("hotkeys.Win+Bogus", DiagnosticSeverity::Error, Some(2)),
+                ("hotkeys.Win+Shift+H", DiagnosticSeverity::Warning, Some(3)),
+                ("hotkeys.Win+K", DiagnosticSeverity::Warning, Some(5)),
+                ("hotkeys.Win+Left.backnd", DiagnosticSeverity::Warning, Some(6)),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_window_rule_conflicts() {
+        let diagnostics = check_str(
+            r#"[[window_rules]]
+match_executable = "Slack.exe"
+action = "float"
+
+[[window_rules]]
+match_executable = "slack.exe"
+action = "tile"
+
+[[window_rules]]
+match_title = "("
+action = "float"
+
+[[window_rules]]
+action = "ignore"
+"#,
+        );
+        let found: Vec<_> = diagnostics.iter().map(|d| (d.field.as_str(), d.severity, d.line)).collect();
+        assert_eq!(
+            found,
+            vec![
+                ("window_rules[1]", DiagnosticSeverity::Warning, Some(5)),
+                ("window_rules[2].match_title", DiagnosticSeverity::Error, Some(10)),
+                ("window_rules[3]", DiagnosticSeverity::Warning, Some(13)),
+            ]
+        );
+        assert!(diagnostics[0].message.contains("window_rules[0]"));
+    }
+
+    #[test]
+    fn test_out_of_range_values_are_located() {
+        let diagnostics = check_str("[layout]\ngap = -5\n\n[monitors.DISPLAY2.reserved_space]\nleft = -1\n");
+        let found: Vec<_> = diagnostics.iter().map(|d| (d.field.as_str(), d.line)).collect();
+        assert_eq!(found, vec![("layout.gap", Some(2)), ("monitors.DISPLAY2.reserved_space.left", Some(5))]);
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("gap", "gap"), 0);
+        assert_eq!(edit_distance("gapp", "gap"), 1);
+        assert_eq!(edit_distance("widht", "width"), 2);
+        assert_eq!(closest_key("outer_gapp", &["gap", "outer_gap"]), Some("outer_gap"));
+        assert_eq!(closest_key("xyz", &["gap"]), None);
+    }
+}
@@ -0,0 +1,278 @@
+//! Crash dumps.
+//!
+//! The event loop records every event it handles and, after events that
+//! changed the daemon state and at most once per [`STATE_INTERVAL`], a
+//! serialized copy of that state. When the daemon panics, the panic hook
+//! writes the panic message, the last state and the last [`MAX_EVENTS`]
+//! events to a JSON file in the `crashes` data directory.
+//!
+//! The state can't be serialized in the hook itself, since the panicking code
+//! usually holds the state lock; the recorded events show what happened since
+//! the snapshot was taken.
+//!
+//! Only a panic on the event loop's thread ends the daemon. For those, the
+//! hook also starts a crash notification on a thread of its own, and
+//! [`MainLoopGuard`] keeps the process alive until it has been shown.
+
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use std::thread::{JoinHandle, ThreadId};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+/// Events kept for a crash dump.
+pub const MAX_EVENTS: usize = 200;
+
+/// Minimum time between state snapshots.
+pub const STATE_INTERVAL: Duration = Duration::from_secs(1);
+
+/// An event handled by the daemon.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RecordedEvent {
+    /// When the event (or its last repeat) was handled, in ms since the Unix epoch.
+    pub at_ms: u64,
+    /// The event, as debug text.
+    pub event: String,
+    /// How many times the same event followed directly (animation ticks, drags).
+    pub repeats: u32,
+}
+
+/// Contents of a crash dump file.
+#[derive(Debug, Serialize)]
+pub struct CrashDump<'a> {
+    /// Daemon version.
+    pub version: &'static str,
+    /// When the panic happened, in ms since the Unix epoch.
+    pub crashed_at_ms: u64,
+    /// Panic message with its source location.
+    pub panic: &'a str,
+    /// When the state was captured, in ms since the Unix epoch.
+    pub state_at_ms: Option<u64>,
+    /// Last captured daemon state.
+    pub state: Option<&'a serde_json::Value>,
+    /// Handled events, oldest first.
+    pub events: &'a VecDeque<RecordedEvent>,
+}
+
+/// Recent events and the last state snapshot.
+#[derive(Debug)]
+pub struct CrashRecorder {
+    events: VecDeque<RecordedEvent>,
+    state: Option<(u64, serde_json::Value)>,
+    last_state: Option<Instant>,
+}
+
+impl CrashRecorder {
+    /// Create an empty recorder.
+    pub const fn new() -> Self {
+        Self { events: VecDeque::new(), state: None, last_state: None }
+    }
+
+    /// Record a handled event, folding direct repeats into one entry.
+    pub fn record_event(&mut self, event: String, at_ms: u64) {
+        if let Some(last) = self.events.back_mut().filter(|last| last.event == event) {
+            last.at_ms = at_ms;
+            last.repeats += 1;
+            return;
+        }
+        if self.events.len() == MAX_EVENTS {
+            self.events.pop_front();
+        }
+        self.events.push_back(RecordedEvent { at_ms, event, repeats: 0 });
+    }
+
+    /// Whether a new state snapshot is due.
+    pub fn state_due(&self, now: Instant) -> bool {
+        self.last_state.is_none_or(|last| now.duration_since(last) >= STATE_INTERVAL)
+    }
+
+    /// Keep a state snapshot, replacing the previous one.
+    pub fn record_state(&mut self, state: serde_json::Value, now: Instant, at_ms: u64) {
+        self.state = Some((at_ms, state));
+        self.last_state = Some(now);
+    }
+
+    /// Crash dump for a panic.
+    pub fn dump<'a>(&'a self, panic: &'a str, crashed_at_ms: u64) -> CrashDump<'a> {
+        CrashDump {
+            version: env!("CARGO_PKG_VERSION"),
+            crashed_at_ms,
+            panic,
+            state_at_ms: self.state.as_ref().map(|(at_ms, _)| *at_ms),
+            state: self.state.as_ref().map(|(_, state)| state),
+            events: &self.events,
+        }
+    }
+}
+
+impl Default for CrashRecorder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The daemon's recorder.
+static RECORDER: Mutex<CrashRecorder> = Mutex::new(CrashRecorder::new());
+
+/// Milliseconds since the Unix epoch.
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or_default()
+}
+
+/// Record a handled event.
+pub fn record_event(event: String) {
+    let mut recorder = RECORDER.lock().unwrap_or_else(|e| e.into_inner());
+    recorder.record_event(event, now_ms());
+}
+
+/// Record a state snapshot from `state` if one is due. Returns whether it
+/// was recorded.
+pub fn record_state_if_due(state: impl FnOnce() -> serde_json::Value) -> bool {
+    let now = Instant::now();
+    let mut recorder = RECORDER.lock().unwrap_or_else(|e| e.into_inner());
+    if !recorder.state_due(now) {
+        return false;
+    }
+    recorder.record_state(state(), now, now_ms());
+    true
+}
+
+/// Thread running the event loop, set by [`MainLoopGuard::new`].
+static MAIN_LOOP_THREAD: OnceLock<ThreadId> = OnceLock::new();
+
+/// Crash notification started by [`notify_if_fatal`].
+static NOTICE: Mutex<Option<JoinHandle<()>>> = Mutex::new(None);
+
+/// Marks the current thread as the event loop's for as long as it lives.
+///
+/// Dropped while unwinding from a panic, it waits for the crash notification
+/// so the process doesn't exit while it is shown.
+pub struct MainLoopGuard(());
+
+impl MainLoopGuard {
+    /// Mark the current thread as the one running the event loop.
+    pub fn new() -> Self {
+        let _ = MAIN_LOOP_THREAD.set(std::thread::current().id());
+        Self(())
+    }
+}
+
+impl Drop for MainLoopGuard {
+    fn drop(&mut self) {
+        if !std::thread::panicking() {
+            return;
+        }
+        let notice = NOTICE.lock().unwrap_or_else(|e| e.into_inner()).take();
+        if let Some(notice) = notice {
+            let _ = notice.join();
+        }
+    }
+}
+
+/// Whether a panic on the current thread ends the daemon.
+pub fn is_fatal_panic() -> bool {
+    MAIN_LOOP_THREAD.get() == Some(&std::thread::current().id())
+}
+
+/// Run `notify` on a detached thread if a panic on the current thread ends
+/// the daemon, so the panic hook doesn't block while it runs.
+pub fn notify_if_fatal(notify: impl FnOnce() + Send + 'static) {
+    if !is_fatal_panic() {
+        return;
+    }
+    if let Ok(notice) = std::thread::Builder::new().name("crash-notice".to_string()).spawn(notify) {
+        *NOTICE.lock().unwrap_or_else(|e| e.into_inner()) = Some(notice);
+    }
+}
+
+/// Directory crash dumps are written to.
+pub fn dump_dir() -> PathBuf {
+    directories::ProjectDirs::from("", "", "openniri")
+        .map(|dirs| dirs.data_dir().join("crashes"))
+        .unwrap_or_else(|| PathBuf::from("crashes"))
+}
+
+/// Write a crash dump for a panic to `dir` and return its path.
+///
+/// Called from the panic hook, so it doesn't wait for the recorder: if the
+/// panic happened while recording, the dump has no state or events.
+pub fn write_dump(dir: &Path, panic: &str) -> std::io::Result<PathBuf> {
+    let crashed_at_ms = now_ms();
+    let json = match RECORDER.try_lock() {
+        Ok(recorder) => serde_json::to_string_pretty(&recorder.dump(panic, crashed_at_ms)),
+        Err(_) => serde_json::to_string_pretty(&CrashRecorder::new().dump(panic, crashed_at_ms)),
+    }?;
+    std::fs::create_dir_all(dir)?;
+    let path = dir.join(format!("crash-{}.json", crashed_at_ms));
+    std::fs::write(&path, json)?;
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_fold_repeats_and_keep_the_latest() {
+        let mut recorder = CrashRecorder::new();
+        recorder.record_event("AnimationTick".to_string(), 1);
+        recorder.record_event("AnimationTick".to_string(), 2);
+        recorder.record_event("Hotkey".to_string(), 3);
+        assert_eq!(recorder.events.len(), 2);
+        assert_eq!(recorder.events[0], RecordedEvent { at_ms: 2, event: "AnimationTick".to_string(), repeats: 1 });
+
+        for i in 0..MAX_EVENTS as u64 {
+            recorder.record_event(format!("Event {}", i), 10 + i);
+        }
+        assert_eq!(recorder.events.len(), MAX_EVENTS);
+        assert_eq!(recorder.events[0].event, "Event 0");
+        assert_eq!(recorder.events.back().unwrap().event, format!("Event {}", MAX_EVENTS - 1));
+    }
+
+    #[test]
+    fn test_state_snapshots_are_throttled() {
+        let mut recorder = CrashRecorder::new();
+        let start = Instant::now();
+        assert!(recorder.state_due(start));
+        recorder.record_state(serde_json::json!({ "paused": false }), start, 100);
+        assert!(!recorder.state_due(start + STATE_INTERVAL / 2));
+        assert!(recorder.state_due(start + STATE_INTERVAL));
+    }
+
+    #[test]
+    fn test_dump_contents() {
+        let mut recorder = CrashRecorder::new();
+        recorder.record_event("Hotkey".to_string(), 5);
+        recorder.record_state(serde_json::json!({ "paused": true }), Instant::now(), 4);
+
+        let dump = serde_json::to_value(recorder.dump("boom at main.rs:1:1", 6)).unwrap();
+        assert_eq!(dump["panic"], "boom at main.rs:1:1");
+        assert_eq!(dump["crashed_at_ms"], 6);
+        assert_eq!(dump["state_at_ms"], 4);
+        assert_eq!(dump["state"]["paused"], true);
+        assert_eq!(dump["events"][0]["event"], "Hotkey");
+    }
+
+    #[test]
+    fn test_only_the_main_loop_thread_is_fatal() {
+        let guard = std::thread::spawn(|| {
+            let _guard = MainLoopGuard::new();
+            is_fatal_panic()
+        });
+        assert!(guard.join().unwrap());
+        assert!(!is_fatal_panic());
+    }
+
+    #[test]
+    fn test_write_dump_creates_file() {
+        let dir = std::env::temp_dir().join(format!("openniri-crash-test-{}", std::process::id()));
+        let path = write_dump(&dir, "boom").unwrap();
+        let json: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(json["panic"], "boom");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
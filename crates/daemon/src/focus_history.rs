@@ -0,0 +1,97 @@
+//! Most-recently-used order of focused windows.
+//!
+//! Backs `FocusPrevious`, which jumps back to the window focused before the
+//! current one on any monitor or workspace, like Alt-Tab within the layout.
+
+use serde::Serialize;
+use std::collections::VecDeque;
+
+/// Number of windows remembered; older entries are forgotten.
+const HISTORY_LEN: usize = 64;
+
+/// Focused windows, most recent first, without duplicates.
+#[derive(Debug, Default, Serialize)]
+pub struct FocusHistory {
+    entries: VecDeque<u64>,
+}
+
+impl FocusHistory {
+    /// Record that `window_id` gained focus.
+    pub fn record(&mut self, window_id: u64) {
+        if self.entries.front() == Some(&window_id) {
+            return;
+        }
+        self.remove(window_id);
+        self.entries.push_front(window_id);
+        self.entries.truncate(HISTORY_LEN);
+    }
+
+    /// Forget a window, e.g. because it was destroyed.
+    pub fn remove(&mut self, window_id: u64) {
+        self.entries.retain(|&id| id != window_id);
+    }
+
+    /// The window focused before the most recent one.
+    ///
+    /// Entries for which `is_alive` returns false are windows that went away
+    /// without us noticing; they are skipped and forgotten.
+    pub fn previous(&mut self, is_alive: impl Fn(u64) -> bool) -> Option<u64> {
+        self.entries.retain(|&id| is_alive(id));
+        self.entries.get(1).copied()
+    }
+
+    /// Windows in most-recently-focused order.
+    #[cfg(test)]
+    pub fn iter(&self) -> impl Iterator<Item = u64> + '_ {
+        self.entries.iter().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_moves_window_to_front() {
+        let mut history = FocusHistory::default();
+        for id in [1, 2, 3, 2] {
+            history.record(id);
+        }
+        assert_eq!(history.iter().collect::<Vec<_>>(), vec![2, 3, 1]);
+    }
+
+    #[test]
+    fn test_previous_toggles_between_last_two() {
+        let mut history = FocusHistory::default();
+        history.record(1);
+        assert_eq!(history.previous(|_| true), None);
+        history.record(2);
+        assert_eq!(history.previous(|_| true), Some(1));
+        // Focusing the previous window makes the other one previous
+        history.record(1);
+        assert_eq!(history.previous(|_| true), Some(2));
+    }
+
+    #[test]
+    fn test_previous_skips_dead_windows() {
+        let mut history = FocusHistory::default();
+        for id in [1, 2, 3] {
+            history.record(id);
+        }
+        assert_eq!(history.previous(|id| id != 2), Some(1));
+        assert_eq!(history.iter().collect::<Vec<_>>(), vec![3, 1]);
+
+        history.remove(1);
+        assert_eq!(history.previous(|_| true), None);
+    }
+
+    #[test]
+    fn test_history_is_bounded() {
+        let mut history = FocusHistory::default();
+        for id in 0..(HISTORY_LEN as u64 + 10) {
+            history.record(id);
+        }
+        assert_eq!(history.iter().count(), HISTORY_LEN);
+        assert_eq!(history.iter().last(), Some(10));
+    }
+}
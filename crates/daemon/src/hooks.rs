@@ -0,0 +1,371 @@
+//! External command hooks.
+//!
+//! Hooks run a user-configured program when a daemon event occurs, allowing
+//! lightweight automation without writing a pipe client:
+//!
+//! ```toml
+//! [[hooks]]
+//! event = "focus_changed"
+//! command = "powershell"
+//! args = ["-NoProfile", "-File", "C:\\scripts\\on_focus.ps1"]
+//! ```
+//!
+//! The event is written to the program's stdin as a JSON object. Its name is
+//! also passed in `OPENNIRI_EVENT` and the JSON in `OPENNIRI_EVENT_DATA`.
+//! Hooks run in the background, are killed if they outlive [`HOOK_TIMEOUT`],
+//! and are rate-limited per hook by `min_interval_ms`.
+
+use crate::config::{HookConfig, HookEventKind};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::process::{ExitStatus, Stdio};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tokio::io::AsyncWriteExt;
+use tracing::{debug, warn};
+
+/// Maximum run time of a hook before it is killed.
+pub const HOOK_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Environment variable holding the event name.
+const EVENT_ENV_VAR: &str = "OPENNIRI_EVENT";
+
+/// Environment variable holding the event JSON.
+const EVENT_DATA_ENV_VAR: &str = "OPENNIRI_EVENT_DATA";
+
+/// Scroll changes smaller than this (in pixels) don't fire hooks.
+const SCROLL_EPSILON: f64 = 0.5;
+
+/// Errors from running a hook command.
+#[derive(Debug, Error)]
+pub enum HookError {
+    #[error("failed to start '{command}': {source}")]
+    Spawn {
+        command: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("'{command}' failed: {source}")]
+    Io {
+        command: String,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("'{command}' exited with {status}")]
+    Failed { command: String, status: ExitStatus },
+    #[error("'{0}' did not finish within {secs}s and was killed", secs = HOOK_TIMEOUT.as_secs())]
+    Timeout(String),
+}
+
+/// Event data passed to hook commands.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum HookEvent {
+    /// A new window was added to the layout.
+    WindowCreated {
+        window_id: u64,
+        monitor_id: i64,
+        title: String,
+        class_name: String,
+        executable: String,
+        floating: bool,
+    },
+    /// The focused window changed.
+    FocusChanged {
+        window_id: Option<u64>,
+        monitor_id: i64,
+    },
+    /// A workspace's scroll position changed.
+    WorkspaceScrolled { monitor_id: i64, scroll_offset: f64 },
+    /// A window took focus without user input and was marked urgent.
+    WindowUrgent {
+        window_id: u64,
+        monitor_id: i64,
+        title: String,
+        executable: String,
+    },
+}
+
+impl HookEvent {
+    /// The kind of event, for matching against configured hooks.
+    pub fn kind(&self) -> HookEventKind {
+        match self {
+            HookEvent::WindowCreated { .. } => HookEventKind::WindowCreated,
+            HookEvent::FocusChanged { .. } => HookEventKind::FocusChanged,
+            HookEvent::WorkspaceScrolled { .. } => HookEventKind::WorkspaceScrolled,
+            HookEvent::WindowUrgent { .. } => HookEventKind::WindowUrgent,
+        }
+    }
+}
+
+/// Runs configured hooks and tracks the state needed to detect changes.
+#[derive(Debug, Default)]
+pub struct HookRunner {
+    /// Configured hooks.
+    hooks: Vec<HookConfig>,
+    /// Last run time of each hook, parallel to `hooks`.
+    last_run: Vec<Option<Instant>>,
+    /// Focused monitor and window at the last observation.
+    last_focus: Option<(i64, Option<u64>)>,
+    /// Scroll offset of each monitor's workspace at the last observation.
+    last_scroll: HashMap<i64, f64>,
+}
+
+impl HookRunner {
+    /// Create a runner for the given hooks.
+    pub fn new(hooks: Vec<HookConfig>) -> Self {
+        let mut runner = Self::default();
+        runner.set_hooks(hooks);
+        runner
+    }
+
+    /// Replace the configured hooks (e.g. after a config reload).
+    pub fn set_hooks(&mut self, hooks: Vec<HookConfig>) {
+        self.last_run = vec![None; hooks.len()];
+        self.hooks = hooks;
+        self.last_focus = None;
+        self.last_scroll.clear();
+    }
+
+    /// Whether any hook is configured for the event kind.
+    pub fn wants(&self, kind: HookEventKind) -> bool {
+        self.hooks.iter().any(|h| h.event == kind)
+    }
+
+    /// Hooks to run for an event at `now`, recording their run time.
+    ///
+    /// Hooks that ran less than `min_interval_ms` ago are skipped.
+    pub fn due(&mut self, kind: HookEventKind, now: Instant) -> Vec<HookConfig> {
+        let mut due = Vec::new();
+        for (hook, last_run) in self.hooks.iter().zip(self.last_run.iter_mut()) {
+            if hook.event != kind {
+                continue;
+            }
+            let interval = Duration::from_millis(hook.min_interval_ms);
+            if last_run.is_some_and(|last| now.duration_since(last) < interval) {
+                debug!("Hook '{}' rate-limited for {}", hook.command, kind.as_str());
+                continue;
+            }
+            *last_run = Some(now);
+            due.push(hook.clone());
+        }
+        due
+    }
+
+    /// Record the current focus; returns an event if it changed.
+    ///
+    /// The first observation only establishes the baseline.
+    pub fn observe_focus(&mut self, monitor_id: i64, window_id: Option<u64>) -> Option<HookEvent> {
+        let focus = (monitor_id, window_id);
+        let previous = self.last_focus.replace(focus);
+        match previous {
+            Some(previous) if previous != focus => Some(HookEvent::FocusChanged { window_id, monitor_id }),
+            _ => None,
+        }
+    }
+
+    /// Record a workspace's scroll offset; returns an event if it changed.
+    ///
+    /// The first observation of a monitor only establishes the baseline.
+    pub fn observe_scroll(&mut self, monitor_id: i64, scroll_offset: f64) -> Option<HookEvent> {
+        let previous = self.last_scroll.insert(monitor_id, scroll_offset);
+        match previous {
+            Some(previous) if (previous - scroll_offset).abs() >= SCROLL_EPSILON => {
+                Some(HookEvent::WorkspaceScrolled { monitor_id, scroll_offset })
+            }
+            _ => None,
+        }
+    }
+
+    /// Run the hooks due for an event in the background.
+    pub fn dispatch(&mut self, event: &HookEvent) {
+        let hooks = self.due(event.kind(), Instant::now());
+        if hooks.is_empty() {
+            return;
+        }
+
+        let payload = match serde_json::to_string(event) {
+            Ok(payload) => payload,
+            Err(e) => {
+                warn!("Failed to serialize {} hook event: {}", event.kind().as_str(), e);
+                return;
+            }
+        };
+        let runtime = match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => runtime,
+            Err(_) => {
+                warn!("No async runtime available; skipping {} hooks", event.kind().as_str());
+                return;
+            }
+        };
+
+        for hook in hooks {
+            let payload = payload.clone();
+            runtime.spawn(async move {
+                match run_hook(&hook, &payload).await {
+                    Ok(()) => debug!("Hook '{}' for {} completed", hook.command, hook.event.as_str()),
+                    Err(e) => warn!("Hook for {} failed: {}", hook.event.as_str(), e),
+                }
+            });
+        }
+    }
+}
+
+/// Run one hook command to completion, feeding it the event JSON.
+async fn run_hook(hook: &HookConfig, payload: &str) -> Result<(), HookError> {
+    let mut command = tokio::process::Command::new(&hook.command);
+    command
+        .args(&hook.args)
+        .env(EVENT_ENV_VAR, hook.event.as_str())
+        .env(EVENT_DATA_ENV_VAR, payload)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true);
+    #[cfg(windows)]
+    {
+        // Don't flash a console window for console programs
+        const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    let mut child = command.spawn().map_err(|source| HookError::Spawn {
+        command: hook.command.clone(),
+        source,
+    })?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // Hooks that ignore stdin may exit before reading it; that's fine
+        let _ = stdin.write_all(payload.as_bytes()).await;
+    }
+
+    match tokio::time::timeout(HOOK_TIMEOUT, child.wait()).await {
+        Ok(Ok(status)) if status.success() => Ok(()),
+        Ok(Ok(status)) => Err(HookError::Failed {
+            command: hook.command.clone(),
+            status,
+        }),
+        Ok(Err(source)) => Err(HookError::Io {
+            command: hook.command.clone(),
+            source,
+        }),
+        // Dropping the child kills it
+        Err(_) => Err(HookError::Timeout(hook.command.clone())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hook(event: HookEventKind, command: &str, min_interval_ms: u64) -> HookConfig {
+        HookConfig {
+            event,
+            command: command.to_string(),
+            args: Vec::new(),
+            min_interval_ms,
+        }
+    }
+
+    #[test]
+    fn test_due_filters_by_event_kind() {
+        let mut runner = HookRunner::new(vec![
+            hook(HookEventKind::WindowCreated, "a.exe", 0),
+            hook(HookEventKind::FocusChanged, "b.exe", 0),
+            hook(HookEventKind::FocusChanged, "c.exe", 0),
+        ]);
+        assert!(runner.wants(HookEventKind::FocusChanged));
+        assert!(!runner.wants(HookEventKind::WorkspaceScrolled));
+
+        let due = runner.due(HookEventKind::FocusChanged, Instant::now());
+        let commands: Vec<_> = due.iter().map(|h| h.command.as_str()).collect();
+        assert_eq!(commands, vec!["b.exe", "c.exe"]);
+    }
+
+    #[test]
+    fn test_due_rate_limits_each_hook() {
+        let mut runner = HookRunner::new(vec![
+            hook(HookEventKind::WorkspaceScrolled, "slow.exe", 1000),
+            hook(HookEventKind::WorkspaceScrolled, "fast.exe", 100),
+        ]);
+        let start = Instant::now();
+        assert_eq!(runner.due(HookEventKind::WorkspaceScrolled, start).len(), 2);
+
+        let due = runner.due(HookEventKind::WorkspaceScrolled, start + Duration::from_millis(200));
+        assert_eq!(due.len(), 1);
+        assert_eq!(due[0].command, "fast.exe");
+
+        assert!(runner.due(HookEventKind::WorkspaceScrolled, start + Duration::from_millis(250)).is_empty());
+        assert_eq!(runner.due(HookEventKind::WorkspaceScrolled, start + Duration::from_secs(1)).len(), 2);
+    }
+
+    #[test]
+    fn test_observe_focus_reports_changes() {
+        let mut runner = HookRunner::default();
+        assert_eq!(runner.observe_focus(1, Some(100)), None);
+        assert_eq!(runner.observe_focus(1, Some(100)), None);
+        assert_eq!(
+            runner.observe_focus(1, Some(200)),
+            Some(HookEvent::FocusChanged { window_id: Some(200), monitor_id: 1 })
+        );
+        assert_eq!(
+            runner.observe_focus(2, None),
+            Some(HookEvent::FocusChanged { window_id: None, monitor_id: 2 })
+        );
+    }
+
+    #[test]
+    fn test_observe_scroll_ignores_subpixel_changes() {
+        let mut runner = HookRunner::default();
+        assert_eq!(runner.observe_scroll(1, 0.0), None);
+        assert_eq!(runner.observe_scroll(1, 0.2), None);
+        assert_eq!(
+            runner.observe_scroll(1, 400.0),
+            Some(HookEvent::WorkspaceScrolled { monitor_id: 1, scroll_offset: 400.0 })
+        );
+        // Other monitors have their own baseline
+        assert_eq!(runner.observe_scroll(2, 400.0), None);
+    }
+
+    #[test]
+    fn test_set_hooks_resets_tracking() {
+        let mut runner = HookRunner::new(vec![hook(HookEventKind::FocusChanged, "a.exe", 1000)]);
+        runner.observe_focus(1, Some(100));
+        runner.due(HookEventKind::FocusChanged, Instant::now());
+
+        runner.set_hooks(vec![hook(HookEventKind::FocusChanged, "a.exe", 1000)]);
+        assert_eq!(runner.observe_focus(1, Some(200)), None);
+        assert_eq!(runner.due(HookEventKind::FocusChanged, Instant::now()).len(), 1);
+    }
+
+    #[test]
+    fn test_hook_event_json() {
+        let event = HookEvent::WindowCreated {
+            window_id: 42,
+            monitor_id: 1,
+            title: "Untitled - Notepad".to_string(),
+            class_name: "Notepad".to_string(),
+            executable: "notepad.exe".to_string(),
+            floating: false,
+        };
+        let json: serde_json::Value = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["event"], "window_created");
+        assert_eq!(json["window_id"], 42);
+        assert_eq!(json["executable"], "notepad.exe");
+        assert_eq!(event.kind().as_str(), "window_created");
+
+        let json = serde_json::to_value(HookEvent::FocusChanged { window_id: None, monitor_id: 2 }).unwrap();
+        assert_eq!(json["event"], "focus_changed");
+        assert!(json["window_id"].is_null());
+
+        let event = HookEvent::WindowUrgent {
+            window_id: 7,
+            monitor_id: 1,
+            title: "Setup".to_string(),
+            executable: "setup.exe".to_string(),
+        };
+        let json = serde_json::to_value(&event).unwrap();
+        assert_eq!(json["event"], "window_urgent");
+        assert_eq!(event.kind(), HookEventKind::WindowUrgent);
+    }
+}
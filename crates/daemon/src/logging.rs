@@ -0,0 +1,233 @@
+//! Daemon logging: console output plus a size-rotated log file under the
+//! local app data directory, with a level that can change at runtime.
+
+use std::collections::VecDeque;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::{fmt, reload, Registry};
+
+/// Name of the current log file for a daemon whose files carry `suffix`
+/// (see [`openniri_ipc::pipe_file_suffix`]).
+fn log_file_name(suffix: &str) -> String {
+    format!("openniri{}.log", suffix)
+}
+
+/// Size in bytes at which the log file is rotated.
+const MAX_LOG_FILE_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Rotated files kept next to the current one; `<log file>.1` is the newest.
+const ROTATED_LOG_FILES: usize = 3;
+
+/// Most lines a single `QueryLogs` request returns.
+pub const MAX_QUERY_LOG_LINES: usize = 1000;
+
+/// Parse a log level name (case-insensitive).
+pub fn parse_level(level: &str) -> Option<LevelFilter> {
+    match level.to_ascii_lowercase().as_str() {
+        "trace" => Some(LevelFilter::TRACE),
+        "debug" => Some(LevelFilter::DEBUG),
+        "info" => Some(LevelFilter::INFO),
+        "warn" => Some(LevelFilter::WARN),
+        "error" => Some(LevelFilter::ERROR),
+        "off" => Some(LevelFilter::OFF),
+        _ => None,
+    }
+}
+
+/// Directory holding the log files (`%LOCALAPPDATA%\openniri\data\logs`).
+pub fn log_dir() -> Option<PathBuf> {
+    directories::ProjectDirs::from("", "", "openniri").map(|dirs| dirs.data_local_dir().join("logs"))
+}
+
+/// Path of a rotated log file, `n` rotations old.
+fn rotated_path(dir: &Path, name: &str, n: usize) -> PathBuf {
+    dir.join(format!("{}.{}", name, n))
+}
+
+/// Log file that rotates once it grows past a size limit.
+struct RotatingFile {
+    dir: PathBuf,
+    name: String,
+    file: File,
+    size: u64,
+    max_bytes: u64,
+}
+
+impl RotatingFile {
+    /// Open (or create) the log file `name` in `dir`, appending to it.
+    fn open(dir: &Path, name: &str, max_bytes: u64) -> io::Result<Self> {
+        fs::create_dir_all(dir)?;
+        let file = OpenOptions::new().create(true).append(true).open(dir.join(name))?;
+        let size = file.metadata()?.len();
+        Ok(Self { dir: dir.to_path_buf(), name: name.to_string(), file, size, max_bytes })
+    }
+
+    /// Shift rotated files up by one, dropping the oldest, and start a new file.
+    fn rotate(&mut self) -> io::Result<()> {
+        for n in (1..ROTATED_LOG_FILES).rev() {
+            let from = rotated_path(&self.dir, &self.name, n);
+            if from.exists() {
+                fs::rename(&from, rotated_path(&self.dir, &self.name, n + 1))?;
+            }
+        }
+        let current = self.dir.join(&self.name);
+        fs::rename(&current, rotated_path(&self.dir, &self.name, 1))?;
+        self.file = OpenOptions::new().create(true).append(true).open(&current)?;
+        self.size = 0;
+        Ok(())
+    }
+}
+
+impl Write for RotatingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.size > 0 && self.size + buf.len() as u64 > self.max_bytes {
+            // Keep logging to the old file if rotating fails (e.g. it's open elsewhere)
+            if let Err(e) = self.rotate() {
+                eprintln!("[openniri] Failed to rotate log file: {}", e);
+                self.size = 0;
+            }
+        }
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
+
+/// Handle to the installed logger.
+pub struct Logger {
+    level: reload::Handle<LevelFilter, Registry>,
+    file: Option<PathBuf>,
+    /// Level given with `--log-level`; config reloads don't override it.
+    pinned: bool,
+}
+
+impl Logger {
+    /// Path of the current log file, if logging to a file.
+    pub fn file_path(&self) -> Option<&Path> {
+        self.file.as_deref()
+    }
+
+    /// Change the log level.
+    pub fn set_level(&self, level: &str) -> Result<(), String> {
+        let filter = parse_level(level).ok_or_else(|| {
+            format!("Invalid log level '{}' (expected trace, debug, info, warn, error or off)", level)
+        })?;
+        self.level.modify(|current| *current = filter).map_err(|e| e.to_string())
+    }
+
+    /// Apply the level from a (re)loaded config, unless `--log-level` pinned it.
+    pub fn apply_config_level(&self, level: &str) {
+        if !self.pinned {
+            if let Err(e) = self.set_level(level) {
+                tracing::warn!("{}", e);
+            }
+        }
+    }
+}
+
+/// Install the global logger, writing to the console and to the log file.
+///
+/// `pinned` marks a level given on the command line. The log file name
+/// carries `suffix`, like the pipe the daemon serves, so daemons running side
+/// by side don't write to the same file. Failing to open the log file leaves
+/// console logging only.
+pub fn init(level: LevelFilter, pinned: bool, suffix: &str) -> anyhow::Result<Logger> {
+    let (filter, handle) = reload::Layer::new(level);
+    let name = log_file_name(suffix);
+    let file = match log_dir().map(|dir| RotatingFile::open(&dir, &name, MAX_LOG_FILE_BYTES)) {
+        Some(Ok(file)) => Some(file),
+        Some(Err(e)) => {
+            eprintln!("[openniri] Failed to open log file: {}. Logging to the console only.", e);
+            None
+        }
+        None => None,
+    };
+    let path = file.as_ref().map(|f| f.dir.join(&f.name));
+    let file_layer = file.map(|f| fmt::layer().with_ansi(false).with_writer(Mutex::new(f)));
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(fmt::layer())
+        .with(file_layer)
+        .try_init()?;
+    Ok(Logger { level: handle, file: path, pinned })
+}
+
+/// Last `lines` lines of a log file, oldest first.
+pub fn tail(path: &Path, lines: usize) -> io::Result<Vec<String>> {
+    let lines = lines.min(MAX_QUERY_LOG_LINES);
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut last = VecDeque::with_capacity(lines);
+    let mut buf = Vec::new();
+    while reader.read_until(b'\n', &mut buf)? > 0 {
+        if lines > 0 {
+            if last.len() == lines {
+                last.pop_front();
+            }
+            let line = String::from_utf8_lossy(&buf);
+            last.push_back(line.trim_end_matches(['\r', '\n']).to_string());
+        }
+        buf.clear();
+    }
+    Ok(last.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("openniri-logging-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_parse_level() {
+        assert_eq!(parse_level("debug"), Some(LevelFilter::DEBUG));
+        assert_eq!(parse_level("WARN"), Some(LevelFilter::WARN));
+        assert_eq!(parse_level("off"), Some(LevelFilter::OFF));
+        assert_eq!(parse_level("verbose"), None);
+    }
+
+    #[test]
+    fn test_rotating_file_keeps_a_bounded_history() {
+        let dir = temp_dir("rotate");
+        let name = log_file_name("-session-1");
+        let mut file = RotatingFile::open(&dir, &name, 10).unwrap();
+        for line in ["first\n", "second\n", "third\n", "fourth\n", "fifth\n"] {
+            file.write_all(line.as_bytes()).unwrap();
+        }
+
+        let read = |path: PathBuf| fs::read_to_string(path).unwrap();
+        assert_eq!(read(dir.join("openniri-session-1.log")), "fifth\n");
+        assert_eq!(read(rotated_path(&dir, &name, 1)), "fourth\n");
+        assert_eq!(read(rotated_path(&dir, &name, 3)), "second\n");
+        assert!(!rotated_path(&dir, &name, 4).exists(), "the oldest file is dropped");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_tail_returns_last_lines() {
+        let dir = temp_dir("tail");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(log_file_name(""));
+        fs::write(&path, "one\r\ntwo\nthree\nfour").unwrap();
+
+        assert_eq!(tail(&path, 2).unwrap(), vec!["three", "four"]);
+        assert_eq!(tail(&path, 10).unwrap(), vec!["one", "two", "three", "four"]);
+        assert!(tail(&path, 0).unwrap().is_empty());
+        assert!(tail(&dir.join("missing.log"), 5).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,254 @@
+//! Event pipeline health monitoring.
+//!
+//! Tracks how far the daemon's event queues fall behind, so a hook storm shows
+//! up in the logs and in `QueryMetrics` instead of as unexplained lag, and how
+//! evenly animation frames arrive (`QueryPerfStats`).
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Fill level, as a fraction of capacity, at which a queue counts as backed up.
+const BACKLOG_HIGH_FRACTION: f64 = 0.5;
+
+/// How long a queue must stay backed up before it is reported.
+const SUSTAINED_BACKLOG: Duration = Duration::from_secs(2);
+
+/// Minimum time between two warnings for the same queue.
+const WARNING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Current depth and high-water mark of a bounded queue.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QueueGauge {
+    /// Items currently queued.
+    pub backlog: usize,
+    /// Highest backlog recorded.
+    pub peak: usize,
+    /// Maximum number of queued items.
+    pub capacity: usize,
+}
+
+impl QueueGauge {
+    /// Record the current queue depth.
+    pub fn record(&mut self, backlog: usize, capacity: usize) {
+        self.backlog = backlog;
+        self.peak = self.peak.max(backlog);
+        self.capacity = capacity;
+    }
+}
+
+/// A queue health problem worth logging.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BacklogWarning {
+    /// The queue has stayed at least half full for `duration`.
+    Sustained {
+        backlog: usize,
+        capacity: usize,
+        duration: Duration,
+    },
+    /// Events were dropped since the last warning.
+    Dropped { count: u64 },
+}
+
+impl fmt::Display for BacklogWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BacklogWarning::Sustained { backlog, capacity, duration } => write!(
+                f,
+                "{}/{} events queued for {}s; daemon is not keeping up",
+                backlog,
+                capacity,
+                duration.as_secs()
+            ),
+            BacklogWarning::Dropped { count } => {
+                write!(f, "{} low-priority events dropped because the queue was full", count)
+            }
+        }
+    }
+}
+
+/// Detects sustained backlog and new drops on one queue, rate-limiting warnings.
+#[derive(Debug, Default)]
+pub struct BacklogMonitor {
+    /// When the queue last crossed the high-water fraction, if still above it.
+    high_since: Option<Instant>,
+    /// When the last warning was raised.
+    last_warning: Option<Instant>,
+    /// Drop count already included in a warning.
+    reported_dropped: u64,
+}
+
+impl BacklogMonitor {
+    /// Create a monitor with no history.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Feed the latest queue counters; returns a warning when one is due.
+    ///
+    /// `dropped` is the cumulative number of dropped events for the queue.
+    pub fn observe(
+        &mut self,
+        backlog: usize,
+        capacity: usize,
+        dropped: u64,
+        now: Instant,
+    ) -> Option<BacklogWarning> {
+        let high = capacity > 0 && backlog as f64 >= capacity as f64 * BACKLOG_HIGH_FRACTION;
+        if !high {
+            self.high_since = None;
+        } else if self.high_since.is_none() {
+            self.high_since = Some(now);
+        }
+
+        if self
+            .last_warning
+            .is_some_and(|last| now.duration_since(last) < WARNING_INTERVAL)
+        {
+            return None;
+        }
+
+        let warning = if dropped > self.reported_dropped {
+            let count = dropped - self.reported_dropped;
+            self.reported_dropped = dropped;
+            Some(BacklogWarning::Dropped { count })
+        } else {
+            self.high_since
+                .map(|since| now.duration_since(since))
+                .filter(|duration| *duration >= SUSTAINED_BACKLOG)
+                .map(|duration| BacklogWarning::Sustained { backlog, capacity, duration })
+        };
+
+        if warning.is_some() {
+            self.last_warning = Some(now);
+        }
+        warning
+    }
+}
+
+/// Longest frame time fed to animations, so a stall doesn't make them jump.
+pub const MAX_ANIMATION_STEP: Duration = Duration::from_millis(100);
+
+/// Frame pacing of the animation scheduler since startup.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FrameStats {
+    /// Animation frames applied.
+    pub frames: u64,
+    /// Display refreshes that passed without an animation frame.
+    pub missed_frames: u64,
+    /// Whether the current or last animation was paced by DWM composition.
+    pub vblank_synced: bool,
+    /// Expected time between frames: the refresh period, or the fallback timer's.
+    pub frame_period: Duration,
+    /// Sum of recorded frame times.
+    total_frame_time: Duration,
+}
+
+impl FrameStats {
+    /// Start an animation run, paced by the display refresh if DWM reports
+    /// one and by a `fallback` timer period otherwise.
+    pub fn start_run(&mut self, refresh_period: Option<Duration>, fallback: Duration) {
+        self.vblank_synced = refresh_period.is_some();
+        self.frame_period = refresh_period.unwrap_or(fallback);
+    }
+
+    /// Record the time since the previous frame of the run.
+    ///
+    /// A frame arriving more than half a period late counts the refreshes
+    /// it skipped as missed.
+    pub fn record(&mut self, frame_time: Duration) {
+        self.frames += 1;
+        self.total_frame_time += frame_time;
+        let period = self.frame_period.as_secs_f64();
+        if period > 0.0 && frame_time.as_secs_f64() > period * 1.5 {
+            self.missed_frames += (frame_time.as_secs_f64() / period).round() as u64 - 1;
+        }
+    }
+
+    /// Average time between frames in milliseconds (0 before the first frame).
+    pub fn average_frame_ms(&self) -> f64 {
+        if self.frames == 0 {
+            return 0.0;
+        }
+        self.total_frame_time.as_secs_f64() * 1000.0 / self.frames as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_queue_gauge_tracks_peak() {
+        let mut gauge = QueueGauge::default();
+        gauge.record(5, 100);
+        gauge.record(2, 100);
+        assert_eq!(gauge, QueueGauge { backlog: 2, peak: 5, capacity: 100 });
+    }
+
+    #[test]
+    fn test_short_backlog_not_reported() {
+        let mut monitor = BacklogMonitor::new();
+        let start = Instant::now();
+        assert_eq!(monitor.observe(80, 100, 0, start), None);
+        assert_eq!(monitor.observe(80, 100, 0, start + Duration::from_secs(1)), None);
+        // Drained before the threshold: the timer restarts
+        assert_eq!(monitor.observe(10, 100, 0, start + Duration::from_millis(1500)), None);
+        assert_eq!(monitor.observe(80, 100, 0, start + Duration::from_millis(2500)), None);
+    }
+
+    #[test]
+    fn test_sustained_backlog_reported_once_per_interval() {
+        let mut monitor = BacklogMonitor::new();
+        let start = Instant::now();
+        monitor.observe(60, 100, 0, start);
+
+        let warning = monitor.observe(70, 100, 0, start + Duration::from_secs(3));
+        assert_eq!(
+            warning,
+            Some(BacklogWarning::Sustained { backlog: 70, capacity: 100, duration: Duration::from_secs(3) })
+        );
+        assert_eq!(monitor.observe(70, 100, 0, start + Duration::from_secs(10)), None);
+        assert!(monitor.observe(70, 100, 0, start + Duration::from_secs(34)).is_some());
+    }
+
+    #[test]
+    fn test_drops_reported_as_delta() {
+        let mut monitor = BacklogMonitor::new();
+        let start = Instant::now();
+        assert_eq!(monitor.observe(0, 100, 7, start), Some(BacklogWarning::Dropped { count: 7 }));
+        assert_eq!(monitor.observe(0, 100, 9, start + Duration::from_secs(1)), None);
+        assert_eq!(
+            monitor.observe(0, 100, 12, start + Duration::from_secs(31)),
+            Some(BacklogWarning::Dropped { count: 5 })
+        );
+    }
+
+    #[test]
+    fn test_frame_stats_count_missed_refreshes() {
+        let mut stats = FrameStats::default();
+        stats.start_run(Some(Duration::from_micros(16_667)), Duration::from_millis(16));
+        assert!(stats.vblank_synced);
+        stats.record(Duration::from_micros(16_500));
+        stats.record(Duration::from_micros(17_000));
+        assert_eq!(stats.missed_frames, 0);
+        // Two refreshes skipped
+        stats.record(Duration::from_micros(50_000));
+        assert_eq!(stats.frames, 3);
+        assert_eq!(stats.missed_frames, 2);
+        assert!((stats.average_frame_ms() - 27.833).abs() < 0.01);
+
+        stats.start_run(None, Duration::from_millis(16));
+        assert!(!stats.vblank_synced);
+        assert_eq!(stats.frame_period, Duration::from_millis(16));
+        assert_eq!(stats.frames, 3, "runs accumulate");
+        assert_eq!(FrameStats::default().average_frame_ms(), 0.0);
+    }
+
+    #[test]
+    fn test_zero_capacity_never_high() {
+        let mut monitor = BacklogMonitor::new();
+        let start = Instant::now();
+        monitor.observe(0, 0, 0, start);
+        assert_eq!(monitor.observe(0, 0, 0, start + Duration::from_secs(60)), None);
+    }
+}
@@ -0,0 +1,141 @@
+//! Named monitor profiles ("docked", "laptop-only").
+//!
+//! A profile records the column arrangement of every monitor together with
+//! a fingerprint of the monitor set it was saved on. When a display change
+//! produces a monitor set matching a saved profile, the daemon moves the
+//! open windows back into that profile's columns (matched like a restored
+//! arrangement, see [`crate::arrangement`]).
+
+use crate::arrangement::SavedColumn;
+use crate::config::normalize_device_name;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Identify a monitor set by device names and resolutions, independent of
+/// enumeration order (e.g. `DISPLAY1@2560x1440;DISPLAY2@1920x1080`).
+pub fn monitor_fingerprint<'a>(monitors: impl IntoIterator<Item = (&'a str, i32, i32)>) -> String {
+    let mut entries: Vec<String> = monitors
+        .into_iter()
+        .map(|(name, width, height)| format!("{}@{}x{}", normalize_device_name(name), width, height))
+        .collect();
+    entries.sort();
+    entries.join(";")
+}
+
+/// Columns saved for one monitor of a profile.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileMonitor {
+    /// Monitor device name.
+    pub device_name: String,
+    /// Columns with window fingerprints.
+    pub columns: Vec<SavedColumn>,
+    /// Focused window on the monitor when saved.
+    #[serde(default)]
+    pub focused_window: Option<u64>,
+}
+
+/// A named arrangement of windows across a monitor set.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MonitorProfile {
+    /// Profile name, e.g. "docked".
+    pub name: String,
+    /// [`monitor_fingerprint`] of the monitors the profile was saved on.
+    pub fingerprint: String,
+    /// Saved columns per monitor.
+    pub monitors: Vec<ProfileMonitor>,
+}
+
+/// Saved monitor profiles, oldest first.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProfileStore {
+    profiles: Vec<MonitorProfile>,
+}
+
+impl ProfileStore {
+    /// Load profiles from a JSON file. A missing file is an empty store.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(json) => Ok(serde_json::from_str(&json)?),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Write profiles to a JSON file, creating its directory.
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    /// Profile with this name (case-insensitive).
+    pub fn get(&self, name: &str) -> Option<&MonitorProfile> {
+        self.profiles.iter().find(|p| p.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Add a profile, replacing one with the same name.
+    ///
+    /// The profile becomes the newest, so it wins fingerprint lookups.
+    pub fn insert(&mut self, profile: MonitorProfile) {
+        self.profiles.retain(|p| !p.name.eq_ignore_ascii_case(&profile.name));
+        self.profiles.push(profile);
+    }
+
+    /// Newest profile saved on a monitor set with this fingerprint.
+    pub fn find_by_fingerprint(&self, fingerprint: &str) -> Option<&MonitorProfile> {
+        self.profiles.iter().rev().find(|p| p.fingerprint == fingerprint)
+    }
+
+    /// Profile names, oldest first.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.profiles.iter().map(|p| p.name.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(name: &str, fingerprint: &str) -> MonitorProfile {
+        MonitorProfile { name: name.to_string(), fingerprint: fingerprint.to_string(), monitors: Vec::new() }
+    }
+
+    #[test]
+    fn test_fingerprint_ignores_order_and_prefix() {
+        let a = monitor_fingerprint([(r"\\.\DISPLAY2", 1920, 1080), ("DISPLAY1", 2560, 1440)]);
+        let b = monitor_fingerprint([("display1", 2560, 1440), ("DISPLAY2", 1920, 1080)]);
+        assert_eq!(a, "DISPLAY1@2560x1440;DISPLAY2@1920x1080");
+        assert_eq!(a, b);
+        assert_ne!(a, monitor_fingerprint([("DISPLAY1", 2560, 1440)]));
+    }
+
+    #[test]
+    fn test_insert_replaces_and_newest_fingerprint_wins() {
+        let mut store = ProfileStore::default();
+        store.insert(profile("docked", "A;B"));
+        store.insert(profile("laptop-only", "A"));
+        store.insert(profile("presenting", "A;B"));
+        assert_eq!(store.find_by_fingerprint("A;B").unwrap().name, "presenting");
+
+        store.insert(profile("Docked", "A;B"));
+        assert_eq!(store.names().collect::<Vec<_>>(), vec!["laptop-only", "presenting", "Docked"]);
+        assert_eq!(store.find_by_fingerprint("A;B").unwrap().name, "Docked");
+        assert!(store.get("docked").is_some());
+        assert!(store.find_by_fingerprint("C").is_none());
+    }
+
+    #[test]
+    fn test_load_and_save_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("openniri-profiles-{}", std::process::id()));
+        let path = dir.join("monitor-profiles.json");
+        assert_eq!(ProfileStore::load(&path).unwrap(), ProfileStore::default());
+
+        let mut store = ProfileStore::default();
+        store.insert(profile("docked", "A;B"));
+        store.save(&path).unwrap();
+        assert_eq!(ProfileStore::load(&path).unwrap(), store);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
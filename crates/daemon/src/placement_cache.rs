@@ -0,0 +1,116 @@
+//! Placements last applied to each window.
+//!
+//! `apply_layout` runs for every animation frame and most commands, but
+//! usually only some windows change. Applications that repaint on every
+//! `SetWindowPos` flicker when moved in place, so only placements whose rect
+//! or visibility differ from the last applied one are passed on.
+
+use openniri_core_layout::{Rect, Visibility, WindowId, WindowPlacement};
+use std::collections::HashMap;
+
+/// How a window was last placed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Applied {
+    /// Shown at a rect.
+    Shown(Rect),
+    /// Hidden (cloaked, minimized or moved off-screen); where it would be
+    /// off-screen doesn't matter.
+    Hidden,
+}
+
+impl Applied {
+    fn of(placement: &WindowPlacement) -> Self {
+        match placement.visibility {
+            Visibility::Visible => Self::Shown(placement.rect),
+            Visibility::OffScreenLeft | Visibility::OffScreenRight => Self::Hidden,
+        }
+    }
+}
+
+/// Last applied placement per window.
+#[derive(Debug, Default)]
+pub struct PlacementCache {
+    applied: HashMap<WindowId, Applied>,
+}
+
+impl PlacementCache {
+    /// Placements that differ from the last applied ones, recording them as
+    /// applied.
+    ///
+    /// Windows missing from `placements` (paused monitors, parked or removed
+    /// windows, one being dragged) are forgotten, so they are placed again
+    /// once they come back.
+    pub fn changed(&mut self, placements: &[WindowPlacement]) -> Vec<WindowPlacement> {
+        let mut applied = HashMap::with_capacity(placements.len());
+        let mut changed = Vec::new();
+        for placement in placements {
+            let state = Applied::of(placement);
+            if self.applied.get(&placement.window_id) != Some(&state) {
+                changed.push(placement.clone());
+            }
+            applied.insert(placement.window_id, state);
+        }
+        self.applied = applied;
+        changed
+    }
+
+    /// Whether a window was last shown at `rect`.
+    pub fn is_shown_at(&self, window_id: WindowId, rect: Rect) -> bool {
+        self.applied.get(&window_id) == Some(&Applied::Shown(rect))
+    }
+
+    /// Forget a window, so its next placement is applied even if unchanged.
+    pub fn forget(&mut self, window_id: WindowId) {
+        self.applied.remove(&window_id);
+    }
+
+    /// Forget all windows, e.g. after they were uncloaked or moved by others.
+    pub fn clear(&mut self) {
+        self.applied.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn placement(window_id: WindowId, x: i32, visibility: Visibility) -> WindowPlacement {
+        WindowPlacement { window_id, rect: Rect::new(x, 0, 800, 1000), visibility, column_index: 0 }
+    }
+
+    fn ids(placements: &[WindowPlacement]) -> Vec<WindowId> {
+        placements.iter().map(|p| p.window_id).collect()
+    }
+
+    #[test]
+    fn test_only_changed_placements_are_applied() {
+        let mut cache = PlacementCache::default();
+        let first = [placement(1, 0, Visibility::Visible), placement(2, 2000, Visibility::OffScreenRight)];
+        assert_eq!(ids(&cache.changed(&first)), vec![1, 2]);
+        assert!(cache.changed(&first).is_empty());
+
+        // Moving a hidden window further off-screen changes nothing on screen
+        let second = [placement(1, 100, Visibility::Visible), placement(2, 2100, Visibility::OffScreenRight)];
+        assert_eq!(ids(&cache.changed(&second)), vec![1]);
+
+        let third = [placement(1, 100, Visibility::Visible), placement(2, 1000, Visibility::Visible)];
+        assert_eq!(ids(&cache.changed(&third)), vec![2]);
+        assert!(cache.is_shown_at(2, Rect::new(1000, 0, 800, 1000)));
+    }
+
+    #[test]
+    fn test_missing_and_forgotten_windows_are_placed_again() {
+        let mut cache = PlacementCache::default();
+        let both = [placement(1, 0, Visibility::Visible), placement(2, 900, Visibility::Visible)];
+        cache.changed(&both);
+
+        cache.changed(&both[..1]);
+        assert_eq!(ids(&cache.changed(&both)), vec![2]);
+
+        cache.forget(1);
+        assert_eq!(ids(&cache.changed(&both)), vec![1]);
+
+        cache.clear();
+        assert_eq!(ids(&cache.changed(&both)), vec![1, 2]);
+    }
+}
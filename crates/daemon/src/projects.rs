@@ -0,0 +1,267 @@
+//! Per-project layout profiles.
+//!
+//! A profile is applied when the focused window is working inside the
+//! project's directory, as detected from its process command line (editors
+//! and shells launched with a path) or its title (terminals showing the
+//! current directory):
+//!
+//! ```toml
+//! [[projects]]
+//! name = "openniri"
+//! path = "C:\\src\\OpenNiri-Windows"
+//!
+//! [[projects.launch]]
+//! command = "wt.exe"
+//! args = ["-d", "."]
+//!
+//! [[projects.widths]]
+//! match_executable = "Code.exe"
+//! fraction = 0.667
+//! ```
+//!
+//! Launch commands run in the project directory the first time the project
+//! is detected; widths are applied each time it becomes active.
+
+use crate::config::ProjectProfile;
+use std::collections::HashSet;
+
+/// Normalize a path for comparison: backslashes, lowercase, no trailing separator.
+pub fn normalize_path(path: &str) -> String {
+    let path = path.trim().trim_matches('"').replace('/', "\\").to_lowercase();
+    path.trim_end_matches('\\').to_string()
+}
+
+/// Whether a path looks absolute (`C:\...` or a `\\server\share` UNC path).
+fn is_absolute(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    (bytes.len() >= 3 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && (bytes[2] == b'\\' || bytes[2] == b'/'))
+        || path.starts_with("\\\\")
+}
+
+/// Split a Windows command line into arguments.
+///
+/// Handles double-quoted arguments; backslash escapes are not interpreted,
+/// which is sufficient for finding paths.
+pub fn split_command_line(command_line: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+    let mut has_arg = false;
+
+    for c in command_line.chars() {
+        match c {
+            '"' => {
+                in_quotes = !in_quotes;
+                has_arg = true;
+            }
+            c if c.is_whitespace() && !in_quotes => {
+                if has_arg {
+                    args.push(std::mem::take(&mut current));
+                    has_arg = false;
+                }
+            }
+            c => {
+                current.push(c);
+                has_arg = true;
+            }
+        }
+    }
+    if has_arg {
+        args.push(current);
+    }
+    args
+}
+
+/// Absolute paths mentioned in a command line, normalized.
+///
+/// The program itself is skipped; `--flag=PATH` arguments are included.
+pub fn path_candidates(command_line: &str) -> Vec<String> {
+    split_command_line(command_line)
+        .into_iter()
+        .skip(1)
+        .map(|arg| match arg.split_once('=') {
+            Some((_, value)) if arg.starts_with('-') => value.to_string(),
+            _ => arg,
+        })
+        .filter(|arg| is_absolute(arg))
+        .map(|arg| normalize_path(&arg))
+        .collect()
+}
+
+/// Whether a normalized path is `root` or inside it.
+fn path_within(path: &str, root: &str) -> bool {
+    path.strip_prefix(root)
+        .is_some_and(|rest| rest.is_empty() || rest.starts_with('\\'))
+}
+
+/// Whether a window title mentions `root` or a path inside it.
+fn title_mentions(title: &str, root: &str) -> bool {
+    let title = title.replace('/', "\\").to_lowercase();
+    title.match_indices(root).any(|(start, _)| {
+        title[start + root.len()..]
+            .chars()
+            .next()
+            .is_none_or(|c| c == '\\' || c == '"' || c == ')' || c.is_whitespace())
+    })
+}
+
+/// Find the project a window belongs to.
+///
+/// When several projects match (nested directories), the deepest one wins.
+pub fn find_project<'a>(
+    profiles: &'a [ProjectProfile],
+    command_line: Option<&str>,
+    title: &str,
+) -> Option<&'a ProjectProfile> {
+    let candidates = command_line.map(path_candidates).unwrap_or_default();
+
+    profiles
+        .iter()
+        .filter_map(|profile| {
+            let root = normalize_path(&profile.path);
+            if root.is_empty() {
+                return None;
+            }
+            let matches = candidates.iter().any(|path| path_within(path, &root))
+                || title_mentions(title, &root);
+            matches.then_some((root.len(), profile))
+        })
+        .max_by_key(|(depth, _)| *depth)
+        .map(|(_, profile)| profile)
+}
+
+/// Start a profile's programs in the project directory.
+///
+/// Returns the programs that failed to start; one bad entry doesn't stop the rest.
+pub fn launch(profile: &ProjectProfile) -> Vec<(String, std::io::Error)> {
+    profile
+        .launch
+        .iter()
+        .filter_map(|program| {
+            std::process::Command::new(&program.command)
+                .args(&program.args)
+                .current_dir(&profile.path)
+                .spawn()
+                .err()
+                .map(|e| (program.command.clone(), e))
+        })
+        .collect()
+}
+
+/// Tracks the active project and which projects have been launched.
+#[derive(Debug, Default)]
+pub struct ProjectTracker {
+    /// Name of the project applied most recently.
+    active: Option<String>,
+    /// Projects whose launch commands already ran.
+    launched: HashSet<String>,
+}
+
+impl ProjectTracker {
+    /// Name of the active project, if any.
+    pub fn active(&self) -> Option<&str> {
+        self.active.as_deref()
+    }
+
+    /// Record the project of the focused window.
+    ///
+    /// Returns true if it differs from the active project. Windows outside
+    /// any project leave the active project unchanged, so briefly focusing a
+    /// browser doesn't re-apply the profile on return.
+    pub fn observe(&mut self, project: Option<&str>) -> bool {
+        match project {
+            Some(name) if self.active.as_deref() != Some(name) => {
+                self.active = Some(name.to_string());
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Mark a project's launch commands as run. Returns false if they already were.
+    pub fn mark_launched(&mut self, name: &str) -> bool {
+        self.launched.insert(name.to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(name: &str, path: &str) -> ProjectProfile {
+        ProjectProfile {
+            name: name.to_string(),
+            path: path.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_normalize_path() {
+        assert_eq!(normalize_path("C:/Src/Project/"), "c:\\src\\project");
+        assert_eq!(normalize_path("\"D:\\Work\""), "d:\\work");
+    }
+
+    #[test]
+    fn test_split_command_line() {
+        assert_eq!(
+            split_command_line(r#""C:\Program Files\Code\Code.exe" --new-window "C:\src\my app" -n"#),
+            vec!["C:\\Program Files\\Code\\Code.exe", "--new-window", "C:\\src\\my app", "-n"]
+        );
+        assert_eq!(split_command_line("  nvim   "), vec!["nvim"]);
+        assert_eq!(split_command_line(r#"app.exe """#), vec!["app.exe", ""]);
+    }
+
+    #[test]
+    fn test_path_candidates() {
+        let candidates = path_candidates(
+            r#""C:\src\tool.exe" --folder-uri=C:/src/web relative\dir "\\server\share\docs" -v"#,
+        );
+        assert_eq!(candidates, vec!["c:\\src\\web", "\\\\server\\share\\docs"]);
+    }
+
+    #[test]
+    fn test_find_project_by_command_line() {
+        let profiles = vec![profile("web", "C:\\src\\web"), profile("api", "C:\\src\\api")];
+        let found = find_project(&profiles, Some(r#"Code.exe "C:\src\api\main.rs""#), "main.rs - Code");
+        assert_eq!(found.map(|p| p.name.as_str()), Some("api"));
+
+        // Prefix of a different directory doesn't match
+        assert!(find_project(&profiles, Some(r#"Code.exe C:\src\website"#), "").is_none());
+    }
+
+    #[test]
+    fn test_find_project_by_title() {
+        let profiles = vec![profile("web", "C:\\src\\web")];
+        let found = find_project(&profiles, Some("WindowsTerminal.exe"), "PS C:\\src\\web\\client> ");
+        assert_eq!(found.map(|p| p.name.as_str()), Some("web"));
+        assert!(find_project(&profiles, None, "C:\\src\\web2 - pwsh").is_none());
+        assert!(find_project(&profiles, None, "c:/src/web - pwsh").is_some());
+    }
+
+    #[test]
+    fn test_find_project_prefers_deepest_match() {
+        let profiles = vec![profile("src", "C:\\src"), profile("web", "C:\\src\\web")];
+        let found = find_project(&profiles, Some(r#"nvim C:\src\web\index.html"#), "");
+        assert_eq!(found.map(|p| p.name.as_str()), Some("web"));
+    }
+
+    #[test]
+    fn test_tracker_observe() {
+        let mut tracker = ProjectTracker::default();
+        assert!(tracker.observe(Some("web")));
+        assert!(!tracker.observe(Some("web")));
+        // Unrelated windows keep the project active
+        assert!(!tracker.observe(None));
+        assert_eq!(tracker.active(), Some("web"));
+        assert!(tracker.observe(Some("api")));
+    }
+
+    #[test]
+    fn test_tracker_launches_once() {
+        let mut tracker = ProjectTracker::default();
+        assert!(tracker.mark_launched("web"));
+        assert!(!tracker.mark_launched("web"));
+        assert!(tracker.mark_launched("api"));
+    }
+}
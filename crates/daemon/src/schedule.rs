@@ -0,0 +1,264 @@
+//! Per-monitor pause scheduling.
+//!
+//! Monitors can be paused by hand (focused monitor, via IPC) or automatically
+//! by `pause_when` conditions in their `[monitors."NAME"]` config section:
+//!
+//! ```toml
+//! [[monitors."DISPLAY3".pause_when]]
+//! between = "19:00-23:00"
+//!
+//! [[monitors."DISPLAY3".pause_when]]
+//! fullscreen_executable = "vlc.exe"
+//! ```
+//!
+//! The daemon re-evaluates the conditions periodically. A manual toggle
+//! overrides the schedule until the scheduled state next changes.
+
+use crate::config::PauseCondition;
+use openniri_core_layout::Rect;
+use std::str::FromStr;
+use thiserror::Error;
+
+/// Minutes in a day.
+const MINUTES_PER_DAY: u32 = 24 * 60;
+
+/// Errors from parsing a schedule time range.
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+pub enum ScheduleError {
+    #[error("expected a time range like \"19:00-23:00\", got '{0}'")]
+    InvalidRange(String),
+    #[error("invalid time '{0}' (expected HH:MM between 00:00 and 24:00)")]
+    InvalidTime(String),
+    #[error("time range '{0}' is empty")]
+    EmptyRange(String),
+}
+
+/// A daily local-time window, in minutes since midnight.
+///
+/// `start` is inclusive and `end` exclusive. Ranges whose end is before
+/// their start wrap past midnight (e.g. `22:00-02:00`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimeRange {
+    start: u32,
+    end: u32,
+}
+
+impl TimeRange {
+    /// Whether the given minute of the day falls inside the range.
+    pub fn contains(&self, minute_of_day: u32) -> bool {
+        let minute = minute_of_day % MINUTES_PER_DAY;
+        if self.start < self.end {
+            (self.start..self.end).contains(&minute)
+        } else {
+            minute >= self.start || minute < self.end
+        }
+    }
+}
+
+/// Parse `HH:MM` into minutes since midnight; `24:00` is accepted as an end time.
+fn parse_time(s: &str) -> Result<u32, ScheduleError> {
+    let invalid = || ScheduleError::InvalidTime(s.to_string());
+    let (hours, minutes) = s.trim().split_once(':').ok_or_else(invalid)?;
+    let hours: u32 = hours.parse().map_err(|_| invalid())?;
+    let minutes: u32 = minutes.parse().map_err(|_| invalid())?;
+    if minutes >= 60 || hours > 24 || (hours == 24 && minutes != 0) {
+        return Err(invalid());
+    }
+    Ok(hours * 60 + minutes)
+}
+
+impl FromStr for TimeRange {
+    type Err = ScheduleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (start, end) = s
+            .split_once('-')
+            .ok_or_else(|| ScheduleError::InvalidRange(s.to_string()))?;
+        let start = parse_time(start)? % MINUTES_PER_DAY;
+        let end = parse_time(end)? % MINUTES_PER_DAY;
+        if start == end {
+            return Err(ScheduleError::EmptyRange(s.to_string()));
+        }
+        Ok(Self { start, end })
+    }
+}
+
+/// Facts about the current moment that pause conditions are checked against.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PauseContext<'a> {
+    /// Local time, in minutes since midnight.
+    pub minute_of_day: u32,
+    /// Executable of the fullscreen window on the monitor, if any.
+    pub fullscreen_executable: Option<&'a str>,
+}
+
+/// Check whether a pause condition holds.
+///
+/// All criteria set on the condition must hold; a condition without any
+/// criteria (or with an unparseable time range) never matches.
+pub fn condition_matches(condition: &PauseCondition, ctx: &PauseContext) -> bool {
+    if condition.between.is_none() && condition.fullscreen_executable.is_none() {
+        return false;
+    }
+
+    if let Some(ref between) = condition.between {
+        match between.parse::<TimeRange>() {
+            Ok(range) if range.contains(ctx.minute_of_day) => {}
+            _ => return false,
+        }
+    }
+
+    if let Some(ref exe) = condition.fullscreen_executable {
+        if !ctx.fullscreen_executable.is_some_and(|fs| fs.eq_ignore_ascii_case(exe)) {
+            return false;
+        }
+    }
+
+    true
+}
+
+/// Whether a window rect covers a whole monitor.
+pub fn is_fullscreen_on(window: &Rect, monitor: &Rect) -> bool {
+    window.x <= monitor.x
+        && window.y <= monitor.y
+        && window.right() >= monitor.right()
+        && window.bottom() >= monitor.bottom()
+}
+
+/// Pause state of one monitor.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MonitorPause {
+    /// Manual override, cleared when the scheduled state changes.
+    manual: Option<bool>,
+    /// Result of the most recent schedule evaluation.
+    scheduled: bool,
+}
+
+impl MonitorPause {
+    /// Whether tiling is currently paused on the monitor.
+    pub fn is_paused(&self) -> bool {
+        self.manual.unwrap_or(self.scheduled)
+    }
+
+    /// Flip the effective pause state by hand. Returns the new state.
+    pub fn toggle(&mut self) -> bool {
+        let paused = !self.is_paused();
+        self.manual = Some(paused);
+        paused
+    }
+
+    /// Record the latest schedule evaluation.
+    ///
+    /// Returns `Some(paused)` if the effective state changed.
+    pub fn set_scheduled(&mut self, scheduled: bool) -> Option<bool> {
+        if scheduled == self.scheduled {
+            return None;
+        }
+        let was_paused = self.is_paused();
+        self.scheduled = scheduled;
+        self.manual = None;
+        (self.is_paused() != was_paused).then_some(scheduled)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(hours: u32, minutes: u32) -> u32 {
+        hours * 60 + minutes
+    }
+
+    #[test]
+    fn test_time_range_parse_and_contains() {
+        let range: TimeRange = "19:00-23:00".parse().unwrap();
+        assert!(!range.contains(at(18, 59)));
+        assert!(range.contains(at(19, 0)));
+        assert!(range.contains(at(22, 59)));
+        assert!(!range.contains(at(23, 0)));
+    }
+
+    #[test]
+    fn test_time_range_wraps_midnight() {
+        let range: TimeRange = "22:30 - 02:00".parse().unwrap();
+        assert!(range.contains(at(23, 0)));
+        assert!(range.contains(at(1, 59)));
+        assert!(!range.contains(at(2, 0)));
+        assert!(!range.contains(at(12, 0)));
+
+        let range: TimeRange = "18:00-24:00".parse().unwrap();
+        assert!(range.contains(at(23, 59)));
+        assert!(!range.contains(at(0, 0)));
+    }
+
+    #[test]
+    fn test_time_range_parse_errors() {
+        assert!(matches!("19:00".parse::<TimeRange>(), Err(ScheduleError::InvalidRange(_))));
+        assert!(matches!("7pm-9pm".parse::<TimeRange>(), Err(ScheduleError::InvalidTime(_))));
+        assert!(matches!("19:60-20:00".parse::<TimeRange>(), Err(ScheduleError::InvalidTime(_))));
+        assert!(matches!("24:30-01:00".parse::<TimeRange>(), Err(ScheduleError::InvalidTime(_))));
+        assert!(matches!("10:00-10:00".parse::<TimeRange>(), Err(ScheduleError::EmptyRange(_))));
+    }
+
+    #[test]
+    fn test_condition_matches_schedule() {
+        let condition = PauseCondition { between: Some("19:00-23:00".to_string()), ..Default::default() };
+        assert!(condition_matches(&condition, &PauseContext { minute_of_day: at(20, 0), ..Default::default() }));
+        assert!(!condition_matches(&condition, &PauseContext { minute_of_day: at(9, 0), ..Default::default() }));
+    }
+
+    #[test]
+    fn test_condition_matches_fullscreen_executable() {
+        let condition = PauseCondition { fullscreen_executable: Some("vlc.exe".to_string()), ..Default::default() };
+        let ctx = PauseContext { minute_of_day: 0, fullscreen_executable: Some("VLC.exe") };
+        assert!(condition_matches(&condition, &ctx));
+        assert!(!condition_matches(&condition, &PauseContext::default()));
+    }
+
+    #[test]
+    fn test_condition_requires_all_criteria() {
+        let condition = PauseCondition {
+            between: Some("19:00-23:00".to_string()),
+            fullscreen_executable: Some("vlc.exe".to_string()),
+        };
+        let ctx = PauseContext { minute_of_day: at(20, 0), fullscreen_executable: None };
+        assert!(!condition_matches(&condition, &ctx));
+        assert!(!condition_matches(&PauseCondition::default(), &ctx));
+    }
+
+    #[test]
+    fn test_is_fullscreen_on() {
+        let monitor = Rect::new(1920, 0, 1920, 1080);
+        assert!(is_fullscreen_on(&Rect::new(1920, 0, 1920, 1080), &monitor));
+        assert!(is_fullscreen_on(&Rect::new(1912, -8, 1936, 1096), &monitor));
+        assert!(!is_fullscreen_on(&Rect::new(1920, 0, 1920, 1040), &monitor));
+    }
+
+    #[test]
+    fn test_monitor_pause_schedule_transitions() {
+        let mut pause = MonitorPause::default();
+        assert!(!pause.is_paused());
+        assert_eq!(pause.set_scheduled(false), None);
+        assert_eq!(pause.set_scheduled(true), Some(true));
+        assert!(pause.is_paused());
+        assert_eq!(pause.set_scheduled(true), None);
+        assert_eq!(pause.set_scheduled(false), Some(false));
+    }
+
+    #[test]
+    fn test_monitor_pause_manual_override() {
+        let mut pause = MonitorPause::default();
+        assert_eq!(pause.set_scheduled(true), Some(true));
+
+        // Resume by hand during the scheduled window
+        assert!(!pause.toggle());
+        assert!(!pause.is_paused());
+        assert_eq!(pause.set_scheduled(true), None);
+        assert!(!pause.is_paused());
+
+        // Schedule ending clears the override without a visible change
+        assert_eq!(pause.set_scheduled(false), None);
+        assert!(!pause.is_paused());
+        assert!(pause.toggle());
+    }
+}
@@ -0,0 +1,103 @@
+//! Named scratchpad windows.
+//!
+//! A scratchpad window (a dropdown terminal, a music player) is taken out of
+//! tiling and kept hidden until `toggle_scratchpad <name>` summons it,
+//! floating and centered on the focused monitor; toggling again hides it.
+//! Windows become scratchpads through a window rule's `scratchpad` name or the
+//! `move_to_scratchpad <name>` command. Each name holds one window.
+
+use serde::Serialize;
+
+/// A window held by a scratchpad.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Scratchpad {
+    /// Scratchpad name, e.g. "term".
+    pub name: String,
+    /// The window.
+    pub window_id: u64,
+    /// Whether the window is currently shown.
+    pub visible: bool,
+}
+
+/// Scratchpads by name.
+#[derive(Debug, Default, Serialize)]
+pub struct Scratchpads {
+    entries: Vec<Scratchpad>,
+}
+
+impl Scratchpads {
+    /// Scratchpad with this name (case-insensitive).
+    pub fn get(&self, name: &str) -> Option<&Scratchpad> {
+        self.entries.iter().find(|s| s.name.eq_ignore_ascii_case(name))
+    }
+
+    /// Scratchpad holding this window.
+    pub fn find_window(&self, window_id: u64) -> Option<&Scratchpad> {
+        self.entries.iter().find(|s| s.window_id == window_id)
+    }
+
+    /// Put a hidden window into the scratchpad `name`.
+    ///
+    /// Returns false if the name already holds another window.
+    pub fn insert(&mut self, name: &str, window_id: u64) -> bool {
+        if self.get(name).is_some_and(|s| s.window_id != window_id) {
+            return false;
+        }
+        self.remove_window(window_id);
+        self.entries.push(Scratchpad { name: name.to_string(), window_id, visible: false });
+        true
+    }
+
+    /// Record whether the scratchpad `name` is shown.
+    pub fn set_visible(&mut self, name: &str, visible: bool) {
+        if let Some(scratchpad) = self.entries.iter_mut().find(|s| s.name.eq_ignore_ascii_case(name)) {
+            scratchpad.visible = visible;
+        }
+    }
+
+    /// Forget a window, e.g. because it was destroyed. Returns its scratchpad.
+    pub fn remove_window(&mut self, window_id: u64) -> Option<Scratchpad> {
+        let index = self.entries.iter().position(|s| s.window_id == window_id)?;
+        Some(self.entries.remove(index))
+    }
+
+    /// Windows of scratchpads that are hidden.
+    pub fn hidden_window_ids(&self) -> impl Iterator<Item = u64> + '_ {
+        self.entries.iter().filter(|s| !s.visible).map(|s| s.window_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert_one_window_per_name() {
+        let mut scratchpads = Scratchpads::default();
+        assert!(scratchpads.insert("term", 1));
+        assert!(!scratchpads.insert("Term", 2));
+        assert!(scratchpads.insert("term", 1), "re-inserting the same window is fine");
+        assert_eq!(scratchpads.get("TERM").unwrap().window_id, 1);
+
+        // Moving a window to another scratchpad frees the old name
+        assert!(scratchpads.insert("music", 1));
+        assert!(scratchpads.get("term").is_none());
+        assert_eq!(scratchpads.find_window(1).unwrap().name, "music");
+    }
+
+    #[test]
+    fn test_visibility_and_removal() {
+        let mut scratchpads = Scratchpads::default();
+        scratchpads.insert("term", 1);
+        scratchpads.insert("music", 2);
+        assert_eq!(scratchpads.hidden_window_ids().collect::<Vec<_>>(), vec![1, 2]);
+
+        scratchpads.set_visible("term", true);
+        assert!(scratchpads.get("term").unwrap().visible);
+        assert_eq!(scratchpads.hidden_window_ids().collect::<Vec<_>>(), vec![2]);
+
+        assert_eq!(scratchpads.remove_window(2).unwrap().name, "music");
+        assert!(scratchpads.remove_window(2).is_none());
+        assert!(scratchpads.get("music").is_none());
+    }
+}
@@ -0,0 +1,121 @@
+//! Supervised mode: restart the daemon after a crash.
+//!
+//! `openniri --supervised` doesn't manage windows itself. It runs the daemon
+//! as a child process and, when the child exits abnormally, makes the
+//! windows it may have hidden visible again and starts a new daemon. The new
+//! daemon restores the layout the old one saved and cloaks only the windows
+//! that are off-screen in it. A clean exit (`openniri-cli stop`, tray Exit)
+//! ends the supervisor too.
+
+use anyhow::{Context, Result};
+use std::process::Command;
+use std::time::{Duration, Instant};
+
+/// Environment variable telling a supervised daemon how often it has been
+/// restarted (`0` for the first start).
+pub const RESTART_ENV_VAR: &str = "OPENNIRI_SUPERVISED_RESTARTS";
+
+/// Crashes within this period count towards [`MAX_CRASHES`].
+const CRASH_WINDOW: Duration = Duration::from_secs(60);
+
+/// Crashes within [`CRASH_WINDOW`] after which the supervisor gives up
+/// instead of restarting a daemon that can't stay up.
+const MAX_CRASHES: usize = 5;
+
+/// Delay before restarting after a single recent crash; doubled for each
+/// further crash within [`CRASH_WINDOW`].
+const BASE_RESTART_DELAY: Duration = Duration::from_millis(500);
+
+/// Recent crashes, deciding whether and when to restart.
+#[derive(Debug, Default)]
+pub struct CrashHistory {
+    crashes: Vec<Instant>,
+}
+
+impl CrashHistory {
+    /// Record a crash at `now`.
+    ///
+    /// Returns the delay before restarting, or `None` if the daemon crashed
+    /// too often recently.
+    pub fn record(&mut self, now: Instant) -> Option<Duration> {
+        self.crashes.retain(|&crash| now.duration_since(crash) < CRASH_WINDOW);
+        self.crashes.push(now);
+        if self.crashes.len() > MAX_CRASHES {
+            return None;
+        }
+        Some(BASE_RESTART_DELAY * (1 << (self.crashes.len() - 1)))
+    }
+}
+
+/// Number of restarts from [`RESTART_ENV_VAR`], or `None` if this daemon
+/// isn't supervised.
+pub fn restart_count() -> Option<u32> {
+    std::env::var(RESTART_ENV_VAR).ok()?.parse().ok()
+}
+
+/// Run this executable with `args` until it exits cleanly, restarting it
+/// after each crash.
+///
+/// `recover` runs after every crash, before the restart.
+///
+/// # Errors
+///
+/// Returns an error if the daemon can't be started, or if it crashed too
+/// often to keep restarting it.
+pub fn run(args: &[String], mut recover: impl FnMut()) -> Result<()> {
+    let exe = std::env::current_exe().context("Failed to locate the daemon executable")?;
+    let mut history = CrashHistory::default();
+    let mut restarts = 0u32;
+    loop {
+        eprintln!("[openniri] Supervisor starting daemon (restart {})", restarts);
+        let status = Command::new(&exe)
+            .args(args)
+            .env(RESTART_ENV_VAR, restarts.to_string())
+            .status()
+            .context("Failed to start the daemon")?;
+        if status.success() {
+            eprintln!("[openniri] Daemon exited cleanly; supervisor stopping");
+            return Ok(());
+        }
+
+        eprintln!("[openniri] Daemon exited abnormally ({}); recovering windows", status);
+        recover();
+        let Some(delay) = history.record(Instant::now()) else {
+            anyhow::bail!(
+                "Daemon crashed more than {} times within {}s; not restarting it",
+                MAX_CRASHES,
+                CRASH_WINDOW.as_secs()
+            );
+        };
+        std::thread::sleep(delay);
+        restarts += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restart_delay_backs_off() {
+        let start = Instant::now();
+        let mut history = CrashHistory::default();
+        assert_eq!(history.record(start), Some(Duration::from_millis(500)));
+        assert_eq!(history.record(start + Duration::from_secs(1)), Some(Duration::from_millis(1000)));
+        assert_eq!(history.record(start + Duration::from_secs(2)), Some(Duration::from_millis(2000)));
+
+        // Crashes older than the window are forgotten
+        let later = start + CRASH_WINDOW + Duration::from_secs(5);
+        assert_eq!(history.record(later), Some(Duration::from_millis(500)));
+    }
+
+    #[test]
+    fn test_gives_up_after_repeated_crashes() {
+        let start = Instant::now();
+        let mut history = CrashHistory::default();
+        for i in 0..MAX_CRASHES as u64 {
+            assert!(history.record(start + Duration::from_secs(i)).is_some());
+        }
+        assert_eq!(history.record(start + Duration::from_secs(MAX_CRASHES as u64)), None);
+    }
+}
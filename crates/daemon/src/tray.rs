@@ -1,17 +1,29 @@
 //! System tray icon management for OpenNiri daemon.
 //!
 //! Provides a system tray icon with a context menu for common operations:
+//! - Pause/resume tiling
+//! - Monitors with their workspace and window counts (click to focus one)
+//! - Gather all windows onto the focused monitor
+//! - Focus a window that requests attention, flagged by a dot on the icon
 //! - Refresh windows
 //! - Reload configuration
 //! - Exit daemon
 
+use openniri_ipc::text::truncate_utf16_with_ellipsis;
+use openniri_platform_win32::MonitorId;
 use std::sync::mpsc;
 use tray_icon::{
-    menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem},
+    menu::{CheckMenuItem, Menu, MenuEvent, MenuItem, PredefinedMenuItem, Submenu},
     TrayIcon, TrayIconBuilder,
 };
 use thiserror::Error;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
+
+/// Tooltip shown while tiling runs normally.
+const DEFAULT_TOOLTIP: &str = "OpenNiri Windows - Tiling Window Manager";
+
+/// Longest tray tooltip Windows displays, in UTF-16 units (excluding the terminator).
+const TOOLTIP_MAX_UTF16: usize = 127;
 
 /// Menu item IDs for tray context menu.
 mod menu_ids {
@@ -21,6 +33,10 @@ mod menu_ids {
     pub const TOGGLE_PAUSE: &str = "toggle_pause";
     pub const OPEN_CONFIG: &str = "open_config";
     pub const VIEW_LOGS: &str = "view_logs";
+    pub const GATHER_WINDOWS: &str = "gather_windows";
+    pub const FOCUS_URGENT: &str = "focus_urgent";
+    /// Prefix of the per-monitor items, followed by the monitor ID.
+    pub const FOCUS_MONITOR_PREFIX: &str = "focus_monitor:";
 }
 
 /// Events emitted by the tray icon.
@@ -38,11 +54,82 @@ pub enum TrayEvent {
     OpenConfig,
     /// User clicked "View Logs" menu item.
     ViewLogs,
+    /// User clicked a monitor in the "Monitors" submenu.
+    FocusMonitor(MonitorId),
+    /// User clicked "Gather Windows Here" menu item.
+    GatherWindows,
+    /// User clicked the "Focus Urgent Window" menu item.
+    FocusUrgent,
+}
+
+impl TrayEvent {
+    /// Event for a clicked menu item ID.
+    fn from_menu_id(id: &str) -> Option<Self> {
+        let event = match id {
+            menu_ids::REFRESH => Self::Refresh,
+            menu_ids::RELOAD => Self::Reload,
+            menu_ids::EXIT => Self::Exit,
+            menu_ids::TOGGLE_PAUSE => Self::TogglePause,
+            menu_ids::OPEN_CONFIG => Self::OpenConfig,
+            menu_ids::VIEW_LOGS => Self::ViewLogs,
+            menu_ids::GATHER_WINDOWS => Self::GatherWindows,
+            menu_ids::FOCUS_URGENT => Self::FocusUrgent,
+            _ => Self::FocusMonitor(id.strip_prefix(menu_ids::FOCUS_MONITOR_PREFIX)?.parse().ok()?),
+        };
+        Some(event)
+    }
+}
+
+/// State shown in the tray menu, rebuilt by the daemon as it changes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TraySummary {
+    /// Whether tiling is paused globally.
+    pub paused: bool,
+    /// Monitors from left to right.
+    pub monitors: Vec<MonitorSummary>,
+    /// Managed windows requesting attention.
+    pub urgent: usize,
+}
+
+/// One monitor's line in the "Monitors" submenu.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MonitorSummary {
+    pub id: MonitorId,
+    /// Device name without the `\\.\` prefix, e.g. `DISPLAY1`.
+    pub name: String,
+    /// Name of the active workspace.
+    pub workspace: String,
+    /// Tiled and floating windows of the active workspace.
+    pub windows: usize,
+    pub focused: bool,
+    /// Whether tiling is paused on this monitor alone.
+    pub paused: bool,
+}
+
+impl MonitorSummary {
+    /// Menu label, e.g. `DISPLAY1 - code: 3 windows (paused)`.
+    fn label(&self) -> String {
+        let windows = match self.windows {
+            1 => "1 window".to_string(),
+            n => format!("{} windows", n),
+        };
+        let mut label = format!("{} - {}: {}", self.name, self.workspace, windows);
+        if self.paused {
+            label.push_str(" (paused)");
+        }
+        label
+    }
 }
 
 /// Manages the system tray icon and context menu.
 pub struct TrayManager {
-    _tray: TrayIcon,
+    tray: TrayIcon,
+    toggle_pause: MenuItem,
+    monitors: Submenu,
+    gather_windows: MenuItem,
+    focus_urgent: MenuItem,
+    /// What the menu currently shows.
+    summary: TraySummary,
 }
 
 impl TrayManager {
@@ -59,6 +146,26 @@ impl TrayManager {
         let title = MenuItem::new("OpenNiri Windows", false, None);
         menu.append(&title).map_err(|e| TrayError::Menu(e.to_string()))?;
 
+        // Separator
+        menu.append(&PredefinedMenuItem::separator())
+            .map_err(|e| TrayError::Menu(e.to_string()))?;
+
+        // Toggle Pause
+        let toggle_pause = MenuItem::with_id(menu_ids::TOGGLE_PAUSE, pause_label(false), true, None);
+        menu.append(&toggle_pause).map_err(|e| TrayError::Menu(e.to_string()))?;
+
+        // Monitors, filled in by update()
+        let monitors = Submenu::new("Monitors", false);
+        menu.append(&monitors).map_err(|e| TrayError::Menu(e.to_string()))?;
+
+        // Gather Windows
+        let gather_windows = MenuItem::with_id(menu_ids::GATHER_WINDOWS, "Gather Windows Here", false, None);
+        menu.append(&gather_windows).map_err(|e| TrayError::Menu(e.to_string()))?;
+
+        // Focus Urgent Window, enabled while a window requests attention
+        let focus_urgent = MenuItem::with_id(menu_ids::FOCUS_URGENT, urgent_label(0), false, None);
+        menu.append(&focus_urgent).map_err(|e| TrayError::Menu(e.to_string()))?;
+
         // Separator
         menu.append(&PredefinedMenuItem::separator())
             .map_err(|e| TrayError::Menu(e.to_string()))?;
@@ -71,10 +178,6 @@ impl TrayManager {
         let reload = MenuItem::with_id(menu_ids::RELOAD, "Reload Config", true, None);
         menu.append(&reload).map_err(|e| TrayError::Menu(e.to_string()))?;
 
-        // Toggle Pause
-        let toggle_pause = MenuItem::with_id(menu_ids::TOGGLE_PAUSE, "Pause Tiling", true, None);
-        menu.append(&toggle_pause).map_err(|e| TrayError::Menu(e.to_string()))?;
-
         // Open Config
         let open_config = MenuItem::with_id(menu_ids::OPEN_CONFIG, "Open Config", true, None);
         menu.append(&open_config).map_err(|e| TrayError::Menu(e.to_string()))?;
@@ -92,11 +195,11 @@ impl TrayManager {
         menu.append(&exit).map_err(|e| TrayError::Menu(e.to_string()))?;
 
         // Create the tray icon with a simple embedded icon
-        let icon = create_default_icon()?;
+        let icon = create_icon(false)?;
 
         let tray = TrayIconBuilder::new()
             .with_menu(Box::new(menu))
-            .with_tooltip("OpenNiri Windows - Tiling Window Manager")
+            .with_tooltip(DEFAULT_TOOLTIP)
             .with_icon(icon)
             .build()
             .map_err(|e| TrayError::Build(e.to_string()))?;
@@ -107,17 +210,9 @@ impl TrayManager {
         std::thread::spawn(move || {
             let menu_channel = MenuEvent::receiver();
             while let Ok(event) = menu_channel.recv() {
-                let tray_event = match event.id.0.as_str() {
-                    menu_ids::REFRESH => TrayEvent::Refresh,
-                    menu_ids::RELOAD => TrayEvent::Reload,
-                    menu_ids::EXIT => TrayEvent::Exit,
-                    menu_ids::TOGGLE_PAUSE => TrayEvent::TogglePause,
-                    menu_ids::OPEN_CONFIG => TrayEvent::OpenConfig,
-                    menu_ids::VIEW_LOGS => TrayEvent::ViewLogs,
-                    id => {
-                        debug!("Unknown menu item clicked: {}", id);
-                        continue;
-                    }
+                let Some(tray_event) = TrayEvent::from_menu_id(event.id.0.as_str()) else {
+                    debug!("Unknown menu item clicked: {}", event.id.0);
+                    continue;
                 };
 
                 if event_sender.send(tray_event).is_err() {
@@ -128,18 +223,104 @@ impl TrayManager {
         });
 
         Ok(Self {
-            _tray: tray,
+            tray,
+            toggle_pause,
+            monitors,
+            gather_windows,
+            focus_urgent,
+            summary: TraySummary::default(),
         })
     }
+
+    /// Bring the menu up to date; does nothing if `summary` is unchanged.
+    pub fn update(&mut self, summary: &TraySummary) {
+        if *summary == self.summary {
+            return;
+        }
+        if summary.paused != self.summary.paused {
+            self.toggle_pause.set_text(pause_label(summary.paused));
+        }
+        if summary.monitors != self.summary.monitors {
+            if let Err(e) = self.rebuild_monitors(&summary.monitors) {
+                warn!("Failed to update tray monitor menu: {}", e);
+            }
+            self.gather_windows.set_enabled(summary.monitors.len() > 1);
+        }
+        if summary.urgent != self.summary.urgent {
+            self.focus_urgent.set_text(urgent_label(summary.urgent));
+            self.focus_urgent.set_enabled(summary.urgent > 0);
+            if (summary.urgent > 0) != (self.summary.urgent > 0) {
+                match create_icon(summary.urgent > 0) {
+                    Ok(icon) => {
+                        if let Err(e) = self.tray.set_icon(Some(icon)) {
+                            warn!("Failed to update tray icon: {}", e);
+                        }
+                    }
+                    Err(e) => warn!("Failed to create tray icon: {}", e),
+                }
+            }
+        }
+        self.summary = summary.clone();
+    }
+
+    fn rebuild_monitors(&self, monitors: &[MonitorSummary]) -> Result<(), TrayError> {
+        while self.monitors.remove_at(0).is_some() {}
+        for monitor in monitors {
+            let id = format!("{}{}", menu_ids::FOCUS_MONITOR_PREFIX, monitor.id);
+            let item = CheckMenuItem::with_id(id, monitor.label(), true, monitor.focused, None);
+            self.monitors.append(&item).map_err(|e| TrayError::Menu(e.to_string()))?;
+        }
+        self.monitors.set_enabled(!monitors.is_empty());
+        Ok(())
+    }
+
+    /// Show a status after the app name in the tooltip, or the default tooltip for None.
+    pub fn set_status(&self, status: Option<&str>) {
+        let tooltip = tooltip_text(status);
+        if let Err(e) = self.tray.set_tooltip(Some(tooltip)) {
+            warn!("Failed to update tray tooltip: {}", e);
+        }
+    }
 }
 
-/// Create a default icon for the tray.
+/// Label of the pause menu item, which resumes while paused.
+fn pause_label(paused: bool) -> &'static str {
+    if paused {
+        "Resume Tiling"
+    } else {
+        "Pause Tiling"
+    }
+}
+
+/// Label of the urgent window menu item, with the number of windows
+/// requesting attention.
+fn urgent_label(urgent: usize) -> String {
+    match urgent {
+        0 => "Focus Urgent Window".to_string(),
+        n => format!("Focus Urgent Window ({})", n),
+    }
+}
+
+/// Tooltip text for a status, cut to the length Windows displays.
+fn tooltip_text(status: Option<&str>) -> String {
+    match status {
+        Some(status) => truncate_utf16_with_ellipsis(&format!("OpenNiri Windows - {}", status), TOOLTIP_MAX_UTF16),
+        None => DEFAULT_TOOLTIP.to_string(),
+    }
+}
+
+/// Create the icon for the tray.
 ///
-/// Uses a simple blue square as a placeholder icon.
-fn create_default_icon() -> Result<tray_icon::Icon, TrayError> {
+/// Uses a simple blue square as a placeholder icon, with an orange dot in
+/// the top right corner while a window requests `attention`.
+fn create_icon(attention: bool) -> Result<tray_icon::Icon, TrayError> {
     // Create a simple 32x32 RGBA icon (blue square with rounded appearance)
     const SIZE: usize = 32;
     let mut rgba = vec![0u8; SIZE * SIZE * 4];
+    // Attention dot in the top right corner
+    const DOT_X: f32 = 25.0;
+    const DOT_Y: f32 = 7.0;
+    const DOT_RADIUS: f32 = 6.5;
 
     // Colors: OpenNiri blue theme
     let primary_r = 66u8;
@@ -189,6 +370,17 @@ fn create_default_icon() -> Result<tray_icon::Icon, TrayError> {
                 rgba[idx + 3] = alpha;
             }
             // else: transparent (default 0)
+
+            if attention {
+                let dx = x as f32 - DOT_X;
+                let dy = y as f32 - DOT_Y;
+                if (dx * dx + dy * dy).sqrt() < DOT_RADIUS {
+                    rgba[idx] = 255;
+                    rgba[idx + 1] = 140;
+                    rgba[idx + 2] = 0;
+                    rgba[idx + 3] = 255;
+                }
+            }
         }
     }
 
@@ -215,7 +407,51 @@ mod tests {
 
     #[test]
     fn test_create_default_icon() {
-        let icon = create_default_icon();
+        let icon = create_icon(false);
         assert!(icon.is_ok(), "Should create default icon successfully");
+        assert!(create_icon(true).is_ok(), "Should create attention icon successfully");
+    }
+
+    #[test]
+    fn test_menu_ids_map_to_events() {
+        assert!(matches!(TrayEvent::from_menu_id(menu_ids::TOGGLE_PAUSE), Some(TrayEvent::TogglePause)));
+        assert!(matches!(TrayEvent::from_menu_id(menu_ids::GATHER_WINDOWS), Some(TrayEvent::GatherWindows)));
+        assert!(matches!(TrayEvent::from_menu_id(menu_ids::FOCUS_URGENT), Some(TrayEvent::FocusUrgent)));
+        assert!(matches!(TrayEvent::from_menu_id("focus_monitor:-42"), Some(TrayEvent::FocusMonitor(-42))));
+        assert!(TrayEvent::from_menu_id("focus_monitor:x").is_none());
+        assert!(TrayEvent::from_menu_id("bogus").is_none());
+    }
+
+    #[test]
+    fn test_monitor_label() {
+        let mut monitor = MonitorSummary {
+            id: 1,
+            name: "DISPLAY1".to_string(),
+            workspace: "code".to_string(),
+            windows: 3,
+            focused: true,
+            paused: false,
+        };
+        assert_eq!(monitor.label(), "DISPLAY1 - code: 3 windows");
+        monitor.windows = 1;
+        monitor.paused = true;
+        assert_eq!(monitor.label(), "DISPLAY1 - code: 1 window (paused)");
+    }
+
+    #[test]
+    fn test_urgent_label() {
+        assert_eq!(urgent_label(0), "Focus Urgent Window");
+        assert_eq!(urgent_label(2), "Focus Urgent Window (2)");
+    }
+
+    #[test]
+    fn test_tooltip_text_fits_windows_limit() {
+        assert_eq!(tooltip_text(None), DEFAULT_TOOLTIP);
+        assert_eq!(tooltip_text(Some("Tiling paused")), "OpenNiri Windows - Tiling paused");
+
+        let long = "📁 ".repeat(100);
+        let tooltip = tooltip_text(Some(&long));
+        assert!(tooltip.encode_utf16().count() <= TOOLTIP_MAX_UTF16);
+        assert!(tooltip.ends_with('…'));
     }
 }
@@ -0,0 +1,124 @@
+//! Cached window properties.
+//!
+//! Reading a window's title, class and executable takes several system calls,
+//! and opening its process to find the executable is the slowest of them.
+//! The daemon keeps the properties of windows it has seen here, filled when a
+//! window is created or enumerated and updated from title-change and destroy
+//! events, so window rules and `QueryAllWindows` don't have to ask again.
+
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Properties of one window.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct CachedWindow {
+    /// Window title.
+    pub title: String,
+    /// Window class name.
+    pub class_name: String,
+    /// Owning process ID.
+    pub process_id: u32,
+    /// File name of the owning process's executable, e.g. `notepad.exe`
+    /// (empty if unknown).
+    pub executable: String,
+}
+
+/// Window properties by window handle, with executables by process ID.
+#[derive(Debug, Default, Serialize)]
+pub struct WindowCache {
+    windows: HashMap<u64, CachedWindow>,
+    executables: HashMap<u32, String>,
+}
+
+impl WindowCache {
+    /// Cached properties of a window.
+    pub fn get(&self, window_id: u64) -> Option<&CachedWindow> {
+        self.windows.get(&window_id)
+    }
+
+    /// Cache a window's properties, replacing any earlier entry.
+    pub fn insert(&mut self, window_id: u64, window: CachedWindow) {
+        self.executables.insert(window.process_id, window.executable.clone());
+        self.windows.insert(window_id, window);
+    }
+
+    /// Executable of a process, calling `resolve` only for processes not
+    /// seen before. Unknown executables are cached as empty.
+    pub fn executable(&mut self, process_id: u32, resolve: impl FnOnce(u32) -> Option<String>) -> String {
+        self.executables
+            .entry(process_id)
+            .or_insert_with(|| resolve(process_id).unwrap_or_default())
+            .clone()
+    }
+
+    /// Update a cached window's title. Returns false if it isn't cached.
+    pub fn set_title(&mut self, window_id: u64, title: String) -> bool {
+        match self.windows.get_mut(&window_id) {
+            Some(window) => {
+                window.title = title;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Forget a window, and its process's executable once no cached window
+    /// belongs to the process (process IDs are reused).
+    pub fn remove(&mut self, window_id: u64) {
+        let Some(window) = self.windows.remove(&window_id) else {
+            return;
+        };
+        if !self.windows.values().any(|w| w.process_id == window.process_id) {
+            self.executables.remove(&window.process_id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn window(title: &str, process_id: u32, executable: &str) -> CachedWindow {
+        CachedWindow {
+            title: title.to_string(),
+            class_name: "Class".to_string(),
+            process_id,
+            executable: executable.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_executable_resolved_once_per_process() {
+        let mut cache = WindowCache::default();
+        let mut calls = 0;
+        for _ in 0..3 {
+            let exe = cache.executable(7, |_| {
+                calls += 1;
+                Some("app.exe".to_string())
+            });
+            assert_eq!(exe, "app.exe");
+        }
+        assert_eq!(calls, 1);
+
+        // Inserted windows share their executable with later lookups
+        cache.insert(1, window("Doc", 8, "editor.exe"));
+        assert_eq!(cache.executable(8, |_| panic!("already cached")), "editor.exe");
+    }
+
+    #[test]
+    fn test_title_update_and_remove() {
+        let mut cache = WindowCache::default();
+        cache.insert(1, window("Loading", 7, "app.exe"));
+        cache.insert(2, window("Other", 7, "app.exe"));
+        assert!(cache.set_title(1, "Inbox".to_string()));
+        assert_eq!(cache.get(1).unwrap().title, "Inbox");
+        assert!(!cache.set_title(3, "Missing".to_string()));
+
+        // The executable stays cached while a window of the process remains
+        cache.remove(1);
+        assert_eq!(cache.executable(7, |_| None), "app.exe");
+        cache.remove(2);
+        assert!(cache.get(2).is_none());
+        assert_eq!(cache.executable(7, |_| Some("reused.exe".to_string())), "reused.exe");
+    }
+}
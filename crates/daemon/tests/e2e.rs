@@ -0,0 +1,181 @@
+//! End-to-end tests driving a real daemon against dummy windows.
+//!
+//! The daemon under test moves and cloaks real windows, so these tests are
+//! ignored by default. Run them on a Windows machine or CI agent with:
+//!
+//! ```text
+//! cargo test -p openniri-daemon --test e2e -- --ignored --test-threads=1
+//! ```
+
+use openniri_core_layout::Rect;
+use openniri_ipc::{IpcCommand, IpcRect, IpcResponse, WindowInfo};
+use openniri_platform_win32::enumerate_monitors;
+use openniri_test_harness::{wait_until, DaemonProcess, TestWindow, TEST_CONFIG};
+use std::path::Path;
+use std::time::Duration;
+
+/// How long the daemon may take to react to a window event.
+const EVENT_TIMEOUT: Duration = Duration::from_secs(5);
+
+fn start_daemon() -> DaemonProcess {
+    DaemonProcess::start(Path::new(env!("CARGO_BIN_EXE_openniri")), TEST_CONFIG).expect("start daemon")
+}
+
+fn info_for(windows: &[WindowInfo], window: &TestWindow) -> WindowInfo {
+    windows
+        .iter()
+        .find(|w| w.window_id == window.id())
+        .cloned()
+        .unwrap_or_else(|| panic!("Window {} is not managed", window.id()))
+}
+
+/// Whether a layout rect is on any monitor, i.e. the window should be shown.
+fn on_screen(rect: &IpcRect) -> bool {
+    let rect = Rect::new(rect.x, rect.y, rect.width, rect.height);
+    enumerate_monitors()
+        .expect("enumerate monitors")
+        .iter()
+        .any(|m| m.work_area.intersects(&rect))
+}
+
+// ============================================================================
+// Enumeration
+// ============================================================================
+
+#[test]
+#[ignore = "drives a real daemon on the desktop"]
+fn test_existing_windows_are_enumerated() {
+    let first = TestWindow::visible("e2e first");
+    let second = TestWindow::visible("e2e second");
+    let hidden = TestWindow::message_only("e2e message-only");
+    let daemon = start_daemon();
+
+    let windows = daemon.windows();
+    assert_eq!(info_for(&windows, &first).class_name, openniri_test_harness::TEST_WINDOW_CLASS);
+    info_for(&windows, &second);
+    assert!(!windows.iter().any(|w| w.window_id == hidden.id()));
+}
+
+#[test]
+#[ignore = "drives a real daemon on the desktop"]
+fn test_windows_are_adopted_and_dropped_on_events() {
+    let daemon = start_daemon();
+
+    let window = TestWindow::visible("e2e late");
+    let id = window.id();
+    assert!(wait_until(EVENT_TIMEOUT, || daemon.manages(id)), "new window was not adopted");
+
+    window.close();
+    assert!(wait_until(EVENT_TIMEOUT, || !daemon.manages(id)), "closed window was not dropped");
+}
+
+// ============================================================================
+// Placement and Cloaking
+// ============================================================================
+
+#[test]
+#[ignore = "drives a real daemon on the desktop"]
+fn test_windows_are_placed_side_by_side() {
+    let first = TestWindow::visible("e2e left");
+    let second = TestWindow::visible("e2e right");
+    let daemon = start_daemon();
+    assert_eq!(daemon.send(&IpcCommand::Apply).unwrap(), IpcResponse::Ok);
+
+    let windows = daemon.windows();
+    let mut infos = [info_for(&windows, &first), info_for(&windows, &second)];
+    infos.sort_by_key(|w| w.column_index);
+    assert_eq!(infos[0].rect.width, 800);
+    assert_eq!(infos[1].rect.x, infos[0].rect.x + 800);
+
+    // The daemon's reported layout is what the windows actually got
+    for window in [&first, &second] {
+        assert_eq!(window.rect(), Some(info_for(&windows, window).rect), "window {} was not placed", window.id());
+    }
+}
+
+#[test]
+#[ignore = "drives a real daemon on the desktop"]
+fn test_offscreen_columns_are_cloaked() {
+    // More 800px columns than fit on any common monitor
+    let windows: Vec<TestWindow> = (0..8).map(|i| TestWindow::visible(&format!("e2e {}", i))).collect();
+    let daemon = start_daemon();
+    assert_eq!(daemon.send(&IpcCommand::Apply).unwrap(), IpcResponse::Ok);
+
+    let infos = daemon.windows();
+    let mut cloaked = 0;
+    for window in &windows {
+        let expected_visible = on_screen(&info_for(&infos, window).rect);
+        assert_eq!(window.is_cloaked(), !expected_visible, "window {}", window.id());
+        cloaked += usize::from(window.is_cloaked());
+    }
+    assert!(cloaked > 0, "no column ended up off-screen");
+
+    // Stopping the daemon gives every window back
+    daemon.stop().expect("stop daemon");
+    assert!(windows.iter().all(|w| !w.is_cloaked()));
+}
+
+// ============================================================================
+// IPC
+// ============================================================================
+
+#[test]
+#[ignore = "drives a real daemon on the desktop"]
+fn test_focus_commands_move_focus() {
+    let _first = TestWindow::visible("e2e a");
+    let _second = TestWindow::visible("e2e b");
+    let daemon = start_daemon();
+
+    let focused_column = || match daemon.send(&IpcCommand::QueryFocused).unwrap() {
+        IpcResponse::FocusedWindow { column_index, .. } => column_index,
+        other => panic!("Unexpected response: {:?}", other),
+    };
+    assert_eq!(daemon.send(&IpcCommand::FocusLeft).unwrap(), IpcResponse::Ok);
+    assert_eq!(focused_column(), 0);
+    assert_eq!(daemon.send(&IpcCommand::FocusRight).unwrap(), IpcResponse::Ok);
+    assert_eq!(focused_column(), 1);
+}
+
+#[test]
+#[ignore = "drives a real daemon on the desktop"]
+fn test_status_and_unsupported_commands() {
+    let daemon = start_daemon();
+    assert!(matches!(
+        daemon.send(&IpcCommand::QueryStatus).unwrap(),
+        IpcResponse::StatusInfo { .. }
+    ));
+    assert!(matches!(
+        daemon.send(&IpcCommand::Unknown).unwrap(),
+        IpcResponse::UnsupportedCommand { .. }
+    ));
+    assert!(daemon.stop().expect("stop daemon").success());
+}
+
+// ============================================================================
+// Config
+// ============================================================================
+
+#[test]
+#[ignore = "drives a real daemon on the desktop"]
+fn test_config_edits_are_applied_automatically() {
+    let first = TestWindow::visible("e2e left");
+    let second = TestWindow::visible("e2e right");
+    let daemon = start_daemon();
+
+    let gap_between = || {
+        let windows = daemon.windows();
+        let mut infos = [info_for(&windows, &first), info_for(&windows, &second)];
+        infos.sort_by_key(|w| w.column_index);
+        infos[1].rect.x - (infos[0].rect.x + infos[0].rect.width)
+    };
+    assert_eq!(gap_between(), 0);
+
+    daemon.write_config(&TEST_CONFIG.replacen("gap = 0", "gap = 20", 1)).unwrap();
+    assert!(wait_until(EVENT_TIMEOUT, || gap_between() == 20), "config edit was not applied");
+
+    // A broken edit keeps the last good config
+    daemon.write_config("layout = [").unwrap();
+    std::thread::sleep(Duration::from_secs(1));
+    assert_eq!(gap_between(), 20);
+    assert!(daemon.log().contains("Automatic config reload failed"));
+}
@@ -62,6 +62,8 @@ fn test_all_responses_roundtrip() {
             focused_window: 0,
             scroll_offset: 123.5,
             total_width: 2400,
+            workspace_name: None,
+            columns_detail: vec![],
         },
         IpcResponse::FocusedWindow {
             window_id: Some(12345),
@@ -87,6 +89,7 @@ fn test_all_responses_roundtrip() {
                     monitor_id: 1,
                     is_floating: false,
                     is_focused: true,
+                    is_urgent: false,
                 },
             ],
         },
@@ -187,6 +190,8 @@ fn test_workspace_state_edge_values() {
         focused_window: 0,
         scroll_offset: 0.0,
         total_width: 0,
+        workspace_name: None,
+        columns_detail: vec![],
     };
 
     let json = serde_json::to_string(&resp).expect("serialize");
@@ -211,6 +216,8 @@ fn test_workspace_state_large_values() {
         focused_window: 10,
         scroll_offset: 50000.5,
         total_width: 100000,
+        workspace_name: None,
+        columns_detail: vec![],
     };
 
     let json = serde_json::to_string(&resp).expect("serialize");
@@ -235,6 +242,8 @@ fn test_workspace_state_negative_scroll() {
         focused_window: 0,
         scroll_offset: -100.0,
         total_width: 2400,
+        workspace_name: None,
+        columns_detail: vec![],
     };
 
     let json = serde_json::to_string(&resp).expect("serialize");
@@ -282,6 +291,7 @@ fn test_window_list_multiple_windows() {
             monitor_id: 1,
             is_floating: false,
             is_focused: true,
+            is_urgent: false,
         },
         WindowInfo {
             window_id: 200,
@@ -295,6 +305,7 @@ fn test_window_list_multiple_windows() {
             monitor_id: 1,
             is_floating: false,
             is_focused: false,
+            is_urgent: false,
         },
         WindowInfo {
             window_id: 300,
@@ -308,6 +319,7 @@ fn test_window_list_multiple_windows() {
             monitor_id: 1,
             is_floating: true,
             is_focused: false,
+            is_urgent: false,
         },
     ];
 
@@ -342,6 +354,7 @@ fn test_window_info_unicode_title() {
         monitor_id: 1,
         is_floating: false,
         is_focused: false,
+        is_urgent: false,
     };
 
     let json = serde_json::to_string(&win).expect("serialize");
@@ -0,0 +1,64 @@
+//! Identity of the current process, for namespacing the IPC pipe.
+//!
+//! Only meaningful on Windows; elsewhere both lookups report unknown and the
+//! pipe name falls back to the unqualified [`PIPE_NAME`](crate::PIPE_NAME).
+
+/// String form (`S-1-5-21-...`) of the SID of the user running this process.
+#[cfg(windows)]
+pub(crate) fn current_user_sid() -> Option<String> {
+    use std::ffi::c_void;
+    use windows::core::PWSTR;
+    use windows::Win32::Foundation::{CloseHandle, LocalFree, HANDLE, HLOCAL};
+    use windows::Win32::Security::Authorization::ConvertSidToStringSidW;
+    use windows::Win32::Security::{GetTokenInformation, TokenUser, TOKEN_QUERY, TOKEN_USER};
+    use windows::Win32::System::Threading::{GetCurrentProcess, OpenProcessToken};
+
+    unsafe {
+        let mut token = HANDLE::default();
+        OpenProcessToken(GetCurrentProcess(), TOKEN_QUERY, &mut token).ok()?;
+
+        // The first call only reports the buffer size
+        let mut len = 0u32;
+        let _ = GetTokenInformation(token, TokenUser, None, 0, &mut len);
+        // u64 elements keep the buffer aligned for TOKEN_USER
+        let mut buffer = vec![0u64; (len as usize).div_ceil(8)];
+        let result = GetTokenInformation(
+            token,
+            TokenUser,
+            Some(buffer.as_mut_ptr() as *mut c_void),
+            len,
+            &mut len,
+        );
+        let _ = CloseHandle(token);
+        result.ok()?;
+
+        let user = &*(buffer.as_ptr() as *const TOKEN_USER);
+        let mut sid = PWSTR::null();
+        ConvertSidToStringSidW(user.User.Sid, &mut sid).ok()?;
+        let string = sid.to_string().ok();
+        let _ = LocalFree(Some(HLOCAL(sid.0 as *mut c_void)));
+        string
+    }
+}
+
+#[cfg(not(windows))]
+pub(crate) fn current_user_sid() -> Option<String> {
+    None
+}
+
+/// Terminal Services session of this process.
+#[cfg(windows)]
+pub(crate) fn current_session_id() -> Option<u32> {
+    use windows::Win32::System::RemoteDesktop::ProcessIdToSessionId;
+    use windows::Win32::System::Threading::GetCurrentProcessId;
+
+    let mut session_id = 0u32;
+    unsafe { ProcessIdToSessionId(GetCurrentProcessId(), &mut session_id) }
+        .ok()
+        .map(|_| session_id)
+}
+
+#[cfg(not(windows))]
+pub(crate) fn current_session_id() -> Option<u32> {
+    None
+}
@@ -3,10 +3,153 @@
 //! Shared types for daemon-CLI communication over Windows named pipes.
 
 use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+mod identity;
+pub mod text;
+
+/// Version of the wire protocol, reported by [`IpcCommand::Hello`].
+///
+/// New commands and responses don't change it; clients discover them from
+/// the capabilities in [`IpcResponse::ServerInfo`]. It is only bumped when an
+/// existing message changes shape.
+pub const PROTOCOL_VERSION: u32 = 1;
 
 /// Named pipe path for IPC communication.
 pub const PIPE_NAME: &str = r"\\.\pipe\openniri";
 
+/// Environment variable naming an isolated daemon instance.
+///
+/// A daemon started with it set uses its own pipe and state files, so a test
+/// daemon can run next to the user's. Clients with it set talk to that daemon.
+pub const INSTANCE_ENV_VAR: &str = "OPENNIRI_INSTANCE";
+
+/// Environment variable with an explicit config file path.
+///
+/// When set, the usual locations are not searched, so e.g. a test daemon
+/// never picks up the user's own config.
+pub const CONFIG_ENV_VAR: &str = "OPENNIRI_CONFIG";
+
+/// All possible config file paths in priority order, shared by the daemon
+/// and the CLI so both find the same file.
+pub fn config_paths() -> Vec<PathBuf> {
+    if let Some(path) = std::env::var_os(CONFIG_ENV_VAR).filter(|path| !path.is_empty()) {
+        return vec![PathBuf::from(path)];
+    }
+
+    let mut paths = Vec::new();
+
+    // 1. Windows standard: %APPDATA%/openniri/config/config.toml
+    if let Some(proj_dirs) = directories::ProjectDirs::from("", "", "openniri") {
+        paths.push(proj_dirs.config_dir().join("config.toml"));
+    }
+
+    // 2. Unix-style: ~/.config/openniri/config.toml
+    if let Some(dirs) = directories::BaseDirs::new() {
+        paths.push(dirs.home_dir().join(".config").join("openniri").join("config.toml"));
+    }
+
+    // 3. Current directory: ./config.toml
+    paths.push(PathBuf::from("config.toml"));
+
+    paths
+}
+
+/// Suffix used to namespace per-session resources (pipe, state and log files).
+///
+/// Named pipes are machine-global, so a console and an RDP session on the same
+/// machine each need their own. Returns an empty suffix when the session is
+/// unknown. The instance from [`INSTANCE_ENV_VAR`], if any, is appended.
+pub fn session_suffix(session_id: Option<u32>) -> String {
+    let instance = std::env::var(INSTANCE_ENV_VAR).ok();
+    instance_suffix(session_id, instance.as_deref())
+}
+
+/// Suffix for a session and an optional instance name.
+pub fn instance_suffix(session_id: Option<u32>, instance: Option<&str>) -> String {
+    let mut suffix = session_id.map(|id| format!("-session-{}", id)).unwrap_or_default();
+    if let Some(instance) = instance.filter(|name| !name.is_empty()) {
+        suffix.push('-');
+        suffix.push_str(instance);
+    }
+    suffix
+}
+
+/// Prefix shared by all named pipe paths.
+const PIPE_PREFIX: &str = r"\\.\pipe\";
+
+/// Named pipe path for a user's daemon in the given session.
+///
+/// Named pipes are machine-global, so the pipe is namespaced by the user's
+/// SID as well as the session: two users (or one user in two sessions) each
+/// get their own daemon. Parts that are unknown are left out.
+pub fn user_pipe_name(user_sid: Option<&str>, session_id: Option<u32>, instance: Option<&str>) -> String {
+    let mut name = PIPE_NAME.to_string();
+    if let Some(sid) = user_sid.filter(|sid| !sid.is_empty()) {
+        name.push('-');
+        name.push_str(sid);
+    }
+    name.push_str(&instance_suffix(session_id, instance));
+    name
+}
+
+/// Named pipe path for the current user's daemon in the current session.
+///
+/// This is the default for both the daemon and the CLI; `--pipe-name`
+/// overrides it. The instance from [`INSTANCE_ENV_VAR`], if any, is appended.
+pub fn pipe_name_for_current_user() -> String {
+    let instance = std::env::var(INSTANCE_ENV_VAR).ok();
+    user_pipe_name(
+        identity::current_user_sid().as_deref(),
+        identity::current_session_id(),
+        instance.as_deref(),
+    )
+}
+
+/// Suffix for the state and log files of the daemon serving `pipe_name`.
+///
+/// Every daemon that can run next to another has a pipe of its own, so the
+/// files follow the pipe: the current user's default pipe gets its session
+/// and instance suffix (files are per user already, so the SID is left out),
+/// and any other pipe, e.g. a `--pipe-name` override, gets its own name.
+pub fn pipe_file_suffix(pipe_name: &str) -> String {
+    if pipe_name == pipe_name_for_current_user() {
+        session_suffix(identity::current_session_id())
+    } else {
+        override_file_suffix(pipe_name)
+    }
+}
+
+/// Name of the event the daemon serving `pipe_name` sets once it answers
+/// commands, so `openniri-cli --wait-for-daemon` can wait on it instead of
+/// polling the pipe.
+pub fn ready_event_name(pipe_name: &str) -> String {
+    format!("Local\\openniri-ready{}", pipe_file_suffix(pipe_name))
+}
+
+/// File suffix for a pipe other than the default: its name without the pipe
+/// prefix, with characters that can't appear in file names replaced.
+fn override_file_suffix(pipe_name: &str) -> String {
+    let name = pipe_name.strip_prefix(PIPE_PREFIX).unwrap_or(pipe_name);
+    let name: String = name
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' { c } else { '_' })
+        .collect();
+    format!("-{}", name)
+}
+
+/// Full pipe path for a `--pipe-name` override.
+///
+/// A bare name such as `openniri-test` is placed under `\\.\pipe\`; full
+/// paths are used as given.
+pub fn normalize_pipe_name(name: &str) -> String {
+    if name.starts_with(r"\\") {
+        name.to_string()
+    } else {
+        format!("{}{}", PIPE_PREFIX, name)
+    }
+}
+
 /// Maximum IPC message size (64 KiB). Messages larger than this are rejected.
 pub const MAX_IPC_MESSAGE_SIZE: usize = 64 * 1024;
 
@@ -50,11 +193,90 @@ pub struct WindowInfo {
     pub is_floating: bool,
     /// Whether this window currently has focus.
     pub is_focused: bool,
+    /// Whether this window requested attention and hasn't been focused since.
+    #[serde(default)]
+    pub is_urgent: bool,
+}
+
+/// Summary of one column for workspace queries.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ColumnSummary {
+    /// Column width in pixels.
+    pub width: i32,
+    /// Column width as a fraction of the usable viewport width.
+    pub width_fraction: f64,
+    /// Number of windows stacked in the column.
+    pub window_count: usize,
+    /// Whether the column shows one window at a time as tabs.
+    #[serde(default)]
+    pub tabbed: bool,
+    /// Whether any of the column's windows is on screen.
+    pub visible: bool,
+}
+
+/// How serious a config file problem is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticSeverity {
+    /// The file doesn't load, or a setting is dropped entirely.
+    Error,
+    /// The setting is ignored or adjusted, e.g. an unknown key or a clamped value.
+    Warning,
+}
+
+/// One problem found in a config file.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConfigDiagnostic {
+    /// How serious the problem is.
+    pub severity: DiagnosticSeverity,
+    /// Setting the problem is about, e.g. `layout.gap` or `window_rules[2].match_class`.
+    pub field: String,
+    /// What is wrong and what the daemon does about it.
+    pub message: String,
+    /// 1-based line of the setting in the file, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+}
+
+/// Work area edge a floating window can be snapped against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapEdge {
+    Left,
+    Right,
+    Top,
+    Bottom,
+}
+
+/// Where new windows open in the strip.
+///
+/// Also accepted with `-` in place of `_` (`"right-of-focus"`).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NewWindowPosition {
+    /// Right of the focused column.
+    #[default]
+    #[serde(alias = "right-of-focus")]
+    RightOfFocus,
+    /// After the last column.
+    #[serde(alias = "end-of-strip")]
+    EndOfStrip,
+    /// Before the first column.
+    Start,
+    /// Into empty space showing on screen, or right of the focused column
+    /// if there is none.
+    #[serde(alias = "nearest-empty")]
+    NearestEmpty,
 }
 
 /// Commands that can be sent from the CLI to the daemon.
+///
+/// The CLI and daemon can be different versions, so new commands may be added
+/// at any time: matches need a wildcard arm, and command types this build
+/// doesn't know deserialize as [`IpcCommand::Unknown`] instead of failing.
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
+#[non_exhaustive]
 pub enum IpcCommand {
     /// Focus the column to the left.
     FocusLeft,
@@ -64,20 +286,63 @@ pub enum IpcCommand {
     FocusUp,
     /// Focus the window below (in stacked columns).
     FocusDown,
+    /// Focus a managed window by ID, switching monitor or workspace and
+    /// scrolling its column into view as needed.
+    FocusWindowById {
+        /// Window ID as reported by [`IpcCommand::QueryAllWindows`].
+        window_id: u64,
+    },
+    /// Focus the previously focused window, across monitors and workspaces,
+    /// like Alt-Tab within the tiling layout.
+    FocusPrevious,
+    /// Focus the window that requested attention first, across monitors
+    /// and workspaces.
+    FocusUrgent,
 
     /// Move the focused column left.
     MoveColumnLeft,
     /// Move the focused column right.
     MoveColumnRight,
+    /// Move the focused column up (on vertical strips).
+    MoveColumnUp,
+    /// Move the focused column down (on vertical strips).
+    MoveColumnDown,
+    /// Stack the focused window into the column on its left, or, if it
+    /// shares its column, expel it into a new column on the left.
+    ConsumeWindowLeft,
+    /// Stack the focused window into the column on its right, or, if it
+    /// shares its column, expel it into a new column on the right.
+    ConsumeWindowRight,
+    /// Stack the focused window into the column above, or expel it into a
+    /// new column above (on vertical strips).
+    ConsumeWindowUp,
+    /// Stack the focused window into the column below, or expel it into a
+    /// new column below (on vertical strips).
+    ConsumeWindowDown,
+    /// Split the focused window off its stack into a new column on the right.
+    SplitColumn,
+    /// Swap the focused window with the top window of its stack.
+    PromoteWindow,
 
     /// Focus the monitor to the left.
     FocusMonitorLeft,
     /// Focus the monitor to the right.
     FocusMonitorRight,
+    /// Focus the monitor above.
+    FocusMonitorUp,
+    /// Focus the monitor below.
+    FocusMonitorDown,
     /// Move the focused window to the monitor on the left.
     MoveWindowToMonitorLeft,
     /// Move the focused window to the monitor on the right.
     MoveWindowToMonitorRight,
+    /// Move the focused column (with its whole stack) to the monitor on the left.
+    MoveColumnToMonitorLeft,
+    /// Move the focused column (with its whole stack) to the monitor on the right.
+    MoveColumnToMonitorRight,
+    /// Move the tiled columns of every other monitor onto the focused
+    /// monitor, keeping their left-to-right order.
+    GatherWindows,
 
     /// Resize the focused column.
     Resize {
@@ -85,6 +350,13 @@ pub enum IpcCommand {
         delta: i32,
     },
 
+    /// Resize the focused window within its column, taking the height from
+    /// or giving it to the windows stacked with it.
+    ResizeWindowVertical {
+        /// Height delta in pixels (positive to grow, negative to shrink).
+        delta: i32,
+    },
+
     /// Scroll the viewport.
     Scroll {
         /// Scroll delta (positive = right, negative = left).
@@ -98,12 +370,18 @@ pub enum IpcCommand {
 
     /// Re-enumerate windows and add new ones.
     Refresh,
+    /// Remove managed windows that no longer exist but never reported
+    /// their destruction (crashed processes, suspended apps).
+    Prune,
     /// Apply the current layout to windows.
     Apply,
     /// Reload configuration from file.
     Reload,
     /// Stop the daemon.
     Stop,
+    /// Uncloak every managed window and reset its border color, for windows
+    /// left hidden by a broken layout.
+    RestoreAll,
 
     /// Query detailed information about all managed windows.
     QueryAllWindows,
@@ -112,22 +390,331 @@ pub enum IpcCommand {
     CloseWindow,
     /// Toggle floating state for the focused window.
     ToggleFloating,
+    /// Move the focused floating window, keeping it inside the work area.
+    MoveFloating {
+        /// Horizontal offset in pixels (positive = right).
+        dx: i32,
+        /// Vertical offset in pixels (positive = down).
+        dy: i32,
+    },
+    /// Resize the focused floating window around its top-left corner.
+    ResizeFloating {
+        /// Width delta in pixels (positive to grow, negative to shrink).
+        dw: i32,
+        /// Height delta in pixels (positive to grow, negative to shrink).
+        dh: i32,
+    },
+    /// Center the focused floating window in the work area.
+    CenterFloating,
+    /// Move the focused floating window flush against a work area edge.
+    SnapFloating {
+        /// Edge to snap to.
+        edge: SnapEdge,
+    },
+    /// Keep the focused floating window above all other windows, or stop doing so.
+    SetAlwaysOnTop {
+        /// Whether the window stays on top.
+        enabled: bool,
+    },
+    /// Toggle always-on-top for the focused floating window.
+    ToggleAlwaysOnTop,
     /// Toggle fullscreen for the focused window.
     ToggleFullscreen,
+    /// Open the overview of the focused workspace, or close it and focus the
+    /// selected column.
+    ToggleOverview,
+    /// Resize the focused column and window interactively: the arrow keys
+    /// resize them while held, Enter keeps the new size and Escape restores
+    /// the old one. Sent again, keeps the new size.
+    EnterResizeMode,
     /// Set the focused column width as a fraction of the viewport.
     SetColumnWidth {
         /// Fraction of viewport width (e.g., 0.333, 0.5, 0.667).
         fraction: f64,
     },
+    /// Snap the focused column to the next configured width preset.
+    CycleColumnWidth,
     /// Equalize all column widths.
     EqualizeColumnWidths,
+    /// Toggle stretching columns to fill the viewport on the focused workspace.
+    ToggleAutoBalance,
+    /// Set where new windows open on the focused workspace, until the config
+    /// is reloaded.
+    SetNewWindowPosition {
+        /// Where new windows open.
+        position: NewWindowPosition,
+    },
+    /// Collapse the focused column to a thin placeholder.
+    CollapseColumn,
+    /// Expand the focused column and focus its window.
+    ExpandColumn,
+    /// Toggle tabbed display (one window at a time, full height) for the focused column.
+    ToggleColumnTabbed,
+    /// Toggle expanding the focused column to the viewport width, restoring
+    /// its previous width when toggled off. Unlike fullscreen, the column
+    /// keeps its gaps and title bar and stays tiled.
+    ToggleMaximizeColumn,
+    /// Pause or resume tiling on the focused monitor only.
+    TogglePauseMonitor,
     /// Query daemon status information.
     QueryStatus,
+    /// Query a pre-formatted summary line for each monitor.
+    ///
+    /// Intended for minimal bar integrations that cannot parse JSON.
+    QueryBarText {
+        /// Line template, e.g. `"{monitor} {col_idx}/{col_count} {title:30}"`.
+        format: String,
+    },
+    /// Query event pipeline counters (backlog and dropped events).
+    QueryMetrics,
+    /// Query animation frame statistics (missed frames, average frame time).
+    QueryPerfStats,
+    /// Check a config file for unknown keys, invalid hotkeys, conflicting
+    /// rules and out-of-range values.
+    CheckConfig {
+        /// Config file contents to check; the daemon's own config file if absent.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        contents: Option<String>,
+    },
+    /// Change the daemon's log level until it restarts or reloads its config.
+    SetLogLevel {
+        /// One of `trace`, `debug`, `info`, `warn`, `error` or `off`.
+        level: String,
+    },
+    /// Query the last lines of the daemon's log file.
+    QueryLogs {
+        /// Number of lines to return.
+        lines: usize,
+    },
+    /// Apply a project layout profile by name, launching its programs.
+    ApplyProject {
+        /// Profile name from the `[[projects]]` config.
+        name: String,
+    },
+    /// Save the column arrangement of every monitor as a named monitor
+    /// profile for the connected monitor set.
+    SaveMonitorProfile {
+        /// Profile name, e.g. "docked".
+        name: String,
+    },
+    /// Move open windows into a saved monitor profile's columns.
+    ApplyMonitorProfile {
+        /// Profile name, e.g. "docked".
+        name: String,
+    },
+    /// Switch the focused monitor to a named workspace, creating it if needed.
+    SwitchWorkspace {
+        /// Workspace name, e.g. "code".
+        name: String,
+    },
+    /// Move the focused window to a named workspace on the same monitor.
+    MoveWindowToWorkspace {
+        /// Workspace name, e.g. "chat".
+        name: String,
+    },
+    /// Show a scratchpad's window floating and centered on the focused
+    /// monitor, or hide it again.
+    ToggleScratchpad {
+        /// Scratchpad name, e.g. "term".
+        name: String,
+    },
+    /// Take the focused window out of tiling and hide it in a scratchpad.
+    MoveToScratchpad {
+        /// Scratchpad name, e.g. "term".
+        name: String,
+    },
+    /// Focus a column of the focused workspace by index.
+    FocusColumn {
+        /// Column index, 0-based as in [`IpcResponse::WorkspaceState`].
+        index: usize,
+    },
+    /// Move a column of the focused workspace to another index, shifting
+    /// the columns in between.
+    MoveColumnToIndex {
+        /// Index of the column to move, or the focused column if omitted.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        from: Option<usize>,
+        /// Index the column ends up at.
+        to: usize,
+    },
+    /// Move a managed window into an existing column of its workspace and focus it.
+    MoveWindowToColumn {
+        /// Window to move (HWND as u64).
+        window_id: u64,
+        /// Index of the target column, counted before the window leaves its own.
+        column: usize,
+        /// Index in the target column's stack, or the bottom if omitted.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        position: Option<usize>,
+    },
+    /// Report the daemon's protocol version and the commands it supports.
+    ///
+    /// Answered with [`IpcResponse::ServerInfo`], or with
+    /// [`IpcResponse::ProtocolMismatch`] if the protocol versions differ.
+    /// Daemons from before the handshake answer with
+    /// [`IpcResponse::UnsupportedCommand`].
+    ///
+    /// Like every command, it is only answered once the daemon has finished
+    /// starting up, so clients also send it to wait for a daemon to be ready.
+    Hello {
+        /// The client's [`PROTOCOL_VERSION`].
+        protocol_version: u32,
+    },
+    /// A command type this build doesn't know, e.g. from a newer CLI.
+    ///
+    /// Daemons answer it with [`IpcResponse::UnsupportedCommand`].
+    #[serde(other)]
+    Unknown,
+}
+
+impl IpcCommand {
+    /// Wire names of all commands this build knows, as reported in
+    /// [`IpcResponse::ServerInfo`].
+    pub const TYPES: &'static [&'static str] = &[
+        "focus_left",
+        "focus_right",
+        "focus_up",
+        "focus_down",
+        "focus_window_by_id",
+        "focus_previous",
+        "focus_urgent",
+        "move_column_left",
+        "move_column_right",
+        "move_column_up",
+        "move_column_down",
+        "consume_window_left",
+        "consume_window_right",
+        "consume_window_up",
+        "consume_window_down",
+        "split_column",
+        "promote_window",
+        "focus_monitor_left",
+        "focus_monitor_right",
+        "focus_monitor_up",
+        "focus_monitor_down",
+        "move_window_to_monitor_left",
+        "move_window_to_monitor_right",
+        "move_column_to_monitor_left",
+        "move_column_to_monitor_right",
+        "gather_windows",
+        "resize",
+        "resize_window_vertical",
+        "scroll",
+        "query_workspace",
+        "query_focused",
+        "query_all_windows",
+        "refresh",
+        "prune",
+        "apply",
+        "reload",
+        "stop",
+        "restore_all",
+        "close_window",
+        "toggle_floating",
+        "move_floating",
+        "resize_floating",
+        "center_floating",
+        "snap_floating",
+        "set_always_on_top",
+        "toggle_always_on_top",
+        "toggle_fullscreen",
+        "toggle_overview",
+        "enter_resize_mode",
+        "set_column_width",
+        "cycle_column_width",
+        "equalize_column_widths",
+        "toggle_auto_balance",
+        "set_new_window_position",
+        "collapse_column",
+        "expand_column",
+        "toggle_column_tabbed",
+        "toggle_maximize_column",
+        "toggle_pause_monitor",
+        "query_status",
+        "query_bar_text",
+        "query_metrics",
+        "query_perf_stats",
+        "check_config",
+        "set_log_level",
+        "query_logs",
+        "apply_project",
+        "save_monitor_profile",
+        "apply_monitor_profile",
+        "switch_workspace",
+        "move_window_to_workspace",
+        "toggle_scratchpad",
+        "move_to_scratchpad",
+        "focus_column",
+        "move_column_to_index",
+        "move_window_to_column",
+        "hello",
+    ];
+
+    /// Whether the command only reads daemon state.
+    pub fn is_query(&self) -> bool {
+        matches!(
+            self,
+            IpcCommand::QueryWorkspace
+                | IpcCommand::QueryFocused
+                | IpcCommand::QueryAllWindows
+                | IpcCommand::QueryStatus
+                | IpcCommand::QueryBarText { .. }
+                | IpcCommand::QueryMetrics
+                | IpcCommand::QueryPerfStats
+                | IpcCommand::CheckConfig { .. }
+                | IpcCommand::QueryLogs { .. }
+                | IpcCommand::Hello { .. }
+        )
+    }
+
+    /// Whether the command moves focus, windows or the viewport.
+    ///
+    /// These interrupt a running scroll animation so they act on what the
+    /// user currently sees rather than where the animation would end.
+    pub fn is_navigation(&self) -> bool {
+        matches!(
+            self,
+            IpcCommand::FocusLeft
+                | IpcCommand::FocusRight
+                | IpcCommand::FocusUp
+                | IpcCommand::FocusDown
+                | IpcCommand::FocusWindowById { .. }
+                | IpcCommand::FocusPrevious
+                | IpcCommand::FocusUrgent
+                | IpcCommand::MoveColumnLeft
+                | IpcCommand::MoveColumnRight
+                | IpcCommand::MoveColumnUp
+                | IpcCommand::MoveColumnDown
+                | IpcCommand::ConsumeWindowLeft
+                | IpcCommand::ConsumeWindowRight
+                | IpcCommand::ConsumeWindowUp
+                | IpcCommand::ConsumeWindowDown
+                | IpcCommand::SplitColumn
+                | IpcCommand::PromoteWindow
+                | IpcCommand::FocusMonitorLeft
+                | IpcCommand::FocusMonitorRight
+                | IpcCommand::FocusMonitorUp
+                | IpcCommand::FocusMonitorDown
+                | IpcCommand::MoveWindowToMonitorLeft
+                | IpcCommand::MoveWindowToMonitorRight
+                | IpcCommand::MoveColumnToMonitorLeft
+                | IpcCommand::MoveColumnToMonitorRight
+                | IpcCommand::GatherWindows
+                | IpcCommand::Scroll { .. }
+                | IpcCommand::FocusColumn { .. }
+                | IpcCommand::MoveColumnToIndex { .. }
+                | IpcCommand::MoveWindowToColumn { .. }
+        )
+    }
 }
 
 /// Responses from the daemon to the CLI.
+///
+/// Like [`IpcCommand`], new responses may be added at any time; response
+/// kinds this build doesn't know deserialize as [`IpcResponse::Unknown`].
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "status", rename_all = "snake_case")]
+#[non_exhaustive]
 pub enum IpcResponse {
     /// Command executed successfully.
     Ok,
@@ -150,6 +737,12 @@ pub enum IpcResponse {
         scroll_offset: f64,
         /// Total width of all columns.
         total_width: i32,
+        /// Name of the active workspace on the focused monitor.
+        #[serde(default)]
+        workspace_name: Option<String>,
+        /// Per-column details, left to right.
+        #[serde(default)]
+        columns_detail: Vec<ColumnSummary>,
     },
     /// Focused window query response.
     FocusedWindow {
@@ -183,7 +776,117 @@ pub enum IpcResponse {
         total_windows: usize,
         /// Daemon uptime in seconds.
         uptime_seconds: u64,
+        /// Logon session the daemon belongs to, if known.
+        #[serde(default)]
+        session_id: Option<u32>,
+        /// Project profile applied most recently, if any.
+        #[serde(default)]
+        active_project: Option<String>,
+        /// Features running in a reduced mode, with the reason
+        /// (e.g. snap hints without an overlay window).
+        #[serde(default)]
+        degraded: Vec<String>,
+        /// Whether the daemon runs in safe mode, without hooks, hotkeys or cloaking.
+        #[serde(default)]
+        safe_mode: bool,
+        /// Subsystems started by the daemon, e.g. "hotkeys" or "window_events".
+        #[serde(default)]
+        subsystems: Vec<String>,
+    },
+
+    /// Pre-formatted summary lines, one per monitor (left to right).
+    BarText {
+        /// Rendered lines in monitor order.
+        lines: Vec<String>,
+    },
+
+    /// Event pipeline counters.
+    Metrics {
+        /// Events queued in the WinEvent hook channel.
+        hook_backlog: usize,
+        /// Highest hook channel backlog since startup.
+        hook_backlog_peak: usize,
+        /// Capacity of the hook channel.
+        hook_capacity: usize,
+        /// Low-importance hook events dropped because the channel was full.
+        hook_events_dropped: u64,
+        /// Window location changes merged into a pending one for the same window.
+        #[serde(default)]
+        hook_events_coalesced: u64,
+        /// Location changes of unmanaged windows dropped before reaching the daemon.
+        #[serde(default)]
+        hook_events_unmanaged: u64,
+        /// Events waiting in the daemon's main event queue.
+        queue_backlog: usize,
+        /// Highest main queue backlog since startup.
+        queue_backlog_peak: usize,
+        /// Capacity of the main event queue.
+        queue_capacity: usize,
+    },
+
+    /// Answer to [`IpcCommand::QueryPerfStats`].
+    PerfStats {
+        /// Whether an animation is running.
+        animating: bool,
+        /// Whether frames are paced by DWM composition rather than a timer.
+        vblank_synced: bool,
+        /// Expected time between frames in milliseconds.
+        frame_period_ms: f64,
+        /// Animation frames applied since startup.
+        frames: u64,
+        /// Display refreshes that passed without an animation frame.
+        missed_frames: u64,
+        /// Average time between animation frames in milliseconds.
+        average_frame_ms: f64,
+    },
+
+    /// Answer to [`IpcCommand::Hello`].
+    ServerInfo {
+        /// Daemon version.
+        version: String,
+        /// The daemon's [`PROTOCOL_VERSION`].
+        protocol_version: u32,
+        /// Wire names of the commands the daemon supports.
+        capabilities: Vec<String>,
+    },
+
+    /// Problems found by [`IpcCommand::CheckConfig`], in file order.
+    ConfigDiagnostics {
+        /// File that was checked, or `None` for contents sent by the client
+        /// or when there is no config file.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        path: Option<String>,
+        /// Problems found; empty if the file is fine.
+        diagnostics: Vec<ConfigDiagnostic>,
+    },
+
+    /// Answer to [`IpcCommand::QueryLogs`], oldest line first.
+    Logs {
+        /// Log file the lines were read from, or `None` when logging to a
+        /// file is unavailable.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        path: Option<String>,
+        /// Log lines without line endings.
+        lines: Vec<String>,
+    },
+
+    /// The client and daemon speak incompatible protocol versions.
+    ProtocolMismatch {
+        /// Protocol version the client sent.
+        client_version: u32,
+        /// The daemon's protocol version.
+        server_version: u32,
+    },
+
+    /// The daemon doesn't know the command, usually because the CLI is newer.
+    UnsupportedCommand {
+        /// Command type as sent (e.g. `"focus_left"`), or `"unknown"` if it had none.
+        command: String,
     },
+
+    /// A response kind this build doesn't know, e.g. from a newer daemon.
+    #[serde(other)]
+    Unknown,
 }
 
 impl IpcResponse {
@@ -193,6 +896,41 @@ impl IpcResponse {
             message: message.into(),
         }
     }
+
+    /// Create the answer to a [`IpcCommand::Hello`] from a client speaking
+    /// `client_version`, for a daemon of version `version`.
+    pub fn hello(client_version: u32, version: impl Into<String>) -> Self {
+        if client_version != PROTOCOL_VERSION {
+            return Self::ProtocolMismatch {
+                client_version,
+                server_version: PROTOCOL_VERSION,
+            };
+        }
+        Self::ServerInfo {
+            version: version.into(),
+            protocol_version: PROTOCOL_VERSION,
+            capabilities: IpcCommand::TYPES.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+
+    /// Create the response for a command the daemon doesn't support.
+    ///
+    /// `raw_command` is the JSON as received, used to report its `type`.
+    pub fn unsupported_command(raw_command: &str) -> Self {
+        let command = serde_json::from_str::<serde_json::Value>(raw_command)
+            .ok()
+            .and_then(|value| value.get("type")?.as_str().map(str::to_string))
+            .unwrap_or_else(|| "unknown".to_string());
+        Self::UnsupportedCommand { command }
+    }
+
+    /// Whether the response reports a failure.
+    pub fn is_error(&self) -> bool {
+        matches!(
+            self,
+            IpcResponse::Error { .. } | IpcResponse::UnsupportedCommand { .. } | IpcResponse::ProtocolMismatch { .. }
+        )
+    }
 }
 
 #[cfg(test)]
@@ -239,15 +977,35 @@ mod tests {
             focused_window: 0,
             scroll_offset: 100.5,
             total_width: 2400,
+            workspace_name: Some("code".to_string()),
+            columns_detail: vec![ColumnSummary {
+                width: 960,
+                width_fraction: 0.5,
+                window_count: 2,
+                tabbed: false,
+                visible: true,
+            }],
         };
         let json = serde_json::to_string(&resp).unwrap();
         assert!(json.contains("workspace_state"));
         assert!(json.contains("\"columns\":3"));
+        assert!(json.contains("\"width_fraction\":0.5"));
 
         let resp2: IpcResponse = serde_json::from_str(&json).unwrap();
         assert_eq!(resp, resp2);
     }
 
+    #[test]
+    fn test_workspace_state_without_columns_detail() {
+        // Responses from daemons predating the per-column list still parse
+        let json = r#"{"status":"workspace_state","columns":1,"windows":1,"focused_column":0,"focused_window":0,"scroll_offset":0.0,"total_width":800}"#;
+        let resp: IpcResponse = serde_json::from_str(json).unwrap();
+        assert!(matches!(
+            resp,
+            IpcResponse::WorkspaceState { workspace_name: None, ref columns_detail, .. } if columns_detail.is_empty()
+        ));
+    }
+
     #[test]
     fn test_error_response() {
         let resp = IpcResponse::error("Something went wrong");
@@ -266,30 +1024,87 @@ mod tests {
             IpcCommand::FocusRight,
             IpcCommand::FocusUp,
             IpcCommand::FocusDown,
+            IpcCommand::FocusWindowById { window_id: 12345 },
+            IpcCommand::FocusPrevious,
+            IpcCommand::FocusUrgent,
             IpcCommand::MoveColumnLeft,
             IpcCommand::MoveColumnRight,
+            IpcCommand::MoveColumnUp,
+            IpcCommand::MoveColumnDown,
+            IpcCommand::ConsumeWindowLeft,
+            IpcCommand::ConsumeWindowRight,
+            IpcCommand::ConsumeWindowUp,
+            IpcCommand::ConsumeWindowDown,
+            IpcCommand::SplitColumn,
+            IpcCommand::PromoteWindow,
             IpcCommand::FocusMonitorLeft,
             IpcCommand::FocusMonitorRight,
+            IpcCommand::FocusMonitorUp,
+            IpcCommand::FocusMonitorDown,
             IpcCommand::MoveWindowToMonitorLeft,
             IpcCommand::MoveWindowToMonitorRight,
             IpcCommand::Resize { delta: 100 },
             IpcCommand::Resize { delta: -50 },
+            IpcCommand::ResizeWindowVertical { delta: 120 },
             IpcCommand::Scroll { delta: 150.5 },
             IpcCommand::Scroll { delta: -75.0 },
+            IpcCommand::MoveColumnToMonitorLeft,
+            IpcCommand::MoveColumnToMonitorRight,
+            IpcCommand::GatherWindows,
             IpcCommand::QueryWorkspace,
             IpcCommand::QueryFocused,
             IpcCommand::QueryAllWindows,
             IpcCommand::Refresh,
+            IpcCommand::Prune,
             IpcCommand::Apply,
             IpcCommand::Reload,
             IpcCommand::Stop,
+            IpcCommand::RestoreAll,
             IpcCommand::CloseWindow,
             IpcCommand::ToggleFloating,
+            IpcCommand::MoveFloating { dx: -50, dy: 20 },
+            IpcCommand::ResizeFloating { dw: 100, dh: -100 },
+            IpcCommand::CenterFloating,
+            IpcCommand::SnapFloating { edge: SnapEdge::Left },
+            IpcCommand::SnapFloating { edge: SnapEdge::Bottom },
+            IpcCommand::SetAlwaysOnTop { enabled: true },
+            IpcCommand::ToggleAlwaysOnTop,
             IpcCommand::ToggleFullscreen,
+            IpcCommand::ToggleOverview,
+            IpcCommand::EnterResizeMode,
             IpcCommand::SetColumnWidth { fraction: 0.5 },
             IpcCommand::SetColumnWidth { fraction: 0.333 },
+            IpcCommand::CycleColumnWidth,
             IpcCommand::EqualizeColumnWidths,
+            IpcCommand::ToggleAutoBalance,
+            IpcCommand::SetNewWindowPosition { position: NewWindowPosition::NearestEmpty },
+            IpcCommand::CollapseColumn,
+            IpcCommand::ExpandColumn,
+            IpcCommand::ToggleColumnTabbed,
+            IpcCommand::ToggleMaximizeColumn,
+            IpcCommand::TogglePauseMonitor,
             IpcCommand::QueryStatus,
+            IpcCommand::QueryBarText { format: "{monitor} {col_idx}/{col_count} {title:30}".to_string() },
+            IpcCommand::QueryMetrics,
+            IpcCommand::QueryPerfStats,
+            IpcCommand::CheckConfig { contents: None },
+            IpcCommand::CheckConfig { contents: Some("[layout]\ngap = 5\n".to_string()) },
+            IpcCommand::SetLogLevel { level: "debug".to_string() },
+            IpcCommand::QueryLogs { lines: 200 },
+            IpcCommand::ApplyProject { name: "web".to_string() },
+            IpcCommand::SaveMonitorProfile { name: "docked".to_string() },
+            IpcCommand::ApplyMonitorProfile { name: "laptop-only".to_string() },
+            IpcCommand::SwitchWorkspace { name: "code".to_string() },
+            IpcCommand::MoveWindowToWorkspace { name: "chat".to_string() },
+            IpcCommand::ToggleScratchpad { name: "term".to_string() },
+            IpcCommand::MoveToScratchpad { name: "term".to_string() },
+            IpcCommand::FocusColumn { index: 3 },
+            IpcCommand::MoveColumnToIndex { from: Some(0), to: 2 },
+            IpcCommand::MoveColumnToIndex { from: None, to: 0 },
+            IpcCommand::MoveWindowToColumn { window_id: 42, column: 1, position: Some(0) },
+            IpcCommand::MoveWindowToColumn { window_id: 42, column: 1, position: None },
+            IpcCommand::Hello { protocol_version: PROTOCOL_VERSION },
+            IpcCommand::Unknown,
         ];
 
         for cmd in commands {
@@ -315,6 +1130,8 @@ mod tests {
                 focused_window: 1,
                 scroll_offset: 200.0,
                 total_width: 4000,
+                workspace_name: None,
+                columns_detail: vec![],
             },
             IpcResponse::FocusedWindow {
                 window_id: Some(12345),
@@ -339,6 +1156,7 @@ mod tests {
                     monitor_id: 1,
                     is_floating: false,
                     is_focused: true,
+                    is_urgent: false,
                 }],
             },
             IpcResponse::WindowList {
@@ -357,6 +1175,7 @@ mod tests {
                     monitor_id: 2,
                     is_floating: false,
                     is_focused: true,
+                    is_urgent: false,
                 }),
             },
             IpcResponse::FocusedWindowInfo {
@@ -367,7 +1186,69 @@ mod tests {
                 monitors: 2,
                 total_windows: 5,
                 uptime_seconds: 3600,
+                session_id: Some(2),
+                active_project: Some("web".to_string()),
+                degraded: vec!["snap hints: overlay unavailable".to_string()],
+                safe_mode: true,
+                subsystems: vec!["ipc".to_string(), "window_enumeration".to_string()],
+            },
+            IpcResponse::BarText {
+                lines: vec!["1 2/3 Editor".to_string(), "2 0/0 ".to_string()],
+            },
+            IpcResponse::Metrics {
+                hook_backlog: 3,
+                hook_backlog_peak: 512,
+                hook_capacity: 1024,
+                hook_events_dropped: 40,
+                hook_events_coalesced: 900,
+                hook_events_unmanaged: 1200,
+                queue_backlog: 0,
+                queue_backlog_peak: 17,
+                queue_capacity: 100,
+            },
+            IpcResponse::PerfStats {
+                animating: false,
+                vblank_synced: true,
+                frame_period_ms: 16.667,
+                frames: 1200,
+                missed_frames: 3,
+                average_frame_ms: 16.9,
             },
+            IpcResponse::ServerInfo {
+                version: "0.2.0".to_string(),
+                protocol_version: 1,
+                capabilities: vec!["focus_left".to_string(), "hello".to_string()],
+            },
+            IpcResponse::ConfigDiagnostics {
+                path: Some("C:\\Users\\me\\AppData\\Roaming\\openniri\\config\\config.toml".to_string()),
+                diagnostics: vec![
+                    ConfigDiagnostic {
+                        severity: DiagnosticSeverity::Warning,
+                        field: "layout.gapp".to_string(),
+                        message: "unknown key `gapp`; did you mean `gap`?".to_string(),
+                        line: Some(3),
+                    },
+                    ConfigDiagnostic {
+                        severity: DiagnosticSeverity::Error,
+                        field: "hotkeys.Win+Foo".to_string(),
+                        message: "invalid hotkey".to_string(),
+                        line: None,
+                    },
+                ],
+            },
+            IpcResponse::Logs {
+                path: Some("C:\\Users\\me\\AppData\\Local\\openniri\\data\\logs\\openniri.log".to_string()),
+                lines: vec!["2026-01-01T00:00:00Z  INFO openniri: OpenNiri daemon starting...".to_string()],
+            },
+            IpcResponse::Logs { path: None, lines: Vec::new() },
+            IpcResponse::ProtocolMismatch {
+                client_version: 2,
+                server_version: 1,
+            },
+            IpcResponse::UnsupportedCommand {
+                command: "focus_window_by_title".to_string(),
+            },
+            IpcResponse::Unknown,
         ];
 
         for resp in responses {
@@ -392,6 +1273,7 @@ mod tests {
             monitor_id: 1,
             is_floating: false,
             is_focused: true,
+            is_urgent: false,
         };
 
         let json = serde_json::to_string(&info).unwrap();
@@ -424,6 +1306,7 @@ mod tests {
                 monitor_id: 1,
                 is_floating: false,
                 is_focused: true,
+                is_urgent: false,
             }],
         };
 
@@ -452,6 +1335,8 @@ mod tests {
             focused_window: 0,
             scroll_offset: 0.0,
             total_width: 1600,
+            workspace_name: None,
+            columns_detail: vec![],
         };
         let wire_format = serde_json::to_string(&resp).unwrap() + "\n";
         let parsed: IpcResponse = serde_json::from_str(wire_format.trim()).unwrap();
@@ -464,13 +1349,239 @@ mod tests {
         let result: Result<IpcCommand, _> = serde_json::from_str("not valid json");
         assert!(result.is_err());
 
-        let result: Result<IpcCommand, _> = serde_json::from_str("{\"type\": \"unknown_command\"}");
+        // A message without a type tag is still malformed
+        let result: Result<IpcCommand, _> = serde_json::from_str("{\"delta\": 5}");
         assert!(result.is_err());
 
-        let result: Result<IpcResponse, _> = serde_json::from_str("{\"status\": \"invalid\"}");
+        // Known types with missing fields don't fall back to Unknown
+        let result: Result<IpcCommand, _> = serde_json::from_str("{\"type\": \"resize\"}");
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_unknown_types_from_newer_versions() {
+        let cmd: IpcCommand =
+            serde_json::from_str(r#"{"type":"focus_window_by_title","title":"Inbox"}"#).unwrap();
+        assert_eq!(cmd, IpcCommand::Unknown);
+
+        let resp: IpcResponse = serde_json::from_str(r#"{"status":"layout_tree","columns":[]}"#).unwrap();
+        assert_eq!(resp, IpcResponse::Unknown);
+        assert!(!resp.is_error());
+    }
+
+    #[test]
+    fn test_unsupported_command_response() {
+        let resp = IpcResponse::unsupported_command(r#"{"type":"focus_window_by_title","title":"Inbox"}"#);
+        assert_eq!(resp, IpcResponse::UnsupportedCommand { command: "focus_window_by_title".to_string() });
+        assert!(resp.is_error());
+        assert_eq!(
+            serde_json::to_string(&resp).unwrap(),
+            r#"{"status":"unsupported_command","command":"focus_window_by_title"}"#
+        );
+
+        let resp = IpcResponse::unsupported_command("{}");
+        assert_eq!(resp, IpcResponse::UnsupportedCommand { command: "unknown".to_string() });
+    }
+
+    #[test]
+    fn test_index_commands_default_to_focused_column_and_bottom() {
+        let cmd: IpcCommand = serde_json::from_str(r#"{"type":"move_column_to_index","to":2}"#).unwrap();
+        assert_eq!(cmd, IpcCommand::MoveColumnToIndex { from: None, to: 2 });
+        assert_eq!(serde_json::to_string(&cmd).unwrap(), r#"{"type":"move_column_to_index","to":2}"#);
+        let cmd: IpcCommand =
+            serde_json::from_str(r#"{"type":"move_window_to_column","window_id":7,"column":0}"#).unwrap();
+        assert_eq!(cmd, IpcCommand::MoveWindowToColumn { window_id: 7, column: 0, position: None });
+    }
+
+    #[test]
+    fn test_command_types_cover_all_commands() {
+        for name in IpcCommand::TYPES {
+            // Commands with fields fail to parse without them, but never as Unknown
+            let parsed = serde_json::from_str::<IpcCommand>(&format!(r#"{{"type":"{}"}}"#, name));
+            assert!(!matches!(parsed, Ok(IpcCommand::Unknown)), "{} is not a command", name);
+        }
+
+        let mut names = IpcCommand::TYPES.to_vec();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), IpcCommand::TYPES.len());
+    }
+
+    #[test]
+    fn test_hello_handshake() {
+        match IpcResponse::hello(PROTOCOL_VERSION, "0.2.0") {
+            IpcResponse::ServerInfo { version, protocol_version, capabilities } => {
+                assert_eq!(version, "0.2.0");
+                assert_eq!(protocol_version, PROTOCOL_VERSION);
+                assert!(capabilities.iter().any(|c| c == "focus_left"));
+                assert!(capabilities.iter().any(|c| c == "hello"));
+            }
+            other => panic!("Expected ServerInfo, got {:?}", other),
+        }
+
+        let mismatch = IpcResponse::hello(PROTOCOL_VERSION + 1, "0.2.0");
+        assert_eq!(
+            mismatch,
+            IpcResponse::ProtocolMismatch {
+                client_version: PROTOCOL_VERSION + 1,
+                server_version: PROTOCOL_VERSION,
+            }
+        );
+        assert!(mismatch.is_error());
+
+        // Daemons from before the handshake report it as unsupported
+        assert_eq!(
+            IpcResponse::unsupported_command(r#"{"type":"hello","protocol_version":1}"#),
+            IpcResponse::UnsupportedCommand { command: "hello".to_string() }
+        );
+    }
+
+    #[test]
+    fn test_command_wire_format_is_stable() {
+        // Older and newer CLIs send exactly these strings; changing one breaks
+        // compatibility with already installed daemons
+        let cases = vec![
+            (IpcCommand::FocusLeft, r#"{"type":"focus_left"}"#),
+            (IpcCommand::FocusRight, r#"{"type":"focus_right"}"#),
+            (IpcCommand::FocusUp, r#"{"type":"focus_up"}"#),
+            (IpcCommand::FocusDown, r#"{"type":"focus_down"}"#),
+            (IpcCommand::FocusWindowById { window_id: 42 }, r#"{"type":"focus_window_by_id","window_id":42}"#),
+            (IpcCommand::FocusPrevious, r#"{"type":"focus_previous"}"#),
+            (IpcCommand::FocusUrgent, r#"{"type":"focus_urgent"}"#),
+            (IpcCommand::MoveColumnLeft, r#"{"type":"move_column_left"}"#),
+            (IpcCommand::MoveColumnRight, r#"{"type":"move_column_right"}"#),
+            (IpcCommand::ConsumeWindowLeft, r#"{"type":"consume_window_left"}"#),
+            (IpcCommand::ConsumeWindowRight, r#"{"type":"consume_window_right"}"#),
+            (IpcCommand::MoveColumnDown, r#"{"type":"move_column_down"}"#),
+            (IpcCommand::ConsumeWindowUp, r#"{"type":"consume_window_up"}"#),
+            (IpcCommand::SplitColumn, r#"{"type":"split_column"}"#),
+            (IpcCommand::PromoteWindow, r#"{"type":"promote_window"}"#),
+            (IpcCommand::FocusMonitorLeft, r#"{"type":"focus_monitor_left"}"#),
+            (IpcCommand::FocusMonitorRight, r#"{"type":"focus_monitor_right"}"#),
+            (IpcCommand::FocusMonitorUp, r#"{"type":"focus_monitor_up"}"#),
+            (IpcCommand::FocusMonitorDown, r#"{"type":"focus_monitor_down"}"#),
+            (IpcCommand::MoveWindowToMonitorLeft, r#"{"type":"move_window_to_monitor_left"}"#),
+            (IpcCommand::MoveWindowToMonitorRight, r#"{"type":"move_window_to_monitor_right"}"#),
+            (IpcCommand::MoveColumnToMonitorLeft, r#"{"type":"move_column_to_monitor_left"}"#),
+            (IpcCommand::MoveColumnToMonitorRight, r#"{"type":"move_column_to_monitor_right"}"#),
+            (IpcCommand::GatherWindows, r#"{"type":"gather_windows"}"#),
+            (IpcCommand::Resize { delta: -50 }, r#"{"type":"resize","delta":-50}"#),
+            (IpcCommand::ResizeWindowVertical { delta: -50 }, r#"{"type":"resize_window_vertical","delta":-50}"#),
+            (IpcCommand::Scroll { delta: 1.5 }, r#"{"type":"scroll","delta":1.5}"#),
+            (IpcCommand::QueryWorkspace, r#"{"type":"query_workspace"}"#),
+            (IpcCommand::QueryFocused, r#"{"type":"query_focused"}"#),
+            (IpcCommand::Refresh, r#"{"type":"refresh"}"#),
+            (IpcCommand::Prune, r#"{"type":"prune"}"#),
+            (IpcCommand::Apply, r#"{"type":"apply"}"#),
+            (IpcCommand::Reload, r#"{"type":"reload"}"#),
+            (IpcCommand::Stop, r#"{"type":"stop"}"#),
+            (IpcCommand::RestoreAll, r#"{"type":"restore_all"}"#),
+            (IpcCommand::QueryAllWindows, r#"{"type":"query_all_windows"}"#),
+            (IpcCommand::CloseWindow, r#"{"type":"close_window"}"#),
+            (IpcCommand::ToggleFloating, r#"{"type":"toggle_floating"}"#),
+            (IpcCommand::MoveFloating { dx: -50, dy: 20 }, r#"{"type":"move_floating","dx":-50,"dy":20}"#),
+            (IpcCommand::ResizeFloating { dw: 100, dh: -100 }, r#"{"type":"resize_floating","dw":100,"dh":-100}"#),
+            (IpcCommand::CenterFloating, r#"{"type":"center_floating"}"#),
+            (IpcCommand::SnapFloating { edge: SnapEdge::Right }, r#"{"type":"snap_floating","edge":"right"}"#),
+            (IpcCommand::SetAlwaysOnTop { enabled: false }, r#"{"type":"set_always_on_top","enabled":false}"#),
+            (IpcCommand::ToggleAlwaysOnTop, r#"{"type":"toggle_always_on_top"}"#),
+            (IpcCommand::ToggleFullscreen, r#"{"type":"toggle_fullscreen"}"#),
+            (IpcCommand::ToggleOverview, r#"{"type":"toggle_overview"}"#),
+            (IpcCommand::EnterResizeMode, r#"{"type":"enter_resize_mode"}"#),
+            (IpcCommand::SetColumnWidth { fraction: 0.5 }, r#"{"type":"set_column_width","fraction":0.5}"#),
+            (IpcCommand::CycleColumnWidth, r#"{"type":"cycle_column_width"}"#),
+            (IpcCommand::EqualizeColumnWidths, r#"{"type":"equalize_column_widths"}"#),
+            (IpcCommand::ToggleAutoBalance, r#"{"type":"toggle_auto_balance"}"#),
+            (
+                IpcCommand::SetNewWindowPosition { position: NewWindowPosition::EndOfStrip },
+                r#"{"type":"set_new_window_position","position":"end_of_strip"}"#,
+            ),
+            (IpcCommand::CollapseColumn, r#"{"type":"collapse_column"}"#),
+            (IpcCommand::ExpandColumn, r#"{"type":"expand_column"}"#),
+            (IpcCommand::ToggleColumnTabbed, r#"{"type":"toggle_column_tabbed"}"#),
+            (IpcCommand::ToggleMaximizeColumn, r#"{"type":"toggle_maximize_column"}"#),
+            (IpcCommand::TogglePauseMonitor, r#"{"type":"toggle_pause_monitor"}"#),
+            (IpcCommand::QueryStatus, r#"{"type":"query_status"}"#),
+            (IpcCommand::QueryBarText { format: "{title}".to_string() }, r#"{"type":"query_bar_text","format":"{title}"}"#),
+            (IpcCommand::QueryMetrics, r#"{"type":"query_metrics"}"#),
+            (IpcCommand::QueryPerfStats, r#"{"type":"query_perf_stats"}"#),
+            (IpcCommand::CheckConfig { contents: None }, r#"{"type":"check_config"}"#),
+            (IpcCommand::SetLogLevel { level: "debug".to_string() }, r#"{"type":"set_log_level","level":"debug"}"#),
+            (IpcCommand::QueryLogs { lines: 50 }, r#"{"type":"query_logs","lines":50}"#),
+            (IpcCommand::ApplyProject { name: "web".to_string() }, r#"{"type":"apply_project","name":"web"}"#),
+            (
+                IpcCommand::SaveMonitorProfile { name: "docked".to_string() },
+                r#"{"type":"save_monitor_profile","name":"docked"}"#,
+            ),
+            (
+                IpcCommand::ApplyMonitorProfile { name: "docked".to_string() },
+                r#"{"type":"apply_monitor_profile","name":"docked"}"#,
+            ),
+            (IpcCommand::SwitchWorkspace { name: "code".to_string() }, r#"{"type":"switch_workspace","name":"code"}"#),
+            (
+                IpcCommand::MoveWindowToWorkspace { name: "chat".to_string() },
+                r#"{"type":"move_window_to_workspace","name":"chat"}"#,
+            ),
+            (IpcCommand::ToggleScratchpad { name: "term".to_string() }, r#"{"type":"toggle_scratchpad","name":"term"}"#),
+            (
+                IpcCommand::MoveToScratchpad { name: "term".to_string() },
+                r#"{"type":"move_to_scratchpad","name":"term"}"#,
+            ),
+            (IpcCommand::FocusColumn { index: 0 }, r#"{"type":"focus_column","index":0}"#),
+            (
+                IpcCommand::MoveColumnToIndex { from: Some(1), to: 3 },
+                r#"{"type":"move_column_to_index","from":1,"to":3}"#,
+            ),
+            (
+                IpcCommand::MoveWindowToColumn { window_id: 42, column: 2, position: Some(1) },
+                r#"{"type":"move_window_to_column","window_id":42,"column":2,"position":1}"#,
+            ),
+            (IpcCommand::Hello { protocol_version: 1 }, r#"{"type":"hello","protocol_version":1}"#),
+        ];
+
+        for (cmd, wire) in cases {
+            assert_eq!(serde_json::to_string(&cmd).unwrap(), wire);
+            assert_eq!(serde_json::from_str::<IpcCommand>(wire).unwrap(), cmd);
+        }
+    }
+
+    #[test]
+    fn test_responses_from_older_daemons_parse() {
+        // Minimal responses as sent before optional fields were added
+        let status: IpcResponse = serde_json::from_str(
+            r#"{"status":"status_info","version":"0.1.0","monitors":1,"total_windows":2,"uptime_seconds":5}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            status,
+            IpcResponse::StatusInfo { session_id: None, active_project: None, ref degraded, safe_mode: false, ref subsystems, .. }
+                if degraded.is_empty() && subsystems.is_empty()
+        ));
+
+        let column: ColumnSummary =
+            serde_json::from_str(r#"{"width":800,"width_fraction":0.5,"window_count":1,"visible":true}"#).unwrap();
+        assert!(!column.tabbed);
+
+        let window: WindowInfo = serde_json::from_str(
+            r#"{"window_id":1,"title":"","class_name":"","process_id":0,"executable":"","rect":{"x":0,"y":0,"width":800,"height":600},"column_index":0,"window_index":0,"monitor_id":1,"is_floating":false,"is_focused":true}"#,
+        )
+        .unwrap();
+        assert!(!window.is_urgent);
+
+        let metrics: IpcResponse = serde_json::from_str(
+            r#"{"status":"metrics","hook_backlog":0,"hook_backlog_peak":3,"hook_capacity":1024,"hook_events_dropped":0,"queue_backlog":0,"queue_backlog_peak":1,"queue_capacity":100}"#,
+        )
+        .unwrap();
+        assert!(matches!(
+            metrics,
+            IpcResponse::Metrics { hook_events_coalesced: 0, hook_events_unmanaged: 0, .. }
+        ));
+
+        // Fields added by newer daemons are ignored
+        let ok: IpcResponse = serde_json::from_str(r#"{"status":"ok","request_id":7}"#).unwrap();
+        assert_eq!(ok, IpcResponse::Ok);
+    }
+
     #[test]
     fn test_pipe_name_format() {
         // Verify pipe name follows Windows named pipe convention
@@ -478,6 +1589,68 @@ mod tests {
         assert_eq!(PIPE_NAME, r"\\.\pipe\openniri");
     }
 
+    #[test]
+    fn test_pipe_file_suffix() {
+        assert_eq!(
+            pipe_file_suffix(&pipe_name_for_current_user()),
+            session_suffix(identity::current_session_id())
+        );
+        assert_eq!(pipe_file_suffix(r"\\.\pipe\openniri-test"), "-openniri-test");
+        assert_eq!(override_file_suffix(r"\\.\pipe\a/b:c"), "-a_b_c");
+        assert_ne!(pipe_file_suffix(r"\\.\pipe\openniri-test"), pipe_file_suffix(&pipe_name_for_current_user()));
+    }
+
+    #[test]
+    fn test_ready_event_name() {
+        assert_eq!(ready_event_name(r"\\.\pipe\openniri-test"), r"Local\openniri-ready-openniri-test");
+        assert_ne!(ready_event_name(r"\\.\pipe\openniri-test"), ready_event_name(&pipe_name_for_current_user()));
+    }
+
+    #[test]
+    fn test_user_pipe_name() {
+        assert_eq!(user_pipe_name(None, None, None), PIPE_NAME);
+        assert_eq!(user_pipe_name(None, Some(0), None), r"\\.\pipe\openniri-session-0");
+        assert_eq!(
+            user_pipe_name(Some("S-1-5-21-1-2-3-1001"), Some(3), None),
+            r"\\.\pipe\openniri-S-1-5-21-1-2-3-1001-session-3"
+        );
+        assert_eq!(
+            user_pipe_name(Some("S-1-5-21-1-2-3-1001"), Some(3), Some("test")),
+            r"\\.\pipe\openniri-S-1-5-21-1-2-3-1001-session-3-test"
+        );
+        assert_eq!(user_pipe_name(Some(""), Some(1), None), r"\\.\pipe\openniri-session-1");
+        assert_ne!(user_pipe_name(None, Some(1), None), user_pipe_name(None, Some(2), None));
+        assert_ne!(
+            user_pipe_name(Some("S-1-5-21-1-2-3-1001"), Some(1), None),
+            user_pipe_name(Some("S-1-5-21-1-2-3-1002"), Some(1), None)
+        );
+    }
+
+    #[test]
+    fn test_normalize_pipe_name() {
+        assert_eq!(normalize_pipe_name("openniri-test"), r"\\.\pipe\openniri-test");
+        assert_eq!(normalize_pipe_name(r"\\.\pipe\custom"), r"\\.\pipe\custom");
+        assert!(normalize_pipe_name("x").starts_with(PIPE_PREFIX));
+    }
+
+    #[test]
+    fn test_instance_suffix() {
+        assert_eq!(instance_suffix(Some(1), None), "-session-1");
+        assert_eq!(instance_suffix(Some(1), Some("")), "-session-1");
+        assert_eq!(instance_suffix(Some(1), Some("e2e-7")), "-session-1-e2e-7");
+        assert_eq!(instance_suffix(None, Some("e2e-7")), "-e2e-7");
+    }
+
+    #[test]
+    fn test_status_info_without_session_id_deserializes() {
+        let json = r#"{"status":"status_info","version":"0.1.0","monitors":1,"total_windows":0,"uptime_seconds":5}"#;
+        let resp: IpcResponse = serde_json::from_str(json).unwrap();
+        assert!(matches!(
+            resp,
+            IpcResponse::StatusInfo { session_id: None, active_project: None, ref degraded, .. } if degraded.is_empty()
+        ));
+    }
+
     #[test]
     fn test_max_message_size_defined() {
         const { assert!(MAX_IPC_MESSAGE_SIZE > 0) };
@@ -489,4 +1662,42 @@ mod tests {
         const { assert!(MAX_IPC_MESSAGE_SIZE >= 1024) };
         const { assert!(MAX_IPC_MESSAGE_SIZE <= 1024 * 1024) };
     }
+
+    #[test]
+    fn test_new_window_position_accepts_dashes() {
+        let position: NewWindowPosition = serde_json::from_str(r#""end-of-strip""#).unwrap();
+        assert_eq!(position, NewWindowPosition::EndOfStrip);
+        let position: NewWindowPosition = serde_json::from_str(r#""nearest_empty""#).unwrap();
+        assert_eq!(position, NewWindowPosition::NearestEmpty);
+        assert_eq!(serde_json::to_string(&NewWindowPosition::RightOfFocus).unwrap(), r#""right_of_focus""#);
+    }
+
+    #[test]
+    fn test_is_query() {
+        assert!(IpcCommand::QueryWorkspace.is_query());
+        assert!(IpcCommand::QueryBarText { format: "{title}".to_string() }.is_query());
+        assert!(!IpcCommand::FocusLeft.is_query());
+        assert!(!IpcCommand::Reload.is_query());
+        assert!(!IpcCommand::RestoreAll.is_query());
+        assert!(!IpcCommand::EnterResizeMode.is_query());
+        assert!(IpcCommand::Hello { protocol_version: PROTOCOL_VERSION }.is_query());
+        assert!(IpcCommand::CheckConfig { contents: None }.is_query());
+        assert!(IpcCommand::QueryLogs { lines: 10 }.is_query());
+        assert!(IpcCommand::QueryPerfStats.is_query());
+        assert!(!IpcCommand::SetLogLevel { level: "debug".to_string() }.is_query());
+    }
+
+    #[test]
+    fn test_is_navigation() {
+        assert!(IpcCommand::FocusLeft.is_navigation());
+        assert!(IpcCommand::MoveColumnToMonitorRight.is_navigation());
+        assert!(IpcCommand::FocusWindowById { window_id: 1 }.is_navigation());
+        assert!(IpcCommand::FocusPrevious.is_navigation());
+        assert!(IpcCommand::FocusUrgent.is_navigation());
+        assert!(IpcCommand::Scroll { delta: 10.0 }.is_navigation());
+        assert!(IpcCommand::FocusColumn { index: 0 }.is_navigation());
+        assert!(IpcCommand::SplitColumn.is_navigation());
+        assert!(!IpcCommand::QueryStatus.is_navigation());
+        assert!(!IpcCommand::ToggleFloating.is_navigation());
+    }
 }
@@ -0,0 +1,138 @@
+//! Text helpers for window titles shown in bars, overlays and the tray.
+//!
+//! Titles are truncated by grapheme cluster rather than by `char`, so an
+//! emoji sequence, a flag or a letter with combining marks is never split
+//! into a broken glyph.
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Ellipsis appended to truncated text.
+pub const ELLIPSIS: char = '…';
+
+/// Number of user-perceived characters (grapheme clusters) in a string.
+pub fn grapheme_count(value: &str) -> usize {
+    value.graphemes(true).count()
+}
+
+/// Truncate to at most `max_graphemes` grapheme clusters, marking cuts with an ellipsis.
+///
+/// The ellipsis counts towards the limit. Text that already fits is
+/// returned unchanged.
+pub fn truncate_with_ellipsis(value: &str, max_graphemes: usize) -> String {
+    if max_graphemes == 0 {
+        return String::new();
+    }
+    let mut graphemes = value.grapheme_indices(true);
+    let Some((cut, _)) = graphemes.nth(max_graphemes - 1) else {
+        return value.to_string();
+    };
+    if graphemes.next().is_none() {
+        // Exactly max_graphemes long
+        return value.to_string();
+    }
+    let mut truncated = value[..cut].to_string();
+    truncated.push(ELLIPSIS);
+    truncated
+}
+
+/// Truncate to at most `max_units` UTF-16 code units, for fixed-size Win32
+/// buffers such as tray tooltips.
+///
+/// Cuts at a grapheme boundary and marks cuts with an ellipsis, so surrogate
+/// pairs are never split.
+pub fn truncate_utf16_with_ellipsis(value: &str, max_units: usize) -> String {
+    if value.encode_utf16().count() <= max_units {
+        return value.to_string();
+    }
+    if max_units < ELLIPSIS.len_utf16() {
+        return String::new();
+    }
+    let budget = max_units - ELLIPSIS.len_utf16();
+    let mut used = 0;
+    let mut end = 0;
+    for (index, grapheme) in value.grapheme_indices(true) {
+        let units = grapheme.encode_utf16().count();
+        if used + units > budget {
+            break;
+        }
+        used += units;
+        end = index + grapheme.len();
+    }
+    let mut truncated = value[..end].to_string();
+    truncated.push(ELLIPSIS);
+    truncated
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Family emoji: four people joined by zero-width joiners, one grapheme.
+    const FAMILY: &str = "👨\u{200D}👩\u{200D}👧\u{200D}👦";
+
+    #[test]
+    fn test_short_text_is_unchanged() {
+        assert_eq!(truncate_with_ellipsis("Notepad", 7), "Notepad");
+        assert_eq!(truncate_with_ellipsis("Notepad", 20), "Notepad");
+        assert_eq!(truncate_with_ellipsis("", 3), "");
+    }
+
+    #[test]
+    fn test_truncate_ascii() {
+        assert_eq!(truncate_with_ellipsis("Visual Studio Code", 6), "Visua…");
+        assert_eq!(truncate_with_ellipsis("abc", 0), "");
+        assert_eq!(truncate_with_ellipsis("abc", 1), "…");
+    }
+
+    #[test]
+    fn test_truncate_keeps_emoji_sequences_whole() {
+        let title = format!("{FAMILY}🇩🇪 Photos");
+        assert_eq!(grapheme_count(&title), 9);
+        assert_eq!(truncate_with_ellipsis(&title, 2), format!("{FAMILY}…"));
+        assert_eq!(truncate_with_ellipsis(&title, 3), format!("{FAMILY}🇩🇪…"));
+    }
+
+    #[test]
+    fn test_truncate_cjk_and_combining_marks() {
+        assert_eq!(truncate_with_ellipsis("日本語のタイトル", 4), "日本語…");
+        // "é" as e + combining acute accent stays together
+        let title = "Cafe\u{301} menu";
+        assert_eq!(truncate_with_ellipsis(title, 5), "Cafe\u{301}…");
+    }
+
+    #[test]
+    fn test_truncate_rtl() {
+        let title = "שלום עולם - Firefox";
+        let truncated = truncate_with_ellipsis(title, 5);
+        assert_eq!(truncated, "שלום…");
+        assert!(title.starts_with(truncated.trim_end_matches(ELLIPSIS)));
+        // Arabic with diacritics
+        assert_eq!(truncate_with_ellipsis("مَرْحَبًا بِك", 3), "مَرْ…");
+    }
+
+    #[test]
+    fn test_truncate_utf16_never_splits_surrogates() {
+        let title = format!("ab{FAMILY}😀😀");
+        // The family emoji takes 11 UTF-16 units; it doesn't fit in 10
+        let truncated = truncate_utf16_with_ellipsis(&title, 10);
+        assert_eq!(truncated, "ab…");
+        assert!(truncated.encode_utf16().count() <= 10);
+
+        let truncated = truncate_utf16_with_ellipsis("😀😀😀", 4);
+        assert_eq!(truncated, "😀…");
+        assert_eq!(truncate_utf16_with_ellipsis("😀😀", 4), "😀😀");
+        assert_eq!(truncate_utf16_with_ellipsis("😀😀", 0), "");
+    }
+
+    #[test]
+    fn test_unicode_titles_survive_json() {
+        let title = format!("{FAMILY} 日本語 שלום \u{202E}reversed");
+        let json = serde_json::to_string(&title).unwrap();
+        let parsed: String = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, title);
+
+        // Escaped surrogate pairs from other JSON writers decode to the same text
+        let parsed: String = serde_json::from_str(r#""\ud83d\ude00 smile""#).unwrap();
+        assert_eq!(parsed, "😀 smile");
+    }
+}
@@ -0,0 +1,126 @@
+//! Capability traits for the platform backend.
+//!
+//! The daemon drives windows through these traits instead of calling the
+//! Win32 functions directly, so each capability can be replaced on its own:
+//! a recording backend in tests, or alternative implementations such as an
+//! elevated helper for positioning or a thumbnail-based compositor mode.
+//!
+//! [`Win32Backend`] implements every capability with the functions of this
+//! crate.
+
+use crate::keyboard_hook::{register_hook_hotkeys, KeyboardHookHandle};
+use crate::{
+    cloak_window, get_foreground_window, install_event_hooks, is_window_topmost, register_hotkeys,
+    set_foreground_window, set_window_topmost, uncloak_window, EventReceiver,
+    Hotkey, HotkeyEvent, HotkeyHandle, PlatformConfig, Win32Error,
+};
+use openniri_core_layout::{WindowId, WindowPlacement};
+use std::any::Any;
+use std::sync::mpsc;
+
+/// Moves and sizes windows according to layout placements.
+pub trait Positioner {
+    /// Apply placements, hiding off-screen windows according to `config`.
+    fn apply_placements(&self, placements: &[WindowPlacement], config: &PlatformConfig) -> Result<(), Win32Error>;
+}
+
+/// Hides and shows individual windows without moving them.
+pub trait Hider {
+    /// Hide a window while keeping it in Alt-Tab and the taskbar.
+    fn hide(&self, window_id: WindowId) -> Result<(), Win32Error>;
+    /// Show a window hidden with [`hide`](Self::hide).
+    fn show(&self, window_id: WindowId) -> Result<(), Win32Error>;
+}
+
+/// Reads and changes the foreground window and the topmost z-order band.
+pub trait Stacker {
+    /// The window in the foreground, if any.
+    fn foreground(&self) -> Option<WindowId>;
+    /// Bring a window to the foreground. Returns whether Windows allowed it.
+    fn set_foreground(&self, window_id: WindowId) -> Result<bool, Win32Error>;
+    /// Whether a window is in the topmost band.
+    fn is_topmost(&self, window_id: WindowId) -> bool;
+    /// Move a window into or out of the topmost band.
+    fn set_topmost(&self, window_id: WindowId, topmost: bool) -> Result<(), Win32Error>;
+}
+
+/// Delivers window lifecycle events.
+pub trait EventSource {
+    /// Start delivering events on the returned receiver. The returned guard
+    /// keeps the subscription alive; dropping it unsubscribes.
+    fn subscribe(&self) -> Result<(Box<dyn Any>, EventReceiver), Win32Error>;
+}
+
+/// Registers global hotkeys.
+pub trait HotkeySource {
+    /// Keeps the hotkeys registered; dropping it unregisters them.
+    type Handle;
+
+    /// Register hotkeys and deliver their presses on the returned receiver.
+    fn register(&self, hotkeys: Vec<Hotkey>) -> Result<(Self::Handle, mpsc::Receiver<HotkeyEvent>), Win32Error>;
+}
+
+/// The Win32 implementation of every capability.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Win32Backend;
+
+impl Positioner for Win32Backend {
+    fn apply_placements(&self, placements: &[WindowPlacement], config: &PlatformConfig) -> Result<(), Win32Error> {
+        crate::apply_placements(placements, config)
+    }
+}
+
+impl Hider for Win32Backend {
+    fn hide(&self, window_id: WindowId) -> Result<(), Win32Error> {
+        cloak_window(window_id)
+    }
+
+    fn show(&self, window_id: WindowId) -> Result<(), Win32Error> {
+        uncloak_window(window_id)
+    }
+}
+
+impl Stacker for Win32Backend {
+    fn foreground(&self) -> Option<WindowId> {
+        get_foreground_window()
+    }
+
+    fn set_foreground(&self, window_id: WindowId) -> Result<bool, Win32Error> {
+        set_foreground_window(window_id)
+    }
+
+    fn is_topmost(&self, window_id: WindowId) -> bool {
+        is_window_topmost(window_id)
+    }
+
+    fn set_topmost(&self, window_id: WindowId, topmost: bool) -> Result<(), Win32Error> {
+        set_window_topmost(window_id, topmost)
+    }
+}
+
+impl EventSource for Win32Backend {
+    fn subscribe(&self) -> Result<(Box<dyn Any>, EventReceiver), Win32Error> {
+        install_event_hooks().map(|(handle, receiver)| (Box::new(handle) as Box<dyn Any>, receiver))
+    }
+}
+
+impl HotkeySource for Win32Backend {
+    type Handle = HotkeyHandle;
+
+    fn register(&self, hotkeys: Vec<Hotkey>) -> Result<(HotkeyHandle, mpsc::Receiver<HotkeyEvent>), Win32Error> {
+        register_hotkeys(hotkeys)
+    }
+}
+
+/// Registers hotkeys through a low-level keyboard hook instead of
+/// `RegisterHotKey`, so combinations the shell owns can be claimed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct KeyboardHookBackend;
+
+impl HotkeySource for KeyboardHookBackend {
+    type Handle = KeyboardHookHandle;
+
+    fn register(&self, hotkeys: Vec<Hotkey>) -> Result<(KeyboardHookHandle, mpsc::Receiver<HotkeyEvent>), Win32Error> {
+        register_hook_hotkeys(hotkeys)
+    }
+}
@@ -0,0 +1,116 @@
+//! Standalone tray balloon notifications.
+//!
+//! The daemon's tray icon can't show balloons, and when the daemon crashes it
+//! may already be gone. [`show_error_balloon`] and [`show_info_balloon`] add a
+//! short-lived notification icon of their own, owned by a message-only
+//! window, show the balloon and remove the icon again.
+
+use std::time::Duration;
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+use windows::Win32::UI::Shell::{
+    Shell_NotifyIconW, NIF_ICON, NIF_INFO, NIF_TIP, NIIF_ERROR, NIIF_INFO, NIM_ADD, NIM_DELETE, NOTIFYICONDATAW,
+    NOTIFY_ICON_INFOTIP_FLAGS,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DestroyWindow, LoadIconW, RegisterClassW, HWND_MESSAGE, IDI_ERROR,
+    IDI_INFORMATION, WNDCLASSW,
+};
+
+/// Window procedure of the icon's owner window, which handles nothing itself.
+unsafe extern "system" fn balloon_window_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    DefWindowProcW(hwnd, msg, wparam, lparam)
+}
+
+/// Copy `text` into a fixed-size, NUL-terminated UTF-16 buffer, truncating it to fit.
+fn copy_utf16<const N: usize>(buffer: &mut [u16; N], text: &str) {
+    for (slot, unit) in buffer.iter_mut().take(N - 1).zip(text.encode_utf16()) {
+        *slot = unit;
+    }
+}
+
+/// Show an error balloon from a temporary tray icon for `duration`.
+///
+/// Blocks the calling thread while the balloon is shown. Does nothing if the
+/// icon can't be added (e.g. no shell is running).
+pub fn show_error_balloon(title: &str, message: &str, duration: Duration) {
+    show_balloon(title, message, duration, IDI_ERROR, NIIF_ERROR);
+}
+
+/// Show an information balloon from a temporary tray icon for `duration`.
+///
+/// Blocks the calling thread like [`show_error_balloon`].
+pub fn show_info_balloon(title: &str, message: &str, duration: Duration) {
+    show_balloon(title, message, duration, IDI_INFORMATION, NIIF_INFO);
+}
+
+/// Show a balloon with the stock `icon` and balloon icon `kind`.
+fn show_balloon(
+    title: &str,
+    message: &str,
+    duration: Duration,
+    icon: windows::core::PCWSTR,
+    kind: NOTIFY_ICON_INFOTIP_FLAGS,
+) {
+    unsafe {
+        let class_name: Vec<u16> = "OpenNiriBalloonClass\0".encode_utf16().collect();
+        let wc = WNDCLASSW {
+            lpfnWndProc: Some(balloon_window_proc),
+            lpszClassName: windows::core::PCWSTR(class_name.as_ptr()),
+            ..Default::default()
+        };
+        RegisterClassW(&wc);
+
+        let Ok(hwnd) = CreateWindowExW(
+            Default::default(),
+            windows::core::PCWSTR(class_name.as_ptr()),
+            None,
+            Default::default(),
+            0,
+            0,
+            0,
+            0,
+            Some(HWND_MESSAGE),
+            None,
+            None,
+            None,
+        ) else {
+            return;
+        };
+
+        let mut data = NOTIFYICONDATAW {
+            cbSize: std::mem::size_of::<NOTIFYICONDATAW>() as u32,
+            hWnd: hwnd,
+            uID: 1,
+            uFlags: NIF_ICON | NIF_TIP | NIF_INFO,
+            hIcon: LoadIconW(None, icon).unwrap_or_default(),
+            dwInfoFlags: kind,
+            ..Default::default()
+        };
+        copy_utf16(&mut data.szTip, title);
+        copy_utf16(&mut data.szInfoTitle, title);
+        copy_utf16(&mut data.szInfo, message);
+
+        if Shell_NotifyIconW(NIM_ADD, &data).as_bool() {
+            std::thread::sleep(duration);
+            let _ = Shell_NotifyIconW(NIM_DELETE, &data);
+        }
+        let _ = DestroyWindow(hwnd);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_copy_utf16_truncates_and_terminates() {
+        let mut buffer = [0u16; 6];
+        copy_utf16(&mut buffer, "crash dump");
+        assert_eq!(String::from_utf16_lossy(&buffer[..5]), "crash");
+        assert_eq!(buffer[5], 0);
+
+        let mut buffer = [0u16; 6];
+        copy_utf16(&mut buffer, "ok");
+        assert_eq!(&buffer[..3], &[u16::from(b'o'), u16::from(b'k'), 0]);
+    }
+}
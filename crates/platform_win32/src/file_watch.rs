@@ -0,0 +1,129 @@
+//! Change notifications for a single file.
+//!
+//! The daemon uses this to reload its config as soon as it's saved. Windows
+//! only reports changes per directory, so the watcher waits on a directory
+//! change notification and then checks whether the watched file itself
+//! changed, by its modification time and existence.
+//!
+//! Watching the directory rather than the file also covers editors that save
+//! by writing a temporary file and renaming it over the original, and a
+//! config file that doesn't exist yet.
+
+use crate::Win32Error;
+use std::ffi::c_void;
+use std::os::windows::ffi::OsStrExt;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc};
+use std::thread::JoinHandle;
+use std::time::SystemTime;
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::{HANDLE, WAIT_OBJECT_0};
+use windows::Win32::Storage::FileSystem::{
+    FindCloseChangeNotification, FindFirstChangeNotificationW, FindNextChangeNotification,
+    FILE_NOTIFY_CHANGE_FILE_NAME, FILE_NOTIFY_CHANGE_LAST_WRITE, FILE_NOTIFY_CHANGE_SIZE,
+};
+use windows::Win32::System::Threading::WaitForSingleObject;
+
+/// How long the watcher thread waits for a change before checking whether
+/// it should stop, in milliseconds.
+const STOP_POLL_MS: u32 = 250;
+
+/// Handle for a file watcher.
+///
+/// Dropping this handle stops the watcher thread.
+pub struct FileWatchHandle {
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl Drop for FileWatchHandle {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        tracing::debug!("File watcher stopped");
+    }
+}
+
+/// Watch `path` for changes.
+///
+/// Returns a handle that must be kept alive to keep watching, and a channel
+/// that receives `()` whenever the file was written, replaced, created, or
+/// deleted. A single save often produces several notifications, so callers
+/// should debounce them.
+pub fn watch_file(path: &Path) -> Result<(FileWatchHandle, mpsc::Receiver<()>), Win32Error> {
+    let dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let wide_dir: Vec<u16> = dir.as_os_str().encode_wide().chain(Some(0)).collect();
+
+    let notification = unsafe {
+        FindFirstChangeNotificationW(
+            PCWSTR(wide_dir.as_ptr()),
+            false,
+            FILE_NOTIFY_CHANGE_FILE_NAME | FILE_NOTIFY_CHANGE_LAST_WRITE | FILE_NOTIFY_CHANGE_SIZE,
+        )
+    }
+    .map_err(|e| Win32Error::WatchFailed(format!("{}: {}", dir.display(), e)))?;
+
+    let (tx, rx) = mpsc::channel();
+    let stop = Arc::new(AtomicBool::new(false));
+    let thread_stop = stop.clone();
+    let path = path.to_path_buf();
+    // HANDLE isn't Send, so the thread gets the raw value
+    let raw_notification = notification.0 as usize;
+
+    let thread = std::thread::Builder::new()
+        .name("file-watch".to_string())
+        .spawn(move || {
+            let notification = HANDLE(raw_notification as *mut c_void);
+            watch_loop(notification, &path, &thread_stop, &tx);
+            unsafe {
+                let _ = FindCloseChangeNotification(notification);
+            }
+        });
+    let thread = match thread {
+        Ok(thread) => thread,
+        Err(e) => {
+            unsafe {
+                let _ = FindCloseChangeNotification(notification);
+            }
+            return Err(Win32Error::WatchFailed(format!("Failed to spawn watcher thread: {}", e)));
+        }
+    };
+
+    tracing::info!("Watching {} for changes", dir.display());
+
+    Ok((FileWatchHandle { stop, thread: Some(thread) }, rx))
+}
+
+/// Wait for directory changes until stopped, reporting those that touched `path`.
+fn watch_loop(notification: HANDLE, path: &Path, stop: &AtomicBool, tx: &mpsc::Sender<()>) {
+    let mut last_modified = modified_time(path);
+    while !stop.load(Ordering::Relaxed) {
+        if unsafe { WaitForSingleObject(notification, STOP_POLL_MS) } != WAIT_OBJECT_0 {
+            continue;
+        }
+
+        let modified = modified_time(path);
+        if modified != last_modified {
+            last_modified = modified;
+            if tx.send(()).is_err() {
+                break;
+            }
+        }
+
+        if let Err(e) = unsafe { FindNextChangeNotification(notification) } {
+            tracing::warn!("File watcher stopped: {}", e);
+            break;
+        }
+    }
+}
+
+/// Modification time of a file, or None if it doesn't exist.
+fn modified_time(path: &Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
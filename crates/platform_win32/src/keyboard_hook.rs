@@ -0,0 +1,307 @@
+//! Hotkeys through a low-level keyboard hook.
+//!
+//! `RegisterHotKey` fails for combinations the shell already owns, such as
+//! Win+Arrow or Win+Tab. A `WH_KEYBOARD_LL` hook sees key presses before the
+//! shell does, so [`register_hook_hotkeys`] can claim them: matching presses
+//! are reported as [`HotkeyEvent`]s and swallowed. Win+L is handled by the
+//! system before any hook and can't be claimed this way.
+//!
+//! [`capture_keys`] uses a second hook to claim a few keys outright while a
+//! modal mode such as resize mode is active: their presses are reported and
+//! neither they nor their releases reach the focused window.
+//!
+//! Every key press of the session passes through the hooks, so the callbacks
+//! only compare the key with the bound ones and return. Each hook runs on a
+//! dedicated thread that does nothing but pump messages for it; if a callback
+//! is slow, Windows removes the hook and typing lags until then.
+
+use crate::{
+    held_modifiers, mask_start_menu, recover_poisoned_mutex, spawn_hook_thread, stop_hook_thread, Hotkey, HotkeyEvent,
+    HotkeyId, Modifiers, Win32Error,
+};
+use std::sync::{mpsc, Mutex};
+use windows::Win32::Foundation::{LPARAM, LRESULT, WPARAM};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CallNextHookEx, KBDLLHOOKSTRUCT, WH_KEYBOARD_LL, WM_KEYDOWN, WM_KEYUP, WM_SYSKEYDOWN, WM_SYSKEYUP,
+};
+
+/// What the hook does with a key event.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum KeyAction {
+    /// Pass the event on to the shell and the focused window.
+    Pass,
+    /// Swallow the event without reporting it.
+    Swallow,
+    /// Swallow the event and report the hotkey.
+    Fire(HotkeyId),
+}
+
+/// Bound hotkeys and the key whose press was swallowed last.
+#[derive(Debug, Default)]
+struct HookState {
+    hotkeys: Vec<Hotkey>,
+    /// Key swallowed as a hotkey and not released yet; its repeats and its
+    /// release are swallowed too, so the focused window never sees half a
+    /// key press.
+    swallowed: Option<u32>,
+}
+
+impl HookState {
+    fn on_key(&mut self, vk: u32, down: bool, held: Modifiers) -> KeyAction {
+        if self.swallowed == Some(vk) {
+            if !down {
+                self.swallowed = None;
+            }
+            // Held keys repeat; like RegisterHotKey with MOD_NOREPEAT, fire once
+            return KeyAction::Swallow;
+        }
+        if !down {
+            return KeyAction::Pass;
+        }
+        match self.hotkeys.iter().find(|h| h.vk == vk && h.modifiers == held) {
+            Some(hotkey) => {
+                self.swallowed = Some(vk);
+                KeyAction::Fire(hotkey.id)
+            }
+            None => KeyAction::Pass,
+        }
+    }
+}
+
+/// Global hook state and event sender.
+static KEYBOARD_HOOK: Mutex<Option<(HookState, mpsc::Sender<HotkeyEvent>)>> = Mutex::new(None);
+
+/// Captured keys and the ones whose press was swallowed and not released yet.
+#[derive(Debug, Default)]
+struct CaptureState {
+    keys: Vec<u32>,
+    /// Swallowed presses; their releases are swallowed too.
+    pressed: Vec<u32>,
+}
+
+impl CaptureState {
+    /// Returns true if the key event is claimed, and the key if it's a press
+    /// to report.
+    fn on_key(&mut self, vk: u32, down: bool) -> (bool, Option<u32>) {
+        if !down {
+            let claimed = self.pressed.contains(&vk);
+            self.pressed.retain(|&key| key != vk);
+            return (claimed, None);
+        }
+        if !self.keys.contains(&vk) {
+            return (false, None);
+        }
+        if !self.pressed.contains(&vk) {
+            self.pressed.push(vk);
+        }
+        // Auto-repeat is reported like a press
+        (true, Some(vk))
+    }
+}
+
+/// Global capture state and key press sender.
+static KEY_CAPTURE: Mutex<Option<(CaptureState, mpsc::Sender<u32>)>> = Mutex::new(None);
+
+/// Handle for the keyboard hook thread.
+///
+/// Dropping this handle removes the hook and stops its thread.
+pub struct KeyboardHookHandle {
+    thread_id: u32,
+    thread: Option<std::thread::JoinHandle<()>>,
+    count: usize,
+}
+
+impl KeyboardHookHandle {
+    /// Returns the number of hotkeys the hook claims.
+    pub fn registered_count(&self) -> usize {
+        self.count
+    }
+}
+
+impl Drop for KeyboardHookHandle {
+    fn drop(&mut self) {
+        stop_hook_thread(self.thread_id, self.thread.take());
+        *KEYBOARD_HOOK.lock().unwrap_or_else(recover_poisoned_mutex) = None;
+        tracing::debug!("Keyboard hook hotkeys unregistered");
+    }
+}
+
+/// Claim hotkeys with a low-level keyboard hook, ahead of the shell.
+///
+/// Returns a handle that must be kept alive to receive hotkey events, and a
+/// channel receiver for them.
+pub fn register_hook_hotkeys(
+    hotkeys: Vec<Hotkey>,
+) -> Result<(KeyboardHookHandle, mpsc::Receiver<HotkeyEvent>), Win32Error> {
+    let (tx, rx) = mpsc::channel();
+    let count = hotkeys.len();
+
+    {
+        let mut global = KEYBOARD_HOOK
+            .lock()
+            .map_err(|_| Win32Error::HookInstallFailed("Keyboard hook mutex poisoned".to_string()))?;
+        if global.is_some() {
+            return Err(Win32Error::HookInstallFailed(
+                "Keyboard hook already installed - drop existing KeyboardHookHandle first".to_string(),
+            ));
+        }
+        *global = Some((HookState { hotkeys, swallowed: None }, tx));
+    }
+
+    let (thread_id, thread) = match spawn_hook_thread(WH_KEYBOARD_LL, keyboard_hook_proc) {
+        Ok(spawned) => spawned,
+        Err(e) => {
+            *KEYBOARD_HOOK.lock().unwrap_or_else(recover_poisoned_mutex) = None;
+            return Err(e);
+        }
+    };
+
+    tracing::info!("Registered {} hotkeys with the low-level keyboard hook", count);
+
+    Ok((KeyboardHookHandle { thread_id, thread: Some(thread), count }, rx))
+}
+
+/// Handle for a key capture.
+///
+/// Dropping this handle removes the hook and releases the keys.
+pub struct KeyCaptureHandle {
+    thread_id: u32,
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+impl Drop for KeyCaptureHandle {
+    fn drop(&mut self) {
+        stop_hook_thread(self.thread_id, self.thread.take());
+        *KEY_CAPTURE.lock().unwrap_or_else(recover_poisoned_mutex) = None;
+        tracing::debug!("Key capture released");
+    }
+}
+
+/// Claim the keys with virtual key codes `keys` until the handle is dropped.
+///
+/// Presses of these keys, including auto-repeats, are reported on the
+/// returned receiver whatever modifiers are held, and neither they nor their
+/// releases reach the focused window. Keys already held when the capture
+/// starts are released normally.
+pub fn capture_keys(keys: Vec<u32>) -> Result<(KeyCaptureHandle, mpsc::Receiver<u32>), Win32Error> {
+    let (tx, rx) = mpsc::channel();
+    {
+        let mut global = KEY_CAPTURE
+            .lock()
+            .map_err(|_| Win32Error::HookInstallFailed("Key capture mutex poisoned".to_string()))?;
+        if global.is_some() {
+            return Err(Win32Error::HookInstallFailed(
+                "Keys already captured - drop existing KeyCaptureHandle first".to_string(),
+            ));
+        }
+        *global = Some((CaptureState { keys, pressed: Vec::new() }, tx));
+    }
+
+    match spawn_hook_thread(WH_KEYBOARD_LL, key_capture_proc) {
+        Ok((thread_id, thread)) => Ok((KeyCaptureHandle { thread_id, thread: Some(thread) }, rx)),
+        Err(e) => {
+            *KEY_CAPTURE.lock().unwrap_or_else(recover_poisoned_mutex) = None;
+            Err(e)
+        }
+    }
+}
+
+/// Key direction of a low-level keyboard hook message, or None for others.
+fn key_down(wparam: WPARAM) -> Option<bool> {
+    match wparam.0 as u32 {
+        WM_KEYDOWN | WM_SYSKEYDOWN => Some(true),
+        WM_KEYUP | WM_SYSKEYUP => Some(false),
+        _ => None,
+    }
+}
+
+/// Low-level keyboard hook callback for [`capture_keys`].
+unsafe extern "system" fn key_capture_proc(ncode: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if ncode >= 0 {
+        if let Some(down) = key_down(wparam) {
+            let vk = (*(lparam.0 as *const KBDLLHOOKSTRUCT)).vkCode;
+            let mut guard = KEY_CAPTURE.lock().unwrap_or_else(recover_poisoned_mutex);
+            if let Some((state, sender)) = guard.as_mut() {
+                let (claimed, pressed) = state.on_key(vk, down);
+                if let Some(vk) = pressed {
+                    let _ = sender.send(vk);
+                }
+                if claimed {
+                    return LRESULT(1);
+                }
+            }
+        }
+    }
+
+    CallNextHookEx(None, ncode, wparam, lparam)
+}
+
+/// Low-level keyboard hook callback.
+unsafe extern "system" fn keyboard_hook_proc(ncode: i32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if ncode >= 0 {
+        if let Some(down) = key_down(wparam) {
+            let vk = (*(lparam.0 as *const KBDLLHOOKSTRUCT)).vkCode;
+            let mut guard = KEYBOARD_HOOK.lock().unwrap_or_else(recover_poisoned_mutex);
+            if let Some((state, sender)) = guard.as_mut() {
+                // Only bound keys need the modifier state
+                let bound = state.swallowed == Some(vk) || state.hotkeys.iter().any(|h| h.vk == vk);
+                if bound {
+                    match state.on_key(vk, down, held_modifiers()) {
+                        KeyAction::Pass => {}
+                        KeyAction::Swallow => return LRESULT(1),
+                        KeyAction::Fire(id) => {
+                            let _ = sender.send(HotkeyEvent { id });
+                            drop(guard);
+                            if held_modifiers().win {
+                                mask_start_menu();
+                            }
+                            return LRESULT(1);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    CallNextHookEx(None, ncode, wparam, lparam)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state() -> HookState {
+        HookState { hotkeys: vec![Hotkey::new(1, Modifiers::win(), crate::vk::LEFT)], swallowed: None }
+    }
+
+    #[test]
+    fn test_bound_press_fires_once_and_swallows_release() {
+        let mut state = state();
+        assert_eq!(state.on_key(crate::vk::LEFT, true, Modifiers::win()), KeyAction::Fire(1));
+        assert_eq!(state.on_key(crate::vk::LEFT, true, Modifiers::win()), KeyAction::Swallow, "auto-repeat");
+        // Released after Win: still swallowed, since its press was
+        assert_eq!(state.on_key(crate::vk::LEFT, false, Modifiers::default()), KeyAction::Swallow);
+        assert_eq!(state.on_key(crate::vk::LEFT, false, Modifiers::default()), KeyAction::Pass);
+    }
+
+    #[test]
+    fn test_other_keys_and_modifiers_pass() {
+        let mut state = state();
+        assert_eq!(state.on_key(crate::vk::LEFT, true, Modifiers::default()), KeyAction::Pass);
+        assert_eq!(state.on_key(crate::vk::LEFT, true, Modifiers::win_shift()), KeyAction::Pass);
+        assert_eq!(state.on_key(crate::vk::RIGHT, true, Modifiers::win()), KeyAction::Pass);
+        assert_eq!(state.on_key(crate::vk::LEFT, false, Modifiers::win()), KeyAction::Pass);
+    }
+
+    #[test]
+    fn test_capture_reports_presses_and_swallows_their_releases() {
+        let mut state = CaptureState { keys: vec![crate::vk::LEFT, crate::vk::ESCAPE], pressed: Vec::new() };
+        // Held before the capture started: its release passes
+        assert_eq!(state.on_key(crate::vk::ESCAPE, false), (false, None));
+        assert_eq!(state.on_key(crate::vk::LEFT, true), (true, Some(crate::vk::LEFT)));
+        assert_eq!(state.on_key(crate::vk::LEFT, true), (true, Some(crate::vk::LEFT)), "auto-repeat");
+        assert_eq!(state.on_key(crate::vk::LEFT, false), (true, None));
+        assert_eq!(state.on_key(crate::vk::RIGHT, true), (false, None));
+        assert_eq!(state.on_key(crate::vk::RIGHT, false), (false, None));
+    }
+}
@@ -8,36 +8,58 @@
 //! - Window cloaking/uncloaking via DWM APIs
 //! - WinEvent hooks for window lifecycle events
 //! - Visual overlay for snap hints
+//! - Placeholder strips for collapsed columns
+//!
+//! The [`backend`] module groups these into swappable capability traits.
 
+pub mod backend;
+pub mod balloon;
+pub mod file_watch;
+pub mod keyboard_hook;
 pub mod overlay;
+pub mod pipe_security;
+pub mod placeholder;
+pub mod virtual_desktop;
 
-use openniri_core_layout::{Rect, Visibility, WindowId, WindowPlacement};
+use openniri_core_layout::{Rect, SizeConstraints, Visibility, WindowId, WindowPlacement};
+use std::collections::BTreeSet;
 use std::ffi::c_void;
+use std::sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering};
 use std::sync::mpsc;
 use thiserror::Error;
-use windows::Win32::Foundation::{BOOL, CloseHandle, HWND, LPARAM, RECT, TRUE};
+use windows::Wdk::System::Threading::{NtQueryInformationProcess, ProcessCommandLineInformation};
+use windows::Win32::Foundation::{BOOL, CloseHandle, HWND, LPARAM, RECT, TRUE, UNICODE_STRING, WPARAM};
 use windows::Win32::Graphics::Dwm::{
-    DwmGetWindowAttribute, DwmSetWindowAttribute, DWMWA_CLOAK, DWMWA_CLOAKED,
+    DwmFlush, DwmGetCompositionTimingInfo, DwmGetWindowAttribute, DwmSetWindowAttribute, DWMWA_CLOAK,
+    DWMWA_CLOAKED, DWM_TIMING_INFO,
 };
 use windows::Win32::Graphics::Gdi::{
-    EnumDisplayMonitors, GetMonitorInfoW, HDC, HMONITOR, MONITORINFOEXW,
+    EnumDisplayMonitors, GetMonitorInfoW, MonitorFromPoint, MonitorFromRect, HDC, HMONITOR, MONITORINFOEXW,
+    MONITOR_DEFAULTTONEAREST,
 };
 use windows::Win32::System::ProcessStatus::K32GetModuleFileNameExW;
-use windows::Win32::System::Threading::{OpenProcess, PROCESS_QUERY_LIMITED_INFORMATION};
+use windows::Win32::System::RemoteDesktop::ProcessIdToSessionId;
+use windows::Win32::System::SystemInformation::{GetLocalTime, GetTickCount};
+use windows::Win32::System::Threading::{
+    CreateEventW, GetCurrentProcessId, OpenProcess, SetEvent, WaitForSingleObject, PROCESS_QUERY_LIMITED_INFORMATION,
+};
+use windows::Win32::UI::HiDpi::{GetDpiForMonitor, GetDpiForWindow, GetSystemMetricsForDpi, MDT_EFFECTIVE_DPI};
 use windows::Win32::UI::Accessibility::{SetWinEventHook, UnhookWinEvent, HWINEVENTHOOK};
 use windows::Win32::UI::Input::KeyboardAndMouse::{
-    RegisterHotKey, UnregisterHotKey, HOT_KEY_MODIFIERS, MOD_ALT, MOD_CONTROL, MOD_NOREPEAT,
-    MOD_SHIFT, MOD_WIN,
+    GetAsyncKeyState, GetLastInputInfo, RegisterHotKey, SendInput, UnregisterHotKey, HOT_KEY_MODIFIERS, INPUT, INPUT_0,
+    INPUT_KEYBOARD, KEYBDINPUT, KEYEVENTF_KEYUP, LASTINPUTINFO, MOD_ALT, MOD_CONTROL, MOD_NOREPEAT, MOD_SHIFT, MOD_WIN,
+    VIRTUAL_KEY,
 };
 use windows::Win32::UI::WindowsAndMessaging::{
-    BeginDeferWindowPos, CallNextHookEx, CreateWindowExW, DeferWindowPos, DefWindowProcW,
+    BeginDeferWindowPos, CallNextHookEx, CreateWindowExW, DeferWindowPos, DefWindowProcW, DeregisterShellHookWindow,
     DispatchMessageW, EndDeferWindowPos, EnumWindows, GetAncestor, GetClassNameW, GetMessageW,
-    GetWindow, GetWindowLongW, GetWindowRect, GetWindowTextLengthW, GetWindowTextW,
-    GetWindowThreadProcessId, IsWindow, IsWindowVisible, PostMessageW, RegisterClassW,
-    SetForegroundWindow, SetWindowPos, SetWindowsHookExW, UnhookWindowsHookEx, WindowFromPoint,
-    BringWindowToTop, GA_ROOT, GW_OWNER, GWL_EXSTYLE, GWL_STYLE, HHOOK, HWND_MESSAGE,
-    MSLLHOOKSTRUCT, MSG, SWP_NOACTIVATE, SWP_NOZORDER, WH_MOUSE_LL, WM_HOTKEY, WM_MOUSEMOVE,
-    WM_USER, WNDCLASSW, WS_EX_APPWINDOW, WS_EX_NOACTIVATE, WS_EX_TOOLWINDOW, WS_VISIBLE,
+    GetCursorPos, GetForegroundWindow, GetSystemMetrics, GetTopWindow, GetWindow, GetWindowLongW, GetWindowRect, GetWindowTextLengthW,
+    GetWindowTextW, GetWindowThreadProcessId, IsIconic, IsWindow, IsWindowVisible, PostMessageW, PostThreadMessageW, RegisterClassW,
+    RegisterShellHookWindow, RegisterWindowMessageW,
+    SendMessageTimeoutW, SetForegroundWindow, SetWindowPos, SetWindowsHookExW, ShowWindow, UnhookWindowsHookEx, WindowFromPoint,
+    BringWindowToTop, GA_ROOT, GW_HWNDNEXT, GW_OWNER, GWL_EXSTYLE, GWL_STYLE, HHOOK, HWND_NOTOPMOST, HWND_TOPMOST,
+    MINMAXINFO, MSLLHOOKSTRUCT, MSG, SMTO_ABORTIFHUNG, SMTO_BLOCK, SM_CXMAXTRACK, SM_CXPADDEDBORDER, SM_CYCAPTION, SM_CYMAXTRACK, SM_CYSIZEFRAME, WM_GETMINMAXINFO, SWP_NOACTIVATE, SWP_NOMOVE, SWP_NOSIZE, SWP_NOZORDER, SW_SHOWMINNOACTIVE, SW_SHOWNOACTIVATE, WH_MOUSE_LL, WM_HOTKEY, WM_LBUTTONDOWN, WM_LBUTTONUP, WM_MOUSEMOVE,
+    WINDOWS_HOOK_ID, WM_QUIT, WM_USER, WNDCLASSW, WS_EX_APPWINDOW, WS_EX_NOACTIVATE, WS_EX_TOOLWINDOW, WS_EX_TOPMOST, WS_VISIBLE,
 };
 use windows::Win32::System::Threading::GetCurrentThreadId;
 
@@ -46,22 +68,32 @@ const EVENT_OBJECT_CREATE: u32 = 0x8000;
 const EVENT_OBJECT_DESTROY: u32 = 0x8001;
 const EVENT_OBJECT_FOCUS: u32 = 0x8005;
 const EVENT_SYSTEM_FOREGROUND: u32 = 0x0003;
+const EVENT_SYSTEM_MOVESIZESTART: u32 = 0x000A;
+const EVENT_SYSTEM_MOVESIZEEND: u32 = 0x000B;
 const EVENT_SYSTEM_MINIMIZESTART: u32 = 0x0016;
 const EVENT_SYSTEM_MINIMIZEEND: u32 = 0x0017;
 const EVENT_OBJECT_LOCATIONCHANGE: u32 = 0x800B;
+const EVENT_OBJECT_NAMECHANGE: u32 = 0x800C;
 const OBJID_WINDOW: i32 = 0;
 const WINEVENT_OUTOFCONTEXT: u32 = 0x0000;
 const WINEVENT_SKIPOWNPROCESS: u32 = 0x0002;
 
 // Window message for display configuration changes
 const WM_DISPLAYCHANGE: u32 = 0x007E;
+// System parameter change, with SPI_SETWORKAREA when the taskbar or an app bar
+// moves, resizes or toggles auto-hide
+const WM_SETTINGCHANGE: u32 = 0x001A;
+const SPI_SETWORKAREA: usize = 0x002F;
+// Shell hook notification for a window flashing its taskbar button
+// (FlashWindowEx); lParam is the window
+const HSHELL_FLASH: usize = 0x8006;
 
 /// Recover from a poisoned mutex, logging a warning.
 ///
 /// When a thread panics while holding a mutex, the mutex becomes "poisoned".
 /// This helper logs the event and recovers the inner data so the application
 /// can continue operating.
-fn recover_poisoned_mutex<T>(err: std::sync::PoisonError<std::sync::MutexGuard<'_, T>>) -> std::sync::MutexGuard<'_, T> {
+pub(crate) fn recover_poisoned_mutex<T>(err: std::sync::PoisonError<std::sync::MutexGuard<'_, T>>) -> std::sync::MutexGuard<'_, T> {
     eprintln!("[openniri] WARNING: Mutex poisoned, recovering");
     err.into_inner()
 }
@@ -100,6 +132,15 @@ pub enum Win32Error {
 
     #[error("Window not found: {0}")]
     WindowNotFound(WindowId),
+
+    #[error("Failed to watch file: {0}")]
+    WatchFailed(String),
+
+    #[error("Failed to secure IPC pipe: {0}")]
+    PipeSecurityFailed(String),
+
+    #[error("Failed to open ready event: {0}")]
+    ReadyEventFailed(String),
 }
 
 /// Information about a managed window.
@@ -122,6 +163,9 @@ pub struct WindowInfo {
 /// Unique identifier for a monitor (derived from HMONITOR handle).
 pub type MonitorId = isize;
 
+/// DPI of a display at 100% scaling.
+pub const DEFAULT_DPI: u32 = 96;
+
 /// Information about a display monitor.
 #[derive(Debug, Clone)]
 pub struct MonitorInfo {
@@ -135,6 +179,8 @@ pub struct MonitorInfo {
     pub is_primary: bool,
     /// Device name (e.g., `\\.\DISPLAY1`).
     pub device_name: String,
+    /// Effective DPI ([`DEFAULT_DPI`] at 100% scaling, 144 at 150%).
+    pub dpi: u32,
 }
 
 impl MonitorInfo {
@@ -152,6 +198,11 @@ impl MonitorInfo {
         let center_y = rect.y + rect.height / 2;
         self.contains_point(center_x, center_y)
     }
+
+    /// Display scaling as a factor (1.0 at 100%, 1.5 at 150%).
+    pub fn scale_factor(&self) -> f64 {
+        self.dpi as f64 / DEFAULT_DPI as f64
+    }
 }
 
 /// Strategy for hiding off-screen windows.
@@ -163,6 +214,10 @@ pub enum HideStrategy {
     /// Move windows off-screen (alternative when cloaking is disabled).
     /// Windows are moved far off-screen rather than cloaked.
     MoveOffScreen,
+    /// Minimize windows without activating another one, so off-strip
+    /// windows show as minimized in the taskbar. Restoring one from the
+    /// taskbar is reported through [`take_layout_minimized`].
+    Minimize,
 }
 
 /// Configuration for the Win32 platform layer.
@@ -183,6 +238,64 @@ impl Default for PlatformConfig {
     }
 }
 
+/// Executable name patterns whose windows are never managed, lowercased.
+static IGNORED_PROCESSES: std::sync::Mutex<Vec<String>> = std::sync::Mutex::new(Vec::new());
+
+/// Set the executable name patterns (e.g. `"*.tmp.exe"`) whose windows
+/// [`enumerate_windows`] and [`get_window_info`] skip. Patterns match the
+/// whole file name, case-insensitively; `*` matches any run of characters
+/// and `?` a single one.
+pub fn set_ignored_processes(patterns: &[String]) {
+    let patterns = patterns
+        .iter()
+        .map(|p| p.trim().to_lowercase())
+        .filter(|p| !p.is_empty())
+        .collect();
+    *IGNORED_PROCESSES.lock().unwrap_or_else(recover_poisoned_mutex) = patterns;
+}
+
+/// Whether a glob pattern with `*` and `?` wildcards matches all of `text`.
+/// Callers lowercase both sides for case-insensitive matching.
+pub fn glob_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` and the text position it was tried at
+    let mut star: Option<(usize, usize)> = None;
+    while t < text.len() {
+        if p < pattern.len() && (pattern[p] == '?' || pattern[p] == text[t]) {
+            p += 1;
+            t += 1;
+        } else if p < pattern.len() && pattern[p] == '*' {
+            star = Some((p, t));
+            p += 1;
+        } else if let Some((star_p, star_t)) = star {
+            // Let the last `*` absorb one more character
+            p = star_p + 1;
+            t = star_t + 1;
+            star = Some((star_p, star_t + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Whether a process's executable matches an ignored pattern.
+///
+/// Only looks up the executable when patterns are set.
+fn is_ignored_process(process_id: u32) -> bool {
+    let patterns = IGNORED_PROCESSES.lock().unwrap_or_else(recover_poisoned_mutex);
+    if patterns.is_empty() {
+        return false;
+    }
+    let Some(executable) = get_process_executable(process_id) else {
+        return false;
+    };
+    let executable = executable.to_lowercase();
+    patterns.iter().any(|pattern| glob_match(pattern, &executable))
+}
+
 /// Enumerate all top-level windows that should be managed.
 ///
 /// Filters out:
@@ -191,6 +304,7 @@ impl Default for PlatformConfig {
 /// - Windows with empty titles
 /// - Cloaked windows
 /// - Windows with WS_EX_NOACTIVATE
+/// - Windows of processes matching [`set_ignored_processes`]
 pub fn enumerate_windows() -> Result<Vec<WindowInfo>, Win32Error> {
     let mut windows: Vec<WindowInfo> = Vec::new();
 
@@ -210,6 +324,10 @@ pub fn enumerate_windows() -> Result<Vec<WindowInfo>, Win32Error> {
         }
     }
 
+    // Never manage windows owned by another logon session (e.g. console vs RDP)
+    let own_session = current_session_id();
+    windows.retain(|w| is_same_session(get_process_session_id(w.process_id), own_session));
+
     tracing::debug!("Enumerated {} manageable windows", windows.len());
     Ok(windows)
 }
@@ -243,6 +361,80 @@ pub fn find_monitor_for_rect<'a>(monitors: &'a [MonitorInfo], rect: &Rect) -> Op
         .or_else(|| monitors.iter().find(|m| m.is_primary))
 }
 
+/// Clean up a monitor list reported by the system.
+///
+/// Virtual display drivers sometimes report monitors that would produce
+/// nonsensical layouts. This:
+/// - drops monitors with a zero-size rectangle
+/// - clamps work areas to their monitor (or uses the full monitor if the
+///   work area is empty or outside it)
+/// - merges exact duplicates and monitors lying entirely inside another
+///   (mirrored displays), keeping the primary flag
+///
+/// Partially overlapping monitors are kept but logged. Every change is
+/// logged as a warning.
+pub fn sanitize_monitors(monitors: Vec<MonitorInfo>) -> Vec<MonitorInfo> {
+    let mut sanitized: Vec<MonitorInfo> = Vec::with_capacity(monitors.len());
+
+    for mut monitor in monitors {
+        if monitor.rect.is_empty() {
+            tracing::warn!(
+                "Ignoring monitor {} ({}) with zero-size area {:?}",
+                monitor.id, monitor.device_name, monitor.rect
+            );
+            continue;
+        }
+
+        if !monitor.rect.contains_rect(&monitor.work_area) || monitor.work_area.is_empty() {
+            let clamped = monitor
+                .work_area
+                .intersection(&monitor.rect)
+                .filter(|r| !r.is_empty())
+                .unwrap_or(monitor.rect);
+            tracing::warn!(
+                "Monitor {} ({}): work area {:?} is outside the monitor {:?}, using {:?}",
+                monitor.id, monitor.device_name, monitor.work_area, monitor.rect, clamped
+            );
+            monitor.work_area = clamped;
+        }
+
+        // Merge with an already accepted monitor covering the same area
+        if let Some(index) = sanitized.iter().position(|m| {
+            m.rect.contains_rect(&monitor.rect) || monitor.rect.contains_rect(&m.rect)
+        }) {
+            let existing = &mut sanitized[index];
+            tracing::warn!(
+                "Merging monitor {} ({}) {:?} into overlapping monitor {} ({}) {:?}",
+                monitor.id, monitor.device_name, monitor.rect,
+                existing.id, existing.device_name, existing.rect
+            );
+            let is_primary = existing.is_primary || monitor.is_primary;
+            // Keep the larger monitor; on a tie keep the primary one
+            let replace = if monitor.rect == existing.rect {
+                monitor.is_primary && !existing.is_primary
+            } else {
+                monitor.rect.contains_rect(&existing.rect)
+            };
+            if replace {
+                *existing = monitor;
+            }
+            existing.is_primary = is_primary;
+            continue;
+        }
+
+        if let Some(other) = sanitized.iter().find(|m| m.rect.intersects(&monitor.rect)) {
+            tracing::warn!(
+                "Monitor {} ({}) {:?} overlaps monitor {} ({}) {:?}",
+                monitor.id, monitor.device_name, monitor.rect,
+                other.id, other.device_name, other.rect
+            );
+        }
+        sanitized.push(monitor);
+    }
+
+    sanitized
+}
+
 /// Find a monitor by its ID.
 pub fn find_monitor_by_id(monitors: &[MonitorInfo], id: MonitorId) -> Option<&MonitorInfo> {
     monitors.iter().find(|m| m.id == id)
@@ -258,26 +450,60 @@ pub fn monitors_by_position(monitors: &[MonitorInfo]) -> Vec<&MonitorInfo> {
     sorted
 }
 
+/// Direction from one monitor to another.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MonitorDirection {
+    Left,
+    Right,
+    Up,
+    Down,
+}
+
+/// Find the nearest monitor in `direction` from the given monitor.
+///
+/// Candidates are monitors whose center lies beyond the current monitor's
+/// center in that direction. Monitors overlapping the current one on the
+/// other axis (side by side for left/right, stacked for up/down) win over
+/// diagonal ones; ties go to the smallest center-to-center distance.
+pub fn monitor_in_direction(
+    monitors: &[MonitorInfo],
+    current_id: MonitorId,
+    direction: MonitorDirection,
+) -> Option<&MonitorInfo> {
+    let current = find_monitor_by_id(monitors, current_id)?.rect;
+    // Doubled centers keep the arithmetic exact
+    let center = |r: &Rect| (2 * i64::from(r.x) + i64::from(r.width), 2 * i64::from(r.y) + i64::from(r.height));
+    let spans_overlap = |a_start: i32, a_len: i32, b_start: i32, b_len: i32| {
+        a_start < b_start.saturating_add(b_len) && b_start < a_start.saturating_add(a_len)
+    };
+    let (cx, cy) = center(&current);
+
+    monitors
+        .iter()
+        .filter(|m| m.id != current_id)
+        .filter_map(|m| {
+            let (mx, my) = center(&m.rect);
+            let (dx, dy) = (mx - cx, my - cy);
+            let (ahead, overlaps) = match direction {
+                MonitorDirection::Left => (dx < 0, spans_overlap(m.rect.y, m.rect.height, current.y, current.height)),
+                MonitorDirection::Right => (dx > 0, spans_overlap(m.rect.y, m.rect.height, current.y, current.height)),
+                MonitorDirection::Up => (dy < 0, spans_overlap(m.rect.x, m.rect.width, current.x, current.width)),
+                MonitorDirection::Down => (dy > 0, spans_overlap(m.rect.x, m.rect.width, current.x, current.width)),
+            };
+            ahead.then_some(((!overlaps, dx * dx + dy * dy), m))
+        })
+        .min_by_key(|&(key, m)| (key, m.id))
+        .map(|(_, m)| m)
+}
+
 /// Find the monitor to the left of the given monitor.
 pub fn monitor_to_left(monitors: &[MonitorInfo], current_id: MonitorId) -> Option<&MonitorInfo> {
-    let sorted = monitors_by_position(monitors);
-    let current_idx = sorted.iter().position(|m| m.id == current_id)?;
-    if current_idx > 0 {
-        Some(sorted[current_idx - 1])
-    } else {
-        None
-    }
+    monitor_in_direction(monitors, current_id, MonitorDirection::Left)
 }
 
 /// Find the monitor to the right of the given monitor.
 pub fn monitor_to_right(monitors: &[MonitorInfo], current_id: MonitorId) -> Option<&MonitorInfo> {
-    let sorted = monitors_by_position(monitors);
-    let current_idx = sorted.iter().position(|m| m.id == current_id)?;
-    if current_idx + 1 < sorted.len() {
-        Some(sorted[current_idx + 1])
-    } else {
-        None
-    }
+    monitor_in_direction(monitors, current_id, MonitorDirection::Right)
 }
 
 /// Enumerate all connected monitors.
@@ -304,6 +530,7 @@ pub fn enumerate_monitors() -> Result<Vec<MonitorInfo>, Win32Error> {
         }
     }
 
+    let monitors = sanitize_monitors(monitors);
     if monitors.is_empty() {
         return Err(Win32Error::MonitorEnumerationFailed(
             "No monitors found".to_string(),
@@ -314,6 +541,36 @@ pub fn enumerate_monitors() -> Result<Vec<MonitorInfo>, Win32Error> {
     Ok(monitors)
 }
 
+/// Effective DPI of a monitor.
+fn monitor_dpi(hmonitor: HMONITOR) -> Option<u32> {
+    let (mut dpi_x, mut dpi_y) = (0u32, 0u32);
+    unsafe { GetDpiForMonitor(hmonitor, MDT_EFFECTIVE_DPI, &mut dpi_x, &mut dpi_y).ok()? };
+    (dpi_x > 0).then_some(dpi_x)
+}
+
+/// Effective DPI of the monitor a rectangle mostly lies on.
+fn dpi_at_rect(rect: &Rect) -> Option<u32> {
+    let rect = RECT {
+        left: rect.x,
+        top: rect.y,
+        right: rect.x + rect.width,
+        bottom: rect.y + rect.height,
+    };
+    monitor_dpi(unsafe { MonitorFromRect(&rect, MONITOR_DEFAULTTONEAREST) })
+}
+
+/// DPI a window currently renders at, or None if the handle is invalid.
+pub fn get_window_dpi(hwnd: WindowId) -> Option<u32> {
+    let dpi = unsafe { GetDpiForWindow(HWND(hwnd as *mut c_void)) };
+    (dpi > 0).then_some(dpi)
+}
+
+/// Whether a move from `window_dpi` to a monitor at `target_dpi` changes the
+/// window's DPI. Unknown DPIs are treated as unchanged.
+pub fn crosses_dpi_boundary(window_dpi: Option<u32>, target_dpi: Option<u32>) -> bool {
+    matches!((window_dpi, target_dpi), (Some(from), Some(to)) if from != to)
+}
+
 /// Callback for EnumDisplayMonitors that collects monitor info.
 unsafe extern "system" fn enum_monitors_callback(
     hmonitor: HMONITOR,
@@ -356,6 +613,7 @@ unsafe extern "system" fn enum_monitors_callback(
             // MONITORINFOF_PRIMARY = 1
             is_primary: info.monitorInfo.dwFlags & 1 != 0,
             device_name,
+            dpi: monitor_dpi(hmonitor).unwrap_or(DEFAULT_DPI),
         });
 
         TRUE
@@ -369,9 +627,27 @@ unsafe extern "system" fn enum_monitors_callback(
 unsafe extern "system" fn enum_windows_callback(hwnd: HWND, lparam: LPARAM) -> BOOL {
     let windows = &mut *(lparam.0 as *mut Vec<WindowInfo>);
 
+    if let Some(info) = manageable_window_info(hwnd) {
+        windows.push(info);
+    }
+
+    TRUE
+}
+
+/// Get information about a single window if it should be managed.
+///
+/// Applies the same filters as [`enumerate_windows`], so a window that was
+/// skipped earlier (e.g. because it had no title yet) can be checked again.
+pub fn get_window_info(hwnd: WindowId) -> Option<WindowInfo> {
+    let info = unsafe { manageable_window_info(HWND(hwnd as *mut c_void)) }?;
+    is_same_session(get_process_session_id(info.process_id), current_session_id()).then_some(info)
+}
+
+/// Collect window information, or None if the window should not be managed.
+unsafe fn manageable_window_info(hwnd: HWND) -> Option<WindowInfo> {
     // Skip invisible windows
     if !IsWindowVisible(hwnd).as_bool() {
-        return TRUE;
+        return None;
     }
 
     // Get window styles
@@ -380,49 +656,49 @@ unsafe extern "system" fn enum_windows_callback(hwnd: HWND, lparam: LPARAM) -> B
 
     // Skip if not visible style
     if style & WS_VISIBLE.0 == 0 {
-        return TRUE;
+        return None;
     }
 
     // Skip tool windows (unless they have WS_EX_APPWINDOW)
     let is_tool_window = ex_style & WS_EX_TOOLWINDOW.0 != 0;
     let is_app_window = ex_style & WS_EX_APPWINDOW.0 != 0;
     if is_tool_window && !is_app_window {
-        return TRUE;
+        return None;
     }
 
     // Skip windows with WS_EX_NOACTIVATE (tooltips, popups, etc.)
     if ex_style & WS_EX_NOACTIVATE.0 != 0 {
-        return TRUE;
+        return None;
     }
 
     // Skip owned windows (dialogs, secondary windows)
     if let Ok(owner) = GetWindow(hwnd, GW_OWNER) {
         if !owner.is_invalid() {
-            return TRUE;
+            return None;
         }
     }
 
     // Skip cloaked windows (e.g., on other virtual desktops)
     if is_window_cloaked(hwnd) {
-        return TRUE;
+        return None;
     }
 
     // Get window title
     let title_len = GetWindowTextLengthW(hwnd);
     if title_len == 0 {
-        return TRUE; // Skip windows with no title
+        return None; // Skip windows with no title
     }
 
     let mut title_buf: Vec<u16> = vec![0; (title_len + 1) as usize];
     let actual_len = GetWindowTextW(hwnd, &mut title_buf);
     if actual_len == 0 {
-        return TRUE;
+        return None;
     }
-    let title = String::from_utf16_lossy(&title_buf[..actual_len as usize]);
+    let title = decode_window_text(&title_buf[..actual_len as usize]);
 
     // Skip known system windows by title
     if should_skip_window_by_title(&title) {
-        return TRUE;
+        return None;
     }
 
     // Get class name
@@ -436,17 +712,22 @@ unsafe extern "system" fn enum_windows_callback(hwnd: HWND, lparam: LPARAM) -> B
 
     // Skip known system classes
     if should_skip_window_by_class(&class_name) {
-        return TRUE;
+        return None;
     }
 
     // Get process ID
     let mut process_id: u32 = 0;
     GetWindowThreadProcessId(hwnd, Some(&mut process_id));
 
+    // Skip globally ignored processes (games, launchers) before any rule evaluation
+    if is_ignored_process(process_id) {
+        return None;
+    }
+
     // Get window rect
     let mut win_rect = RECT::default();
     if GetWindowRect(hwnd, &mut win_rect).is_err() {
-        return TRUE;
+        return None;
     }
 
     let rect = Rect::new(
@@ -458,19 +739,17 @@ unsafe extern "system" fn enum_windows_callback(hwnd: HWND, lparam: LPARAM) -> B
 
     // Skip zero-size windows
     if rect.width == 0 || rect.height == 0 {
-        return TRUE;
+        return None;
     }
 
-    windows.push(WindowInfo {
+    Some(WindowInfo {
         hwnd: hwnd.0 as WindowId,
         title,
         class_name,
         process_id,
         rect,
         visible: true,
-    });
-
-    TRUE
+    })
 }
 
 /// Check if a window should be skipped based on its title.
@@ -555,6 +834,120 @@ pub fn get_process_executable(pid: u32) -> Option<String> {
     }
 }
 
+/// Get the full command line of a process by PID.
+///
+/// Requires Windows 8.1 or later. Returns None if the process cannot be
+/// accessed or doesn't exist.
+pub fn get_process_command_line(pid: u32) -> Option<String> {
+    unsafe {
+        let handle = OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid).ok()?;
+
+        // First call reports the required buffer size
+        let mut needed: u32 = 0;
+        let _ = NtQueryInformationProcess(
+            handle,
+            ProcessCommandLineInformation,
+            std::ptr::null_mut(),
+            0,
+            &mut needed,
+        );
+
+        let mut command_line = None;
+        if needed as usize >= std::mem::size_of::<UNICODE_STRING>() {
+            // u64 storage keeps the UNICODE_STRING header aligned
+            let mut buffer: Vec<u64> = vec![0; (needed as usize).div_ceil(8)];
+            let status = NtQueryInformationProcess(
+                handle,
+                ProcessCommandLineInformation,
+                buffer.as_mut_ptr().cast(),
+                needed,
+                &mut needed,
+            );
+            if status.is_ok() {
+                let header = &*(buffer.as_ptr() as *const UNICODE_STRING);
+                if !header.Buffer.is_null() {
+                    let chars = std::slice::from_raw_parts(header.Buffer.0, header.Length as usize / 2);
+                    command_line = Some(String::from_utf16_lossy(chars));
+                }
+            }
+        }
+
+        let _ = CloseHandle(handle);
+        command_line
+    }
+}
+
+/// Get the ID of the process that owns a window.
+///
+/// Returns None if the window handle is invalid.
+pub fn get_window_process_id(hwnd: WindowId) -> Option<u32> {
+    let mut process_id: u32 = 0;
+    unsafe { GetWindowThreadProcessId(HWND(hwnd as *mut c_void), Some(&mut process_id)) };
+    (process_id != 0).then_some(process_id)
+}
+
+/// Get the current foreground window, if any.
+pub fn get_foreground_window() -> Option<WindowId> {
+    let hwnd = unsafe { GetForegroundWindow() };
+    (!hwnd.0.is_null()).then_some(hwnd.0 as WindowId)
+}
+
+/// Upper bound on the windows [`window_z_order`] walks, in case windows
+/// reordered during the walk make the chain loop.
+const MAX_Z_ORDER_WINDOWS: usize = 10_000;
+
+/// Top-level windows in Z-order, topmost first.
+///
+/// Activating a window raises it, so apart from always-on-top windows this
+/// is the order in which windows were last used, as in the Alt+Tab list.
+pub fn window_z_order() -> Vec<WindowId> {
+    let mut order = Vec::new();
+    let mut next = unsafe { GetTopWindow(None) };
+    while let Ok(hwnd) = next {
+        if hwnd.0.is_null() || order.len() >= MAX_Z_ORDER_WINDOWS {
+            break;
+        }
+        order.push(hwnd.0 as WindowId);
+        next = unsafe { GetWindow(hwnd, GW_HWNDNEXT) };
+    }
+    order
+}
+
+/// Get the current local time as minutes since midnight.
+pub fn local_minute_of_day() -> u32 {
+    let time = unsafe { GetLocalTime() };
+    u32::from(time.wHour) * 60 + u32::from(time.wMinute)
+}
+
+/// Get the Remote Desktop Services session ID that owns a process.
+///
+/// Returns None if the process cannot be queried.
+pub fn get_process_session_id(pid: u32) -> Option<u32> {
+    let mut session_id: u32 = 0;
+    unsafe { ProcessIdToSessionId(pid, &mut session_id) }
+        .ok()
+        .map(|_| session_id)
+}
+
+/// Get the session ID of the current process.
+///
+/// Each interactive logon (console, RDP) has its own session, and runs its
+/// own daemon instance.
+pub fn current_session_id() -> Option<u32> {
+    get_process_session_id(unsafe { GetCurrentProcessId() })
+}
+
+/// Check whether a window's session matches our own.
+///
+/// Unknown sessions are treated as matching so a failed lookup never hides
+/// windows from the user.
+pub fn is_same_session(window_session: Option<u32>, own_session: Option<u32>) -> bool {
+    match (window_session, own_session) {
+        (Some(window), Some(own)) => window == own,
+        _ => true,
+    }
+}
+
 /// Check if a window handle is still valid.
 ///
 /// This helps prevent race conditions where a window is destroyed
@@ -569,12 +962,145 @@ pub fn is_valid_window(hwnd: WindowId) -> bool {
     }
 }
 
+/// Get the current screen rectangle of a window.
+///
+/// Returns `None` if the window handle is invalid.
+pub fn get_window_rect(hwnd: WindowId) -> Option<Rect> {
+    unsafe {
+        let hwnd = HWND(hwnd as *mut c_void);
+        let mut rect = RECT::default();
+        GetWindowRect(hwnd, &mut rect).ok()?;
+        Some(Rect::new(
+            rect.left,
+            rect.top,
+            rect.right - rect.left,
+            rect.bottom - rect.top,
+        ))
+    }
+}
+
+/// How long to wait for a window to report its size limits, in milliseconds.
+const MINMAXINFO_TIMEOUT_MS: u32 = 100;
+
+/// Query the size limits a window enforces on itself via `WM_GETMINMAXINFO`.
+///
+/// Maximums at the system default (the whole virtual screen) are reported as
+/// unconstrained. Returns `None` if the window doesn't answer in time, e.g.
+/// because it's hung.
+pub fn get_size_constraints(hwnd: WindowId) -> Option<SizeConstraints> {
+    unsafe {
+        let hwnd = HWND(hwnd as *mut c_void);
+        let default_max_width = GetSystemMetrics(SM_CXMAXTRACK);
+        let default_max_height = GetSystemMetrics(SM_CYMAXTRACK);
+        let mut info = MINMAXINFO::default();
+        info.ptMaxTrackSize.x = default_max_width;
+        info.ptMaxTrackSize.y = default_max_height;
+
+        let answered = SendMessageTimeoutW(
+            hwnd,
+            WM_GETMINMAXINFO,
+            WPARAM(0),
+            LPARAM(&mut info as *mut MINMAXINFO as isize),
+            SMTO_ABORTIFHUNG | SMTO_BLOCK,
+            MINMAXINFO_TIMEOUT_MS,
+            None,
+        );
+        if answered.0 == 0 {
+            return None;
+        }
+
+        let max = |value: i32, default: i32| (value > 0 && value < default).then_some(value);
+        Some(SizeConstraints {
+            min_width: info.ptMinTrackSize.x.max(0),
+            min_height: info.ptMinTrackSize.y.max(0),
+            max_width: max(info.ptMaxTrackSize.x, default_max_width),
+            max_height: max(info.ptMaxTrackSize.y, default_max_height),
+        })
+    }
+}
+
+/// Decode window text read with GetWindowTextW.
+///
+/// A title that grew between measuring and reading it is cut at the buffer
+/// size, which can split a surrogate pair; the dangling high surrogate is
+/// dropped instead of becoming a replacement character. Other unpaired
+/// surrogates are replaced with U+FFFD so the text is always valid UTF-8.
+pub fn decode_window_text(units: &[u16]) -> String {
+    let units = match units.split_last() {
+        Some((&last, rest)) if (0xD800..=0xDBFF).contains(&last) => rest,
+        _ => units,
+    };
+    String::from_utf16_lossy(units)
+}
+
+/// Get the current title of a window.
+///
+/// Returns an empty string if the window has no title or the handle is invalid.
+pub fn get_window_title(hwnd: WindowId) -> String {
+    unsafe {
+        let hwnd = HWND(hwnd as *mut c_void);
+        let title_len = GetWindowTextLengthW(hwnd);
+        if title_len <= 0 {
+            return String::new();
+        }
+        let mut title_buf: Vec<u16> = vec![0; (title_len + 1) as usize];
+        let actual_len = GetWindowTextW(hwnd, &mut title_buf);
+        decode_window_text(&title_buf[..actual_len.max(0) as usize])
+    }
+}
+
+/// Get a window's class name.
+///
+/// Returns an empty string if the handle is invalid.
+pub fn get_window_class_name(hwnd: WindowId) -> String {
+    unsafe {
+        let mut class_buf: Vec<u16> = vec![0; 256];
+        let class_len = GetClassNameW(HWND(hwnd as *mut c_void), &mut class_buf);
+        String::from_utf16_lossy(&class_buf[..class_len.max(0) as usize])
+    }
+}
+
+/// Get the current cursor position in screen coordinates.
+pub fn get_cursor_position() -> Option<(i32, i32)> {
+    unsafe {
+        let mut point = windows::Win32::Foundation::POINT::default();
+        GetCursorPos(&mut point).ok()?;
+        Some((point.x, point.y))
+    }
+}
+
+/// Check whether a key is currently held down.
+///
+/// Samples the asynchronous key state, so it works regardless of which
+/// thread or window has keyboard focus (e.g. during a modal move loop).
+pub fn is_key_down(vk: u32) -> bool {
+    unsafe { (GetAsyncKeyState(vk as i32) as u16 & 0x8000) != 0 }
+}
+
+/// Time since the last keyboard or mouse input of the session.
+///
+/// Returns None if the input time is unavailable.
+pub fn last_input_age() -> Option<std::time::Duration> {
+    let mut info = LASTINPUTINFO { cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32, dwTime: 0 };
+    if !unsafe { GetLastInputInfo(&mut info) }.as_bool() {
+        return None;
+    }
+    // Both tick counts wrap after 49.7 days
+    let elapsed = unsafe { GetTickCount() }.wrapping_sub(info.dwTime);
+    Some(std::time::Duration::from_millis(u64::from(elapsed)))
+}
+
 /// Apply window placements from the layout engine.
 ///
 /// This function:
 /// 1. Groups placements by visibility
-/// 2. Uses DeferWindowPos for visible windows (batched move)
-/// 3. Applies cloaking/uncloaking based on visibility changes
+/// 2. Pre-moves windows that land on a monitor with a different DPI
+/// 3. Uses DeferWindowPos for visible windows (batched move)
+/// 4. Applies cloaking/uncloaking based on visibility changes
+///
+/// Placements are in physical pixels, which is what the per-monitor aware
+/// daemon sees; Windows scales the contents of windows that aren't
+/// per-monitor aware, so only their DPI change needs handling here.
 pub fn apply_placements(
     placements: &[WindowPlacement],
     config: &PlatformConfig,
@@ -590,6 +1116,12 @@ pub fn apply_placements(
 
     // Apply positions for visible windows
     if !visible.is_empty() {
+        // Windows minimized for the layout are restored first; positioning
+        // an iconic window would only move its restore rect
+        for placement in &visible {
+            restore_layout_minimized(placement.window_id);
+        }
+        premove_across_dpi(&visible);
         if config.use_deferred_positioning {
             apply_placements_deferred(&visible)?;
         } else {
@@ -613,6 +1145,13 @@ pub fn apply_placements(
                 }
             }
         }
+        HideStrategy::Minimize => {
+            for placement in &offscreen {
+                if let Err(e) = minimize_for_layout(placement.window_id) {
+                    tracing::warn!("Failed to minimize window {}: {}", placement.window_id, e);
+                }
+            }
+        }
         HideStrategy::MoveOffScreen => {
             // Move windows far off-screen (don't cloak them)
             // They remain in Alt-Tab but aren't visible
@@ -640,6 +1179,83 @@ pub fn apply_placements(
     Ok(())
 }
 
+/// Windows minimized by [`HideStrategy::Minimize`] rather than by the user.
+static LAYOUT_MINIMIZED: std::sync::Mutex<BTreeSet<WindowId>> = std::sync::Mutex::new(BTreeSet::new());
+
+/// Whether a window is currently minimized (iconic).
+pub fn is_window_minimized(hwnd: WindowId) -> bool {
+    window_id_to_hwnd(hwnd).is_ok_and(|hwnd| unsafe { IsIconic(hwnd).as_bool() })
+}
+
+/// Whether a window was minimized because it scrolled out of view under
+/// [`HideStrategy::Minimize`], as opposed to minimized by the user.
+pub fn is_layout_minimized(hwnd: WindowId) -> bool {
+    LAYOUT_MINIMIZED.lock().is_ok_and(|set| set.contains(&hwnd))
+}
+
+/// Forget that the layout minimized a window, returning whether it had.
+///
+/// A restore event for such a window means the user brought it back from
+/// the taskbar; the layout should then scroll to it.
+pub fn take_layout_minimized(hwnd: WindowId) -> bool {
+    LAYOUT_MINIMIZED.lock().is_ok_and(|mut set| set.remove(&hwnd))
+}
+
+/// Minimize an off-strip window without activating another window.
+fn minimize_for_layout(hwnd: WindowId) -> Result<(), Win32Error> {
+    let handle = window_id_to_hwnd(hwnd)?;
+    if unsafe { IsIconic(handle).as_bool() } {
+        return Ok(());
+    }
+    // Recorded before minimizing so the minimize event is recognized as ours
+    if let Ok(mut set) = LAYOUT_MINIMIZED.lock() {
+        set.insert(hwnd);
+    }
+    unsafe {
+        let _ = ShowWindow(handle, SW_SHOWMINNOACTIVE);
+    }
+    Ok(())
+}
+
+/// Restore a window minimized by [`minimize_for_layout`] without activating it.
+fn restore_layout_minimized(hwnd: WindowId) {
+    // Forgotten before restoring so the restore event isn't taken for the user's
+    if !take_layout_minimized(hwnd) {
+        return;
+    }
+    if let Ok(handle) = window_id_to_hwnd(hwnd) {
+        unsafe {
+            if IsIconic(handle).as_bool() {
+                let _ = ShowWindow(handle, SW_SHOWNOACTIVATE);
+            }
+        }
+    }
+}
+
+/// Move windows that change monitor DPI to their target once up front.
+///
+/// A window moved onto a monitor with a different DPI handles WM_DPICHANGED
+/// by resizing itself to a scaled rect, overriding the placement. Moving it
+/// first lets that resize happen before the batched move sets the exact rect.
+fn premove_across_dpi(placements: &[&WindowPlacement]) {
+    for placement in placements {
+        let window_dpi = get_window_dpi(placement.window_id);
+        let target_dpi = dpi_at_rect(&placement.rect);
+        if !crosses_dpi_boundary(window_dpi, target_dpi) {
+            continue;
+        }
+        tracing::debug!(
+            "Window {} moves from {:?} to {:?} DPI, positioning twice",
+            placement.window_id,
+            window_dpi,
+            target_dpi
+        );
+        if let Err(e) = set_window_pos_immediate(placement) {
+            tracing::warn!("DPI pre-move failed for window {}: {}", placement.window_id, e);
+        }
+    }
+}
+
 /// Apply placements using DeferWindowPos for batched positioning.
 ///
 /// This function uses the Windows DeferWindowPos API to batch multiple
@@ -862,6 +1478,27 @@ pub fn close_window(hwnd: WindowId) -> Result<(), Win32Error> {
     Ok(())
 }
 
+/// Keep a window above all non-topmost windows, or return it to the normal z-order.
+///
+/// Uses `SetWindowPos` with `HWND_TOPMOST` / `HWND_NOTOPMOST` without moving,
+/// resizing or activating the window.
+pub fn set_window_topmost(hwnd: WindowId, topmost: bool) -> Result<(), Win32Error> {
+    let hwnd = window_id_to_hwnd(hwnd)?;
+    let insert_after = if topmost { HWND_TOPMOST } else { HWND_NOTOPMOST };
+    unsafe {
+        SetWindowPos(hwnd, Some(insert_after), 0, 0, 0, 0, SWP_NOMOVE | SWP_NOSIZE | SWP_NOACTIVATE)
+            .map_err(|e| Win32Error::SetPositionFailed(format!("SetWindowPos(topmost) failed: {}", e)))
+    }
+}
+
+/// Whether a window currently has the topmost (always-on-top) style.
+pub fn is_window_topmost(hwnd: WindowId) -> bool {
+    let Ok(hwnd) = window_id_to_hwnd(hwnd) else {
+        return false;
+    };
+    unsafe { GetWindowLongW(hwnd, GWL_EXSTYLE) as u32 & WS_EX_TOPMOST.0 != 0 }
+}
+
 /// Set the DWM border color for a window (Windows 11+).
 ///
 /// Returns Ok(true) if the border was set, Ok(false) if the API is unsupported.
@@ -894,8 +1531,8 @@ pub fn reset_window_border_color(hwnd: WindowId) -> Result<bool, Win32Error> {
 
 /// Uncloak a list of managed windows, best-effort.
 ///
-/// Iterates through the provided window IDs and uncloaks each one.
-/// Logs warnings for failures but never panics. Also resets border colors.
+/// Iterates through the provided window IDs and uncloaks each one, restoring
+/// windows minimized by [`HideStrategy::Minimize`]. Logs warnings for failures but never panics. Also resets border colors.
 pub fn uncloak_all_managed_windows(window_ids: &[WindowId]) {
     for &wid in window_ids {
         if wid == 0 {
@@ -904,6 +1541,7 @@ pub fn uncloak_all_managed_windows(window_ids: &[WindowId]) {
         if let Err(e) = uncloak_window(wid) {
             tracing::warn!("Failed to uncloak window {} during shutdown: {}", wid, e);
         }
+        restore_layout_minimized(wid);
         // Best-effort border reset
         let _ = reset_window_border_color(wid);
     }
@@ -934,6 +1572,26 @@ unsafe extern "system" fn uncloak_all_callback(hwnd: HWND, _lparam: LPARAM) -> B
     TRUE // continue enumeration
 }
 
+/// Block until DWM composes the next frame, i.e. the next vblank.
+///
+/// Returns false without waiting if composition timing is unavailable
+/// (e.g. in some remote sessions), so callers can fall back to a timer.
+pub fn wait_for_vblank() -> bool {
+    unsafe { DwmFlush().is_ok() }
+}
+
+/// Display refresh period reported by DWM composition timing, if available.
+pub fn composition_refresh_period() -> Option<std::time::Duration> {
+    let mut info = DWM_TIMING_INFO {
+        cbSize: std::mem::size_of::<DWM_TIMING_INFO>() as u32,
+        ..Default::default()
+    };
+    unsafe { DwmGetCompositionTimingInfo(HWND::default(), &mut info) }.ok()?;
+    let rate = info.rateRefresh;
+    let (frames, seconds) = (rate.uiNumerator, rate.uiDenominator);
+    (frames > 0 && seconds > 0).then(|| std::time::Duration::from_secs_f64(f64::from(seconds) / f64::from(frames)))
+}
+
 /// Set the process DPI awareness to Per-Monitor Aware V2.
 ///
 /// This must be called as early as possible in `main()`, before any
@@ -964,24 +1622,241 @@ pub enum WindowEvent {
     MovedOrResized(WindowId),
     /// Display configuration changed (monitors added/removed/rearranged).
     DisplayChange,
+    /// Monitor work areas changed because the taskbar or a docked toolbar
+    /// moved, resized or toggled auto-hide.
+    WorkAreaChange,
     /// Mouse cursor entered a window (for focus-follows-mouse).
     MouseEnterWindow(WindowId),
+    /// The user started an interactive move or resize of a window.
+    MoveSizeStart(WindowId),
+    /// The user finished an interactive move or resize of a window.
+    MoveSizeEnd(WindowId),
+    /// A window's title changed.
+    TitleChanged(WindowId),
+    /// A window flashed its taskbar button to request attention.
+    Flashed(WindowId),
+    /// The mouse wheel turned over a managed window's title bar, or with Win
+    /// held, and should scroll the viewport of the monitor under the cursor.
+    WheelScroll {
+        /// Monitor under the cursor.
+        monitor: MonitorId,
+        /// Wheel delta (120 per notch); positive scrolls right.
+        delta: i32,
+    },
+    /// The left mouse button was pressed over a managed window.
+    MouseButtonDown {
+        /// Top-level window under the cursor.
+        window_id: WindowId,
+        /// Cursor x position (screen coordinates).
+        x: i32,
+        /// Cursor y position (screen coordinates).
+        y: i32,
+    },
+    /// The left mouse button was released.
+    MouseButtonUp,
 }
 
-/// Global sender for window events from WinEvent callbacks.
-///
-/// This uses a thread-safe channel because WinEvent callbacks run on Windows'
-/// internal thread pool and we need to forward events to the async runtime.
-static EVENT_SENDER: std::sync::OnceLock<mpsc::Sender<WindowEvent>> = std::sync::OnceLock::new();
+impl WindowEvent {
+    /// Whether this event may be dropped when the event channel is full.
+    ///
+    /// Move/resize, hover and title notifications arrive in bursts and are
+    /// superseded by the next one, so losing a few only delays a re-layout.
+    /// Lifecycle and focus events are never dropped.
+    pub fn is_droppable(&self) -> bool {
+        matches!(
+            self,
+            WindowEvent::MovedOrResized(_) | WindowEvent::MouseEnterWindow(_) | WindowEvent::TitleChanged(_)
+        )
+    }
+}
 
-/// Handle for installed event hooks.
-///
-/// Dropping this handle will unhook all installed event hooks.
-pub struct EventHookHandle {
-    hooks: Vec<HWINEVENTHOOK>,
+/// Backlog of the WinEvent hook channel past which droppable events are
+/// discarded.
+pub const EVENT_CHANNEL_CAPACITY: usize = 1024;
+
+/// A WinEvent as received by the hook callback, before any filtering.
+#[derive(Debug, Clone, Copy)]
+struct RawWinEvent {
+    /// WinEvent code (e.g. EVENT_OBJECT_CREATE).
+    event: u32,
+    /// Window handle the event was raised for.
+    hwnd: isize,
 }
 
-impl Drop for EventHookHandle {
+impl RawWinEvent {
+    /// Location and title changes arrive in bursts and may be dropped when the queue is full.
+    fn is_droppable(&self) -> bool {
+        self.event == EVENT_OBJECT_LOCATIONCHANGE || self.event == EVENT_OBJECT_NAMECHANGE
+    }
+}
+
+/// Global sender for raw events from WinEvent callbacks.
+///
+/// The callback runs on Windows' hook dispatch thread, so it only queues the
+/// event code and handle here; window queries and classification happen on
+/// the event filter thread (see [`run_event_filter`]).
+static RAW_EVENT_SENDER: std::sync::OnceLock<mpsc::Sender<RawWinEvent>> = std::sync::OnceLock::new();
+
+/// Raw events queued for the event filter thread.
+static RAW_EVENT_QUEUE: HookQueue = HookQueue::new();
+
+/// Classified events queued for the daemon.
+static EVENT_QUEUE: HookQueue = HookQueue::new();
+
+/// Counters of an unbounded queue fed from a hook.
+///
+/// Sending never blocks, so a consumer that falls behind can't stall the
+/// thread delivering hook callbacks. Once [`EVENT_CHANNEL_CAPACITY`] events
+/// are pending, droppable ones are discarded instead of queued, so a hook
+/// storm can't grow memory without limit; lifecycle events are always queued.
+#[derive(Debug)]
+struct HookQueue {
+    /// Events currently queued.
+    backlog: AtomicUsize,
+    /// Highest backlog observed.
+    peak: AtomicUsize,
+    /// Droppable events discarded because the backlog was full.
+    dropped: AtomicU64,
+}
+
+impl HookQueue {
+    const fn new() -> Self {
+        Self { backlog: AtomicUsize::new(0), peak: AtomicUsize::new(0), dropped: AtomicU64::new(0) }
+    }
+
+    /// Queue `event` without blocking, unless the backlog is full and the
+    /// event `droppable`. Returns whether it was queued.
+    fn send<T>(&self, sender: &mpsc::Sender<T>, event: T, droppable: bool) -> bool {
+        // Count before sending so the receiver can never decrement below zero
+        let backlog = self.backlog.fetch_add(1, Ordering::Relaxed) + 1;
+        if backlog > EVENT_CHANNEL_CAPACITY && droppable {
+            self.backlog.fetch_sub(1, Ordering::Relaxed);
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+            return false;
+        }
+        if sender.send(event).is_err() {
+            self.backlog.fetch_sub(1, Ordering::Relaxed);
+            return false;
+        }
+        self.peak.fetch_max(backlog, Ordering::Relaxed);
+        true
+    }
+
+    /// Account for an event taken off the queue.
+    fn received(&self) {
+        self.backlog.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// Location changes merged into an already pending one for the same window.
+static LOCATION_EVENTS_COALESCED: AtomicU64 = AtomicU64::new(0);
+
+/// Location changes discarded because the window isn't managed.
+static LOCATION_EVENTS_UNMANAGED: AtomicU64 = AtomicU64::new(0);
+
+/// Location changes for the same window within this period are merged into one.
+pub const LOCATION_COALESCE_WINDOW: std::time::Duration = std::time::Duration::from_millis(50);
+
+/// Windows whose location changes are forwarded, or `None` to forward all.
+#[derive(Debug)]
+struct ManagedWindows(std::sync::Mutex<Option<BTreeSet<WindowId>>>);
+
+impl ManagedWindows {
+    const fn new() -> Self {
+        Self(std::sync::Mutex::new(None))
+    }
+
+    /// Replace the set of managed windows.
+    fn set(&self, ids: BTreeSet<WindowId>) {
+        let mut managed = self.0.lock().unwrap_or_else(recover_poisoned_mutex);
+        if managed.as_ref() != Some(&ids) {
+            *managed = Some(ids);
+        }
+    }
+
+    /// Whether `hwnd` is managed; every window is until the set is first given.
+    fn contains(&self, hwnd: WindowId) -> bool {
+        self.0
+            .lock()
+            .unwrap_or_else(recover_poisoned_mutex)
+            .as_ref()
+            .is_none_or(|managed| managed.contains(&hwnd))
+    }
+}
+
+/// Windows the daemon manages, read by the event filter thread.
+static MANAGED_WINDOWS: ManagedWindows = ManagedWindows::new();
+
+/// Set the windows the daemon manages.
+///
+/// Location changes of any other window are dropped on the event filter
+/// thread instead of being queued for the daemon. Until this is first
+/// called, location changes of all visible windows are forwarded.
+pub fn set_managed_windows(ids: impl IntoIterator<Item = WindowId>) {
+    MANAGED_WINDOWS.set(ids.into_iter().collect());
+}
+
+/// Whether location changes of `hwnd` should reach the daemon.
+fn is_managed_window(hwnd: WindowId) -> bool {
+    MANAGED_WINDOWS.contains(hwnd)
+}
+
+/// Snapshot of the WinEvent hook channel counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EventChannelStats {
+    /// Backlog past which low-importance events are dropped.
+    pub capacity: usize,
+    /// Events currently queued.
+    pub backlog: usize,
+    /// Highest backlog observed.
+    pub peak_backlog: usize,
+    /// Low-importance events dropped because the backlog was full.
+    pub dropped: u64,
+    /// Location changes merged into a pending one for the same window.
+    pub coalesced: u64,
+    /// Location changes dropped because the window isn't managed.
+    pub unmanaged_dropped: u64,
+}
+
+/// Get the current WinEvent hook channel counters.
+pub fn event_channel_stats() -> EventChannelStats {
+    EventChannelStats {
+        capacity: EVENT_CHANNEL_CAPACITY,
+        backlog: EVENT_QUEUE.backlog.load(Ordering::Relaxed),
+        peak_backlog: EVENT_QUEUE.peak.load(Ordering::Relaxed),
+        dropped: RAW_EVENT_QUEUE.dropped.load(Ordering::Relaxed) + EVENT_QUEUE.dropped.load(Ordering::Relaxed),
+        coalesced: LOCATION_EVENTS_COALESCED.load(Ordering::Relaxed),
+        unmanaged_dropped: LOCATION_EVENTS_UNMANAGED.load(Ordering::Relaxed),
+    }
+}
+
+/// Receiving end of the WinEvent hook channel.
+///
+/// Iterating blocks until the next event arrives and ends when the hooks are
+/// gone. Received events are subtracted from the backlog counter.
+pub struct EventReceiver {
+    inner: mpsc::Receiver<WindowEvent>,
+    queue: &'static HookQueue,
+}
+
+impl Iterator for EventReceiver {
+    type Item = WindowEvent;
+
+    fn next(&mut self) -> Option<WindowEvent> {
+        let event = self.inner.recv().ok()?;
+        self.queue.received();
+        Some(event)
+    }
+}
+
+/// Handle for installed event hooks.
+///
+/// Dropping this handle will unhook all installed event hooks.
+pub struct EventHookHandle {
+    hooks: Vec<HWINEVENTHOOK>,
+}
+
+impl Drop for EventHookHandle {
     fn drop(&mut self) {
         for hook in &self.hooks {
             unsafe {
@@ -997,7 +1872,9 @@ impl Drop for EventHookHandle {
 /// Install WinEvent hooks to receive window lifecycle events.
 ///
 /// Returns a handle that must be kept alive to receive events.
-/// Also returns a receiver channel for the events.
+/// Also returns a receiver for the events. Past a backlog of
+/// [`EVENT_CHANNEL_CAPACITY`] events, droppable ones are discarded; see
+/// [`event_channel_stats`].
 ///
 /// # Events Hooked
 /// - Window creation (EVENT_OBJECT_CREATE)
@@ -1005,23 +1882,32 @@ impl Drop for EventHookHandle {
 /// - Foreground change (EVENT_SYSTEM_FOREGROUND)
 /// - Minimize/restore (EVENT_SYSTEM_MINIMIZESTART/END)
 /// - Move/resize (EVENT_OBJECT_LOCATIONCHANGE)
-pub fn install_event_hooks() -> Result<(EventHookHandle, mpsc::Receiver<WindowEvent>), Win32Error> {
-    // Create channel for events
+/// - Interactive move/resize drags (EVENT_SYSTEM_MOVESIZESTART/END)
+/// - Title changes (EVENT_OBJECT_NAMECHANGE)
+pub fn install_event_hooks() -> Result<(EventHookHandle, EventReceiver), Win32Error> {
+    // Create channels for raw callback events and classified events
+    let (raw_tx, raw_rx) = mpsc::channel();
     let (tx, rx) = mpsc::channel();
 
-    // Store sender globally for callback access
-    EVENT_SENDER
-        .set(tx)
+    // Store raw sender globally for callback access
+    RAW_EVENT_SENDER
+        .set(raw_tx)
         .map_err(|_| Win32Error::HookInstallFailed("Event sender already initialized".to_string()))?;
 
+    std::thread::Builder::new()
+        .name("openniri-event-filter".to_string())
+        .spawn(move || run_event_filter(raw_rx, tx))
+        .map_err(|e| Win32Error::HookInstallFailed(format!("Failed to start event filter thread: {}", e)))?;
+
     let mut hooks = Vec::new();
 
     // Define events to hook: (min_event, max_event)
     let event_ranges = [
         (EVENT_OBJECT_CREATE, EVENT_OBJECT_DESTROY),      // Create/Destroy
         (EVENT_SYSTEM_FOREGROUND, EVENT_SYSTEM_FOREGROUND), // Foreground
+        (EVENT_SYSTEM_MOVESIZESTART, EVENT_SYSTEM_MOVESIZEEND), // Drag start/end
         (EVENT_SYSTEM_MINIMIZESTART, EVENT_SYSTEM_MINIMIZEEND), // Minimize
-        (EVENT_OBJECT_LOCATIONCHANGE, EVENT_OBJECT_LOCATIONCHANGE), // Move/Resize
+        (EVENT_OBJECT_LOCATIONCHANGE, EVENT_OBJECT_NAMECHANGE), // Move/Resize, title
         (EVENT_OBJECT_FOCUS, EVENT_OBJECT_FOCUS),         // Focus within app
     ];
 
@@ -1053,13 +1939,14 @@ pub fn install_event_hooks() -> Result<(EventHookHandle, mpsc::Receiver<WindowEv
     }
 
     tracing::info!("Installed {} WinEvent hooks", hooks.len());
-    Ok((EventHookHandle { hooks }, rx))
+    Ok((EventHookHandle { hooks }, EventReceiver { inner: rx, queue: &EVENT_QUEUE }))
 }
 
 /// Callback function for WinEvent hooks.
 ///
-/// This runs on Windows' thread pool, so we forward events to the channel.
-/// Wrapped with catch_unwind to prevent panics from crashing the application.
+/// This runs on Windows' hook dispatch thread, so it only queues the raw
+/// event for the filter thread. Wrapped with catch_unwind to prevent panics
+/// from crashing the application.
 unsafe extern "system" fn win_event_callback(
     hook: HWINEVENTHOOK,
     event: u32,
@@ -1080,6 +1967,9 @@ unsafe extern "system" fn win_event_callback(
 }
 
 /// Inner implementation of WinEvent callback.
+///
+/// Only cheap checks on the callback arguments happen here; anything that
+/// calls into Win32 belongs in [`classify_win_event`].
 fn win_event_callback_inner(
     _hook: HWINEVENTHOOK,
     event: u32,
@@ -1099,18 +1989,148 @@ fn win_event_callback_inner(
         return;
     }
 
+    if let Some(sender) = RAW_EVENT_SENDER.get() {
+        let raw = RawWinEvent { event, hwnd: hwnd.0 as isize };
+        RAW_EVENT_QUEUE.send(sender, raw, raw.is_droppable());
+    }
+}
+
+/// Merges bursts of location changes per window.
+///
+/// The first change of a window starts a [`LOCATION_COALESCE_WINDOW`]
+/// period; further changes within it are absorbed, and one change is
+/// forwarded when the period ends.
+#[derive(Debug, Default)]
+struct LocationCoalescer {
+    /// Windows with a pending change, and when it is due.
+    pending: std::collections::HashMap<isize, std::time::Instant>,
+}
+
+impl LocationCoalescer {
+    /// Record a location change of `hwnd` at `now`.
+    ///
+    /// Returns false if it was merged into a pending change.
+    fn offer(&mut self, hwnd: isize, now: std::time::Instant) -> bool {
+        match self.pending.entry(hwnd) {
+            std::collections::hash_map::Entry::Occupied(_) => false,
+            std::collections::hash_map::Entry::Vacant(entry) => {
+                entry.insert(now + LOCATION_COALESCE_WINDOW);
+                true
+            }
+        }
+    }
+
+    /// Remove the pending change of `hwnd`, returning whether there was one.
+    fn take(&mut self, hwnd: isize) -> bool {
+        self.pending.remove(&hwnd).is_some()
+    }
+
+    /// Remove and return the windows whose pending change is due at `now`.
+    fn take_due(&mut self, now: std::time::Instant) -> Vec<isize> {
+        let due: Vec<isize> = self.pending.iter().filter(|(_, &at)| at <= now).map(|(&hwnd, _)| hwnd).collect();
+        for hwnd in &due {
+            self.pending.remove(hwnd);
+        }
+        due
+    }
+
+    /// When the next pending change is due.
+    fn next_deadline(&self) -> Option<std::time::Instant> {
+        self.pending.values().min().copied()
+    }
+}
+
+/// Classify raw WinEvents and forward them to the daemon's event channel.
+///
+/// Runs on its own thread so the Win32 queries used for filtering never
+/// delay Windows' hook dispatch. Location changes, which fire hundreds of
+/// times a second during a drag, are dropped for unmanaged windows and
+/// coalesced per window (see [`LocationCoalescer`]). Exits when either
+/// channel closes.
+fn run_event_filter(raw_events: mpsc::Receiver<RawWinEvent>, sender: mpsc::Sender<WindowEvent>) {
+    let forward = |raw: RawWinEvent| {
+        let result = std::panic::catch_unwind(|| classify_win_event(raw));
+        match result {
+            Ok(Some(event)) => {
+                let droppable = event.is_droppable();
+                EVENT_QUEUE.send(&sender, event, droppable);
+            }
+            Ok(None) => {}
+            Err(e) => tracing::error!("Panic while classifying WinEvent {:#x}: {:?}", raw.event, e),
+        }
+    };
+    let mut coalescer = LocationCoalescer::default();
+
+    loop {
+        let received = match coalescer.next_deadline() {
+            Some(deadline) => {
+                match raw_events.recv_timeout(deadline.saturating_duration_since(std::time::Instant::now())) {
+                    Ok(raw) => Some(raw),
+                    Err(mpsc::RecvTimeoutError::Timeout) => None,
+                    Err(mpsc::RecvTimeoutError::Disconnected) => break,
+                }
+            }
+            None => match raw_events.recv() {
+                Ok(raw) => Some(raw),
+                Err(_) => break,
+            },
+        };
+
+        if let Some(raw) = received {
+            RAW_EVENT_QUEUE.received();
+            if raw.event == EVENT_OBJECT_LOCATIONCHANGE {
+                let hwnd = root_window(raw.hwnd);
+                if !is_managed_window(hwnd as WindowId) {
+                    LOCATION_EVENTS_UNMANAGED.fetch_add(1, Ordering::Relaxed);
+                } else if !coalescer.offer(hwnd, std::time::Instant::now()) {
+                    LOCATION_EVENTS_COALESCED.fetch_add(1, Ordering::Relaxed);
+                }
+            } else {
+                // Keep a window's pending move ahead of its later events
+                let hwnd = root_window(raw.hwnd);
+                if coalescer.take(hwnd) {
+                    forward(RawWinEvent { event: EVENT_OBJECT_LOCATIONCHANGE, hwnd });
+                }
+                forward(raw);
+            }
+        }
+
+        for hwnd in coalescer.take_due(std::time::Instant::now()) {
+            forward(RawWinEvent { event: EVENT_OBJECT_LOCATIONCHANGE, hwnd });
+        }
+    }
+}
+
+/// Top-level window of `hwnd`, or `hwnd` itself if it has none.
+fn root_window(hwnd: isize) -> isize {
+    let root = unsafe { GetAncestor(HWND(hwnd as *mut c_void), GA_ROOT) };
+    if root.0.is_null() { hwnd } else { root.0 as isize }
+}
+
+/// Turn a raw WinEvent into a window event, or None if it should be ignored.
+fn classify_win_event(raw: RawWinEvent) -> Option<WindowEvent> {
+    let hwnd = HWND(raw.hwnd as *mut c_void);
+
     // Get the top-level window (in case we got a child window event)
     let root_hwnd = unsafe { GetAncestor(hwnd, GA_ROOT) };
+    // Child controls changing their text say nothing about the window title
+    if raw.event == EVENT_OBJECT_NAMECHANGE && !root_hwnd.0.is_null() && root_hwnd != hwnd {
+        return None;
+    }
     let hwnd = if root_hwnd.0.is_null() { hwnd } else { root_hwnd };
 
-    let window_id = hwnd.0 as WindowId;
+    map_win_event(raw.event, hwnd.0 as WindowId, || unsafe { IsWindowVisible(hwnd) }.as_bool())
+}
 
-    // Map event to our WindowEvent type
+/// Map a WinEvent code to our WindowEvent type.
+///
+/// `is_visible` is only called for events that are ignored for hidden windows.
+fn map_win_event(event: u32, window_id: WindowId, is_visible: impl FnOnce() -> bool) -> Option<WindowEvent> {
     let window_event = match event {
         EVENT_OBJECT_CREATE => {
             // Quick filter: skip windows that don't look manageable
-            if !unsafe { IsWindowVisible(hwnd) }.as_bool() {
-                return;
+            if !is_visible() {
+                return None;
             }
             WindowEvent::Created(window_id)
         }
@@ -1118,23 +2138,72 @@ fn win_event_callback_inner(
         EVENT_SYSTEM_FOREGROUND | EVENT_OBJECT_FOCUS => WindowEvent::Focused(window_id),
         EVENT_SYSTEM_MINIMIZESTART => WindowEvent::Minimized(window_id),
         EVENT_SYSTEM_MINIMIZEEND => WindowEvent::Restored(window_id),
+        EVENT_SYSTEM_MOVESIZESTART => WindowEvent::MoveSizeStart(window_id),
+        EVENT_SYSTEM_MOVESIZEEND => WindowEvent::MoveSizeEnd(window_id),
         EVENT_OBJECT_LOCATIONCHANGE => {
             // Only track visible windows
-            if !unsafe { IsWindowVisible(hwnd) }.as_bool() {
-                return;
+            if !is_visible() {
+                return None;
             }
             WindowEvent::MovedOrResized(window_id)
         }
-        _ => return,
+        EVENT_OBJECT_NAMECHANGE => {
+            if !is_visible() {
+                return None;
+            }
+            WindowEvent::TitleChanged(window_id)
+        }
+        _ => return None,
     };
+    Some(window_event)
+}
+
+// ============================================================================
+// Readiness Signal
+// ============================================================================
 
-    // Send event through channel
-    if let Some(sender) = EVENT_SENDER.get() {
-        // Use try_send to avoid blocking if channel is full
-        let _ = sender.send(window_event);
+/// A named manual-reset event set while the daemon is ready.
+///
+/// The event exists as long as some process holds a handle to it, so the
+/// daemon keeps this for its whole lifetime.
+pub struct ReadyEvent(windows::Win32::Foundation::HANDLE);
+
+// SAFETY: an event handle can be used and closed from any thread.
+unsafe impl Send for ReadyEvent {}
+
+impl Drop for ReadyEvent {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = CloseHandle(self.0);
+        }
     }
 }
 
+fn open_ready_event(name: &str) -> Result<ReadyEvent, Win32Error> {
+    let wide_name: Vec<u16> = format!("{}\0", name).encode_utf16().collect();
+    // Created unset if nobody has it open yet; opened as-is otherwise
+    unsafe { CreateEventW(None, true, false, windows::core::PCWSTR(wide_name.as_ptr())) }
+        .map(ReadyEvent)
+        .map_err(|e| Win32Error::ReadyEventFailed(format!("{}: {}", name, e)))
+}
+
+/// Set the named event `name`, waking every `wait_for_ready` caller.
+pub fn signal_ready(name: &str) -> Result<ReadyEvent, Win32Error> {
+    let event = open_ready_event(name)?;
+    unsafe { SetEvent(event.0) }.map_err(|e| Win32Error::ReadyEventFailed(format!("{}: {}", name, e)))?;
+    Ok(event)
+}
+
+/// Block until `signal_ready(name)` has been called, or `timeout` passes.
+///
+/// Returns whether the event was set. A waiter that starts before the
+/// daemon creates the event itself, so there is nothing to poll.
+pub fn wait_for_ready(name: &str, timeout: std::time::Duration) -> Result<bool, Win32Error> {
+    let event = open_ready_event(name)?;
+    let millis = u32::try_from(timeout.as_millis()).unwrap_or(u32::MAX - 1);
+    Ok(unsafe { WaitForSingleObject(event.0, millis) } == windows::Win32::Foundation::WAIT_OBJECT_0)
+}
+
 // ============================================================================
 // Global Hotkey Support
 // ============================================================================
@@ -1216,14 +2285,51 @@ pub struct HotkeyEvent {
 static HOTKEY_SENDER: std::sync::Mutex<Option<mpsc::Sender<HotkeyEvent>>> =
     std::sync::Mutex::new(None);
 
-/// Global sender for display change events forwarded to window event channel.
-/// Uses Mutex to allow re-registration after dropping previous EventHookHandle.
-static DISPLAY_CHANGE_SENDER: std::sync::Mutex<Option<mpsc::Sender<WindowEvent>>> =
+/// Global sender for display change and taskbar flash events forwarded to
+/// window event channel.
+/// Uses Mutex to allow re-registration after dropping previous ShellEventsHandle.
+static SHELL_EVENT_SENDER: std::sync::Mutex<Option<mpsc::Sender<WindowEvent>>> =
     std::sync::Mutex::new(None);
 
-/// Custom message to signal the hotkey thread to stop.
+/// Message id of shell hook notifications, registered as "SHELLHOOK" when
+/// the shell events window is created; 0 until then.
+static SHELL_HOOK_MESSAGE: AtomicU32 = AtomicU32::new(0);
+
+/// Custom message to signal the hotkey and shell events threads to stop.
 const WM_QUIT_HOTKEY_THREAD: u32 = WM_USER + 1;
 
+/// Create a hidden top-level tool window of a newly registered class.
+///
+/// A message-only window would be simpler, but it doesn't receive
+/// broadcasts such as WM_DISPLAYCHANGE and WM_SETTINGCHANGE.
+///
+/// # Safety
+/// Must be called on the thread that runs the window's message loop.
+unsafe fn create_hidden_window(
+    class_name: &str,
+    window_proc: windows::Win32::UI::WindowsAndMessaging::WNDPROC,
+) -> windows::core::Result<HWND> {
+    let class_name: Vec<u16> = format!("{}\0", class_name).encode_utf16().collect();
+    let wc = WNDCLASSW {
+        lpfnWndProc: window_proc,
+        lpszClassName: windows::core::PCWSTR(class_name.as_ptr()),
+        ..Default::default()
+    };
+    RegisterClassW(&wc);
+
+    CreateWindowExW(
+        WS_EX_TOOLWINDOW,
+        windows::core::PCWSTR(class_name.as_ptr()),
+        None,
+        Default::default(),
+        0, 0, 0, 0,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
 /// Handle for the hotkey message window and thread.
 ///
 /// Dropping this handle will unregister all hotkeys and stop the message loop.
@@ -1265,25 +2371,105 @@ impl Drop for HotkeyHandle {
             let _ = thread.join();
         }
 
-        // Clear the global senders to allow re-registration (recover from mutex poisoning)
+        // Clear the global sender to allow re-registration (recover from mutex poisoning)
         let mut sender = HOTKEY_SENDER.lock().unwrap_or_else(recover_poisoned_mutex);
         *sender = None;
-        drop(sender);
-        let mut sender = DISPLAY_CHANGE_SENDER.lock().unwrap_or_else(recover_poisoned_mutex);
+    }
+}
+
+/// Handle for the shell events window and its thread.
+///
+/// Dropping this handle deregisters the shell hook and stops the message loop.
+pub struct ShellEventsHandle {
+    hwnd: HWND,
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+impl Drop for ShellEventsHandle {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = DeregisterShellHookWindow(self.hwnd);
+            let _ = PostMessageW(
+                Some(self.hwnd),
+                WM_QUIT_HOTKEY_THREAD,
+                windows::Win32::Foundation::WPARAM(0),
+                windows::Win32::Foundation::LPARAM(0),
+            );
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        let mut sender = SHELL_EVENT_SENDER.lock().unwrap_or_else(recover_poisoned_mutex);
         *sender = None;
     }
 }
 
-/// Register a sender for display change events.
+/// Watch for display changes, work area changes (WM_SETTINGCHANGE with
+/// SPI_SETWORKAREA) and taskbar flashes, forwarding them to `sender`.
 ///
-/// This allows the hotkey window to forward WM_DISPLAYCHANGE messages
-/// to the window event channel. Call this before `register_hotkeys`.
-pub fn set_display_change_sender(sender: mpsc::Sender<WindowEvent>) -> Result<(), Win32Error> {
-    let mut guard = DISPLAY_CHANGE_SENDER
-        .lock()
-        .map_err(|_| Win32Error::HookInstallFailed("Display change sender mutex poisoned".to_string()))?;
-    *guard = Some(sender);
-    Ok(())
+/// The notifications arrive at a hidden window of their own, so they keep
+/// coming whether or not any hotkeys are registered, and across hotkey
+/// re-registration on config reload.
+pub fn watch_shell_events(sender: mpsc::Sender<WindowEvent>) -> Result<ShellEventsHandle, Win32Error> {
+    {
+        let mut guard = SHELL_EVENT_SENDER
+            .lock()
+            .map_err(|_| Win32Error::HookInstallFailed("Shell event sender mutex poisoned".to_string()))?;
+        if guard.is_some() {
+            return Err(Win32Error::HookInstallFailed(
+                "Shell events already watched - drop existing ShellEventsHandle first".to_string(),
+            ));
+        }
+        *guard = Some(sender);
+    }
+
+    // We send isize (raw pointer value) instead of HWND because HWND is !Send
+    let (init_tx, init_rx) = std::sync::mpsc::channel::<Result<isize, Win32Error>>();
+    let thread = std::thread::spawn(move || unsafe {
+        let Ok(hwnd) = create_hidden_window("OpenNiriShellEventsClass", Some(shell_events_window_proc)) else {
+            let _ = init_tx.send(Err(Win32Error::HookInstallFailed(
+                "Failed to create shell events window".to_string(),
+            )));
+            return;
+        };
+
+        // Shell hook notifications report windows flashing their taskbar
+        // button, which is how applications ask for attention
+        let shell_hook_name: Vec<u16> = "SHELLHOOK\0".encode_utf16().collect();
+        SHELL_HOOK_MESSAGE.store(
+            RegisterWindowMessageW(windows::core::PCWSTR(shell_hook_name.as_ptr())),
+            Ordering::Relaxed,
+        );
+        if !RegisterShellHookWindow(hwnd).as_bool() {
+            tracing::warn!("Failed to register shell hook window - taskbar flashes won't be detected");
+        }
+        let _ = init_tx.send(Ok(hwnd.0 as isize));
+
+        let mut msg = MSG::default();
+        while GetMessageW(&mut msg, Some(hwnd), 0, 0).as_bool() {
+            if msg.message == WM_QUIT_HOTKEY_THREAD {
+                break;
+            }
+            let _ = DispatchMessageW(&msg);
+        }
+    });
+
+    let init = init_rx
+        .recv()
+        .map_err(|_| Win32Error::HookInstallFailed("Shell events thread initialization failed".to_string()))
+        .and_then(|result| result);
+    match init {
+        Ok(hwnd_raw) => Ok(ShellEventsHandle {
+            hwnd: HWND(hwnd_raw as *mut c_void),
+            thread: Some(thread),
+        }),
+        Err(e) => {
+            let _ = thread.join();
+            let mut sender = SHELL_EVENT_SENDER.lock().unwrap_or_else(recover_poisoned_mutex);
+            *sender = None;
+            Err(e)
+        }
+    }
 }
 
 /// Register global hotkeys and start listening for them.
@@ -1323,27 +2509,7 @@ pub fn register_hotkeys(
 
     let thread = std::thread::spawn(move || {
         unsafe {
-            // Register window class
-            let class_name: Vec<u16> = "OpenNiriHotkeyClass\0".encode_utf16().collect();
-            let wc = WNDCLASSW {
-                lpfnWndProc: Some(hotkey_window_proc),
-                lpszClassName: windows::core::PCWSTR(class_name.as_ptr()),
-                ..Default::default()
-            };
-            RegisterClassW(&wc);
-
-            // Create message-only window
-            let hwnd = CreateWindowExW(
-                Default::default(),
-                windows::core::PCWSTR(class_name.as_ptr()),
-                None,
-                Default::default(),
-                0, 0, 0, 0,
-                Some(HWND_MESSAGE),
-                None,
-                None,
-                None,
-            );
+            let hwnd = create_hidden_window("OpenNiriHotkeyClass", Some(hotkey_window_proc));
 
             if hwnd.is_err() {
                 let _ = init_tx.send(Err(Win32Error::HotkeyRegistrationFailed(
@@ -1462,17 +2628,76 @@ fn hotkey_window_proc_inner(
 
             windows::Win32::Foundation::LRESULT(0)
         }
+        _ => unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) },
+    }
+}
+
+/// Window procedure for the shell events window.
+///
+/// Wrapped with catch_unwind to prevent panics from crashing the application.
+unsafe extern "system" fn shell_events_window_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: windows::Win32::Foundation::WPARAM,
+    lparam: windows::Win32::Foundation::LPARAM,
+) -> windows::Win32::Foundation::LRESULT {
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        shell_events_window_proc_inner(hwnd, msg, wparam, lparam)
+    }));
+
+    match result {
+        Ok(lresult) => lresult,
+        Err(e) => {
+            tracing::error!("Panic in shell_events_window_proc: {:?}", e);
+            DefWindowProcW(hwnd, msg, wparam, lparam)
+        }
+    }
+}
+
+/// Inner implementation of shell events window procedure.
+fn shell_events_window_proc_inner(
+    hwnd: HWND,
+    msg: u32,
+    wparam: windows::Win32::Foundation::WPARAM,
+    lparam: windows::Win32::Foundation::LPARAM,
+) -> windows::Win32::Foundation::LRESULT {
+    match msg {
         WM_DISPLAYCHANGE => {
             tracing::info!("Display configuration changed (WM_DISPLAYCHANGE)");
 
             // Send display change event through window event channel (recover from mutex poisoning)
-            let sender_guard = DISPLAY_CHANGE_SENDER.lock().unwrap_or_else(recover_poisoned_mutex);
+            let sender_guard = SHELL_EVENT_SENDER.lock().unwrap_or_else(recover_poisoned_mutex);
             if let Some(sender) = sender_guard.as_ref() {
                 let _ = sender.send(WindowEvent::DisplayChange);
             }
 
             windows::Win32::Foundation::LRESULT(0)
         }
+        WM_SETTINGCHANGE if wparam.0 == SPI_SETWORKAREA => {
+            tracing::info!("Monitor work area changed (SPI_SETWORKAREA)");
+
+            let sender_guard = SHELL_EVENT_SENDER.lock().unwrap_or_else(recover_poisoned_mutex);
+            if let Some(sender) = sender_guard.as_ref() {
+                let _ = sender.send(WindowEvent::WorkAreaChange);
+            }
+
+            windows::Win32::Foundation::LRESULT(0)
+        }
+        _ if msg != 0 && msg == SHELL_HOOK_MESSAGE.load(Ordering::Relaxed) => {
+            // HSHELL_FLASH is HSHELL_REDRAW with the high bit set; a plain
+            // redraw is only a title change
+            if wparam.0 == HSHELL_FLASH && lparam.0 != 0 {
+                let window_id = lparam.0 as WindowId;
+                tracing::debug!("Window {} is flashing for attention", window_id);
+
+                let sender_guard = SHELL_EVENT_SENDER.lock().unwrap_or_else(recover_poisoned_mutex);
+                if let Some(sender) = sender_guard.as_ref() {
+                    let _ = sender.send(WindowEvent::Flashed(window_id));
+                }
+            }
+
+            windows::Win32::Foundation::LRESULT(0)
+        }
         _ => unsafe { DefWindowProcW(hwnd, msg, wparam, lparam) },
     }
 }
@@ -1539,6 +2764,13 @@ pub mod vk {
     pub const RIGHT: u32 = 0x27;
     pub const DOWN: u32 = 0x28;
 
+    // Modifiers
+    pub const SHIFT: u32 = 0x10;
+    pub const CONTROL: u32 = 0x11;
+    pub const MENU: u32 = 0x12; // Alt
+    pub const LWIN: u32 = 0x5B;
+    pub const RWIN: u32 = 0x5C;
+
     // Other
     pub const TAB: u32 = 0x09;
     pub const SPACE: u32 = 0x20;
@@ -1552,6 +2784,9 @@ pub mod vk {
     pub const BRACKET_RIGHT: u32 = 0xDD;  // ']'
     pub const COMMA: u32 = 0xBC;      // ','
     pub const PERIOD: u32 = 0xBE;     // '.'
+
+    // Mouse buttons (for polling with `is_key_down`)
+    pub const LBUTTON: u32 = 0x01;
 }
 
 /// Parse a virtual key code from a key name string.
@@ -1601,15 +2836,21 @@ pub fn parse_vk(key: &str) -> Option<u32> {
 ///
 /// Returns modifiers and virtual key code if valid.
 pub fn parse_hotkey_string(s: &str) -> Option<(Modifiers, u32)> {
+    let (modifiers, key) = split_hotkey_string(s)?;
+    let vk = parse_vk(key)?;
+
+    Some((modifiers, vk))
+}
+
+/// Split a binding string into its modifiers and the final key name.
+fn split_hotkey_string(s: &str) -> Option<(Modifiers, &str)> {
     let parts: Vec<&str> = s.split('+').map(|p| p.trim()).collect();
-    if parts.is_empty() {
-        return None;
-    }
+    let (key, modifier_parts) = parts.split_last()?;
 
     let mut modifiers = Modifiers::default();
 
     // Last part is the key, rest are modifiers
-    for part in &parts[..parts.len() - 1] {
+    for part in modifier_parts {
         match part.to_uppercase().as_str() {
             "CTRL" | "CONTROL" => modifiers.ctrl = true,
             "ALT" => modifiers.alt = true,
@@ -1619,11 +2860,7 @@ pub fn parse_hotkey_string(s: &str) -> Option<(Modifiers, u32)> {
         }
     }
 
-    // Parse the key
-    let key = parts.last()?;
-    let vk = parse_vk(key)?;
-
-    Some((modifiers, vk))
+    Some((modifiers, key))
 }
 
 // ============================================================================
@@ -1641,6 +2878,9 @@ pub enum GestureEvent {
     SwipeUp,
     /// Three-finger swipe down
     SwipeDown,
+    /// Horizontal scroll delta in wheel units, sent for every wheel message
+    /// when continuous panning is enabled (positive scrolls right)
+    Pan { delta: i32 },
 }
 
 /// Wheel message constants (not all exposed by windows-rs).
@@ -1663,6 +2903,8 @@ struct GestureAccumState {
     accum_y: i32,
     /// Timestamp of the last scroll event.
     last_scroll_time: std::time::Instant,
+    /// Forward horizontal deltas as [`GestureEvent::Pan`] instead of swipes.
+    continuous: bool,
 }
 
 /// Global sender for gesture events.
@@ -1708,8 +2950,12 @@ impl Drop for GestureHandle {
 ///
 /// Touchpad scroll gestures are delivered as WM_MOUSEWHEEL (vertical) and
 /// WM_MOUSEHWHEEL (horizontal) messages. The hook accumulates wheel deltas
-/// and fires swipe events when the threshold is exceeded.
-pub fn register_gestures() -> Result<(GestureHandle, mpsc::Receiver<GestureEvent>), Win32Error> {
+/// and fires swipe events when the threshold is exceeded. With `continuous`,
+/// horizontal deltas are instead forwarded as they arrive, for panning the
+/// viewport along with the fingers.
+pub fn register_gestures(
+    continuous: bool,
+) -> Result<(GestureHandle, mpsc::Receiver<GestureEvent>), Win32Error> {
     // Create channel for events
     let (tx, rx) = mpsc::channel();
 
@@ -1735,6 +2981,7 @@ pub fn register_gestures() -> Result<(GestureHandle, mpsc::Receiver<GestureEvent
             accum_x: 0,
             accum_y: 0,
             last_scroll_time: std::time::Instant::now(),
+            continuous,
         });
     }
 
@@ -1791,7 +3038,10 @@ unsafe extern "system" fn gesture_mouse_hook_proc(
                 }
 
                 // Check thresholds and determine gesture
-                let gesture = if state.accum_x.abs() >= GESTURE_SCROLL_THRESHOLD {
+                let gesture = if state.continuous && msg == WM_MOUSEHWHEEL {
+                    state.accum_x = 0;
+                    Some(GestureEvent::Pan { delta })
+                } else if state.accum_x.abs() >= GESTURE_SCROLL_THRESHOLD {
                     let g = if state.accum_x > 0 {
                         GestureEvent::SwipeRight
                     } else {
@@ -1832,6 +3082,23 @@ unsafe extern "system" fn gesture_mouse_hook_proc(
 static MOUSE_EVENT_SENDER: std::sync::Mutex<Option<mpsc::Sender<WindowEvent>>> =
     std::sync::Mutex::new(None);
 
+/// What the low-level mouse hook reports.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MouseHookOptions {
+    /// Send [`WindowEvent::MouseEnterWindow`] when the cursor enters a window.
+    pub track_hover: bool,
+    /// Turn the wheel over a managed window's title bar, or with Win held,
+    /// into [`WindowEvent::WheelScroll`]. Such wheel events are swallowed.
+    pub wheel_scroll: bool,
+    /// Send [`WindowEvent::MouseButtonDown`] and [`WindowEvent::MouseButtonUp`]
+    /// for the left mouse button, to detect window drags.
+    pub track_buttons: bool,
+}
+
+/// Options of the installed mouse hook.
+static MOUSE_HOOK_OPTIONS: std::sync::Mutex<MouseHookOptions> =
+    std::sync::Mutex::new(MouseHookOptions { track_hover: false, wheel_scroll: false, track_buttons: false });
+
 /// Track the window the mouse is currently over.
 static CURRENT_MOUSE_WINDOW: std::sync::Mutex<Option<WindowId>> = std::sync::Mutex::new(None);
 
@@ -1857,15 +3124,18 @@ impl Drop for MouseHookHandle {
     }
 }
 
-/// Install a low-level mouse hook for focus-follows-mouse functionality.
+/// Install a low-level mouse hook for focus-follows-mouse and title bar
+/// wheel scrolling.
 ///
 /// Returns a handle that must be kept alive to receive mouse events,
-/// and registers the given sender to receive MouseEnterWindow events.
+/// and registers the given sender to receive the events `options` enables.
 ///
 /// # Arguments
-/// * `event_sender` - Sender for WindowEvent (specifically MouseEnterWindow)
+/// * `event_sender` - Sender for WindowEvent (MouseEnterWindow and WheelScroll)
+/// * `options` - Which events to report
 pub fn install_mouse_hook(
     event_sender: mpsc::Sender<WindowEvent>,
+    options: MouseHookOptions,
 ) -> Result<MouseHookHandle, Win32Error> {
     // Store sender globally
     {
@@ -1879,6 +3149,7 @@ pub fn install_mouse_hook(
         }
         *sender = Some(event_sender);
     }
+    *MOUSE_HOOK_OPTIONS.lock().unwrap_or_else(recover_poisoned_mutex) = options;
 
     // Install low-level mouse hook
     let hook = unsafe {
@@ -1891,15 +3162,188 @@ pub fn install_mouse_hook(
         .map_err(|e| Win32Error::HookInstallFailed(format!("SetWindowsHookExW failed: {}", e)))?
     };
 
-    tracing::info!("Low-level mouse hook installed for focus-follows-mouse");
+    tracing::info!("Low-level mouse hook installed ({:?})", options);
 
     Ok(MouseHookHandle { hook })
 }
 
+/// Whether the left button press seen by [`capture_clicks`] was swallowed,
+/// so its release is swallowed too.
+#[derive(Debug, Default)]
+struct ClickCaptureState {
+    pressed: bool,
+}
+
+impl ClickCaptureState {
+    /// Track a left button event. Returns whether to swallow it.
+    ///
+    /// A button already held when the capture started is released normally.
+    fn on_button(&mut self, down: bool) -> bool {
+        if down {
+            self.pressed = true;
+            true
+        } else {
+            std::mem::take(&mut self.pressed)
+        }
+    }
+}
+
+/// Receives the screen position of each click claimed by [`capture_clicks`].
+type ClickSender = mpsc::Sender<(i32, i32)>;
+
+/// Global state and sender for [`capture_clicks`].
+static CLICK_CAPTURE: std::sync::Mutex<Option<(ClickCaptureState, ClickSender)>> = std::sync::Mutex::new(None);
+
+/// Handle for a click capture.
+///
+/// Dropping this handle removes the hook and releases the left button.
+pub struct ClickCaptureHandle {
+    thread_id: u32,
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+impl Drop for ClickCaptureHandle {
+    fn drop(&mut self) {
+        stop_hook_thread(self.thread_id, self.thread.take());
+        *CLICK_CAPTURE.lock().unwrap_or_else(recover_poisoned_mutex) = None;
+        tracing::debug!("Click capture released");
+    }
+}
+
+/// Claim left clicks until the handle is dropped.
+///
+/// The screen position of every left button press is reported on the
+/// returned receiver, and neither the press nor its release reaches the
+/// window under the cursor. Used while a modal view such as the overview
+/// covers the screen.
+pub fn capture_clicks() -> Result<(ClickCaptureHandle, mpsc::Receiver<(i32, i32)>), Win32Error> {
+    let (tx, rx) = mpsc::channel();
+    {
+        let mut global = CLICK_CAPTURE
+            .lock()
+            .map_err(|_| Win32Error::HookInstallFailed("Click capture mutex poisoned".to_string()))?;
+        if global.is_some() {
+            return Err(Win32Error::HookInstallFailed(
+                "Clicks already captured - drop existing ClickCaptureHandle first".to_string(),
+            ));
+        }
+        *global = Some((ClickCaptureState::default(), tx));
+    }
+
+    match spawn_hook_thread(WH_MOUSE_LL, click_capture_proc) {
+        Ok((thread_id, thread)) => Ok((ClickCaptureHandle { thread_id, thread: Some(thread) }, rx)),
+        Err(e) => {
+            *CLICK_CAPTURE.lock().unwrap_or_else(recover_poisoned_mutex) = None;
+            Err(e)
+        }
+    }
+}
+
+/// Low-level mouse hook callback for [`capture_clicks`].
+unsafe extern "system" fn click_capture_proc(
+    ncode: i32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> windows::Win32::Foundation::LRESULT {
+    let msg = wparam.0 as u32;
+    if ncode >= 0 && (msg == WM_LBUTTONDOWN || msg == WM_LBUTTONUP) {
+        let mut guard = CLICK_CAPTURE.lock().unwrap_or_else(recover_poisoned_mutex);
+        if let Some((state, sender)) = guard.as_mut() {
+            let down = msg == WM_LBUTTONDOWN;
+            if state.on_button(down) {
+                if down {
+                    let pt = (*(lparam.0 as *const MSLLHOOKSTRUCT)).pt;
+                    let _ = sender.send((pt.x, pt.y));
+                }
+                return windows::Win32::Foundation::LRESULT(1);
+            }
+        }
+    }
+
+    CallNextHookEx(None, ncode, wparam, lparam)
+}
+
+/// Whether `(x, y)` lies in the title bar of a window at `window`, taken as
+/// its top `caption_height` pixels.
+fn in_title_bar(window: Rect, x: i32, y: i32, caption_height: i32) -> bool {
+    x >= window.x && x < window.right() && y >= window.y && y < window.y + caption_height
+}
+
+/// Height of a standard title bar, including the resize border above it,
+/// for a window at its DPI.
+fn caption_height(hwnd: HWND) -> i32 {
+    let dpi = unsafe { GetDpiForWindow(hwnd) }.max(96);
+    unsafe {
+        GetSystemMetricsForDpi(SM_CYCAPTION, dpi)
+            + GetSystemMetricsForDpi(SM_CYSIZEFRAME, dpi)
+            + GetSystemMetricsForDpi(SM_CXPADDEDBORDER, dpi)
+    }
+}
+
+/// Viewport scroll for a wheel message, or None if it isn't one to handle.
+///
+/// The wheel scrolls the viewport with Win held (unless a wheel binding
+/// claims the event) or over the title bar of a managed window. Vertical
+/// wheel down and horizontal wheel right scroll right.
+unsafe fn wheel_scroll_event(msg: u32, mouse: &MSLLHOOKSTRUCT) -> Option<WindowEvent> {
+    // The high word of mouseData contains the wheel delta (signed).
+    let delta = (mouse.mouseData >> 16) as i16 as i32;
+    let direction = WheelDirection::from_message(msg, delta)?;
+
+    let held = held_modifiers();
+    let with_win = held == Modifiers::win() && !is_wheel_bound(held, direction);
+    if !with_win {
+        let hwnd = WindowFromPoint(mouse.pt);
+        if hwnd.is_invalid() {
+            return None;
+        }
+        let root = GetAncestor(hwnd, GA_ROOT);
+        let root = if root.is_invalid() { hwnd } else { root };
+        if !is_managed_window(root.0 as WindowId) {
+            return None;
+        }
+        let rect = get_window_rect(root.0 as WindowId)?;
+        if !in_title_bar(rect, mouse.pt.x, mouse.pt.y, caption_height(root)) {
+            return None;
+        }
+    }
+
+    let monitor = MonitorFromPoint(mouse.pt, MONITOR_DEFAULTTONEAREST).0 as MonitorId;
+    let delta = if msg == WM_MOUSEWHEEL { -delta } else { delta };
+    Some(WindowEvent::WheelScroll { monitor, delta })
+}
+
+/// Button event for a left button message, or None if it isn't one to report.
+///
+/// Presses are only reported over managed windows; releases always are, so
+/// a drag ends even if the cursor left the window.
+unsafe fn mouse_button_event(msg: u32, mouse: &MSLLHOOKSTRUCT) -> Option<WindowEvent> {
+    match msg {
+        WM_LBUTTONDOWN => {
+            let hwnd = WindowFromPoint(mouse.pt);
+            if hwnd.is_invalid() {
+                return None;
+            }
+            let root = GetAncestor(hwnd, GA_ROOT);
+            let root = if root.is_invalid() { hwnd } else { root };
+            let window_id = root.0 as WindowId;
+            is_managed_window(window_id).then_some(WindowEvent::MouseButtonDown {
+                window_id,
+                x: mouse.pt.x,
+                y: mouse.pt.y,
+            })
+        }
+        WM_LBUTTONUP => Some(WindowEvent::MouseButtonUp),
+        _ => None,
+    }
+}
+
 /// Low-level mouse hook callback.
 ///
 /// Tracks mouse movement and sends MouseEnterWindow events when the cursor
-/// enters a different window.
+/// enters a different window, turns title bar wheel events into
+/// WheelScroll events, and reports left button presses and releases
+/// (see [`MouseHookOptions`]).
 unsafe extern "system" fn mouse_ll_hook_proc(
     ncode: i32,
     wparam: windows::Win32::Foundation::WPARAM,
@@ -1910,8 +3354,36 @@ unsafe extern "system" fn mouse_ll_hook_proc(
         return CallNextHookEx(None, ncode, wparam, lparam);
     }
 
+    let options = *MOUSE_HOOK_OPTIONS.lock().unwrap_or_else(recover_poisoned_mutex);
+    let msg = wparam.0 as u32;
+    if options.wheel_scroll && (msg == WM_MOUSEWHEEL || msg == WM_MOUSEHWHEEL) {
+        let mouse_struct = &*(lparam.0 as *const MSLLHOOKSTRUCT);
+        if let Some(event) = wheel_scroll_event(msg, mouse_struct) {
+            let sender_guard = MOUSE_EVENT_SENDER.lock().unwrap_or_else(recover_poisoned_mutex);
+            if let Some(sender) = sender_guard.as_ref() {
+                let _ = sender.send(event);
+            }
+            drop(sender_guard);
+            if held_modifiers().win {
+                mask_start_menu();
+            }
+            // Swallow the event so the hovered window doesn't scroll
+            return windows::Win32::Foundation::LRESULT(1);
+        }
+    }
+
+    if options.track_buttons && (msg == WM_LBUTTONDOWN || msg == WM_LBUTTONUP) {
+        let mouse_struct = &*(lparam.0 as *const MSLLHOOKSTRUCT);
+        if let Some(event) = mouse_button_event(msg, mouse_struct) {
+            let sender_guard = MOUSE_EVENT_SENDER.lock().unwrap_or_else(recover_poisoned_mutex);
+            if let Some(sender) = sender_guard.as_ref() {
+                let _ = sender.send(event);
+            }
+        }
+    }
+
     // Only process mouse move events
-    if wparam.0 as u32 == WM_MOUSEMOVE {
+    if options.track_hover && msg == WM_MOUSEMOVE {
         // Get the mouse position from the hook struct
         let mouse_struct = &*(lparam.0 as *const MSLLHOOKSTRUCT);
         let point = mouse_struct.pt;
@@ -1940,10 +3412,274 @@ unsafe extern "system" fn mouse_ll_hook_proc(
     CallNextHookEx(None, ncode, wparam, lparam)
 }
 
+// ============================================================================
+// Modifier + Mouse Wheel Bindings (Low-Level Mouse Hook)
+// ============================================================================
+
+/// Direction of a mouse wheel notch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum WheelDirection {
+    Up,
+    Down,
+    Left,
+    Right,
+}
+
+impl WheelDirection {
+    /// Direction of a WM_MOUSEWHEEL / WM_MOUSEHWHEEL message with a signed delta.
+    fn from_message(msg: u32, delta: i32) -> Option<Self> {
+        match (msg, delta.signum()) {
+            (WM_MOUSEWHEEL, 1) => Some(Self::Up),
+            (WM_MOUSEWHEEL, -1) => Some(Self::Down),
+            (WM_MOUSEHWHEEL, 1) => Some(Self::Right),
+            (WM_MOUSEHWHEEL, -1) => Some(Self::Left),
+            _ => None,
+        }
+    }
+}
+
+/// A modifier + wheel binding, e.g. "Win+WheelUp".
+///
+/// Matching wheel events are reported as [`HotkeyEvent`]s with the binding's
+/// ID, so they share the hotkey command mapping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct WheelBinding {
+    /// The unique ID for this binding (shared ID space with hotkeys).
+    pub id: HotkeyId,
+    /// Modifier keys that must be held; at least one is required.
+    pub modifiers: Modifiers,
+    /// Wheel direction.
+    pub direction: WheelDirection,
+}
+
+/// Parse a wheel binding string like "Win+WheelUp" or "Ctrl+Alt+WheelLeft".
+///
+/// Bindings without modifiers are rejected: they would swallow every wheel event.
+pub fn parse_wheel_binding_string(s: &str) -> Option<(Modifiers, WheelDirection)> {
+    let (modifiers, key) = split_hotkey_string(s)?;
+    if modifiers == Modifiers::default() {
+        return None;
+    }
+    let direction = match key.to_uppercase().as_str() {
+        "WHEELUP" => WheelDirection::Up,
+        "WHEELDOWN" => WheelDirection::Down,
+        "WHEELLEFT" => WheelDirection::Left,
+        "WHEELRIGHT" => WheelDirection::Right,
+        _ => return None,
+    };
+    Some((modifiers, direction))
+}
+
+/// Modifier keys currently held down, from the asynchronous key state.
+pub(crate) fn held_modifiers() -> Modifiers {
+    Modifiers {
+        ctrl: is_key_down(vk::CONTROL),
+        alt: is_key_down(vk::MENU),
+        shift: is_key_down(vk::SHIFT),
+        win: is_key_down(vk::LWIN) || is_key_down(vk::RWIN),
+    }
+}
+
+/// Find the binding for a wheel event given the held modifiers (exact match).
+fn match_wheel_binding(
+    bindings: &[WheelBinding],
+    held: Modifiers,
+    direction: WheelDirection,
+) -> Option<HotkeyId> {
+    bindings
+        .iter()
+        .find(|b| b.modifiers == held && b.direction == direction)
+        .map(|b| b.id)
+}
+
+/// Whether a registered wheel binding matches the held modifiers and direction.
+fn is_wheel_bound(held: Modifiers, direction: WheelDirection) -> bool {
+    WHEEL_BINDINGS
+        .lock()
+        .unwrap_or_else(recover_poisoned_mutex)
+        .as_ref()
+        .is_some_and(|(bindings, _)| match_wheel_binding(bindings, held, direction).is_some())
+}
+
+/// Unassigned virtual key tapped to keep a released Win key from opening Start.
+const VK_START_MENU_MASK: u16 = 0xE8;
+
+/// Tap an unassigned key so releasing Win doesn't open the Start menu.
+///
+/// Windows opens Start when Win is pressed and released with no other key in
+/// between; a swallowed wheel event or key press doesn't count as one.
+pub(crate) fn mask_start_menu() {
+    let key = |flags| INPUT {
+        r#type: INPUT_KEYBOARD,
+        Anonymous: INPUT_0 {
+            ki: KEYBDINPUT { wVk: VIRTUAL_KEY(VK_START_MENU_MASK), dwFlags: flags, ..Default::default() },
+        },
+    };
+    let inputs = [key(Default::default()), key(KEYEVENTF_KEYUP)];
+    unsafe {
+        SendInput(&inputs, std::mem::size_of::<INPUT>() as i32);
+    }
+}
+
+/// Install a low-level hook of type `hook_id` with `proc` on a new thread
+/// that pumps messages for it. Returns the thread's ID, to post `WM_QUIT` to,
+/// and handle.
+///
+/// Low-level hooks are called on the installing thread's message loop; a
+/// thread that doesn't pump stalls all keyboard or mouse input until Windows
+/// gives up on the hook.
+pub(crate) fn spawn_hook_thread(
+    hook_id: WINDOWS_HOOK_ID,
+    proc: unsafe extern "system" fn(i32, WPARAM, LPARAM) -> windows::Win32::Foundation::LRESULT,
+) -> Result<(u32, std::thread::JoinHandle<()>), Win32Error> {
+    let (init_tx, init_rx) = mpsc::channel::<Result<u32, Win32Error>>();
+    let thread = std::thread::spawn(move || unsafe {
+        let hook = match SetWindowsHookExW(hook_id, Some(proc), None, 0) {
+            Ok(hook) => hook,
+            Err(e) => {
+                let _ = init_tx.send(Err(Win32Error::HookInstallFailed(format!(
+                    "SetWindowsHookExW for hook {} failed: {}",
+                    hook_id.0, e
+                ))));
+                return;
+            }
+        };
+        let _ = init_tx.send(Ok(GetCurrentThreadId()));
+
+        let mut msg = MSG::default();
+        while GetMessageW(&mut msg, None, 0, 0).as_bool() {
+            let _ = DispatchMessageW(&msg);
+        }
+        let _ = UnhookWindowsHookEx(hook);
+    });
+
+    match init_rx.recv() {
+        Ok(Ok(thread_id)) => Ok((thread_id, thread)),
+        Ok(Err(e)) => {
+            let _ = thread.join();
+            Err(e)
+        }
+        Err(_) => Err(Win32Error::HookInstallFailed("Hook thread init failed".to_string())),
+    }
+}
+
+/// Stop a thread started by [`spawn_hook_thread`], removing its hook.
+pub(crate) fn stop_hook_thread(thread_id: u32, thread: Option<std::thread::JoinHandle<()>>) {
+    unsafe {
+        let _ = PostThreadMessageW(thread_id, WM_QUIT, WPARAM(0), LPARAM(0));
+    }
+    if let Some(thread) = thread {
+        let _ = thread.join();
+    }
+}
+
+/// Global wheel bindings and event sender.
+static WHEEL_BINDINGS: std::sync::Mutex<Option<(Vec<WheelBinding>, mpsc::Sender<HotkeyEvent>)>> =
+    std::sync::Mutex::new(None);
+
+/// Handle for the wheel binding hook.
+///
+/// Dropping this handle will unhook the mouse hook and clear the bindings.
+pub struct WheelBindingHandle {
+    thread_id: u32,
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+impl Drop for WheelBindingHandle {
+    fn drop(&mut self) {
+        stop_hook_thread(self.thread_id, self.thread.take());
+
+        // Clear the global bindings (recover from mutex poisoning)
+        let mut bindings = WHEEL_BINDINGS.lock().unwrap_or_else(recover_poisoned_mutex);
+        *bindings = None;
+
+        tracing::debug!("Wheel bindings unregistered");
+    }
+}
+
+/// Register modifier + wheel bindings with a low-level mouse hook.
+///
+/// Returns a handle that must be kept alive to receive binding events, and a
+/// channel receiver for them. Wheel events matching a binding are swallowed,
+/// so the window under the cursor doesn't scroll as well. The hook runs on a
+/// thread of its own that pumps messages for it.
+pub fn register_wheel_bindings(
+    bindings: Vec<WheelBinding>,
+) -> Result<(WheelBindingHandle, mpsc::Receiver<HotkeyEvent>), Win32Error> {
+    let (tx, rx) = mpsc::channel();
+
+    {
+        let mut global = WHEEL_BINDINGS
+            .lock()
+            .map_err(|_| Win32Error::HookInstallFailed("Wheel bindings mutex poisoned".to_string()))?;
+        if global.is_some() {
+            return Err(Win32Error::HookInstallFailed(
+                "Wheel bindings already initialized - drop existing WheelBindingHandle first".to_string(),
+            ));
+        }
+        *global = Some((bindings, tx));
+    }
+
+    let (thread_id, thread) = spawn_hook_thread(WH_MOUSE_LL, wheel_binding_hook_proc).inspect_err(|_| {
+        *WHEEL_BINDINGS.lock().unwrap_or_else(recover_poisoned_mutex) = None;
+    })?;
+
+    tracing::info!("Wheel bindings registered (low-level mouse hook)");
+
+    Ok((WheelBindingHandle { thread_id, thread: Some(thread) }, rx))
+}
+
+/// Low-level mouse hook callback for modifier + wheel bindings.
+unsafe extern "system" fn wheel_binding_hook_proc(
+    ncode: i32,
+    wparam: windows::Win32::Foundation::WPARAM,
+    lparam: windows::Win32::Foundation::LPARAM,
+) -> windows::Win32::Foundation::LRESULT {
+    if ncode >= 0 {
+        let msg = wparam.0 as u32;
+        if msg == WM_MOUSEWHEEL || msg == WM_MOUSEHWHEEL {
+            let mouse_struct = &*(lparam.0 as *const MSLLHOOKSTRUCT);
+            // The high word of mouseData contains the wheel delta (signed).
+            let delta = (mouse_struct.mouseData >> 16) as i16 as i32;
+
+            if let Some(direction) = WheelDirection::from_message(msg, delta) {
+                let held = held_modifiers();
+                let guard = WHEEL_BINDINGS.lock().unwrap_or_else(recover_poisoned_mutex);
+                if let Some((bindings, sender)) = guard.as_ref() {
+                    if let Some(id) = match_wheel_binding(bindings, held, direction) {
+                        let _ = sender.send(HotkeyEvent { id });
+                        drop(guard);
+                        if held.win {
+                            mask_start_menu();
+                        }
+                        // Swallow the event so the hovered window doesn't scroll
+                        return windows::Win32::Foundation::LRESULT(1);
+                    }
+                }
+            }
+        }
+    }
+
+    CallNextHookEx(None, ncode, wparam, lparam)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("epicgameslauncher.exe", "epicgameslauncher.exe"));
+        assert!(glob_match("*.tmp.exe", "setup123.tmp.exe"));
+        assert!(!glob_match("*.tmp.exe", "setup.exe"));
+        assert!(glob_match("steam*", "steamwebhelper.exe"));
+        assert!(glob_match("game?.exe", "game2.exe"));
+        assert!(!glob_match("game?.exe", "game10.exe"));
+        assert!(glob_match("*a*b*", "xxaxxbxx"));
+        assert!(glob_match("*", ""));
+        assert!(!glob_match("", "a"));
+    }
+
     #[test]
     fn test_platform_config_default() {
         let config = PlatformConfig::default();
@@ -1951,6 +3687,208 @@ mod tests {
         assert!(config.use_deferred_positioning);
     }
 
+    #[test]
+    fn test_layout_minimized_tracking() {
+        let hwnd: WindowId = 0xDEAD_0001;
+        assert!(!is_layout_minimized(hwnd));
+        LAYOUT_MINIMIZED.lock().unwrap().insert(hwnd);
+        assert!(is_layout_minimized(hwnd));
+        // A restore is only attributed to the user once
+        assert!(take_layout_minimized(hwnd));
+        assert!(!take_layout_minimized(hwnd));
+        assert!(!is_layout_minimized(hwnd));
+    }
+
+    #[test]
+    fn test_window_event_droppable() {
+        assert!(WindowEvent::MovedOrResized(1).is_droppable());
+        assert!(WindowEvent::MouseEnterWindow(1).is_droppable());
+        assert!(WindowEvent::TitleChanged(1).is_droppable());
+        assert!(!WindowEvent::Created(1).is_droppable());
+        assert!(!WindowEvent::Destroyed(1).is_droppable());
+        assert!(!WindowEvent::Focused(1).is_droppable());
+        assert!(!WindowEvent::DisplayChange.is_droppable());
+        assert!(!WindowEvent::WorkAreaChange.is_droppable());
+    }
+
+    #[test]
+    fn test_hook_queue_drops_only_droppable_events_when_full() {
+        static QUEUE: HookQueue = HookQueue::new();
+        let (tx, rx) = mpsc::channel();
+        let mut receiver = EventReceiver { inner: rx, queue: &QUEUE };
+
+        for id in 0..EVENT_CHANNEL_CAPACITY as WindowId {
+            assert!(QUEUE.send(&tx, WindowEvent::MovedOrResized(id), true));
+        }
+        assert!(!QUEUE.send(&tx, WindowEvent::MovedOrResized(9999), true));
+        // Lifecycle events are queued past the limit without blocking
+        assert!(QUEUE.send(&tx, WindowEvent::Destroyed(9999), false));
+
+        assert_eq!(QUEUE.dropped.load(Ordering::Relaxed), 1);
+        assert_eq!(QUEUE.peak.load(Ordering::Relaxed), EVENT_CHANNEL_CAPACITY + 1);
+        assert!(matches!(receiver.next(), Some(WindowEvent::MovedOrResized(0))));
+        assert_eq!(QUEUE.backlog.load(Ordering::Relaxed), EVENT_CHANNEL_CAPACITY);
+        drop(tx);
+        assert!(matches!(receiver.last(), Some(WindowEvent::Destroyed(9999))));
+        assert_eq!(QUEUE.backlog.load(Ordering::Relaxed), 0);
+    }
+
+    #[test]
+    fn test_map_win_event() {
+        assert!(matches!(map_win_event(EVENT_OBJECT_DESTROY, 7, || unreachable!()), Some(WindowEvent::Destroyed(7))));
+        assert!(matches!(map_win_event(EVENT_OBJECT_FOCUS, 7, || unreachable!()), Some(WindowEvent::Focused(7))));
+        assert!(matches!(map_win_event(EVENT_OBJECT_CREATE, 7, || true), Some(WindowEvent::Created(7))));
+        assert!(matches!(map_win_event(EVENT_OBJECT_NAMECHANGE, 7, || true), Some(WindowEvent::TitleChanged(7))));
+        // Hidden windows are filtered for create, location and title events
+        assert!(map_win_event(EVENT_OBJECT_CREATE, 7, || false).is_none());
+        assert!(map_win_event(EVENT_OBJECT_LOCATIONCHANGE, 7, || false).is_none());
+        assert!(map_win_event(EVENT_OBJECT_NAMECHANGE, 7, || false).is_none());
+        assert!(map_win_event(0x7FFF_FFFF, 7, || true).is_none());
+    }
+
+    #[test]
+    fn test_location_coalescer_merges_bursts_per_window() {
+        let start = std::time::Instant::now();
+        let mut coalescer = LocationCoalescer::default();
+        assert_eq!(coalescer.next_deadline(), None);
+
+        assert!(coalescer.offer(1, start));
+        assert!(!coalescer.offer(1, start + std::time::Duration::from_millis(10)));
+        assert!(coalescer.offer(2, start + std::time::Duration::from_millis(20)));
+        assert_eq!(coalescer.next_deadline(), Some(start + LOCATION_COALESCE_WINDOW));
+
+        // Only the window whose period has ended is flushed
+        assert!(coalescer.take_due(start + std::time::Duration::from_millis(40)).is_empty());
+        assert_eq!(coalescer.take_due(start + LOCATION_COALESCE_WINDOW), vec![1]);
+        assert!(coalescer.offer(1, start + LOCATION_COALESCE_WINDOW));
+
+        assert!(coalescer.take(2));
+        assert!(!coalescer.take(2));
+    }
+
+    #[test]
+    fn test_in_title_bar() {
+        let window = Rect::new(100, 50, 800, 600);
+        assert!(in_title_bar(window, 100, 50, 31));
+        assert!(in_title_bar(window, 899, 80, 31));
+        assert!(!in_title_bar(window, 500, 81, 31));
+        assert!(!in_title_bar(window, 900, 60, 31));
+        assert!(!in_title_bar(window, 500, 49, 31));
+    }
+
+    #[test]
+    fn test_managed_windows_filter() {
+        // A set of its own, so tests running in parallel can't change it
+        static MANAGED: ManagedWindows = ManagedWindows::new();
+        assert!(MANAGED.contains(0xBEEF_0002), "everything is managed until the set is given");
+        MANAGED.set([0xBEEF_0001].into());
+        assert!(MANAGED.contains(0xBEEF_0001));
+        assert!(!MANAGED.contains(0xBEEF_0002));
+    }
+
+    fn monitor(id: MonitorId, rect: Rect, work_area: Rect, is_primary: bool) -> MonitorInfo {
+        MonitorInfo {
+            id,
+            rect,
+            work_area,
+            is_primary,
+            device_name: format!("\\\\.\\DISPLAY{}", id),
+            dpi: DEFAULT_DPI,
+        }
+    }
+
+    #[test]
+    fn test_decode_window_text() {
+        let title: Vec<u16> = "😀 日本語 שלום".encode_utf16().collect();
+        assert_eq!(decode_window_text(&title), "😀 日本語 שלום");
+        // Cut in the middle of the trailing emoji's surrogate pair
+        let cut: Vec<u16> = "Chat 😀".encode_utf16().collect();
+        assert_eq!(decode_window_text(&cut[..cut.len() - 1]), "Chat ");
+        // A lone low surrogate inside the text is replaced, not dropped
+        assert_eq!(decode_window_text(&[0x41, 0xDC00, 0x42]), "A\u{FFFD}B");
+        assert_eq!(decode_window_text(&[]), "");
+    }
+
+    #[test]
+    fn test_monitor_scale_factor() {
+        let mut laptop = monitor(1, Rect::new(0, 0, 2880, 1800), Rect::new(0, 0, 2880, 1740), true);
+        assert_eq!(laptop.scale_factor(), 1.0);
+        laptop.dpi = 144;
+        assert_eq!(laptop.scale_factor(), 1.5);
+    }
+
+    #[test]
+    fn test_crosses_dpi_boundary() {
+        assert!(crosses_dpi_boundary(Some(144), Some(96)));
+        assert!(!crosses_dpi_boundary(Some(96), Some(96)));
+        // Unknown DPI never triggers the extra move
+        assert!(!crosses_dpi_boundary(None, Some(144)));
+        assert!(!crosses_dpi_boundary(Some(144), None));
+    }
+
+    #[test]
+    fn test_sanitize_monitors_drops_zero_size() {
+        let full = Rect::new(0, 0, 1920, 1080);
+        let monitors = sanitize_monitors(vec![
+            monitor(1, full, full, true),
+            monitor(2, Rect::new(1920, 0, 0, 1080), Rect::new(1920, 0, 0, 1080), false),
+        ]);
+        assert_eq!(monitors.len(), 1);
+        assert_eq!(monitors[0].id, 1);
+    }
+
+    #[test]
+    fn test_sanitize_monitors_clamps_work_area() {
+        let full = Rect::new(0, 0, 1920, 1080);
+        let monitors = sanitize_monitors(vec![
+            monitor(1, full, Rect::new(-100, 0, 2020, 1040), true),
+            monitor(2, Rect::new(1920, 0, 1920, 1080), Rect::new(1920, 0, 0, 0), false),
+            monitor(3, Rect::new(3840, 0, 1920, 1080), Rect::new(0, 0, 100, 100), false),
+        ]);
+        assert_eq!(monitors[0].work_area, Rect::new(0, 0, 1920, 1040));
+        assert_eq!(monitors[1].work_area, monitors[1].rect);
+        assert_eq!(monitors[2].work_area, monitors[2].rect);
+    }
+
+    #[test]
+    fn test_sanitize_monitors_merges_duplicates() {
+        let full = Rect::new(0, 0, 1920, 1080);
+        let work = Rect::new(0, 0, 1920, 1040);
+        let monitors = sanitize_monitors(vec![
+            monitor(1, full, work, false),
+            monitor(2, full, work, true),
+            // Mirrored at a lower resolution
+            monitor(3, Rect::new(0, 0, 1280, 720), Rect::new(0, 0, 1280, 720), false),
+        ]);
+        assert_eq!(monitors.len(), 1);
+        assert_eq!(monitors[0].id, 2);
+        assert!(monitors[0].is_primary);
+    }
+
+    #[test]
+    fn test_sanitize_monitors_keeps_partial_overlap() {
+        let monitors = sanitize_monitors(vec![
+            monitor(1, Rect::new(0, 0, 1920, 1080), Rect::new(0, 0, 1920, 1080), true),
+            monitor(2, Rect::new(1800, 0, 1920, 1080), Rect::new(1800, 0, 1920, 1080), false),
+        ]);
+        assert_eq!(monitors.len(), 2);
+    }
+
+    #[test]
+    fn test_is_same_session() {
+        assert!(is_same_session(Some(1), Some(1)));
+        assert!(!is_same_session(Some(2), Some(1)));
+        assert!(is_same_session(None, Some(1)));
+        assert!(is_same_session(Some(2), None));
+    }
+
+    #[test]
+    fn test_current_session_id_matches_process_lookup() {
+        let own = current_session_id();
+        assert!(own.is_some());
+        assert_eq!(own, get_process_session_id(std::process::id()));
+    }
+
     #[test]
     #[ignore = "Requires display hardware - run with: cargo test -- --ignored"]
     fn test_enumerate_monitors() {
@@ -1991,6 +3929,7 @@ mod tests {
             work_area: Rect::new(0, 0, 1920, 1040),
             is_primary: true,
             device_name: "DISPLAY1".to_string(),
+            dpi: DEFAULT_DPI,
         };
 
         // Point inside monitor
@@ -2013,6 +3952,7 @@ mod tests {
             work_area: Rect::new(0, 0, 1920, 1040),
             is_primary: true,
             device_name: "DISPLAY1".to_string(),
+            dpi: DEFAULT_DPI,
         };
 
         // Window centered in monitor
@@ -2037,6 +3977,7 @@ mod tests {
                 work_area: Rect::new(0, 0, 1920, 1040),
                 is_primary: true,
                 device_name: "DISPLAY1".to_string(),
+                dpi: DEFAULT_DPI,
             },
             MonitorInfo {
                 id: 2,
@@ -2044,6 +3985,7 @@ mod tests {
                 work_area: Rect::new(1920, 0, 1920, 1080),
                 is_primary: false,
                 device_name: "DISPLAY2".to_string(),
+                dpi: DEFAULT_DPI,
             },
         ];
 
@@ -2067,6 +4009,7 @@ mod tests {
                 work_area: Rect::new(1920, 0, 1920, 1080),
                 is_primary: false,
                 device_name: "DISPLAY2".to_string(),
+                dpi: DEFAULT_DPI,
             },
             MonitorInfo {
                 id: 1,
@@ -2074,6 +4017,7 @@ mod tests {
                 work_area: Rect::new(0, 0, 1920, 1040),
                 is_primary: true,
                 device_name: "DISPLAY1".to_string(),
+                dpi: DEFAULT_DPI,
             },
         ];
 
@@ -2091,6 +4035,7 @@ mod tests {
                 work_area: Rect::new(0, 0, 1920, 1040),
                 is_primary: true,
                 device_name: "DISPLAY1".to_string(),
+                dpi: DEFAULT_DPI,
             },
             MonitorInfo {
                 id: 2,
@@ -2098,6 +4043,7 @@ mod tests {
                 work_area: Rect::new(1920, 0, 1920, 1080),
                 is_primary: false,
                 device_name: "DISPLAY2".to_string(),
+                dpi: DEFAULT_DPI,
             },
         ];
 
@@ -2118,6 +4064,44 @@ mod tests {
         assert!(no_right.is_none());
     }
 
+    #[test]
+    fn test_monitor_in_direction_stacked_and_diagonal() {
+        let monitor = |id, x, y, width, height| MonitorInfo {
+            id,
+            rect: Rect::new(x, y, width, height),
+            work_area: Rect::new(x, y, width, height),
+            is_primary: id == 1,
+            device_name: format!("DISPLAY{}", id),
+            dpi: DEFAULT_DPI,
+        };
+        // Laptop (1) below a wide external monitor (2), a portrait monitor (3)
+        // to the right of the laptop and a small one (4) diagonally up-left
+        let monitors = vec![
+            monitor(1, 0, 1440, 1920, 1080),
+            monitor(2, -320, 0, 2560, 1440),
+            monitor(3, 1920, 1000, 1080, 1920),
+            monitor(4, -1600, -900, 1280, 900),
+        ];
+
+        let target = |id, direction| monitor_in_direction(&monitors, id, direction).map(|m| m.id);
+        assert_eq!(target(1, MonitorDirection::Up), Some(2));
+        assert_eq!(target(2, MonitorDirection::Down), Some(1));
+        assert_eq!(target(1, MonitorDirection::Right), Some(3));
+        assert_eq!(target(3, MonitorDirection::Left), Some(1));
+        assert_eq!(target(1, MonitorDirection::Down), None);
+        assert_eq!(target(1, MonitorDirection::Left), Some(4), "diagonal monitors are reachable when nothing overlaps");
+        assert_eq!(target(2, MonitorDirection::Up), Some(4));
+        assert_eq!(target(9, MonitorDirection::Up), None);
+
+        // A lower monitor beside the current one beats a nearer diagonal one
+        let monitors = vec![
+            monitor(1, 0, 0, 1920, 1080),
+            monitor(2, 1920, 800, 1920, 1080),
+            monitor(3, 1920, -700, 800, 600),
+        ];
+        assert_eq!(monitor_in_direction(&monitors, 1, MonitorDirection::Right).map(|m| m.id), Some(2));
+    }
+
     #[test]
     fn test_parse_vk() {
         // Letters
@@ -2257,6 +4241,12 @@ mod tests {
         assert!(matches!(result.unwrap_err(), Win32Error::WindowNotFound(0)));
     }
 
+    #[test]
+    fn test_set_window_topmost_zero_fails() {
+        assert!(matches!(set_window_topmost(0, true), Err(Win32Error::WindowNotFound(0))));
+        assert!(!is_window_topmost(0));
+    }
+
     #[test]
     fn test_set_window_border_color_zero_fails() {
         let result = set_window_border_color(0, 0x4285F4);
@@ -2301,4 +4291,51 @@ mod tests {
         // On CI/test environments this may return false (already set), but must not panic
         let _result = set_dpi_awareness();
     }
+
+    #[test]
+    fn test_parse_wheel_binding_string() {
+        let (mods, direction) = parse_wheel_binding_string("Win+WheelUp").unwrap();
+        assert_eq!(mods, Modifiers::win());
+        assert_eq!(direction, WheelDirection::Up);
+
+        let (mods, direction) = parse_wheel_binding_string("ctrl+alt+wheelleft").unwrap();
+        assert!(mods.ctrl && mods.alt && !mods.win);
+        assert_eq!(direction, WheelDirection::Left);
+
+        // A bare wheel binding would swallow all scrolling
+        assert!(parse_wheel_binding_string("WheelDown").is_none());
+        assert!(parse_wheel_binding_string("Win+H").is_none());
+        assert!(parse_hotkey_string("Win+WheelUp").is_none());
+    }
+
+    #[test]
+    fn test_wheel_direction_from_message() {
+        assert_eq!(WheelDirection::from_message(WM_MOUSEWHEEL, 120), Some(WheelDirection::Up));
+        assert_eq!(WheelDirection::from_message(WM_MOUSEWHEEL, -120), Some(WheelDirection::Down));
+        assert_eq!(WheelDirection::from_message(WM_MOUSEHWHEEL, 120), Some(WheelDirection::Right));
+        assert_eq!(WheelDirection::from_message(WM_MOUSEHWHEEL, -30), Some(WheelDirection::Left));
+        assert_eq!(WheelDirection::from_message(WM_MOUSEWHEEL, 0), None);
+    }
+
+    #[test]
+    fn test_match_wheel_binding_requires_exact_modifiers() {
+        let bindings = [
+            WheelBinding { id: 1, modifiers: Modifiers::win(), direction: WheelDirection::Up },
+            WheelBinding { id: 2, modifiers: Modifiers::win_shift(), direction: WheelDirection::Up },
+        ];
+        assert_eq!(match_wheel_binding(&bindings, Modifiers::win(), WheelDirection::Up), Some(1));
+        assert_eq!(match_wheel_binding(&bindings, Modifiers::win_shift(), WheelDirection::Up), Some(2));
+        assert_eq!(match_wheel_binding(&bindings, Modifiers::win(), WheelDirection::Down), None);
+        assert_eq!(match_wheel_binding(&bindings, Modifiers::default(), WheelDirection::Up), None);
+    }
+
+    #[test]
+    fn test_click_capture_swallows_only_its_own_presses() {
+        let mut state = ClickCaptureState::default();
+        // Held before the capture started: its release passes
+        assert!(!state.on_button(false));
+        assert!(state.on_button(true));
+        assert!(state.on_button(false));
+        assert!(!state.on_button(false));
+    }
 }
@@ -10,6 +10,27 @@
 //! message loop. This ensures that drawing operations don't block the
 //! main daemon event loop.
 //!
+//! # Fallback
+//!
+//! Layered windows can be unavailable (restricted environments, some RDP
+//! configurations). [`create_overlay`] detects this once at startup and
+//! falls back to a [`NullOverlay`] that shows nothing, reporting why in
+//! its [`OverlayStatus`].
+//!
+//! # Focus Border
+//!
+//! `DWMWA_BORDER_COLOR` only exists on Windows 11. On Windows 10 the daemon
+//! uses a [`BorderOverlay`] instead: a second click-through, topmost window
+//! laid over the focused window whose interior is color-keyed transparent,
+//! leaving only a colored outline.
+//!
+//! # Column Backgrounds
+//!
+//! [`ColumnBackgrounds`] paints translucent rectangles behind tiled windows,
+//! so empty parts of the viewport and columns still sliding into place don't
+//! show the bare desktop. Unlike the other overlays they are not topmost:
+//! they sit just above the desktop, below every application window.
+//!
 //! # Thread Safety
 //!
 //! The [`OverlayWindow`] struct can be safely shared across threads.
@@ -19,29 +40,56 @@
 
 use crate::Win32Error;
 use openniri_core_layout::Rect;
+use std::cell::RefCell;
 use std::ffi::c_void;
-use std::sync::mpsc;
-use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, WPARAM};
+use std::sync::{mpsc, Mutex};
+use windows::Win32::Foundation::{COLORREF, HWND, LPARAM, LRESULT, RECT, WPARAM};
 use windows::Win32::Graphics::Gdi::{
-    BeginPaint, CreateSolidBrush, EndPaint, FillRect, InvalidateRect, PAINTSTRUCT,
+    BeginPaint, CreateFontW, CreateSolidBrush, DeleteObject, DrawTextW, EndPaint, FillRect,
+    InvalidateRect, SelectObject, SetBkMode, SetTextColor, CLIP_DEFAULT_PRECIS, DEFAULT_CHARSET,
+    DEFAULT_QUALITY, DT_CENTER, DT_SINGLELINE, DT_VCENTER, FW_SEMIBOLD, HGDIOBJ,
+    OUT_DEFAULT_PRECIS, PAINTSTRUCT, TRANSPARENT,
 };
 use windows::Win32::UI::WindowsAndMessaging::{
-    CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW, PostMessageW, RegisterClassW,
-    SetWindowPos, ShowWindow, HWND_TOPMOST, MSG, SWP_NOACTIVATE, SWP_SHOWWINDOW, SW_HIDE,
-    SW_SHOWNA, WM_PAINT, WM_USER, WNDCLASSW, WS_EX_LAYERED, WS_EX_NOACTIVATE, WS_EX_TOOLWINDOW,
-    WS_EX_TOPMOST, WS_EX_TRANSPARENT, WS_POPUP,
+    CreateWindowExW, DefWindowProcW, DispatchMessageW, GetClientRect, GetMessageW, PostMessageW,
+    RegisterClassW, SetWindowPos, ShowWindow, HWND_TOPMOST, MSG, SWP_NOACTIVATE, SWP_SHOWWINDOW,
+    SW_HIDE, SW_SHOWNA, WM_PAINT, WM_USER, WNDCLASSW, WS_EX_LAYERED, WS_EX_NOACTIVATE,
+    WS_EX_TOOLWINDOW, WS_EX_TOPMOST, WS_EX_TRANSPARENT, WS_POPUP,
 };
 
 /// Custom message to quit the overlay thread.
 const WM_QUIT_OVERLAY: u32 = WM_USER + 102;
 
+/// Custom message to quit the border overlay thread.
+const WM_QUIT_BORDER: u32 = WM_USER + 103;
+
+/// Custom message to apply the pending column background list.
+const WM_SYNC_BACKGROUNDS: u32 = WM_USER + 104;
+
+/// Custom message to apply a new column background style.
+const WM_RESTYLE_BACKGROUNDS: u32 = WM_USER + 105;
+
+/// Custom message to quit the column background thread.
+const WM_QUIT_BACKGROUNDS: u32 = WM_USER + 106;
+
+/// Color painted inside the focus border, made fully transparent by the color key.
+/// Magenta, since it's unlikely to be chosen as a border color.
+const BORDER_TRANSPARENT_KEY: u32 = 0x00FF00FF;
+
 /// RGBA color for overlay (semi-transparent blue).
 const OVERLAY_COLOR: u32 = 0x00FF8040; // RGB: 0x4080FF (reversed for Windows)
 
+/// Overlay label text color (BGR).
+const LABEL_TEXT_COLOR: u32 = 0x00FFFFFF;
+
+/// Overlay label font height in pixels.
+const LABEL_FONT_HEIGHT: i32 = 28;
+
 /// Global state for the overlay window.
 static OVERLAY_STATE: std::sync::Mutex<OverlayState> = std::sync::Mutex::new(OverlayState {
     rect: None,
     color: OVERLAY_COLOR,
+    label: None,
 });
 
 /// Current overlay display state.
@@ -50,6 +98,8 @@ struct OverlayState {
     rect: Option<Rect>,
     /// Color for the overlay.
     color: u32,
+    /// Text centered in the rectangle, if any.
+    label: Option<String>,
 }
 
 /// A transparent overlay window for displaying visual snap hints.
@@ -156,9 +206,16 @@ impl OverlayWindow {
 
                 // Set transparency (alpha = 128, about 50% opacity)
                 use windows::Win32::UI::WindowsAndMessaging::{
-                    SetLayeredWindowAttributes, LWA_ALPHA,
+                    DestroyWindow, SetLayeredWindowAttributes, LWA_ALPHA,
                 };
-                let _ = SetLayeredWindowAttributes(hwnd, Default::default(), 128, LWA_ALPHA);
+                if let Err(e) = SetLayeredWindowAttributes(hwnd, Default::default(), 128, LWA_ALPHA) {
+                    // Without layering the overlay would be an opaque box over the windows
+                    let _ = DestroyWindow(hwnd);
+                    let _ = init_tx.send(Err(Win32Error::HookInstallFailed(format!(
+                        "Layered window attributes not supported: {}", e
+                    ))));
+                    return;
+                }
 
                 let hwnd_raw = hwnd.0 as isize;
                 let _ = init_tx.send(Ok(hwnd_raw));
@@ -209,9 +266,23 @@ impl OverlayWindow {
     /// This method is safe to call from any thread. It updates the global
     /// overlay state and sends a message to the overlay thread to repaint.
     pub fn show_snap_target(&self, rect: Rect) {
+        self.show(rect, None);
+    }
+
+    /// Show a highlight at the given rectangle with a line of text centered
+    /// in it, e.g. the width of a column being resized.
+    ///
+    /// Like [`show_snap_target`](Self::show_snap_target), this is safe to
+    /// call from any thread.
+    pub fn show_labeled(&self, rect: Rect, label: &str) {
+        self.show(rect, Some(label.to_string()));
+    }
+
+    fn show(&self, rect: Rect, label: Option<String>) {
         // Update global state
         if let Ok(mut state) = OVERLAY_STATE.lock() {
             state.rect = Some(rect);
+            state.label = label;
         }
 
         unsafe {
@@ -262,6 +333,7 @@ impl OverlayWindow {
         // Clear global state
         if let Ok(mut state) = OVERLAY_STATE.lock() {
             state.rect = None;
+            state.label = None;
         }
 
         unsafe {
@@ -325,6 +397,654 @@ impl Drop for OverlayWindow {
     }
 }
 
+/// A surface for displaying snap hints.
+///
+/// Implemented by [`OverlayWindow`] and by [`NullOverlay`] when no overlay
+/// can be shown, so callers don't need to handle the missing case.
+pub trait Overlay {
+    /// Show a highlight at the given screen rectangle.
+    fn show_snap_target(&self, rect: Rect);
+    /// Show a highlight with a line of text centered in it.
+    ///
+    /// Surfaces that can't draw text show the plain highlight.
+    fn show_labeled(&self, rect: Rect, label: &str) {
+        let _ = label;
+        self.show_snap_target(rect);
+    }
+    /// Hide the highlight.
+    fn hide(&self);
+    /// Whether a highlight is currently shown.
+    fn is_visible(&self) -> bool;
+}
+
+impl Overlay for OverlayWindow {
+    fn show_snap_target(&self, rect: Rect) {
+        OverlayWindow::show_snap_target(self, rect);
+    }
+
+    fn show_labeled(&self, rect: Rect, label: &str) {
+        OverlayWindow::show_labeled(self, rect, label);
+    }
+
+    fn hide(&self) {
+        OverlayWindow::hide(self);
+    }
+
+    fn is_visible(&self) -> bool {
+        OverlayWindow::is_visible(self)
+    }
+}
+
+/// An overlay that never shows anything.
+///
+/// Used when snap hints are disabled or the overlay window can't be created.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NullOverlay;
+
+impl Overlay for NullOverlay {
+    fn show_snap_target(&self, _rect: Rect) {}
+
+    fn hide(&self) {}
+
+    fn is_visible(&self) -> bool {
+        false
+    }
+}
+
+/// Whether snap hint overlays are shown, and if not, why.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OverlayStatus {
+    /// The overlay window was created.
+    Available,
+    /// Snap hints are turned off in the config.
+    Disabled,
+    /// The overlay window couldn't be created; hints are not shown.
+    Unavailable(String),
+}
+
+impl OverlayStatus {
+    /// Reason the overlay is degraded, or None if it works as configured.
+    pub fn degraded_reason(&self) -> Option<&str> {
+        match self {
+            OverlayStatus::Unavailable(reason) => Some(reason),
+            OverlayStatus::Available | OverlayStatus::Disabled => None,
+        }
+    }
+}
+
+/// Create the snap hint overlay.
+///
+/// Falls back to a [`NullOverlay`] when `enabled` is false or the layered
+/// overlay window can't be created; the returned status says which.
+pub fn create_overlay(enabled: bool) -> (Box<dyn Overlay>, OverlayStatus) {
+    if !enabled {
+        return (Box::new(NullOverlay), OverlayStatus::Disabled);
+    }
+    match OverlayWindow::new() {
+        Ok(overlay) => (Box::new(overlay), OverlayStatus::Available),
+        Err(e) => (Box::new(NullOverlay), OverlayStatus::Unavailable(e.to_string())),
+    }
+}
+
+/// Current focus border appearance, read by the border thread when painting.
+static BORDER_STYLE: Mutex<BorderStyle> = Mutex::new(BorderStyle { color: 0, thickness: 0 });
+
+/// Color and thickness of the focus border.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BorderStyle {
+    /// Outline color in BGR format (0x00BBGGRR).
+    color: u32,
+    /// Outline thickness in pixels.
+    thickness: i32,
+}
+
+/// A colored outline drawn around the focused window.
+///
+/// Fallback for the DWM border color on Windows 10. The outline is drawn
+/// just outside the given window rectangle, so it doesn't cover the window's
+/// own edges, and like the snap hint overlay it is click-through, topmost and
+/// hidden from the taskbar.
+///
+/// Dropping it destroys the window and stops its thread.
+pub struct BorderOverlay {
+    hwnd: HWND,
+    thread: Option<std::thread::JoinHandle<()>>,
+    /// Last window rectangle shown, to avoid redundant moves during animations.
+    last: Mutex<Option<Rect>>,
+}
+
+// SAFETY: `hwnd` is only used with SetWindowPos, ShowWindow, InvalidateRect
+// and PostMessageW, which may be called from any thread.
+unsafe impl Send for BorderOverlay {}
+unsafe impl Sync for BorderOverlay {}
+
+impl BorderOverlay {
+    /// Create a hidden focus border.
+    ///
+    /// `color` is in BGR format (0x00BBGGRR); `thickness` is in pixels.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Win32Error::HookInstallFailed`](crate::Win32Error::HookInstallFailed)
+    /// if the border window or thread cannot be created.
+    pub fn new(color: u32, thickness: i32) -> Result<Self, Win32Error> {
+        if let Ok(mut style) = BORDER_STYLE.lock() {
+            *style = BorderStyle { color, thickness: thickness.max(1) };
+        }
+
+        let (init_tx, init_rx) = mpsc::channel::<Result<isize, Win32Error>>();
+
+        let thread = std::thread::spawn(move || unsafe {
+            let class_name: Vec<u16> = "OpenNiriBorderClass\0".encode_utf16().collect();
+            let wc = WNDCLASSW {
+                lpfnWndProc: Some(border_window_proc),
+                lpszClassName: windows::core::PCWSTR(class_name.as_ptr()),
+                ..Default::default()
+            };
+            RegisterClassW(&wc);
+
+            let hwnd = match CreateWindowExW(
+                WS_EX_LAYERED | WS_EX_TRANSPARENT | WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE,
+                windows::core::PCWSTR(class_name.as_ptr()),
+                None,
+                WS_POPUP,
+                0,
+                0,
+                1,
+                1,
+                None,
+                None,
+                None,
+                None,
+            ) {
+                Ok(hwnd) => hwnd,
+                Err(_) => {
+                    let _ = init_tx.send(Err(Win32Error::HookInstallFailed(
+                        "Failed to create border window".to_string(),
+                    )));
+                    return;
+                }
+            };
+
+            // Everything painted in the key color is see-through, leaving only the outline
+            use windows::Win32::UI::WindowsAndMessaging::{
+                DestroyWindow, SetLayeredWindowAttributes, LWA_COLORKEY,
+            };
+            if let Err(e) =
+                SetLayeredWindowAttributes(hwnd, COLORREF(BORDER_TRANSPARENT_KEY), 0, LWA_COLORKEY)
+            {
+                let _ = DestroyWindow(hwnd);
+                let _ = init_tx.send(Err(Win32Error::HookInstallFailed(format!(
+                    "Layered window attributes not supported: {}", e
+                ))));
+                return;
+            }
+
+            let _ = init_tx.send(Ok(hwnd.0 as isize));
+
+            let mut msg = MSG::default();
+            loop {
+                let result = GetMessageW(&mut msg, None, 0, 0);
+                if !result.as_bool() || msg.message == WM_QUIT_BORDER {
+                    break;
+                }
+                let _ = DispatchMessageW(&msg);
+            }
+
+            let _ = DestroyWindow(hwnd);
+        });
+
+        let hwnd_raw = init_rx
+            .recv()
+            .map_err(|_| Win32Error::HookInstallFailed("Border thread init failed".to_string()))??;
+
+        tracing::debug!("Focus border overlay created");
+
+        Ok(Self {
+            hwnd: HWND(hwnd_raw as *mut c_void),
+            thread: Some(thread),
+            last: Mutex::new(None),
+        })
+    }
+
+    /// Outline the given window rectangle, or hide the border for `None`.
+    ///
+    /// Safe to call from any thread; does nothing if the rectangle is unchanged.
+    pub fn update(&self, window_rect: Option<Rect>) {
+        if let Ok(mut last) = self.last.lock() {
+            if *last == window_rect {
+                return;
+            }
+            *last = window_rect;
+        }
+
+        let Some(rect) = window_rect else {
+            unsafe {
+                let _ = ShowWindow(self.hwnd, SW_HIDE);
+            }
+            return;
+        };
+
+        let thickness = BORDER_STYLE.lock().map(|s| s.thickness).unwrap_or(1);
+        let outer = border_outer_rect(rect, thickness);
+        unsafe {
+            let _ = SetWindowPos(
+                self.hwnd,
+                Some(HWND_TOPMOST),
+                outer.x,
+                outer.y,
+                outer.width,
+                outer.height,
+                SWP_NOACTIVATE | SWP_SHOWWINDOW,
+            );
+            let _ = ShowWindow(self.hwnd, SW_SHOWNA);
+            let _ = InvalidateRect(Some(self.hwnd), None, true);
+        }
+    }
+
+    /// Change the outline color (BGR) and thickness, e.g. after a config reload.
+    pub fn set_style(&self, color: u32, thickness: i32) {
+        if let Ok(mut style) = BORDER_STYLE.lock() {
+            *style = BorderStyle { color, thickness: thickness.max(1) };
+        }
+        // Force the next update to resize for the new thickness
+        let current = self.last.lock().ok().and_then(|mut last| last.take());
+        self.update(current);
+    }
+}
+
+impl Drop for BorderOverlay {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = PostMessageW(Some(self.hwnd), WM_QUIT_BORDER, WPARAM(0), LPARAM(0));
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        tracing::debug!("Focus border overlay destroyed");
+    }
+}
+
+/// Screen rectangle of the border window: the window rectangle grown by
+/// `thickness` on every side.
+fn border_outer_rect(window: Rect, thickness: i32) -> Rect {
+    Rect::new(
+        window.x - thickness,
+        window.y - thickness,
+        window.width + 2 * thickness,
+        window.height + 2 * thickness,
+    )
+}
+
+/// The four edge strips (top, bottom, left, right) of an outline of the
+/// given size, in client coordinates.
+fn border_edges(width: i32, height: i32, thickness: i32) -> [Rect; 4] {
+    let thickness = thickness.clamp(0, width.min(height).max(0) / 2);
+    [
+        Rect::new(0, 0, width, thickness),
+        Rect::new(0, height - thickness, width, thickness),
+        Rect::new(0, thickness, thickness, height - 2 * thickness),
+        Rect::new(width - thickness, thickness, thickness, height - 2 * thickness),
+    ]
+}
+
+/// Window procedure for the focus border.
+///
+/// Wrapped with catch_unwind to prevent panics from crashing the application.
+unsafe extern "system" fn border_window_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        if msg == WM_PAINT {
+            paint_border(hwnd);
+            LRESULT(0)
+        } else {
+            DefWindowProcW(hwnd, msg, wparam, lparam)
+        }
+    }));
+
+    match result {
+        Ok(lresult) => lresult,
+        Err(e) => {
+            tracing::error!("Panic in border_window_proc: {:?}", e);
+            DefWindowProcW(hwnd, msg, wparam, lparam)
+        }
+    }
+}
+
+/// Paint the transparent interior and the colored outline.
+fn paint_border(hwnd: HWND) {
+    let style = BORDER_STYLE
+        .lock()
+        .map(|s| *s)
+        .unwrap_or(BorderStyle { color: 0, thickness: 1 });
+
+    unsafe {
+        let mut ps = PAINTSTRUCT::default();
+        let hdc = BeginPaint(hwnd, &mut ps);
+
+        let mut client = RECT::default();
+        let _ = GetClientRect(hwnd, &mut client);
+
+        let key = CreateSolidBrush(COLORREF(BORDER_TRANSPARENT_KEY));
+        let _ = FillRect(hdc, &client, key);
+        let _ = DeleteObject(HGDIOBJ(key.0));
+
+        let brush = CreateSolidBrush(COLORREF(style.color));
+        for edge in border_edges(client.right - client.left, client.bottom - client.top, style.thickness) {
+            let edge = RECT {
+                left: edge.x,
+                top: edge.y,
+                right: edge.x + edge.width,
+                bottom: edge.y + edge.height,
+            };
+            let _ = FillRect(hdc, &edge, brush);
+        }
+        let _ = DeleteObject(HGDIOBJ(brush.0));
+
+        let _ = EndPaint(hwnd, &ps);
+    }
+}
+
+/// Current column background appearance, read by the background thread.
+static BACKGROUND_STYLE: Mutex<BackgroundStyle> = Mutex::new(BackgroundStyle { color: 0, alpha: 0 });
+
+/// Column background list waiting to be applied by the background thread.
+static PENDING_BACKGROUNDS: Mutex<Option<Vec<Rect>>> = Mutex::new(None);
+
+thread_local! {
+    /// Background windows owned by the background thread, with their rects.
+    static BACKGROUNDS: RefCell<Vec<(HWND, Rect)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Color and opacity of column backgrounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct BackgroundStyle {
+    /// Fill color in BGR format (0x00BBGGRR).
+    color: u32,
+    /// Opacity (0-255).
+    alpha: u8,
+}
+
+/// Translucent rectangles drawn behind tiled windows.
+///
+/// Like the collapsed-column [`placeholder`](crate::placeholder) strips, the
+/// windows live on a dedicated thread that creates, moves, or destroys them
+/// to match the list given to [`update`](Self::update). They are
+/// click-through and hidden from the taskbar, and are kept directly above
+/// the desktop so every application window covers them.
+///
+/// Dropping it destroys all backgrounds and stops the background thread.
+pub struct ColumnBackgrounds {
+    /// Message-only window used to signal the background thread.
+    host: HWND,
+    thread: Option<std::thread::JoinHandle<()>>,
+    /// Last list sent, to avoid redundant syncs during animations.
+    last: Mutex<Vec<Rect>>,
+}
+
+// SAFETY: `host` is only used with PostMessageW, which is thread-safe.
+unsafe impl Send for ColumnBackgrounds {}
+unsafe impl Sync for ColumnBackgrounds {}
+
+impl ColumnBackgrounds {
+    /// Start the background thread with no backgrounds shown.
+    ///
+    /// `color` is in BGR format (0x00BBGGRR); `alpha` is the opacity (0-255).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Win32Error::HookInstallFailed`](crate::Win32Error::HookInstallFailed)
+    /// if the host window or thread cannot be created.
+    pub fn new(color: u32, alpha: u8) -> Result<Self, Win32Error> {
+        if let Ok(mut style) = BACKGROUND_STYLE.lock() {
+            *style = BackgroundStyle { color, alpha };
+        }
+
+        let (init_tx, init_rx) = mpsc::channel::<Result<isize, Win32Error>>();
+
+        let thread = std::thread::spawn(move || unsafe {
+            use windows::Win32::UI::WindowsAndMessaging::{DestroyWindow, HWND_MESSAGE};
+
+            let class_name: Vec<u16> = "OpenNiriColumnBackgroundClass\0".encode_utf16().collect();
+            let wc = WNDCLASSW {
+                lpfnWndProc: Some(background_window_proc),
+                lpszClassName: windows::core::PCWSTR(class_name.as_ptr()),
+                ..Default::default()
+            };
+            RegisterClassW(&wc);
+
+            let host = match CreateWindowExW(
+                Default::default(),
+                windows::core::PCWSTR(class_name.as_ptr()),
+                None,
+                Default::default(),
+                0,
+                0,
+                0,
+                0,
+                Some(HWND_MESSAGE),
+                None,
+                None,
+                None,
+            ) {
+                Ok(hwnd) => hwnd,
+                Err(_) => {
+                    let _ = init_tx.send(Err(Win32Error::HookInstallFailed(
+                        "Failed to create column background host window".to_string(),
+                    )));
+                    return;
+                }
+            };
+            let _ = init_tx.send(Ok(host.0 as isize));
+
+            let mut msg = MSG::default();
+            loop {
+                let result = GetMessageW(&mut msg, None, 0, 0);
+                if !result.as_bool() || msg.message == WM_QUIT_BACKGROUNDS {
+                    break;
+                }
+                match msg.message {
+                    WM_SYNC_BACKGROUNDS => {
+                        let pending = PENDING_BACKGROUNDS.lock().ok().and_then(|mut p| p.take());
+                        if let Some(rects) = pending {
+                            sync_backgrounds(&class_name, rects);
+                        }
+                    }
+                    WM_RESTYLE_BACKGROUNDS => restyle_backgrounds(),
+                    _ => {
+                        let _ = DispatchMessageW(&msg);
+                    }
+                }
+            }
+
+            sync_backgrounds(&class_name, Vec::new());
+            let _ = DestroyWindow(host);
+        });
+
+        let host_raw = init_rx.recv().map_err(|_| {
+            Win32Error::HookInstallFailed("Column background thread init failed".to_string())
+        })??;
+
+        tracing::debug!("Column background thread started");
+
+        Ok(Self {
+            host: HWND(host_raw as *mut c_void),
+            thread: Some(thread),
+            last: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Show exactly the given background rectangles, hiding any others.
+    ///
+    /// Safe to call from any thread; does nothing if the list is unchanged.
+    pub fn update(&self, rects: Vec<Rect>) {
+        if let Ok(mut last) = self.last.lock() {
+            if *last == rects {
+                return;
+            }
+            *last = rects.clone();
+        }
+        if let Ok(mut pending) = PENDING_BACKGROUNDS.lock() {
+            *pending = Some(rects);
+        }
+        unsafe {
+            let _ = PostMessageW(Some(self.host), WM_SYNC_BACKGROUNDS, WPARAM(0), LPARAM(0));
+        }
+    }
+
+    /// Change the fill color (BGR) and opacity, e.g. after a config reload.
+    pub fn set_style(&self, color: u32, alpha: u8) {
+        if let Ok(mut style) = BACKGROUND_STYLE.lock() {
+            *style = BackgroundStyle { color, alpha };
+        }
+        unsafe {
+            let _ = PostMessageW(Some(self.host), WM_RESTYLE_BACKGROUNDS, WPARAM(0), LPARAM(0));
+        }
+    }
+}
+
+impl Drop for ColumnBackgrounds {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = PostMessageW(Some(self.host), WM_QUIT_BACKGROUNDS, WPARAM(0), LPARAM(0));
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        tracing::debug!("Column background thread stopped");
+    }
+}
+
+/// Create, move, and destroy background windows to match `rects`.
+///
+/// Must run on the background thread.
+unsafe fn sync_backgrounds(class_name: &[u16], rects: Vec<Rect>) {
+    use windows::Win32::UI::WindowsAndMessaging::{DestroyWindow, SetLayeredWindowAttributes, LWA_ALPHA};
+
+    let alpha = BACKGROUND_STYLE.lock().map(|s| s.alpha).unwrap_or(0);
+
+    BACKGROUNDS.with(|backgrounds| {
+        let mut backgrounds = backgrounds.borrow_mut();
+
+        // Destroy surplus backgrounds
+        while backgrounds.len() > rects.len() {
+            if let Some((hwnd, _)) = backgrounds.pop() {
+                let _ = DestroyWindow(hwnd);
+            }
+        }
+
+        // Create missing backgrounds
+        while backgrounds.len() < rects.len() {
+            let hwnd = CreateWindowExW(
+                WS_EX_LAYERED | WS_EX_TRANSPARENT | WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE,
+                windows::core::PCWSTR(class_name.as_ptr()),
+                None,
+                WS_POPUP,
+                0,
+                0,
+                1,
+                1,
+                None,
+                None,
+                None,
+                None,
+            );
+            match hwnd {
+                Ok(hwnd) => {
+                    let _ = SetLayeredWindowAttributes(hwnd, COLORREF(0), alpha, LWA_ALPHA);
+                    backgrounds.push((hwnd, Rect::new(0, 0, 0, 0)));
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to create column background window: {}", e);
+                    break;
+                }
+            }
+        }
+
+        let insert_after = background_insert_after();
+        for ((hwnd, shown), rect) in backgrounds.iter_mut().zip(rects) {
+            if *shown == rect {
+                continue;
+            }
+            let _ = SetWindowPos(
+                *hwnd,
+                Some(insert_after),
+                rect.x,
+                rect.y,
+                rect.width,
+                rect.height,
+                SWP_NOACTIVATE | SWP_SHOWWINDOW,
+            );
+            let _ = ShowWindow(*hwnd, SW_SHOWNA);
+            *shown = rect;
+        }
+    });
+}
+
+/// Apply the current opacity to all backgrounds and repaint them.
+///
+/// Must run on the background thread.
+unsafe fn restyle_backgrounds() {
+    use windows::Win32::UI::WindowsAndMessaging::{SetLayeredWindowAttributes, LWA_ALPHA};
+
+    let alpha = BACKGROUND_STYLE.lock().map(|s| s.alpha).unwrap_or(0);
+    BACKGROUNDS.with(|backgrounds| {
+        for (hwnd, _) in backgrounds.borrow().iter() {
+            let _ = SetLayeredWindowAttributes(*hwnd, COLORREF(0), alpha, LWA_ALPHA);
+            let _ = InvalidateRect(Some(*hwnd), None, true);
+        }
+    });
+}
+
+/// Window that backgrounds are placed behind: the one just above the
+/// desktop, so they cover the wallpaper but no application window.
+unsafe fn background_insert_after() -> HWND {
+    use windows::Win32::UI::WindowsAndMessaging::{GetShellWindow, GetWindow, GW_HWNDPREV, HWND_BOTTOM};
+
+    let desktop = GetShellWindow();
+    if desktop.is_invalid() {
+        return HWND_BOTTOM;
+    }
+    GetWindow(desktop, GW_HWNDPREV).unwrap_or(HWND_BOTTOM)
+}
+
+/// Window procedure for column backgrounds.
+///
+/// Wrapped with catch_unwind to prevent panics from crashing the application.
+unsafe extern "system" fn background_window_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        if msg == WM_PAINT {
+            let color = BACKGROUND_STYLE.lock().map(|s| s.color).unwrap_or(0);
+            let mut ps = PAINTSTRUCT::default();
+            let hdc = BeginPaint(hwnd, &mut ps);
+            let brush = CreateSolidBrush(COLORREF(color));
+            let _ = FillRect(hdc, &ps.rcPaint, brush);
+            let _ = DeleteObject(HGDIOBJ(brush.0));
+            let _ = EndPaint(hwnd, &ps);
+            LRESULT(0)
+        } else {
+            DefWindowProcW(hwnd, msg, wparam, lparam)
+        }
+    }));
+
+    match result {
+        Ok(lresult) => lresult,
+        Err(e) => {
+            tracing::error!("Panic in background_window_proc: {:?}", e);
+            DefWindowProcW(hwnd, msg, wparam, lparam)
+        }
+    }
+}
+
 /// Window procedure for the overlay window.
 ///
 /// Wrapped with catch_unwind to prevent panics from crashing the application.
@@ -362,17 +1082,48 @@ fn overlay_window_proc_inner(
             let mut ps = PAINTSTRUCT::default();
             let hdc = unsafe { BeginPaint(hwnd, &mut ps) };
 
-            // Get current color from state
-            let color = if let Ok(state) = OVERLAY_STATE.lock() {
-                state.color
+            // Get current color and label from state
+            let (color, label) = if let Ok(state) = OVERLAY_STATE.lock() {
+                (state.color, state.label.clone())
             } else {
-                OVERLAY_COLOR
+                (OVERLAY_COLOR, None)
             };
 
             // Fill with the overlay color
             let brush = unsafe { CreateSolidBrush(windows::Win32::Foundation::COLORREF(color)) };
             let _ = unsafe { FillRect(hdc, &ps.rcPaint, brush) };
 
+            if let Some(label) = label {
+                let mut text: Vec<u16> = label.encode_utf16().collect();
+                let mut client = RECT::default();
+                unsafe {
+                    let _ = GetClientRect(hwnd, &mut client);
+                    let face: Vec<u16> = "Segoe UI\0".encode_utf16().collect();
+                    let font = CreateFontW(
+                        LABEL_FONT_HEIGHT,
+                        0,
+                        0,
+                        0,
+                        FW_SEMIBOLD.0 as i32,
+                        0,
+                        0,
+                        0,
+                        DEFAULT_CHARSET,
+                        OUT_DEFAULT_PRECIS,
+                        CLIP_DEFAULT_PRECIS,
+                        DEFAULT_QUALITY,
+                        0,
+                        windows::core::PCWSTR(face.as_ptr()),
+                    );
+                    let previous = SelectObject(hdc, HGDIOBJ(font.0));
+                    let _ = SetBkMode(hdc, TRANSPARENT);
+                    let _ = SetTextColor(hdc, COLORREF(LABEL_TEXT_COLOR));
+                    let _ = DrawTextW(hdc, &mut text, &mut client, DT_CENTER | DT_VCENTER | DT_SINGLELINE);
+                    SelectObject(hdc, previous);
+                    let _ = DeleteObject(HGDIOBJ(font.0));
+                }
+            }
+
             let _ = unsafe { EndPaint(hwnd, &ps) };
             LRESULT(0)
         }
@@ -466,4 +1217,46 @@ mod tests {
             assert_eq!(state.color, OVERLAY_COLOR);
         }
     }
+
+    #[test]
+    fn test_null_overlay_is_never_visible() {
+        let overlay = NullOverlay;
+        overlay.show_snap_target(Rect::new(0, 0, 100, 100));
+        assert!(!overlay.is_visible());
+        overlay.hide();
+    }
+
+    #[test]
+    fn test_create_overlay_disabled() {
+        let (overlay, status) = create_overlay(false);
+        assert_eq!(status, OverlayStatus::Disabled);
+        assert!(!overlay.is_visible());
+        assert_eq!(status.degraded_reason(), None);
+    }
+
+    #[test]
+    fn test_border_outer_rect_surrounds_window() {
+        assert_eq!(border_outer_rect(Rect::new(100, 50, 800, 600), 3), Rect::new(97, 47, 806, 606));
+    }
+
+    #[test]
+    fn test_border_edges() {
+        let edges = border_edges(100, 60, 4);
+        assert_eq!(edges[0], Rect::new(0, 0, 100, 4));
+        assert_eq!(edges[1], Rect::new(0, 56, 100, 4));
+        assert_eq!(edges[2], Rect::new(0, 4, 4, 52));
+        assert_eq!(edges[3], Rect::new(96, 4, 4, 52));
+
+        // A border thicker than half the window just fills it
+        let edges = border_edges(10, 6, 20);
+        assert_eq!(edges[0], Rect::new(0, 0, 10, 3));
+        assert_eq!(edges[2].height, 0);
+    }
+
+    #[test]
+    fn test_overlay_status_degraded_reason() {
+        let status = OverlayStatus::Unavailable("no layered windows".to_string());
+        assert_eq!(status.degraded_reason(), Some("no layered windows"));
+        assert_eq!(OverlayStatus::Available.degraded_reason(), None);
+    }
 }
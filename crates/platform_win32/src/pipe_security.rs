@@ -0,0 +1,242 @@
+//! Access control for the daemon's IPC pipe.
+//!
+//! Named pipes are machine-global and, with default security, other local
+//! users can open them too. [`PipeAccess`] restricts the pipe to the user the
+//! daemon runs as, and optionally the members of one group, in two ways: the
+//! pipe is created with a DACL granting access only to them, and each client
+//! that connects is checked by the user and groups of its process.
+
+use crate::Win32Error;
+use std::ffi::c_void;
+use std::os::windows::io::RawHandle;
+use windows::core::{HSTRING, PCWSTR, PWSTR};
+use windows::Win32::Foundation::{CloseHandle, LocalFree, HANDLE, HLOCAL};
+use windows::Win32::Security::Authorization::{
+    ConvertSidToStringSidW, ConvertStringSecurityDescriptorToSecurityDescriptorW, SDDL_REVISION_1,
+};
+use windows::Win32::Security::{
+    GetTokenInformation, LookupAccountNameW, TokenGroups, TokenUser, PSECURITY_DESCRIPTOR, PSID,
+    SECURITY_ATTRIBUTES, SID_NAME_USE, TOKEN_GROUPS, TOKEN_INFORMATION_CLASS, TOKEN_QUERY,
+    TOKEN_USER,
+};
+use windows::Win32::System::Pipes::GetNamedPipeClientProcessId;
+use windows::Win32::System::Threading::{
+    GetCurrentProcess, OpenProcess, OpenProcessToken, PROCESS_QUERY_LIMITED_INFORMATION,
+};
+
+/// Pipe rights granted to group members: read and write, but not
+/// `FILE_CREATE_PIPE_INSTANCE`, so they can't serve the pipe themselves.
+const GROUP_PIPE_RIGHTS: &str = "0x12019b";
+
+/// Attribute of token groups that are in effect (not exposed by windows-rs
+/// without the SystemServices feature).
+const SE_GROUP_ENABLED: u32 = 0x0000_0004;
+
+/// Who may talk to the daemon over its pipe.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PipeAccess {
+    user_sid: String,
+    group_sid: Option<String>,
+}
+
+impl PipeAccess {
+    /// Access for the user running this process, plus the members of
+    /// `group` (an account name such as `Administrators`, or a SID string).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the current user or the group can't be resolved.
+    pub fn current_user(group: Option<&str>) -> Result<Self, Win32Error> {
+        let user_sid = unsafe { process_user_sid(GetCurrentProcess()) }
+            .ok_or_else(|| Win32Error::PipeSecurityFailed("Could not determine the current user".to_string()))?;
+        let group_sid = group.map(resolve_sid).transpose()?;
+        Ok(Self { user_sid, group_sid })
+    }
+
+    /// SID string of the user the pipe belongs to.
+    pub fn user_sid(&self) -> &str {
+        &self.user_sid
+    }
+
+    /// Security attributes for creating the pipe with this access.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the security descriptor can't be built.
+    pub fn security_attributes(&self) -> Result<PipeSecurityAttributes, Win32Error> {
+        let sddl = HSTRING::from(pipe_sddl(&self.user_sid, self.group_sid.as_deref()));
+        let mut descriptor = PSECURITY_DESCRIPTOR::default();
+        unsafe {
+            ConvertStringSecurityDescriptorToSecurityDescriptorW(
+                PCWSTR(sddl.as_ptr()),
+                SDDL_REVISION_1,
+                &mut descriptor,
+                None,
+            )
+        }
+        .map_err(|e| Win32Error::PipeSecurityFailed(format!("Invalid pipe security descriptor: {}", e)))?;
+
+        Ok(PipeSecurityAttributes {
+            attributes: SECURITY_ATTRIBUTES {
+                nLength: std::mem::size_of::<SECURITY_ATTRIBUTES>() as u32,
+                lpSecurityDescriptor: descriptor.0,
+                bInheritHandle: false.into(),
+            },
+            descriptor,
+        })
+    }
+
+    /// Whether the process on the other end of a connected pipe may use it.
+    ///
+    /// Clients whose process can't be inspected are rejected.
+    pub fn allows_client(&self, pipe: RawHandle) -> bool {
+        let mut pid = 0u32;
+        if unsafe { GetNamedPipeClientProcessId(HANDLE(pipe), &mut pid) }.is_err() {
+            return false;
+        }
+        let Ok(process) = (unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, false, pid) }) else {
+            return false;
+        };
+        let allowed = unsafe {
+            process_user_sid(process).is_some_and(|sid| sid == self.user_sid)
+                || self
+                    .group_sid
+                    .as_deref()
+                    .is_some_and(|group| process_group_sids(process).iter().any(|sid| sid == group))
+        };
+        unsafe {
+            let _ = CloseHandle(process);
+        }
+        allowed
+    }
+}
+
+/// `SECURITY_ATTRIBUTES` for creating a pipe, owning their security descriptor.
+pub struct PipeSecurityAttributes {
+    attributes: SECURITY_ATTRIBUTES,
+    descriptor: PSECURITY_DESCRIPTOR,
+}
+
+impl PipeSecurityAttributes {
+    /// Pointer to pass as `lpSecurityAttributes`, valid while `self` lives.
+    pub fn as_mut_ptr(&mut self) -> *mut c_void {
+        &mut self.attributes as *mut SECURITY_ATTRIBUTES as *mut c_void
+    }
+}
+
+impl Drop for PipeSecurityAttributes {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = LocalFree(Some(HLOCAL(self.descriptor.0)));
+        }
+    }
+}
+
+/// SDDL for the pipe: a protected DACL giving the user and SYSTEM full
+/// access, and the group read/write access.
+fn pipe_sddl(user_sid: &str, group_sid: Option<&str>) -> String {
+    let mut sddl = format!("D:P(A;;GA;;;{})(A;;GA;;;SY)", user_sid);
+    if let Some(group) = group_sid {
+        sddl.push_str(&format!("(A;;{};;;{})", GROUP_PIPE_RIGHTS, group));
+    }
+    sddl
+}
+
+/// SID string for an account name, or the name itself if it's a SID string.
+fn resolve_sid(account: &str) -> Result<String, Win32Error> {
+    if account.starts_with("S-") {
+        return Ok(account.to_string());
+    }
+    let name = HSTRING::from(account);
+    let mut sid_len = 0u32;
+    let mut domain_len = 0u32;
+    let mut use_kind = SID_NAME_USE::default();
+    unsafe {
+        // The first call only reports the buffer sizes
+        let _ = LookupAccountNameW(
+            PCWSTR::null(),
+            PCWSTR(name.as_ptr()),
+            None,
+            &mut sid_len,
+            None,
+            &mut domain_len,
+            &mut use_kind,
+        );
+        let mut sid = vec![0u8; sid_len as usize];
+        let mut domain = vec![0u16; domain_len as usize];
+        LookupAccountNameW(
+            PCWSTR::null(),
+            PCWSTR(name.as_ptr()),
+            Some(PSID(sid.as_mut_ptr() as *mut c_void)),
+            &mut sid_len,
+            Some(PWSTR(domain.as_mut_ptr())),
+            &mut domain_len,
+            &mut use_kind,
+        )
+        .map_err(|e| Win32Error::PipeSecurityFailed(format!("Unknown account '{}': {}", account, e)))?;
+        sid_string(PSID(sid.as_mut_ptr() as *mut c_void))
+            .ok_or_else(|| Win32Error::PipeSecurityFailed(format!("Invalid SID for account '{}'", account)))
+    }
+}
+
+/// String form (`S-1-5-...`) of a SID.
+unsafe fn sid_string(sid: PSID) -> Option<String> {
+    let mut string = PWSTR::null();
+    ConvertSidToStringSidW(sid, &mut string).ok()?;
+    let result = string.to_string().ok();
+    let _ = LocalFree(Some(HLOCAL(string.0 as *mut c_void)));
+    result
+}
+
+/// Read a token information class into a buffer aligned for its struct.
+unsafe fn token_information(process: HANDLE, class: TOKEN_INFORMATION_CLASS) -> Option<Vec<u64>> {
+    let mut token = HANDLE::default();
+    OpenProcessToken(process, TOKEN_QUERY, &mut token).ok()?;
+    // The first call only reports the buffer size
+    let mut len = 0u32;
+    let _ = GetTokenInformation(token, class, None, 0, &mut len);
+    let mut buffer = vec![0u64; (len as usize).div_ceil(8)];
+    let result = GetTokenInformation(token, class, Some(buffer.as_mut_ptr() as *mut c_void), len, &mut len);
+    let _ = CloseHandle(token);
+    result.ok()?;
+    Some(buffer)
+}
+
+/// SID string of the user a process runs as.
+unsafe fn process_user_sid(process: HANDLE) -> Option<String> {
+    let buffer = token_information(process, TokenUser)?;
+    let user = &*(buffer.as_ptr() as *const TOKEN_USER);
+    sid_string(user.User.Sid)
+}
+
+/// SID strings of the enabled groups of a process's token.
+unsafe fn process_group_sids(process: HANDLE) -> Vec<String> {
+    let Some(buffer) = token_information(process, TokenGroups) else {
+        return Vec::new();
+    };
+    let groups = &*(buffer.as_ptr() as *const TOKEN_GROUPS);
+    std::slice::from_raw_parts(groups.Groups.as_ptr(), groups.GroupCount as usize)
+        .iter()
+        .filter(|group| group.Attributes & SE_GROUP_ENABLED != 0)
+        .filter_map(|group| sid_string(group.Sid))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pipe_sddl() {
+        assert_eq!(pipe_sddl("S-1-5-21-1-2-3-1001", None), "D:P(A;;GA;;;S-1-5-21-1-2-3-1001)(A;;GA;;;SY)");
+        assert_eq!(
+            pipe_sddl("S-1-5-21-1-2-3-1001", Some("S-1-5-32-544")),
+            "D:P(A;;GA;;;S-1-5-21-1-2-3-1001)(A;;GA;;;SY)(A;;0x12019b;;;S-1-5-32-544)"
+        );
+    }
+
+    #[test]
+    fn test_resolve_sid_passes_sid_strings_through() {
+        assert_eq!(resolve_sid("S-1-5-32-544").unwrap(), "S-1-5-32-544");
+    }
+}
@@ -0,0 +1,337 @@
+//! Placeholder strips for collapsed columns.
+//!
+//! Each collapsed column that is in view is drawn as a thin, click-through
+//! strip showing the column's window title vertically, so the user can still
+//! see what was tucked away.
+//!
+//! # Architecture
+//!
+//! Like the [`overlay`](crate::overlay), all placeholder windows live on a
+//! dedicated background thread with its own message loop. Callers publish the
+//! desired set of strips with [`PlaceholderWindows::update`]; the thread then
+//! creates, moves, repaints, or destroys windows to match.
+
+use crate::Win32Error;
+use openniri_core_layout::Rect;
+use std::cell::RefCell;
+use std::ffi::c_void;
+use std::sync::{mpsc, Mutex};
+use windows::Win32::Foundation::{COLORREF, HWND, LPARAM, LRESULT, RECT, WPARAM};
+use windows::Win32::Graphics::Gdi::{
+    BeginPaint, CreateFontW, CreateSolidBrush, DeleteObject, EndPaint, FillRect, InvalidateRect,
+    SelectObject, SetBkMode, SetTextColor, TextOutW, CLIP_DEFAULT_PRECIS, DEFAULT_CHARSET,
+    DEFAULT_QUALITY, FW_NORMAL, HGDIOBJ, OUT_DEFAULT_PRECIS, PAINTSTRUCT, TRANSPARENT,
+};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DestroyWindow, DispatchMessageW, GetClientRect, GetMessageW,
+    PostMessageW, RegisterClassW, SetLayeredWindowAttributes, SetWindowPos, ShowWindow,
+    HWND_MESSAGE, HWND_TOPMOST, LWA_ALPHA, MSG, SWP_NOACTIVATE, SWP_SHOWWINDOW, SW_SHOWNA,
+    WM_PAINT, WM_USER, WNDCLASSW, WS_EX_LAYERED, WS_EX_NOACTIVATE, WS_EX_TOOLWINDOW,
+    WS_EX_TOPMOST, WS_EX_TRANSPARENT, WS_POPUP,
+};
+
+/// Custom message to apply the pending placeholder list.
+const WM_SYNC_PLACEHOLDERS: u32 = WM_USER + 110;
+
+/// Custom message to quit the placeholder thread.
+const WM_QUIT_PLACEHOLDERS: u32 = WM_USER + 111;
+
+/// Background color of a placeholder strip (BGR).
+const PLACEHOLDER_COLOR: u32 = 0x00302828;
+
+/// Title text color (BGR).
+const PLACEHOLDER_TEXT_COLOR: u32 = 0x00E0E0E0;
+
+/// Strip opacity (0-255).
+const PLACEHOLDER_ALPHA: u8 = 220;
+
+/// Title font height in pixels.
+const TITLE_FONT_HEIGHT: i32 = 16;
+
+/// Space above the title in pixels.
+const TITLE_PADDING: i32 = 8;
+
+/// Text escapement for top-to-bottom vertical text, in tenths of a degree.
+const VERTICAL_ESCAPEMENT: i32 = 2700;
+
+/// A placeholder strip to display.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Placeholder {
+    /// Screen rectangle of the strip.
+    pub rect: Rect,
+    /// Title drawn vertically inside the strip.
+    pub title: String,
+}
+
+/// Placeholder list waiting to be applied by the placeholder thread.
+static PENDING: Mutex<Option<Vec<Placeholder>>> = Mutex::new(None);
+
+thread_local! {
+    /// Strip windows owned by the placeholder thread, with what they show.
+    static STRIPS: RefCell<Vec<(HWND, Placeholder)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Manager for collapsed-column placeholder strips.
+///
+/// Dropping it destroys all strips and stops the background thread.
+pub struct PlaceholderWindows {
+    /// Message-only window used to signal the placeholder thread.
+    host: HWND,
+    thread: Option<std::thread::JoinHandle<()>>,
+    /// Last list sent, to avoid redundant syncs during animations.
+    last: Mutex<Vec<Placeholder>>,
+}
+
+// SAFETY: `host` is only used with PostMessageW, which is thread-safe.
+unsafe impl Send for PlaceholderWindows {}
+unsafe impl Sync for PlaceholderWindows {}
+
+impl PlaceholderWindows {
+    /// Start the placeholder thread.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Win32Error::HookInstallFailed`](crate::Win32Error::HookInstallFailed)
+    /// if the host window or thread cannot be created.
+    pub fn new() -> Result<Self, Win32Error> {
+        let (init_tx, init_rx) = mpsc::channel::<Result<isize, Win32Error>>();
+
+        let thread = std::thread::spawn(move || unsafe {
+            let class_name: Vec<u16> = "OpenNiriPlaceholderClass\0".encode_utf16().collect();
+            let wc = WNDCLASSW {
+                lpfnWndProc: Some(placeholder_window_proc),
+                lpszClassName: windows::core::PCWSTR(class_name.as_ptr()),
+                ..Default::default()
+            };
+            RegisterClassW(&wc);
+
+            let host = match CreateWindowExW(
+                Default::default(),
+                windows::core::PCWSTR(class_name.as_ptr()),
+                None,
+                Default::default(),
+                0,
+                0,
+                0,
+                0,
+                Some(HWND_MESSAGE),
+                None,
+                None,
+                None,
+            ) {
+                Ok(hwnd) => hwnd,
+                Err(_) => {
+                    let _ = init_tx.send(Err(Win32Error::HookInstallFailed(
+                        "Failed to create placeholder host window".to_string(),
+                    )));
+                    return;
+                }
+            };
+            let _ = init_tx.send(Ok(host.0 as isize));
+
+            let mut msg = MSG::default();
+            loop {
+                let result = GetMessageW(&mut msg, None, 0, 0);
+                if !result.as_bool() || msg.message == WM_QUIT_PLACEHOLDERS {
+                    break;
+                }
+                if msg.message == WM_SYNC_PLACEHOLDERS {
+                    let pending = PENDING.lock().ok().and_then(|mut p| p.take());
+                    if let Some(placeholders) = pending {
+                        sync_strips(&class_name, placeholders);
+                    }
+                    continue;
+                }
+                let _ = DispatchMessageW(&msg);
+            }
+
+            sync_strips(&class_name, Vec::new());
+            let _ = DestroyWindow(host);
+        });
+
+        let host_raw = init_rx.recv().map_err(|_| {
+            Win32Error::HookInstallFailed("Placeholder thread init failed".to_string())
+        })??;
+
+        tracing::debug!("Placeholder thread started");
+
+        Ok(Self {
+            host: HWND(host_raw as *mut c_void),
+            thread: Some(thread),
+            last: Mutex::new(Vec::new()),
+        })
+    }
+
+    /// Show exactly the given placeholder strips, hiding any others.
+    ///
+    /// Safe to call from any thread; does nothing if the list is unchanged.
+    pub fn update(&self, placeholders: Vec<Placeholder>) {
+        if let Ok(mut last) = self.last.lock() {
+            if *last == placeholders {
+                return;
+            }
+            *last = placeholders.clone();
+        }
+        if let Ok(mut pending) = PENDING.lock() {
+            *pending = Some(placeholders);
+        }
+        unsafe {
+            let _ = PostMessageW(Some(self.host), WM_SYNC_PLACEHOLDERS, WPARAM(0), LPARAM(0));
+        }
+    }
+}
+
+impl Drop for PlaceholderWindows {
+    fn drop(&mut self) {
+        unsafe {
+            let _ = PostMessageW(Some(self.host), WM_QUIT_PLACEHOLDERS, WPARAM(0), LPARAM(0));
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+        tracing::debug!("Placeholder thread stopped");
+    }
+}
+
+/// Create, move, and destroy strip windows to match `placeholders`.
+///
+/// Must run on the placeholder thread.
+unsafe fn sync_strips(class_name: &[u16], placeholders: Vec<Placeholder>) {
+    STRIPS.with(|strips| {
+        let mut strips = strips.borrow_mut();
+
+        // Destroy surplus strips
+        while strips.len() > placeholders.len() {
+            if let Some((hwnd, _)) = strips.pop() {
+                let _ = DestroyWindow(hwnd);
+            }
+        }
+
+        // Create missing strips
+        while strips.len() < placeholders.len() {
+            let hwnd = CreateWindowExW(
+                WS_EX_LAYERED | WS_EX_TRANSPARENT | WS_EX_TOPMOST | WS_EX_TOOLWINDOW | WS_EX_NOACTIVATE,
+                windows::core::PCWSTR(class_name.as_ptr()),
+                None,
+                WS_POPUP,
+                0,
+                0,
+                1,
+                1,
+                None,
+                None,
+                None,
+                None,
+            );
+            match hwnd {
+                Ok(hwnd) => {
+                    let _ = SetLayeredWindowAttributes(hwnd, COLORREF(0), PLACEHOLDER_ALPHA, LWA_ALPHA);
+                    strips.push((hwnd, Placeholder { rect: Rect::new(0, 0, 0, 0), title: String::new() }));
+                }
+                Err(e) => {
+                    tracing::warn!("Failed to create placeholder window: {}", e);
+                    break;
+                }
+            }
+        }
+
+        for ((hwnd, shown), placeholder) in strips.iter_mut().zip(placeholders) {
+            if *shown == placeholder {
+                continue;
+            }
+            let rect = placeholder.rect;
+            let _ = SetWindowPos(
+                *hwnd,
+                Some(HWND_TOPMOST),
+                rect.x,
+                rect.y,
+                rect.width,
+                rect.height,
+                SWP_NOACTIVATE | SWP_SHOWWINDOW,
+            );
+            let _ = ShowWindow(*hwnd, SW_SHOWNA);
+            *shown = placeholder;
+            let _ = InvalidateRect(Some(*hwnd), None, true);
+        }
+    });
+}
+
+/// Window procedure for placeholder strips.
+///
+/// Wrapped with catch_unwind to prevent panics from crashing the application.
+unsafe extern "system" fn placeholder_window_proc(
+    hwnd: HWND,
+    msg: u32,
+    wparam: WPARAM,
+    lparam: LPARAM,
+) -> LRESULT {
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        if msg == WM_PAINT {
+            paint_strip(hwnd);
+            LRESULT(0)
+        } else {
+            DefWindowProcW(hwnd, msg, wparam, lparam)
+        }
+    }));
+
+    match result {
+        Ok(lresult) => lresult,
+        Err(e) => {
+            tracing::error!("Panic in placeholder_window_proc: {:?}", e);
+            DefWindowProcW(hwnd, msg, wparam, lparam)
+        }
+    }
+}
+
+/// Paint a strip's background and vertical title.
+fn paint_strip(hwnd: HWND) {
+    let title: Vec<u16> = STRIPS.with(|strips| {
+        strips
+            .borrow()
+            .iter()
+            .find(|(h, _)| *h == hwnd)
+            .map(|(_, p)| p.title.encode_utf16().collect())
+            .unwrap_or_default()
+    });
+
+    unsafe {
+        let mut ps = PAINTSTRUCT::default();
+        let hdc = BeginPaint(hwnd, &mut ps);
+
+        let mut client = RECT::default();
+        let _ = GetClientRect(hwnd, &mut client);
+
+        let brush = CreateSolidBrush(COLORREF(PLACEHOLDER_COLOR));
+        let _ = FillRect(hdc, &client, brush);
+        let _ = DeleteObject(HGDIOBJ(brush.0));
+
+        let face: Vec<u16> = "Segoe UI\0".encode_utf16().collect();
+        let font = CreateFontW(
+            TITLE_FONT_HEIGHT,
+            0,
+            VERTICAL_ESCAPEMENT,
+            VERTICAL_ESCAPEMENT,
+            FW_NORMAL.0 as i32,
+            0,
+            0,
+            0,
+            DEFAULT_CHARSET,
+            OUT_DEFAULT_PRECIS,
+            CLIP_DEFAULT_PRECIS,
+            DEFAULT_QUALITY,
+            0,
+            windows::core::PCWSTR(face.as_ptr()),
+        );
+        let previous = SelectObject(hdc, HGDIOBJ(font.0));
+        let _ = SetBkMode(hdc, TRANSPARENT);
+        let _ = SetTextColor(hdc, COLORREF(PLACEHOLDER_TEXT_COLOR));
+
+        // Rotated text extends down and to the left of its origin
+        let x = (client.right - client.left + TITLE_FONT_HEIGHT) / 2;
+        let _ = TextOutW(hdc, x, TITLE_PADDING, &title);
+
+        SelectObject(hdc, previous);
+        let _ = DeleteObject(HGDIOBJ(font.0));
+        let _ = EndPaint(hwnd, &ps);
+    }
+}
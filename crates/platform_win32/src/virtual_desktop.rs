@@ -0,0 +1,66 @@
+//! Windows virtual desktop queries.
+//!
+//! Uses the documented `IVirtualDesktopManager` COM interface, which tells
+//! which desktop a window is on. It can't enumerate or switch desktops and
+//! doesn't report switches, so the daemon notices a switch when a window on
+//! another desktop takes focus.
+//!
+//! The COM object is created once per thread on first use. Where it is
+//! unavailable (e.g. Windows Server without the desktop experience), every
+//! window counts as being on the current desktop.
+
+use crate::window_id_to_hwnd;
+use openniri_core_layout::WindowId;
+use std::cell::RefCell;
+use windows::Win32::System::Com::{CoCreateInstance, CoInitializeEx, CLSCTX_ALL, COINIT_MULTITHREADED};
+use windows::Win32::UI::Shell::{IVirtualDesktopManager, VirtualDesktopManager};
+
+/// Identifier of a virtual desktop (its GUID).
+pub type DesktopId = u128;
+
+thread_local! {
+    /// This thread's desktop manager; the inner `None` records that creating it failed.
+    static MANAGER: RefCell<Option<Option<IVirtualDesktopManager>>> = const { RefCell::new(None) };
+}
+
+/// Run `f` with this thread's desktop manager, if there is one.
+fn with_manager<T>(f: impl FnOnce(&IVirtualDesktopManager) -> Option<T>) -> Option<T> {
+    MANAGER.with(|cell| {
+        let mut cell = cell.borrow_mut();
+        let manager = cell.get_or_insert_with(|| unsafe {
+            // Fails harmlessly if the thread already joined an apartment
+            let _ = CoInitializeEx(None, COINIT_MULTITHREADED);
+            match CoCreateInstance(&VirtualDesktopManager, None, CLSCTX_ALL) {
+                Ok(manager) => Some(manager),
+                Err(e) => {
+                    tracing::warn!("Virtual desktop manager unavailable: {}", e);
+                    None
+                }
+            }
+        });
+        manager.as_ref().and_then(f)
+    })
+}
+
+/// The virtual desktop a top-level window is on.
+///
+/// Returns `None` if it can't be determined, e.g. for shell windows, which
+/// belong to no desktop.
+pub fn window_desktop_id(window_id: WindowId) -> Option<DesktopId> {
+    let hwnd = window_id_to_hwnd(window_id).ok()?;
+    with_manager(|manager| unsafe { manager.GetWindowDesktopId(hwnd) }.ok())
+        .map(|guid| guid.to_u128())
+        .filter(|&id| id != 0)
+}
+
+/// Whether a top-level window is on the current virtual desktop.
+///
+/// Windows pinned to all desktops are always on the current one. Returns
+/// true if it can't be determined.
+pub fn is_window_on_current_desktop(window_id: WindowId) -> bool {
+    let Ok(hwnd) = window_id_to_hwnd(window_id) else {
+        return true;
+    };
+    with_manager(|manager| unsafe { manager.IsWindowOnCurrentVirtualDesktop(hwnd) }.ok())
+        .is_none_or(|on_current| on_current.as_bool())
+}
@@ -0,0 +1,409 @@
+//! Headless replay of window event traces through the layout engine.
+//!
+//! A [`Trace`] is a JSON record of windows being created, focused and
+//! destroyed on one monitor, with timestamps. [`replay`] feeds the events
+//! through a [`Workspace`] the way the daemon handles them, checks the
+//! workspace invariants after every event and reports how each event changed
+//! the window placements.
+//!
+//! Traces make layout bugs reproducible without a desktop: attach one to a
+//! bug report, or add it under `traces/` so the tests replay it.
+
+use openniri_core_layout::{
+    CenteringMode, LayoutError, Rect, Visibility, WindowId, WindowPlacement, Workspace, DEFAULT_COLUMN_ANIMATION_MS,
+    DEFAULT_COLUMN_WIDTH, DEFAULT_GAP, DEFAULT_OUTER_GAP,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use thiserror::Error;
+
+/// Errors from loading or replaying a trace.
+#[derive(Debug, Error)]
+pub enum SimError {
+    #[error("Invalid trace: {0}")]
+    Parse(#[from] serde_json::Error),
+
+    #[error("Event {index} at {at_ms}ms is earlier than the event before it")]
+    OutOfOrder { index: usize, at_ms: u64 },
+
+    #[error("Event {index} at {at_ms}ms: {source}")]
+    Layout {
+        index: usize,
+        at_ms: u64,
+        #[source]
+        source: LayoutError,
+    },
+
+    #[error("Final placements differ from the expected ones: {0}")]
+    UnexpectedPlacements(String),
+}
+
+/// Layout settings of the simulated monitor.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TraceLayout {
+    pub gap: i32,
+    pub outer_gap: i32,
+    pub default_column_width: i32,
+    pub centering_mode: CenteringMode,
+}
+
+impl Default for TraceLayout {
+    fn default() -> Self {
+        Self {
+            gap: DEFAULT_GAP,
+            outer_gap: DEFAULT_OUTER_GAP,
+            default_column_width: DEFAULT_COLUMN_WIDTH,
+            centering_mode: CenteringMode::default(),
+        }
+    }
+}
+
+/// A recorded sequence of window events on one monitor.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Trace {
+    /// Work area of the monitor.
+    pub viewport: Rect,
+    #[serde(default)]
+    pub layout: TraceLayout,
+    /// Events in the order they happened.
+    pub events: Vec<TraceEvent>,
+    /// Placements the trace must end with, if it pins them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected: Option<Vec<WindowPlacement>>,
+}
+
+impl Trace {
+    /// Parse a trace from its JSON form.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the JSON isn't a valid trace.
+    pub fn from_json(json: &str) -> Result<Self, SimError> {
+        Ok(serde_json::from_str(json)?)
+    }
+}
+
+/// One window event of a trace.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TraceEvent {
+    /// Milliseconds since the start of the trace.
+    pub at_ms: u64,
+    #[serde(flatten)]
+    pub kind: EventKind,
+}
+
+/// What happened to a window.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum EventKind {
+    /// A window appeared and is tiled in a new column; `width` defaults to
+    /// the default column width.
+    Created {
+        window_id: WindowId,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        width: Option<i32>,
+    },
+    /// A window was closed.
+    Destroyed { window_id: WindowId },
+    /// A window gained focus.
+    Focused { window_id: WindowId },
+}
+
+/// How one window's placement changed.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "change", rename_all = "snake_case")]
+pub enum PlacementChange {
+    Added { window_id: WindowId, rect: Rect, visibility: Visibility },
+    Removed { window_id: WindowId },
+    Changed { window_id: WindowId, from: Rect, to: Rect, visibility: Visibility },
+}
+
+/// An event and the placement changes it caused.
+#[derive(Debug, Clone, Serialize)]
+pub struct Step {
+    pub at_ms: u64,
+    pub event: EventKind,
+    /// Empty if the event didn't move anything, e.g. because the daemon
+    /// would ignore it.
+    pub changes: Vec<PlacementChange>,
+}
+
+/// Result of replaying a trace.
+#[derive(Debug, Clone, Serialize)]
+pub struct Replay {
+    pub steps: Vec<Step>,
+    /// Placements after the last event.
+    pub placements: Vec<WindowPlacement>,
+}
+
+/// Replay a trace, checking invariants after every event.
+///
+/// Scroll animations run between events according to their timestamps, so
+/// events arriving mid-animation behave as in the daemon. Placements are
+/// reported for the layout the animations are heading to.
+///
+/// # Errors
+///
+/// Returns an error at the first event that is out of order, fails or breaks
+/// an invariant, or if the final placements differ from `trace.expected`.
+pub fn replay(trace: &Trace) -> Result<Replay, SimError> {
+    let mut workspace = Workspace::with_gaps(trace.layout.gap, trace.layout.outer_gap);
+    workspace.set_default_column_width(trace.layout.default_column_width);
+    workspace.set_centering_mode(trace.layout.centering_mode);
+    let viewport_width = trace.viewport.width;
+
+    let mut now = 0;
+    let mut placements = Vec::new();
+    let mut steps = Vec::with_capacity(trace.events.len());
+    for (index, event) in trace.events.iter().enumerate() {
+        let at_ms = event.at_ms;
+        if at_ms < now {
+            return Err(SimError::OutOfOrder { index, at_ms });
+        }
+        workspace.tick_animation(at_ms - now);
+        now = at_ms;
+
+        let layout_error = |source| SimError::Layout { index, at_ms, source };
+        apply_event(&mut workspace, &event.kind, viewport_width).map_err(layout_error)?;
+
+        let mut settled = workspace.clone();
+        settled.stop_animation();
+        settled.check_invariants(viewport_width).map_err(layout_error)?;
+
+        let after = settled.compute_placements(trace.viewport);
+        steps.push(Step {
+            at_ms,
+            event: event.kind.clone(),
+            changes: diff_placements(&placements, &after),
+        });
+        placements = after;
+    }
+
+    if let Some(expected) = &trace.expected {
+        let changes = diff_placements(expected, &placements);
+        if !changes.is_empty() {
+            let described: Vec<String> = changes.iter().map(describe_change).collect();
+            return Err(SimError::UnexpectedPlacements(described.join("; ")));
+        }
+    }
+
+    Ok(Replay { steps, placements })
+}
+
+/// Apply an event like the daemon's window event handler does.
+///
+/// Events for windows the workspace doesn't (or already does) manage are
+/// ignored, as the daemon ignores them.
+fn apply_event(workspace: &mut Workspace, event: &EventKind, viewport_width: i32) -> Result<(), LayoutError> {
+    match *event {
+        EventKind::Created { window_id, width } => {
+            if workspace.contains_window(window_id) {
+                return Ok(());
+            }
+            let before = workspace.strip_positions();
+            workspace.insert_window(window_id, width)?;
+            workspace.animate_columns_from(&before, DEFAULT_COLUMN_ANIMATION_MS);
+        }
+        EventKind::Destroyed { window_id } => {
+            if !workspace.contains_window(window_id) {
+                return Ok(());
+            }
+            let before = workspace.strip_positions();
+            workspace.remove_window(window_id)?;
+            workspace.animate_columns_from(&before, DEFAULT_COLUMN_ANIMATION_MS);
+        }
+        EventKind::Focused { window_id } => {
+            if !workspace.contains_window(window_id) {
+                return Ok(());
+            }
+            workspace.focus_window(window_id)?;
+        }
+    }
+    workspace.ensure_focused_visible_animated(viewport_width);
+    Ok(())
+}
+
+/// Changes from `before` to `after`, ordered by window ID.
+pub fn diff_placements(before: &[WindowPlacement], after: &[WindowPlacement]) -> Vec<PlacementChange> {
+    let before: BTreeMap<WindowId, &WindowPlacement> = before.iter().map(|p| (p.window_id, p)).collect();
+    let after: BTreeMap<WindowId, &WindowPlacement> = after.iter().map(|p| (p.window_id, p)).collect();
+
+    let mut changes = Vec::new();
+    for (&window_id, old) in &before {
+        match after.get(&window_id) {
+            None => changes.push(PlacementChange::Removed { window_id }),
+            Some(new) if new.rect != old.rect || new.visibility != old.visibility => {
+                changes.push(PlacementChange::Changed {
+                    window_id,
+                    from: old.rect,
+                    to: new.rect,
+                    visibility: new.visibility,
+                });
+            }
+            Some(_) => {}
+        }
+    }
+    for (&window_id, new) in &after {
+        if !before.contains_key(&window_id) {
+            changes.push(PlacementChange::Added { window_id, rect: new.rect, visibility: new.visibility });
+        }
+    }
+    changes.sort_by_key(|change| match change {
+        PlacementChange::Added { window_id, .. }
+        | PlacementChange::Removed { window_id }
+        | PlacementChange::Changed { window_id, .. } => *window_id,
+    });
+    changes
+}
+
+/// One-line description of a change, e.g. for terminal output.
+pub fn describe_change(change: &PlacementChange) -> String {
+    match change {
+        PlacementChange::Added { window_id, rect, visibility } => {
+            format!("window {} added at {} ({:?})", window_id, describe_rect(rect), visibility)
+        }
+        PlacementChange::Removed { window_id } => format!("window {} removed", window_id),
+        PlacementChange::Changed { window_id, from, to, visibility } => format!(
+            "window {} moved {} -> {} ({:?})",
+            window_id,
+            describe_rect(from),
+            describe_rect(to),
+            visibility
+        ),
+    }
+}
+
+fn describe_rect(rect: &Rect) -> String {
+    format!("({}, {}) {}x{}", rect.x, rect.y, rect.width, rect.height)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn event(at_ms: u64, kind: EventKind) -> TraceEvent {
+        TraceEvent { at_ms, kind }
+    }
+
+    fn trace(events: Vec<TraceEvent>) -> Trace {
+        Trace {
+            viewport: Rect::new(0, 0, 1920, 1080),
+            layout: TraceLayout { gap: 0, outer_gap: 0, ..TraceLayout::default() },
+            events,
+            expected: None,
+        }
+    }
+
+    #[test]
+    fn test_trace_json_format() {
+        let trace = Trace::from_json(
+            r#"{
+                "viewport": {"x": 0, "y": 0, "width": 1920, "height": 1080},
+                "layout": {"gap": 0},
+                "events": [
+                    {"at_ms": 0, "event": "created", "window_id": 1, "width": 600},
+                    {"at_ms": 40, "event": "focused", "window_id": 1},
+                    {"at_ms": 90, "event": "destroyed", "window_id": 1}
+                ]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(trace.layout.gap, 0);
+        assert_eq!(trace.layout.outer_gap, DEFAULT_OUTER_GAP);
+        assert_eq!(trace.events[0].kind, EventKind::Created { window_id: 1, width: Some(600) });
+        assert_eq!(trace.events[2], event(90, EventKind::Destroyed { window_id: 1 }));
+
+        assert!(Trace::from_json(r#"{"viewport": {}, "events": []}"#).is_err());
+    }
+
+    #[test]
+    fn test_replay_reports_placement_changes() {
+        let replay = replay(&trace(vec![
+            event(0, EventKind::Created { window_id: 1, width: Some(800) }),
+            event(10, EventKind::Created { window_id: 2, width: Some(800) }),
+            event(20, EventKind::Created { window_id: 3, width: Some(800) }),
+            event(30, EventKind::Destroyed { window_id: 1 }),
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            replay.steps[0].changes,
+            vec![PlacementChange::Added {
+                window_id: 1,
+                rect: Rect::new(0, 0, 800, 1080),
+                visibility: Visibility::Visible,
+            }]
+        );
+        assert_eq!(replay.steps[1].changes.len(), 1);
+        // The third column overflows the viewport and scrolls the others left
+        assert_eq!(
+            replay.steps[2].changes[0],
+            PlacementChange::Changed {
+                window_id: 1,
+                from: Rect::new(0, 0, 800, 1080),
+                to: Rect::new(-480, 0, 800, 1080),
+                visibility: Visibility::Visible,
+            }
+        );
+        assert_eq!(replay.steps[2].changes.len(), 3);
+        assert!(matches!(replay.steps[3].changes[0], PlacementChange::Removed { window_id: 1 }));
+        assert_eq!(replay.placements.len(), 2);
+    }
+
+    #[test]
+    fn test_replay_ignores_events_for_unknown_windows() {
+        let replay = replay(&trace(vec![
+            event(0, EventKind::Created { window_id: 1, width: None }),
+            event(5, EventKind::Created { window_id: 1, width: None }),
+            event(10, EventKind::Focused { window_id: 7 }),
+            event(20, EventKind::Destroyed { window_id: 7 }),
+        ]))
+        .unwrap();
+        assert!(replay.steps[1..].iter().all(|step| step.changes.is_empty()));
+    }
+
+    #[test]
+    fn test_replay_rejects_out_of_order_events() {
+        let result = replay(&trace(vec![
+            event(10, EventKind::Created { window_id: 1, width: None }),
+            event(5, EventKind::Created { window_id: 2, width: None }),
+        ]));
+        assert!(matches!(result, Err(SimError::OutOfOrder { index: 1, at_ms: 5 })));
+    }
+
+    #[test]
+    fn test_replay_checks_expected_placements() {
+        let mut pinned = trace(vec![event(0, EventKind::Created { window_id: 1, width: Some(800) })]);
+        let placements = replay(&pinned).unwrap().placements;
+
+        pinned.expected = Some(placements.clone());
+        assert!(replay(&pinned).is_ok());
+
+        let mut moved = placements;
+        moved[0].rect.x += 1;
+        pinned.expected = Some(moved);
+        let err = replay(&pinned).unwrap_err();
+        assert!(matches!(err, SimError::UnexpectedPlacements(_)));
+        assert!(err.to_string().contains("window 1 moved"));
+    }
+
+    /// Every trace checked into `traces/` replays cleanly.
+    #[test]
+    fn test_checked_in_traces() {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("traces");
+        let mut count = 0;
+        for entry in std::fs::read_dir(&dir).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_some_and(|ext| ext == "json") {
+                let trace = Trace::from_json(&std::fs::read_to_string(&path).unwrap()).unwrap();
+                if let Err(e) = replay(&trace) {
+                    panic!("{}: {}", path.display(), e);
+                }
+                count += 1;
+            }
+        }
+        assert!(count > 0, "no traces in {}", dir.display());
+    }
+}
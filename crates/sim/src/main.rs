@@ -0,0 +1,62 @@
+//! OpenNiri Sim
+//!
+//! Replays a window event trace through the layout engine without a desktop.
+//!
+//! Usage: `openniri-sim <trace.json> [--json]`
+//!
+//! Prints the placement changes of each event, or the whole replay as JSON
+//! with `--json`. Exits with status 1 if the trace fails to replay.
+
+use openniri_sim::{describe_change, replay, Trace};
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let mut path = None;
+    let mut json = false;
+    for arg in std::env::args().skip(1) {
+        match arg.as_str() {
+            "--json" => json = true,
+            _ if path.is_none() && !arg.starts_with('-') => path = Some(arg),
+            _ => {
+                eprintln!("Usage: openniri-sim <trace.json> [--json]");
+                return ExitCode::from(2);
+            }
+        }
+    }
+    let Some(path) = path else {
+        eprintln!("Usage: openniri-sim <trace.json> [--json]");
+        return ExitCode::from(2);
+    };
+
+    let result = std::fs::read_to_string(&path)
+        .map_err(|e| format!("Failed to read {}: {}", path, e))
+        .and_then(|contents| Trace::from_json(&contents).map_err(|e| e.to_string()))
+        .and_then(|trace| replay(&trace).map_err(|e| e.to_string()));
+    let replay = match result {
+        Ok(replay) => replay,
+        Err(e) => {
+            eprintln!("Error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+
+    if json {
+        match serde_json::to_string_pretty(&replay) {
+            Ok(output) => println!("{}", output),
+            Err(e) => {
+                eprintln!("Error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        }
+        return ExitCode::SUCCESS;
+    }
+
+    for step in &replay.steps {
+        println!("{:>8}ms  {:?}", step.at_ms, step.event);
+        for change in &step.changes {
+            println!("            {}", describe_change(change));
+        }
+    }
+    println!("{} events replayed, invariants held", replay.steps.len());
+    ExitCode::SUCCESS
+}
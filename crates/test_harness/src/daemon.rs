@@ -0,0 +1,175 @@
+//! A daemon process under test.
+//!
+//! Each daemon runs as an isolated instance (see
+//! [`INSTANCE_ENV_VAR`](openniri_ipc::INSTANCE_ENV_VAR)) on a pipe given with
+//! `--pipe-name` and with a config file of the test's choosing, so it neither
+//! talks to nor disturbs a daemon the user already has running.
+
+use crate::wait_until;
+use openniri_client::Client;
+use openniri_ipc::{normalize_pipe_name, IpcCommand, IpcResponse, CONFIG_ENV_VAR, INSTANCE_ENV_VAR};
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, ExitStatus, Stdio};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// How long the daemon may take to start serving IPC.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long the daemon may take to exit after a stop command.
+const SHUTDOWN_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Distinguishes daemons started by the same test process.
+static NEXT_INSTANCE: AtomicUsize = AtomicUsize::new(0);
+
+/// Config that manages only test windows, with no hotkeys or gaps, so
+/// placements are easy to predict.
+pub const TEST_CONFIG: &str = r#"
+management_mode = "whitelist"
+
+[[window_rules]]
+match_class = "^OpenNiriTestWindow$"
+action = "tile"
+
+[layout]
+gap = 0
+outer_gap = 0
+default_column_width = 800
+centering_mode = "just_in_view"
+
+[appearance]
+use_cloaking = true
+active_border = false
+
+[behavior]
+focus_new_windows = true
+focus_follows_mouse = false
+log_level = "debug"
+
+[hotkeys]
+"#;
+
+/// A running daemon, killed on drop if it wasn't stopped.
+pub struct DaemonProcess {
+    child: Child,
+    client: Client,
+    /// Drives the async client from the synchronous tests.
+    runtime: tokio::runtime::Runtime,
+    dir: PathBuf,
+}
+
+impl DaemonProcess {
+    /// Start the daemon binary with `config` and wait until it answers IPC.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the binary can't be started or doesn't serve IPC
+    /// within a few seconds.
+    pub fn start(binary: &Path, config: &str) -> io::Result<Self> {
+        let instance = format!(
+            "e2e-{}-{}",
+            std::process::id(),
+            NEXT_INSTANCE.fetch_add(1, Ordering::Relaxed)
+        );
+        let dir = std::env::temp_dir().join(format!("openniri-{}", instance));
+        fs::create_dir_all(&dir)?;
+        let config_path = dir.join("config.toml");
+        fs::write(&config_path, config)?;
+        let log = File::create(dir.join("daemon.log"))?;
+
+        let pipe_name = normalize_pipe_name(&format!("openniri-{}", instance));
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build()?;
+        let child = Command::new(binary)
+            .args(["--pipe-name", &pipe_name])
+            .env(INSTANCE_ENV_VAR, &instance)
+            .env(CONFIG_ENV_VAR, &config_path)
+            .stdin(Stdio::null())
+            .stdout(log.try_clone()?)
+            .stderr(log)
+            .spawn()?;
+
+        let client = Client::new(pipe_name);
+        let mut daemon = Self { child, client, runtime, dir };
+        if !wait_until(STARTUP_TIMEOUT, || daemon.send(&IpcCommand::QueryStatus).is_ok()) {
+            let _ = daemon.child.kill();
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                format!("Daemon did not start serving IPC; log:\n{}", daemon.log()),
+            ));
+        }
+        Ok(daemon)
+    }
+
+    /// Send one command and read the response.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the daemon can't be reached or the response isn't valid.
+    pub fn send(&self, command: &IpcCommand) -> io::Result<IpcResponse> {
+        self.runtime
+            .block_on(self.client.send(command.clone()))
+            .map_err(io::Error::other)
+    }
+
+    /// Managed windows as reported by the daemon.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the daemon doesn't answer with a window list.
+    pub fn windows(&self) -> Vec<openniri_ipc::WindowInfo> {
+        match self.send(&IpcCommand::QueryAllWindows) {
+            Ok(IpcResponse::WindowList { windows }) => windows,
+            other => panic!("Unexpected response to window query: {:?}", other),
+        }
+    }
+
+    /// Whether the daemon manages a window with this ID.
+    pub fn manages(&self, window_id: u64) -> bool {
+        self.windows().iter().any(|w| w.window_id == window_id)
+    }
+
+    /// Overwrite the daemon's config file, as a user editing it would.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file can't be written.
+    pub fn write_config(&self, config: &str) -> io::Result<()> {
+        fs::write(self.dir.join("config.toml"), config)
+    }
+
+    /// Everything the daemon logged so far.
+    pub fn log(&self) -> String {
+        fs::read_to_string(self.dir.join("daemon.log")).unwrap_or_default()
+    }
+
+    /// Send a stop command and wait for the process to exit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the command fails or the daemon doesn't exit in time.
+    pub fn stop(mut self) -> io::Result<ExitStatus> {
+        self.send(&IpcCommand::Stop)?;
+        let mut status = None;
+        wait_until(SHUTDOWN_TIMEOUT, || {
+            status = self.child.try_wait().ok().flatten();
+            status.is_some()
+        });
+        status.ok_or_else(|| io::Error::new(io::ErrorKind::TimedOut, "Daemon did not exit after stop"))
+    }
+}
+
+impl Drop for DaemonProcess {
+    fn drop(&mut self) {
+        if self.child.try_wait().ok().flatten().is_none() {
+            let _ = self.child.kill();
+            let _ = self.child.wait();
+        }
+        if std::thread::panicking() {
+            // Shown with the failing test's output
+            eprintln!("--- daemon log ---\n{}", self.log());
+        }
+        let _ = fs::remove_dir_all(&self.dir);
+    }
+}
@@ -0,0 +1,42 @@
+//! End-to-end test harness for the OpenNiri daemon.
+//!
+//! Spawns dummy Win32 windows ([`TestWindow`]) and a real daemon process
+//! ([`DaemonProcess`]) with its own pipe, state files and config, then lets
+//! tests drive the daemon over IPC and inspect the windows it placed with
+//! `GetWindowRect` and the DWM cloak attribute.
+//!
+//! The daemon moves real windows on the desktop it runs on, so the tests
+//! using this harness are `#[ignore]`d and run explicitly, one at a time, on
+//! a Windows CI agent:
+//!
+//! ```text
+//! cargo test -p openniri-daemon --test e2e -- --ignored --test-threads=1
+//! ```
+
+pub mod daemon;
+pub mod window;
+
+pub use daemon::{DaemonProcess, TEST_CONFIG};
+pub use window::{TestWindow, TEST_WINDOW_CLASS};
+
+use std::time::{Duration, Instant};
+
+/// Interval between checks in [`wait_until`].
+const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Poll `condition` until it holds, giving up after `timeout`.
+///
+/// The daemon reacts to window events asynchronously, so assertions on its
+/// effects wait for them instead of sleeping a fixed time.
+pub fn wait_until(timeout: Duration, mut condition: impl FnMut() -> bool) -> bool {
+    let start = Instant::now();
+    loop {
+        if condition() {
+            return true;
+        }
+        if start.elapsed() >= timeout {
+            return false;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
@@ -0,0 +1,162 @@
+//! Dummy Win32 windows for the daemon to manage.
+//!
+//! Each window lives on its own thread with a message loop, like a window
+//! of a separate application, and is destroyed when dropped.
+
+use openniri_ipc::IpcRect;
+use std::ffi::c_void;
+use std::sync::mpsc;
+use std::thread::JoinHandle;
+use windows::core::PCWSTR;
+use windows::Win32::Foundation::{HWND, LPARAM, LRESULT, RECT, WPARAM};
+use windows::Win32::Graphics::Dwm::{DwmGetWindowAttribute, DWMWA_CLOAKED};
+use windows::Win32::UI::WindowsAndMessaging::{
+    CreateWindowExW, DefWindowProcW, DispatchMessageW, GetMessageW, GetWindowRect, IsIconic, IsWindow,
+    PostMessageW, PostQuitMessage, RegisterClassW, TranslateMessage, CW_USEDEFAULT, HWND_MESSAGE, MSG,
+    WINDOW_EX_STYLE, WM_CLOSE, WM_DESTROY, WNDCLASSW, WS_OVERLAPPEDWINDOW, WS_VISIBLE,
+};
+
+/// Window class of every test window, for whitelisting them in the test config.
+pub const TEST_WINDOW_CLASS: &str = "OpenNiriTestWindow";
+
+/// Initial size of visible test windows.
+const INITIAL_WIDTH: i32 = 640;
+const INITIAL_HEIGHT: i32 = 480;
+
+/// A dummy top-level window owned by the test.
+pub struct TestWindow {
+    id: u64,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl TestWindow {
+    /// Create a normal visible window, as an application would.
+    pub fn visible(title: &str) -> Self {
+        Self::spawn(title, false)
+    }
+
+    /// Create a message-only window, which the daemon must never manage.
+    pub fn message_only(title: &str) -> Self {
+        Self::spawn(title, true)
+    }
+
+    fn spawn(title: &str, message_only: bool) -> Self {
+        let title: Vec<u16> = title.encode_utf16().chain(Some(0)).collect();
+        let (id_tx, id_rx) = mpsc::channel();
+
+        let thread = std::thread::spawn(move || unsafe {
+            let class_name: Vec<u16> = TEST_WINDOW_CLASS.encode_utf16().chain(Some(0)).collect();
+            let wc = WNDCLASSW {
+                lpfnWndProc: Some(test_window_proc),
+                lpszClassName: PCWSTR(class_name.as_ptr()),
+                ..Default::default()
+            };
+            // Fails harmlessly once the class is registered
+            RegisterClassW(&wc);
+
+            let (style, parent) = if message_only {
+                (Default::default(), Some(HWND_MESSAGE))
+            } else {
+                (WS_OVERLAPPEDWINDOW | WS_VISIBLE, None)
+            };
+            let hwnd = CreateWindowExW(
+                WINDOW_EX_STYLE::default(),
+                PCWSTR(class_name.as_ptr()),
+                PCWSTR(title.as_ptr()),
+                style,
+                CW_USEDEFAULT,
+                CW_USEDEFAULT,
+                INITIAL_WIDTH,
+                INITIAL_HEIGHT,
+                parent,
+                None,
+                None,
+                None,
+            );
+            let Ok(hwnd) = hwnd else {
+                let _ = id_tx.send(None);
+                return;
+            };
+            let _ = id_tx.send(Some(hwnd.0 as u64));
+
+            let mut msg = MSG::default();
+            while GetMessageW(&mut msg, None, 0, 0).as_bool() {
+                let _ = TranslateMessage(&msg);
+                DispatchMessageW(&msg);
+            }
+        });
+
+        let id = id_rx
+            .recv()
+            .ok()
+            .flatten()
+            .expect("Failed to create test window");
+        Self { id, thread: Some(thread) }
+    }
+
+    /// Window handle, as used for window IDs in IPC.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+
+    fn hwnd(&self) -> HWND {
+        HWND(self.id as *mut c_void)
+    }
+
+    /// Current window rect from `GetWindowRect`.
+    pub fn rect(&self) -> Option<IpcRect> {
+        let mut rect = RECT::default();
+        unsafe { GetWindowRect(self.hwnd(), &mut rect) }.ok()?;
+        Some(IpcRect::new(rect.left, rect.top, rect.right - rect.left, rect.bottom - rect.top))
+    }
+
+    /// Whether the window is cloaked, by the daemon or otherwise.
+    pub fn is_cloaked(&self) -> bool {
+        let mut cloaked: u32 = 0;
+        let result = unsafe {
+            DwmGetWindowAttribute(
+                self.hwnd(),
+                DWMWA_CLOAKED,
+                &mut cloaked as *mut u32 as *mut c_void,
+                std::mem::size_of::<u32>() as u32,
+            )
+        };
+        result.is_ok() && cloaked != 0
+    }
+
+    /// Whether the window is minimized.
+    pub fn is_minimized(&self) -> bool {
+        unsafe { IsIconic(self.hwnd()).as_bool() }
+    }
+
+    /// Destroy the window now, as if its application closed it.
+    pub fn close(mut self) {
+        self.destroy();
+    }
+
+    fn destroy(&mut self) {
+        let Some(thread) = self.thread.take() else {
+            return;
+        };
+        unsafe {
+            if IsWindow(Some(self.hwnd())).as_bool() {
+                let _ = PostMessageW(Some(self.hwnd()), WM_CLOSE, WPARAM(0), LPARAM(0));
+            }
+        }
+        let _ = thread.join();
+    }
+}
+
+impl Drop for TestWindow {
+    fn drop(&mut self) {
+        self.destroy();
+    }
+}
+
+unsafe extern "system" fn test_window_proc(hwnd: HWND, msg: u32, wparam: WPARAM, lparam: LPARAM) -> LRESULT {
+    if msg == WM_DESTROY {
+        PostQuitMessage(0);
+        return LRESULT(0);
+    }
+    DefWindowProcW(hwnd, msg, wparam, lparam)
+}